@@ -0,0 +1,99 @@
+//! Command-line mode - Controls the running GUI instead of opening a window
+
+use std::path::Path;
+
+use crate::core::ipc::{self, Activation, IpcCommand};
+
+/// Opens the GUI with a temporary database and without restoring the last session
+pub const SAFE_MODE_FLAG: &str = "--safe-mode";
+
+/// Opens the GUI and launches a profile
+pub const PROFILE_FLAG: &str = "--profile";
+
+const USAGE: &str = "Usage:
+  multiinstance                    Open the GUI
+  multiinstance --safe-mode        Open the GUI with a temporary database, for
+                                   recovering from a crash at startup
+  multiinstance --profile <name>   Open the GUI and launch a profile
+  multiinstance <executable>       Open the GUI with a new instance of an executable
+  multiinstance launch <name|id>   Launch a profile, or start an instance
+  multiinstance stop <name|id>     Stop an instance
+  multiinstance list               List instances and profiles
+  multiinstance status             Summarize running instances";
+
+/// Arguments that open the GUI, or are forwarded to it when it is already running.
+/// None for CLI commands and safe mode.
+pub fn activation(args: &[String]) -> Option<Activation> {
+    match args {
+        [] => Some(Activation::Show),
+        [flag, name @ ..] if flag == PROFILE_FLAG && !name.is_empty() => {
+            Some(Activation::LaunchProfile(name.join(" ")))
+        }
+        [path] if is_executable_path(Path::new(path)) => {
+            Some(Activation::OpenExecutable(std::path::absolute(path).ok()?))
+        }
+        _ => None,
+    }
+}
+
+/// A file, or a macOS app bundle, as dropped on the binary
+fn is_executable_path(path: &Path) -> bool {
+    path.is_file() || (path.is_dir() && path.extension().is_some_and(|e| e == "app"))
+}
+
+/// Parse the arguments into a command; `Err` holds a usage error
+fn parse(args: &[String]) -> Result<IpcCommand, String> {
+    let target = || {
+        let target = args[1..].join(" ");
+        if target.trim().is_empty() {
+            Err(format!("'{}' needs a name or ID", args[0]))
+        } else {
+            Ok(target)
+        }
+    };
+    match args[0].as_str() {
+        "launch" => target().map(IpcCommand::Launch),
+        "stop" => target().map(IpcCommand::Stop),
+        "list" => Ok(IpcCommand::List),
+        "status" => Ok(IpcCommand::Status),
+        other => Err(format!("Unknown command '{}'", other)),
+    }
+}
+
+/// Run a CLI command against the running GUI, returning the process exit code
+pub fn run(args: &[String]) -> i32 {
+    attach_console();
+
+    if matches!(args[0].as_str(), "help" | "-h" | "--help") {
+        println!("{}", USAGE);
+        return 0;
+    }
+    let command = match parse(args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return 2;
+        }
+    };
+
+    match ipc::send(command) {
+        Ok(output) => {
+            println!("{}", output);
+            0
+        }
+        Err(e) => {
+            eprintln!("{:#}", e);
+            1
+        }
+    }
+}
+
+/// Release builds use the GUI subsystem on Windows, so borrow the terminal we
+/// were started from for output
+fn attach_console() {
+    #[cfg(windows)]
+    unsafe {
+        use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+        let _ = AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
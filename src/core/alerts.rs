@@ -0,0 +1,261 @@
+//! Alerts - Routes instance events to notification channels
+//!
+//! Rules are checked in order and the first one matching the event and the
+//! instance (or its group) picks the channels. Events no rule matches follow
+//! the notification level and sound settings.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::email;
+use super::instance::InstanceId;
+use super::settings::{NotificationLevel, Settings};
+
+/// How long a webhook may take before it is given up on
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Kind of event an alert is raised for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AlertEvent {
+    /// The process exited with an error
+    Crashed,
+    /// The process crashed repeatedly in a short time
+    CrashLoop,
+    /// The process exited cleanly without being stopped from the app
+    Exited,
+    /// Status detection found a problem
+    Unhealthy,
+    /// A previously unhealthy instance looks fine again
+    Recovered,
+    /// A run limit is about to stop, or stopped, an instance
+    RunLimit,
+    /// A daily usage limit was approached or reached
+    UsageLimit,
+    /// The machine is almost out of memory
+    HostMemory,
+}
+
+impl AlertEvent {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Crashed => "Crashed",
+            Self::CrashLoop => "Crash loop",
+            Self::Exited => "Exited",
+            Self::Unhealthy => "Unhealthy",
+            Self::Recovered => "Recovered",
+            Self::RunLimit => "Run limit",
+            Self::UsageLimit => "Usage limit",
+            Self::HostMemory => "Host memory",
+        }
+    }
+
+    pub fn all() -> &'static [AlertEvent] {
+        &[
+            AlertEvent::Crashed,
+            AlertEvent::CrashLoop,
+            AlertEvent::Exited,
+            AlertEvent::Unhealthy,
+            AlertEvent::Recovered,
+            AlertEvent::RunLimit,
+            AlertEvent::UsageLimit,
+            AlertEvent::HostMemory,
+        ]
+    }
+
+    /// Shown under the "Important Only" notification level
+    pub fn is_important(&self) -> bool {
+        !matches!(self, Self::Exited | Self::Recovered)
+    }
+
+    /// Emailed when email alerts are enabled, without needing a rule
+    pub fn is_critical(&self) -> bool {
+        matches!(self, Self::CrashLoop | Self::HostMemory)
+    }
+}
+
+/// Where an alert is delivered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlertChannel {
+    /// In-app toast
+    Toast,
+    /// Operating system notification
+    System,
+    /// POST to the configured webhook URL
+    Webhook,
+    /// Alert sound
+    Sound,
+    /// Email over SMTP
+    Email,
+}
+
+impl AlertChannel {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Toast => "Toast",
+            Self::System => "OS",
+            Self::Webhook => "Webhook",
+            Self::Sound => "Sound",
+            Self::Email => "Email",
+        }
+    }
+
+    pub fn all() -> &'static [AlertChannel] {
+        &[
+            AlertChannel::Toast,
+            AlertChannel::System,
+            AlertChannel::Webhook,
+            AlertChannel::Sound,
+            AlertChannel::Email,
+        ]
+    }
+}
+
+/// Instances a rule applies to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum AlertTarget {
+    #[default]
+    Any,
+    Instance(InstanceId),
+    Group(String),
+}
+
+/// Routes matching alerts to a set of channels
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlertRule {
+    /// Event the rule applies to; `None` matches every event
+    #[serde(default)]
+    pub event: Option<AlertEvent>,
+    #[serde(default)]
+    pub target: AlertTarget,
+    /// Channels to deliver to; empty silences matching alerts
+    #[serde(default)]
+    pub channels: Vec<AlertChannel>,
+}
+
+impl Default for AlertRule {
+    fn default() -> Self {
+        Self {
+            event: None,
+            target: AlertTarget::Any,
+            channels: vec![AlertChannel::Toast],
+        }
+    }
+}
+
+impl AlertRule {
+    pub fn matches(&self, alert: &Alert) -> bool {
+        if self.event.is_some_and(|event| event != alert.event) {
+            return false;
+        }
+        match &self.target {
+            AlertTarget::Any => true,
+            AlertTarget::Instance(id) => alert.instance == Some(*id),
+            AlertTarget::Group(group) => alert.group.as_ref() == Some(group),
+        }
+    }
+}
+
+/// Something worth telling the user about
+#[derive(Debug, Clone, Serialize)]
+pub struct Alert {
+    pub event: AlertEvent,
+    /// Instance the alert is about, if any
+    pub instance: Option<InstanceId>,
+    /// Name and group of that instance, filled in when the alert is raised
+    pub instance_name: Option<String>,
+    pub group: Option<String>,
+    pub message: String,
+}
+
+impl Alert {
+    pub fn new(event: AlertEvent, instance: Option<InstanceId>, message: String) -> Self {
+        Self {
+            event,
+            instance,
+            instance_name: None,
+            group: None,
+            message,
+        }
+    }
+}
+
+/// Channels an alert goes to
+pub fn route(settings: &Settings, alert: &Alert) -> Vec<AlertChannel> {
+    if let Some(rule) = settings.alert_rules.iter().find(|r| r.matches(alert)) {
+        return rule.channels.clone();
+    }
+
+    let shown = match settings.notification_level {
+        NotificationLevel::All => true,
+        NotificationLevel::Important => alert.event.is_important(),
+        NotificationLevel::None => false,
+    };
+    let mut channels = Vec::new();
+    if shown {
+        channels.push(AlertChannel::Toast);
+        if settings.notification_sound {
+            channels.push(AlertChannel::Sound);
+        }
+    }
+    if settings.email_alerts.enabled && alert.event.is_critical() {
+        channels.push(AlertChannel::Email);
+    }
+    channels
+}
+
+/// Deliver an alert to every channel but the toast, which the UI shows itself.
+/// Delivery runs on a background thread so slow webhooks never stall a frame.
+pub fn deliver(alert: &Alert, channels: &[AlertChannel], settings: &Settings) {
+    let channels: Vec<AlertChannel> = channels
+        .iter()
+        .copied()
+        .filter(|c| *c != AlertChannel::Toast)
+        .collect();
+    if channels.is_empty() {
+        return;
+    }
+
+    let alert = alert.clone();
+    let webhook_url = settings.alert_webhook_url.trim().to_string();
+    let email_settings = settings.email_alerts.clone();
+    std::thread::spawn(move || {
+        for channel in channels {
+            let result = match channel {
+                AlertChannel::System => {
+                    crate::platform::show_notification(alert.event.label(), &alert.message)
+                }
+                AlertChannel::Webhook => send_webhook(&webhook_url, &alert),
+                AlertChannel::Sound => crate::platform::play_alert_sound(),
+                AlertChannel::Email => email::send_alert(&email_settings, &alert),
+                AlertChannel::Toast => Ok(()),
+            };
+            if let Err(e) = result {
+                warn!("Failed to deliver alert via {}: {}", channel.label(), e);
+            }
+        }
+    });
+}
+
+/// POST the alert as JSON
+fn send_webhook(url: &str, alert: &Alert) -> Result<()> {
+    if url.is_empty() {
+        anyhow::bail!("No webhook URL is set");
+    }
+    let body = serde_json::json!({
+        "event": alert.event,
+        "instance": alert.instance,
+        "instance_name": alert.instance_name,
+        "group": alert.group,
+        "message": alert.message,
+        "timestamp": chrono::Utc::now(),
+    });
+    ureq::post(url)
+        .timeout(WEBHOOK_TIMEOUT)
+        .set("Content-Type", "application/json")
+        .send_string(&body.to_string())
+        .context("Webhook request failed")?;
+    Ok(())
+}
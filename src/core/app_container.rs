@@ -0,0 +1,101 @@
+//! AppContainer sandbox - Windows isolation enforced by the OS rather than the environment
+//!
+//! Environment isolation only redirects apps that look their folders up through
+//! APPDATA and friends. An AppContainer process runs with its own low-privilege
+//! identity and can only reach the files, registry keys and network access
+//! granted to it, so other copies of the app cannot see its data at all.
+
+use serde::{Deserialize, Serialize};
+
+use super::instance::InstanceId;
+
+/// How strongly an instance is separated from other copies of its app
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum IsolationLevel {
+    /// Point APPDATA and related variables into the data directory
+    #[default]
+    Environment,
+    /// Also run inside a per-instance AppContainer (Windows only)
+    AppContainer,
+}
+
+impl IsolationLevel {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Environment => "Environment variables",
+            Self::AppContainer => "AppContainer sandbox",
+        }
+    }
+
+    pub fn all() -> [Self; 2] {
+        [Self::Environment, Self::AppContainer]
+    }
+}
+
+/// Access granted to an AppContainer beyond its own data directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AppContainerCapability {
+    /// Outbound connections to the internet
+    InternetClient,
+    /// Accept connections from the internet
+    InternetClientServer,
+    /// Connect and listen on home and work networks
+    PrivateNetwork,
+    DocumentsLibrary,
+    PicturesLibrary,
+    VideosLibrary,
+    MusicLibrary,
+    RemovableStorage,
+}
+
+impl AppContainerCapability {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::InternetClient => "Internet (client)",
+            Self::InternetClientServer => "Internet (client and server)",
+            Self::PrivateNetwork => "Private networks",
+            Self::DocumentsLibrary => "Documents library",
+            Self::PicturesLibrary => "Pictures library",
+            Self::VideosLibrary => "Videos library",
+            Self::MusicLibrary => "Music library",
+            Self::RemovableStorage => "Removable storage",
+        }
+    }
+
+    /// Well-known capability SID
+    pub fn sid(&self) -> &'static str {
+        match self {
+            Self::InternetClient => "S-1-15-3-1",
+            Self::InternetClientServer => "S-1-15-3-2",
+            Self::PrivateNetwork => "S-1-15-3-3",
+            Self::PicturesLibrary => "S-1-15-3-4",
+            Self::VideosLibrary => "S-1-15-3-5",
+            Self::MusicLibrary => "S-1-15-3-6",
+            Self::DocumentsLibrary => "S-1-15-3-7",
+            Self::RemovableStorage => "S-1-15-3-10",
+        }
+    }
+
+    pub fn all() -> [Self; 8] {
+        [
+            Self::InternetClient,
+            Self::InternetClientServer,
+            Self::PrivateNetwork,
+            Self::DocumentsLibrary,
+            Self::PicturesLibrary,
+            Self::VideosLibrary,
+            Self::MusicLibrary,
+            Self::RemovableStorage,
+        ]
+    }
+
+    /// Most apps expect to reach the internet
+    pub fn defaults() -> Vec<Self> {
+        vec![Self::InternetClient]
+    }
+}
+
+/// AppContainer profile name of an instance; names are limited to 64 characters
+pub fn container_name(id: InstanceId) -> String {
+    format!("MultiInstance.{}", id)
+}
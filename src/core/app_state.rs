@@ -1,20 +1,154 @@
 //! Application state - Central state management for MultiInstance
 
-use std::collections::HashMap;
-use std::path::PathBuf;
-use std::sync::{Arc, RwLock};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock, RwLockWriteGuard};
 use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
-use tracing::{error, info, warn};
+use image::RgbaImage;
+use tracing::{debug, error, info, warn};
 
+use super::alerts::{self, Alert, AlertChannel};
+use super::app_container::{self, IsolationLevel};
+use super::broadcast::BroadcastTargets;
+use super::bundle;
+use super::checkpoint::Checkpoint;
+use super::conflicts::{self, Conflict};
+use super::crash_dialogs;
+use super::crash_report::{self, CrashReport};
+use super::custom_theme::CustomTheme;
+use super::data_compression;
+use super::deployment;
+use super::environment::EnvVar;
+use super::executable_icon;
+use super::history::{self, HistoryEntry, HistoryEvent, HistoryFilter};
+use super::inspect::{Connection, Inspection, OpenHandle};
 use super::instance::{Instance, InstanceConfig, InstanceId, InstanceStatus};
-use super::monitor::SharedResourceMonitor;
-use super::process::SharedProcessManager;
-use super::profile::{Profile, ProfileId};
+use super::ipc::Activation;
+use super::isolation_check::{self, IsolationCheck};
+use super::limit_schedule;
+use super::macros::Macro;
+use super::migration::{self, ImportOptions, ImportSummary, MigrationPackage};
+use super::monitor::{SharedResourceMonitor, UsageHistory};
+use super::network_condition::NetworkCondition;
+use super::network_limit;
+use super::orphans::{self, OrphanProcess};
+use super::output_log;
+use super::process::{self, SharedProcessManager};
+use super::profile::{MemberDependency, Profile, ProfileId, ProfileRun, Readiness};
+use super::profiler::Profiler;
+use super::recovery::{MarkedInstance, RecoveryReport, ShutdownMarker};
+use super::reservation::{self, ReservationPolicy};
+use super::resource::ResourceLimits;
+use super::rolling_restart::{RestartHealth, RollingRestart, RollingRestartTarget};
+use super::scheduler::{Schedule, ScheduleId, ScheduleTarget};
 use super::settings::Settings;
+use super::snapshot::Snapshot;
+use super::status_detection::Health;
+use super::usage::{self, UsageEvent, UsageTracker};
+use super::window_capture::{self, RegionCapture, Screenshot};
+use super::window_layout::{self, WindowLayout};
+use super::window_titles;
+use crate::persistence::data_lock::DataLock;
+use crate::persistence::profile_sync::ProfileSync;
 use crate::persistence::Database;
 
+/// How often to scan all processes for copies of managed executables
+const COPY_SCAN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often instance logs past the history retention are deleted
+const LOG_PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// How often a waiting profile member checks whether the one it waits for is ready
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How often accumulated usage is written to the database
+const USAGE_FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often active instances are checked for crash dialogs
+const CRASH_DIALOG_INTERVAL: Duration = Duration::from_secs(3);
+
+/// How often pending instances' network conditions are re-checked
+const NETWORK_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often handles of processes that are gone are released
+const HANDLE_RECONCILE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Warning time before a run limit stops an instance
+const RUN_LIMIT_WARNING: chrono::TimeDelta = chrono::TimeDelta::minutes(1);
+
+/// Crashes within `CRASH_LOOP_WINDOW` that count as a crash loop
+const CRASH_LOOP_COUNT: usize = 3;
+
+/// Window in which repeated crashes count as a crash loop
+const CRASH_LOOP_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+/// Host memory use that raises an alert, in percent
+const HOST_MEMORY_ALERT_PERCENT: f32 = 95.0;
+
+/// Host memory use the alert re-arms below, so it does not repeat while hovering
+const HOST_MEMORY_REARM_PERCENT: f32 = 90.0;
+
+/// Run limit progress worth telling the user about
+#[derive(Debug, Clone)]
+pub enum RunLimitEvent {
+    /// The instance will be stopped shortly
+    Expiring {
+        id: InstanceId,
+        name: String,
+        remaining_secs: i64,
+    },
+    /// The instance was stopped by its run limit
+    Expired { id: InstanceId, name: String },
+}
+
+/// Health check outcome worth telling the user about
+#[derive(Debug, Clone)]
+pub enum HealthEvent {
+    /// Status detection found a problem on screen
+    Unhealthy {
+        id: InstanceId,
+        name: String,
+        reason: String,
+    },
+    /// A previously unhealthy instance looks fine again
+    Recovered { id: InstanceId, name: String },
+}
+
+/// A managed process ended on its own, without being stopped from the app
+#[derive(Debug, Clone)]
+pub struct ExitEvent {
+    pub id: InstanceId,
+    pub name: String,
+    /// Exited with an error rather than cleanly
+    pub crashed: bool,
+    pub error: Option<String>,
+    /// This crash makes `CRASH_LOOP_COUNT` within `CRASH_LOOP_WINDOW`
+    pub crash_loop: bool,
+    /// Correlation ID of the launch that exited
+    pub launch_id: Option<String>,
+}
+
+/// What to do once an instance has stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AfterStop {
+    Nothing,
+    Start,
+    Compress,
+}
+
+/// An instance waiting on its stop command in the background
+struct StopJob {
+    id: InstanceId,
+    pid: u32,
+    /// Status to go back to if the process cannot be ended
+    previous: InstanceStatus,
+    timeout: Duration,
+    after: AfterStop,
+}
+
 /// Central application state
 pub struct AppState {
     /// All managed instances
@@ -37,13 +171,128 @@ pub struct AppState {
     pub recent_apps: Arc<RwLock<Vec<PathBuf>>>,
     /// Last resource update time
     last_resource_update: Arc<RwLock<Instant>>,
+    /// Last system-wide scan for copies of managed executables
+    last_copy_scan: Arc<RwLock<Option<Instant>>>,
+    /// Last time old instance logs were deleted
+    last_log_prune: Arc<RwLock<Option<Instant>>>,
+    /// Rolling restarts in progress
+    pub rolling_restarts: Arc<RwLock<Vec<RollingRestart>>>,
+    /// Profiles, quick launch, groups and recent apps have finished loading
+    data_loaded: Arc<AtomicBool>,
+    /// Untracked processes found in instance data directories at startup
+    pub orphans: Arc<RwLock<Vec<OrphanProcess>>>,
+    /// Latest open files snapshot per instance, for the details dialog
+    pub open_files: Arc<RwLock<HashMap<InstanceId, Inspection<OpenHandle>>>>,
+    /// Latest TCP/UDP connections snapshot per instance, for the details dialog
+    pub connections: Arc<RwLock<HashMap<InstanceId, Inspection<Connection>>>>,
+    /// Instances whose connections are being listed right now
+    connections_in_flight: Arc<RwLock<HashSet<InstanceId>>>,
+    /// Instances from a previous run that ended unexpectedly
+    pub recovery: Arc<RwLock<Option<RecoveryReport>>>,
+    /// Running instances last written to the dirty shutdown marker
+    marked_instances: Arc<RwLock<Vec<(InstanceId, u32)>>>,
+    /// Saved session waiting for the user to pick what to restore
+    pub pending_session: Arc<RwLock<Vec<InstanceConfig>>>,
+    /// Today's runtime per executable, for usage limits
+    pub usage: Arc<RwLock<UsageTracker>>,
+    /// Last time usage was written to the database
+    last_usage_flush: Arc<RwLock<Instant>>,
+    /// Last network condition check for pending instances (None = check now)
+    last_network_check: Arc<RwLock<Option<Instant>>>,
+    /// A network condition check is in progress
+    network_check_running: Arc<AtomicBool>,
+    /// Latest capture of each running instance's pinned window region
+    pub region_captures: Arc<RwLock<HashMap<InstanceId, RegionCapture>>>,
+    /// Last time pinned regions were captured
+    last_region_capture: Arc<RwLock<Option<Instant>>>,
+    /// Pinned regions are being captured right now
+    region_capture_running: Arc<AtomicBool>,
+    /// Latest scaled-down capture of each running instance's window, for the quick switcher
+    pub window_thumbnails: Arc<RwLock<HashMap<InstanceId, RegionCapture>>>,
+    /// Shell icons by executable path; None while extracting or when there is none
+    executable_icons: Arc<RwLock<HashMap<PathBuf, Option<Arc<RgbaImage>>>>>,
+    /// Latest screenshot taken of each instance this session
+    pub screenshots: Arc<RwLock<HashMap<InstanceId, Screenshot>>>,
+    /// User-defined color themes, sorted by name
+    custom_themes: Arc<RwLock<Vec<CustomTheme>>>,
+    /// Window thumbnails are being captured right now
+    thumbnail_capture_running: Arc<AtomicBool>,
+    /// The quick switcher hotkey was pressed and the UI has not opened it yet
+    switcher_requested: Arc<AtomicBool>,
+    /// Command lines given at startup or forwarded by a second start, waiting for the UI
+    activations: Arc<RwLock<Vec<Activation>>>,
+    /// Launches that went ahead despite not fitting in memory, waiting for the UI
+    launch_warnings: Arc<RwLock<Vec<String>>>,
+    /// Sync folder profiles and quick launch items are mirrored into
+    profile_sync: Arc<RwLock<ProfileSync>>,
+    /// Last time health checks ran
+    last_health_check: Arc<RwLock<Option<Instant>>>,
+    /// Last pass closing handles of processes that are gone
+    last_handle_reconcile: Arc<RwLock<Option<Instant>>>,
+    /// Health checks are running right now
+    health_check_running: Arc<AtomicBool>,
+    /// Health changes found by the background checks, waiting for the UI
+    health_events: Arc<RwLock<Vec<HealthEvent>>>,
+    /// Processes that ended on their own, waiting for the UI
+    exit_events: Arc<RwLock<Vec<ExitEvent>>>,
+    /// Recent crash times per instance, for crash loop detection
+    crash_history: Arc<RwLock<HashMap<InstanceId, Vec<Instant>>>>,
+    /// Host memory use is above the alert threshold
+    host_memory_alerted: Arc<AtomicBool>,
+    /// Timings and footprint of the app itself
+    pub profiler: Arc<RwLock<Profiler>>,
+    /// Why each global hotkey that failed to register did, by shortcut
+    pub hotkey_errors: Arc<RwLock<HashMap<String, String>>>,
+    /// Instances a macro is being played into right now
+    macros_running: Arc<RwLock<HashSet<InstanceId>>>,
+    /// Instances whose data directory is being compressed, decompressed,
+    /// snapshotted or restored
+    data_jobs: Arc<RwLock<HashSet<InstanceId>>>,
+    /// Snapshots of each instance, listed once and refreshed after changes
+    snapshot_lists: Arc<RwLock<HashMap<InstanceId, Vec<Snapshot>>>>,
+    /// Checkpoints on disk by directory, listed once and refreshed after changes
+    checkpoint_lists: Arc<RwLock<HashMap<PathBuf, Vec<Checkpoint>>>>,
+    /// Bundle imports running in the background
+    bundle_imports: Arc<AtomicUsize>,
+    /// Outcomes of background bundle exports and imports not yet shown
+    bundle_results: Arc<RwLock<Vec<Result<String, String>>>>,
+    /// Last time active instances were checked for crash dialogs
+    last_crash_dialog_check: Arc<RwLock<Option<Instant>>>,
+    /// Crash dialogs are being looked for right now
+    crash_dialog_check_running: Arc<AtomicBool>,
+    /// Last time the window titles of renamed instances were checked
+    last_title_check: Arc<RwLock<Option<Instant>>>,
+    /// Window titles are being checked right now
+    title_check_running: Arc<AtomicBool>,
+    /// Instance environments are being read back right now
+    isolation_check_running: Arc<AtomicBool>,
+    /// Input broadcasting to the configured group is switched on
+    broadcasting: Arc<AtomicBool>,
+    /// Processes input is being mirrored between right now
+    broadcast_targets: Arc<RwLock<Option<BroadcastTargets>>>,
+    /// Timed launches of instances and profiles
+    pub schedules: Arc<RwLock<HashMap<ScheduleId, Schedule>>>,
+    /// Schedules due up to this time have been fired
+    last_schedule_check: Arc<RwLock<chrono::DateTime<chrono::Local>>>,
+    /// Bumped whenever an instance is saved, so views derived from configs can refresh
+    instance_revision: Arc<AtomicU64>,
 }
 
 impl AppState {
     /// Create a new application state
     pub fn new(database: Database) -> Result<Self> {
-        // Load settings from database
-        let settings = database.load_settings()?.unwrap_or_default();
+        // Load settings from database, inheriting the administrator's defaults on first launch
+        let settings = match database.load_settings()? {
+            Some(settings) => settings,
+            None => match deployment::load_default_settings() {
+                Some(defaults) => {
+                    info!("Applying machine default settings for new user");
+                    database.save_settings(&defaults)?;
+                    defaults
+                }
+                None => Settings::default(),
+            },
+        };
         let settings = Arc::new(RwLock::new(settings));
 
         // Create data directories
@@ -58,8 +307,13 @@ impl AppState {
         std::fs::create_dir_all(&data_dir)?;
         std::fs::create_dir_all(&instances_dir)?;
 
+        let logs_dir = settings
+            .read()
+            .map_err(|e| anyhow::anyhow!("Settings lock poisoned: {}", e))?
+            .get_logs_directory();
+
         // Initialize process manager
-        let process_manager = SharedProcessManager::new(instances_dir);
+        let process_manager = SharedProcessManager::new(instances_dir, logs_dir);
 
         // Initialize resource monitor
         let monitor_interval = settings
@@ -68,347 +322,3777 @@ impl AppState {
             .monitor_interval_ms;
         let resource_monitor = SharedResourceMonitor::new(monitor_interval);
 
-        // Load profiles from database
-        let profiles = database.load_all_profiles()?;
-        let profiles: HashMap<ProfileId, Profile> =
-            profiles.into_iter().map(|p| (p.id, p)).collect();
+        // Usage limits must hold from the first launch, so today's totals load eagerly
+        let today = UsageTracker::today();
+        let used_today = database.load_usage(today).unwrap_or_else(|e| {
+            error!("Failed to load usage totals: {}", e);
+            HashMap::new()
+        });
+        let usage = UsageTracker::new(today, used_today);
 
-        // Load quick launch items
-        let quick_launch = database.load_quick_launch()?;
+        // A marker left behind means the last run did not exit cleanly. Safe mode
+        // leaves it for the next normal run, whose database knows its instances.
+        // A read-only database shares its data directory, and so the marker, with
+        // another machine
+        let safe_mode = database.is_temporary() || database.foreign_lock().is_some();
+        let recovery = ShutdownMarker::load(&data_dir)
+            .filter(|_| !safe_mode)
+            .filter(|marker| !marker.instances.is_empty())
+            .map(|marker| {
+                warn!(
+                    "Previous run ended unexpectedly with {} instances running",
+                    marker.instances.len()
+                );
+                RecoveryReport::build(marker)
+            });
+        if !safe_mode {
+            if let Err(e) = ShutdownMarker::new(Vec::new()).save(&data_dir) {
+                warn!("Failed to write shutdown marker: {}", e);
+            }
+        }
 
-        // Load groups
-        let groups = database.load_groups()?;
+        // Loaded eagerly so no launch due during startup is missed
+        let schedules = database
+            .load_all_schedules()
+            .unwrap_or_else(|e| {
+                error!("Failed to load schedules: {}", e);
+                Vec::new()
+            })
+            .into_iter()
+            .map(|s| (s.id, s))
+            .collect();
 
-        // Load recent apps
-        let recent_apps = database.load_recent_apps()?;
+        // Needed before the first frame to apply the selected theme
+        let custom_themes = database.load_themes().unwrap_or_else(|e| {
+            error!("Failed to load custom themes: {}", e);
+            Vec::new()
+        });
 
         let database = Arc::new(database);
 
         Ok(Self {
             instances: Arc::new(RwLock::new(HashMap::new())),
-            profiles: Arc::new(RwLock::new(profiles)),
+            profiles: Arc::new(RwLock::new(HashMap::new())),
             settings,
             process_manager,
             resource_monitor,
             database,
-            quick_launch: Arc::new(RwLock::new(quick_launch)),
-            groups: Arc::new(RwLock::new(groups)),
-            recent_apps: Arc::new(RwLock::new(recent_apps)),
+            quick_launch: Arc::new(RwLock::new(Vec::new())),
+            groups: Arc::new(RwLock::new(Vec::new())),
+            recent_apps: Arc::new(RwLock::new(Vec::new())),
             last_resource_update: Arc::new(RwLock::new(Instant::now())),
+            last_copy_scan: Arc::new(RwLock::new(None)),
+            last_log_prune: Arc::new(RwLock::new(None)),
+            rolling_restarts: Arc::new(RwLock::new(Vec::new())),
+            data_loaded: Arc::new(AtomicBool::new(false)),
+            orphans: Arc::new(RwLock::new(Vec::new())),
+            open_files: Arc::new(RwLock::new(HashMap::new())),
+            connections: Arc::new(RwLock::new(HashMap::new())),
+            connections_in_flight: Arc::new(RwLock::new(HashSet::new())),
+            recovery: Arc::new(RwLock::new(recovery)),
+            marked_instances: Arc::new(RwLock::new(Vec::new())),
+            pending_session: Arc::new(RwLock::new(Vec::new())),
+            usage: Arc::new(RwLock::new(usage)),
+            last_usage_flush: Arc::new(RwLock::new(Instant::now())),
+            last_network_check: Arc::new(RwLock::new(None)),
+            network_check_running: Arc::new(AtomicBool::new(false)),
+            region_captures: Arc::new(RwLock::new(HashMap::new())),
+            last_region_capture: Arc::new(RwLock::new(None)),
+            region_capture_running: Arc::new(AtomicBool::new(false)),
+            window_thumbnails: Arc::new(RwLock::new(HashMap::new())),
+            executable_icons: Arc::new(RwLock::new(HashMap::new())),
+            screenshots: Arc::new(RwLock::new(HashMap::new())),
+            custom_themes: Arc::new(RwLock::new(custom_themes)),
+            thumbnail_capture_running: Arc::new(AtomicBool::new(false)),
+            switcher_requested: Arc::new(AtomicBool::new(false)),
+            activations: Arc::new(RwLock::new(Vec::new())),
+            launch_warnings: Arc::new(RwLock::new(Vec::new())),
+            profile_sync: Arc::new(RwLock::new(ProfileSync::default())),
+            last_health_check: Arc::new(RwLock::new(None)),
+            last_handle_reconcile: Arc::new(RwLock::new(None)),
+            health_check_running: Arc::new(AtomicBool::new(false)),
+            health_events: Arc::new(RwLock::new(Vec::new())),
+            exit_events: Arc::new(RwLock::new(Vec::new())),
+            crash_history: Arc::new(RwLock::new(HashMap::new())),
+            host_memory_alerted: Arc::new(AtomicBool::new(false)),
+            profiler: Arc::new(RwLock::new(Profiler::default())),
+            hotkey_errors: Arc::new(RwLock::new(HashMap::new())),
+            macros_running: Arc::new(RwLock::new(HashSet::new())),
+            data_jobs: Arc::new(RwLock::new(HashSet::new())),
+            snapshot_lists: Arc::new(RwLock::new(HashMap::new())),
+            checkpoint_lists: Arc::new(RwLock::new(HashMap::new())),
+            bundle_imports: Arc::new(AtomicUsize::new(0)),
+            bundle_results: Arc::new(RwLock::new(Vec::new())),
+            last_crash_dialog_check: Arc::new(RwLock::new(None)),
+            crash_dialog_check_running: Arc::new(AtomicBool::new(false)),
+            last_title_check: Arc::new(RwLock::new(None)),
+            title_check_running: Arc::new(AtomicBool::new(false)),
+            isolation_check_running: Arc::new(AtomicBool::new(false)),
+            broadcasting: Arc::new(AtomicBool::new(false)),
+            broadcast_targets: Arc::new(RwLock::new(None)),
+            schedules: Arc::new(RwLock::new(schedules)),
+            last_schedule_check: Arc::new(RwLock::new(chrono::Local::now())),
+            instance_revision: Arc::new(AtomicU64::new(0)),
         })
     }
 
-    /// Create a new instance and optionally start it
-    pub fn create_instance(&self, config: InstanceConfig, start: bool) -> Result<InstanceId> {
-        let mut instance = Instance::new(config);
-        let id = instance.id;
-
-        // Add to recent apps
-        self.add_recent_app(&instance.config.executable_path);
-
-        // Start if requested
-        if start {
-            self.process_manager.spawn(&mut instance)?;
-        }
-
-        // Store instance
-        self.instances
-            .write()
-            .expect("Instances lock poisoned")
-            .insert(id, instance.clone());
+    /// Load profiles, quick launch, groups and recent apps on a background thread.
+    /// Each collection is published as soon as it is read so the UI fills in progressively.
+    pub fn load_deferred_data(&self) {
+        let state = self.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = state.load_profiles() {
+                error!("Failed to load profiles: {}", e);
+            }
+            if let Err(e) = state.load_quick_launch() {
+                error!("Failed to load quick launch items: {}", e);
+            }
+            if let Err(e) = state.load_groups() {
+                error!("Failed to load groups: {}", e);
+            }
+            if let Err(e) = state.load_recent_apps() {
+                error!("Failed to load recent apps: {}", e);
+            }
+            if let Err(e) = state.load_executable_icons() {
+                error!("Failed to load executable icons: {}", e);
+            }
 
-        // Persist to database
-        self.database.save_instance(&instance)?;
+            state.data_loaded.store(true, Ordering::Release);
+            info!("Deferred data loaded");
 
-        info!("Created instance {} (started: {})", id, start);
-        Ok(id)
+            // Leftovers from a crash; the UI offers to adopt or terminate them.
+            // Safe mode tracks no instances, so every running one would look orphaned.
+            if !state.safe_mode() {
+                state.scan_orphans();
+            }
+        });
     }
 
-    /// Start an existing instance
-    pub fn start_instance(&self, id: InstanceId) -> Result<()> {
-        let mut instances = self
+    /// Look for untracked processes running in our instance data directories
+    pub fn scan_orphans(&self) {
+        let Ok(instances_dir) = self.settings.read().map(|s| s.get_instances_directory()) else {
+            return;
+        };
+        let mut tracked: Vec<u32> = self
             .instances
-            .write()
-            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
-        let instance = instances.get_mut(&id).context("Instance not found")?;
+            .read()
+            .map(|instances| instances.values().filter_map(|i| i.pid).collect())
+            .unwrap_or_default();
+        // Survivors of a crash are offered in the recovery report instead
+        if let Ok(Some(report)) = self.recovery.read().as_deref() {
+            tracked.extend(report.alive().map(|r| r.instance.pid));
+        }
 
-        if instance.status.is_active() {
-            anyhow::bail!("Instance is already running");
+        let found = orphans::scan(&instances_dir, &tracked);
+        if !found.is_empty() {
+            warn!("Found {} orphaned instance processes", found.len());
         }
+        if let Ok(mut orphans) = self.orphans.write() {
+            *orphans = found;
+        }
+    }
 
-        self.process_manager.spawn(instance)?;
-        self.database.update_instance_status(id, &instance.status)?;
+    /// Track an orphaned process as a running instance, reusing its old configuration if known
+    pub fn adopt_orphan(&self, pid: u32) -> Result<InstanceId> {
+        let orphan = self.take_orphan(pid)?;
 
-        Ok(())
-    }
+        let known = orphan.instance_id.and_then(|id| {
+            self.database
+                .load_all_instances()
+                .ok()?
+                .into_iter()
+                .find(|i| i.id == id)
+        });
+        let mut instance = known.unwrap_or_else(|| {
+            let mut instance = Instance::new(InstanceConfig {
+                name: orphan.name.clone(),
+                executable_path: orphan.exe.clone().unwrap_or_default(),
+                use_environment_isolation: true,
+                ..Default::default()
+            });
+            if let Some(id) = orphan.instance_id {
+                instance.id = id;
+            }
+            instance
+        });
 
-    /// Stop an instance
-    pub fn stop_instance(&self, id: InstanceId) -> Result<()> {
-        let mut instances = self
-            .instances
-            .write()
-            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
-        let instance = instances.get_mut(&id).context("Instance not found")?;
+        instance.mark_starting(pid);
+        instance.mark_running();
+        let id = instance.id;
 
-        if !instance.status.is_active() {
-            return Ok(()); // Already stopped
-        }
+        self.save_instance_record(&instance)?;
+        self.instances
+            .write()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?
+            .insert(id, instance);
 
-        self.process_manager.stop(instance)?;
-        self.database.update_instance_status(id, &instance.status)?;
+        info!("Adopted orphaned process {} as instance {}", pid, id);
+        Ok(id)
+    }
 
+    /// Kill an orphaned process
+    pub fn terminate_orphan(&self, pid: u32) -> Result<()> {
+        self.take_orphan(pid)?;
+        crate::platform::kill_process(pid)?;
+        info!("Terminated orphaned process {}", pid);
         Ok(())
     }
 
-    /// Kill an instance forcefully
-    pub fn kill_instance(&self, id: InstanceId) -> Result<()> {
-        let mut instances = self
-            .instances
+    /// Leave the remaining orphaned processes alone
+    pub fn dismiss_orphans(&self) {
+        if let Ok(mut orphans) = self.orphans.write() {
+            orphans.clear();
+        }
+    }
+
+    fn take_orphan(&self, pid: u32) -> Result<OrphanProcess> {
+        let mut orphans = self
+            .orphans
             .write()
-            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
-        let instance = instances.get_mut(&id).context("Instance not found")?;
+            .map_err(|e| anyhow::anyhow!("Orphans lock poisoned: {}", e))?;
+        let index = orphans
+            .iter()
+            .position(|o| o.pid == pid)
+            .context("Orphaned process not found")?;
+        Ok(orphans.remove(index))
+    }
 
-        self.process_manager.kill(instance)?;
-        self.database.update_instance_status(id, &instance.status)?;
+    /// Track a process that survived the previous run as its old instance
+    pub fn adopt_recovered(&self, id: InstanceId) -> Result<()> {
+        let recovered = {
+            let mut recovery = self
+                .recovery
+                .write()
+                .map_err(|e| anyhow::anyhow!("Recovery lock poisoned: {}", e))?;
+            let report = recovery.as_mut().context("No recovery report")?;
+            let index = report
+                .instances
+                .iter()
+                .position(|r| r.alive && r.instance.id == id)
+                .context("Instance is not running")?;
+            report.instances.remove(index).instance
+        };
 
-        Ok(())
-    }
+        let mut instance = Instance::new(recovered.config);
+        instance.id = recovered.id;
+        instance.mark_starting(recovered.pid);
+        instance.started_at = recovered.started_at.or(instance.started_at);
+        instance.mark_running();
 
-    /// Pause an instance
-    pub fn pause_instance(&self, id: InstanceId) -> Result<()> {
-        let mut instances = self
-            .instances
+        self.save_instance_record(&instance)?;
+        self.instances
             .write()
-            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
-        let instance = instances.get_mut(&id).context("Instance not found")?;
-
-        self.process_manager.pause(instance)?;
-        self.database.update_instance_status(id, &instance.status)?;
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?
+            .insert(id, instance);
 
+        info!(
+            "Re-adopted process {} as instance {}",
+            recovered.pid, recovered.id
+        );
         Ok(())
     }
 
-    /// Resume a paused instance
-    pub fn resume_instance(&self, id: InstanceId) -> Result<()> {
-        let mut instances = self
-            .instances
-            .write()
-            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
-        let instance = instances.get_mut(&id).context("Instance not found")?;
+    /// Whether the app was started with `--safe-mode`: nothing is saved and
+    /// the previous session is not restored
+    pub fn safe_mode(&self) -> bool {
+        self.database.is_temporary()
+    }
 
-        self.process_manager.resume(instance)?;
-        self.database.update_instance_status(id, &instance.status)?;
+    /// Another machine using the same data directory, while the database is
+    /// read-only because of it
+    pub fn data_lock_conflict(&self) -> Option<DataLock> {
+        self.database.foreign_lock()
+    }
 
-        Ok(())
+    /// Keep our claim on the data directory fresh for other machines to see
+    pub fn refresh_data_lock(&self) {
+        self.database.refresh_lock();
     }
 
-    /// Remove an instance (must be stopped first)
-    pub fn remove_instance(&self, id: InstanceId, cleanup_data: bool) -> Result<()> {
-        let mut instances = self
-            .instances
-            .write()
-            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
-        let instance = instances.get(&id).context("Instance not found")?;
+    /// Give up our claim on the data directory on a clean exit
+    pub fn release_data_lock(&self) {
+        self.database.release_lock();
+    }
 
-        if instance.status.is_active() {
-            anyhow::bail!("Cannot remove a running instance");
+    /// Merge with the sync folder when it is first set, then pick up the
+    /// profiles and quick launch items other machines change in it
+    pub fn sync_profiles(&self) {
+        if !self.is_data_loaded() || self.data_lock_conflict().is_some() {
+            return;
         }
+        if let Err(e) = self.sync_profiles_now() {
+            warn!("Failed to sync profiles: {}", e);
+        }
+    }
 
-        // Remove from database
-        self.database.delete_instance(id)?;
+    fn sync_profiles_now(&self) -> Result<()> {
+        let root = self
+            .settings
+            .read()
+            .map_err(|e| anyhow::anyhow!("Settings lock poisoned: {}", e))?
+            .sync_directory
+            .clone();
+        let mut sync = self
+            .profile_sync
+            .write()
+            .map_err(|e| anyhow::anyhow!("Profile sync lock poisoned: {}", e))?;
+        let merge = sync.set_root(root.as_deref());
+        if !sync.is_enabled() || !(merge || sync.scan_due()) {
+            return Ok(());
+        }
+        let changes = sync.scan()?;
 
-        // Clean up data directory if requested
-        if cleanup_data {
-            let data_dir = self
-                .settings
-                .read()
-                .map(|s| s.get_instances_directory())
-                .unwrap_or_else(|_| std::path::PathBuf::from("."));
-            let instance_dir = data_dir.join(id.to_string());
-            if instance_dir.exists() {
-                if let Err(e) = std::fs::remove_dir_all(&instance_dir) {
-                    warn!("Failed to clean up instance data: {}", e);
+        let mut profiles = self
+            .profiles
+            .write()
+            .map_err(|e| anyhow::anyhow!("Profiles lock poisoned: {}", e))?;
+        for tombstone in &changes.deleted {
+            if profiles
+                .get(&tombstone.id)
+                .is_some_and(|p| p.modified_at <= tombstone.deleted_at)
+            {
+                self.database.delete_profile(tombstone.id)?;
+                if let Some(profile) = profiles.remove(&tombstone.id) {
+                    info!(
+                        "Removed profile '{}' deleted on another machine",
+                        profile.name
+                    );
                 }
             }
         }
+        // Profiles the folder holds at least as recent a copy of
+        let mut up_to_date = HashSet::new();
+        for remote in changes.profiles {
+            let deleted = changes
+                .deleted
+                .iter()
+                .any(|t| t.id == remote.id && t.deleted_at >= remote.modified_at);
+            if deleted {
+                continue;
+            }
+            match profiles.get(&remote.id) {
+                Some(local) if local.modified_at > remote.modified_at => continue,
+                Some(local) if local.modified_at == remote.modified_at => {}
+                _ => {
+                    self.database.save_profile(&remote)?;
+                    info!("Synced profile '{}'", remote.name);
+                    profiles.insert(remote.id, remote.clone());
+                }
+            }
+            up_to_date.insert(remote.id);
+        }
+        if merge {
+            // Share what only this machine has, or has a newer copy of
+            for profile in profiles.values() {
+                if !up_to_date.contains(&profile.id) {
+                    sync.write_profile(profile)?;
+                }
+            }
+        }
+        drop(profiles);
 
-        // Remove from state
-        instances.remove(&id);
-
-        info!("Removed instance {}", id);
+        let mut quick_launch = self
+            .quick_launch
+            .write()
+            .map_err(|e| anyhow::anyhow!("Quick launch lock poisoned: {}", e))?;
+        if let Some(remote) = changes.quick_launch {
+            if merge {
+                // Keep the items of both machines
+                let known: Vec<_> = quick_launch
+                    .iter()
+                    .filter_map(|c| serde_json::to_value(c).ok())
+                    .collect();
+                let extra: Vec<_> = remote
+                    .into_iter()
+                    .filter(|c| serde_json::to_value(c).is_ok_and(|v| !known.contains(&v)))
+                    .collect();
+                quick_launch.extend(extra);
+            } else {
+                *quick_launch = remote;
+            }
+            self.database.save_quick_launch(&quick_launch)?;
+        }
+        if merge {
+            sync.write_quick_launch(&quick_launch)?;
+        }
         Ok(())
     }
 
-    /// Restart an instance
-    pub fn restart_instance(&self, id: InstanceId) -> Result<()> {
-        self.stop_instance(id)?;
-        std::thread::sleep(Duration::from_millis(500));
-        self.start_instance(id)?;
-        Ok(())
+    /// Copy a saved profile into the sync folder, if one is set
+    fn mirror_profile(&self, profile: &Profile) {
+        if let Ok(mut sync) = self.profile_sync.write() {
+            if let Err(e) = sync.write_profile(profile) {
+                warn!("Failed to sync profile '{}': {}", profile.name, e);
+            }
+        }
     }
 
-    /// Start all instances in a profile
-    pub fn launch_profile(&self, profile_id: ProfileId) -> Result<Vec<InstanceId>> {
-        // Extract data from profile with minimal lock hold time
-        let (staggered, delay, configs) = {
-            let mut profiles = self
-                .profiles
-                .write()
-                .map_err(|e| anyhow::anyhow!("Profiles lock poisoned: {}", e))?;
-            let profile = profiles.get_mut(&profile_id).context("Profile not found")?;
+    /// Close the recovery report, leaving surviving processes untracked
+    pub fn dismiss_recovery(&self) {
+        if let Ok(mut recovery) = self.recovery.write() {
+            *recovery = None;
+        }
+    }
 
-            profile.mark_used();
-            (
-                profile.staggered_launch,
-                Duration::from_millis(profile.launch_delay_ms as u64),
-                profile.instances.clone(),
-            )
-        }; // Lock released here before any I/O operations
+    /// Rewrite the dirty shutdown marker when the set of running instances changed
+    pub fn update_shutdown_marker(&self) {
+        if self.safe_mode() || self.data_lock_conflict().is_some() {
+            return;
+        }
+        let Ok(instances) = self.instances.read() else {
+            return;
+        };
+        let mut running: Vec<MarkedInstance> = instances
+            .values()
+            .filter(|i| i.status.is_active())
+            .filter_map(|i| {
+                Some(MarkedInstance {
+                    id: i.id,
+                    pid: i.pid?,
+                    config: i.config.clone(),
+                    started_at: i.started_at,
+                })
+            })
+            .collect();
+        drop(instances);
+        running.sort_by_key(|i| i.pid);
 
-        let mut ids = Vec::new();
-        for (i, config) in configs.into_iter().enumerate() {
-            if staggered && i > 0 {
-                std::thread::sleep(delay);
-            }
-            let id = self.create_instance(config, true)?;
-            ids.push(id);
+        let current: Vec<(InstanceId, u32)> = running.iter().map(|i| (i.id, i.pid)).collect();
+        let Ok(mut marked) = self.marked_instances.write() else {
+            return;
+        };
+        if *marked == current {
+            return;
         }
 
-        // Update profile in database
-        if let Ok(profiles) = self.profiles.read() {
-            if let Some(profile) = profiles.get(&profile_id) {
-                self.database.save_profile(profile)?;
-            }
+        let Ok(data_dir) = self.settings.read().map(|s| s.get_data_directory()) else {
+            return;
+        };
+        match ShutdownMarker::new(running).save(&data_dir) {
+            Ok(()) => *marked = current,
+            Err(e) => warn!("Failed to write shutdown marker: {}", e),
         }
+    }
 
-        Ok(ids)
+    /// Remove the dirty shutdown marker on a clean exit
+    pub fn clear_shutdown_marker(&self) {
+        if self.safe_mode() || self.data_lock_conflict().is_some() {
+            return;
+        }
+        let Ok(data_dir) = self.settings.read().map(|s| s.get_data_directory()) else {
+            return;
+        };
+        if let Err(e) = ShutdownMarker::clear(&data_dir) {
+            warn!("Failed to remove shutdown marker: {}", e);
+        }
     }
 
-    /// Stop all running instances
-    pub fn stop_all(&self) -> Result<()> {
-        let ids: Vec<InstanceId> = self
+    /// List the files and sockets an instance holds open, on a background thread
+    pub fn inspect_open_files(&self, id: InstanceId) -> Result<()> {
+        let pid = self
             .instances
             .read()
-            .map(|i| i.keys().copied().collect())
-            .unwrap_or_default();
-        for id in ids {
-            if let Err(e) = self.stop_instance(id) {
-                error!("Failed to stop instance {}: {}", id, e);
-            }
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?
+            .get(&id)
+            .and_then(|i| i.pid)
+            .context("Instance is not running")?;
+
+        let mut open_files = self
+            .open_files
+            .write()
+            .map_err(|e| anyhow::anyhow!("Open files lock poisoned: {}", e))?;
+        if matches!(open_files.get(&id), Some(Inspection::Loading)) {
+            return Ok(());
         }
+        open_files.insert(id, Inspection::Loading);
+        drop(open_files);
+
+        let open_files = Arc::clone(&self.open_files);
+        std::thread::spawn(move || {
+            let result = crate::platform::open_handles(pid);
+            if let Ok(mut open_files) = open_files.write() {
+                open_files.insert(id, Inspection::finished(result));
+            }
+        });
         Ok(())
     }
 
-    /// Pause all running instances
-    pub fn pause_all(&self) -> Result<()> {
-        let ids: Vec<InstanceId> = self
+    /// List an instance's TCP/UDP connections on a background thread. The previous
+    /// snapshot stays visible meanwhile and is used to work out throughput.
+    pub fn inspect_connections(&self, id: InstanceId) -> Result<()> {
+        let pid = self
             .instances
             .read()
-            .map(|i| i.keys().copied().collect())
-            .unwrap_or_default();
-        for id in ids {
-            if let Err(e) = self.pause_instance(id) {
-                error!("Failed to pause instance {}: {}", id, e);
-            }
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?
+            .get(&id)
+            .and_then(|i| i.pid)
+            .context("Instance is not running")?;
+
+        if !self
+            .connections_in_flight
+            .write()
+            .map_err(|e| anyhow::anyhow!("Connections lock poisoned: {}", e))?
+            .insert(id)
+        {
+            return Ok(());
         }
+
+        let previous = {
+            let mut connections = self
+                .connections
+                .write()
+                .map_err(|e| anyhow::anyhow!("Connections lock poisoned: {}", e))?;
+            match connections.get(&id) {
+                Some(Inspection::Ready { items, taken_at }) => Some((items.clone(), *taken_at)),
+                _ => {
+                    connections.insert(id, Inspection::Loading);
+                    None
+                }
+            }
+        };
+
+        let connections = Arc::clone(&self.connections);
+        let in_flight = Arc::clone(&self.connections_in_flight);
+        std::thread::spawn(move || {
+            let mut inspection = Inspection::finished(crate::platform::connections(pid));
+            if let (Inspection::Ready { items, taken_at }, Some((before, before_at))) =
+                (&mut inspection, previous)
+            {
+                let elapsed = (*taken_at - before_at).num_milliseconds() as f64 / 1000.0;
+                Connection::fill_rates(items, &before, elapsed);
+            }
+            if let Ok(mut connections) = connections.write() {
+                connections.insert(id, inspection);
+            }
+            if let Ok(mut in_flight) = in_flight.write() {
+                in_flight.remove(&id);
+            }
+        });
         Ok(())
     }
 
-    /// Resume all paused instances
-    pub fn resume_all(&self) -> Result<()> {
-        let ids: Vec<InstanceId> = self
-            .instances
-            .read()
-            .map(|i| i.keys().copied().collect())
+    /// Whether the deferred startup data has finished loading
+    pub fn is_data_loaded(&self) -> bool {
+        self.data_loaded.load(Ordering::Acquire)
+    }
+
+    /// Counter that changes every time an instance is saved
+    pub fn instance_revision(&self) -> u64 {
+        self.instance_revision.load(Ordering::Acquire)
+    }
+
+    /// Persist an instance and bump the instance revision
+    fn save_instance_record(&self, instance: &Instance) -> Result<()> {
+        self.database.save_instance(instance)?;
+        self.instance_revision.fetch_add(1, Ordering::AcqRel);
+        Ok(())
+    }
+
+    /// Load profiles from the database, keeping any saved before the load finished
+    fn load_profiles(&self) -> Result<()> {
+        let mut loaded: HashMap<ProfileId, Profile> = self
+            .database
+            .load_all_profiles()?
+            .into_iter()
+            .map(|p| (p.id, p))
+            .collect();
+
+        // Add machine-wide templates; a user's own copy takes precedence
+        for template in deployment::load_shared_templates() {
+            loaded.entry(template.id).or_insert(template);
+        }
+
+        let mut profiles = self
+            .profiles
+            .write()
+            .map_err(|e| anyhow::anyhow!("Profiles lock poisoned: {}", e))?;
+        for (id, profile) in loaded {
+            profiles.entry(id).or_insert(profile);
+        }
+        Ok(())
+    }
+
+    /// Load quick launch items, placing them ahead of any added before the load finished
+    fn load_quick_launch(&self) -> Result<()> {
+        let loaded = self.database.load_quick_launch()?;
+        let mut quick_launch = self
+            .quick_launch
+            .write()
+            .map_err(|e| anyhow::anyhow!("Quick launch lock poisoned: {}", e))?;
+        let added_early = !quick_launch.is_empty();
+        quick_launch.splice(0..0, loaded);
+        if added_early {
+            self.database.save_quick_launch(&quick_launch)?;
+        }
+        Ok(())
+    }
+
+    /// Load groups, keeping any added before the load finished
+    fn load_groups(&self) -> Result<()> {
+        let loaded = self.database.load_groups()?;
+        let mut groups = self
+            .groups
+            .write()
+            .map_err(|e| anyhow::anyhow!("Groups lock poisoned: {}", e))?;
+        let added_early = std::mem::replace(&mut *groups, loaded);
+        let needs_save = !added_early.is_empty();
+        for group in added_early {
+            if !groups.contains(&group) {
+                groups.push(group);
+            }
+        }
+        if needs_save {
+            self.database.save_groups(&groups)?;
+        }
+        Ok(())
+    }
+
+    /// Load recent apps, keeping any launched before the load finished at the front
+    fn load_recent_apps(&self) -> Result<()> {
+        let loaded = self.database.load_recent_apps()?;
+        let mut recent = self
+            .recent_apps
+            .write()
+            .map_err(|e| anyhow::anyhow!("Recent apps lock poisoned: {}", e))?;
+        let needs_save = !recent.is_empty();
+        for path in loaded {
+            if !recent.contains(&path) {
+                recent.push(path);
+            }
+        }
+        recent.truncate(10);
+        if needs_save {
+            self.database.save_recent_apps(&recent)?;
+        }
+        Ok(())
+    }
+
+    /// Load stored icons; none are extracted before this finished
+    fn load_executable_icons(&self) -> Result<()> {
+        let loaded = self.database.load_executable_icons()?;
+        let mut icons = self
+            .executable_icons
+            .write()
+            .map_err(|e| anyhow::anyhow!("Icons lock poisoned: {}", e))?;
+        for (path, png) in loaded {
+            match executable_icon::decode(&png) {
+                Ok(icon) => {
+                    icons.insert(path, Some(Arc::new(icon)));
+                }
+                Err(e) => warn!("Skipping stored icon of {}: {}", path.display(), e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Shell icon of an executable. The first request for a path without a
+    /// stored icon extracts it on a background thread and returns None.
+    pub fn executable_icon(&self, path: &Path) -> Option<Arc<RgbaImage>> {
+        if let Some(icon) = self
+            .executable_icons
+            .read()
+            .ok()
+            .and_then(|icons| icons.get(path).cloned())
+        {
+            return icon;
+        }
+        // Wait for the stored icons rather than extracting them all again
+        if !self.is_data_loaded() || path.as_os_str().is_empty() {
+            return None;
+        }
+        self.executable_icons
+            .write()
+            .ok()?
+            .insert(path.to_path_buf(), None);
+
+        let path = path.to_path_buf();
+        let icons = Arc::clone(&self.executable_icons);
+        let database = Arc::clone(&self.database);
+        std::thread::spawn(move || match executable_icon::extract(&path) {
+            Ok((icon, png)) => {
+                if let Err(e) = database.save_executable_icon(&path, &png) {
+                    warn!("Failed to store icon of {}: {}", path.display(), e);
+                }
+                if let Ok(mut icons) = icons.write() {
+                    icons.insert(path, Some(Arc::new(icon)));
+                }
+            }
+            Err(e) => debug!("No icon for {}: {}", path.display(), e),
+        });
+        None
+    }
+
+    /// Create a new instance and optionally start it
+    pub fn create_instance(&self, config: InstanceConfig, start: bool) -> Result<InstanceId> {
+        let mut instance = Instance::new(config);
+        let id = instance.id;
+        instance.compressed_bytes = self
+            .data_directory_of(&instance)
+            .ok()
+            .and_then(|dir| data_compression::compressed_size(&dir));
+
+        // Add to recent apps
+        self.add_recent_app(&instance.config.executable_path);
+        self.executable_icon(&instance.config.executable_path);
+
+        // Start if requested
+        let unpack_first = start && instance.compressed_bytes.is_some();
+        if unpack_first {
+            self.check_usage_budget(&instance.config)?;
+            instance.mark_pending();
+        } else if start && instance.config.network_condition.is_some() {
+            self.check_usage_budget(&instance.config)?;
+            instance.mark_pending();
+            self.request_network_check();
+        } else if start {
+            {
+                let instances = self
+                    .instances
+                    .read()
+                    .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+                check_launch_conflicts(&instance.config, None, &instances)?;
+                self.check_memory_reservation(&instance.config, None, &instances)?;
+            }
+            self.check_usage_budget(&instance.config)?;
+            self.process_manager.spawn(&mut instance)?;
+            let pid = instance.pid.map(|pid| format!("PID {}", pid));
+            let launch_id = instance.launch_id.as_deref();
+            self.record_event(id, launch_id, HistoryEvent::Started, pid.as_deref());
+        }
+
+        // Store instance
+        self.instances
+            .write()
+            .expect("Instances lock poisoned")
+            .insert(id, instance.clone());
+
+        // Persist to database
+        self.save_instance_record(&instance)?;
+        if unpack_first {
+            self.spawn_decompression(id, true)?;
+        }
+
+        info!("Created instance {} (started: {})", id, start);
+        Ok(id)
+    }
+
+    /// Start an existing instance. Instances with a network condition wait in
+    /// the pending state until it holds, and compressed data is unpacked first.
+    pub fn start_instance(&self, id: InstanceId) -> Result<()> {
+        self.launch_instance(id, true)
+    }
+
+    fn launch_instance(&self, id: InstanceId, wait_for_network: bool) -> Result<()> {
+        let instances = self
+            .instances
+            .write()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+        self.launch_locked(instances, id, wait_for_network)
+    }
+
+    /// Launch an instance with the instances lock already held
+    fn launch_locked(
+        &self,
+        mut instances: RwLockWriteGuard<'_, HashMap<InstanceId, Instance>>,
+        id: InstanceId,
+        wait_for_network: bool,
+    ) -> Result<()> {
+        let instance = instances.get_mut(&id).context("Instance not found")?;
+
+        if instance.status.is_active() {
+            anyhow::bail!("Instance is already running");
+        }
+        if instance.status == InstanceStatus::Stopping {
+            anyhow::bail!("Instance is still stopping");
+        }
+        // Edits made while it ran, keeping live changes made since
+        if let Some(mut pending) = instance.pending_config.take() {
+            pending.apply_live_settings(&instance.config);
+            instance.config = pending;
+            self.save_instance_record(instance)?;
+            info!("Applied pending changes to '{}'", instance.display_name());
+        }
+        if self.is_data_busy(id) {
+            if instance.compressed_bytes.is_none() {
+                anyhow::bail!("Instance data is still being compressed");
+            }
+            // Already being unpacked for this launch
+            return Ok(());
+        }
+        if instance.compressed_bytes.is_some() {
+            self.check_usage_budget(&instance.config)?;
+            instance.mark_pending();
+            self.database.update_instance_status(id, &instance.status)?;
+            drop(instances);
+            return self.spawn_decompression(id, true);
+        }
+        if wait_for_network && instance.status == InstanceStatus::Pending {
+            return Ok(());
+        }
+
+        let config = instance.config.clone();
+        if wait_for_network && config.network_condition.is_some() {
+            self.check_usage_budget(&config)?;
+            instance.mark_pending();
+            self.database.update_instance_status(id, &instance.status)?;
+            drop(instances);
+            self.request_network_check();
+            return Ok(());
+        }
+
+        check_launch_conflicts(&config, Some(id), &instances)?;
+        self.check_memory_reservation(&config, Some(id), &instances)?;
+        self.check_usage_budget(&config)?;
+
+        let instance = instances.get_mut(&id).context("Instance not found")?;
+        self.process_manager.spawn(instance)?;
+        self.database.update_instance_status(id, &instance.status)?;
+        let pid = instance.pid.map(|pid| format!("PID {}", pid));
+        let launch_id = instance.launch_id.as_deref();
+        self.record_event(id, launch_id, HistoryEvent::Started, pid.as_deref());
+
+        Ok(())
+    }
+
+    /// Conflicts launching `config` would have with running instances or other programs
+    pub fn launch_conflicts(
+        &self,
+        config: &InstanceConfig,
+        id: Option<InstanceId>,
+    ) -> Vec<Conflict> {
+        self.instances
+            .read()
+            .map(|instances| conflicts::detect(config, id, &instances))
+            .unwrap_or_default()
+    }
+
+    /// Rename an instance and save it
+    pub fn rename_instance(&self, id: InstanceId, name: String) -> Result<()> {
+        let mut instances = self
+            .instances
+            .write()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+        let instance = instances.get_mut(&id).context("Instance not found")?;
+        instance.config.name = name;
+        self.save_instance_record(instance)?;
+        Ok(())
+    }
+
+    /// Save an edited config. A running instance takes the settings that apply
+    /// while it runs at once and keeps the rest for its next launch. Returns
+    /// whether changes are waiting for a restart.
+    pub fn update_instance_config(&self, id: InstanceId, config: InstanceConfig) -> Result<bool> {
+        let mut instances = self
+            .instances
+            .write()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+        let instance = instances.get_mut(&id).context("Instance not found")?;
+        let cpu_percent = config.resource_limits.cpu_percent;
+        let cpu_changed = instance.config.resource_limits.cpu_percent != cpu_percent;
+
+        let pending = if instance.status.is_active() {
+            instance.config.apply_live_settings(&config);
+            let pending = instance.config.differs_at_launch(&config);
+            instance.pending_config = pending.then_some(config);
+            pending
+        } else {
+            instance.config = config;
+            instance.pending_config = None;
+            false
+        };
+        self.save_instance_record(instance)?;
+        drop(instances);
+
+        // Unlike other limits, the CPU cap can change while running
+        if cpu_changed {
+            if let Err(e) = self.set_cpu_limit(id, cpu_percent) {
+                warn!("Failed to change CPU limit: {}", e);
+            }
+        }
+        Ok(pending)
+    }
+
+    /// Drop edits waiting for an instance's next launch
+    pub fn discard_pending_config(&self, id: InstanceId) -> Result<()> {
+        let mut instances = self
+            .instances
+            .write()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+        let instance = instances.get_mut(&id).context("Instance not found")?;
+        instance.pending_config = None;
+        self.save_instance_record(instance)?;
+        Ok(())
+    }
+
+    /// Pin or unpin an instance, keeping it running through "Stop all except pinned"
+    pub fn set_pinned(&self, id: InstanceId, pinned: bool) -> Result<()> {
+        let mut instances = self
+            .instances
+            .write()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+        let instance = instances.get_mut(&id).context("Instance not found")?;
+        instance.config.pinned = pinned;
+        self.save_instance_record(instance)?;
+        Ok(())
+    }
+
+    /// Choose the argument variant used for an instance's next launch and save it
+    pub fn set_active_variant(&self, id: InstanceId, variant: Option<usize>) -> Result<()> {
+        let mut instances = self
+            .instances
+            .write()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+        let instance = instances.get_mut(&id).context("Instance not found")?;
+        instance.config.active_variant = match variant {
+            Some(index) => Some(
+                instance
+                    .config
+                    .argument_variants
+                    .get(index)
+                    .context("Argument variant not found")?
+                    .name
+                    .clone(),
+            ),
+            None => None,
+        };
+        self.save_instance_record(instance)?;
+        Ok(())
+    }
+
+    /// Change an instance's CPU cap, applying it at once if the instance is running
+    pub fn set_cpu_limit(&self, id: InstanceId, percent: u8) -> Result<()> {
+        let mut instances = self
+            .instances
+            .write()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+        let instance = instances.get_mut(&id).context("Instance not found")?;
+        instance.config.resource_limits.cpu_percent = percent.min(100);
+        self.save_instance_record(instance)?;
+        if let Some(pid) = instance.pid.filter(|_| instance.status.is_active()) {
+            super::cpu_throttle::set_limit(pid, percent.min(100))?;
+        }
+        Ok(())
+    }
+
+    /// Add an event to the instance history and its log; failures are only logged
+    fn record_event(
+        &self,
+        id: InstanceId,
+        launch_id: Option<&str>,
+        event: HistoryEvent,
+        details: Option<&str>,
+    ) {
+        if let Err(e) = self
+            .database
+            .record_instance_event(id, launch_id, event.as_str(), details)
+        {
+            warn!(
+                "Failed to record {} event for {}: {}",
+                event.as_str(),
+                id,
+                e
+            );
+        }
+        if let Err(e) = output_log::write_event(
+            &self.output_log_path(id),
+            event.as_str(),
+            launch_id,
+            details,
+        ) {
+            warn!("Failed to log {} event for {}: {}", event.as_str(), id, e);
+        }
+    }
+
+    /// Add the instance's log tail and crash dumps to a report and save it,
+    /// returning the summary for the history event
+    fn save_crash_report(&self, id: InstanceId, mut report: CrashReport) -> String {
+        let logs_dir = self
+            .settings
+            .read()
+            .map(|s| s.get_logs_directory())
+            .unwrap_or_else(|_| PathBuf::from("logs"));
+        report.collect(&output_log::log_path(&logs_dir, id));
+        match report.save(&logs_dir, id) {
+            Ok(path) => info!("Saved crash report {}", path.display()),
+            Err(e) => warn!("Failed to save crash report for {}: {}", id, e),
+        }
+        report.summary()
+    }
+
+    /// Newest crash report saved for an instance
+    pub fn latest_crash_report(&self, id: InstanceId) -> Option<PathBuf> {
+        let logs_dir = self.settings.read().ok()?.get_logs_directory();
+        crash_report::latest(&logs_dir, id)
+    }
+
+    /// Delete instance logs and crash reports older than the history retention,
+    /// at most once an hour
+    pub fn prune_logs(&self) {
+        if let Ok(mut last_prune) = self.last_log_prune.write() {
+            if last_prune.is_some_and(|t| t.elapsed() < LOG_PRUNE_INTERVAL) {
+                return;
+            }
+            *last_prune = Some(Instant::now());
+        }
+        let Ok((logs_dir, days)) = self
+            .settings
+            .read()
+            .map(|s| (s.get_logs_directory(), s.history_retention_days))
+        else {
+            return;
+        };
+        if days == 0 {
+            return;
+        }
+
+        std::thread::spawn(move || {
+            match output_log::prune(&logs_dir, days) {
+                Ok(0) => {}
+                Ok(removed) => info!("Deleted {} instance logs older than {} days", removed, days),
+                Err(e) => warn!("Failed to delete old instance logs: {}", e),
+            }
+            match crash_report::prune(&logs_dir, days) {
+                Ok(0) => {}
+                Ok(removed) => info!("Deleted {} crash reports older than {} days", removed, days),
+                Err(e) => warn!("Failed to delete old crash reports: {}", e),
+            }
+        });
+    }
+
+    /// A page of instance history, newest first, after the entry `after`
+    pub fn history_page(
+        &self,
+        filter: &HistoryFilter,
+        after: Option<&HistoryEntry>,
+    ) -> Result<Vec<HistoryEntry>> {
+        self.database.query_instance_history(
+            filter,
+            after.map(|e| (e.time, e.id)),
+            history::PAGE_SIZE,
+        )
+    }
+
+    /// Write every history entry matching `filter` to a CSV file
+    pub fn export_history(&self, filter: &HistoryFilter, path: &std::path::Path) -> Result<usize> {
+        let mut entries: Vec<HistoryEntry> = Vec::new();
+        loop {
+            let page = self.history_page(filter, entries.last())?;
+            let done = page.len() < history::PAGE_SIZE;
+            entries.extend(page);
+            if done {
+                break;
+            }
+        }
+
+        let names: HashMap<InstanceId, String> = self
+            .instances
+            .read()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?
+            .values()
+            .map(|i| (i.id, i.display_name().to_string()))
+            .collect();
+        history::export_csv(
+            &entries,
+            |id| names.get(&id).cloned().unwrap_or_default(),
+            path,
+        )?;
+        Ok(entries.len())
+    }
+
+    /// Delete the whole instance history
+    pub fn clear_history(&self) -> Result<()> {
+        self.database.clear_instance_history()?;
+        Ok(())
+    }
+
+    /// Stop an instance
+    pub fn stop_instance(&self, id: InstanceId) -> Result<()> {
+        self.stop_then(id, AfterStop::Nothing)
+    }
+
+    /// Stop an instance, then run `after`. An instance with a stop command is
+    /// left in the stopping state while a background thread waits for it to
+    /// exit, so a slow shutdown does not hold up the caller or the state locks.
+    fn stop_then(&self, id: InstanceId, after: AfterStop) -> Result<()> {
+        let mut instances = self
+            .instances
+            .write()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+        let instance = instances.get_mut(&id).context("Instance not found")?;
+
+        match instance.status {
+            InstanceStatus::Pending => {
+                instance.mark_stopped();
+                self.database.update_instance_status(id, &instance.status)?;
+            }
+            InstanceStatus::Stopping if after == AfterStop::Nothing => return Ok(()),
+            InstanceStatus::Stopping => anyhow::bail!("Instance is still stopping"),
+            // Already stopped
+            status if !status.is_active() => {}
+            _ => {
+                if let Some(command) = self.process_manager.stop_command(instance)? {
+                    let job = StopJob {
+                        id,
+                        pid: instance.pid.context("Instance has no running process")?,
+                        previous: instance.status,
+                        timeout: Duration::from_secs(instance.config.stop_timeout_secs as u64),
+                        after,
+                    };
+                    instance.mark_stopping();
+                    self.database.update_instance_status(id, &instance.status)?;
+                    drop(instances);
+
+                    let state = self.clone();
+                    std::thread::spawn(move || state.finish_stop(job, command));
+                    return Ok(());
+                }
+
+                self.process_manager.stop(instance)?;
+                self.database.update_instance_status(id, &instance.status)?;
+                let launch_id = instance.launch_id.as_deref();
+                self.record_event(id, launch_id, HistoryEvent::Stopped, None);
+            }
+        }
+        drop(instances);
+
+        match after {
+            AfterStop::Nothing => Ok(()),
+            AfterStop::Start => {
+                std::thread::sleep(Duration::from_millis(500));
+                self.start_instance(id)
+            }
+            AfterStop::Compress => self.compress_instance_data(id),
+        }
+    }
+
+    /// Run an instance's stop command and wait for the process to exit, ending
+    /// it ourselves if it does not, then mark it stopped and run the follow-up
+    fn finish_stop(&self, job: StopJob, command: std::process::Command) {
+        let StopJob {
+            id,
+            pid,
+            previous,
+            timeout,
+            after,
+        } = job;
+        let ended = if self
+            .process_manager
+            .run_stop_command(id, pid, command, timeout)
+        {
+            Ok(())
+        } else {
+            process::end_process(pid)
+        };
+        if ended.is_ok() && after == AfterStop::Start {
+            // Give the process a moment to release what it held
+            std::thread::sleep(Duration::from_millis(500));
+        }
+
+        let Ok(mut instances) = self.instances.write() else {
+            return;
+        };
+        // Killed or otherwise finished while the command ran
+        let Some(instance) = instances
+            .get_mut(&id)
+            .filter(|i| i.status == InstanceStatus::Stopping && i.pid == Some(pid))
+        else {
+            return;
+        };
+
+        if let Err(e) = ended {
+            error!("Failed to stop instance {}: {}", id, e);
+            instance.status = previous;
+            instance.last_error = Some(format!("Failed to stop: {:#}", e));
+            let _ = self.database.update_instance_status(id, &instance.status);
+            return;
+        }
+        let result = self
+            .process_manager
+            .release(instance)
+            .and_then(|()| self.database.update_instance_status(id, &instance.status));
+        if let Err(e) = result {
+            error!("Failed to record stop of instance {}: {}", id, e);
+        }
+        let launch_id = instance.launch_id.as_deref();
+        self.record_event(id, launch_id, HistoryEvent::Stopped, None);
+
+        let result = match after {
+            AfterStop::Nothing => Ok(()),
+            // Still under the lock, so nothing sees the instance stopped in between
+            AfterStop::Start => self.launch_locked(instances, id, true),
+            AfterStop::Compress => {
+                drop(instances);
+                self.compress_instance_data(id)
+            }
+        };
+        if let Err(e) = result {
+            error!("Failed to finish stopping instance {}: {}", id, e);
+            if let Ok(mut instances) = self.instances.write() {
+                if let Some(instance) = instances.get_mut(&id) {
+                    instance.last_error = Some(format!("{:#}", e));
+                }
+            }
+        }
+    }
+
+    /// Kill an instance forcefully
+    pub fn kill_instance(&self, id: InstanceId) -> Result<()> {
+        let mut instances = self
+            .instances
+            .write()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+        let instance = instances.get_mut(&id).context("Instance not found")?;
+
+        self.process_manager.kill(instance)?;
+        self.database.update_instance_status(id, &instance.status)?;
+        let launch_id = instance.launch_id.as_deref();
+        self.record_event(id, launch_id, HistoryEvent::Killed, None);
+
+        Ok(())
+    }
+
+    /// Pause an instance
+    pub fn pause_instance(&self, id: InstanceId) -> Result<()> {
+        let mut instances = self
+            .instances
+            .write()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+        let instance = instances.get_mut(&id).context("Instance not found")?;
+
+        self.process_manager.pause(instance)?;
+        self.database.update_instance_status(id, &instance.status)?;
+        let launch_id = instance.launch_id.as_deref();
+        self.record_event(id, launch_id, HistoryEvent::Paused, None);
+
+        Ok(())
+    }
+
+    /// Resume a paused instance
+    pub fn resume_instance(&self, id: InstanceId) -> Result<()> {
+        let mut instances = self
+            .instances
+            .write()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+        let instance = instances.get_mut(&id).context("Instance not found")?;
+
+        self.process_manager.resume(instance)?;
+        self.database.update_instance_status(id, &instance.status)?;
+        let launch_id = instance.launch_id.as_deref();
+        self.record_event(id, launch_id, HistoryEvent::Resumed, None);
+
+        Ok(())
+    }
+
+    /// Remove an instance (must be stopped first)
+    pub fn remove_instance(&self, id: InstanceId, cleanup_data: bool) -> Result<()> {
+        let mut instances = self
+            .instances
+            .write()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+        let instance = instances.get(&id).context("Instance not found")?;
+
+        if instance.status.is_active() {
+            anyhow::bail!("Cannot remove a running instance");
+        }
+
+        // Remove from database
+        self.database.delete_instance(id)?;
+
+        // Clean up data directory if requested
+        if cleanup_data {
+            let data_dir = self
+                .settings
+                .read()
+                .map(|s| s.get_instances_directory())
+                .unwrap_or_else(|_| std::path::PathBuf::from("."));
+            let instance_dir = data_dir.join(id.to_string());
+            if instance_dir.exists() {
+                if let Err(e) = std::fs::remove_dir_all(&instance_dir) {
+                    warn!("Failed to clean up instance data: {}", e);
+                }
+            }
+            if let Err(e) = data_compression::remove(&instance_dir) {
+                warn!("Failed to remove compressed instance data: {}", e);
+            }
+            if let Err(e) = output_log::remove(&self.output_log_path(id)) {
+                warn!("Failed to remove output logs: {}", e);
+            }
+            let logs_dir = self
+                .settings
+                .read()
+                .map(|s| s.get_logs_directory())
+                .unwrap_or_else(|_| std::path::PathBuf::from("logs"));
+            let reports_dir = crash_report::reports_dir(&logs_dir, id);
+            if reports_dir.exists() {
+                if let Err(e) = std::fs::remove_dir_all(&reports_dir) {
+                    warn!("Failed to remove crash reports: {}", e);
+                }
+            }
+            if instance.config.isolation_level == IsolationLevel::AppContainer {
+                if let Err(e) =
+                    crate::platform::delete_app_container(&app_container::container_name(id))
+                {
+                    warn!("Failed to remove AppContainer profile: {}", e);
+                }
+            }
+        }
+
+        // Remove from state
+        instances.remove(&id);
+
+        info!("Removed instance {}", id);
+        Ok(())
+    }
+
+    /// Where an instance's captured output is written
+    pub fn output_log_path(&self, id: InstanceId) -> std::path::PathBuf {
+        let logs_dir = self
+            .settings
+            .read()
+            .map(|s| s.get_logs_directory())
+            .unwrap_or_else(|_| std::path::PathBuf::from("logs"));
+        output_log::log_path(&logs_dir, id)
+    }
+
+    /// Suspend an instance to disk so it can be restored later (experimental)
+    pub fn checkpoint_instance(&self, id: InstanceId) -> Result<Checkpoint> {
+        let checkpoints_dir = {
+            let settings = self
+                .settings
+                .read()
+                .map_err(|e| anyhow::anyhow!("Settings lock poisoned: {}", e))?;
+            if !settings.experimental_checkpoints {
+                anyhow::bail!("Checkpoints are disabled; enable them in Settings > Advanced");
+            }
+            settings.get_checkpoints_directory()
+        };
+
+        let mut instances = self
+            .instances
+            .write()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+        let instance = instances.get_mut(&id).context("Instance not found")?;
+
+        if !instance.status.is_active() {
+            anyhow::bail!("Only running instances can be checkpointed");
+        }
+        let pid = instance.pid.context("Instance has no running process")?;
+
+        let directory = checkpoints_dir.join(format!(
+            "{}-{}",
+            id,
+            chrono::Utc::now().format("%Y%m%d%H%M%S")
+        ));
+        let checkpoint = Checkpoint::new(id, instance.config.clone(), pid, directory);
+
+        if let Err(e) = self
+            .process_manager
+            .checkpoint(instance, &checkpoint.directory)
+        {
+            let _ = std::fs::remove_dir_all(&checkpoint.directory);
+            return Err(e);
+        }
+        let saved = checkpoint.save();
+        self.refresh_checkpoints();
+        saved?;
+        self.database.update_instance_status(id, &instance.status)?;
+
+        info!("Checkpointed instance {} to {:?}", id, checkpoint.directory);
+        Ok(checkpoint)
+    }
+
+    /// Restore a checkpoint, reusing its instance if it still exists (experimental)
+    pub fn restore_checkpoint(&self, checkpoint: &Checkpoint) -> Result<InstanceId> {
+        let enabled = self
+            .settings
+            .read()
+            .map(|s| s.experimental_checkpoints)
+            .unwrap_or(false);
+        if !enabled {
+            anyhow::bail!("Checkpoints are disabled; enable them in Settings > Advanced");
+        }
+
+        let mut instances = self
+            .instances
+            .write()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+
+        let instance = instances.entry(checkpoint.instance_id).or_insert_with(|| {
+            let mut instance = Instance::new(checkpoint.config.clone());
+            instance.id = checkpoint.instance_id;
+            instance
+        });
+        if instance.status.is_active() {
+            anyhow::bail!("Instance is already running");
+        }
+
+        self.process_manager
+            .restore(instance, &checkpoint.directory)?;
+        self.save_instance_record(instance)?;
+
+        // Process images can only be restored once
+        if let Err(e) = checkpoint.delete() {
+            warn!("Failed to remove restored checkpoint: {}", e);
+        }
+        self.refresh_checkpoints();
+
+        Ok(checkpoint.instance_id)
+    }
+
+    /// List checkpoints saved in a directory. The list is read from disk once
+    /// and refreshed after checkpoints are saved, restored or deleted.
+    pub fn list_checkpoints(&self, directory: &Path) -> Vec<Checkpoint> {
+        if let Some(checkpoints) = self
+            .checkpoint_lists
+            .read()
+            .ok()
+            .and_then(|lists| lists.get(directory).cloned())
+        {
+            return checkpoints;
+        }
+        let checkpoints = Checkpoint::list(directory);
+        if let Ok(mut lists) = self.checkpoint_lists.write() {
+            lists.insert(directory.to_path_buf(), checkpoints.clone());
+        }
+        checkpoints
+    }
+
+    /// Delete a saved checkpoint
+    pub fn delete_checkpoint(&self, checkpoint: &Checkpoint) -> Result<()> {
+        let result = checkpoint.delete();
+        self.refresh_checkpoints();
+        result
+    }
+
+    /// Forget the listed checkpoints so they are read again
+    fn refresh_checkpoints(&self) {
+        if let Ok(mut lists) = self.checkpoint_lists.write() {
+            lists.clear();
+        }
+    }
+
+    /// Get the isolated data directory used by an instance
+    pub fn instance_data_directory(&self, id: InstanceId) -> Result<PathBuf> {
+        let instances = self
+            .instances
+            .read()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+        let instance = instances.get(&id).context("Instance not found")?;
+        self.data_directory_of(instance)
+    }
+
+    fn data_directory_of(&self, instance: &Instance) -> Result<PathBuf> {
+        if let Some(ref dir) = instance.config.data_directory {
+            return Ok(dir.clone());
+        }
+        Ok(self
+            .settings
+            .read()
+            .map_err(|e| anyhow::anyhow!("Settings lock poisoned: {}", e))?
+            .get_instances_directory()
+            .join(instance.id.to_string()))
+    }
+
+    /// Whether an instance's data directory is being compressed, decompressed,
+    /// snapshotted or restored
+    pub fn is_data_busy(&self, id: InstanceId) -> bool {
+        self.data_jobs
+            .read()
+            .map(|jobs| jobs.contains(&id))
+            .unwrap_or(false)
+    }
+
+    /// Fail unless an instance's data directory is in place
+    fn ensure_data_unpacked(&self, id: InstanceId) -> Result<()> {
+        let compressed = self
+            .instances
+            .read()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?
+            .get(&id)
+            .is_some_and(|i| i.compressed_bytes.is_some());
+        if compressed || self.is_data_busy(id) {
+            anyhow::bail!("Instance data is compressed; decompress it first");
+        }
+        Ok(())
+    }
+
+    /// Compress a stopped instance's data directory on a background thread.
+    /// It is unpacked again before the next launch.
+    pub fn compress_instance_data(&self, id: InstanceId) -> Result<()> {
+        {
+            let instances = self
+                .instances
+                .read()
+                .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+            let instance = instances.get(&id).context("Instance not found")?;
+            if instance.status.is_active() || instance.status == InstanceStatus::Pending {
+                anyhow::bail!("Stop the instance before compressing its data");
+            }
+            if instance.compressed_bytes.is_some() {
+                anyhow::bail!("Instance data is already compressed");
+            }
+        }
+        let data_dir = self.instance_data_directory(id)?;
+        if !data_dir.is_dir() {
+            anyhow::bail!("Instance has no data to compress");
+        }
+        self.begin_data_job(id)?;
+
+        let state = self.clone();
+        std::thread::spawn(move || {
+            let result = data_compression::compress(&data_dir);
+            if let Ok(mut instances) = state.instances.write() {
+                if let Some(instance) = instances.get_mut(&id) {
+                    // A failed removal still leaves the data compressed
+                    instance.compressed_bytes = data_compression::compressed_size(&data_dir);
+                    if let Err(e) = result {
+                        warn!("Failed to compress data of instance {}: {:#}", id, e);
+                        instance.last_error = Some(format!("Failed to compress data: {:#}", e));
+                    }
+                }
+            }
+            state.end_data_job(id);
+        });
+        Ok(())
+    }
+
+    /// Stop an instance, then compress its data directory
+    pub fn stop_and_compress(&self, id: InstanceId) -> Result<()> {
+        self.stop_then(id, AfterStop::Compress)
+    }
+
+    /// Unpack an instance's compressed data without launching it
+    pub fn decompress_instance_data(&self, id: InstanceId) -> Result<()> {
+        self.spawn_decompression(id, false)
+    }
+
+    /// Unpack an instance's data directory on a background thread, then launch
+    /// it if `launch` is set and the pending launch was not cancelled meanwhile
+    fn spawn_decompression(&self, id: InstanceId, launch: bool) -> Result<()> {
+        let data_dir = self.instance_data_directory(id)?;
+        self.begin_data_job(id)?;
+
+        let state = self.clone();
+        std::thread::spawn(move || {
+            let result = data_compression::decompress(&data_dir);
+            let mut relaunch = false;
+            if let Ok(mut instances) = state.instances.write() {
+                if let Some(instance) = instances.get_mut(&id) {
+                    let pending = instance.status == InstanceStatus::Pending;
+                    match &result {
+                        Ok(()) => instance.compressed_bytes = None,
+                        Err(e) => {
+                            error!("Failed to decompress data of instance {}: {:#}", id, e);
+                            instance.last_error =
+                                Some(format!("Failed to decompress data: {:#}", e));
+                        }
+                    }
+                    if pending {
+                        // Launched again below, now that the data is in place
+                        instance.mark_stopped();
+                        let _ = state.database.update_instance_status(id, &instance.status);
+                        relaunch = launch && result.is_ok();
+                    }
+                }
+            }
+            state.end_data_job(id);
+
+            if relaunch {
+                if let Err(e) = state.start_instance(id) {
+                    error!("Failed to launch instance {}: {}", id, e);
+                    if let Ok(mut instances) = state.instances.write() {
+                        if let Some(instance) = instances.get_mut(&id) {
+                            instance.last_error = Some(e.to_string());
+                        }
+                    }
+                }
+            }
+        });
+        Ok(())
+    }
+
+    fn begin_data_job(&self, id: InstanceId) -> Result<()> {
+        let started = self
+            .data_jobs
+            .write()
+            .map_err(|e| anyhow::anyhow!("Data jobs lock poisoned: {}", e))?
+            .insert(id);
+        if !started {
+            anyhow::bail!("Another operation on the instance data is still running");
+        }
+        Ok(())
+    }
+
+    fn end_data_job(&self, id: InstanceId) {
+        if let Ok(mut jobs) = self.data_jobs.write() {
+            jobs.remove(&id);
+        }
+    }
+
+    /// Directory holding the data snapshots of an instance
+    fn instance_snapshots_directory(&self, id: InstanceId) -> Result<PathBuf> {
+        Ok(self
+            .settings
+            .read()
+            .map_err(|e| anyhow::anyhow!("Settings lock poisoned: {}", e))?
+            .get_snapshots_directory()
+            .join(id.to_string()))
+    }
+
+    /// Take a named snapshot of a stopped instance's data directory on a
+    /// background thread. Failures are reported as the instance's last error.
+    pub fn create_snapshot(&self, id: InstanceId, name: &str) -> Result<()> {
+        self.ensure_data_idle(id, "taking a snapshot")?;
+        let data_dir = self.instance_data_directory(id)?;
+        let snapshots_dir = self.instance_snapshots_directory(id)?;
+        self.begin_data_job(id)?;
+
+        let state = self.clone();
+        let name = name.to_string();
+        std::thread::spawn(move || {
+            let result = Snapshot::create(id, &name, &data_dir, &snapshots_dir);
+            state.finish_snapshot_job(id, result.err().map(|e| ("take snapshot", e)));
+        });
+        Ok(())
+    }
+
+    /// Snapshots of an instance, newest first. The list is read from disk once
+    /// and refreshed after snapshots are taken or deleted.
+    pub fn list_snapshots(&self, id: InstanceId) -> Vec<Snapshot> {
+        if let Some(snapshots) = self
+            .snapshot_lists
+            .read()
+            .ok()
+            .and_then(|lists| lists.get(&id).cloned())
+        {
+            return snapshots;
+        }
+        let snapshots = self
+            .instance_snapshots_directory(id)
+            .map(|dir| Snapshot::list(&dir))
+            .unwrap_or_default();
+        if let Ok(mut lists) = self.snapshot_lists.write() {
+            lists.insert(id, snapshots.clone());
+        }
+        snapshots
+    }
+
+    /// Roll a stopped instance's data directory back to a snapshot on a
+    /// background thread. Failures are reported as the instance's last error.
+    pub fn restore_snapshot(&self, id: InstanceId, snapshot: &Snapshot) -> Result<()> {
+        self.ensure_data_idle(id, "restoring a snapshot")?;
+        let data_dir = self.instance_data_directory(id)?;
+        self.begin_data_job(id)?;
+
+        let state = self.clone();
+        let snapshot = snapshot.clone();
+        std::thread::spawn(move || {
+            let result = snapshot.restore(&data_dir);
+            state.finish_snapshot_job(id, result.err().map(|e| ("restore snapshot", e)));
+        });
+        Ok(())
+    }
+
+    /// Delete a snapshot of an instance
+    pub fn delete_snapshot(&self, id: InstanceId, snapshot: &Snapshot) -> Result<()> {
+        let result = snapshot.delete();
+        self.refresh_snapshots(id);
+        result
+    }
+
+    /// Fail unless an instance is stopped and its data directory is in place
+    fn ensure_data_idle(&self, id: InstanceId, action: &str) -> Result<()> {
+        let is_active = self
+            .instances
+            .read()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?
+            .get(&id)
+            .map(|i| i.status.is_active() || i.status == InstanceStatus::Pending)
+            .context("Instance not found")?;
+        if is_active {
+            anyhow::bail!("Stop the instance before {}", action);
+        }
+        self.ensure_data_unpacked(id)
+    }
+
+    fn finish_snapshot_job(&self, id: InstanceId, failure: Option<(&str, anyhow::Error)>) {
+        if let Some((action, e)) = failure {
+            error!("Failed to {} of instance {}: {:#}", action, id, e);
+            if let Ok(mut instances) = self.instances.write() {
+                if let Some(instance) = instances.get_mut(&id) {
+                    instance.last_error = Some(format!("Failed to {}: {:#}", action, e));
+                }
+            }
+        }
+        self.refresh_snapshots(id);
+        self.end_data_job(id);
+    }
+
+    /// Forget the listed snapshots of an instance so they are read again
+    fn refresh_snapshots(&self, id: InstanceId) {
+        if let Ok(mut lists) = self.snapshot_lists.write() {
+            lists.remove(&id);
+        }
+    }
+
+    /// Export an instance's config and data directory as a portable bundle on
+    /// a background thread; the outcome is reported by `take_bundle_results`
+    pub fn export_instance(&self, id: InstanceId, destination: &std::path::Path) -> Result<()> {
+        let config = self
+            .instances
+            .read()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?
+            .get(&id)
+            .map(|i| i.config.clone())
+            .context("Instance not found")?;
+        self.ensure_data_unpacked(id)?;
+        let data_dir = self.instance_data_directory(id)?;
+        self.begin_data_job(id)?;
+
+        let state = self.clone();
+        let destination = destination.to_path_buf();
+        std::thread::spawn(move || {
+            let result = bundle::export(&config, &data_dir, &destination)
+                .map(|()| format!("Exported '{}' to {}", config.name, destination.display()))
+                .map_err(|e| {
+                    error!("Failed to export instance {}: {:#}", id, e);
+                    format!("Failed to export '{}': {:#}", config.name, e)
+                });
+            state.end_data_job(id);
+            state.push_bundle_result(result);
+        });
+        Ok(())
+    }
+
+    /// Import a bundle created by `export_instance` as a new, stopped instance
+    /// on a background thread; the outcome is reported by `take_bundle_results`
+    pub fn import_instance(&self, bundle_path: &std::path::Path) -> Result<()> {
+        let mut instance = Instance::new(InstanceConfig::default());
+        let data_dir = self
+            .settings
+            .read()
+            .map_err(|e| anyhow::anyhow!("Settings lock poisoned: {}", e))?
+            .get_instances_directory()
+            .join(instance.id.to_string());
+
+        self.bundle_imports.fetch_add(1, Ordering::AcqRel);
+        let state = self.clone();
+        let bundle_path = bundle_path.to_path_buf();
+        std::thread::spawn(move || {
+            let imported = bundle::import(&bundle_path, &data_dir).and_then(|config| {
+                instance.config = config;
+                state.save_instance_record(&instance)?;
+                let name = instance.config.name.clone();
+                state
+                    .instances
+                    .write()
+                    .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?
+                    .insert(instance.id, instance);
+                Ok(name)
+            });
+            let result = match imported {
+                Ok(name) => {
+                    info!("Imported instance '{}' from {:?}", name, bundle_path);
+                    Ok(format!("Imported '{}'", name))
+                }
+                Err(e) => {
+                    let _ = std::fs::remove_dir_all(&data_dir);
+                    error!("Failed to import {:?}: {:#}", bundle_path, e);
+                    Err(format!("Failed to import bundle: {:#}", e))
+                }
+            };
+            state.bundle_imports.fetch_sub(1, Ordering::AcqRel);
+            state.push_bundle_result(result);
+        });
+        Ok(())
+    }
+
+    /// Whether a bundle is being imported right now
+    pub fn is_importing_bundle(&self) -> bool {
+        self.bundle_imports.load(Ordering::Acquire) > 0
+    }
+
+    /// Messages about bundle exports and imports finished since the last call
+    pub fn take_bundle_results(&self) -> Vec<Result<String, String>> {
+        self.bundle_results
+            .write()
+            .map(|mut results| std::mem::take(&mut *results))
+            .unwrap_or_default()
+    }
+
+    fn push_bundle_result(&self, result: Result<String, String>) {
+        if let Ok(mut results) = self.bundle_results.write() {
+            results.push(result);
+        }
+    }
+
+    /// Export the whole application state, with every instance's data when
+    /// `include_data`, to an encrypted archive for moving to another machine
+    pub fn export_migration(
+        &self,
+        destination: &std::path::Path,
+        passphrase: &str,
+        include_data: bool,
+    ) -> Result<()> {
+        let data_dirs = if include_data {
+            self.instances
+                .read()
+                .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?
+                .values()
+                .map(|i| Ok((i.id, self.data_directory_of(i)?)))
+                .collect::<Result<Vec<_>>>()?
+        } else {
+            Vec::new()
+        };
+        let instances_directory = self
+            .settings
+            .read()
+            .map_err(|e| anyhow::anyhow!("Settings lock poisoned: {}", e))?
+            .get_instances_directory();
+
+        migration::export(
+            &self.database,
+            &data_dirs,
+            &instances_directory,
+            destination,
+            passphrase,
+        )
+    }
+
+    /// Add everything from a migration archive that is not here yet, with
+    /// its paths rewritten for this machine
+    pub fn import_migration(
+        &self,
+        package: &MigrationPackage,
+        options: &ImportOptions,
+    ) -> Result<ImportSummary> {
+        let mut summary = ImportSummary::default();
+
+        if let Some(imported) = package.settings.as_ref().filter(|_| options.settings) {
+            let json = imported.export_json()?;
+            {
+                let mut settings = self
+                    .settings
+                    .write()
+                    .map_err(|e| anyhow::anyhow!("Settings lock poisoned: {}", e))?;
+                *settings = Settings::import_json(&json, &settings)?;
+            }
+            self.save_settings()?;
+        }
+
+        let existing: HashSet<InstanceId> = self
+            .instances
+            .read()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?
+            .keys()
+            .copied()
+            .collect();
+        let mut imported = Vec::new();
+        for instance in &package.instances {
+            if existing.contains(&instance.id) {
+                summary.skipped += 1;
+                continue;
+            }
+            let mut instance = instance.clone();
+            migration::rewrite_paths(&mut instance.config, &options.rewrites);
+            let data_dir = self.data_directory_of(&instance)?;
+            imported.push((instance, data_dir));
+        }
+        if options.data {
+            package.unpack_data(
+                &imported
+                    .iter()
+                    .map(|(instance, data_dir)| (instance.id, data_dir.clone()))
+                    .collect(),
+            )?;
+        }
+
+        let mut instance_ids = Vec::new();
+        for (mut instance, data_dir) in imported {
+            instance.compressed_bytes = data_compression::compressed_size(&data_dir);
+
+            self.save_instance_record(&instance)?;
+            instance_ids.push(instance.id);
+            self.instances
+                .write()
+                .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?
+                .insert(instance.id, instance);
+        }
+        summary.instances = instance_ids.len();
+
+        let existing: HashSet<ProfileId> = self
+            .profiles
+            .read()
+            .map_err(|e| anyhow::anyhow!("Profiles lock poisoned: {}", e))?
+            .keys()
+            .copied()
+            .collect();
+        let mut profile_ids = Vec::new();
+        for profile in &package.profiles {
+            if existing.contains(&profile.id) {
+                summary.skipped += 1;
+                continue;
+            }
+            let mut profile = profile.clone();
+            for config in &mut profile.instances {
+                migration::rewrite_paths(config, &options.rewrites);
+            }
+            profile_ids.push(profile.id);
+            self.save_profile(profile)?;
+        }
+        summary.profiles = profile_ids.len();
+
+        let existing: HashSet<ScheduleId> = self
+            .schedules
+            .read()
+            .map_err(|e| anyhow::anyhow!("Schedules lock poisoned: {}", e))?
+            .keys()
+            .copied()
+            .collect();
+        for schedule in &package.schedules {
+            if !existing.contains(&schedule.id) {
+                self.save_schedule(schedule.clone())?;
+                summary.schedules += 1;
+            }
+        }
+
+        {
+            let mut quick_launch = self
+                .quick_launch
+                .write()
+                .map_err(|e| anyhow::anyhow!("Quick launch lock poisoned: {}", e))?;
+            for config in &package.quick_launch {
+                let mut config = config.clone();
+                migration::rewrite_paths(&mut config, &options.rewrites);
+                if !quick_launch
+                    .iter()
+                    .any(|c| c.name == config.name && c.executable_path == config.executable_path)
+                {
+                    quick_launch.push(config);
+                }
+            }
+        }
+        self.save_quick_launch()?;
+        for group in &package.groups {
+            self.add_group(group.clone())?;
+        }
+
+        summary.events =
+            self.database
+                .import_history(&package.database, &instance_ids, &profile_ids)?;
+        info!("Migration import: {}", summary.describe());
+        Ok(summary)
+    }
+
+    /// Restart an instance
+    pub fn restart_instance(&self, id: InstanceId) -> Result<()> {
+        self.stop_then(id, AfterStop::Start)
+    }
+
+    /// Start all instances in a profile
+    pub fn launch_profile(&self, profile_id: ProfileId) -> Result<Vec<InstanceId>> {
+        self.launch_profile_members(profile_id, None)
+    }
+
+    /// Launch only the chosen members of a profile and remember the choice
+    pub fn launch_profile_partial(
+        &self,
+        profile_id: ProfileId,
+        selected: &[bool],
+    ) -> Result<Vec<InstanceId>> {
+        self.launch_profile_members(profile_id, Some(selected))
+    }
+
+    /// Launch a profile's members, or those flagged in `selected`. Members
+    /// waiting for another one are launched on a background thread once it is
+    /// ready, so only the ids of the members launched right away are returned.
+    fn launch_profile_members(
+        &self,
+        profile_id: ProfileId,
+        selected: Option<&[bool]>,
+    ) -> Result<Vec<InstanceId>> {
+        // Extract data from profile with minimal lock hold time
+        let profiles_dir = self
+            .settings
+            .read()
+            .map_err(|e| anyhow::anyhow!("Settings lock poisoned: {}", e))?
+            .get_profiles_directory();
+
+        let (staggered, delay, mut configs, order, dependencies, isolation_root) = {
+            let mut profiles = self
+                .profiles
+                .write()
+                .map_err(|e| anyhow::anyhow!("Profiles lock poisoned: {}", e))?;
+            let profile = profiles.get_mut(&profile_id).context("Profile not found")?;
+
+            let mut members: Vec<usize> = (0..profile.instances.len()).collect();
+            if let Some(selected) = selected {
+                members.retain(|&i| selected.get(i).copied().unwrap_or(false));
+                if members.is_empty() {
+                    anyhow::bail!("No profile members selected");
+                }
+                profile.last_partial_run = Some(members.clone());
+            }
+            let order = profile.launch_order(&members)?;
+            let configs: HashMap<usize, InstanceConfig> = members
+                .iter()
+                .map(|&i| (i, profile.instances[i].clone()))
+                .collect();
+            // Only waits for members launched this time count
+            let dependencies: HashMap<usize, MemberDependency> = profile
+                .dependencies
+                .iter()
+                .filter(|d| configs.contains_key(&d.after) && d.after != d.member)
+                .map(|d| (d.member, d.clone()))
+                .collect();
+            profile.mark_used();
+            (
+                profile.staggered_launch,
+                Duration::from_millis(profile.launch_delay_ms as u64),
+                configs,
+                order,
+                dependencies,
+                profile.shared_isolation_root(&profiles_dir),
+            )
+        }; // Lock released here before any I/O operations
+
+        // Point every member at the shared root so they see each other's data
+        if let Some(ref root) = isolation_root {
+            for config in configs.values_mut() {
+                config.data_directory = Some(root.clone());
+                config.use_environment_isolation = true;
+            }
+        }
+
+        // Keep going past failed members so the run records every outcome
+        let mut run = ProfileRun::new(order.len());
+        let mut launched: HashMap<usize, (InstanceId, Instant)> = HashMap::new();
+        let mut ids = Vec::new();
+        let (now, waiting): (Vec<usize>, Vec<usize>) = order
+            .into_iter()
+            .partition(|member| !dependencies.contains_key(member));
+        for (i, member) in now.into_iter().enumerate() {
+            if staggered && i > 0 {
+                std::thread::sleep(delay);
+            }
+            let config = configs[&member].clone();
+            if let Some(id) = self.launch_profile_member(profile_id, config, &mut run) {
+                launched.insert(member, (id, Instant::now()));
+                ids.push(id);
+            }
+        }
+
+        if waiting.is_empty() {
+            self.finish_profile_run(profile_id, &run)?;
+            if ids.is_empty() {
+                if let Some(failure) = run.failures.first() {
+                    anyhow::bail!("No profile members started ({})", failure);
+                }
+            }
+            return Ok(ids);
+        }
+
+        let state = self.clone();
+        std::thread::spawn(move || {
+            for member in waiting {
+                let config = configs[&member].clone();
+                let dependency = &dependencies[&member];
+                let ready = match launched.get(&dependency.after) {
+                    Some(&(id, launched_at)) => state.wait_until_ready(id, launched_at, dependency),
+                    None => Err(anyhow::anyhow!("it waits for a member that did not start")),
+                };
+                if let Err(e) = ready {
+                    error!("Skipped profile member '{}': {}", config.name, e);
+                    run.failures.push(format!("{}: {}", config.name, e));
+                    continue;
+                }
+                if let Some(id) = state.launch_profile_member(profile_id, config, &mut run) {
+                    launched.insert(member, (id, Instant::now()));
+                }
+            }
+            if let Err(e) = state.finish_profile_run(profile_id, &run) {
+                warn!("Failed to save profile run: {}", e);
+            }
+        });
+
+        Ok(ids)
+    }
+
+    /// Create and start one profile member, recording the outcome in `run`
+    fn launch_profile_member(
+        &self,
+        profile_id: ProfileId,
+        config: InstanceConfig,
+        run: &mut ProfileRun,
+    ) -> Option<InstanceId> {
+        let name = config.name.clone();
+        match self.create_instance(config, true) {
+            Ok(id) => {
+                self.tag_instance_profile(id, profile_id);
+                run.started += 1;
+                Some(id)
+            }
+            Err(e) => {
+                error!("Failed to start profile member '{}': {}", name, e);
+                run.failures.push(format!("{}: {}", name, e));
+                None
+            }
+        }
+    }
+
+    /// Block until the instance a profile member waits for is ready
+    fn wait_until_ready(
+        &self,
+        id: InstanceId,
+        launched_at: Instant,
+        dependency: &MemberDependency,
+    ) -> Result<()> {
+        if let Readiness::Delay { ms } = dependency.ready {
+            let ready_at = launched_at + Duration::from_millis(ms as u64);
+            std::thread::sleep(ready_at.saturating_duration_since(Instant::now()));
+            return Ok(());
+        }
+
+        let deadline = launched_at + Duration::from_secs(dependency.timeout_secs as u64);
+        loop {
+            let pid = self
+                .instances
+                .read()
+                .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?
+                .get(&id)
+                .filter(|i| i.status.is_active())
+                .and_then(|i| i.pid)
+                .context("the member it waits for stopped before it was ready")?;
+            if dependency.ready.is_met(pid) {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                anyhow::bail!(
+                    "the member it waits for was not ready ({}) after {}s",
+                    dependency.ready.label().to_lowercase(),
+                    dependency.timeout_secs
+                );
+            }
+            std::thread::sleep(READY_POLL_INTERVAL);
+        }
+    }
+
+    /// Record a profile launch and remember it as the profile's last run
+    fn finish_profile_run(&self, profile_id: ProfileId, run: &ProfileRun) -> Result<()> {
+        if let Err(e) = self.database.record_profile_run(profile_id, run) {
+            warn!("Failed to record profile run: {}", e);
+        }
+
+        // Update profile in database
+        if let Ok(mut profiles) = self.profiles.write() {
+            if let Some(profile) = profiles.get_mut(&profile_id) {
+                profile.last_run = Some(run.clone());
+                self.database.save_profile(profile)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remember which profile an instance was launched from
+    fn tag_instance_profile(&self, id: InstanceId, profile_id: ProfileId) {
+        if let Ok(mut instances) = self.instances.write() {
+            if let Some(instance) = instances.get_mut(&id) {
+                instance.profile_id = Some(profile_id);
+                if let Err(e) = self.save_instance_record(instance) {
+                    warn!("Failed to save instance {}: {}", id, e);
+                }
+            }
+        }
+    }
+
+    /// Begin restarting the active members of a group or profile one at a time
+    pub fn start_rolling_restart(&self, target: RollingRestartTarget) -> Result<usize> {
+        let label = match &target {
+            RollingRestartTarget::Group(group) => group.clone(),
+            RollingRestartTarget::Profile(profile_id) => self
+                .profiles
+                .read()
+                .map_err(|e| anyhow::anyhow!("Profiles lock poisoned: {}", e))?
+                .get(profile_id)
+                .map(|p| p.name.clone())
+                .context("Profile not found")?,
+        };
+
+        let mut rollouts = self
+            .rolling_restarts
+            .write()
+            .map_err(|e| anyhow::anyhow!("Rolling restart lock poisoned: {}", e))?;
+        if rollouts.iter().any(|r| r.target == target) {
+            anyhow::bail!("A rolling restart of '{}' is already in progress", label);
+        }
+
+        let mut members: Vec<(InstanceId, chrono::DateTime<chrono::Utc>)> = self
+            .instances
+            .read()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?
+            .values()
+            .filter(|i| i.status.is_active() && target.matches(i))
+            .map(|i| (i.id, i.created_at))
+            .collect();
+        if members.is_empty() {
+            anyhow::bail!("No running instances in '{}'", label);
+        }
+        members.sort_by_key(|(_, created_at)| *created_at);
+
+        let count = members.len();
+        info!(
+            "Starting rolling restart of '{}' ({} instances)",
+            label, count
+        );
+        rollouts.push(RollingRestart::new(
+            target,
+            label,
+            members.into_iter().map(|(id, _)| id).collect(),
+        ));
+        Ok(count)
+    }
+
+    /// Cancel a rolling restart, leaving already restarted instances running
+    pub fn cancel_rolling_restart(&self, target: &RollingRestartTarget) {
+        if let Ok(mut rollouts) = self.rolling_restarts.write() {
+            rollouts.retain(|r| &r.target != target);
+        }
+    }
+
+    /// Advance rolling restarts: wait for the current member to become healthy, then restart the next
+    pub fn advance_rolling_restarts(&self) {
+        let Ok(mut rollouts) = self.rolling_restarts.write() else {
+            return;
+        };
+
+        rollouts.retain_mut(|rollout| {
+            if let Some((id, restarted_at)) = rollout.current {
+                let Ok(instances) = self.instances.read() else {
+                    return false;
+                };
+                let instance = instances.get(&id);
+                // The health timeout starts once a stop command has let it exit
+                if instance.is_some_and(|i| i.status == InstanceStatus::Stopping) {
+                    rollout.current = Some((id, Instant::now()));
+                    return true;
+                }
+                let health = RollingRestart::check_health(instance, restarted_at);
+                drop(instances);
+
+                match health {
+                    RestartHealth::Waiting => return true,
+                    RestartHealth::Healthy => rollout.current = None,
+                    RestartHealth::Failed => {
+                        error!(
+                            "Rolling restart of '{}' halted: instance {} did not become healthy",
+                            rollout.label, id
+                        );
+                        return false;
+                    }
+                }
+            }
+
+            while let Some(id) = rollout.pending.pop_front() {
+                let still_active = self
+                    .instances
+                    .read()
+                    .map(|instances| {
+                        instances
+                            .get(&id)
+                            .map(|i| i.status.is_active())
+                            .unwrap_or(false)
+                    })
+                    .unwrap_or(false);
+                if !still_active {
+                    continue;
+                }
+
+                info!(
+                    "Rolling restart of '{}': restarting instance {} ({}/{})",
+                    rollout.label,
+                    id,
+                    rollout.completed(),
+                    rollout.total
+                );
+                if let Err(e) = self.restart_instance(id) {
+                    error!(
+                        "Rolling restart of '{}' halted: failed to restart {}: {}",
+                        rollout.label, id, e
+                    );
+                    return false;
+                }
+                rollout.current = Some((id, Instant::now()));
+                return true;
+            }
+
+            info!("Rolling restart of '{}' complete", rollout.label);
+            false
+        });
+    }
+
+    /// Stop all running instances
+    pub fn stop_all(&self) -> Result<()> {
+        let ids: Vec<InstanceId> = self
+            .instances
+            .read()
+            .map(|i| i.keys().copied().collect())
+            .unwrap_or_default();
+        for id in ids {
+            if let Err(e) = self.stop_instance(id) {
+                error!("Failed to stop instance {}: {}", id, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop the running instances among `ids`, such as those in the current view
+    pub fn stop_instances(&self, ids: &[InstanceId]) -> Result<()> {
+        let active: Vec<InstanceId> = self
+            .instances
+            .read()
+            .map(|instances| {
+                ids.iter()
+                    .copied()
+                    .filter(|id| instances.get(id).is_some_and(|i| i.status.is_active()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        for id in active {
+            if let Err(e) = self.stop_instance(id) {
+                error!("Failed to stop instance {}: {}", id, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop every running instance that is not pinned
+    pub fn stop_all_except_pinned(&self) -> Result<()> {
+        let ids: Vec<InstanceId> = self
+            .instances
+            .read()
+            .map(|i| {
+                i.values()
+                    .filter(|i| i.status.is_active() && !i.config.pinned)
+                    .map(|i| i.id)
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.stop_instances(&ids)
+    }
+
+    /// Groups with at least one running instance (`None` = ungrouped), sorted
+    pub fn active_groups(&self) -> Vec<Option<String>> {
+        let mut groups: Vec<Option<String>> = self
+            .instances
+            .read()
+            .map(|i| {
+                i.values()
+                    .filter(|i| i.status.is_active())
+                    .map(|i| i.config.group.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+        groups.sort();
+        groups.dedup();
+        groups
+    }
+
+    /// Arrange the windows of running instances, of only `profile`'s when given.
+    /// Windows are moved in the background since listing them can be slow.
+    pub fn arrange_windows(&self, layout: WindowLayout, profile: Option<ProfileId>) -> Result<()> {
+        let mut pids: Vec<(String, u32)> = self
+            .instances
+            .read()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?
+            .values()
+            .filter(|i| i.status.is_active())
+            .filter(|i| profile.is_none() || i.profile_id == profile)
+            .filter_map(|i| Some((i.display_name().to_lowercase(), i.pid?)))
+            .collect();
+        if pids.is_empty() {
+            anyhow::bail!("No running instances to arrange");
+        }
+        pids.sort();
+        let area = crate::platform::work_area()?;
+
+        std::thread::spawn(move || {
+            let windows: Vec<u64> = pids
+                .iter()
+                .flat_map(|(_, pid)| match crate::platform::process_windows(*pid) {
+                    Ok(windows) => windows.into_iter().map(|(w, _)| w).collect(),
+                    Err(e) => {
+                        warn!("Failed to list windows of PID {}: {}", pid, e);
+                        Vec::new()
+                    }
+                })
+                .collect();
+            let rects = window_layout::arrange(layout, area, windows.len());
+            for (window, rect) in windows.into_iter().zip(rects) {
+                if let Err(e) = crate::platform::set_window_bounds(window, rect) {
+                    warn!("Failed to move window {}: {}", window, e);
+                }
+            }
+            info!("Arranged windows: {}", layout.label());
+        });
+        Ok(())
+    }
+
+    /// Kill every running instance at once, skipping graceful stop commands
+    pub fn kill_all(&self) -> Result<()> {
+        let ids: Vec<InstanceId> = self
+            .instances
+            .read()
+            .map(|i| {
+                i.values()
+                    .filter(|i| i.status.is_active())
+                    .map(|i| i.id)
+                    .collect()
+            })
+            .unwrap_or_default();
+        for id in ids {
+            if let Err(e) = self.kill_instance(id) {
+                error!("Failed to kill instance {}: {}", id, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Start every stopped instance in a group (`None` = ungrouped instances)
+    pub fn start_group(&self, group: Option<&str>) -> Result<()> {
+        for id in self.group_members(group, |i| !i.status.is_active()) {
+            if let Err(e) = self.start_instance(id) {
+                error!("Failed to start instance {}: {}", id, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop every running instance in a group (`None` = ungrouped instances)
+    pub fn stop_group(&self, group: Option<&str>) -> Result<()> {
+        for id in self.group_members(group, |i| i.status.is_active()) {
+            if let Err(e) = self.stop_instance(id) {
+                error!("Failed to stop instance {}: {}", id, e);
+            }
+        }
+        Ok(())
+    }
+
+    fn group_members(
+        &self,
+        group: Option<&str>,
+        filter: impl Fn(&Instance) -> bool,
+    ) -> Vec<InstanceId> {
+        self.instances
+            .read()
+            .map(|instances| {
+                instances
+                    .values()
+                    .filter(|i| i.config.group.as_deref() == group && filter(i))
+                    .map(|i| i.id)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Combined usage history of a group's running members
+    pub fn group_usage_history(&self, group: &str) -> UsageHistory {
+        let pids: Vec<u32> = self
+            .instances
+            .read()
+            .map(|instances| {
+                instances
+                    .values()
+                    .filter(|i| i.config.group.as_deref() == Some(group) && i.status.is_active())
+                    .filter_map(|i| i.pid)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let histories: Vec<UsageHistory> = pids
+            .into_iter()
+            .filter_map(|pid| self.resource_monitor.usage_history(pid))
+            .collect();
+        UsageHistory::sum(&histories)
+    }
+
+    /// The most recent history events of a group's members, newest first
+    pub fn group_history(&self, group: &str, limit: usize) -> Result<Vec<HistoryEntry>> {
+        let members = self.group_members(Some(group), |_| true);
+        if members.is_empty() {
+            return Ok(Vec::new());
+        }
+        let filter = HistoryFilter {
+            instances: members,
+            ..HistoryFilter::default()
+        };
+        self.database.query_instance_history(&filter, None, limit)
+    }
+
+    /// Pause all running instances
+    pub fn pause_all(&self) -> Result<()> {
+        let ids: Vec<InstanceId> = self
+            .instances
+            .read()
+            .map(|i| i.keys().copied().collect())
+            .unwrap_or_default();
+        for id in ids {
+            if let Err(e) = self.pause_instance(id) {
+                error!("Failed to pause instance {}: {}", id, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Resume all paused instances
+    pub fn resume_all(&self) -> Result<()> {
+        let ids: Vec<InstanceId> = self
+            .instances
+            .read()
+            .map(|i| i.keys().copied().collect())
+            .unwrap_or_default();
+        for id in ids {
+            let should_resume = self
+                .instances
+                .read()
+                .map(|instances| {
+                    instances
+                        .get(&id)
+                        .map(|i| i.status == InstanceStatus::Paused)
+                        .unwrap_or(false)
+                })
+                .unwrap_or(false);
+            if should_resume {
+                if let Err(e) = self.resume_instance(id) {
+                    error!("Failed to resume instance {}: {}", id, e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Sample resource usage of the instances that are due. Cheap when nothing is due,
+    /// so it can run every frame.
+    pub fn update_resources(&self) {
+        // Refresh only the processes we manage, each at its own interval
+        let (default_interval, history_minutes) = self
+            .settings
+            .read()
+            .map(|s| (s.monitor_interval_ms, s.usage_history_minutes))
+            .unwrap_or((1000, super::monitor::DEFAULT_HISTORY_MINUTES));
+        let schedule: Vec<(u32, Duration)> = self
+            .instances
+            .read()
+            .map(|instances| {
+                instances
+                    .values()
+                    .filter_map(|i| {
+                        let interval = match i.config.monitor_interval_ms {
+                            0 => default_interval,
+                            ms => ms,
+                        };
+                        Some((i.pid?, Duration::from_millis(interval as u64)))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.resource_monitor.set_schedule(&schedule);
+        self.resource_monitor.set_history_minutes(history_minutes);
+        let refreshed = self.resource_monitor.refresh();
+        if refreshed.is_empty() {
+            return;
+        }
+
+        // Update usage of the instances that were sampled
+        let mut exits = Vec::new();
+        let mut crashes = Vec::new();
+        if let Ok(mut instances) = self.instances.write() {
+            for instance in instances.values_mut() {
+                if let Some(pid) = instance.pid.filter(|pid| refreshed.contains(pid)) {
+                    // Check if process is still running
+                    let was_active = instance.status.is_active();
+                    if !self.process_manager.check_process(instance) {
+                        if was_active && !instance.status.is_active() {
+                            if instance.status == InstanceStatus::Crashed {
+                                crashes.push((instance.id, CrashReport::new(instance)));
+                            }
+                            exits.push(ExitEvent {
+                                id: instance.id,
+                                name: instance.display_name().to_string(),
+                                crashed: instance.status == InstanceStatus::Crashed,
+                                error: instance.last_error.clone(),
+                                crash_loop: false,
+                                launch_id: instance.launch_id.clone(),
+                            });
+                        }
+                        continue;
+                    }
+
+                    // Update resource usage
+                    if let Some(usage) = self.resource_monitor.get_process_usage(pid) {
+                        instance.update_resource_usage(usage);
+                    }
+                }
+            }
+        }
+
+        // Crashes are recorded with the summary of their report instead of the bare error
+        let crash_summaries: HashMap<InstanceId, String> = crashes
+            .into_iter()
+            .map(|(id, report)| (id, self.save_crash_report(id, report)))
+            .collect();
+        for exit in &exits {
+            let event = if exit.crashed {
+                HistoryEvent::Crashed
+            } else {
+                HistoryEvent::Exited
+            };
+            let details = crash_summaries
+                .get(&exit.id)
+                .map(String::as_str)
+                .or(exit.error.as_deref());
+            self.record_event(exit.id, exit.launch_id.as_deref(), event, details);
+        }
+
+        if !exits.is_empty() {
+            if let Ok(mut history) = self.crash_history.write() {
+                let now = Instant::now();
+                for exit in exits.iter_mut().filter(|e| e.crashed) {
+                    let crashes = history.entry(exit.id).or_default();
+                    crashes.retain(|t| now.duration_since(*t) < CRASH_LOOP_WINDOW);
+                    crashes.push(now);
+                    if crashes.len() >= CRASH_LOOP_COUNT {
+                        exit.crash_loop = true;
+                        crashes.clear();
+                    }
+                }
+            }
+            if let Ok(mut events) = self.exit_events.write() {
+                events.extend(exits);
+            }
+        }
+
+        if let Ok(mut last_update) = self.last_resource_update.write() {
+            *last_update = Instant::now();
+        }
+    }
+
+    /// Count running copies of each managed executable system-wide, including ones we
+    /// did not launch. Scans every process, so it runs far less often than the monitor.
+    pub fn discover_system_copies(&self) {
+        if let Ok(mut last_scan) = self.last_copy_scan.write() {
+            if last_scan.is_some_and(|t| t.elapsed() < COPY_SCAN_INTERVAL) {
+                return;
+            }
+            *last_scan = Some(Instant::now());
+        }
+
+        let (names, managed) = {
+            let Ok(instances) = self.instances.read() else {
+                return;
+            };
+            let mut names: Vec<String> = instances
+                .values()
+                .filter_map(|i| i.config.executable_path.file_name())
+                .map(|n| n.to_string_lossy().to_string())
+                .collect();
+            names.sort();
+            names.dedup();
+            let managed: HashSet<u32> = instances
+                .values()
+                .filter(|i| i.status.is_active() || i.status == InstanceStatus::Stopping)
+                .filter_map(|i| i.pid)
+                .collect();
+            (names, managed)
+        };
+        if names.is_empty() {
+            return;
+        }
+
+        let counts = self
+            .resource_monitor
+            .count_processes_by_name(&names, &managed);
+        if let Ok(mut instances) = self.instances.write() {
+            for instance in instances.values_mut() {
+                let Some(name) = instance.config.executable_path.file_name() else {
+                    continue;
+                };
+                let count = counts
+                    .get(name.to_string_lossy().as_ref())
+                    .copied()
+                    .unwrap_or_default();
+                instance.system_copies = count.total;
+                instance.external_copies = count.external;
+                if instance.status.is_active() && instance.config.resource_limits.network_kbps > 0 {
+                    network_limit::external_copies_found(
+                        &instance.config.executable_path,
+                        count.external,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Detect running instances whose executable was updated on disk
+    pub fn check_executable_updates(&self) {
+        if let Ok(mut instances) = self.instances.write() {
+            for instance in instances.values_mut() {
+                if instance.check_for_update() {
+                    info!(
+                        "Executable for instance {} changed on disk, restart to update",
+                        instance.id
+                    );
+                }
+            }
+        }
+    }
+
+    /// Handle auto-restart for crashed instances
+    pub fn handle_auto_restarts(&self) {
+        let restart_candidates: Vec<InstanceId> = self
+            .instances
+            .read()
+            .map(|instances| {
+                instances
+                    .iter()
+                    .filter(|(_, i)| i.should_auto_restart())
+                    .map(|(id, _)| *id)
+                    .collect()
+            })
             .unwrap_or_default();
-        for id in ids {
-            let should_resume = self
+
+        for id in restart_candidates {
+            let delay = self
                 .instances
                 .read()
                 .map(|instances| {
                     instances
                         .get(&id)
-                        .map(|i| i.status == InstanceStatus::Paused)
-                        .unwrap_or(false)
+                        .map(|i| Duration::from_secs(i.config.restart_delay_secs as u64))
+                        .unwrap_or(Duration::from_secs(5))
                 })
-                .unwrap_or(false);
-            if should_resume {
-                if let Err(e) = self.resume_instance(id) {
-                    error!("Failed to resume instance {}: {}", id, e);
+                .unwrap_or(Duration::from_secs(5));
+
+            std::thread::sleep(delay);
+
+            if let Ok(mut instances) = self.instances.write() {
+                if let Some(instance) = instances.get_mut(&id) {
+                    instance.increment_restart_count();
+                    info!(
+                        "Auto-restarting instance {} (attempt {})",
+                        id, instance.restart_count
+                    );
+                    match self.process_manager.spawn(instance) {
+                        Ok(()) => {
+                            let attempt = format!("Attempt {}", instance.restart_count);
+                            let launch_id = instance.launch_id.as_deref();
+                            self.record_event(
+                                id,
+                                launch_id,
+                                HistoryEvent::Restarted,
+                                Some(&attempt),
+                            );
+                        }
+                        Err(e) => error!("Failed to auto-restart instance {}: {}", id, e),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Close crash dialogs holding active instances open so they exit and can
+    /// auto-restart. A process still running `EXIT_GRACE` after its dialog
+    /// closed is killed.
+    pub fn dismiss_crash_dialogs(&self) {
+        let settings = match self.settings.read() {
+            Ok(s) if s.crash_dialogs.enabled => s.crash_dialogs.clone(),
+            _ => return,
+        };
+        let due = self
+            .last_crash_dialog_check
+            .read()
+            .map(|last| last.is_none_or(|t| t.elapsed() >= CRASH_DIALOG_INTERVAL))
+            .unwrap_or(false);
+        if !due || self.crash_dialog_check_running.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        if let Ok(mut last) = self.last_crash_dialog_check.write() {
+            *last = Some(Instant::now());
+        }
+
+        let active: Vec<(InstanceId, u32)> = self
+            .instances
+            .read()
+            .map(|instances| {
+                instances
+                    .values()
+                    .filter(|i| i.status.is_active())
+                    .filter_map(|i| Some((i.id, i.pid?)))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let state = self.clone();
+        std::thread::spawn(move || {
+            for (id, pid) in active {
+                let closed = match crash_dialogs::dismiss(pid, &settings) {
+                    Ok(closed) if !closed.is_empty() => closed,
+                    Ok(_) => continue,
+                    Err(e) => {
+                        warn!("Failed to check PID {} for crash dialogs: {}", pid, e);
+                        continue;
+                    }
+                };
+                info!(
+                    "Closed crash dialog '{}' of instance {}",
+                    closed.join("', '"),
+                    id
+                );
+
+                let state = state.clone();
+                std::thread::spawn(move || {
+                    std::thread::sleep(crash_dialogs::EXIT_GRACE);
+                    let same_process = state
+                        .instances
+                        .read()
+                        .map(|i| i.get(&id).is_some_and(|i| i.pid == Some(pid)))
+                        .unwrap_or(false);
+                    if same_process && crate::platform::is_process_running(pid) {
+                        warn!("Instance {} kept running after its crash dialog closed", id);
+                        if let Err(e) = crate::platform::kill_process(pid) {
+                            error!("Failed to kill crashed instance {}: {}", id, e);
+                        }
+                    }
+                });
+            }
+            state
+                .crash_dialog_check_running
+                .store(false, Ordering::Release);
+        });
+    }
+
+    /// Append instance names to the window titles of instances that ask for it,
+    /// again wherever the app has replaced its title since the last check
+    pub fn rename_windows(&self) {
+        let due = self
+            .last_title_check
+            .read()
+            .map(|last| last.is_none_or(|t| t.elapsed() >= window_titles::CHECK_INTERVAL))
+            .unwrap_or(false);
+        if !due {
+            return;
+        }
+        let renamed: Vec<(String, u32)> = self
+            .instances
+            .read()
+            .map(|instances| {
+                instances
+                    .values()
+                    .filter(|i| i.status.is_active() && i.config.rename_windows)
+                    .filter_map(|i| Some((i.display_name().to_string(), i.pid?)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if renamed.is_empty() || self.title_check_running.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        if let Ok(mut last) = self.last_title_check.write() {
+            *last = Some(Instant::now());
+        }
+
+        let running = Arc::clone(&self.title_check_running);
+        std::thread::spawn(move || {
+            for (name, pid) in renamed {
+                let windows = match crate::platform::process_windows(pid) {
+                    Ok(windows) => windows,
+                    Err(e) => {
+                        warn!("Failed to list windows of PID {}: {}", pid, e);
+                        continue;
+                    }
+                };
+                for (window, title) in windows {
+                    let Some(title) = window_titles::renamed(&title, &name) else {
+                        continue;
+                    };
+                    if let Err(e) = crate::platform::set_window_title(window, &title) {
+                        warn!("Failed to rename window of PID {}: {}", pid, e);
+                    }
+                }
+            }
+            running.store(false, Ordering::Release);
+        });
+    }
+
+    /// Whether input is being mirrored to the broadcast group
+    pub fn is_broadcasting(&self) -> bool {
+        self.broadcasting.load(Ordering::Acquire)
+    }
+
+    /// Switch input broadcasting on or off. Returns whether it is now on.
+    pub fn toggle_broadcast(&self) -> Result<bool> {
+        let on = !self.is_broadcasting();
+        if on {
+            self.settings
+                .read()
+                .map_err(|e| anyhow::anyhow!("Settings lock poisoned: {}", e))?
+                .broadcast
+                .group
+                .as_ref()
+                .context("Choose a group to broadcast input to in Settings")?;
+        }
+        self.broadcasting.store(on, Ordering::Release);
+        self.update_broadcast()?;
+        info!("Input broadcasting {}", if on { "on" } else { "off" });
+        Ok(on)
+    }
+
+    /// Keep the mirrored processes in step with the running members of the
+    /// broadcast group. Broadcasting is switched off if it fails.
+    pub fn update_broadcast(&self) -> Result<()> {
+        let targets = if self.is_broadcasting() {
+            let broadcast = self
+                .settings
+                .read()
+                .map_err(|e| anyhow::anyhow!("Settings lock poisoned: {}", e))?
+                .broadcast
+                .clone();
+            broadcast.group.map(|group| {
+                let mut pids: Vec<u32> = self
+                    .instances
+                    .read()
+                    .map(|instances| {
+                        instances
+                            .values()
+                            .filter(|i| i.status.is_active())
+                            .filter(|i| i.config.group.as_deref() == Some(group.as_str()))
+                            .filter_map(|i| i.pid)
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                pids.sort_unstable();
+                BroadcastTargets {
+                    pids,
+                    keys: broadcast.keys,
+                    mouse: broadcast.mouse,
+                }
+            })
+        } else {
+            None
+        };
+
+        let mut current = self
+            .broadcast_targets
+            .write()
+            .map_err(|e| anyhow::anyhow!("Broadcast lock poisoned: {}", e))?;
+        if *current == targets {
+            return Ok(());
+        }
+        match crate::platform::broadcast_input(targets.as_ref()) {
+            Ok(()) => {
+                *current = targets;
+                Ok(())
+            }
+            Err(e) => {
+                self.broadcasting.store(false, Ordering::Release);
+                if current.take().is_some() {
+                    let _ = crate::platform::broadcast_input(None);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Warn about and stop instances that reached their run limit
+    pub fn enforce_run_limits(&self) -> Vec<RunLimitEvent> {
+        let mut events = Vec::new();
+        let mut expired = Vec::new();
+
+        if let Ok(mut instances) = self.instances.write() {
+            for instance in instances.values_mut().filter(|i| i.status.is_active()) {
+                let Some(remaining) = instance.time_remaining() else {
+                    continue;
+                };
+                if remaining <= chrono::TimeDelta::zero() {
+                    expired.push((instance.id, instance.display_name().to_string()));
+                } else if remaining <= RUN_LIMIT_WARNING && !instance.expiry_warned {
+                    instance.expiry_warned = true;
+                    events.push(RunLimitEvent::Expiring {
+                        id: instance.id,
+                        name: instance.display_name().to_string(),
+                        remaining_secs: remaining.num_seconds(),
+                    });
+                }
+            }
+        }
+
+        for (id, name) in expired {
+            info!("Run limit reached for instance {}, stopping", id);
+            if let Err(e) = self.stop_instance(id) {
+                error!("Failed to stop instance {} at its run limit: {}", id, e);
+                continue;
+            }
+            events.push(RunLimitEvent::Expired { id, name });
+        }
+
+        events
+    }
+
+    /// Check pending instances' network conditions on the next tick instead of waiting
+    fn request_network_check(&self) {
+        if let Ok(mut last) = self.last_network_check.write() {
+            *last = None;
+        }
+    }
+
+    /// Launch pending instances whose network condition now holds.
+    /// Conditions are probed on a background thread since reachability checks block.
+    pub fn check_pending_launches(&self) {
+        let due = self
+            .last_network_check
+            .read()
+            .map(|last| last.is_none_or(|t| t.elapsed() >= NETWORK_CHECK_INTERVAL))
+            .unwrap_or(false);
+        if !due || self.network_check_running.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        if let Ok(mut last) = self.last_network_check.write() {
+            *last = Some(Instant::now());
+        }
+
+        let pending: Vec<(InstanceId, NetworkCondition)> = self
+            .instances
+            .read()
+            .map(|instances| {
+                instances
+                    .values()
+                    .filter(|i| i.status == InstanceStatus::Pending)
+                    .filter_map(|i| Some((i.id, i.config.network_condition.clone()?)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if pending.is_empty() {
+            self.network_check_running.store(false, Ordering::Release);
+            return;
+        }
+
+        let state = self.clone();
+        std::thread::spawn(move || {
+            // Several instances often wait on the same condition; probe each once
+            let mut results: Vec<(NetworkCondition, bool)> = Vec::new();
+            for (id, condition) in pending {
+                let met = match results.iter().find(|(c, _)| *c == condition) {
+                    Some((_, met)) => *met,
+                    None => {
+                        let met = condition.is_met();
+                        results.push((condition.clone(), met));
+                        met
+                    }
+                };
+                if !met {
+                    continue;
                 }
+
+                info!("Network condition met for instance {}, launching", id);
+                if let Err(e) = state.launch_instance(id, false) {
+                    error!("Failed to launch pending instance {}: {}", id, e);
+                    if let Ok(mut instances) = state.instances.write() {
+                        if let Some(instance) = instances.get_mut(&id) {
+                            instance.mark_stopped();
+                            instance.last_error = Some(e.to_string());
+                        }
+                    }
+                }
+            }
+            state.network_check_running.store(false, Ordering::Release);
+        });
+    }
+
+    /// Capture the pinned window region of every running instance that has one.
+    /// Window captures can take a while, so they run on a background thread.
+    pub fn capture_pinned_regions(&self) {
+        let interval = self
+            .settings
+            .read()
+            .map(|s| Duration::from_secs(s.region_capture_secs.max(1) as u64))
+            .unwrap_or(Duration::from_secs(5));
+        let due = self
+            .last_region_capture
+            .read()
+            .map(|last| last.is_none_or(|t| t.elapsed() >= interval))
+            .unwrap_or(false);
+        if !due || self.region_capture_running.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        if let Ok(mut last) = self.last_region_capture.write() {
+            *last = Some(Instant::now());
+        }
+
+        let pinned: Vec<_> = self
+            .instances
+            .read()
+            .map(|instances| {
+                instances
+                    .values()
+                    .filter(|i| i.status.is_active())
+                    .filter_map(|i| Some((i.id, i.pid?, i.config.pinned_region?)))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let region_captures = Arc::clone(&self.region_captures);
+        let running = Arc::clone(&self.region_capture_running);
+        std::thread::spawn(move || {
+            let captures: HashMap<_, _> = pinned
+                .into_iter()
+                .map(|(id, pid, region)| (id, window_capture::capture(pid, &region)))
+                .collect();
+            // Replacing the map also drops instances that stopped or were unpinned
+            if let Ok(mut region_captures) = region_captures.write() {
+                *region_captures = captures;
+            }
+            running.store(false, Ordering::Release);
+        });
+    }
+
+    /// Save a screenshot of an instance's main window into its data directory.
+    /// The capture runs on a background thread and lands in `screenshots`.
+    pub fn capture_screenshot(&self, id: InstanceId) -> Result<()> {
+        let (pid, data_dir) = {
+            let instances = self
+                .instances
+                .read()
+                .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+            let instance = instances.get(&id).context("Instance not found")?;
+            let pid = instance
+                .pid
+                .filter(|_| instance.status.is_active())
+                .context("Instance is not running")?;
+            (pid, self.data_directory_of(instance)?)
+        };
+
+        let screenshots = Arc::clone(&self.screenshots);
+        std::thread::spawn(move || {
+            let screenshot = window_capture::screenshot(pid, &data_dir);
+            if let Some(ref path) = screenshot.path {
+                info!("Saved screenshot of {} to {}", id, path.display());
+            } else if let Err(ref e) = screenshot.thumbnail.image {
+                warn!("Failed to take screenshot of {}: {}", id, e);
+            }
+            if let Ok(mut screenshots) = screenshots.write() {
+                screenshots.insert(id, screenshot);
+            }
+        });
+        Ok(())
+    }
+
+    /// Capture a thumbnail of every running instance's window on a background
+    /// thread, unless a capture is still in progress
+    pub fn capture_thumbnails(&self) {
+        if self.thumbnail_capture_running.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let running_pids: Vec<_> = self
+            .instances
+            .read()
+            .map(|instances| {
+                instances
+                    .values()
+                    .filter(|i| i.status.is_active())
+                    .filter_map(|i| Some((i.id, i.pid?)))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let thumbnails = Arc::clone(&self.window_thumbnails);
+        let running = Arc::clone(&self.thumbnail_capture_running);
+        std::thread::spawn(move || {
+            let captures: HashMap<_, _> = running_pids
+                .into_iter()
+                .map(|(id, pid)| (id, window_capture::thumbnail(pid)))
+                .collect();
+            if let Ok(mut thumbnails) = thumbnails.write() {
+                *thumbnails = captures;
+            }
+            running.store(false, Ordering::Release);
+        });
+    }
+
+    /// Ask the UI to open the quick switcher
+    pub fn request_switcher(&self) {
+        self.switcher_requested.store(true, Ordering::Release);
+    }
+
+    /// Whether the quick switcher was requested since the last call
+    pub fn take_switcher_request(&self) -> bool {
+        self.switcher_requested.swap(false, Ordering::AcqRel)
+    }
+
+    /// Queue a command line for the UI, which also raises the main window
+    pub fn request_activation(&self, activation: Activation) {
+        if let Ok(mut activations) = self.activations.write() {
+            activations.push(activation);
+        }
+    }
+
+    /// Command lines queued since the last call
+    pub fn take_activations(&self) -> Vec<Activation> {
+        self.activations
+            .write()
+            .map(|mut a| std::mem::take(&mut *a))
+            .unwrap_or_default()
+    }
+
+    /// Read back the environment of instances launched with isolation a few
+    /// seconds after they start, flagging those that dropped the overrides.
+    /// Reading process environments is slow, so checks run on a background thread.
+    pub fn verify_isolation(&self) {
+        let due: Vec<(InstanceId, u32, Vec<EnvVar>)> = match self.instances.read() {
+            Ok(instances) => instances
+                .values()
+                .filter(|i| i.status == InstanceStatus::Running && i.isolation_check.is_none())
+                .filter(|i| {
+                    i.started_at.is_some_and(|t| {
+                        (chrono::Utc::now() - t).num_seconds() >= isolation_check::DELAY_SECS
+                    })
+                })
+                .filter(|i| isolation_check::applies(&i.spawn_environment))
+                .filter_map(|i| Some((i.id, i.pid?, i.spawn_environment.clone())))
+                .collect(),
+            Err(_) => return,
+        };
+        if due.is_empty() || self.isolation_check_running.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let instances = Arc::clone(&self.instances);
+        let running = Arc::clone(&self.isolation_check_running);
+        std::thread::spawn(move || {
+            for (id, pid, environment) in due {
+                let check = isolation_check::verify(pid, &environment);
+                let Ok(mut instances) = instances.write() else {
+                    break;
+                };
+                let Some(instance) = instances.get_mut(&id).filter(|i| i.pid == Some(pid)) else {
+                    continue;
+                };
+                if let IsolationCheck::Ignored { .. } = check {
+                    let _launch = instance.launch_span().entered();
+                    warn!(
+                        "'{}' ignored its isolated environment: {}",
+                        instance.config.name,
+                        check.details().replace('\n', "; ")
+                    );
+                }
+                instance.isolation_check = Some(check);
+            }
+            running.store(false, Ordering::Release);
+        });
+    }
+
+    /// Read the on-screen status of every running instance with status detection.
+    /// Capture and OCR are slow, so checks run on a background thread.
+    pub fn run_health_checks(&self) {
+        let interval = match self.settings.read() {
+            Ok(s) if s.enable_health_checks => {
+                Duration::from_secs(s.health_check_interval_secs.max(5) as u64)
+            }
+            _ => return,
+        };
+        let due = self
+            .last_health_check
+            .read()
+            .map(|last| last.is_none_or(|t| t.elapsed() >= interval))
+            .unwrap_or(false);
+        if !due || self.health_check_running.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        if let Ok(mut last) = self.last_health_check.write() {
+            *last = Some(Instant::now());
+        }
+
+        let checks: Vec<_> = self
+            .instances
+            .read()
+            .map(|instances| {
+                instances
+                    .values()
+                    .filter(|i| i.status == InstanceStatus::Running)
+                    .filter_map(|i| {
+                        let detection = i.config.status_detection.clone();
+                        let probe = i.config.health_probe.clone().filter(|p| p.is_set());
+                        if detection.is_none() && probe.is_none() {
+                            return None;
+                        }
+                        // The probe runs where the instance runs, with its environment
+                        let working_dir = i.config.working_directory.clone().or_else(|| {
+                            i.config
+                                .executable_path
+                                .parent()
+                                .filter(|p| p.is_dir())
+                                .map(|p| p.to_path_buf())
+                        });
+                        let probe = probe.map(|p| (p, working_dir, i.spawn_environment.clone()));
+                        Some((i.id, i.pid?, detection, probe))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let state = self.clone();
+        std::thread::spawn(move || {
+            for (id, pid, detection, probe) in checks {
+                let detected = detection.map(|d| d.check(pid));
+                let probed = probe.map(|(probe, dir, env)| probe.check(pid, dir.as_deref(), &env));
+                let check = match (detected, probed) {
+                    (Some(detected), Some(probed)) => detected.combine(probed),
+                    (Some(check), None) | (None, Some(check)) => check,
+                    (None, None) => continue,
+                };
+                let Ok(mut instances) = state.instances.write() else {
+                    break;
+                };
+                // Skip instances that stopped or relaunched while we were reading them
+                let Some(instance) = instances.get_mut(&id).filter(|i| i.pid == Some(pid)) else {
+                    continue;
+                };
+
+                let previous = instance.health.as_ref().map(|h| &h.health);
+                let was_unhealthy = matches!(previous, Some(Health::Unhealthy(_)));
+                let event = match &check.health {
+                    Health::Unhealthy(reason) if !was_unhealthy => Some(HealthEvent::Unhealthy {
+                        id,
+                        name: instance.display_name().to_string(),
+                        reason: reason.clone(),
+                    }),
+                    Health::Healthy if was_unhealthy => Some(HealthEvent::Recovered {
+                        id,
+                        name: instance.display_name().to_string(),
+                    }),
+                    Health::Unknown(reason) if !matches!(previous, Some(Health::Unknown(_))) => {
+                        warn!("Health check of instance {} failed: {}", id, reason);
+                        None
+                    }
+                    _ => None,
+                };
+                instance.health = Some(check);
+                drop(instances);
+
+                if let Some(event) = event {
+                    if let Ok(mut events) = state.health_events.write() {
+                        events.push(event);
+                    }
+                }
+            }
+            state.health_check_running.store(false, Ordering::Release);
+        });
+    }
+
+    /// Health changes found since the last call
+    pub fn take_health_events(&self) -> Vec<HealthEvent> {
+        self.health_events
+            .write()
+            .map(|mut events| std::mem::take(&mut *events))
+            .unwrap_or_default()
+    }
+
+    /// Host memory use in percent when it just crossed the alert threshold
+    pub fn check_host_memory(&self) -> Option<f32> {
+        let percent = self
+            .resource_monitor
+            .get_system_resources()
+            .memory_percent();
+        if percent >= HOST_MEMORY_ALERT_PERCENT {
+            let already = self.host_memory_alerted.swap(true, Ordering::AcqRel);
+            (!already).then_some(percent)
+        } else {
+            if percent < HOST_MEMORY_REARM_PERCENT {
+                self.host_memory_alerted.store(false, Ordering::Release);
+            }
+            None
+        }
+    }
+
+    /// Sample our own CPU and memory use. Returns a warning when the footprint
+    /// has stayed over the budget in settings, once per overrun.
+    pub fn check_self_budget(&self) -> Option<String> {
+        let usage = self.resource_monitor.own_usage()?;
+        let budget = self.settings.read().ok()?.self_budget;
+        self.profiler
+            .write()
+            .ok()?
+            .record_usage(usage, &budget)
+            .map(|exceeded| format!("MultiInstance is using {}", exceeded))
+    }
+
+    /// Drop child and job handles kept for processes that are gone and record
+    /// what is still held for the profiler overlay. Runs once a minute.
+    pub fn reconcile_handles(&self) {
+        let due = self
+            .last_handle_reconcile
+            .read()
+            .map(|last| last.is_none_or(|t| t.elapsed() >= HANDLE_RECONCILE_INTERVAL))
+            .unwrap_or(false);
+        if !due {
+            return;
+        }
+        if let Ok(mut last) = self.last_handle_reconcile.write() {
+            *last = Some(Instant::now());
+        }
+
+        let active: HashSet<InstanceId> = match self.instances.read() {
+            Ok(instances) => instances
+                .values()
+                .filter(|i| i.status.is_active())
+                .map(|i| i.id)
+                .collect(),
+            Err(_) => return,
+        };
+        let pruned =
+            self.process_manager.prune_children(&active) + crate::platform::prune_job_handles();
+        if pruned > 0 {
+            info!("Released {} handles of processes that are gone", pruned);
+        }
+
+        if let Ok(mut profiler) = self.profiler.write() {
+            profiler.handles.children = self.process_manager.running_count();
+            profiler.handles.jobs = crate::platform::job_handle_count();
+            profiler.handles.pruned += pruned;
+        }
+    }
+
+    /// Processes that ended on their own since the last call
+    pub fn take_exit_events(&self) -> Vec<ExitEvent> {
+        self.exit_events
+            .write()
+            .map(|mut events| std::mem::take(&mut *events))
+            .unwrap_or_default()
+    }
+
+    /// Route an alert by the notification rules and deliver it to every channel
+    /// but the toast. Returns whether the UI should show it as a toast.
+    pub fn raise_alert(&self, mut alert: Alert) -> bool {
+        if let Some(id) = alert.instance {
+            if let Some(instance) = self.instances.read().ok().and_then(|i| i.get(&id).cloned()) {
+                alert.instance_name = Some(instance.display_name().to_string());
+                alert.group = instance.config.group;
+            }
+        }
+        let Ok(settings) = self.settings.read() else {
+            return true;
+        };
+        let channels = alerts::route(&settings, &alert);
+        alerts::deliver(&alert, &channels, &settings);
+        channels.contains(&AlertChannel::Toast)
+    }
+
+    /// Replace an instance's macros and save them
+    pub fn set_macros(&self, id: InstanceId, macros: Vec<Macro>) -> Result<()> {
+        let mut instances = self
+            .instances
+            .write()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+        let instance = instances.get_mut(&id).context("Instance not found")?;
+        instance.macro_runs.retain(|index, _| *index < macros.len());
+        instance.config.macros = macros;
+        self.save_instance_record(instance)?;
+        Ok(())
+    }
+
+    /// Restore an instance's window that was started minimized or hidden
+    pub fn show_instance_window(&self, id: InstanceId) -> Result<()> {
+        let pid = self
+            .instances
+            .read()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?
+            .get(&id)
+            .context("Instance not found")?
+            .pid
+            .context("Instance is not running")?;
+        crate::platform::show_window(pid)
+    }
+
+    /// Bring a running instance's window to the front
+    pub fn focus_instance(&self, id: InstanceId) -> Result<()> {
+        let pid = self
+            .instances
+            .read()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?
+            .get(&id)
+            .context("Instance not found")?
+            .pid
+            .context("Instance is not running")?;
+        crate::platform::focus_window(pid)
+    }
+
+    /// Whether a macro is being played into an instance
+    pub fn is_macro_running(&self, id: InstanceId) -> bool {
+        self.macros_running
+            .read()
+            .map(|running| running.contains(&id))
+            .unwrap_or(false)
+    }
+
+    /// Play one of an instance's macros on a background thread.
+    /// Only one macro plays into an instance at a time.
+    pub fn run_macro(&self, id: InstanceId, index: usize) -> Result<()> {
+        let (pid, recorded) = {
+            let mut instances = self
+                .instances
+                .write()
+                .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+            let instance = instances.get_mut(&id).context("Instance not found")?;
+            if instance.status != InstanceStatus::Running {
+                anyhow::bail!("Instance is not running");
             }
+            let pid = instance.pid.context("Instance is not running")?;
+            let recorded = instance
+                .config
+                .macros
+                .get(index)
+                .cloned()
+                .context("Macro not found")?;
+            instance.macro_runs.insert(index, chrono::Utc::now());
+            (pid, recorded)
+        };
+
+        if !self
+            .macros_running
+            .write()
+            .map_err(|e| anyhow::anyhow!("Macros lock poisoned: {}", e))?
+            .insert(id)
+        {
+            anyhow::bail!("Another macro is already running in this instance");
         }
+
+        let state = self.clone();
+        std::thread::spawn(move || {
+            let outcome = match recorded.play(pid) {
+                Ok(()) => format!("'{}' finished", recorded.name),
+                Err(e) => {
+                    error!("Macro '{}' failed in instance {}: {}", recorded.name, id, e);
+                    format!("'{}' failed: {}", recorded.name, e)
+                }
+            };
+            if let Ok(mut instances) = state.instances.write() {
+                if let Some(instance) = instances.get_mut(&id) {
+                    instance.macro_outcome = Some(outcome);
+                }
+            }
+            if let Ok(mut running) = state.macros_running.write() {
+                running.remove(&id);
+            }
+        });
         Ok(())
     }
 
-    /// Update resource usage for all instances
-    pub fn update_resources(&self) {
-        // Refresh system resources
-        self.resource_monitor.refresh();
-
-        // Update per-instance usage
-        if let Ok(mut instances) = self.instances.write() {
-            for instance in instances.values_mut() {
-                if let Some(pid) = instance.pid {
-                    // Check if process is still running
-                    if !self.process_manager.check_process(instance) {
-                        continue;
-                    }
+    /// Start macros whose launch-complete or schedule trigger is due
+    pub fn run_due_macros(&self) {
+        let due: Vec<(InstanceId, usize)> = self
+            .instances
+            .read()
+            .map(|instances| {
+                instances
+                    .values()
+                    .filter(|i| i.status == InstanceStatus::Running)
+                    .filter_map(|i| Some((i, i.started_at?)))
+                    .flat_map(|(i, started_at)| {
+                        i.config
+                            .macros
+                            .iter()
+                            .enumerate()
+                            .filter(move |(index, m)| {
+                                !m.steps.is_empty()
+                                    && m.is_due(started_at, i.macro_runs.get(index).copied())
+                            })
+                            .map(move |(index, _)| (i.id, index))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
 
-                    // Update resource usage
-                    if let Some(usage) = self.resource_monitor.get_process_usage(pid) {
-                        instance.update_resource_usage(usage);
-                    }
-                }
+        for (id, index) in due {
+            if self.is_macro_running(id) {
+                continue;
             }
+            if let Err(e) = self.run_macro(id, index) {
+                warn!("Failed to start macro {} of instance {}: {}", index, id, e);
+            }
+        }
+    }
+
+    /// Refuse to launch an executable whose daily budget is used up
+    fn check_usage_budget(&self, config: &InstanceConfig) -> Result<()> {
+        let key = usage::executable_key(&config.executable_path);
+        let settings = self
+            .settings
+            .read()
+            .map_err(|e| anyhow::anyhow!("Settings lock poisoned: {}", e))?;
+        let usage = self
+            .usage
+            .read()
+            .map_err(|e| anyhow::anyhow!("Usage lock poisoned: {}", e))?;
+        if usage.is_exhausted(&key, &settings.usage_limits) {
+            anyhow::bail!(
+                "Cannot launch '{}': daily usage limit for {} reached",
+                config.name,
+                key
+            );
         }
+        Ok(())
+    }
 
-        if let Ok(mut last_update) = self.last_resource_update.write() {
-            *last_update = Instant::now();
+    /// Refuse, or warn about, a launch whose memory limit does not fit in free
+    /// RAM or its group's budget, as the settings say
+    fn check_memory_reservation(
+        &self,
+        config: &InstanceConfig,
+        id: Option<InstanceId>,
+        instances: &HashMap<InstanceId, Instance>,
+    ) -> Result<()> {
+        let (policy, budgets) = {
+            let settings = self
+                .settings
+                .read()
+                .map_err(|e| anyhow::anyhow!("Settings lock poisoned: {}", e))?;
+            (
+                settings.memory_reservation,
+                settings.group_memory_budgets.clone(),
+            )
+        };
+        if policy == ReservationPolicy::Off {
+            return Ok(());
+        }
+        let system = self.resource_monitor.get_system_resources();
+        let Some(reason) = reservation::shortfall(config, id, instances, &system, &budgets) else {
+            return Ok(());
+        };
+        let name = if config.name.is_empty() {
+            config.executable_path.display().to_string()
+        } else {
+            config.name.clone()
+        };
+        if policy == ReservationPolicy::Deny {
+            anyhow::bail!("Cannot launch '{}': {}", name, reason);
         }
+        warn!("Launching '{}' although {}", name, reason);
+        if let Ok(mut warnings) = self.launch_warnings.write() {
+            warnings.push(format!("Launched '{}' although {}", name, reason));
+        }
+        Ok(())
     }
 
-    /// Handle auto-restart for crashed instances
-    pub fn handle_auto_restarts(&self) {
-        let restart_candidates: Vec<InstanceId> = self
+    /// Warnings about launches that went ahead, since the last call
+    pub fn take_launch_warnings(&self) -> Vec<String> {
+        self.launch_warnings
+            .write()
+            .map(|mut w| std::mem::take(&mut *w))
+            .unwrap_or_default()
+    }
+
+    /// Count runtime towards each executable's daily budget, warn near the limit and
+    /// stop every instance of an executable whose budget ran out
+    pub fn enforce_usage_limits(&self) -> Vec<UsageEvent> {
+        let limits = self
+            .settings
+            .read()
+            .map(|s| s.usage_limits.clone())
+            .unwrap_or_default();
+
+        let running: std::collections::HashSet<String> = self
             .instances
             .read()
             .map(|instances| {
                 instances
-                    .iter()
-                    .filter(|(_, i)| i.should_auto_restart())
-                    .map(|(id, _)| *id)
+                    .values()
+                    .filter(|i| i.status.is_active())
+                    .map(|i| usage::executable_key(&i.config.executable_path))
                     .collect()
             })
             .unwrap_or_default();
 
-        for id in restart_candidates {
-            let delay = self
+        let (mut events, over) = {
+            let Ok(mut tracker) = self.usage.write() else {
+                return Vec::new();
+            };
+            if let Some((day, totals)) = tracker.roll_over(UsageTracker::today()) {
+                if let Err(e) = self.database.add_usage(day, &totals) {
+                    error!("Failed to save usage for {}: {}", day, e);
+                }
+            }
+            tracker.tick(&running);
+            tracker.check(&limits)
+        };
+
+        for key in over.into_iter().filter(|k| running.contains(k)) {
+            let ids: Vec<InstanceId> = self
                 .instances
                 .read()
                 .map(|instances| {
                     instances
-                        .get(&id)
-                        .map(|i| Duration::from_secs(i.config.restart_delay_secs as u64))
-                        .unwrap_or(Duration::from_secs(5))
+                        .values()
+                        .filter(|i| {
+                            i.status.is_active()
+                                && usage::executable_key(&i.config.executable_path) == key
+                        })
+                        .map(|i| i.id)
+                        .collect()
                 })
-                .unwrap_or(Duration::from_secs(5));
+                .unwrap_or_default();
 
-            std::thread::sleep(delay);
-
-            if let Ok(mut instances) = self.instances.write() {
-                if let Some(instance) = instances.get_mut(&id) {
-                    instance.increment_restart_count();
-                    info!(
-                        "Auto-restarting instance {} (attempt {})",
-                        id, instance.restart_count
-                    );
-                    if let Err(e) = self.process_manager.spawn(instance) {
-                        error!("Failed to auto-restart instance {}: {}", id, e);
+            info!(
+                "Daily usage limit reached for {}, stopping {} instances",
+                key,
+                ids.len()
+            );
+            let stopped = ids
+                .into_iter()
+                .filter(|id| match self.stop_instance(*id) {
+                    Ok(()) => true,
+                    Err(e) => {
+                        error!("Failed to stop instance {} at its usage limit: {}", id, e);
+                        false
                     }
-                }
-            }
+                })
+                .count();
+            events.push(UsageEvent::Exhausted {
+                executable: key,
+                stopped,
+            });
+        }
+
+        let due = self
+            .last_usage_flush
+            .read()
+            .map(|last| last.elapsed() >= USAGE_FLUSH_INTERVAL)
+            .unwrap_or(false);
+        if due {
+            self.flush_usage();
+        }
+
+        events
+    }
+
+    /// Write accumulated usage to the database
+    pub fn flush_usage(&self) {
+        let (day, totals) = match self.usage.write() {
+            Ok(mut tracker) => (tracker.day(), tracker.take_unsaved()),
+            Err(_) => return,
+        };
+        if let Ok(mut last) = self.last_usage_flush.write() {
+            *last = Instant::now();
+        }
+        if totals.is_empty() {
+            return;
         }
+        if let Err(e) = self.database.add_usage(day, &totals) {
+            error!("Failed to save usage: {}", e);
+        }
+    }
+
+    /// Seconds each executable has run today
+    pub fn usage_today(&self) -> HashMap<String, u64> {
+        self.usage
+            .read()
+            .map(|tracker| tracker.used().clone())
+            .unwrap_or_default()
     }
 
     /// Save current session state
@@ -430,12 +4114,20 @@ impl AppState {
         Ok(())
     }
 
-    /// Restore previous session
+    /// Restore previous session, skipping instances whose executable is gone
     pub fn restore_session(&self) -> Result<()> {
         let configs = self.database.load_session()?;
         info!("Restoring session with {} instances", configs.len());
 
         for config in configs {
+            if !config.executable_exists() {
+                warn!(
+                    "Skipping '{}': {} no longer exists",
+                    config.name,
+                    config.executable_path.display()
+                );
+                continue;
+            }
             if let Err(e) = self.create_instance(config, true) {
                 error!("Failed to restore instance: {}", e);
             }
@@ -444,9 +4136,53 @@ impl AppState {
         Ok(())
     }
 
+    /// Load the previous session for the restore prompt instead of relaunching it
+    pub fn prepare_session_restore(&self) -> Result<()> {
+        let configs = self.database.load_session()?;
+        info!("Previous session has {} instances", configs.len());
+        *self
+            .pending_session
+            .write()
+            .map_err(|e| anyhow::anyhow!("Pending session lock poisoned: {}", e))? = configs;
+        Ok(())
+    }
+
+    /// Launch the chosen entries of the pending session and drop the rest.
+    /// Returns how many instances were restored.
+    pub fn restore_pending_session(&self, selected: &[bool]) -> Result<usize> {
+        let configs = std::mem::take(
+            &mut *self
+                .pending_session
+                .write()
+                .map_err(|e| anyhow::anyhow!("Pending session lock poisoned: {}", e))?,
+        );
+
+        let mut restored = 0;
+        for (config, _) in configs
+            .into_iter()
+            .zip(selected.iter().chain(std::iter::repeat(&false)))
+            .filter(|(config, chosen)| **chosen && config.executable_exists())
+        {
+            match self.create_instance(config, true) {
+                Ok(_) => restored += 1,
+                Err(e) => error!("Failed to restore instance: {}", e),
+            }
+        }
+        info!("Restored {} instances from previous session", restored);
+        Ok(restored)
+    }
+
+    /// Forget the pending session without restoring anything
+    pub fn dismiss_pending_session(&self) {
+        if let Ok(mut pending) = self.pending_session.write() {
+            pending.clear();
+        }
+    }
+
     /// Save a profile
     pub fn save_profile(&self, profile: Profile) -> Result<()> {
         self.database.save_profile(&profile)?;
+        self.mirror_profile(&profile);
         self.profiles
             .write()
             .map_err(|e| anyhow::anyhow!("Profiles lock poisoned: {}", e))?
@@ -454,9 +4190,79 @@ impl AppState {
         Ok(())
     }
 
+    /// Remember `layout` as the window arrangement of a profile
+    pub fn set_profile_window_layout(
+        &self,
+        id: ProfileId,
+        layout: Option<WindowLayout>,
+    ) -> Result<()> {
+        let mut profiles = self
+            .profiles
+            .write()
+            .map_err(|e| anyhow::anyhow!("Profiles lock poisoned: {}", e))?;
+        let profile = profiles.get_mut(&id).context("Profile not found")?;
+        profile.window_layout = layout;
+        profile.mark_modified();
+        self.database.save_profile(profile)?;
+        let profile = profile.clone();
+        drop(profiles);
+        self.mirror_profile(&profile);
+        Ok(())
+    }
+
+    /// Replace the resource limits of one profile member
+    pub fn set_profile_member_limits(
+        &self,
+        id: ProfileId,
+        member: usize,
+        limits: ResourceLimits,
+    ) -> Result<()> {
+        let mut profiles = self
+            .profiles
+            .write()
+            .map_err(|e| anyhow::anyhow!("Profiles lock poisoned: {}", e))?;
+        let profile = profiles.get_mut(&id).context("Profile not found")?;
+        let config = profile
+            .instances
+            .get_mut(member)
+            .context("Profile member not found")?;
+        config.resource_limits = limits;
+        profile.mark_modified();
+        self.database.save_profile(profile)?;
+        let profile = profile.clone();
+        drop(profiles);
+        self.mirror_profile(&profile);
+        Ok(())
+    }
+
+    /// Set or clear what a profile member waits for before it is launched
+    pub fn set_profile_member_dependency(
+        &self,
+        id: ProfileId,
+        member: usize,
+        dependency: Option<MemberDependency>,
+    ) -> Result<()> {
+        let mut profiles = self
+            .profiles
+            .write()
+            .map_err(|e| anyhow::anyhow!("Profiles lock poisoned: {}", e))?;
+        let profile = profiles.get_mut(&id).context("Profile not found")?;
+        profile.set_dependency(member, dependency)?;
+        self.database.save_profile(profile)?;
+        let profile = profile.clone();
+        drop(profiles);
+        self.mirror_profile(&profile);
+        Ok(())
+    }
+
     /// Delete a profile
     pub fn delete_profile(&self, id: ProfileId) -> Result<()> {
         self.database.delete_profile(id)?;
+        if let Ok(mut sync) = self.profile_sync.write() {
+            if let Err(e) = sync.remove_profile(id) {
+                warn!("Failed to remove synced profile: {}", e);
+            }
+        }
         self.profiles
             .write()
             .map_err(|e| anyhow::anyhow!("Profiles lock poisoned: {}", e))?
@@ -464,6 +4270,120 @@ impl AppState {
         Ok(())
     }
 
+    /// Save a schedule
+    pub fn save_schedule(&self, schedule: Schedule) -> Result<()> {
+        self.database.save_schedule(&schedule)?;
+        self.schedules
+            .write()
+            .map_err(|e| anyhow::anyhow!("Schedules lock poisoned: {}", e))?
+            .insert(schedule.id, schedule);
+        Ok(())
+    }
+
+    /// Delete a schedule
+    pub fn delete_schedule(&self, id: ScheduleId) -> Result<()> {
+        self.database.delete_schedule(id)?;
+        self.schedules
+            .write()
+            .map_err(|e| anyhow::anyhow!("Schedules lock poisoned: {}", e))?
+            .remove(&id);
+        Ok(())
+    }
+
+    /// Fire schedules that came due since the last check. Launches run on a
+    /// background thread since staggered profile launches sleep between members.
+    pub fn run_due_schedules(&self) {
+        let now = chrono::Local::now();
+        let since = match self.last_schedule_check.write() {
+            Ok(mut last) => std::mem::replace(&mut *last, now),
+            Err(_) => return,
+        };
+        let due: Vec<ScheduleId> = self
+            .schedules
+            .read()
+            .map(|schedules| {
+                schedules
+                    .values()
+                    .filter(|s| s.is_due(since, now))
+                    .map(|s| s.id)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        for id in due {
+            let state = self.clone();
+            std::thread::spawn(move || {
+                if let Err(e) = state.run_schedule(id) {
+                    warn!("Scheduled launch {} failed: {}", id, e);
+                }
+            });
+        }
+    }
+
+    /// Switch running instances between their own limits and those of the
+    /// limit window open now, applied to the process straight away
+    pub fn apply_limit_schedules(&self) {
+        let now = chrono::Local::now();
+        let Ok(mut instances) = self.instances.write() else {
+            return;
+        };
+        for instance in instances.values_mut() {
+            if !instance.status.is_active()
+                || (instance.config.limit_schedule.is_empty() && instance.limit_window.is_none())
+            {
+                continue;
+            }
+            let window = limit_schedule::active(&instance.config.limit_schedule, now).cloned();
+            if window == instance.limit_window {
+                continue;
+            }
+            let limits = window
+                .as_ref()
+                .map_or(&instance.config.resource_limits, |w| &w.limits)
+                .clone();
+            let label = window.as_ref().map_or("its own", |w| w.name.as_str());
+            match self.process_manager.apply_limits_now(instance, &limits) {
+                Ok(()) => info!("'{}' switched to {} limits", instance.display_name(), label),
+                Err(e) => warn!(
+                    "Failed to switch '{}' to {} limits: {}",
+                    instance.display_name(),
+                    label,
+                    e
+                ),
+            }
+            // Not retried on failure, as the same error would repeat every tick
+            instance.limit_window = window;
+        }
+    }
+
+    /// Launch a schedule's target now and record the outcome on the schedule
+    pub fn run_schedule(&self, id: ScheduleId) -> Result<()> {
+        let (name, target) = self
+            .schedules
+            .read()
+            .map_err(|e| anyhow::anyhow!("Schedules lock poisoned: {}", e))?
+            .get(&id)
+            .map(|s| (s.name.clone(), s.target))
+            .context("Schedule not found")?;
+        info!("Running schedule '{}'", name);
+
+        let result = match target {
+            ScheduleTarget::Instance(instance_id) => self.start_instance(instance_id),
+            ScheduleTarget::Profile(profile_id) => self.launch_profile(profile_id).map(|_| ()),
+        };
+
+        let mut schedules = self
+            .schedules
+            .write()
+            .map_err(|e| anyhow::anyhow!("Schedules lock poisoned: {}", e))?;
+        if let Some(schedule) = schedules.get_mut(&id) {
+            schedule.last_run = Some(chrono::Utc::now());
+            schedule.last_error = result.as_ref().err().map(|e| e.to_string());
+            self.database.save_schedule(schedule)?;
+        }
+        result
+    }
+
     /// Save settings
     pub fn save_settings(&self) -> Result<()> {
         let settings = self
@@ -471,16 +4391,162 @@ impl AppState {
             .read()
             .map_err(|e| anyhow::anyhow!("Settings lock poisoned: {}", e))?;
         self.database.save_settings(&settings)?;
+
+        if let Err(e) = deployment::sync_autostart(settings.start_with_system) {
+            warn!("Failed to update start with system: {}", e);
+        }
+        Ok(())
+    }
+
+    /// Write settings alone to a JSON file, leaving out machine-local keys
+    pub fn export_settings(&self, destination: &std::path::Path) -> Result<()> {
+        let json = self
+            .settings
+            .read()
+            .map_err(|e| anyhow::anyhow!("Settings lock poisoned: {}", e))?
+            .export_json()?;
+        std::fs::write(destination, json)
+            .with_context(|| format!("Failed to write {:?}", destination))?;
+        info!("Exported settings to {:?}", destination);
+        Ok(())
+    }
+
+    /// Replace settings with those from a file written by `export_settings`
+    pub fn import_settings(&self, source: &std::path::Path) -> Result<()> {
+        let json = std::fs::read_to_string(source)
+            .with_context(|| format!("Failed to read {:?}", source))?;
+        {
+            let mut settings = self
+                .settings
+                .write()
+                .map_err(|e| anyhow::anyhow!("Settings lock poisoned: {}", e))?;
+            *settings = Settings::import_json(&json, &settings)?;
+        }
+        self.save_settings()?;
+        info!("Imported settings from {:?}", source);
+        Ok(())
+    }
+
+    /// Custom theme saved under a name
+    pub fn custom_theme(&self, name: &str) -> Option<CustomTheme> {
+        self.custom_themes
+            .read()
+            .ok()?
+            .iter()
+            .find(|t| t.name == name)
+            .cloned()
+    }
+
+    /// Names of all custom themes
+    pub fn custom_theme_names(&self) -> Vec<String> {
+        self.custom_themes
+            .read()
+            .map(|themes| themes.iter().map(|t| t.name.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Save a custom theme, replacing `original` when it was renamed
+    pub fn save_custom_theme(&self, original: Option<&str>, theme: CustomTheme) -> Result<()> {
+        let name = theme.name.trim().to_string();
+        if name.is_empty() {
+            anyhow::bail!("Theme name cannot be empty");
+        }
+        let theme = CustomTheme { name, ..theme };
+        let renamed = original.filter(|o| *o != theme.name);
+
+        {
+            let mut themes = self
+                .custom_themes
+                .write()
+                .map_err(|e| anyhow::anyhow!("Custom themes lock poisoned: {}", e))?;
+            if original != Some(theme.name.as_str()) && themes.iter().any(|t| t.name == theme.name)
+            {
+                anyhow::bail!("A theme named {} already exists", theme.name);
+            }
+
+            self.database.save_theme(&theme)?;
+            if let Some(old) = renamed {
+                self.database.delete_theme(old)?;
+            }
+            themes.retain(|t| Some(t.name.as_str()) != original && t.name != theme.name);
+            themes.push(theme.clone());
+            themes.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+
+        if let Some(old) = renamed {
+            let mut settings = self
+                .settings
+                .write()
+                .map_err(|e| anyhow::anyhow!("Settings lock poisoned: {}", e))?;
+            if settings.custom_theme.as_deref() == Some(old) {
+                settings.custom_theme = Some(theme.name.clone());
+            }
+        }
+        info!("Saved custom theme {}", theme.name);
+        Ok(())
+    }
+
+    /// Delete a custom theme, falling back to the built-in theme if it was selected
+    pub fn delete_custom_theme(&self, name: &str) -> Result<()> {
+        self.database.delete_theme(name)?;
+        self.custom_themes
+            .write()
+            .map_err(|e| anyhow::anyhow!("Custom themes lock poisoned: {}", e))?
+            .retain(|t| t.name != name);
+
+        let mut settings = self
+            .settings
+            .write()
+            .map_err(|e| anyhow::anyhow!("Settings lock poisoned: {}", e))?;
+        if settings.custom_theme.as_deref() == Some(name) {
+            settings.custom_theme = None;
+        }
+        info!("Deleted custom theme {}", name);
+        Ok(())
+    }
+
+    /// Write a custom theme to a file
+    pub fn export_custom_theme(&self, name: &str, destination: &Path) -> Result<()> {
+        let theme = self
+            .custom_theme(name)
+            .with_context(|| format!("No theme named {}", name))?;
+        std::fs::write(destination, theme.export_json()?)
+            .with_context(|| format!("Failed to write {:?}", destination))?;
+        info!("Exported theme {} to {:?}", name, destination);
         Ok(())
     }
 
+    /// Add a theme from a file written by `export_custom_theme`, returning its name.
+    /// A number is appended when a theme with the same name exists.
+    pub fn import_custom_theme(&self, source: &Path) -> Result<String> {
+        let json = std::fs::read_to_string(source)
+            .with_context(|| format!("Failed to read {:?}", source))?;
+        let mut theme = CustomTheme::import_json(&json)?;
+
+        let names = self.custom_theme_names();
+        let base = theme.name.trim().to_string();
+        theme.name = base.clone();
+        let mut n = 2;
+        while names.contains(&theme.name) {
+            theme.name = format!("{} ({})", base, n);
+            n += 1;
+        }
+
+        let name = theme.name.clone();
+        self.save_custom_theme(None, theme)?;
+        info!("Imported theme {} from {:?}", name, source);
+        Ok(name)
+    }
+
     /// Add to quick launch
     pub fn add_quick_launch(&self, config: InstanceConfig) -> Result<()> {
+        let executable = config.executable_path.clone();
         self.quick_launch
             .write()
             .map_err(|e| anyhow::anyhow!("Quick launch lock poisoned: {}", e))?
             .push(config);
         self.save_quick_launch()?;
+        self.executable_icon(&executable);
         Ok(())
     }
 
@@ -501,11 +4567,18 @@ impl AppState {
 
     /// Save quick launch items to database
     fn save_quick_launch(&self) -> Result<()> {
+        // Copied so the sync folder is written without holding the list
         let quick_launch = self
             .quick_launch
             .read()
-            .map_err(|e| anyhow::anyhow!("Quick launch lock poisoned: {}", e))?;
+            .map_err(|e| anyhow::anyhow!("Quick launch lock poisoned: {}", e))?
+            .clone();
         self.database.save_quick_launch(&quick_launch)?;
+        if let Ok(mut sync) = self.profile_sync.write() {
+            if let Err(e) = sync.write_quick_launch(&quick_launch) {
+                warn!("Failed to sync quick launch items: {}", e);
+            }
+        }
         Ok(())
     }
 
@@ -562,6 +4635,92 @@ impl AppState {
         Ok(())
     }
 
+    /// Rename a group, moving its members along
+    pub fn rename_group(&self, old: &str, new: &str) -> Result<()> {
+        let new = new.trim();
+        if new.is_empty() {
+            anyhow::bail!("Group name cannot be empty");
+        }
+        if new != old && self.group_names().iter().any(|g| g == new) {
+            anyhow::bail!("A group named '{}' already exists; merge instead", new);
+        }
+        self.move_group(old, new)
+    }
+
+    /// Move every member of `from` into `into` and drop `from`
+    pub fn merge_group(&self, from: &str, into: &str) -> Result<()> {
+        if from == into {
+            anyhow::bail!("Cannot merge a group into itself");
+        }
+        self.move_group(from, into)
+    }
+
+    /// Every group name, whether added explicitly or only used by an instance
+    pub fn group_names(&self) -> Vec<String> {
+        let mut names = self.groups.read().map(|g| g.clone()).unwrap_or_default();
+        if let Ok(instances) = self.instances.read() {
+            for group in instances.values().filter_map(|i| i.config.group.as_ref()) {
+                if !names.contains(group) {
+                    names.push(group.clone());
+                }
+            }
+        }
+        names.sort();
+        names
+    }
+
+    /// Replace group `from` with `to` in the group list and every member's
+    /// config, saving both in one transaction
+    fn move_group(&self, from: &str, to: &str) -> Result<()> {
+        let mut instances = self
+            .instances
+            .write()
+            .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+        let mut groups = self
+            .groups
+            .write()
+            .map_err(|e| anyhow::anyhow!("Groups lock poisoned: {}", e))?;
+
+        let mut renamed = groups.clone();
+        match renamed.iter().position(|g| g == from) {
+            Some(index) if !renamed.iter().any(|g| g == to) => renamed[index] = to.to_string(),
+            _ => {
+                renamed.retain(|g| g != from);
+                if !renamed.iter().any(|g| g == to) {
+                    renamed.push(to.to_string());
+                }
+            }
+        }
+
+        // Save first so a failed write leaves memory and database in agreement
+        let updated: Vec<Instance> = instances
+            .values()
+            .filter(|i| i.config.group.as_deref() == Some(from))
+            .map(|i| {
+                let mut instance = i.clone();
+                instance.config.group = Some(to.to_string());
+                instance
+            })
+            .collect();
+        self.database
+            .save_groups_and_members(&renamed, &updated.iter().collect::<Vec<_>>())?;
+
+        for member in &updated {
+            if let Some(instance) = instances.get_mut(&member.id) {
+                instance.config.group = Some(to.to_string());
+            }
+        }
+        *groups = renamed;
+        self.instance_revision.fetch_add(1, Ordering::AcqRel);
+        info!(
+            "Moved group '{}' to '{}' ({} instance(s))",
+            from,
+            to,
+            updated.len()
+        );
+        Ok(())
+    }
+
     /// Get count of active instances
     pub fn active_instance_count(&self) -> usize {
         self.instances
@@ -581,6 +4740,22 @@ impl AppState {
     }
 }
 
+/// Fail on blocking launch conflicts and log the rest
+fn check_launch_conflicts(
+    config: &InstanceConfig,
+    id: Option<InstanceId>,
+    instances: &HashMap<InstanceId, Instance>,
+) -> Result<()> {
+    let conflicts = conflicts::detect(config, id, instances);
+    if let Some(conflict) = conflicts.iter().find(|c| c.is_blocking()) {
+        anyhow::bail!("Cannot launch '{}': {}", config.name, conflict.message());
+    }
+    for conflict in &conflicts {
+        warn!("Launching '{}': {}", config.name, conflict.message());
+    }
+    Ok(())
+}
+
 impl Clone for AppState {
     fn clone(&self) -> Self {
         Self {
@@ -594,6 +4769,58 @@ impl Clone for AppState {
             groups: Arc::clone(&self.groups),
             recent_apps: Arc::clone(&self.recent_apps),
             last_resource_update: Arc::clone(&self.last_resource_update),
+            last_copy_scan: Arc::clone(&self.last_copy_scan),
+            last_log_prune: Arc::clone(&self.last_log_prune),
+            rolling_restarts: Arc::clone(&self.rolling_restarts),
+            data_loaded: Arc::clone(&self.data_loaded),
+            orphans: Arc::clone(&self.orphans),
+            open_files: Arc::clone(&self.open_files),
+            connections: Arc::clone(&self.connections),
+            connections_in_flight: Arc::clone(&self.connections_in_flight),
+            recovery: Arc::clone(&self.recovery),
+            marked_instances: Arc::clone(&self.marked_instances),
+            pending_session: Arc::clone(&self.pending_session),
+            usage: Arc::clone(&self.usage),
+            last_usage_flush: Arc::clone(&self.last_usage_flush),
+            last_network_check: Arc::clone(&self.last_network_check),
+            network_check_running: Arc::clone(&self.network_check_running),
+            region_captures: Arc::clone(&self.region_captures),
+            last_region_capture: Arc::clone(&self.last_region_capture),
+            region_capture_running: Arc::clone(&self.region_capture_running),
+            window_thumbnails: Arc::clone(&self.window_thumbnails),
+            executable_icons: Arc::clone(&self.executable_icons),
+            screenshots: Arc::clone(&self.screenshots),
+            custom_themes: Arc::clone(&self.custom_themes),
+            thumbnail_capture_running: Arc::clone(&self.thumbnail_capture_running),
+            switcher_requested: Arc::clone(&self.switcher_requested),
+            activations: Arc::clone(&self.activations),
+            launch_warnings: Arc::clone(&self.launch_warnings),
+            profile_sync: Arc::clone(&self.profile_sync),
+            last_health_check: Arc::clone(&self.last_health_check),
+            last_handle_reconcile: Arc::clone(&self.last_handle_reconcile),
+            health_check_running: Arc::clone(&self.health_check_running),
+            health_events: Arc::clone(&self.health_events),
+            exit_events: Arc::clone(&self.exit_events),
+            crash_history: Arc::clone(&self.crash_history),
+            host_memory_alerted: Arc::clone(&self.host_memory_alerted),
+            profiler: Arc::clone(&self.profiler),
+            hotkey_errors: Arc::clone(&self.hotkey_errors),
+            macros_running: Arc::clone(&self.macros_running),
+            data_jobs: Arc::clone(&self.data_jobs),
+            snapshot_lists: Arc::clone(&self.snapshot_lists),
+            checkpoint_lists: Arc::clone(&self.checkpoint_lists),
+            bundle_imports: Arc::clone(&self.bundle_imports),
+            bundle_results: Arc::clone(&self.bundle_results),
+            last_crash_dialog_check: Arc::clone(&self.last_crash_dialog_check),
+            crash_dialog_check_running: Arc::clone(&self.crash_dialog_check_running),
+            last_title_check: Arc::clone(&self.last_title_check),
+            title_check_running: Arc::clone(&self.title_check_running),
+            isolation_check_running: Arc::clone(&self.isolation_check_running),
+            broadcasting: Arc::clone(&self.broadcasting),
+            broadcast_targets: Arc::clone(&self.broadcast_targets),
+            schedules: Arc::clone(&self.schedules),
+            last_schedule_check: Arc::clone(&self.last_schedule_check),
+            instance_revision: Arc::clone(&self.instance_revision),
         }
     }
 }
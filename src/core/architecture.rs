@@ -0,0 +1,300 @@
+//! CPU architecture - Detect executable architectures and emulated launches
+//!
+//! Covers x64 apps on ARM64 Windows (x64 emulation) and Intel apps on Apple
+//! Silicon (Rosetta 2), where instances run noticeably slower than native ones.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+/// A CPU architecture an executable can be built for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuArch {
+    X86,
+    X86_64,
+    Arm64,
+}
+
+impl CpuArch {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::X86 => "x86",
+            Self::X86_64 => "x86-64",
+            Self::Arm64 => "ARM64",
+        }
+    }
+}
+
+/// Which architecture to launch a multi-architecture executable as
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum LaunchArchitecture {
+    /// Let the OS pick, preferring the native slice
+    #[default]
+    Native,
+    /// Force the Intel slice (runs under Rosetta 2 on Apple Silicon)
+    X86_64,
+    /// Force the Apple Silicon slice
+    Arm64,
+}
+
+impl LaunchArchitecture {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Native => "Native",
+            Self::X86_64 => "Intel (x86-64)",
+            Self::Arm64 => "Apple Silicon (arm64)",
+        }
+    }
+
+    pub fn all() -> &'static [Self] {
+        &[Self::Native, Self::X86_64, Self::Arm64]
+    }
+
+    /// Architecture to request, if one is forced
+    pub fn forced(&self) -> Option<CpuArch> {
+        match self {
+            Self::Native => None,
+            Self::X86_64 => Some(CpuArch::X86_64),
+            Self::Arm64 => Some(CpuArch::Arm64),
+        }
+    }
+
+    /// Flag for the macOS `arch` command
+    pub fn arch_flag(&self) -> Option<&'static str> {
+        match self {
+            Self::Native => None,
+            Self::X86_64 => Some("-x86_64"),
+            Self::Arm64 => Some("-arm64"),
+        }
+    }
+}
+
+/// Architecture details of an executable on this machine
+#[derive(Debug, Clone)]
+pub struct ArchitectureInfo {
+    /// Architectures the executable contains (several for macOS universal binaries)
+    pub binary: Vec<CpuArch>,
+    /// Native architecture of this machine
+    pub host: CpuArch,
+    /// Architecture the executable will run as
+    pub runs_as: CpuArch,
+}
+
+impl ArchitectureInfo {
+    /// Inspect an executable, taking a forced launch architecture into account
+    pub fn inspect(executable: &Path, launch: LaunchArchitecture) -> Option<Self> {
+        let binary = executable_architectures(executable);
+        let host = host_architecture();
+
+        let runs_as = launch
+            .forced()
+            .filter(|arch| binary.contains(arch))
+            .or_else(|| binary.iter().copied().find(|arch| *arch == host))
+            .or_else(|| binary.first().copied())?;
+
+        Some(Self {
+            binary,
+            host,
+            runs_as,
+        })
+    }
+
+    /// Whether the executable runs through a translation layer
+    pub fn is_emulated(&self) -> bool {
+        // 32-bit x86 on x64 runs natively through WOW64
+        self.runs_as != self.host && !(self.host == CpuArch::X86_64 && self.runs_as == CpuArch::X86)
+    }
+
+    /// Name of the translation layer used on this platform
+    pub fn emulation_label(&self) -> &'static str {
+        if cfg!(target_os = "macos") {
+            "Rosetta 2"
+        } else if cfg!(windows) {
+            "Windows x64 emulation"
+        } else {
+            "binfmt emulation"
+        }
+    }
+
+    /// One-line summary, e.g. "x86-64 (emulated via Rosetta 2)"
+    pub fn summary(&self) -> String {
+        let mut summary = self.runs_as.label().to_string();
+        if self.is_emulated() {
+            summary.push_str(&format!(" (emulated via {})", self.emulation_label()));
+        }
+        if self.binary.len() > 1 {
+            let slices: Vec<_> = self.binary.iter().map(|a| a.label()).collect();
+            summary.push_str(&format!(", universal: {}", slices.join(" + ")));
+        }
+        summary
+    }
+}
+
+/// Native architecture of this machine, even when MultiInstance itself is emulated
+pub fn host_architecture() -> CpuArch {
+    static HOST: OnceLock<CpuArch> = OnceLock::new();
+    *HOST.get_or_init(detect_host_architecture)
+}
+
+fn detect_host_architecture() -> CpuArch {
+    #[cfg(windows)]
+    {
+        use winreg::enums::HKEY_LOCAL_MACHINE;
+        use winreg::RegKey;
+
+        let native = RegKey::predef(HKEY_LOCAL_MACHINE)
+            .open_subkey(r"SYSTEM\CurrentControlSet\Control\Session Manager\Environment")
+            .and_then(|key| key.get_value::<String, _>("PROCESSOR_ARCHITECTURE"));
+        match native.as_deref() {
+            Ok("ARM64") => return CpuArch::Arm64,
+            Ok("AMD64") => return CpuArch::X86_64,
+            Ok("x86") => return CpuArch::X86,
+            _ => {}
+        }
+    }
+    #[cfg(target_os = "macos")]
+    {
+        // Reports 1 on Apple Silicon, including from a process running under Rosetta
+        let arm64 = std::process::Command::new("sysctl")
+            .args(["-n", "hw.optional.arm64"])
+            .output()
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim() == "1")
+            .unwrap_or(false);
+        if arm64 {
+            return CpuArch::Arm64;
+        }
+    }
+
+    match std::env::consts::ARCH {
+        "aarch64" => CpuArch::Arm64,
+        "x86" => CpuArch::X86,
+        _ => CpuArch::X86_64,
+    }
+}
+
+/// Read the architectures an executable is built for from its PE, Mach-O or ELF header
+pub fn executable_architectures(path: &Path) -> Vec<CpuArch> {
+    let path = resolve_bundle_executable(path);
+    let mut header = Vec::new();
+    let read = std::fs::File::open(&path)
+        .and_then(|file| file.take(4096).read_to_end(&mut header))
+        .is_ok();
+    if !read || header.len() < 8 {
+        return Vec::new();
+    }
+
+    if header.starts_with(b"MZ") {
+        parse_pe(&header).into_iter().collect()
+    } else if header.starts_with(b"\x7fELF") {
+        parse_elf(&header).into_iter().collect()
+    } else {
+        parse_mach_o(&header)
+    }
+}
+
+/// Find the main executable inside a macOS `.app` bundle
+fn resolve_bundle_executable(path: &Path) -> PathBuf {
+    if !path.is_dir() {
+        return path.to_path_buf();
+    }
+
+    let macos_dir = path.join("Contents").join("MacOS");
+    if let Some(stem) = path.file_stem() {
+        let candidate = macos_dir.join(stem);
+        if candidate.is_file() {
+            return candidate;
+        }
+    }
+    std::fs::read_dir(&macos_dir)
+        .ok()
+        .and_then(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .find(|p| p.is_file())
+        })
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+fn read_u16_le(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn read_u32(data: &[u8], offset: usize, big_endian: bool) -> Option<u32> {
+    let bytes: [u8; 4] = data.get(offset..offset + 4)?.try_into().ok()?;
+    Some(if big_endian {
+        u32::from_be_bytes(bytes)
+    } else {
+        u32::from_le_bytes(bytes)
+    })
+}
+
+fn parse_pe(header: &[u8]) -> Option<CpuArch> {
+    let pe_offset = read_u32(header, 0x3C, false)? as usize;
+    if header.get(pe_offset..pe_offset + 4)? != b"PE\0\0" {
+        return None;
+    }
+    match read_u16_le(header, pe_offset + 4)? {
+        0x014C => Some(CpuArch::X86),
+        0x8664 => Some(CpuArch::X86_64),
+        0xAA64 => Some(CpuArch::Arm64),
+        _ => None,
+    }
+}
+
+fn parse_elf(header: &[u8]) -> Option<CpuArch> {
+    let big_endian = header.get(5) == Some(&2);
+    let machine = read_u16_le(header, 0x12)?;
+    let machine = if big_endian {
+        machine.swap_bytes()
+    } else {
+        machine
+    };
+    match machine {
+        0x03 => Some(CpuArch::X86),
+        0x3E => Some(CpuArch::X86_64),
+        0xB7 => Some(CpuArch::Arm64),
+        _ => None,
+    }
+}
+
+fn mach_o_cpu(cpu_type: u32) -> Option<CpuArch> {
+    match cpu_type {
+        0x0000_0007 => Some(CpuArch::X86),
+        0x0100_0007 => Some(CpuArch::X86_64),
+        0x0100_000C => Some(CpuArch::Arm64),
+        _ => None,
+    }
+}
+
+fn parse_mach_o(header: &[u8]) -> Vec<CpuArch> {
+    let Some(magic) = read_u32(header, 0, true) else {
+        return Vec::new();
+    };
+
+    match magic {
+        // Universal binary: big-endian list of slices
+        0xCAFE_BABE | 0xCAFE_BABF => {
+            let entry_size = if magic == 0xCAFE_BABF { 32 } else { 20 };
+            let count = read_u32(header, 4, true).unwrap_or(0) as usize;
+            (0..count.min(16))
+                .filter_map(|i| read_u32(header, 8 + i * entry_size, true))
+                .filter_map(mach_o_cpu)
+                .collect()
+        }
+        // Thin binaries, in either byte order
+        0xFEED_FACE | 0xFEED_FACF => read_u32(header, 4, true)
+            .and_then(mach_o_cpu)
+            .into_iter()
+            .collect(),
+        0xCEFA_EDFE | 0xCFFA_EDFE => read_u32(header, 4, false)
+            .and_then(mach_o_cpu)
+            .into_iter()
+            .collect(),
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,28 @@
+//! Input broadcasting - Mirror keyboard and mouse input across a group
+//!
+//! While broadcasting is on, keys pressed in the focused window of a group
+//! member are also sent to the windows of the other running members, so
+//! several game clients can be played at once. Only physical input is
+//! mirrored, never the copies sent, and a key list can limit which keys are.
+
+use serde::{Deserialize, Serialize};
+
+/// What is broadcast and to which group
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BroadcastSettings {
+    /// Group whose members share input
+    pub group: Option<String>,
+    /// Keys mirrored, named as in macros (e.g. `F1`, `Space`, `1`); empty mirrors every key
+    pub keys: Vec<String>,
+    /// Mirror mouse clicks too, at the same relative spot of each window
+    pub mouse: bool,
+}
+
+/// Processes input is mirrored between, as handed to the platform layer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BroadcastTargets {
+    pub pids: Vec<u32>,
+    pub keys: Vec<String>,
+    pub mouse: bool,
+}
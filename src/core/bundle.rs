@@ -0,0 +1,177 @@
+//! Instance bundles - Portable archives of an instance's config and data
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::data_compression;
+use super::instance::InstanceConfig;
+
+/// File extension used for instance bundles
+pub const BUNDLE_EXTENSION: &str = "mibundle";
+
+/// Bundle format version, bumped on incompatible layout changes
+const FORMAT_VERSION: u32 = 1;
+
+/// Manifest file inside the bundle
+const MANIFEST_FILE: &str = "instance.json";
+
+/// Folder inside the bundle holding the data directory
+const DATA_FOLDER: &str = "data";
+
+/// Placeholder for the user's home directory in exported paths
+const HOME_TOKEN: &str = "{HOME}";
+
+/// Metadata stored at the root of a bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    /// Bundle format version
+    pub format_version: u32,
+    /// When the bundle was created
+    pub exported_at: DateTime<Utc>,
+    /// Instance configuration with machine-specific paths made portable
+    pub config: InstanceConfig,
+}
+
+/// Package an instance's config and data directory into a single archive
+pub fn export(config: &InstanceConfig, data_dir: &Path, destination: &Path) -> Result<()> {
+    let result = write_bundle(config, data_dir, destination);
+    if result.is_err() {
+        let _ = std::fs::remove_file(destination);
+    }
+    result?;
+
+    info!("Exported instance '{}' to {:?}", config.name, destination);
+    Ok(())
+}
+
+fn write_bundle(config: &InstanceConfig, data_dir: &Path, destination: &Path) -> Result<()> {
+    let manifest = BundleManifest {
+        format_version: FORMAT_VERSION,
+        exported_at: Utc::now(),
+        config: make_portable(config),
+    };
+    let json = serde_json::to_vec_pretty(&manifest)?;
+
+    let file =
+        File::create(destination).with_context(|| format!("Failed to write {:?}", destination))?;
+    let mut builder =
+        tar::Builder::new(GzEncoder::new(BufWriter::new(file), Compression::default()));
+    let mut header = tar::Header::new_gnu();
+    header.set_size(json.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(manifest.exported_at.timestamp().max(0) as u64);
+    header.set_cksum();
+    builder.append_data(&mut header, MANIFEST_FILE, json.as_slice())?;
+
+    if data_dir.is_dir() {
+        builder.append_dir(DATA_FOLDER, data_dir)?;
+        data_compression::append_dir(&mut builder, data_dir, Path::new(DATA_FOLDER))?;
+    }
+
+    let mut writer = builder.into_inner()?.finish()?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Unpack a bundle, writing its data into `data_dir` and returning the config
+pub fn import(bundle: &Path, data_dir: &Path) -> Result<InstanceConfig> {
+    let config = read_bundle(bundle, data_dir)?;
+
+    info!("Imported instance '{}' from {:?}", config.name, bundle);
+    Ok(config)
+}
+
+fn read_bundle(bundle: &Path, data_dir: &Path) -> Result<InstanceConfig> {
+    let file = File::open(bundle).with_context(|| format!("Failed to read {:?}", bundle))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(BufReader::new(file)));
+
+    let mut manifest = None;
+    for entry in archive.entries().context("Bundle is damaged")? {
+        let mut entry = entry.context("Bundle is damaged")?;
+        // Bundles written by the tar tool prefix every path with "./"
+        let path: PathBuf = entry
+            .path()?
+            .components()
+            .filter(|c| !matches!(c, Component::CurDir))
+            .collect();
+        if path == Path::new(MANIFEST_FILE) {
+            let mut json = String::new();
+            entry.read_to_string(&mut json)?;
+            manifest = Some(serde_json::from_str::<BundleManifest>(&json)?);
+            continue;
+        }
+        let Ok(relative) = path.strip_prefix(DATA_FOLDER) else {
+            continue;
+        };
+        // Never let an entry escape the data directory
+        if !relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+        {
+            continue;
+        }
+        let destination = data_dir.join(relative);
+        if let Some(parent) = destination.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        entry
+            .unpack(&destination)
+            .with_context(|| format!("Failed to unpack {:?}", destination))?;
+    }
+
+    let manifest = manifest.context("Bundle is missing its manifest")?;
+    if manifest.format_version > FORMAT_VERSION {
+        anyhow::bail!(
+            "Bundle was created by a newer version (format {})",
+            manifest.format_version
+        );
+    }
+    Ok(resolve_portable(manifest.config))
+}
+
+/// Replace the home directory prefix with a placeholder and drop the data directory,
+/// which is recreated on the importing machine
+fn make_portable(config: &InstanceConfig) -> InstanceConfig {
+    let mut config = config.clone();
+    config.data_directory = None;
+
+    if let Some(home) = dirs::home_dir() {
+        rewrite_paths(&mut config, &home, Path::new(HOME_TOKEN));
+    }
+    config
+}
+
+/// Expand the home directory placeholder written by `make_portable`
+fn resolve_portable(mut config: InstanceConfig) -> InstanceConfig {
+    if let Some(home) = dirs::home_dir() {
+        rewrite_paths(&mut config, Path::new(HOME_TOKEN), &home);
+    }
+    config
+}
+
+/// Swap the `from` prefix for `to` in every machine-specific path of a config
+fn rewrite_paths(config: &mut InstanceConfig, from: &Path, to: &Path) {
+    for path in machine_paths(config) {
+        if let Ok(rest) = path.strip_prefix(from) {
+            *path = to.join(rest);
+        }
+    }
+}
+
+/// Paths in a config that only hold on the machine it was written on
+pub fn machine_paths(config: &mut InstanceConfig) -> Vec<&mut PathBuf> {
+    let mut paths = vec![&mut config.executable_path];
+    paths.extend(config.working_directory.as_mut());
+    paths.extend(config.data_directory.as_mut());
+    paths.extend(config.icon_path.as_mut());
+    paths
+}
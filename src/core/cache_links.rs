@@ -0,0 +1,190 @@
+//! Shared caches - Link cache folders of instance data dirs to a shared store
+
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Context, Result};
+use tracing::{debug, info, warn};
+
+/// Well-known cache folders, relative to an isolated data directory
+pub fn default_patterns() -> Vec<String> {
+    #[cfg(windows)]
+    {
+        vec![
+            "AppData/Local/*/Cache".to_string(),
+            "AppData/Local/*/Code Cache".to_string(),
+            "AppData/Local/*/GPUCache".to_string(),
+            "AppData/Local/*/ShaderCache".to_string(),
+        ]
+    }
+    #[cfg(target_os = "macos")]
+    {
+        vec!["Library/Caches/*".to_string()]
+    }
+    #[cfg(not(any(windows, target_os = "macos")))]
+    {
+        vec![".cache/*".to_string()]
+    }
+}
+
+/// Link every folder matching `patterns` inside `data_dir` to the same path inside `store_dir`.
+///
+/// Folders are symlinked where possible. If symlinks are unavailable the store is
+/// cloned copy-on-write instead, which still avoids duplicating blocks on APFS/Btrfs/XFS.
+/// Returns the number of folders linked.
+pub fn link_shared_caches(data_dir: &Path, store_dir: &Path, patterns: &[String]) -> usize {
+    let mut linked = 0;
+
+    for pattern in patterns {
+        let Some(relative_paths) = expand_pattern(data_dir, store_dir, pattern) else {
+            warn!("Ignoring invalid shared cache pattern '{}'", pattern);
+            continue;
+        };
+
+        for relative in relative_paths {
+            let target = data_dir.join(&relative);
+            let shared = store_dir.join(&relative);
+            match link_folder(&target, &shared) {
+                Ok(true) => linked += 1,
+                Ok(false) => {}
+                Err(e) => warn!("Failed to share cache folder {:?}: {}", target, e),
+            }
+        }
+    }
+
+    if linked > 0 {
+        info!("Linked {} cache folders to {:?}", linked, store_dir);
+    }
+    linked
+}
+
+/// Expand a pattern into relative paths. `*` matches one folder name that exists in
+/// either the data directory or the shared store.
+fn expand_pattern(data_dir: &Path, store_dir: &Path, pattern: &str) -> Option<Vec<PathBuf>> {
+    let pattern = Path::new(pattern.trim());
+    if pattern.as_os_str().is_empty() {
+        return None;
+    }
+
+    let mut prefixes = vec![PathBuf::new()];
+    for component in pattern.components() {
+        let Component::Normal(part) = component else {
+            // Reject absolute paths and `..` so links never leave the data directory
+            return None;
+        };
+
+        if part == "*" {
+            let mut expanded = Vec::new();
+            for prefix in &prefixes {
+                for base in [data_dir, store_dir] {
+                    let Ok(entries) = std::fs::read_dir(base.join(prefix)) else {
+                        continue;
+                    };
+                    for entry in entries.filter_map(|e| e.ok()) {
+                        let is_dir = std::fs::metadata(entry.path())
+                            .map(|m| m.is_dir())
+                            .unwrap_or(false);
+                        let candidate = prefix.join(entry.file_name());
+                        if is_dir && !expanded.contains(&candidate) {
+                            expanded.push(candidate);
+                        }
+                    }
+                }
+            }
+            prefixes = expanded;
+        } else {
+            for prefix in &mut prefixes {
+                prefix.push(part);
+            }
+        }
+    }
+
+    Some(prefixes)
+}
+
+/// Point `target` at `shared`. Returns `Ok(false)` when it was already linked.
+fn link_folder(target: &Path, shared: &Path) -> Result<bool> {
+    if std::fs::symlink_metadata(target)
+        .map(|m| m.file_type().is_symlink())
+        .unwrap_or(false)
+    {
+        return Ok(false);
+    }
+
+    let mut seeded = false;
+    if target.exists() {
+        let is_empty = std::fs::read_dir(target)?.next().is_none();
+        if !shared.exists() {
+            // First instance seeds the shared store with its existing cache
+            if let Some(parent) = shared.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::rename(target, shared).context("Failed to move cache to shared store")?;
+            seeded = true;
+        } else if is_empty {
+            std::fs::remove_dir(target)?;
+        } else {
+            debug!("Keeping existing cache folder {:?}", target);
+            return Ok(false);
+        }
+    } else {
+        std::fs::create_dir_all(shared)?;
+    }
+
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    if let Err(e) = symlink_dir(shared, target) {
+        debug!(
+            "Symlink failed ({}), falling back to copy-on-write clone",
+            e
+        );
+        if let Err(e) = clone_dir(shared, target) {
+            // Put the instance's own cache back rather than leaving it without one
+            if seeded {
+                let _ = std::fs::rename(shared, target);
+            }
+            return Err(e);
+        }
+    }
+
+    Ok(true)
+}
+
+#[cfg(unix)]
+fn symlink_dir(original: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(original, link)
+}
+
+#[cfg(windows)]
+fn symlink_dir(original: &Path, link: &Path) -> std::io::Result<()> {
+    // Requires Developer Mode or the SeCreateSymbolicLink privilege
+    std::os::windows::fs::symlink_dir(original, link)
+}
+
+/// Clone a folder using copy-on-write where the filesystem supports it
+fn clone_dir(source: &Path, destination: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        let mut cmd = std::process::Command::new("cp");
+        #[cfg(target_os = "macos")]
+        cmd.args(["-c", "-R"]);
+        #[cfg(not(target_os = "macos"))]
+        cmd.args(["-R", "--reflink=auto"]);
+
+        let status = cmd
+            .arg(source)
+            .arg(destination)
+            .status()
+            .context("Failed to run cp")?;
+        if !status.success() {
+            anyhow::bail!("cp exited with {}", status);
+        }
+        Ok(())
+    }
+    #[cfg(windows)]
+    {
+        let _ = (source, destination);
+        anyhow::bail!("Symlinks need Developer Mode and copy-on-write cloning is unavailable")
+    }
+}
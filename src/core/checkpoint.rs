@@ -0,0 +1,83 @@
+//! Experimental checkpoints - Instances suspended to disk for later restore
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::instance::{InstanceConfig, InstanceId};
+
+/// Name of the metadata file written next to the process images
+const MANIFEST_FILE: &str = "checkpoint.json";
+
+/// Metadata describing a checkpoint on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Instance that was checkpointed
+    pub instance_id: InstanceId,
+    /// Configuration the instance was launched with
+    pub config: InstanceConfig,
+    /// PID at the time of the checkpoint
+    pub original_pid: u32,
+    /// When the checkpoint was taken
+    pub created_at: DateTime<Utc>,
+    /// Directory holding the process images
+    #[serde(skip)]
+    pub directory: PathBuf,
+}
+
+impl Checkpoint {
+    pub fn new(
+        instance_id: InstanceId,
+        config: InstanceConfig,
+        original_pid: u32,
+        directory: PathBuf,
+    ) -> Self {
+        Self {
+            instance_id,
+            config,
+            original_pid,
+            created_at: Utc::now(),
+            directory,
+        }
+    }
+
+    /// Write the manifest into the checkpoint directory
+    pub fn save(&self) -> Result<()> {
+        std::fs::create_dir_all(&self.directory)?;
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(self.directory.join(MANIFEST_FILE), json)?;
+        Ok(())
+    }
+
+    /// Load a checkpoint from its directory
+    pub fn load(directory: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(directory.join(MANIFEST_FILE))
+            .context("Failed to read checkpoint manifest")?;
+        let mut checkpoint: Self = serde_json::from_str(&json)?;
+        checkpoint.directory = directory.to_path_buf();
+        Ok(checkpoint)
+    }
+
+    /// List all checkpoints under a base directory, newest first
+    pub fn list(base_dir: &Path) -> Vec<Self> {
+        let Ok(entries) = std::fs::read_dir(base_dir) else {
+            return Vec::new();
+        };
+
+        let mut checkpoints: Vec<Self> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_dir())
+            .filter_map(|e| Self::load(&e.path()).ok())
+            .collect();
+        checkpoints.sort_by_key(|c| std::cmp::Reverse(c.created_at));
+        checkpoints
+    }
+
+    /// Delete the checkpoint and its process images
+    pub fn delete(&self) -> Result<()> {
+        std::fs::remove_dir_all(&self.directory)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+//! Launch conflict detection - Resources an instance would fight over with running ones
+
+use std::collections::HashMap;
+use std::net::TcpListener;
+
+use super::instance::{Instance, InstanceConfig, InstanceId};
+
+/// What two instances would compete for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConflictKind {
+    /// Same executable without isolation, so both use the app's real data directory
+    SharedData,
+    /// A configured TCP port is already taken
+    Port(u16),
+    /// A configured device is held exclusively by another instance
+    Device(String),
+}
+
+/// A conflict between a launch and a running instance or another program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub kind: ConflictKind,
+    /// Display name of the instance holding the resource, if it is one of ours
+    pub holder: Option<String>,
+}
+
+impl Conflict {
+    /// Blocking conflicts make the launch fail; the rest are warnings
+    pub fn is_blocking(&self) -> bool {
+        !matches!(self.kind, ConflictKind::SharedData)
+    }
+
+    pub fn message(&self) -> String {
+        let holder = self
+            .holder
+            .as_deref()
+            .map(|name| format!("'{}'", name))
+            .unwrap_or_else(|| "another program".to_string());
+        match &self.kind {
+            ConflictKind::SharedData => format!(
+                "Shares its data directory with {} (enable environment isolation on both to separate them)",
+                holder
+            ),
+            ConflictKind::Port(port) => format!("Port {} is in use by {}", port, holder),
+            ConflictKind::Device(device) => format!("{} is held by {}", device, holder),
+        }
+    }
+}
+
+/// Find what launching `config` would conflict with.
+/// `id` is the instance being started, so it is not compared against itself.
+pub fn detect(
+    config: &InstanceConfig,
+    id: Option<InstanceId>,
+    instances: &HashMap<InstanceId, Instance>,
+) -> Vec<Conflict> {
+    let mut conflicts = Vec::new();
+    let mut held_ports = Vec::new();
+
+    for other in instances.values() {
+        if Some(other.id) == id || !other.status.is_active() {
+            continue;
+        }
+        let holder = Some(other.display_name().to_string());
+
+        let isolated = |c: &InstanceConfig| c.bypass_single_instance && c.use_environment_isolation;
+        if other.config.executable_path == config.executable_path
+            && !(isolated(config) && isolated(&other.config))
+        {
+            conflicts.push(Conflict {
+                kind: ConflictKind::SharedData,
+                holder: holder.clone(),
+            });
+        }
+
+        for port in config
+            .ports
+            .iter()
+            .filter(|p| other.config.ports.contains(p))
+        {
+            held_ports.push(*port);
+            conflicts.push(Conflict {
+                kind: ConflictKind::Port(*port),
+                holder: holder.clone(),
+            });
+        }
+
+        for device in &config.exclusive_devices {
+            if other
+                .config
+                .exclusive_devices
+                .iter()
+                .any(|d| d.eq_ignore_ascii_case(device))
+            {
+                conflicts.push(Conflict {
+                    kind: ConflictKind::Device(device.clone()),
+                    holder: holder.clone(),
+                });
+            }
+        }
+    }
+
+    // Ports held by programs we do not manage. Loopback only, to avoid firewall prompts.
+    for port in config.ports.iter().filter(|p| !held_ports.contains(p)) {
+        if TcpListener::bind(("127.0.0.1", *port)).is_err() {
+            conflicts.push(Conflict {
+                kind: ConflictKind::Port(*port),
+                holder: None,
+            });
+        }
+    }
+
+    conflicts
+}
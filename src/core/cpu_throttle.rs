@@ -0,0 +1,154 @@
+//! CPU throttle - Holds an instance to a share of the machine's CPU time
+//!
+//! The cap is a percentage of all cores together, as in Task Manager. It is
+//! enforced by the kernel where possible (Job Object CPU rate control on
+//! Windows, the cgroup v2 CPU controller on Linux). Otherwise the process is
+//! duty-cycled: suspended for as long as it has run over its share, then resumed.
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use tracing::{debug, info};
+
+use crate::platform;
+
+/// How often a duty-cycled process's CPU time is measured
+const TICK: Duration = Duration::from_millis(50);
+
+/// Longest single suspension, so a burst never freezes a window for long
+const MAX_SUSPEND: Duration = Duration::from_secs(1);
+
+/// A duty-cycled process
+struct Throttle {
+    percent: u8,
+    /// Paused by the user, so the throttle must not resume it
+    held: bool,
+}
+
+static THROTTLES: LazyLock<Mutex<HashMap<u32, Throttle>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Cap `pid` at `percent` of the machine's CPU time; 0 removes the cap
+pub fn set_limit(pid: u32, percent: u8) -> Result<()> {
+    match platform::set_cpu_rate(pid, percent) {
+        Ok(()) => {
+            stop(pid);
+            Ok(())
+        }
+        Err(_) if percent == 0 || percent >= 100 => {
+            stop(pid);
+            Ok(())
+        }
+        Err(e) => {
+            debug!("No kernel CPU cap for PID {}: {:#}", pid, e);
+            start(pid, percent)
+        }
+    }
+}
+
+/// Stop or restart suspending `pid` while the user has it paused
+pub fn hold(pid: u32, held: bool) {
+    if let Ok(mut throttles) = THROTTLES.lock() {
+        if let Some(throttle) = throttles.get_mut(&pid) {
+            throttle.held = held;
+        }
+    }
+}
+
+fn start(pid: u32, percent: u8) -> Result<()> {
+    let mut throttles = THROTTLES
+        .lock()
+        .map_err(|e| anyhow::anyhow!("CPU throttle lock poisoned: {}", e))?;
+    if let Some(throttle) = throttles.get_mut(&pid) {
+        throttle.percent = percent;
+        return Ok(());
+    }
+    throttles.insert(
+        pid,
+        Throttle {
+            percent,
+            held: false,
+        },
+    );
+    drop(throttles);
+
+    std::thread::spawn(move || duty_cycle(pid));
+    info!("Duty-cycling PID {} at {}% CPU", pid, percent);
+    Ok(())
+}
+
+fn stop(pid: u32) {
+    if let Ok(mut throttles) = THROTTLES.lock() {
+        throttles.remove(&pid);
+    }
+}
+
+/// Current (percent, held) for `pid`, or None once its throttle is removed
+fn throttle_of(pid: u32) -> Option<(u8, bool)> {
+    THROTTLES
+        .lock()
+        .ok()?
+        .get(&pid)
+        .map(|t| (t.percent, t.held))
+}
+
+/// Suspend and resume `pid` so that it runs no more than its share, until
+/// its throttle is removed or it exits
+fn duty_cycle(pid: u32) {
+    let cores = std::thread::available_parallelism().map_or(1, |n| n.get()) as f64;
+    let Some(mut last_cpu) = platform::process_cpu_time(pid) else {
+        stop(pid);
+        return;
+    };
+    let mut last = Instant::now();
+    // Seconds of CPU time the process may still use
+    let mut credit = 0.0_f64;
+
+    loop {
+        std::thread::sleep(TICK);
+        let Some((percent, held)) = throttle_of(pid) else {
+            return;
+        };
+        let cpu = match platform::process_cpu_time(pid) {
+            Some(cpu) if platform::is_process_running(pid) => cpu,
+            _ => {
+                stop(pid);
+                return;
+            }
+        };
+
+        // Share of one core per second of wall time
+        let share = cores * percent as f64 / 100.0;
+        let now = Instant::now();
+        credit += share * (now - last).as_secs_f64() - (cpu.saturating_sub(last_cpu)).as_secs_f64();
+        // Unused time is not saved up for a later burst
+        credit = credit.min(share * TICK.as_secs_f64());
+        last = now;
+        last_cpu = cpu;
+
+        if held {
+            credit = 0.0;
+            continue;
+        }
+        if credit >= 0.0 || share <= 0.0 {
+            continue;
+        }
+
+        let pause = Duration::from_secs_f64(-credit / share).min(MAX_SUSPEND);
+        if platform::suspend_process(pid).is_err() {
+            continue;
+        }
+        std::thread::sleep(pause);
+        // Windows counts suspensions, so ours must be undone even if the user
+        // paused meanwhile; elsewhere resuming would undo the user's pause
+        let held = throttle_of(pid).is_some_and(|(_, held)| held);
+        if cfg!(windows) || !held {
+            let _ = platform::resume_process(pid);
+        }
+        last = Instant::now();
+        last_cpu = platform::process_cpu_time(pid).unwrap_or(last_cpu);
+        credit = 0.0;
+    }
+}
@@ -0,0 +1,156 @@
+//! CPU topology - Performance and efficiency cores on hybrid CPUs
+
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::platform;
+
+/// Kind of core a logical CPU belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoreKind {
+    /// Performance core (P-core, big core)
+    Performance,
+    /// Efficiency core (E-core, LITTLE core)
+    Efficiency,
+}
+
+/// A logical processor as numbered by the OS
+#[derive(Debug, Clone)]
+pub struct LogicalCpu {
+    /// Logical processor index used in affinity masks
+    pub index: usize,
+    /// Physical core it belongs to (shared by SMT siblings)
+    pub core: usize,
+    /// Performance or efficiency core
+    pub kind: CoreKind,
+}
+
+/// Logical processors of this machine grouped by core kind
+#[derive(Debug, Clone)]
+pub struct CpuTopology {
+    pub cpus: Vec<LogicalCpu>,
+}
+
+impl CpuTopology {
+    /// Topology of this machine, detected once
+    pub fn get() -> &'static Self {
+        static TOPOLOGY: OnceLock<CpuTopology> = OnceLock::new();
+        TOPOLOGY.get_or_init(Self::detect)
+    }
+
+    fn detect() -> Self {
+        let Some(processors) = platform::cpu_efficiency_classes().filter(|p| !p.is_empty()) else {
+            let count = std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1);
+            return Self {
+                cpus: (0..count)
+                    .map(|index| LogicalCpu {
+                        index,
+                        core: index,
+                        kind: CoreKind::Performance,
+                    })
+                    .collect(),
+            };
+        };
+
+        // The highest efficiency class is the fastest; everything below it is an E-core
+        let top_class = processors.iter().map(|p| p.2).max().unwrap_or(0);
+        let cpus = processors
+            .into_iter()
+            .map(|(index, core, class)| LogicalCpu {
+                index,
+                core,
+                kind: if class == top_class {
+                    CoreKind::Performance
+                } else {
+                    CoreKind::Efficiency
+                },
+            })
+            .collect();
+        Self { cpus }
+    }
+
+    /// Whether the CPU mixes performance and efficiency cores
+    pub fn is_hybrid(&self) -> bool {
+        self.cpus.iter().any(|c| c.kind == CoreKind::Efficiency)
+            && self.cpus.iter().any(|c| c.kind == CoreKind::Performance)
+    }
+
+    /// Logical processors of one kind
+    pub fn logical_cpus(&self, kind: CoreKind) -> Vec<usize> {
+        self.cpus
+            .iter()
+            .filter(|c| c.kind == kind)
+            .map(|c| c.index)
+            .collect()
+    }
+
+    /// Physical cores of one kind, each with its logical processors
+    pub fn physical_cores(&self, kind: CoreKind) -> Vec<Vec<usize>> {
+        let mut cores: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for cpu in self.cpus.iter().filter(|c| c.kind == kind) {
+            cores.entry(cpu.core).or_default().push(cpu.index);
+        }
+        cores.into_values().collect()
+    }
+
+    /// Short description, e.g. "8 P-cores + 16 E-cores"
+    pub fn summary(&self) -> String {
+        let p_cores = self.physical_cores(CoreKind::Performance).len();
+        let e_cores = self.physical_cores(CoreKind::Efficiency).len();
+        if e_cores == 0 {
+            format!("{} cores, {} threads", p_cores, self.cpus.len())
+        } else {
+            format!("{} P-cores + {} E-cores", p_cores, e_cores)
+        }
+    }
+}
+
+/// Affinity shortcuts resolved against the CPU topology at launch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AffinityPreset {
+    /// Run on performance cores only
+    PerformanceCores,
+    /// Run on efficiency cores only
+    EfficiencyCores,
+    /// Give each launched instance its own performance core
+    OnePerformanceCoreEach,
+}
+
+impl AffinityPreset {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::PerformanceCores => "P-cores only",
+            Self::EfficiencyCores => "E-cores only",
+            Self::OnePerformanceCoreEach => "One P-core each",
+        }
+    }
+
+    pub fn all() -> &'static [Self] {
+        &[
+            Self::PerformanceCores,
+            Self::EfficiencyCores,
+            Self::OnePerformanceCoreEach,
+        ]
+    }
+
+    /// Logical processors for this preset. `slot` picks the core for
+    /// `OnePerformanceCoreEach` and wraps around when instances outnumber cores.
+    pub fn resolve(&self, topology: &CpuTopology, slot: usize) -> Vec<usize> {
+        match self {
+            Self::PerformanceCores => topology.logical_cpus(CoreKind::Performance),
+            Self::EfficiencyCores => topology.logical_cpus(CoreKind::Efficiency),
+            Self::OnePerformanceCoreEach => {
+                let cores = topology.physical_cores(CoreKind::Performance);
+                if cores.is_empty() {
+                    Vec::new()
+                } else {
+                    cores[slot % cores.len()].clone()
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Crash dialogs - Closes the dialogs that keep a crashed instance from exiting
+//!
+//! A crashed process stays alive while Windows Error Reporting or the app's
+//! own crash reporter shows its dialog, so it is never seen to crash and never
+//! auto-restarts. Dialogs whose title matches a pattern are closed instead.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+use crate::platform;
+
+/// How long a process may take to exit after its crash dialog closed before it is killed
+pub const EXIT_GRACE: Duration = Duration::from_secs(5);
+
+/// Which crash dialogs are closed automatically
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CrashDialogSettings {
+    pub enabled: bool,
+    /// Case-insensitive fragments of dialog titles; blank lines are ignored
+    pub title_patterns: Vec<String>,
+}
+
+impl Default for CrashDialogSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            title_patterns: [
+                "has stopped working",
+                "Application Error",
+                "Unhandled exception",
+                "Crash Reporter",
+                "has crashed",
+                "Fatal error",
+            ]
+            .map(String::from)
+            .to_vec(),
+        }
+    }
+}
+
+impl CrashDialogSettings {
+    /// The pattern a window title matches, if any
+    pub fn matching_pattern(&self, title: &str) -> Option<&str> {
+        let title = title.to_lowercase();
+        self.title_patterns
+            .iter()
+            .map(|p| p.trim())
+            .find(|p| !p.is_empty() && title.contains(&p.to_lowercase()))
+    }
+}
+
+/// Close the crash dialogs shown for `pid`, by the process itself or by
+/// Windows Error Reporting on its behalf. Returns the titles closed.
+pub fn dismiss(pid: u32, settings: &CrashDialogSettings) -> Result<Vec<String>> {
+    let mut owners = vec![pid];
+    owners.extend(error_reporters(pid));
+
+    let mut closed = Vec::new();
+    for owner in owners {
+        for (window, title) in platform::process_windows(owner)? {
+            if settings.matching_pattern(&title).is_some() {
+                platform::close_window(window)?;
+                closed.push(title);
+            }
+        }
+    }
+    Ok(closed)
+}
+
+/// Windows Error Reporting processes (`WerFault.exe -p <pid>`) reporting on `pid`
+fn error_reporters(pid: u32) -> Vec<u32> {
+    if !cfg!(windows) {
+        return Vec::new();
+    }
+
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::new().with_cmd(UpdateKind::Always),
+    );
+    let pid = pid.to_string();
+    system
+        .processes()
+        .iter()
+        .filter(|(_, process)| {
+            process
+                .name()
+                .to_string_lossy()
+                .eq_ignore_ascii_case("WerFault.exe")
+        })
+        .filter(|(_, process)| {
+            process
+                .cmd()
+                .windows(2)
+                .any(|args| args[0].eq_ignore_ascii_case("-p") && args[1] == pid.as_str())
+        })
+        .map(|(reporter, _)| reporter.as_u32())
+        .collect()
+}
@@ -0,0 +1,191 @@
+//! Crash reports - Diagnostics collected when an instance crashes
+//!
+//! A report holds the exit code, the last lines of the instance's log and any
+//! dumps Windows Error Reporting wrote for the process. It is saved as a text
+//! file under `logs/crashes` and summarized in the Crashed history event.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, Utc};
+
+use super::instance::{Instance, InstanceId};
+use super::output_log;
+
+/// Lines of the instance's log kept in a report
+pub const OUTPUT_LINES: usize = 50;
+
+/// What is known about one crash
+#[derive(Debug, Clone)]
+pub struct CrashReport {
+    pub name: String,
+    pub executable: PathBuf,
+    pub pid: Option<u32>,
+    pub launch_id: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub crashed_at: DateTime<Utc>,
+    pub exit_code: Option<i32>,
+    pub error: Option<String>,
+    /// Last lines of the instance's log, oldest first
+    pub output: Vec<String>,
+    /// Crash dumps and error report folders found for the process
+    pub dumps: Vec<PathBuf>,
+}
+
+impl CrashReport {
+    /// Report of an instance that just crashed, before its log and dumps are read
+    pub fn new(instance: &Instance) -> Self {
+        Self {
+            name: instance.display_name().to_string(),
+            executable: instance.config.executable_path.clone(),
+            pid: instance.pid,
+            launch_id: instance.launch_id.clone(),
+            started_at: instance.started_at,
+            crashed_at: instance.stopped_at.unwrap_or_else(Utc::now),
+            exit_code: instance.exit_code,
+            error: instance.last_error.clone(),
+            output: Vec::new(),
+            dumps: Vec::new(),
+        }
+    }
+
+    /// Add the end of the instance's log and the crash dumps of its process
+    pub fn collect(&mut self, log_path: &Path) {
+        if log_path.exists() {
+            self.output = output_log::read_tail(log_path, OUTPUT_LINES).unwrap_or_default();
+        }
+        if let Some(pid) = self.pid {
+            let since = self
+                .started_at
+                .map(SystemTime::from)
+                .unwrap_or_else(|| SystemTime::now() - Duration::from_secs(60 * 60));
+            self.dumps = crate::platform::crash_dumps(&self.executable, pid, since);
+        }
+    }
+
+    /// Exit code as usually written; Windows exception codes in hex
+    pub fn exit_code_label(&self) -> Option<String> {
+        self.exit_code.map(|code| {
+            if code < 0 {
+                format!("{:#010X}", code as u32)
+            } else {
+                code.to_string()
+            }
+        })
+    }
+
+    /// One line for the history event
+    pub fn summary(&self) -> String {
+        let mut summary = match (self.exit_code_label(), &self.error) {
+            (Some(code), _) => format!("Exit code {}", code),
+            (None, Some(error)) => error.clone(),
+            (None, None) => "Crashed".to_string(),
+        };
+        if !self.dumps.is_empty() {
+            summary.push_str(&format!(", {} crash dump(s)", self.dumps.len()));
+        }
+        summary
+    }
+
+    /// Human-readable report
+    pub fn to_text(&self) -> String {
+        let time = |t: DateTime<Utc>| {
+            t.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        };
+        let mut text = format!("Crash report for {}\n\n", self.name);
+        text.push_str(&format!("Executable: {}\n", self.executable.display()));
+        if let Some(pid) = self.pid {
+            text.push_str(&format!("PID: {}\n", pid));
+        }
+        if let Some(launch_id) = &self.launch_id {
+            text.push_str(&format!("Launch: {}\n", launch_id));
+        }
+        if let Some(started_at) = self.started_at {
+            text.push_str(&format!("Started: {}\n", time(started_at)));
+        }
+        text.push_str(&format!("Crashed: {}\n", time(self.crashed_at)));
+        if let Some(code) = self.exit_code_label() {
+            text.push_str(&format!("Exit code: {}\n", code));
+        }
+        if let Some(error) = &self.error {
+            text.push_str(&format!("Error: {}\n", error));
+        }
+
+        text.push_str("\nCrash dumps:\n");
+        if self.dumps.is_empty() {
+            text.push_str("  none found\n");
+        }
+        for dump in &self.dumps {
+            text.push_str(&format!("  {}\n", dump.display()));
+        }
+
+        text.push_str(&format!("\nLast {} log lines:\n", self.output.len()));
+        for line in &self.output {
+            text.push_str(line);
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Write the report into the crash folder of its instance, returning its path
+    pub fn save(&self, logs_dir: &Path, id: InstanceId) -> Result<PathBuf> {
+        let dir = reports_dir(logs_dir, id);
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        let path = dir.join(format!(
+            "{}.txt",
+            self.crashed_at
+                .with_timezone(&Local)
+                .format("%Y-%m-%d_%H-%M-%S")
+        ));
+        std::fs::write(&path, self.to_text())
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+/// Folder holding an instance's crash reports
+pub fn reports_dir(logs_dir: &Path, id: InstanceId) -> PathBuf {
+    logs_dir.join("crashes").join(id.0.to_string())
+}
+
+/// Newest saved report of an instance
+pub fn latest(logs_dir: &Path, id: InstanceId) -> Option<PathBuf> {
+    // Names are timestamps, so they sort by time
+    std::fs::read_dir(reports_dir(logs_dir, id))
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("txt"))
+        .max()
+}
+
+/// Delete crash reports saved more than `days` days ago
+pub fn prune(logs_dir: &Path, days: u32) -> Result<usize> {
+    let dir = logs_dir.join("crashes");
+    if !dir.exists() {
+        return Ok(0);
+    }
+    let cutoff = SystemTime::now() - Duration::from_secs(days as u64 * 24 * 60 * 60);
+
+    let mut removed = 0;
+    for instance_dir in std::fs::read_dir(&dir)?.flatten() {
+        let Ok(reports) = std::fs::read_dir(instance_dir.path()) else {
+            continue;
+        };
+        for path in reports.flatten().map(|entry| entry.path()) {
+            let stale = std::fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .is_ok_and(|modified| modified < cutoff);
+            if stale && std::fs::remove_file(&path).is_ok() {
+                removed += 1;
+            }
+        }
+        // Only succeeds once the folder is empty
+        let _ = std::fs::remove_dir(instance_dir.path());
+    }
+    Ok(removed)
+}
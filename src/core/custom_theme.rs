@@ -0,0 +1,45 @@
+//! Custom themes - User-defined color palettes
+//!
+//! A custom theme starts from the dark or light palette and overrides some of
+//! its colors. Themes are stored by name in the database and can be shared as
+//! small JSON files.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// A named set of color overrides
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CustomTheme {
+    pub name: String,
+    /// Start from the light palette instead of the dark one
+    pub light: bool,
+    /// Overridden colors by palette key (e.g. `primary`), as `#rrggbb`
+    pub colors: BTreeMap<String, String>,
+}
+
+impl CustomTheme {
+    pub fn new(name: impl Into<String>, light: bool) -> Self {
+        Self {
+            name: name.into(),
+            light,
+            colors: BTreeMap::new(),
+        }
+    }
+
+    /// Serialize as a theme file
+    pub fn export_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Read a theme file written by `export_json`
+    pub fn import_json(json: &str) -> Result<Self> {
+        let theme: Self = serde_json::from_str(json).context("Not a theme file")?;
+        if theme.name.trim().is_empty() {
+            anyhow::bail!("Theme file has no name");
+        }
+        Ok(theme)
+    }
+}
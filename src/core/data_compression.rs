@@ -0,0 +1,160 @@
+//! Data compression - Shrinks the data directories of dormant instances
+//!
+//! The data directory is packed into a zstd-compressed tar archive next to it
+//! and removed. The archive is unpacked again before the instance next launches.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use tracing::info;
+
+/// Appended to the data directory's name to name its archive
+pub const ARCHIVE_SUFFIX: &str = ".tar.zst";
+
+/// Dormant data is written once and read rarely, so favor size over speed
+const LEVEL: i32 = 9;
+
+/// Sizes of a data directory before and after compression
+#[derive(Debug, Clone, Copy)]
+pub struct Compressed {
+    pub original_bytes: u64,
+    pub compressed_bytes: u64,
+}
+
+/// Archive holding `data_dir` while it is compressed
+pub fn archive_path(data_dir: &Path) -> PathBuf {
+    with_suffix(data_dir, ARCHIVE_SUFFIX)
+}
+
+/// Size of the archive of `data_dir`, if it is compressed
+pub fn compressed_size(data_dir: &Path) -> Option<u64> {
+    std::fs::metadata(archive_path(data_dir))
+        .ok()
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+}
+
+/// Pack `data_dir` into its archive and remove it. Symlinks such as shared
+/// cache links are skipped since they are recreated on the next launch.
+pub fn compress(data_dir: &Path) -> Result<Compressed> {
+    if !data_dir.is_dir() {
+        anyhow::bail!("{} does not exist", data_dir.display());
+    }
+    let archive = archive_path(data_dir);
+    if archive.exists() {
+        anyhow::bail!("Data is already compressed");
+    }
+
+    let partial = with_suffix(&archive, ".partial");
+    let original_bytes = match write_archive(data_dir, &partial) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            let _ = std::fs::remove_file(&partial);
+            return Err(e);
+        }
+    };
+    std::fs::rename(&partial, &archive)?;
+    // The archive is complete, so a directory left half-removed is replaced on decompression
+    std::fs::remove_dir_all(data_dir)
+        .with_context(|| format!("Failed to remove {}", data_dir.display()))?;
+
+    let compressed_bytes = std::fs::metadata(&archive)?.len();
+    info!(
+        "Compressed {:?} from {} to {} bytes",
+        data_dir, original_bytes, compressed_bytes
+    );
+    Ok(Compressed {
+        original_bytes,
+        compressed_bytes,
+    })
+}
+
+/// Unpack the archive of `data_dir` back into place and remove the archive
+pub fn decompress(data_dir: &Path) -> Result<()> {
+    let archive = archive_path(data_dir);
+    let staging = with_suffix(data_dir, ".partial");
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging)?;
+    }
+
+    let unpacked = File::open(&archive)
+        .with_context(|| format!("Failed to open {}", archive.display()))
+        .and_then(|file| Ok(zstd::Decoder::new(file)?))
+        .and_then(|decoder| {
+            tar::Archive::new(decoder)
+                .unpack(&staging)
+                .context("Archive is damaged")
+        });
+    if let Err(e) = unpacked {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    // Left over from a compression that could not remove everything
+    if data_dir.exists() {
+        std::fs::remove_dir_all(data_dir)?;
+    }
+    std::fs::rename(&staging, data_dir)?;
+    std::fs::remove_file(&archive)?;
+
+    info!("Decompressed {:?}", data_dir);
+    Ok(())
+}
+
+/// Remove the archive of `data_dir`, if any
+pub fn remove(data_dir: &Path) -> Result<()> {
+    match std::fs::remove_file(archive_path(data_dir)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Write the archive, returning the number of bytes packed
+fn write_archive(data_dir: &Path, path: &Path) -> Result<u64> {
+    let file =
+        File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut builder = tar::Builder::new(zstd::Encoder::new(BufWriter::new(file), LEVEL)?);
+    let total = append_dir(&mut builder, data_dir, Path::new(""))?;
+
+    let mut writer = builder.into_inner()?.finish()?;
+    writer.flush()?;
+    writer
+        .into_inner()
+        .map_err(|e| anyhow::anyhow!("Failed to write archive: {}", e))?
+        .sync_all()?;
+    Ok(total)
+}
+
+/// Append the contents of `source` under `name`, skipping symlinks. Returns
+/// the number of bytes appended.
+pub fn append_dir<W: Write>(
+    builder: &mut tar::Builder<W>,
+    source: &Path,
+    name: &Path,
+) -> Result<u64> {
+    let mut total = 0;
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let entry_name = name.join(entry.file_name());
+
+        if file_type.is_symlink() {
+            continue;
+        } else if file_type.is_dir() {
+            builder.append_dir(&entry_name, entry.path())?;
+            total += append_dir(builder, &entry.path(), &entry_name)?;
+        } else {
+            builder.append_path_with_name(entry.path(), &entry_name)?;
+            total += entry.metadata()?.len();
+        }
+    }
+    Ok(total)
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
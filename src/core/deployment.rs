@@ -0,0 +1,181 @@
+//! Deployment - Per-machine installs, administrator defaults and shared templates
+//!
+//! A per-machine install keeps shared, read-mostly files in a machine-wide folder
+//! (`%ProgramData%\MultiInstance` on Windows) while each user's instances, logs and
+//! database stay in their own data directory.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use tracing::{info, warn};
+
+use super::profile::Profile;
+use super::settings::Settings;
+
+/// Settings inherited by users who launch MultiInstance for the first time
+const DEFAULTS_FILE: &str = "default_settings.json";
+
+/// Folder holding profile templates shared with every user
+const TEMPLATES_FOLDER: &str = "templates";
+
+/// How MultiInstance was installed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallScope {
+    /// Installed for the current user only, or run from a portable folder
+    PerUser,
+    /// Installed for all users by an administrator (MSI, pkg, distro package)
+    PerMachine,
+}
+
+impl InstallScope {
+    /// Detect the install scope from the location of the running executable
+    pub fn detect() -> Self {
+        match std::env::current_exe() {
+            Ok(exe) if is_machine_location(&exe) => Self::PerMachine,
+            _ => Self::PerUser,
+        }
+    }
+
+    /// Human-readable label
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::PerUser => "Per-user",
+            Self::PerMachine => "Per-machine",
+        }
+    }
+}
+
+/// Check whether a path lies inside a folder only administrators can write to
+fn is_machine_location(exe: &Path) -> bool {
+    #[cfg(windows)]
+    {
+        ["ProgramFiles", "ProgramFiles(x86)", "ProgramW6432"]
+            .iter()
+            .filter_map(|var| std::env::var_os(var))
+            .any(|dir| exe.starts_with(dir))
+    }
+    #[cfg(target_os = "macos")]
+    {
+        exe.starts_with("/Applications")
+    }
+    #[cfg(not(any(windows, target_os = "macos")))]
+    {
+        ["/usr", "/opt"].iter().any(|dir| exe.starts_with(dir))
+    }
+}
+
+/// Machine-wide folder for shared templates and administrator defaults
+pub fn machine_directory() -> PathBuf {
+    #[cfg(windows)]
+    {
+        std::env::var_os("ProgramData")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(r"C:\ProgramData"))
+            .join("MultiInstance")
+    }
+    #[cfg(target_os = "macos")]
+    {
+        PathBuf::from("/Library/Application Support/MultiInstance")
+    }
+    #[cfg(not(any(windows, target_os = "macos")))]
+    {
+        PathBuf::from("/etc/multiinstance")
+    }
+}
+
+/// Folder holding shared profile templates
+pub fn templates_directory() -> PathBuf {
+    machine_directory().join(TEMPLATES_FOLDER)
+}
+
+/// Load the administrator's default configuration, if one has been published
+pub fn load_default_settings() -> Option<Settings> {
+    let path = machine_directory().join(DEFAULTS_FILE);
+    let json = std::fs::read_to_string(&path).ok()?;
+
+    match serde_json::from_str::<Settings>(&json) {
+        Ok(mut settings) => {
+            settings.validate();
+            Some(settings)
+        }
+        Err(e) => {
+            warn!("Ignoring invalid default settings {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Publish settings as the default configuration for new users (needs administrator rights).
+///
+/// Per-user values such as the data directory and the PIN lock are not published.
+pub fn save_default_settings(settings: &Settings) -> Result<PathBuf> {
+    let mut defaults = settings.clone();
+    defaults.data_directory = None;
+    defaults.settings_lock = None;
+
+    let dir = machine_directory();
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create {:?} (administrator rights needed)", dir))?;
+
+    let path = dir.join(DEFAULTS_FILE);
+    std::fs::write(&path, serde_json::to_string_pretty(&defaults)?)
+        .with_context(|| format!("Failed to write {:?} (administrator rights needed)", path))?;
+
+    info!("Published default settings to {:?}", path);
+    Ok(path)
+}
+
+/// Load profile templates shared with every user of this machine
+pub fn load_shared_templates() -> Vec<Profile> {
+    let Ok(entries) = std::fs::read_dir(templates_directory()) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let json = std::fs::read_to_string(&path).ok()?;
+            match Profile::from_json(&json) {
+                Ok(profile) => Some(profile),
+                Err(e) => {
+                    warn!("Ignoring invalid profile template {:?}: {}", path, e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Share a profile with every user of this machine (needs administrator rights)
+pub fn publish_template(profile: &Profile) -> Result<PathBuf> {
+    let dir = templates_directory();
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create {:?} (administrator rights needed)", dir))?;
+
+    let path = dir.join(format!("{}.json", profile.id));
+    std::fs::write(&path, profile.to_json()?)
+        .with_context(|| format!("Failed to write {:?} (administrator rights needed)", path))?;
+
+    info!(
+        "Published profile template '{}' to {:?}",
+        profile.name, path
+    );
+    Ok(path)
+}
+
+/// Apply the "start with system" setting for the current user.
+///
+/// When an installer already starts MultiInstance for all users the per-user
+/// entry is removed so the app is not launched twice.
+pub fn sync_autostart(enabled: bool) -> Result<()> {
+    let machine_managed = crate::platform::machine_autostart_registered(crate::APP_NAME);
+    let exe = std::env::current_exe().context("Failed to locate executable")?;
+
+    crate::platform::set_autostart(
+        crate::APP_NAME,
+        &exe.to_string_lossy(),
+        enabled && !machine_managed,
+    )
+}
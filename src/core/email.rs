@@ -0,0 +1,244 @@
+//! Email alerts - Sends alerts over SMTP, rate limited for unattended machines
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use serde::{Deserialize, Serialize};
+
+use super::alerts::{Alert, AlertEvent};
+use super::instance::InstanceId;
+
+/// How long the SMTP server may take to answer
+const SMTP_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Placeholders available in the subject and body templates
+pub const TEMPLATE_HELP: &str = "{event}, {instance}, {group}, {message}, {time}, {host}";
+
+/// Connection security to the SMTP server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum SmtpSecurity {
+    /// Plain connection upgraded with STARTTLS (usually port 587)
+    #[default]
+    StartTls,
+    /// TLS from the start (usually port 465)
+    Tls,
+    /// Unencrypted, for relays on the local network
+    None,
+}
+
+impl SmtpSecurity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::StartTls => "STARTTLS",
+            Self::Tls => "TLS",
+            Self::None => "None",
+        }
+    }
+
+    pub fn all() -> &'static [SmtpSecurity] {
+        &[
+            SmtpSecurity::StartTls,
+            SmtpSecurity::Tls,
+            SmtpSecurity::None,
+        ]
+    }
+
+    /// Port the security mode usually runs on
+    pub fn default_port(&self) -> u16 {
+        match self {
+            Self::StartTls => 587,
+            Self::Tls => 465,
+            Self::None => 25,
+        }
+    }
+}
+
+/// SMTP server, recipients and templates for email alerts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmailSettings {
+    /// Send critical events (crash loops, host memory exhaustion) by email
+    /// even without a routing rule
+    pub enabled: bool,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub security: SmtpSecurity,
+    /// Empty for servers that need no login
+    pub username: String,
+    pub password: String,
+    pub from: String,
+    /// Comma-separated recipient addresses
+    pub to: String,
+    pub subject_template: String,
+    pub body_template: String,
+    /// Minimum minutes between emails for the same event and instance
+    pub repeat_interval_mins: u32,
+    /// Most emails sent in any hour (0 = unlimited)
+    pub max_per_hour: u32,
+}
+
+impl Default for EmailSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            smtp_host: String::new(),
+            smtp_port: SmtpSecurity::StartTls.default_port(),
+            security: SmtpSecurity::StartTls,
+            username: String::new(),
+            password: String::new(),
+            from: String::new(),
+            to: String::new(),
+            subject_template: "[MultiInstance] {event}: {instance} on {host}".to_string(),
+            body_template:
+                "{message}\n\nInstance: {instance}\nGroup: {group}\nHost: {host}\nTime: {time}"
+                    .to_string(),
+            repeat_interval_mins: 30,
+            max_per_hour: 10,
+        }
+    }
+}
+
+impl EmailSettings {
+    /// Host, sender and at least one recipient are filled in
+    pub fn is_configured(&self) -> bool {
+        !self.smtp_host.trim().is_empty()
+            && !self.from.trim().is_empty()
+            && self.recipients().next().is_some()
+    }
+
+    fn recipients(&self) -> impl Iterator<Item = &str> {
+        self.to
+            .split(',')
+            .map(str::trim)
+            .filter(|to| !to.is_empty())
+    }
+}
+
+/// Emails recently sent, for rate limiting
+#[derive(Default)]
+struct RateLimiter {
+    last_sent: HashMap<(AlertEvent, Option<InstanceId>), Instant>,
+    sent: VecDeque<Instant>,
+}
+
+impl RateLimiter {
+    /// Record a send if the limits allow it
+    fn allow(&mut self, settings: &EmailSettings, alert: &Alert) -> bool {
+        let now = Instant::now();
+        let hour = Duration::from_secs(3600);
+        while self.sent.front().is_some_and(|t| now - *t >= hour) {
+            self.sent.pop_front();
+        }
+        if settings.max_per_hour > 0 && self.sent.len() >= settings.max_per_hour as usize {
+            return false;
+        }
+
+        let key = (alert.event, alert.instance);
+        let repeat = Duration::from_secs(settings.repeat_interval_mins as u64 * 60);
+        if self.last_sent.get(&key).is_some_and(|t| now - *t < repeat) {
+            return false;
+        }
+
+        self.last_sent.insert(key, now);
+        self.sent.push_back(now);
+        true
+    }
+}
+
+static RATE_LIMITER: LazyLock<Mutex<RateLimiter>> =
+    LazyLock::new(|| Mutex::new(RateLimiter::default()));
+
+/// Email an alert unless the rate limits hold it back
+pub fn send_alert(settings: &EmailSettings, alert: &Alert) -> Result<()> {
+    let allowed = RATE_LIMITER
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Email rate limiter lock poisoned: {}", e))?
+        .allow(settings, alert);
+    if !allowed {
+        tracing::info!(
+            "Email for {} alert held back by rate limit",
+            alert.event.label()
+        );
+        return Ok(());
+    }
+
+    let subject = render(&settings.subject_template, alert);
+    let body = render(&settings.body_template, alert);
+    send(settings, &subject, body)
+}
+
+/// Send a test email, ignoring the rate limits
+pub fn send_test(settings: &EmailSettings) -> Result<()> {
+    send(
+        settings,
+        "[MultiInstance] Test email",
+        format!(
+            "Email alerts from MultiInstance on {} are working.",
+            host_name()
+        ),
+    )
+}
+
+fn send(settings: &EmailSettings, subject: &str, body: String) -> Result<()> {
+    if !settings.is_configured() {
+        anyhow::bail!("Email alerts need an SMTP host, a sender and a recipient");
+    }
+
+    let from: Mailbox = settings
+        .from
+        .trim()
+        .parse()
+        .with_context(|| format!("Invalid sender address '{}'", settings.from))?;
+    let mut message = Message::builder().from(from).subject(subject);
+    for to in settings.recipients() {
+        let to: Mailbox = to
+            .parse()
+            .with_context(|| format!("Invalid recipient address '{}'", to))?;
+        message = message.to(to);
+    }
+    let message = message.body(body)?;
+
+    let host = settings.smtp_host.trim();
+    let mut transport = match settings.security {
+        SmtpSecurity::StartTls => SmtpTransport::starttls_relay(host)?,
+        SmtpSecurity::Tls => SmtpTransport::relay(host)?,
+        SmtpSecurity::None => SmtpTransport::builder_dangerous(host),
+    }
+    .port(settings.smtp_port)
+    .timeout(Some(SMTP_TIMEOUT));
+    if !settings.username.is_empty() {
+        transport = transport.credentials(Credentials::new(
+            settings.username.clone(),
+            settings.password.clone(),
+        ));
+    }
+
+    transport
+        .build()
+        .send(&message)
+        .context("SMTP server rejected the email")?;
+    Ok(())
+}
+
+/// Fill in a template's placeholders
+fn render(template: &str, alert: &Alert) -> String {
+    template
+        .replace("{event}", alert.event.label())
+        .replace("{instance}", alert.instance_name.as_deref().unwrap_or("-"))
+        .replace("{group}", alert.group.as_deref().unwrap_or("-"))
+        .replace("{message}", &alert.message)
+        .replace(
+            "{time}",
+            &chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        )
+        .replace("{host}", &host_name())
+}
+
+fn host_name() -> String {
+    sysinfo::System::host_name().unwrap_or_else(|| "unknown host".to_string())
+}
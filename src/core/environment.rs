@@ -0,0 +1,296 @@
+//! Instance environment - What a spawned instance sees, and how it differs from ours now
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::instance::InstanceConfig;
+
+/// Where a variable in a spawned instance's environment came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvSource {
+    /// Inherited from MultiInstance's own environment
+    Inherited,
+    /// Set by environment isolation to point into the instance data directory
+    Isolation,
+    /// Set in the instance's configuration
+    Custom,
+}
+
+impl EnvSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Inherited => "Inherited",
+            Self::Isolation => "Isolation",
+            Self::Custom => "Custom",
+        }
+    }
+}
+
+/// One variable of a spawned instance's environment
+#[derive(Debug, Clone)]
+pub struct EnvVar {
+    pub key: String,
+    pub value: String,
+    pub source: EnvSource,
+}
+
+/// Variables environment isolation points into the data directory
+pub fn isolation_vars(data_dir: &Path) -> Vec<(&'static str, PathBuf)> {
+    #[cfg(windows)]
+    {
+        vec![
+            ("APPDATA", data_dir.join("AppData").join("Roaming")),
+            ("LOCALAPPDATA", data_dir.join("AppData").join("Local")),
+            ("USERPROFILE", data_dir.to_path_buf()),
+        ]
+    }
+    #[cfg(target_os = "macos")]
+    {
+        vec![
+            ("HOME", data_dir.to_path_buf()),
+            ("XDG_DATA_HOME", data_dir.join("Library")),
+            (
+                "XDG_CONFIG_HOME",
+                data_dir.join("Library").join("Preferences"),
+            ),
+            ("XDG_CACHE_HOME", data_dir.join("Library").join("Caches")),
+        ]
+    }
+    #[cfg(target_os = "linux")]
+    {
+        vec![
+            ("HOME", data_dir.to_path_buf()),
+            ("XDG_DATA_HOME", data_dir.join(".local").join("share")),
+            ("XDG_CONFIG_HOME", data_dir.join(".config")),
+            ("XDG_CACHE_HOME", data_dir.join(".cache")),
+        ]
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        let _ = data_dir;
+        Vec::new()
+    }
+}
+
+/// Variables the spawn sets on top of the inherited environment, in the order applied
+pub fn overrides(data_dir: &Path, config: &InstanceConfig) -> Vec<EnvVar> {
+    let mut vars = Vec::new();
+    // Note: Disable isolation for games with anti-cheat
+    if config.bypass_single_instance && config.use_environment_isolation {
+        vars.extend(
+            isolation_vars(data_dir)
+                .into_iter()
+                .map(|(key, path)| EnvVar {
+                    key: key.to_string(),
+                    value: path.to_string_lossy().to_string(),
+                    source: EnvSource::Isolation,
+                }),
+        );
+    }
+    let expanded = expand_all(&config.environment, &vars);
+    vars.extend(
+        config
+            .environment
+            .iter()
+            .zip(expanded)
+            .map(|((key, _), (value, _))| EnvVar {
+                key: key.clone(),
+                value,
+                source: EnvSource::Custom,
+            }),
+    );
+    vars
+}
+
+/// Expand references in configured variables, in order: each sees `base`, the
+/// variables before it and then our own environment. Returns every expanded
+/// value with the names it referenced that nothing defines.
+pub fn expand_all(vars: &[(String, String)], base: &[EnvVar]) -> Vec<(String, Vec<String>)> {
+    let mut known: HashMap<String, String> = base
+        .iter()
+        .map(|var| (normalize(&var.key), var.value.clone()))
+        .collect();
+    vars.iter()
+        .map(|(key, value)| {
+            let (expanded, missing) = expand(value, |name| {
+                known
+                    .get(&normalize(name))
+                    .cloned()
+                    .or_else(|| std::env::var(name).ok())
+            });
+            known.insert(normalize(key), expanded.clone());
+            (expanded, missing)
+        })
+        .collect()
+}
+
+/// Expand `$NAME` and `${NAME}` references, and `%NAME%` on Windows. Unknown
+/// references are kept as written and returned; `$$` is a literal `$`.
+pub fn expand(value: &str, lookup: impl Fn(&str) -> Option<String>) -> (String, Vec<String>) {
+    let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut out = String::with_capacity(value.len());
+    let mut missing = Vec::new();
+    let mut rest = value;
+
+    while let Some(pos) = rest.find(['$', '%']) {
+        out.push_str(&rest[..pos]);
+        let tail = &rest[pos..];
+
+        // Reference syntax, name, and length of the whole reference
+        let reference = if let Some(after) = tail.strip_prefix("$$") {
+            out.push('$');
+            rest = after;
+            continue;
+        } else if let Some(braced) = tail.strip_prefix("${") {
+            braced.find('}').map(|end| (&braced[..end], end + 3))
+        } else if let Some(plain) = tail.strip_prefix('$') {
+            let end = plain.find(|c| !is_name_char(c)).unwrap_or(plain.len());
+            (end > 0).then(|| (&plain[..end], end + 1))
+        } else if cfg!(windows) {
+            let inner = &tail[1..];
+            inner
+                .find('%')
+                .filter(|end| *end > 0 && inner[..*end].chars().all(|c| c != ' '))
+                .map(|end| (&inner[..end], end + 2))
+        } else {
+            None
+        };
+
+        match reference {
+            Some((name, len)) => {
+                match lookup(name) {
+                    Some(found) => out.push_str(&found),
+                    None => {
+                        out.push_str(&tail[..len]);
+                        if !missing.iter().any(|m| m == name) {
+                            missing.push(name.to_string());
+                        }
+                    }
+                }
+                rest = &tail[len..];
+            }
+            None => {
+                out.push_str(&tail[..1]);
+                rest = &tail[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    (out, missing)
+}
+
+/// First problem with a list of configured variables, if any
+pub fn validate(vars: &[(String, String)]) -> Option<String> {
+    let mut seen = HashSet::new();
+    for (key, _) in vars {
+        let key = key.trim();
+        if key.is_empty() {
+            return Some("A variable has no name".to_string());
+        }
+        if key.contains('=') || key.contains('\0') {
+            return Some(format!("'{}' is not a valid variable name", key));
+        }
+        if !seen.insert(normalize(key)) {
+            return Some(format!("'{}' is set more than once", key));
+        }
+    }
+    None
+}
+
+/// Whether `key` is set more than once in `vars`
+pub fn is_duplicate(vars: &[(String, String)], key: &str) -> bool {
+    let key = normalize(key.trim());
+    vars.iter()
+        .filter(|(other, _)| normalize(other.trim()) == key)
+        .count()
+        > 1
+}
+
+/// Full environment an instance starts with: ours, with its overrides applied
+pub fn resolve(data_dir: &Path, config: &InstanceConfig) -> Vec<EnvVar> {
+    let mut resolved: BTreeMap<String, EnvVar> = std::env::vars_os()
+        .map(|(key, value)| EnvVar {
+            key: key.to_string_lossy().to_string(),
+            value: value.to_string_lossy().to_string(),
+            source: EnvSource::Inherited,
+        })
+        .map(|var| (normalize(&var.key), var))
+        .collect();
+    for var in overrides(data_dir, config) {
+        resolved.insert(normalize(&var.key), var);
+    }
+    resolved.into_values().collect()
+}
+
+/// How a variable compares between launch time and now
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvChange {
+    /// Same value now as at launch
+    Unchanged,
+    /// Set by isolation or the instance config, so ours never applied
+    Overridden,
+    /// Our value changed since launch
+    Changed,
+    /// Set in our environment since launch
+    Added,
+    /// No longer set in our environment
+    Removed,
+}
+
+/// One row of the environment diff
+#[derive(Debug, Clone)]
+pub struct EnvDiff {
+    pub key: String,
+    /// Value the instance was launched with
+    pub spawned: Option<EnvVar>,
+    /// Value in MultiInstance's environment now
+    pub current: Option<String>,
+    pub change: EnvChange,
+}
+
+/// Compare a launch-time environment with MultiInstance's current one
+pub fn diff(spawned: &[EnvVar]) -> Vec<EnvDiff> {
+    let mut current: HashMap<String, (String, String)> = std::env::vars_os()
+        .map(|(key, value)| {
+            let key = key.to_string_lossy().to_string();
+            (normalize(&key), (key, value.to_string_lossy().to_string()))
+        })
+        .collect();
+
+    let mut rows: Vec<EnvDiff> = spawned
+        .iter()
+        .map(|var| {
+            let now = current.remove(&normalize(&var.key)).map(|(_, value)| value);
+            let change = match (&now, var.source) {
+                (_, EnvSource::Isolation | EnvSource::Custom) => EnvChange::Overridden,
+                (None, EnvSource::Inherited) => EnvChange::Removed,
+                (Some(value), EnvSource::Inherited) if *value != var.value => EnvChange::Changed,
+                (Some(_), EnvSource::Inherited) => EnvChange::Unchanged,
+            };
+            EnvDiff {
+                key: var.key.clone(),
+                spawned: Some(var.clone()),
+                current: now,
+                change,
+            }
+        })
+        .collect();
+
+    rows.extend(current.into_values().map(|(key, value)| EnvDiff {
+        key,
+        spawned: None,
+        current: Some(value),
+        change: EnvChange::Added,
+    }));
+    rows.sort_by_key(|row| normalize(&row.key));
+    rows
+}
+
+/// Variable names are case-insensitive on Windows
+fn normalize(key: &str) -> String {
+    if cfg!(windows) {
+        key.to_uppercase()
+    } else {
+        key.to_string()
+    }
+}
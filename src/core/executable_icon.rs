@@ -0,0 +1,37 @@
+//! Executable icons - The shell icon of an instance's executable or app bundle
+//!
+//! Icons are extracted once per executable, scaled down and kept in the
+//! database as PNG, so cards can show them without asking the shell again.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use image::RgbaImage;
+
+/// Largest edge an icon is stored at, in pixels
+pub const ICON_SIZE: u32 = 64;
+
+/// Extract an executable's icon, returning it with its PNG encoding for the database
+pub fn extract(path: &Path) -> Result<(RgbaImage, Vec<u8>)> {
+    let icon = crate::platform::executable_icon(path)?;
+    let icon = if icon.width() > ICON_SIZE || icon.height() > ICON_SIZE {
+        image::DynamicImage::ImageRgba8(icon)
+            .thumbnail(ICON_SIZE, ICON_SIZE)
+            .to_rgba8()
+    } else {
+        icon
+    };
+    let mut png = Vec::new();
+    icon.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .context("Failed to encode icon")?;
+    Ok((icon, png))
+}
+
+/// Decode an icon stored by `extract`
+pub fn decode(png: &[u8]) -> Result<RgbaImage> {
+    Ok(
+        image::load_from_memory_with_format(png, image::ImageFormat::Png)
+            .context("Failed to decode icon")?
+            .to_rgba8(),
+    )
+}
@@ -0,0 +1,150 @@
+//! Health probe - A user command whose exit code decides an instance's health
+
+use std::io::Read;
+use std::path::Path;
+use std::process::{Child, Stdio};
+use std::time::{Duration, Instant};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use super::environment::EnvVar;
+use super::process::shell_command;
+use super::status_detection::{Health, HealthCheck};
+
+/// Longest probe output kept for the details dialog
+const MAX_OUTPUT_CHARS: usize = 500;
+
+/// Command run during health checks; exit code 0 means healthy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthProbe {
+    /// Shell command; `{pid}` is replaced with the process ID
+    pub command: String,
+    /// Seconds before an unfinished probe is killed and counts as unhealthy
+    pub timeout_secs: u32,
+}
+
+impl Default for HealthProbe {
+    fn default() -> Self {
+        Self {
+            command: String::new(),
+            timeout_secs: 10,
+        }
+    }
+}
+
+impl HealthProbe {
+    pub fn is_set(&self) -> bool {
+        !self.command.trim().is_empty()
+    }
+
+    /// Run the probe in the instance's working directory and environment
+    pub fn check(
+        &self,
+        pid: u32,
+        working_dir: Option<&Path>,
+        environment: &[EnvVar],
+    ) -> HealthCheck {
+        let command = self.command.replace("{pid}", &pid.to_string());
+        let mut cmd = shell_command(&command);
+        if let Some(dir) = working_dir {
+            cmd.current_dir(dir);
+        }
+        if !environment.is_empty() {
+            cmd.env_clear()
+                .envs(environment.iter().map(|v| (&v.key, &v.value)));
+        }
+
+        let (health, output) = match cmd
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+        {
+            Ok(child) => self.wait(child),
+            Err(e) => (
+                Health::Unknown(format!("Failed to run probe: {}", e)),
+                String::new(),
+            ),
+        };
+        HealthCheck {
+            health,
+            text: String::new(),
+            output,
+            checked_at: Local::now(),
+        }
+    }
+
+    /// Wait for the probe within its timeout, collecting what it printed
+    fn wait(&self, mut child: Child) -> (Health, String) {
+        // Read on separate threads so a chatty probe cannot fill the pipe and stall
+        let readers: Vec<_> = [
+            child
+                .stdout
+                .take()
+                .map(|s| Box::new(s) as Box<dyn Read + Send>),
+            child
+                .stderr
+                .take()
+                .map(|s| Box::new(s) as Box<dyn Read + Send>),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|mut pipe| {
+            std::thread::spawn(move || {
+                let mut text = String::new();
+                let _ = pipe.read_to_string(&mut text);
+                text
+            })
+        })
+        .collect();
+
+        let timeout = Duration::from_secs(self.timeout_secs.max(1) as u64);
+        let deadline = Instant::now() + timeout;
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break Ok(status),
+                Ok(None) if Instant::now() >= deadline => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    break Err(format!("Probe timed out after {}s", timeout.as_secs()));
+                }
+                Ok(None) => std::thread::sleep(Duration::from_millis(100)),
+                Err(e) => break Err(format!("Failed to wait for probe: {}", e)),
+            }
+        };
+
+        // After a timeout a grandchild of the shell may still hold the pipes open,
+        // so leave the readers behind rather than wait for them
+        let output: String = match status {
+            Ok(_) => readers
+                .into_iter()
+                .filter_map(|reader| reader.join().ok())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Err(_) => String::new(),
+        };
+        let output = output.trim();
+        let output = match output.char_indices().nth(MAX_OUTPUT_CHARS) {
+            Some((end, _)) => format!("{}…", &output[..end]),
+            None => output.to_string(),
+        };
+
+        let health = match status {
+            Ok(status) if status.success() => Health::Healthy,
+            Ok(status) => {
+                let code = status
+                    .code()
+                    .map_or_else(|| "a signal".to_string(), |c| format!("code {}", c));
+                match output.lines().next().filter(|l| !l.is_empty()) {
+                    Some(line) => {
+                        Health::Unhealthy(format!("Probe exited with {}: {}", code, line))
+                    }
+                    None => Health::Unhealthy(format!("Probe exited with {}", code)),
+                }
+            }
+            Err(reason) => Health::Unhealthy(reason),
+        };
+        (health, output)
+    }
+}
@@ -0,0 +1,164 @@
+//! Instance history - Lifecycle events recorded for the History panel
+
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+
+use super::instance::InstanceId;
+
+/// Rows loaded per page of the History panel
+pub const PAGE_SIZE: usize = 100;
+
+/// What happened to an instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HistoryEvent {
+    Started,
+    Stopped,
+    Killed,
+    Paused,
+    Resumed,
+    /// Exited on its own with a success code
+    Exited,
+    Crashed,
+    /// Started again by auto-restart after crashing
+    Restarted,
+}
+
+impl HistoryEvent {
+    /// Value stored in the `event_type` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Started => "started",
+            Self::Stopped => "stopped",
+            Self::Killed => "killed",
+            Self::Paused => "paused",
+            Self::Resumed => "resumed",
+            Self::Exited => "exited",
+            Self::Crashed => "crashed",
+            Self::Restarted => "restarted",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::all().into_iter().find(|e| e.as_str() == value)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Started => "Started",
+            Self::Stopped => "Stopped",
+            Self::Killed => "Killed",
+            Self::Paused => "Paused",
+            Self::Resumed => "Resumed",
+            Self::Exited => "Exited",
+            Self::Crashed => "Crashed",
+            Self::Restarted => "Auto-restarted",
+        }
+    }
+
+    pub fn all() -> [Self; 8] {
+        [
+            Self::Started,
+            Self::Stopped,
+            Self::Killed,
+            Self::Paused,
+            Self::Resumed,
+            Self::Exited,
+            Self::Crashed,
+            Self::Restarted,
+        ]
+    }
+}
+
+/// One row of the instance history
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    /// Row ID, used to page through events with the same timestamp
+    pub id: i64,
+    pub instance_id: InstanceId,
+    /// Raw event type; rows written by other versions may not parse
+    pub event_type: String,
+    pub time: DateTime<Utc>,
+    pub details: Option<String>,
+    /// Correlation ID of the launch the event belongs to
+    pub launch_id: Option<String>,
+}
+
+impl HistoryEntry {
+    pub fn event(&self) -> Option<HistoryEvent> {
+        HistoryEvent::parse(&self.event_type)
+    }
+
+    pub fn event_label(&self) -> &str {
+        self.event().map_or(self.event_type.as_str(), |e| e.label())
+    }
+}
+
+/// Which history rows to show
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HistoryFilter {
+    /// Local dates, both inclusive; None = all time
+    pub dates: Option<(NaiveDate, NaiveDate)>,
+    /// Event types to include; empty = all
+    pub events: Vec<HistoryEvent>,
+    pub instance: Option<InstanceId>,
+    /// Only events of these instances, e.g. a group's; empty = all
+    pub instances: Vec<InstanceId>,
+    /// Only events of this launch
+    pub launch_id: Option<String>,
+}
+
+impl HistoryFilter {
+    /// UTC bounds of the date range, as `[since, until)`
+    pub fn time_bounds(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let (from, to) = self.dates?;
+        let (from, to) = if from <= to { (from, to) } else { (to, from) };
+        let start = |date: NaiveDate| {
+            Local
+                .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+                .earliest()
+                .map(|t| t.with_timezone(&Utc))
+        };
+        let since = start(from)?;
+        let until = start(to.succ_opt()?)?;
+        Some((since, until))
+    }
+}
+
+/// Write history rows to a CSV file, naming instances with `name_of`
+pub fn export_csv(
+    entries: &[HistoryEntry],
+    name_of: impl Fn(InstanceId) -> String,
+    path: &Path,
+) -> Result<()> {
+    let mut file = std::io::BufWriter::new(
+        std::fs::File::create(path)
+            .with_context(|| format!("Failed to create {}", path.display()))?,
+    );
+    writeln!(file, "time,instance,instance_id,launch_id,event,details")?;
+    for entry in entries {
+        writeln!(
+            file,
+            "{},{},{},{},{},{}",
+            entry.time.with_timezone(&Local).to_rfc3339(),
+            csv_field(&name_of(entry.instance_id)),
+            entry.instance_id,
+            csv_field(entry.launch_id.as_deref().unwrap_or("")),
+            csv_field(&entry.event_type),
+            csv_field(entry.details.as_deref().unwrap_or("")),
+        )?;
+    }
+    file.flush()?;
+    Ok(())
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
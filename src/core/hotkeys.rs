@@ -0,0 +1,162 @@
+//! Global hotkeys - Keyboard shortcuts that work while MultiInstance is not focused
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use anyhow::Result;
+use global_hotkey::hotkey::HotKey;
+use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+
+use super::app_state::AppState;
+use super::profile::ProfileId;
+
+/// What a hotkey does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HotkeyAction {
+    LaunchProfile(ProfileId),
+    PauseAll,
+    ResumeAll,
+    /// Kill every running instance without waiting for a graceful stop
+    EmergencyStop,
+    /// Show running instances to pick one to bring to the front
+    QuickSwitcher,
+    /// Start or stop mirroring input to the broadcast group
+    ToggleBroadcast,
+}
+
+impl HotkeyAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::LaunchProfile(_) => "Launch profile",
+            Self::PauseAll => "Pause all",
+            Self::ResumeAll => "Resume all",
+            Self::EmergencyStop => "Emergency stop all",
+            Self::QuickSwitcher => "Switch instance",
+            Self::ToggleBroadcast => "Toggle input broadcasting",
+        }
+    }
+}
+
+/// A shortcut such as `Ctrl+Alt+F12` and the action it triggers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HotkeyBinding {
+    pub shortcut: String,
+    pub action: HotkeyAction,
+}
+
+/// Parse a shortcut, explaining what is wrong with it
+pub fn parse(shortcut: &str) -> Result<HotKey> {
+    shortcut
+        .parse::<HotKey>()
+        .map_err(|e| anyhow::anyhow!("'{}' is not a valid shortcut: {}", shortcut, e))
+}
+
+/// Registers the bindings in settings with the OS and runs their actions.
+/// Must be created on the UI thread, which pumps the messages hotkeys arrive in.
+pub struct HotkeyService {
+    manager: GlobalHotKeyManager,
+    /// Registered hotkey IDs and their actions, shared with the event handler
+    actions: Arc<RwLock<HashMap<u32, HotkeyAction>>>,
+    registered: Vec<HotKey>,
+    /// Bindings last applied, to skip re-registering unchanged ones
+    applied: Vec<HotkeyBinding>,
+}
+
+impl HotkeyService {
+    pub fn new(state: AppState, ctx: egui::Context) -> Result<Self> {
+        let manager = GlobalHotKeyManager::new()
+            .map_err(|e| anyhow::anyhow!("Failed to start global hotkeys: {}", e))?;
+        let actions: Arc<RwLock<HashMap<u32, HotkeyAction>>> = Arc::default();
+
+        // Actions run as soon as the key is pressed rather than on the next
+        // frame, which may be a long way off while the window is minimized
+        let handler_actions = Arc::clone(&actions);
+        GlobalHotKeyEvent::set_event_handler(Some(move |event: GlobalHotKeyEvent| {
+            if event.state() != HotKeyState::Pressed {
+                return;
+            }
+            let action = handler_actions
+                .read()
+                .ok()
+                .and_then(|a| a.get(&event.id()).copied());
+            if let Some(action) = action {
+                let state = state.clone();
+                let ctx = ctx.clone();
+                std::thread::spawn(move || {
+                    run(&state, action);
+                    ctx.request_repaint();
+                });
+            }
+        }));
+
+        Ok(Self {
+            manager,
+            actions,
+            registered: Vec::new(),
+            applied: Vec::new(),
+        })
+    }
+
+    /// Register `bindings` in place of the previous ones. Returns the problem
+    /// with each binding that could not be registered, by shortcut.
+    pub fn apply(&mut self, bindings: &[HotkeyBinding]) -> Option<HashMap<String, String>> {
+        if bindings == self.applied {
+            return None;
+        }
+
+        if let Err(e) = self.manager.unregister_all(&self.registered) {
+            warn!("Failed to unregister hotkeys: {}", e);
+        }
+        self.registered.clear();
+
+        let mut actions = HashMap::new();
+        let mut errors = HashMap::new();
+        for binding in bindings {
+            let result = parse(&binding.shortcut).and_then(|hotkey| {
+                if actions.contains_key(&hotkey.id()) {
+                    anyhow::bail!("Bound to more than one action");
+                }
+                self.manager
+                    .register(hotkey)
+                    .map_err(|e| anyhow::anyhow!("{}", e))?;
+                Ok(hotkey)
+            });
+            match result {
+                Ok(hotkey) => {
+                    actions.insert(hotkey.id(), binding.action);
+                    self.registered.push(hotkey);
+                }
+                Err(e) => {
+                    errors.insert(binding.shortcut.clone(), e.to_string());
+                }
+            }
+        }
+        info!("Registered {} global hotkeys", self.registered.len());
+
+        if let Ok(mut current) = self.actions.write() {
+            *current = actions;
+        }
+        self.applied = bindings.to_vec();
+        Some(errors)
+    }
+}
+
+fn run(state: &AppState, action: HotkeyAction) {
+    info!("Hotkey: {}", action.label());
+    let result = match action {
+        HotkeyAction::LaunchProfile(id) => state.launch_profile(id).map(|_| ()),
+        HotkeyAction::PauseAll => state.pause_all(),
+        HotkeyAction::ResumeAll => state.resume_all(),
+        HotkeyAction::EmergencyStop => state.kill_all(),
+        HotkeyAction::QuickSwitcher => {
+            state.request_switcher();
+            Ok(())
+        }
+        HotkeyAction::ToggleBroadcast => state.toggle_broadcast().map(|_| ()),
+    };
+    if let Err(e) = result {
+        error!("Hotkey action '{}' failed: {}", action.label(), e);
+    }
+}
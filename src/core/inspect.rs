@@ -0,0 +1,100 @@
+//! Process inspection - On-demand snapshots of what a running instance holds open
+
+use chrono::{DateTime, Local};
+
+/// What kind of object an open handle refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandleKind {
+    File,
+    Directory,
+    Socket,
+    Pipe,
+    /// Devices, anonymous inodes and anything else
+    Other,
+}
+
+impl HandleKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::File => "File",
+            Self::Directory => "Directory",
+            Self::Socket => "Socket",
+            Self::Pipe => "Pipe",
+            Self::Other => "Other",
+        }
+    }
+}
+
+/// A file, socket or pipe held open by a process
+#[derive(Debug, Clone)]
+pub struct OpenHandle {
+    pub kind: HandleKind,
+    /// Path, address or kernel description of the object
+    pub name: String,
+}
+
+/// A TCP or UDP socket owned by a process
+#[derive(Debug, Clone)]
+pub struct Connection {
+    /// `TCP`, `TCP6`, `UDP` or `UDP6`
+    pub protocol: &'static str,
+    pub local: String,
+    /// Remote endpoint; None for listening and unconnected sockets
+    pub remote: Option<String>,
+    /// TCP state, empty for UDP
+    pub state: String,
+    /// Bytes received over the connection's lifetime, where the platform reports it
+    pub bytes_in: Option<u64>,
+    /// Bytes sent over the connection's lifetime, where the platform reports it
+    pub bytes_out: Option<u64>,
+    /// Receive rate in bytes/sec since the previous snapshot
+    pub rx_rate: Option<u64>,
+    /// Send rate in bytes/sec since the previous snapshot
+    pub tx_rate: Option<u64>,
+}
+
+impl Connection {
+    fn same_socket(&self, other: &Connection) -> bool {
+        self.protocol == other.protocol && self.local == other.local && self.remote == other.remote
+    }
+
+    /// Work out throughput from the byte counters of an earlier snapshot
+    pub fn fill_rates(current: &mut [Connection], previous: &[Connection], elapsed_secs: f64) {
+        if elapsed_secs <= 0.0 {
+            return;
+        }
+        let rate = |now: Option<u64>, before: Option<u64>| {
+            Some((now?.saturating_sub(before?) as f64 / elapsed_secs) as u64)
+        };
+        for connection in current.iter_mut() {
+            if let Some(before) = previous.iter().find(|p| p.same_socket(connection)) {
+                connection.rx_rate = rate(connection.bytes_in, before.bytes_in);
+                connection.tx_rate = rate(connection.bytes_out, before.bytes_out);
+            }
+        }
+    }
+}
+
+/// Result of an inspection that runs on a background thread
+#[derive(Debug, Clone)]
+pub enum Inspection<T> {
+    Loading,
+    Ready {
+        items: Vec<T>,
+        taken_at: DateTime<Local>,
+    },
+    Failed(String),
+}
+
+impl<T> Inspection<T> {
+    /// Wrap the outcome of a finished inspection
+    pub fn finished(result: anyhow::Result<Vec<T>>) -> Self {
+        match result {
+            Ok(items) => Self::Ready {
+                items,
+                taken_at: Local::now(),
+            },
+            Err(e) => Self::Failed(e.to_string()),
+        }
+    }
+}
@@ -2,10 +2,25 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use uuid::Uuid;
 
-use super::resource::{ResourceLimits, ResourceUsage};
+use super::app_container::{AppContainerCapability, IsolationLevel};
+use super::architecture::LaunchArchitecture;
+use super::environment::EnvVar;
+use super::health_probe::HealthProbe;
+use super::isolation_check::IsolationCheck;
+use super::limit_schedule::LimitWindow;
+use super::macros::Macro;
+use super::network_condition::NetworkCondition;
+use super::packaged_apps;
+use super::profile::ProfileId;
+use super::resource::{format_duration_secs, ResourceLimits, ResourceUsage};
+use super::status_detection::{HealthCheck, StatusDetection};
+use super::virtual_desktop::VirtualDesktop;
+use super::window_capture::CaptureRegion;
 
 /// Unique identifier for an instance
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -32,6 +47,8 @@ impl std::fmt::Display for InstanceId {
 /// Status of an instance
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum InstanceStatus {
+    /// Instance is waiting for its network condition before launching
+    Pending,
     /// Instance is starting up
     Starting,
     /// Instance is running normally
@@ -55,18 +72,20 @@ impl InstanceStatus {
 
     pub fn color(&self) -> egui::Color32 {
         match self {
+            Self::Pending => egui::Color32::from_rgb(168, 85, 247), // Purple
             Self::Starting => egui::Color32::from_rgb(251, 191, 36), // Yellow
-            Self::Running => egui::Color32::from_rgb(34, 197, 94),   // Green
-            Self::Paused => egui::Color32::from_rgb(59, 130, 246),   // Blue
+            Self::Running => egui::Color32::from_rgb(34, 197, 94),  // Green
+            Self::Paused => egui::Color32::from_rgb(59, 130, 246),  // Blue
             Self::Stopping => egui::Color32::from_rgb(251, 146, 60), // Orange
             Self::Stopped => egui::Color32::from_rgb(156, 163, 175), // Gray
-            Self::Crashed => egui::Color32::from_rgb(239, 68, 68),   // Red
+            Self::Crashed => egui::Color32::from_rgb(239, 68, 68),  // Red
             Self::Unknown => egui::Color32::from_rgb(107, 114, 128), // Dark gray
         }
     }
 
     pub fn label(&self) -> &'static str {
         match self {
+            Self::Pending => "Waiting for network",
             Self::Starting => "Starting",
             Self::Running => "Running",
             Self::Paused => "Paused",
@@ -78,6 +97,42 @@ impl InstanceStatus {
     }
 }
 
+/// How an instance's main window is shown once it appears
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum WindowState {
+    #[default]
+    Normal,
+    Minimized,
+    /// Not shown at all, until shown again from the instance's menu
+    Hidden,
+}
+
+impl WindowState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Normal => "Normal",
+            Self::Minimized => "Minimized",
+            Self::Hidden => "Hidden",
+        }
+    }
+
+    pub fn all() -> &'static [WindowState] {
+        &[
+            WindowState::Normal,
+            WindowState::Minimized,
+            WindowState::Hidden,
+        ]
+    }
+}
+
+/// Named set of extra arguments, e.g. "EU server", picked at launch time
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArgumentVariant {
+    pub name: String,
+    /// Appended after the instance's base arguments
+    pub arguments: Vec<String>,
+}
+
 /// Configuration for launching an instance
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstanceConfig {
@@ -93,6 +148,9 @@ pub struct InstanceConfig {
     pub environment: Vec<(String, String)>,
     /// Resource limits for this instance
     pub resource_limits: ResourceLimits,
+    /// Time windows whose limits replace `resource_limits` while they are open
+    #[serde(default)]
+    pub limit_schedule: Vec<LimitWindow>,
     /// Custom data directory for isolation
     pub data_directory: Option<PathBuf>,
     /// Whether to bypass single-instance checks
@@ -101,6 +159,12 @@ pub struct InstanceConfig {
     /// Disable this for games with anti-cheat
     #[serde(default)]
     pub use_environment_isolation: bool,
+    /// Whether to also sandbox the instance in an AppContainer (Windows)
+    #[serde(default)]
+    pub isolation_level: IsolationLevel,
+    /// What the AppContainer may reach besides the instance's own folders
+    #[serde(default = "AppContainerCapability::defaults")]
+    pub app_container_capabilities: Vec<AppContainerCapability>,
     /// Group/category for organization
     pub group: Option<String>,
     /// Custom icon path
@@ -114,6 +178,76 @@ pub struct InstanceConfig {
     /// Hide instance window from taskbar
     #[serde(default)]
     pub hide_from_taskbar: bool,
+    /// Launch without taking focus from the window in front
+    #[serde(default)]
+    pub launch_in_background: bool,
+    /// Window state applied once the main window appears
+    #[serde(default)]
+    pub start_window_state: WindowState,
+    /// Virtual desktop the windows are moved to once they appear (Windows)
+    #[serde(default)]
+    pub virtual_desktop: Option<VirtualDesktop>,
+    /// Append the instance name to the window titles, e.g. "Discord — Work"
+    #[serde(default)]
+    pub rename_windows: bool,
+    /// Cache folders (relative to the data directory) linked to a shared store
+    #[serde(default)]
+    pub shared_cache_patterns: Vec<String>,
+    /// Slice of a universal binary to launch (macOS)
+    #[serde(default)]
+    pub launch_architecture: LaunchArchitecture,
+    /// Command run for a graceful shutdown before terminating the process
+    /// (`{pid}` is replaced with the process ID, empty = none)
+    #[serde(default)]
+    pub stop_command: String,
+    /// Seconds to wait for the instance to exit after the stop command
+    #[serde(default = "default_stop_timeout")]
+    pub stop_timeout_secs: u32,
+    /// Write stdout and stderr to a rotating log instead of discarding them
+    #[serde(default)]
+    pub capture_output: bool,
+    /// TCP ports the instance listens on; two instances cannot share one
+    #[serde(default)]
+    pub ports: Vec<u16>,
+    /// Devices the instance needs exclusive access to (e.g. COM3, /dev/ttyUSB0)
+    #[serde(default)]
+    pub exclusive_devices: Vec<String>,
+    /// Stop the instance this many minutes after each launch (0 = no limit)
+    #[serde(default)]
+    pub run_limit_mins: u32,
+    /// Network state to wait for before launching
+    #[serde(default)]
+    pub network_condition: Option<NetworkCondition>,
+    /// Free-form labels
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Values of the custom metadata fields defined in settings, by field name
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+    /// How often to sample this instance's resource usage (0 = global monitor interval)
+    #[serde(default)]
+    pub monitor_interval_ms: u32,
+    /// Part of the window to capture periodically and show on the dashboard
+    #[serde(default)]
+    pub pinned_region: Option<CaptureRegion>,
+    /// On-screen text rules read with OCR during health checks
+    #[serde(default)]
+    pub status_detection: Option<StatusDetection>,
+    /// Input sequences that can be replayed into the instance's window
+    #[serde(default)]
+    pub macros: Vec<Macro>,
+    /// Alternative argument sets selectable from the card
+    #[serde(default)]
+    pub argument_variants: Vec<ArgumentVariant>,
+    /// Name of the variant used for the next launch (None = base arguments only)
+    #[serde(default)]
+    pub active_variant: Option<String>,
+    /// Left running by "Stop all except pinned"
+    #[serde(default)]
+    pub pinned: bool,
+    /// Command whose exit code decides health during health checks
+    #[serde(default)]
+    pub health_probe: Option<HealthProbe>,
 }
 
 #[allow(dead_code)]
@@ -121,6 +255,10 @@ fn default_true() -> bool {
     true
 }
 
+fn default_stop_timeout() -> u32 {
+    10
+}
+
 impl Default for InstanceConfig {
     fn default() -> Self {
         Self {
@@ -130,15 +268,41 @@ impl Default for InstanceConfig {
             working_directory: None,
             environment: Vec::new(),
             resource_limits: ResourceLimits::default(),
+            limit_schedule: Vec::new(),
             data_directory: None,
             bypass_single_instance: true,
             use_environment_isolation: false, // Default OFF for compatibility with anti-cheat
+            isolation_level: IsolationLevel::default(),
+            app_container_capabilities: AppContainerCapability::defaults(),
             group: None,
             icon_path: None,
             notes: String::new(),
             auto_restart: false,
             restart_delay_secs: 5,
             hide_from_taskbar: false,
+            launch_in_background: false,
+            start_window_state: WindowState::Normal,
+            virtual_desktop: None,
+            rename_windows: false,
+            shared_cache_patterns: Vec::new(),
+            launch_architecture: LaunchArchitecture::Native,
+            stop_command: String::new(),
+            stop_timeout_secs: default_stop_timeout(),
+            capture_output: false,
+            ports: Vec::new(),
+            exclusive_devices: Vec::new(),
+            run_limit_mins: 0,
+            network_condition: None,
+            tags: Vec::new(),
+            metadata: BTreeMap::new(),
+            monitor_interval_ms: 0,
+            pinned_region: None,
+            status_detection: None,
+            macros: Vec::new(),
+            argument_variants: Vec::new(),
+            active_variant: None,
+            pinned: false,
+            health_probe: None,
         }
     }
 }
@@ -157,6 +321,75 @@ impl InstanceConfig {
         self
     }
 
+    /// The selected argument variant, if it still exists
+    pub fn variant(&self) -> Option<&ArgumentVariant> {
+        let name = self.active_variant.as_ref()?;
+        self.argument_variants.iter().find(|v| &v.name == name)
+    }
+
+    /// Base arguments followed by those of the selected variant
+    pub fn launch_arguments(&self) -> Vec<String> {
+        let mut args = self.arguments.clone();
+        if let Some(variant) = self.variant() {
+            args.extend(variant.arguments.iter().cloned());
+        }
+        args
+    }
+
+    /// The Store app launched and the arguments passed to it, whether given as
+    /// `shell:AppsFolder\<AUMID>` directly or through explorer.exe
+    pub fn packaged_app(&self) -> Option<(String, Vec<String>)> {
+        let mut args = self.launch_arguments();
+        if let Some(id) = packaged_apps::app_id(&self.executable_path.to_string_lossy()) {
+            return Some((id.to_string(), args));
+        }
+        let is_explorer = self
+            .executable_path
+            .file_name()
+            .is_some_and(|name| name.eq_ignore_ascii_case("explorer.exe"));
+        let id = packaged_apps::app_id(args.first().filter(|_| is_explorer)?)?.to_string();
+        args.remove(0);
+        Some((id, args))
+    }
+
+    /// Whether there is something to launch; Store apps are checked on activation
+    pub fn executable_exists(&self) -> bool {
+        self.packaged_app().is_some() || self.executable_path.exists()
+    }
+
+    /// Take the settings that apply while the instance runs from `other`:
+    /// labels, window titles, stop behavior, macros, the CPU cap, the limit
+    /// schedule and the next launch's variant
+    pub fn apply_live_settings(&mut self, other: &InstanceConfig) {
+        self.name = other.name.clone();
+        self.group = other.group.clone();
+        self.icon_path = other.icon_path.clone();
+        self.notes = other.notes.clone();
+        self.tags = other.tags.clone();
+        self.metadata = other.metadata.clone();
+        self.pinned = other.pinned;
+        self.rename_windows = other.rename_windows;
+        self.auto_restart = other.auto_restart;
+        self.restart_delay_secs = other.restart_delay_secs;
+        self.stop_command = other.stop_command.clone();
+        self.stop_timeout_secs = other.stop_timeout_secs;
+        self.resource_limits.cpu_percent = other.resource_limits.cpu_percent;
+        self.limit_schedule = other.limit_schedule.clone();
+        self.pinned_region = other.pinned_region;
+        self.status_detection = other.status_detection.clone();
+        self.health_probe = other.health_probe.clone();
+        self.macros = other.macros.clone();
+        self.argument_variants = other.argument_variants.clone();
+        self.active_variant = other.active_variant.clone();
+    }
+
+    /// Whether `edited` changes settings that only take effect at launch
+    pub fn differs_at_launch(&self, edited: &InstanceConfig) -> bool {
+        let mut current = self.clone();
+        current.apply_live_settings(edited);
+        serde_json::to_value(&current).ok() != serde_json::to_value(edited).ok()
+    }
+
     pub fn with_resource_limits(mut self, limits: ResourceLimits) -> Self {
         self.resource_limits = limits;
         self
@@ -173,6 +406,26 @@ impl InstanceConfig {
     }
 }
 
+/// Snapshot of an executable's on-disk metadata, used to detect updates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutableFingerprint {
+    /// Last modification time
+    pub modified: Option<SystemTime>,
+    /// File size in bytes
+    pub size: u64,
+}
+
+impl ExecutableFingerprint {
+    /// Read the current fingerprint of an executable, if it exists
+    pub fn read(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(Self {
+            modified: metadata.modified().ok(),
+            size: metadata.len(),
+        })
+    }
+}
+
 /// Represents a managed application instance
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Instance {
@@ -197,6 +450,63 @@ pub struct Instance {
     pub restart_count: u32,
     /// Last error message if crashed
     pub last_error: Option<String>,
+    /// Executable fingerprint captured at launch
+    #[serde(skip)]
+    pub executable_fingerprint: Option<ExecutableFingerprint>,
+    /// Executable was updated on disk while the instance was running
+    #[serde(skip)]
+    pub update_available: bool,
+    /// Profile this instance was launched from
+    #[serde(default)]
+    pub profile_id: Option<ProfileId>,
+    /// Pointer width of the running process (32 or 64), detected at launch
+    #[serde(skip)]
+    pub process_bits: Option<u32>,
+    /// Environment the process was spawned with; empty if we did not launch it
+    #[serde(skip)]
+    pub spawn_environment: Vec<EnvVar>,
+    /// Whether the process kept the isolation variables it was launched with
+    #[serde(skip)]
+    pub isolation_check: Option<IsolationCheck>,
+    /// Running copies of this executable system-wide, including ones we did not
+    /// launch; helper processes of a copy are not counted
+    #[serde(skip)]
+    pub system_copies: usize,
+    /// How many of `system_copies` were not launched by us
+    #[serde(skip)]
+    pub external_copies: usize,
+    /// When the run limit stops this launch
+    #[serde(skip)]
+    pub stop_at: Option<DateTime<Utc>>,
+    /// The pre-expiry warning for this launch was sent
+    #[serde(skip)]
+    pub expiry_warned: bool,
+    /// Latest on-screen status check
+    #[serde(skip)]
+    pub health: Option<HealthCheck>,
+    /// When each macro (by index) last ran during this launch
+    #[serde(skip)]
+    pub macro_runs: HashMap<usize, DateTime<Utc>>,
+    /// Outcome of the most recent macro run, for the details dialog
+    #[serde(skip)]
+    pub macro_outcome: Option<String>,
+    /// Correlation ID of the latest launch, shared by its history events,
+    /// output log and tracing span; kept after it exits
+    #[serde(skip)]
+    pub launch_id: Option<String>,
+    /// Size of the data directory's archive while the data is compressed
+    #[serde(skip)]
+    pub compressed_bytes: Option<u64>,
+    /// Edits made while running that take effect at the next launch, saved
+    /// in their own column so they survive a restart of the app
+    #[serde(skip)]
+    pub pending_config: Option<InstanceConfig>,
+    /// Limit window whose limits the running process has, None for its own
+    #[serde(skip)]
+    pub limit_window: Option<LimitWindow>,
+    /// Exit code of the process's last exit, if it had one
+    #[serde(skip)]
+    pub exit_code: Option<i32>,
 }
 
 impl Instance {
@@ -212,6 +522,24 @@ impl Instance {
             resource_usage: ResourceUsage::default(),
             restart_count: 0,
             last_error: None,
+            executable_fingerprint: None,
+            update_available: false,
+            profile_id: None,
+            process_bits: None,
+            spawn_environment: Vec::new(),
+            isolation_check: None,
+            system_copies: 0,
+            external_copies: 0,
+            stop_at: None,
+            expiry_warned: false,
+            health: None,
+            macro_runs: HashMap::new(),
+            macro_outcome: None,
+            launch_id: None,
+            compressed_bytes: None,
+            pending_config: None,
+            limit_window: None,
+            exit_code: None,
         }
     }
 
@@ -228,6 +556,11 @@ impl Instance {
         }
     }
 
+    /// Config the next launch uses, including edits waiting for a restart
+    pub fn next_launch_config(&self) -> &InstanceConfig {
+        self.pending_config.as_ref().unwrap_or(&self.config)
+    }
+
     /// Get uptime duration if running
     pub fn uptime(&self) -> Option<chrono::Duration> {
         self.started_at.map(|started| Utc::now() - started)
@@ -236,27 +569,49 @@ impl Instance {
     /// Format uptime as human-readable string
     pub fn uptime_string(&self) -> String {
         match self.uptime() {
-            Some(duration) => {
-                let secs = duration.num_seconds();
-                if secs < 60 {
-                    format!("{}s", secs)
-                } else if secs < 3600 {
-                    format!("{}m {}s", secs / 60, secs % 60)
-                } else if secs < 86400 {
-                    format!("{}h {}m", secs / 3600, (secs % 3600) / 60)
-                } else {
-                    format!("{}d {}h", secs / 86400, (secs % 86400) / 3600)
-                }
-            }
+            Some(duration) => format_duration_secs(duration.num_seconds().max(0) as u64),
             None => "-".to_string(),
         }
     }
 
+    /// Time left before the run limit stops this launch
+    pub fn time_remaining(&self) -> Option<chrono::TimeDelta> {
+        self.stop_at
+            .map(|stop_at| (stop_at - Utc::now()).max(chrono::TimeDelta::zero()))
+    }
+
+    /// Format the remaining run time as a countdown
+    pub fn countdown_string(&self) -> Option<String> {
+        self.time_remaining().map(|remaining| {
+            let secs = remaining.num_seconds();
+            if secs < 3600 {
+                format!("{}:{:02}", secs / 60, secs % 60)
+            } else {
+                format!("{}:{:02}:{:02}", secs / 3600, (secs % 3600) / 60, secs % 60)
+            }
+        })
+    }
+
     /// Check if instance should be auto-restarted
     pub fn should_auto_restart(&self) -> bool {
         self.config.auto_restart && matches!(self.status, InstanceStatus::Crashed)
     }
 
+    /// Mark instance as waiting for its network condition
+    pub fn mark_pending(&mut self) {
+        self.status = InstanceStatus::Pending;
+        self.last_error = None;
+    }
+
+    /// Tracing span of the latest launch, so log lines can be matched to it
+    pub fn launch_span(&self) -> tracing::Span {
+        tracing::info_span!(
+            "launch",
+            id = self.launch_id.as_deref().unwrap_or("-"),
+            instance = %self.config.name
+        )
+    }
+
     /// Mark instance as starting
     pub fn mark_starting(&mut self, pid: u32) {
         self.status = InstanceStatus::Starting;
@@ -264,6 +619,16 @@ impl Instance {
         self.started_at = Some(Utc::now());
         self.stopped_at = None;
         self.last_error = None;
+        self.exit_code = None;
+        self.executable_fingerprint = ExecutableFingerprint::read(&self.config.executable_path);
+        self.update_available = false;
+        self.stop_at = (self.config.run_limit_mins > 0)
+            .then(|| Utc::now() + chrono::TimeDelta::minutes(self.config.run_limit_mins as i64));
+        self.expiry_warned = false;
+        self.health = None;
+        self.isolation_check = None;
+        self.macro_runs.clear();
+        self.limit_window = None;
     }
 
     /// Mark instance as running
@@ -271,11 +636,18 @@ impl Instance {
         self.status = InstanceStatus::Running;
     }
 
+    /// Mark instance as waiting for its stop command to end it
+    pub fn mark_stopping(&mut self) {
+        self.status = InstanceStatus::Stopping;
+    }
+
     /// Mark instance as stopped
     pub fn mark_stopped(&mut self) {
         self.status = InstanceStatus::Stopped;
         self.stopped_at = Some(Utc::now());
         self.resource_usage = ResourceUsage::default();
+        self.update_available = false;
+        self.stop_at = None;
     }
 
     /// Mark instance as crashed
@@ -284,6 +656,8 @@ impl Instance {
         self.stopped_at = Some(Utc::now());
         self.last_error = error;
         self.resource_usage = ResourceUsage::default();
+        self.update_available = false;
+        self.stop_at = None;
     }
 
     /// Mark instance as paused
@@ -300,4 +674,23 @@ impl Instance {
     pub fn update_resource_usage(&mut self, usage: ResourceUsage) {
         self.resource_usage = usage;
     }
+
+    /// Compare the executable on disk against the launch fingerprint.
+    /// Returns true if an update was newly detected.
+    pub fn check_for_update(&mut self) -> bool {
+        if self.update_available || !self.status.is_active() {
+            return false;
+        }
+        let Some(ref launched) = self.executable_fingerprint else {
+            return false;
+        };
+        // A missing file usually means an updater is mid-replace; check again later
+        match ExecutableFingerprint::read(&self.config.executable_path) {
+            Some(current) if current != *launched => {
+                self.update_available = true;
+                true
+            }
+            _ => false,
+        }
+    }
 }
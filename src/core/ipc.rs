@@ -0,0 +1,324 @@
+//! IPC - Lets the command line control the running GUI
+//!
+//! The GUI listens on a loopback TCP port and writes the port, with a random
+//! token, to an endpoint file only the current user can read. Each request is
+//! one JSON line answered by one JSON line, on a thread per connection.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use super::app_state::AppState;
+use super::instance::InstanceStatus;
+use super::resource::format_bytes;
+
+/// File in the data directory holding the GUI's port and token
+const ENDPOINT_FILE: &str = "ipc.json";
+
+/// How long the CLI waits for an answer; staggered profile launches take a while
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A command sent from the CLI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum IpcCommand {
+    /// Launch a profile, or start an instance, by name or ID
+    Launch(String),
+    /// Stop an instance by name or ID
+    Stop(String),
+    /// List instances and profiles
+    List,
+    /// Summary of what is running
+    Status,
+    /// Command line of a second start of the GUI
+    Activate(Activation),
+}
+
+/// What a second start of the GUI asks the running one to do; the running one
+/// brings its window to the front in every case
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Activation {
+    Show,
+    /// Open the New Instance dialog for an executable dropped on the binary
+    OpenExecutable(PathBuf),
+    /// Launch a profile by name or ID
+    LaunchProfile(String),
+}
+
+#[derive(Serialize, Deserialize)]
+struct Endpoint {
+    port: u16,
+    token: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Request {
+    token: String,
+    command: IpcCommand,
+}
+
+/// Text to print, or an error message
+type Response = std::result::Result<String, String>;
+
+fn endpoint_path() -> Result<PathBuf> {
+    Ok(dirs::data_dir()
+        .context("Failed to get data directory")?
+        .join("MultiInstance")
+        .join(ENDPOINT_FILE))
+}
+
+/// Start answering CLI requests on a background thread
+pub fn serve(state: AppState) -> Result<()> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let endpoint = Endpoint {
+        port: listener.local_addr()?.port(),
+        token: uuid::Uuid::new_v4().to_string(),
+    };
+    write_endpoint(&endpoint)?;
+    info!("Listening for CLI commands on port {}", endpoint.port);
+
+    // Each connection gets its own thread so a slow command, like a staggered
+    // profile launch, does not hold up the next request
+    let token = Arc::new(endpoint.token);
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    warn!("CLI connection failed: {}", e);
+                    continue;
+                }
+            };
+            let state = state.clone();
+            let token = Arc::clone(&token);
+            std::thread::spawn(move || {
+                if let Err(e) = handle_connection(&state, &token, stream) {
+                    warn!("CLI request failed: {}", e);
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+/// Write the endpoint file, readable only by the current user
+fn write_endpoint(endpoint: &Endpoint) -> Result<()> {
+    let path = endpoint_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    // Lock the file down before the token goes in; the mode above only
+    // applies when the file is created
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    #[cfg(windows)]
+    crate::platform::windows::restrict_to_owner(&path)?;
+    file.write_all(serde_json::to_string(endpoint)?.as_bytes())?;
+    Ok(())
+}
+
+fn handle_connection(state: &AppState, token: &str, stream: TcpStream) -> Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line)?;
+    let request: Request = serde_json::from_str(&line).context("Malformed request")?;
+
+    let response: Response = if !tokens_match(&request.token, token) {
+        Err("Invalid token".to_string())
+    } else {
+        info!("CLI command: {:?}", request.command);
+        execute(state, request.command).map_err(|e| format!("{:#}", e))
+    };
+    let mut stream = stream;
+    writeln!(stream, "{}", serde_json::to_string(&response)?)?;
+    Ok(())
+}
+
+/// Compare tokens in time independent of where they differ
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Run a command against the app state
+fn execute(state: &AppState, command: IpcCommand) -> Result<String> {
+    match command {
+        IpcCommand::Launch(target) => {
+            if let Some((profile_id, name)) = find_profile(state, &target)? {
+                let ids = state.launch_profile(profile_id)?;
+                return Ok(format!(
+                    "Launched profile '{}' ({} instance(s))",
+                    name,
+                    ids.len()
+                ));
+            }
+            let (id, name) = find_instance(state, &target)?;
+            state.start_instance(id)?;
+            Ok(format!("Started instance '{}'", name))
+        }
+        IpcCommand::Stop(target) => {
+            let (id, name) = find_instance(state, &target)?;
+            state.stop_instance(id)?;
+            Ok(format!("Stopped instance '{}'", name))
+        }
+        IpcCommand::List => list(state),
+        IpcCommand::Status => status(state),
+        IpcCommand::Activate(activation) => {
+            state.request_activation(activation);
+            Ok("Forwarded to the running window".to_string())
+        }
+    }
+}
+
+/// Profile whose ID or name (case-insensitive) matches
+pub fn find_profile(state: &AppState, target: &str) -> Result<Option<(super::ProfileId, String)>> {
+    let profiles = state
+        .profiles
+        .read()
+        .map_err(|e| anyhow::anyhow!("Profiles lock poisoned: {}", e))?;
+    let matches: Vec<_> = profiles
+        .values()
+        .filter(|p| p.id.to_string() == target || p.name.eq_ignore_ascii_case(target))
+        .map(|p| (p.id, p.name.clone()))
+        .collect();
+    match matches.len() {
+        0 | 1 => Ok(matches.into_iter().next()),
+        n => anyhow::bail!("{} profiles are named '{}'; use the profile ID", n, target),
+    }
+}
+
+/// Instance whose ID or name (case-insensitive) matches
+fn find_instance(state: &AppState, target: &str) -> Result<(super::InstanceId, String)> {
+    let instances = state
+        .instances
+        .read()
+        .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+    let matches: Vec<_> = instances
+        .values()
+        .filter(|i| i.id.to_string() == target || i.display_name().eq_ignore_ascii_case(target))
+        .map(|i| (i.id, i.display_name().to_string()))
+        .collect();
+    match matches.len() {
+        0 => anyhow::bail!("No profile or instance named '{}'", target),
+        1 => Ok(matches.into_iter().next().unwrap()),
+        n => anyhow::bail!(
+            "{} instances are named '{}'; use the instance ID",
+            n,
+            target
+        ),
+    }
+}
+
+fn list(state: &AppState) -> Result<String> {
+    let instances = state
+        .instances
+        .read()
+        .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+    let mut instances: Vec<_> = instances.values().collect();
+    instances.sort_by_key(|i| i.display_name().to_lowercase());
+
+    let mut lines = vec!["INSTANCES".to_string()];
+    for instance in instances {
+        let pid = instance
+            .pid
+            .filter(|_| instance.status.is_active())
+            .map(|pid| format!("PID {}", pid))
+            .unwrap_or_default();
+        lines.push(format!(
+            "  {:<28} {:<20} {:<10} {}",
+            instance.display_name(),
+            instance.status.label(),
+            pid,
+            instance.id
+        ));
+    }
+
+    let profiles = state
+        .profiles
+        .read()
+        .map_err(|e| anyhow::anyhow!("Profiles lock poisoned: {}", e))?;
+    let mut profiles: Vec<_> = profiles.values().collect();
+    profiles.sort_by_key(|p| p.name.to_lowercase());
+
+    lines.push(String::new());
+    lines.push("PROFILES".to_string());
+    for profile in profiles {
+        lines.push(format!(
+            "  {:<28} {:<20} {}",
+            profile.name,
+            format!("{} instance(s)", profile.instance_count()),
+            profile.id
+        ));
+    }
+    Ok(lines.join("\n"))
+}
+
+fn status(state: &AppState) -> Result<String> {
+    let instances = state
+        .instances
+        .read()
+        .map_err(|e| anyhow::anyhow!("Instances lock poisoned: {}", e))?;
+    let count = |status: InstanceStatus| instances.values().filter(|i| i.status == status).count();
+    let active = instances.values().filter(|i| i.status.is_active());
+    let cpu: f32 = active.clone().map(|i| i.resource_usage.cpu_percent).sum();
+    let memory: u64 = active.map(|i| i.resource_usage.memory_bytes).sum();
+
+    Ok(format!(
+        "{} instance(s): {} running, {} paused, {} waiting, {} crashed\nCPU {:.0}%, memory {}",
+        instances.len(),
+        count(InstanceStatus::Running),
+        count(InstanceStatus::Paused),
+        count(InstanceStatus::Pending),
+        count(InstanceStatus::Crashed),
+        cpu,
+        format_bytes(memory)
+    ))
+}
+
+/// Send a command to the running GUI and return what it printed
+pub fn send(command: IpcCommand) -> Result<String> {
+    let not_running = || anyhow::anyhow!("MultiInstance is not running");
+    let endpoint: Endpoint = std::fs::read_to_string(endpoint_path()?)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .ok_or_else(not_running)?;
+
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, endpoint.port));
+    let mut stream =
+        TcpStream::connect_timeout(&address, Duration::from_secs(2)).map_err(|_| not_running())?;
+    stream.set_read_timeout(Some(RESPONSE_TIMEOUT))?;
+    let request = Request {
+        token: endpoint.token,
+        command,
+    };
+    writeln!(stream, "{}", serde_json::to_string(&request)?)?;
+
+    let mut line = String::new();
+    BufReader::new(&stream)
+        .read_line(&mut line)
+        .context("No answer from MultiInstance")?;
+    let response: Response = serde_json::from_str(&line).map_err(|_| not_running())?;
+    response.map_err(|e| anyhow::anyhow!(e))
+}
@@ -0,0 +1,147 @@
+//! Isolation check - Confirms a launched process kept its isolated environment
+//!
+//! Environment isolation only works while the target keeps the variables it was
+//! given. A launcher that restarts itself with a fresh environment silently
+//! writes to the real user profile instead. Shortly after launch the
+//! environments of the process and every process it started are read back
+//! (from their PEBs on Windows) and compared.
+
+use std::collections::{HashMap, HashSet};
+
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+use super::environment::{EnvSource, EnvVar};
+
+/// Seconds after launch before the environment is read back, giving
+/// launchers time to hand over to the real process
+pub const DELAY_SECS: i64 = 5;
+
+/// Outcome of reading back a process's isolation variables
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IsolationCheck {
+    /// Every isolation variable still has the value it was given
+    Verified,
+    /// Variables that differ, with the value the process has (None when unset)
+    Ignored {
+        /// Name and PID of the descendant that dropped them, None for the process itself
+        process: Option<String>,
+        vars: Vec<(String, Option<String>)>,
+    },
+    /// The environment could not be read, e.g. of an elevated process
+    Unreadable,
+}
+
+impl IsolationCheck {
+    /// One line per variable that did not take effect
+    pub fn details(&self) -> String {
+        match self {
+            Self::Verified => "The process sees its isolated data directory".to_string(),
+            Self::Ignored { process, vars } => process
+                .iter()
+                .map(|process| format!("In child process {}:", process))
+                .chain(vars.iter().map(|(key, value)| match value {
+                    Some(value) => format!("{} is {}", key, value),
+                    None => format!("{} is not set", key),
+                }))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Self::Unreadable => "The process's environment could not be read".to_string(),
+        }
+    }
+}
+
+/// Whether the spawn set any isolation variables to check
+pub fn applies(environment: &[EnvVar]) -> bool {
+    environment.iter().any(|v| v.source == EnvSource::Isolation)
+}
+
+/// Compare the isolation variables of `environment` with those process `pid`
+/// and its descendants have now. A launcher may have exited after handing over,
+/// so its descendants are checked even when `pid` itself is gone.
+pub fn verify(pid: u32, environment: &[EnvVar]) -> IsolationCheck {
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::new().with_environ(UpdateKind::Always),
+    );
+
+    let root = Pid::from_u32(pid);
+    let mut tree = HashSet::from([root]);
+    // Parents may be listed after their children, so repeat until nothing is added
+    loop {
+        let added: Vec<Pid> = system
+            .processes()
+            .iter()
+            .filter(|(child, process)| {
+                !tree.contains(*child) && process.parent().is_some_and(|p| tree.contains(&p))
+            })
+            .map(|(child, _)| *child)
+            .collect();
+        if added.is_empty() {
+            break;
+        }
+        tree.extend(added);
+    }
+
+    let mut readable = false;
+    // The process itself first, then its descendants in PID order
+    let mut members: Vec<Pid> = tree.into_iter().collect();
+    members.sort_by_key(|p| (*p != root, p.as_u32()));
+    for member in members {
+        let Some(process) = system.process(member) else {
+            continue;
+        };
+        let actual: HashMap<String, String> = process
+            .environ()
+            .iter()
+            .filter_map(|entry| {
+                let (key, value) = entry.to_str()?.split_once('=')?;
+                Some((key.to_uppercase(), value.to_string()))
+            })
+            .collect();
+        if actual.is_empty() {
+            continue;
+        }
+        readable = true;
+
+        let vars = ignored(&actual, environment);
+        if !vars.is_empty() {
+            return IsolationCheck::Ignored {
+                process: (member != root).then(|| {
+                    format!(
+                        "{} (PID {})",
+                        process.name().to_string_lossy(),
+                        member.as_u32()
+                    )
+                }),
+                vars,
+            };
+        }
+    }
+
+    if readable {
+        IsolationCheck::Verified
+    } else {
+        IsolationCheck::Unreadable
+    }
+}
+
+/// Isolation variables of `environment` that differ in `actual`
+fn ignored(
+    actual: &HashMap<String, String>,
+    environment: &[EnvVar],
+) -> Vec<(String, Option<String>)> {
+    environment
+        .iter()
+        .filter(|v| v.source == EnvSource::Isolation)
+        .filter_map(|var| {
+            let value = actual.get(&var.key.to_uppercase());
+            let kept = value.is_some_and(|value| {
+                // Windows paths are case-insensitive
+                value == &var.value || (cfg!(windows) && value.eq_ignore_ascii_case(&var.value))
+            });
+            (!kept).then(|| (var.key.clone(), value.cloned()))
+        })
+        .collect()
+}
@@ -0,0 +1,94 @@
+//! Known issues - Hints about running several copies of specific applications
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// A hint shown when creating an instance of an executable
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KnownIssue {
+    /// Executable name without extension, e.g. "discord"; case-insensitive
+    pub executable: String,
+    pub hint: String,
+}
+
+impl KnownIssue {
+    fn new(executable: &str, hint: &str) -> Self {
+        Self {
+            executable: executable.to_string(),
+            hint: hint.to_string(),
+        }
+    }
+
+    /// Hints shipped with the app
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::new(
+                "discord",
+                "Discord needs environment isolation AND a different --user-data-dir; closing its single-instance handle is not enough",
+            ),
+            Self::new(
+                "chrome",
+                "Chrome opens new windows in the first copy unless each instance has its own --user-data-dir",
+            ),
+            Self::new(
+                "msedge",
+                "Edge opens new windows in the first copy unless each instance has its own --user-data-dir",
+            ),
+            Self::new(
+                "firefox",
+                "Firefox needs -no-remote and its own -profile folder per instance",
+            ),
+            Self::new(
+                "code",
+                "VS Code needs its own --user-data-dir (and --extensions-dir to keep extensions apart) per instance",
+            ),
+            Self::new(
+                "slack",
+                "Slack needs environment isolation, or every copy signs in to the same workspace session",
+            ),
+            Self::new(
+                "telegram",
+                "Telegram Desktop needs its own -workdir folder per instance",
+            ),
+            Self::new(
+                "obs64",
+                "OBS Studio needs --multi to start more than once, and --portable from separate folders to keep settings apart",
+            ),
+            Self::new(
+                "steam",
+                "Steam runs one client per user account; a second copy hands over to the first even with the single-instance bypass",
+            ),
+            Self::new(
+                "robloxplayerbeta",
+                "Roblox needs the single-instance bypass; leave environment isolation off, as its anti-cheat may reject it",
+            ),
+        ]
+    }
+
+    /// Whether the hint is about the executable at `path`
+    pub fn applies_to(&self, path: &Path) -> bool {
+        let wanted = self.executable.trim().to_lowercase();
+        let wanted = wanted
+            .strip_suffix(".exe")
+            .or_else(|| wanted.strip_suffix(".app"))
+            .unwrap_or(&wanted);
+        !wanted.is_empty() && executable_name(path) == wanted
+    }
+}
+
+/// Lowercase file name of an executable without its extension
+pub fn executable_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Hints that apply to the executable at `path`
+pub fn hints_for<'a>(issues: &'a [KnownIssue], path: &Path) -> Vec<&'a str> {
+    issues
+        .iter()
+        .filter(|issue| !issue.hint.trim().is_empty() && issue.applies_to(path))
+        .map(|issue| issue.hint.as_str())
+        .collect()
+}
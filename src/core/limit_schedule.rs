@@ -0,0 +1,57 @@
+//! Limit schedule - Resource limits that change with the time of day
+//!
+//! An instance can have time windows, e.g. low limits during work hours, each
+//! with its own limits. While a window is open its limits replace the instance's
+//! own on the running process; outside every window the instance's own apply.
+
+use chrono::{DateTime, Datelike, Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+use super::resource::ResourceLimits;
+use super::scheduler::WEEKDAYS;
+
+/// Limits applied while a time window is open
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LimitWindow {
+    pub name: String,
+    /// Days the window opens on, Monday first
+    pub days: [bool; 7],
+    /// Local time the window opens
+    pub start: NaiveTime,
+    /// Local time it closes; before `start` for windows that run past midnight
+    pub end: NaiveTime,
+    pub limits: ResourceLimits,
+}
+
+impl LimitWindow {
+    /// A weekday 9:00 to 17:00 window starting from `limits`
+    pub fn work_hours(limits: ResourceLimits) -> Self {
+        Self {
+            name: "Work hours".to_string(),
+            days: WEEKDAYS,
+            start: NaiveTime::from_hms_opt(9, 0, 0).unwrap_or_default(),
+            end: NaiveTime::from_hms_opt(17, 0, 0).unwrap_or_default(),
+            limits,
+        }
+    }
+
+    /// Whether the window is open at `at`. A window that runs past midnight
+    /// belongs to the day it opens on; equal start and end mean all day.
+    pub fn contains(&self, at: DateTime<Local>) -> bool {
+        let day = at.weekday().num_days_from_monday() as usize;
+        let time = at.time();
+        if self.start == self.end {
+            self.days[day]
+        } else if self.start < self.end {
+            self.days[day] && self.start <= time && time < self.end
+        } else {
+            let yesterday = (day + 6) % 7;
+            (self.days[day] && time >= self.start) || (self.days[yesterday] && time < self.end)
+        }
+    }
+}
+
+/// The first window open at `at`
+pub fn active(windows: &[LimitWindow], at: DateTime<Local>) -> Option<&LimitWindow> {
+    windows.iter().find(|w| w.contains(at))
+}
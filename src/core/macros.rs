@@ -0,0 +1,166 @@
+//! Automation macros - Recorded input sequences replayed into an instance's window
+
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Pause after each input so the target window keeps up
+const INPUT_GAP: Duration = Duration::from_millis(25);
+
+/// Modifier keys held during a key press
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    /// ⌘ on macOS, the Windows/Super key elsewhere
+    pub command: bool,
+}
+
+impl Modifiers {
+    /// Prefix such as `Ctrl+Shift+` for display
+    fn prefix(&self) -> String {
+        [
+            (self.ctrl, "Ctrl+"),
+            (self.shift, "Shift+"),
+            (self.alt, "Alt+"),
+            (self.command, "Cmd+"),
+        ]
+        .iter()
+        .filter(|(held, _)| *held)
+        .map(|(_, name)| *name)
+        .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+impl MouseButton {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Left => "Left",
+            Self::Right => "Right",
+            Self::Middle => "Middle",
+        }
+    }
+}
+
+/// One piece of input sent to a window
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InputEvent {
+    /// A named key such as `Enter`, `F5`, `ArrowUp` or `A`
+    Key { key: String, modifiers: Modifiers },
+    /// Text typed character by character
+    Text(String),
+    /// A click in pixels from the window's top-left corner
+    Click { x: i32, y: i32, button: MouseButton },
+}
+
+/// One step of a macro
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MacroStep {
+    Input(InputEvent),
+    Wait { ms: u32 },
+}
+
+impl MacroStep {
+    /// Short description for the step list
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Input(InputEvent::Key { key, modifiers }) => {
+                format!("Press {}{}", modifiers.prefix(), key)
+            }
+            Self::Input(InputEvent::Text(text)) => format!("Type \"{}\"", text),
+            Self::Input(InputEvent::Click { x, y, button }) => {
+                format!("{} click at {}, {}", button.label(), x, y)
+            }
+            Self::Wait { ms } => format!("Wait {} ms", ms),
+        }
+    }
+}
+
+/// When a macro runs without being started by hand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MacroTrigger {
+    /// Only from the Run button
+    Manual,
+    /// Once per launch, this many seconds after the instance is running
+    LaunchComplete { delay_secs: u32 },
+    /// Repeatedly while the instance is running
+    Every { minutes: u32 },
+}
+
+impl MacroTrigger {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Manual => "Manual",
+            Self::LaunchComplete { .. } => "After launch",
+            Self::Every { .. } => "On a schedule",
+        }
+    }
+
+    pub fn all() -> [MacroTrigger; 3] {
+        [
+            Self::Manual,
+            Self::LaunchComplete { delay_secs: 5 },
+            Self::Every { minutes: 10 },
+        ]
+    }
+}
+
+/// A named input sequence for an instance
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Macro {
+    pub name: String,
+    pub steps: Vec<MacroStep>,
+    pub trigger: MacroTrigger,
+}
+
+impl Macro {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            steps: Vec::new(),
+            trigger: MacroTrigger::Manual,
+        }
+    }
+
+    /// Whether the trigger wants the macro to run now, given when the instance
+    /// started and when this macro last ran during the current launch
+    pub fn is_due(&self, started_at: DateTime<Utc>, last_run: Option<DateTime<Utc>>) -> bool {
+        let now = Utc::now();
+        match self.trigger {
+            MacroTrigger::Manual => false,
+            MacroTrigger::LaunchComplete { delay_secs } => {
+                last_run.is_none()
+                    && now - started_at >= chrono::TimeDelta::seconds(delay_secs as i64)
+            }
+            MacroTrigger::Every { minutes } => {
+                let since = last_run.unwrap_or(started_at);
+                now - since >= chrono::TimeDelta::minutes(minutes.max(1) as i64)
+            }
+        }
+    }
+
+    /// Replay the steps into a process's main window
+    pub fn play(&self, pid: u32) -> Result<()> {
+        crate::platform::focus_window(pid)?;
+        for step in &self.steps {
+            match step {
+                MacroStep::Input(event) => {
+                    crate::platform::send_input(pid, event)?;
+                    std::thread::sleep(INPUT_GAP);
+                }
+                MacroStep::Wait { ms } => std::thread::sleep(Duration::from_millis(*ms as u64)),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,554 @@
+//! Migration - Moves the whole application state to another machine
+//!
+//! An export writes a copy of the database (instances, profiles, schedules,
+//! quick launch items, groups, settings and history) and optionally every
+//! instance's data directory into a tar archive. Instance data often holds
+//! logged-in sessions, so the archive is encrypted with a key derived from a
+//! passphrase as it is written, and decrypted as it is read, so no unencrypted
+//! copy reaches the disk. An import reads it back and rewrites the paths that differ on
+//! the new machine, starting with the home and data directories.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Context, Result};
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::stream::{DecryptorBE32, EncryptorBE32};
+use chacha20poly1305::aead::{KeyInit, OsRng};
+use chacha20poly1305::ChaCha20Poly1305;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::bundle;
+use super::data_compression;
+use super::instance::{Instance, InstanceConfig, InstanceId};
+use super::profile::Profile;
+use super::scheduler::Schedule;
+use super::settings::Settings;
+use crate::persistence::Database;
+
+/// File extension used for migration archives
+pub const MIGRATION_EXTENSION: &str = "mimigrate";
+
+/// Start of every migration archive, before the salt and nonce. The last two
+/// digits are the container version.
+const MAGIC: &[u8; 8] = b"MIMIGR02";
+
+/// Archive format version, bumped on incompatible layout changes
+const FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "migration.json";
+const DATABASE_FILE: &str = "multiinstance.db";
+const DATA_FOLDER: &str = "instances";
+
+const SALT_SIZE: usize = 16;
+/// Nonce prefix of the STREAM construction; the rest is a chunk counter
+const NONCE_SIZE: usize = 7;
+const TAG_SIZE: usize = 16;
+/// Plaintext bytes encrypted at a time
+const CHUNK_SIZE: usize = 1 << 20;
+
+/// zstd level of the archive; instance data is large, so favor speed
+const LEVEL: i32 = 3;
+
+/// Metadata stored at the root of a migration archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationManifest {
+    pub format_version: u32,
+    pub exported_at: DateTime<Utc>,
+    /// Name of the machine the archive was written on
+    pub host: String,
+    /// Home directory on that machine
+    pub home: Option<PathBuf>,
+    /// Default parent of instance data directories on that machine
+    pub instances_directory: PathBuf,
+    /// Whether instance data directories are included
+    pub includes_data: bool,
+}
+
+/// A path prefix on the old machine and what replaces it on this one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathRewrite {
+    pub from: String,
+    pub to: String,
+}
+
+/// What to take from a migration archive
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+    pub rewrites: Vec<PathRewrite>,
+    /// Replace this machine's settings, keeping its PIN lock and data directory
+    pub settings: bool,
+    /// Copy the bundled instance data
+    pub data: bool,
+}
+
+/// What an import added
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImportSummary {
+    pub instances: usize,
+    pub profiles: usize,
+    pub schedules: usize,
+    pub events: usize,
+    /// Instances and profiles already here, left as they are
+    pub skipped: usize,
+}
+
+impl ImportSummary {
+    pub fn describe(&self) -> String {
+        let mut text = format!(
+            "Imported {} instances, {} profiles, {} schedules and {} history events",
+            self.instances, self.profiles, self.schedules, self.events
+        );
+        if self.skipped > 0 {
+            text.push_str(&format!(
+                "; {} already on this machine were skipped",
+                self.skipped
+            ));
+        }
+        text
+    }
+}
+
+/// Write the database and, when given, instance data directories to an
+/// encrypted archive at `destination`
+pub fn export(
+    database: &Database,
+    data_dirs: &[(InstanceId, PathBuf)],
+    instances_directory: &Path,
+    destination: &Path,
+    passphrase: &str,
+) -> Result<()> {
+    if passphrase.is_empty() {
+        anyhow::bail!("A passphrase is needed to encrypt the archive");
+    }
+    let result = write_archive(
+        database,
+        data_dirs,
+        instances_directory,
+        destination,
+        passphrase,
+    );
+    if result.is_err() {
+        let _ = std::fs::remove_file(destination);
+    }
+    result?;
+
+    info!("Exported application state to {:?}", destination);
+    Ok(())
+}
+
+/// Stream the tar archive through zstd into the encryptor, so nothing is
+/// written to disk unencrypted
+fn write_archive(
+    database: &Database,
+    data_dirs: &[(InstanceId, PathBuf)],
+    instances_directory: &Path,
+    destination: &Path,
+    passphrase: &str,
+) -> Result<()> {
+    let file =
+        File::create(destination).with_context(|| format!("Failed to write {:?}", destination))?;
+    let writer = EncryptWriter::new(BufWriter::new(file), passphrase)?;
+    let mut builder = tar::Builder::new(zstd::Encoder::new(writer, LEVEL)?);
+
+    // The manifest and database come first so opening stops reading after them
+    let manifest = MigrationManifest {
+        format_version: FORMAT_VERSION,
+        exported_at: Utc::now(),
+        host: sysinfo::System::host_name().unwrap_or_else(|| "unknown".to_string()),
+        home: dirs::home_dir(),
+        instances_directory: instances_directory.to_path_buf(),
+        includes_data: !data_dirs.is_empty(),
+    };
+    append_bytes(
+        &mut builder,
+        MANIFEST_FILE,
+        &serde_json::to_vec_pretty(&manifest)?,
+    )?;
+    append_bytes(&mut builder, DATABASE_FILE, &database.serialize()?)?;
+
+    let data = Path::new(DATA_FOLDER);
+    for (id, dir) in data_dirs {
+        let archive = data_compression::archive_path(dir);
+        if dir.is_dir() {
+            let name = data.join(id.to_string());
+            builder.append_dir(&name, dir)?;
+            data_compression::append_dir(&mut builder, dir, &name)?;
+        } else if archive.is_file() {
+            // Compressed data travels as it is and stays compressed
+            builder.append_path_with_name(&archive, data_archive(data, *id))?;
+        } else {
+            warn!("Data directory {:?} of instance {} is missing", dir, id);
+        }
+    }
+
+    let mut writer = builder.into_inner()?.finish()?.finish()?;
+    writer.flush()?;
+    writer
+        .into_inner()
+        .map_err(|e| anyhow::anyhow!("Failed to write archive: {}", e))?
+        .sync_all()?;
+    Ok(())
+}
+
+fn append_bytes<W: Write>(builder: &mut tar::Builder<W>, name: &str, bytes: &[u8]) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o600);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    builder.append_data(&mut header, name, bytes)?;
+    Ok(())
+}
+
+/// A decrypted migration archive's manifest and database, kept in memory
+/// until it is imported or dropped
+pub struct MigrationPackage {
+    pub manifest: MigrationManifest,
+    pub instances: Vec<Instance>,
+    pub profiles: Vec<Profile>,
+    pub schedules: Vec<Schedule>,
+    pub quick_launch: Vec<InstanceConfig>,
+    pub groups: Vec<String>,
+    pub settings: Option<Settings>,
+    /// The database copy, for reading history from
+    pub database: Vec<u8>,
+    /// Archive and passphrase, to stream instance data from on import
+    archive: PathBuf,
+    passphrase: String,
+}
+
+/// Decrypt an archive written by `export` and read its manifest and database
+pub fn open(archive: &Path, passphrase: &str) -> Result<MigrationPackage> {
+    let mut manifest = None;
+    let mut database = None;
+    let mut contents = read_archive(archive, passphrase)?;
+    for entry in contents.entries().context("Archive is damaged")? {
+        let mut entry = entry.context("Archive is damaged")?;
+        let path = entry.path()?.into_owned();
+        if path == Path::new(MANIFEST_FILE) {
+            let mut json = String::new();
+            entry.read_to_string(&mut json)?;
+            manifest = Some(serde_json::from_str::<MigrationManifest>(&json)?);
+        } else if path == Path::new(DATABASE_FILE) {
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes)?;
+            database = Some(bytes);
+        }
+        if manifest.is_some() && database.is_some() {
+            break;
+        }
+    }
+
+    let manifest = manifest.context("Archive is missing its manifest")?;
+    if manifest.format_version > FORMAT_VERSION {
+        anyhow::bail!(
+            "Archive was created by a newer version (format {})",
+            manifest.format_version
+        );
+    }
+    let bytes = database.context("Archive is missing its database")?;
+    let database = Database::open_serialized(&bytes)?;
+    Ok(MigrationPackage {
+        manifest,
+        instances: database.load_all_instances()?,
+        profiles: database.load_all_profiles()?,
+        schedules: database.load_all_schedules()?,
+        quick_launch: database.load_quick_launch()?,
+        groups: database.load_groups()?,
+        settings: database.load_settings()?,
+        database: bytes,
+        archive: archive.to_path_buf(),
+        passphrase: passphrase.to_string(),
+    })
+}
+
+/// The tar archive inside an encrypted migration archive
+fn read_archive(archive: &Path, passphrase: &str) -> Result<tar::Archive<impl Read>> {
+    let reader = DecryptReader::open(archive, passphrase)?;
+    Ok(tar::Archive::new(zstd::Decoder::new(reader)?))
+}
+
+impl MigrationPackage {
+    /// Unpack the bundled data of each instance in `targets` straight into its
+    /// data directory; compressed data is written as that directory's archive
+    pub fn unpack_data(&self, targets: &HashMap<InstanceId, PathBuf>) -> Result<()> {
+        if targets.is_empty() {
+            return Ok(());
+        }
+        let mut contents = read_archive(&self.archive, &self.passphrase)?;
+        for entry in contents.entries().context("Archive is damaged")? {
+            let mut entry = entry.context("Archive is damaged")?;
+            let path = entry.path()?.into_owned();
+            let Ok(rest) = path.strip_prefix(DATA_FOLDER) else {
+                continue;
+            };
+            let mut components = rest.components();
+            let Some(first) = components.next() else {
+                continue;
+            };
+            let first = first.as_os_str().to_string_lossy();
+            let (id, target) = match first.strip_suffix(data_compression::ARCHIVE_SUFFIX) {
+                Some(id) => (id.to_string(), None),
+                None => (first.to_string(), Some(components.as_path())),
+            };
+            let Some(data_dir) = Uuid::parse_str(&id)
+                .ok()
+                .and_then(|id| targets.get(&InstanceId(id)))
+            else {
+                continue;
+            };
+            let destination = match target {
+                // Never let an entry escape the data directory
+                Some(relative)
+                    if relative
+                        .components()
+                        .all(|c| matches!(c, Component::Normal(_))) =>
+                {
+                    data_dir.join(relative)
+                }
+                Some(_) => continue,
+                None => data_compression::archive_path(data_dir),
+            };
+            if let Some(parent) = destination.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            entry
+                .unpack(&destination)
+                .with_context(|| format!("Failed to unpack {:?}", destination))?;
+        }
+        Ok(())
+    }
+
+    /// Rewrites of the old home and instance data directories to this machine's
+    pub fn default_rewrites(&self, instances_directory: &Path) -> Vec<PathRewrite> {
+        let mut rewrites = vec![PathRewrite {
+            from: self
+                .manifest
+                .instances_directory
+                .to_string_lossy()
+                .to_string(),
+            to: instances_directory.to_string_lossy().to_string(),
+        }];
+        if let (Some(from), Some(to)) = (&self.manifest.home, dirs::home_dir()) {
+            rewrites.push(PathRewrite {
+                from: from.to_string_lossy().to_string(),
+                to: to.to_string_lossy().to_string(),
+            });
+        }
+        rewrites.retain(|r| r.from != r.to);
+        rewrites
+    }
+
+    /// Names and rewritten paths of instances whose executable is not on this machine
+    pub fn missing_executables(&self, rewrites: &[PathRewrite]) -> Vec<(String, PathBuf)> {
+        self.instances
+            .iter()
+            .map(|i| &i.config)
+            .chain(self.profiles.iter().flat_map(|p| &p.instances))
+            .filter_map(|config| {
+                let mut config = config.clone();
+                rewrite_paths(&mut config, rewrites);
+                (!config.executable_exists()).then_some((config.name, config.executable_path))
+            })
+            .collect()
+    }
+}
+
+/// Rewrite every machine-specific path of a config by the first rewrite whose
+/// prefix it starts with
+pub fn rewrite_paths(config: &mut InstanceConfig, rewrites: &[PathRewrite]) {
+    for path in bundle::machine_paths(config) {
+        let rewritten = rewrites.iter().find_map(|r| {
+            let rest = path.strip_prefix(r.from.trim()).ok()?;
+            Some(Path::new(r.to.trim()).join(rest))
+        });
+        if let Some(rewritten) = rewritten {
+            *path = rewritten;
+        }
+    }
+}
+
+fn data_archive(data: &Path, id: InstanceId) -> PathBuf {
+    data.join(format!("{}{}", id, data_compression::ARCHIVE_SUFFIX))
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32]> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow::anyhow!("Failed to derive key: {}", e))?;
+    Ok(key)
+}
+
+/// Encrypts what is written to it in chunks, so archives larger than memory
+/// can be written
+struct EncryptWriter<W: Write> {
+    inner: W,
+    encryptor: EncryptorBE32<ChaCha20Poly1305>,
+    chunk: Vec<u8>,
+}
+
+impl<W: Write> EncryptWriter<W> {
+    /// Write the header to `inner` with a fresh salt and nonce
+    fn new(mut inner: W, passphrase: &str) -> Result<Self> {
+        let mut salt = [0u8; SALT_SIZE];
+        let mut nonce = [0u8; NONCE_SIZE];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce);
+        let key = derive_key(passphrase, &salt)?;
+        inner.write_all(MAGIC)?;
+        inner.write_all(&salt)?;
+        inner.write_all(&nonce)?;
+        Ok(Self {
+            inner,
+            encryptor: EncryptorBE32::from_aead(
+                ChaCha20Poly1305::new(&key.into()),
+                (&nonce).into(),
+            ),
+            chunk: Vec::with_capacity(CHUNK_SIZE),
+        })
+    }
+
+    /// Seal the last chunk, which is always short and possibly empty
+    fn finish(self) -> io::Result<W> {
+        let Self {
+            mut inner,
+            encryptor,
+            chunk,
+        } = self;
+        let sealed = encryptor
+            .encrypt_last(&chunk[..])
+            .map_err(|_| io::Error::other("Failed to encrypt archive"))?;
+        inner.write_all(&sealed)?;
+        Ok(inner)
+    }
+}
+
+impl<W: Write> Write for EncryptWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let taken = buf.len().min(CHUNK_SIZE - self.chunk.len());
+        self.chunk.extend_from_slice(&buf[..taken]);
+        if self.chunk.len() == CHUNK_SIZE {
+            let sealed = self
+                .encryptor
+                .encrypt_next(&self.chunk[..])
+                .map_err(|_| io::Error::other("Failed to encrypt archive"))?;
+            self.inner.write_all(&sealed)?;
+            self.chunk.clear();
+        }
+        Ok(taken)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Decrypts an archive written through `EncryptWriter` as it is read
+struct DecryptReader<R: Read> {
+    inner: R,
+    /// None once the last chunk was decrypted
+    decryptor: Option<DecryptorBE32<ChaCha20Poly1305>>,
+    sealed: Vec<u8>,
+    plain: Vec<u8>,
+    /// Bytes of `plain` already read
+    consumed: usize,
+}
+
+impl DecryptReader<BufReader<File>> {
+    /// Check the header of `source` and decrypt its first chunk, so a wrong
+    /// passphrase is reported here
+    fn open(source: &Path, passphrase: &str) -> Result<Self> {
+        let mut inner = BufReader::new(
+            File::open(source).with_context(|| format!("Failed to read {:?}", source))?,
+        );
+        let mut header = [0u8; MAGIC.len() + SALT_SIZE + NONCE_SIZE];
+        let len = read_full(&mut inner, &mut header)?;
+        if len < header.len() || header[..MAGIC.len() - 2] != MAGIC[..MAGIC.len() - 2] {
+            anyhow::bail!("{:?} is not a MultiInstance migration archive", source);
+        }
+        if &header[..MAGIC.len()] != MAGIC {
+            anyhow::bail!("Archive was written by another version of MultiInstance");
+        }
+        let salt = &header[MAGIC.len()..MAGIC.len() + SALT_SIZE];
+        let nonce = &header[MAGIC.len() + SALT_SIZE..];
+        let key = derive_key(passphrase, salt)?;
+
+        let mut reader = Self {
+            inner,
+            decryptor: Some(DecryptorBE32::from_aead(
+                ChaCha20Poly1305::new(&key.into()),
+                nonce.into(),
+            )),
+            sealed: vec![0u8; CHUNK_SIZE + TAG_SIZE],
+            plain: Vec::new(),
+            consumed: 0,
+        };
+        reader.next_chunk()?;
+        Ok(reader)
+    }
+}
+
+impl<R: Read> DecryptReader<R> {
+    /// Decrypt the next chunk into `plain`
+    fn next_chunk(&mut self) -> io::Result<()> {
+        let wrong = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Wrong passphrase, or the archive is damaged",
+            )
+        };
+        let len = read_full(&mut self.inner, &mut self.sealed)?;
+        self.plain = if len < self.sealed.len() {
+            match self.decryptor.take() {
+                Some(decryptor) => decryptor
+                    .decrypt_last(&self.sealed[..len])
+                    .map_err(|_| wrong())?,
+                None => Vec::new(),
+            }
+        } else {
+            match self.decryptor.as_mut() {
+                Some(decryptor) => decryptor
+                    .decrypt_next(&self.sealed[..])
+                    .map_err(|_| wrong())?,
+                None => Vec::new(),
+            }
+        };
+        self.consumed = 0;
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for DecryptReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.consumed == self.plain.len() {
+            if self.decryptor.is_none() {
+                return Ok(0);
+            }
+            self.next_chunk()?;
+        }
+        let len = buf.len().min(self.plain.len() - self.consumed);
+        buf[..len].copy_from_slice(&self.plain[self.consumed..self.consumed + len]);
+        self.consumed += len;
+        Ok(len)
+    }
+}
+
+/// Fill `buf` unless the reader ends first; returns the bytes read
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
@@ -1,15 +1,65 @@
 //! Core module - Application state, instance management, and resource monitoring
 
+pub mod alerts;
+pub mod app_container;
 mod app_state;
+pub mod architecture;
+pub mod broadcast;
+pub mod bundle;
+pub mod cache_links;
+pub mod checkpoint;
+pub mod conflicts;
+pub mod cpu_throttle;
+pub mod cpu_topology;
+pub mod crash_dialogs;
+pub mod crash_report;
+pub mod custom_theme;
+pub mod data_compression;
+pub mod deployment;
+pub mod email;
+pub mod environment;
+pub mod executable_icon;
+pub mod health_probe;
+pub mod history;
+pub mod hotkeys;
+pub mod inspect;
 mod instance;
+pub mod ipc;
+pub mod isolation_check;
+pub mod known_issues;
+pub mod limit_schedule;
+pub mod macros;
+pub mod migration;
 mod monitor;
+pub mod network_condition;
+pub mod network_limit;
+pub mod orphans;
+pub mod output_log;
+pub mod packaged_apps;
 mod process;
 pub mod profile;
+pub mod profile_template;
+pub mod profiler;
+pub mod recovery;
+pub mod reservation;
 pub mod resource;
+pub mod rolling_restart;
+pub mod scheduler;
 pub mod settings;
+pub mod snapshot;
+pub mod status_detection;
+pub mod usage;
+pub mod virtual_desktop;
+pub mod window_capture;
+pub mod window_layout;
+pub mod window_titles;
 
-pub use app_state::AppState;
-pub use instance::{Instance, InstanceConfig, InstanceId, InstanceStatus};
+pub use app_state::{AppState, HealthEvent, RunLimitEvent};
+pub use custom_theme::CustomTheme;
+pub use instance::{
+    ArgumentVariant, Instance, InstanceConfig, InstanceId, InstanceStatus, WindowState,
+};
+pub use monitor::{UsageSample, MIN_MONITOR_INTERVAL_MS};
 pub use profile::{Profile, ProfileId};
-pub use resource::ResourceLimits;
+pub use resource::{IoPriority, LimitPreset, ResourceLimits};
 pub use settings::Settings;
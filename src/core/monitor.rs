@@ -1,93 +1,406 @@
 //! Resource monitoring - System and process resource tracking
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 
+use chrono::{DateTime, Local};
+
+use super::profiler::SelfUsage;
 use super::resource::{NetworkInterface, ResourceUsage, SystemResources};
 use sysinfo::{
-    CpuRefreshKind, MemoryRefreshKind, Networks, Pid, ProcessRefreshKind, ProcessesToUpdate, System,
+    CpuRefreshKind, MemoryRefreshKind, Networks, Pid, ProcessRefreshKind, ProcessesToUpdate,
+    RefreshKind, System, UpdateKind,
 };
 use tracing::trace;
 
+/// System-wide totals are refreshed once every this many monitor intervals
+const SYSTEM_REFRESH_FACTOR: u32 = 4;
+
+/// Finest per-process sampling interval, and the scheduling wheel's tick
+pub const MIN_MONITOR_INTERVAL_MS: u32 = 100;
+
+/// Slots in the scheduling wheel; longer intervals wrap around for extra rounds
+const WHEEL_SLOTS: usize = 64;
+
+/// A process waiting in a wheel slot
+#[derive(Debug, Clone, Copy)]
+struct WheelEntry {
+    pid: Pid,
+    /// Full turns of the wheel left before it is due
+    rounds: u32,
+    /// Schedule generation the entry was made for; stale entries are dropped
+    generation: u64,
+}
+
+/// Running copies of one executable, counting each copy's root process only
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CopyCount {
+    pub total: usize,
+    /// Copies not started by, or as, a managed process
+    pub external: usize,
+}
+
+/// Whether a process or one of its ancestors is among `pids`
+fn descends_from(
+    processes: &HashMap<Pid, sysinfo::Process>,
+    process: &sysinfo::Process,
+    pids: &HashSet<u32>,
+) -> bool {
+    let mut current = Some(process);
+    // Bounded in case of a parent loop from reused PIDs
+    for _ in 0..64 {
+        let Some(process) = current else {
+            return false;
+        };
+        if pids.contains(&process.pid().as_u32()) {
+            return true;
+        }
+        current = process.parent().and_then(|parent| processes.get(&parent));
+    }
+    false
+}
+
+/// Hashed timing wheel deciding which processes are due for a refresh.
+/// Each process has its own interval, so a tick only touches the processes that are due.
+#[derive(Debug)]
+struct SchedulingWheel {
+    slots: Vec<Vec<WheelEntry>>,
+    /// Slot of the current tick
+    cursor: usize,
+    /// Start of the current tick
+    tick_started: Instant,
+    /// Interval and schedule generation per process
+    schedule: HashMap<Pid, (Duration, u64)>,
+    next_generation: u64,
+}
+
+impl SchedulingWheel {
+    fn new() -> Self {
+        Self {
+            slots: vec![Vec::new(); WHEEL_SLOTS],
+            cursor: 0,
+            tick_started: Instant::now(),
+            schedule: HashMap::new(),
+            next_generation: 0,
+        }
+    }
+
+    fn tick() -> Duration {
+        Duration::from_millis(MIN_MONITOR_INTERVAL_MS as u64)
+    }
+
+    /// Replace the set of scheduled processes. New processes and ones whose interval
+    /// changed are due on the next tick.
+    fn set_schedule(&mut self, processes: &[(Pid, Duration)]) {
+        let wanted: HashMap<Pid, Duration> = processes.iter().copied().collect();
+        self.schedule.retain(|pid, _| wanted.contains_key(pid));
+
+        for (pid, interval) in wanted {
+            if self
+                .schedule
+                .get(&pid)
+                .is_some_and(|(current, _)| *current == interval)
+            {
+                continue;
+            }
+            let generation = self.next_generation;
+            self.next_generation += 1;
+            self.schedule.insert(pid, (interval, generation));
+            self.slots[(self.cursor + 1) % WHEEL_SLOTS].push(WheelEntry {
+                pid,
+                rounds: 0,
+                generation,
+            });
+        }
+    }
+
+    /// Place a process `interval` after the slot at `from`
+    fn insert(&mut self, from: usize, pid: Pid, interval: Duration, generation: u64) {
+        let ticks = (interval.as_millis() / Self::tick().as_millis()).max(1) as usize;
+        self.slots[(from + ticks) % WHEEL_SLOTS].push(WheelEntry {
+            pid,
+            rounds: ((ticks - 1) / WHEEL_SLOTS) as u32,
+            generation,
+        });
+    }
+
+    /// Advance the wheel to `now`, returning the processes that are due
+    fn advance(&mut self, now: Instant) -> Vec<Pid> {
+        let mut due = Vec::new();
+        while now.duration_since(self.tick_started) >= Self::tick() {
+            self.tick_started += Self::tick();
+            self.cursor = (self.cursor + 1) % WHEEL_SLOTS;
+
+            for mut entry in std::mem::take(&mut self.slots[self.cursor]) {
+                let Some(&(interval, generation)) = self.schedule.get(&entry.pid) else {
+                    continue;
+                };
+                if generation != entry.generation {
+                    continue;
+                }
+                if entry.rounds > 0 {
+                    entry.rounds -= 1;
+                    self.slots[self.cursor].push(entry);
+                } else {
+                    due.push(entry.pid);
+                    self.insert(self.cursor, entry.pid, interval, generation);
+                }
+            }
+        }
+        due.sort_unstable();
+        due.dedup();
+        due
+    }
+}
+
+/// How long a process's last sample counts in a sum of usage histories
+const SUM_HOLD: Duration = Duration::from_secs(60);
+
+/// Default length of the per-process usage history
+pub const DEFAULT_HISTORY_MINUTES: u32 = 30;
+
+/// One sample of a process's resource usage
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UsageSample {
+    pub time: DateTime<Local>,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    pub network_rx_rate: u64,
+    pub network_tx_rate: u64,
+}
+
+impl UsageSample {
+    fn new(time: DateTime<Local>, usage: &ResourceUsage) -> Self {
+        Self {
+            time,
+            cpu_percent: usage.cpu_percent,
+            memory_bytes: usage.memory_bytes,
+            network_rx_rate: usage.network_rx_rate,
+            network_tx_rate: usage.network_tx_rate,
+        }
+    }
+}
+
+/// Usage samples of one process over the history window, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct UsageHistory {
+    samples: VecDeque<UsageSample>,
+}
+
+impl UsageHistory {
+    /// Add a sample and drop the ones older than `window`
+    fn record(&mut self, sample: UsageSample, window: Duration) {
+        let oldest = sample.time - chrono::Duration::from_std(window).unwrap_or_default();
+        self.samples.push_back(sample);
+        while self.samples.front().is_some_and(|s| s.time < oldest) {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn samples(&self) -> &VecDeque<UsageSample> {
+        &self.samples
+    }
+
+    /// Usage of several processes added up, one sample per second at which
+    /// any of them was sampled. Each process counts with its latest sample
+    /// for up to `SUM_HOLD`, as processes are sampled at their own intervals.
+    pub fn sum(histories: &[UsageHistory]) -> Self {
+        let mut times: Vec<DateTime<Local>> = histories
+            .iter()
+            .flat_map(|h| h.samples.iter().map(|s| s.time))
+            .collect();
+        times.sort();
+        times.dedup_by_key(|t| t.timestamp());
+
+        let hold = chrono::Duration::from_std(SUM_HOLD).unwrap_or_default();
+        let samples = times
+            .into_iter()
+            .map(|time| {
+                let mut total = UsageSample {
+                    time,
+                    cpu_percent: 0.0,
+                    memory_bytes: 0,
+                    network_rx_rate: 0,
+                    network_tx_rate: 0,
+                };
+                for history in histories {
+                    let index = history.samples.partition_point(|s| s.time <= time);
+                    let Some(sample) = index.checked_sub(1).map(|i| &history.samples[i]) else {
+                        continue;
+                    };
+                    if time - sample.time <= hold {
+                        total.cpu_percent += sample.cpu_percent;
+                        total.memory_bytes += sample.memory_bytes;
+                        total.network_rx_rate += sample.network_rx_rate;
+                        total.network_tx_rate += sample.network_tx_rate;
+                    }
+                }
+                total
+            })
+            .collect();
+        Self { samples }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Whether any sample has network traffic; per-process rates are not measured everywhere
+    pub fn has_network(&self) -> bool {
+        self.samples
+            .iter()
+            .any(|s| s.network_rx_rate > 0 || s.network_tx_rate > 0)
+    }
+}
+
 /// Resource monitor that tracks system and per-process resource usage
 pub struct ResourceMonitor {
     /// System information
     system: System,
     /// Network information
     networks: Networks,
-    /// Last network readings for rate calculation
-    last_network: HashMap<String, (u64, u64, Instant)>,
+    /// Network receive/transmit rates per interface, in bytes/sec
+    network_rates: HashMap<String, (u64, u64)>,
     /// Per-process network tracking (estimated from system delta)
     process_network: HashMap<u32, (u64, u64)>,
-    /// Last update time
-    last_update: Instant,
+    /// Decides which tracked processes are due for a refresh
+    wheel: SchedulingWheel,
+    /// Recent usage of each scheduled process
+    history: HashMap<u32, UsageHistory>,
+    /// How far back the usage history goes
+    history_window: Duration,
+    /// Last system-wide (CPU, memory, network) update time
+    last_system_update: Instant,
     /// Update interval
     update_interval: Duration,
 }
 
 impl ResourceMonitor {
     pub fn new(update_interval_ms: u32) -> Self {
+        // Only load CPU and memory info up front; processes are refreshed on demand
+        let system = System::new_with_specifics(
+            RefreshKind::new()
+                .with_cpu(CpuRefreshKind::everything())
+                .with_memory(MemoryRefreshKind::everything()),
+        );
+
         Self {
-            system: System::new_all(),
+            system,
             networks: Networks::new_with_refreshed_list(),
-            last_network: HashMap::new(),
+            network_rates: HashMap::new(),
             process_network: HashMap::new(),
-            last_update: Instant::now(),
+            wheel: SchedulingWheel::new(),
+            history: HashMap::new(),
+            history_window: Duration::from_secs(DEFAULT_HISTORY_MINUTES as u64 * 60),
+            last_system_update: Instant::now(),
             update_interval: Duration::from_millis(update_interval_ms as u64),
         }
     }
 
-    /// Refresh all system information
-    pub fn refresh(&mut self) {
+    /// Set the processes to monitor, each with its own sampling interval
+    pub fn set_schedule(&mut self, processes: &[(u32, Duration)]) {
+        let processes: Vec<(Pid, Duration)> = processes
+            .iter()
+            .map(|(pid, interval)| (Pid::from_u32(*pid), *interval))
+            .collect();
+        self.wheel.set_schedule(&processes);
+        self.history
+            .retain(|pid, _| processes.iter().any(|(p, _)| p.as_u32() == *pid));
+    }
+
+    /// Keep `minutes` of usage history per process
+    pub fn set_history_minutes(&mut self, minutes: u32) {
+        self.history_window = Duration::from_secs(minutes.max(1) as u64 * 60);
+    }
+
+    /// Recent usage of a process, oldest first
+    pub fn usage_history(&self, pid: u32) -> Option<&UsageHistory> {
+        self.history.get(&pid)
+    }
+
+    /// Refresh the processes that are due, and system-wide totals at a lower frequency.
+    /// Returns the PIDs that were refreshed.
+    pub fn refresh(&mut self) -> Vec<u32> {
         let now = Instant::now();
-        if now.duration_since(self.last_update) < self.update_interval {
-            return;
+        let due = self.wheel.advance(now);
+
+        if !due.is_empty() {
+            self.system.refresh_processes_specifics(
+                ProcessesToUpdate::Some(&due),
+                true,
+                ProcessRefreshKind::new()
+                    .with_cpu()
+                    .with_memory()
+                    .with_disk_usage()
+                    .with_exe(UpdateKind::OnlyIfNotSet)
+                    .with_cmd(UpdateKind::OnlyIfNotSet),
+            );
+            trace!("Refreshed {} processes", due.len());
+
+            let time = Local::now();
+            for pid in &due {
+                if let Some(usage) = self.get_process_usage(pid.as_u32()) {
+                    self.history
+                        .entry(pid.as_u32())
+                        .or_default()
+                        .record(UsageSample::new(time, &usage), self.history_window);
+                }
+            }
         }
 
+        if now.duration_since(self.last_system_update)
+            >= self.update_interval * SYSTEM_REFRESH_FACTOR
+        {
+            self.refresh_system(now);
+        }
+
+        due.into_iter().map(|pid| pid.as_u32()).collect()
+    }
+
+    /// Refresh CPU, memory and network totals
+    fn refresh_system(&mut self, now: Instant) {
         self.system
             .refresh_cpu_specifics(CpuRefreshKind::everything());
         self.system
             .refresh_memory_specifics(MemoryRefreshKind::everything());
-        self.system.refresh_processes_specifics(
-            ProcessesToUpdate::All,
-            true,
-            ProcessRefreshKind::everything(),
-        );
         self.networks.refresh();
 
-        self.last_update = now;
-        trace!("Resource monitor refreshed");
+        // `received()`/`transmitted()` are the bytes since the previous refresh
+        let elapsed = now.duration_since(self.last_system_update).as_secs_f64();
+        if elapsed > 0.0 {
+            self.network_rates = self
+                .networks
+                .iter()
+                .map(|(name, data)| {
+                    let rx_rate = (data.received() as f64 / elapsed) as u64;
+                    let tx_rate = (data.transmitted() as f64 / elapsed) as u64;
+                    (name.clone(), (rx_rate, tx_rate))
+                })
+                .collect();
+        }
+
+        self.last_system_update = now;
     }
 
     /// Get system-wide resource information
     pub fn get_system_resources(&self) -> SystemResources {
         let cpus = self.system.cpus();
 
-        let mut network_interfaces = Vec::new();
-        for (name, data) in self.networks.iter() {
-            let last = self.last_network.get(name);
-            let (rx_rate, tx_rate) = if let Some((last_rx, last_tx, last_time)) = last {
-                let elapsed = last_time.elapsed().as_secs_f64();
-                if elapsed > 0.0 {
-                    let rx_rate = ((data.total_received() - last_rx) as f64 / elapsed) as u64;
-                    let tx_rate = ((data.total_transmitted() - last_tx) as f64 / elapsed) as u64;
-                    (rx_rate, tx_rate)
-                } else {
-                    (0, 0)
+        let network_interfaces = self
+            .networks
+            .iter()
+            .map(|(name, data)| {
+                let (rx_rate, tx_rate) = self.network_rates.get(name).copied().unwrap_or((0, 0));
+                NetworkInterface {
+                    name: name.clone(),
+                    rx_bytes: data.total_received(),
+                    tx_bytes: data.total_transmitted(),
+                    rx_rate,
+                    tx_rate,
                 }
-            } else {
-                (0, 0)
-            };
-
-            network_interfaces.push(NetworkInterface {
-                name: name.clone(),
-                rx_bytes: data.total_received(),
-                tx_bytes: data.total_transmitted(),
-                rx_rate,
-                tx_rate,
-            });
-        }
+            })
+            .collect();
 
         SystemResources {
             cpu_percent: self.system.global_cpu_usage(),
@@ -128,6 +441,10 @@ impl ResourceMonitor {
             thread_count: 0,  // Would need platform-specific code
             gpu_percent: 0.0, // Would need GPU-specific libraries
             gpu_memory_bytes: 0,
+            cpu_time_ms: crate::platform::process_cpu_time(pid)
+                .map(|t| t.as_millis() as u64)
+                .unwrap_or(0),
+            run_time_secs: process.run_time(),
         })
     }
 
@@ -136,8 +453,9 @@ impl ResourceMonitor {
         self.system.process(Pid::from_u32(pid)).is_some()
     }
 
-    /// Get all running process IDs
-    pub fn get_running_pids(&self) -> Vec<u32> {
+    /// Get all running process IDs. Scans every process on the system.
+    pub fn get_running_pids(&mut self) -> Vec<u32> {
+        self.refresh_all_processes();
         self.system
             .processes()
             .keys()
@@ -145,19 +463,9 @@ impl ResourceMonitor {
             .collect()
     }
 
-    /// Refresh and update network rate calculations
-    pub fn update_network_rates(&mut self) {
-        let now = Instant::now();
-        for (name, data) in self.networks.iter() {
-            self.last_network.insert(
-                name.clone(),
-                (data.total_received(), data.total_transmitted(), now),
-            );
-        }
-    }
-
-    /// Get process by name
-    pub fn find_processes_by_name(&self, name: &str) -> Vec<u32> {
+    /// Get process by name. Scans every process on the system.
+    pub fn find_processes_by_name(&mut self, name: &str) -> Vec<u32> {
+        self.refresh_all_processes();
         self.system
             .processes()
             .iter()
@@ -171,6 +479,73 @@ impl ResourceMonitor {
             .collect()
     }
 
+    /// Count running copies of each executable file name (case-insensitive).
+    /// Helper processes started by a copy of the same name, as multi-process
+    /// apps like browsers do, are not copies of their own. Copies descended
+    /// from one of `managed` are not external. Scans every process on the system.
+    pub fn count_processes_by_name(
+        &mut self,
+        names: &[String],
+        managed: &HashSet<u32>,
+    ) -> HashMap<String, CopyCount> {
+        self.refresh_all_processes();
+
+        let processes = self.system.processes();
+        let mut counts: HashMap<String, CopyCount> = names
+            .iter()
+            .map(|n| (n.clone(), CopyCount::default()))
+            .collect();
+        for name in names {
+            let name_lower = name.to_lowercase();
+            let is_copy = |process: &sysinfo::Process| {
+                let process_name = process.name().to_string_lossy().to_lowercase();
+                // Linux reports the name truncated to 15 bytes
+                process_name == name_lower
+                    || (process_name.len() == 15 && name_lower.starts_with(&process_name))
+            };
+
+            for process in processes.values().filter(|p| is_copy(p)) {
+                let helper = process
+                    .parent()
+                    .and_then(|parent| processes.get(&parent))
+                    .is_some_and(&is_copy);
+                if helper {
+                    continue;
+                }
+                let count = counts.entry(name.clone()).or_default();
+                count.total += 1;
+                if !descends_from(processes, process, managed) {
+                    count.external += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    /// Refresh the full process list with the minimum of detail
+    fn refresh_all_processes(&mut self) {
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::new(),
+        );
+    }
+
+    /// Sample CPU and memory of our own process
+    pub fn own_usage(&mut self) -> Option<SelfUsage> {
+        let pid = sysinfo::get_current_pid().ok()?;
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[pid]),
+            false,
+            ProcessRefreshKind::new().with_cpu().with_memory(),
+        );
+        let process = self.system.process(pid)?;
+        Some(SelfUsage {
+            cpu_percent: process.cpu_usage(),
+            memory_bytes: process.memory(),
+        })
+    }
+
     /// Get the command line of a process
     pub fn get_process_command(&self, pid: u32) -> Option<Vec<String>> {
         self.system.process(Pid::from_u32(pid)).map(|p| {
@@ -221,13 +596,44 @@ impl SharedResourceMonitor {
         }
     }
 
-    pub fn refresh(&self) {
+    pub fn refresh(&self) -> Vec<u32> {
+        self.inner
+            .write()
+            .map(|mut m| m.refresh())
+            .unwrap_or_default()
+    }
+
+    pub fn set_schedule(&self, processes: &[(u32, Duration)]) {
+        if let Ok(mut monitor) = self.inner.write() {
+            monitor.set_schedule(processes);
+        }
+    }
+
+    pub fn set_history_minutes(&self, minutes: u32) {
         if let Ok(mut monitor) = self.inner.write() {
-            monitor.refresh();
-            monitor.update_network_rates();
+            monitor.set_history_minutes(minutes);
         }
     }
 
+    pub fn usage_history(&self, pid: u32) -> Option<UsageHistory> {
+        self.inner.read().ok()?.usage_history(pid).cloned()
+    }
+
+    pub fn count_processes_by_name(
+        &self,
+        names: &[String],
+        managed: &HashSet<u32>,
+    ) -> HashMap<String, CopyCount> {
+        self.inner
+            .write()
+            .map(|mut m| m.count_processes_by_name(names, managed))
+            .unwrap_or_default()
+    }
+
+    pub fn own_usage(&self) -> Option<SelfUsage> {
+        self.inner.write().ok()?.own_usage()
+    }
+
     pub fn get_system_resources(&self) -> SystemResources {
         self.inner
             .read()
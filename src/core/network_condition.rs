@@ -0,0 +1,119 @@
+//! Network launch conditions - Hold a launch until the network is in a given state
+
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sysinfo::Networks;
+
+use crate::platform;
+
+/// How long a reachability probe may take
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Port probed when a host is given without one
+const DEFAULT_PORT: u16 = 443;
+
+/// Network state an instance waits for before launching
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NetworkCondition {
+    /// A network interface whose name contains this text is up (e.g. `wg0`, `tun`, `NordLynx`)
+    VpnConnected(String),
+    /// A TCP connection to `host[:port]` succeeds
+    HostReachable(String),
+    /// The machine is on the Wi-Fi network with this SSID
+    WifiSsid(String),
+}
+
+impl NetworkCondition {
+    /// Condition kinds with an empty value, for pickers
+    pub fn all() -> [NetworkCondition; 3] {
+        [
+            Self::VpnConnected(String::new()),
+            Self::HostReachable(String::new()),
+            Self::WifiSsid(String::new()),
+        ]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::VpnConnected(_) => "VPN connected",
+            Self::HostReachable(_) => "Host reachable",
+            Self::WifiSsid(_) => "Wi-Fi network",
+        }
+    }
+
+    /// Hint for the value field
+    pub fn hint(&self) -> &'static str {
+        match self {
+            Self::VpnConnected(_) => "Interface name, e.g. wg0",
+            Self::HostReachable(_) => "host:port, e.g. intranet.local:443",
+            Self::WifiSsid(_) => "SSID",
+        }
+    }
+
+    pub fn value(&self) -> &str {
+        match self {
+            Self::VpnConnected(v) | Self::HostReachable(v) | Self::WifiSsid(v) => v,
+        }
+    }
+
+    pub fn value_mut(&mut self) -> &mut String {
+        match self {
+            Self::VpnConnected(v) | Self::HostReachable(v) | Self::WifiSsid(v) => v,
+        }
+    }
+
+    /// Whether two conditions are the same kind, ignoring the value
+    pub fn same_kind(&self, other: &NetworkCondition) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    /// What the instance is waiting for, for status text
+    pub fn describe(&self) -> String {
+        match self {
+            Self::VpnConnected(name) => format!("VPN interface '{}'", name),
+            Self::HostReachable(host) => format!("{} to be reachable", host),
+            Self::WifiSsid(ssid) => format!("Wi-Fi network '{}'", ssid),
+        }
+    }
+
+    /// Check the condition now. Blocks for up to a couple of seconds on reachability probes.
+    /// An empty value never holds back a launch.
+    pub fn is_met(&self) -> bool {
+        let value = self.value().trim();
+        if value.is_empty() {
+            return true;
+        }
+        match self {
+            Self::VpnConnected(_) => {
+                let value = value.to_lowercase();
+                Networks::new_with_refreshed_list()
+                    .keys()
+                    .any(|name| name.to_lowercase().contains(&value))
+            }
+            Self::HostReachable(_) => host_reachable(value),
+            Self::WifiSsid(_) => platform::current_wifi_ssid()
+                .map(|ssid| ssid == value)
+                .unwrap_or(false),
+        }
+    }
+}
+
+fn host_reachable(target: &str) -> bool {
+    let with_port = if target
+        .rsplit_once(':')
+        .is_some_and(|(_, p)| p.parse::<u16>().is_ok())
+    {
+        target.to_string()
+    } else {
+        format!("{}:{}", target, DEFAULT_PORT)
+    };
+
+    let Ok(addrs) = with_port.to_socket_addrs() else {
+        return false;
+    };
+    addrs
+        .into_iter()
+        .any(|addr| TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT).is_ok())
+}
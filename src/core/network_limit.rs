@@ -0,0 +1,222 @@
+//! Network limit - Caps an instance's bandwidth
+//!
+//! Windows throttles the instance's outbound traffic with a QoS policy in the
+//! active (non-persistent) policy store. QoS policies match the executable name
+//! rather than the process, so a limit is refused while another process runs the
+//! same executable, and lifted when another instance of it launches or the copy
+//! scan finds a copy we did not launch.
+//! macOS sends the instance's traffic through a dummynet pipe, with pf rules on
+//! the local ports it has open. Both need administrator rights.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+use tracing::{debug, info, warn};
+
+use crate::platform;
+
+/// Whether an instance's network limit is in effect
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LimitStatus {
+    Applying,
+    Enforced,
+    Failed(String),
+    /// Not applied because this many other processes run the same executable,
+    /// and the limit would throttle them too
+    Shared(usize),
+}
+
+/// A limit applied to a running process
+struct Limit {
+    kbps: u64,
+    status: LimitStatus,
+    executable: PathBuf,
+}
+
+/// Limits applied to running processes, by PID
+static LIMITS: LazyLock<Mutex<HashMap<u32, Limit>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Whether the platform limits every process of an executable name rather than one PID
+const LIMITS_BY_NAME: bool = cfg!(windows);
+
+/// Cap `pid` at `kbps` KB/s on a background thread, as the OS tools are slow,
+/// replacing any limit it already has; 0 lifts the limit.
+/// Failures are only logged and reported by `status`; they never fail a launch.
+pub fn apply(pid: u32, executable: &Path, kbps: u64) {
+    if kbps == 0 {
+        remove(pid);
+        return;
+    }
+    if let Ok(mut limits) = LIMITS.lock() {
+        limits.insert(
+            pid,
+            Limit {
+                kbps,
+                status: LimitStatus::Applying,
+                executable: executable.to_path_buf(),
+            },
+        );
+    }
+
+    let executable = executable.to_path_buf();
+    std::thread::spawn(move || {
+        let sharing = if LIMITS_BY_NAME {
+            sharing_processes(pid, &executable)
+        } else {
+            0
+        };
+        let status = if sharing > 0 {
+            let _ = platform::clear_network_limit(pid);
+            warn!(
+                "Network limit not applied to PID {}: {} other process(es) run the same executable",
+                pid, sharing
+            );
+            LimitStatus::Shared(sharing)
+        } else {
+            match platform::set_network_limit(pid, &executable, kbps) {
+                Ok(()) => {
+                    info!("Limited PID {} to {} KB/s of network traffic", pid, kbps);
+                    LimitStatus::Enforced
+                }
+                Err(e) => {
+                    warn!("Network limit not applied to PID {}: {:#}", pid, e);
+                    LimitStatus::Failed(format!("{:#}", e))
+                }
+            }
+        };
+        let lifted = match LIMITS.lock() {
+            Ok(mut limits) => match limits.get_mut(&pid) {
+                // Another copy launched while the limit was being applied
+                Some(entry) if matches!(entry.status, LimitStatus::Shared(_)) => true,
+                Some(entry) => {
+                    entry.status = status.clone();
+                    false
+                }
+                // The process stopped while the limit was being applied
+                None => true,
+            },
+            Err(_) => false,
+        };
+        if lifted && status == LimitStatus::Enforced {
+            let _ = platform::clear_network_limit(pid);
+        }
+    });
+}
+
+/// The limit applied to `pid` and whether it is in effect
+pub fn status(pid: u32) -> Option<(u64, LimitStatus)> {
+    LIMITS
+        .lock()
+        .ok()?
+        .get(&pid)
+        .map(|limit| (limit.kbps, limit.status.clone()))
+}
+
+/// A process was launched from `executable`. Where limits match executable
+/// names, lift the limits of other processes of that name, which would
+/// otherwise throttle the new one too.
+pub fn process_started(pid: u32, executable: &Path) {
+    if !LIMITS_BY_NAME {
+        return;
+    }
+    let Ok(mut limits) = LIMITS.lock() else {
+        return;
+    };
+    for (&other, limit) in limits.iter_mut() {
+        if other == pid || !same_name(&limit.executable, executable) {
+            continue;
+        }
+        if limit.status == LimitStatus::Enforced {
+            std::thread::spawn(move || {
+                if let Err(e) = platform::clear_network_limit(other) {
+                    debug!("Failed to remove network limit of PID {}: {:#}", other, e);
+                }
+            });
+            info!(
+                "Lifted network limit of PID {}: PID {} runs the same executable",
+                other, pid
+            );
+        }
+        limit.status = match limit.status {
+            LimitStatus::Shared(count) => LimitStatus::Shared(count + 1),
+            _ => LimitStatus::Shared(1),
+        };
+    }
+}
+
+/// The copy scan found `external` processes of `executable` that we did not
+/// launch. Where limits match executable names, lift the limits of that name,
+/// which would otherwise throttle those copies too.
+pub fn external_copies_found(executable: &Path, external: usize) {
+    if !LIMITS_BY_NAME || external == 0 {
+        return;
+    }
+    let Ok(mut limits) = LIMITS.lock() else {
+        return;
+    };
+    for (&pid, limit) in limits.iter_mut() {
+        if !same_name(&limit.executable, executable)
+            || matches!(limit.status, LimitStatus::Shared(_))
+        {
+            continue;
+        }
+        if limit.status == LimitStatus::Enforced {
+            std::thread::spawn(move || {
+                if let Err(e) = platform::clear_network_limit(pid) {
+                    debug!("Failed to remove network limit of PID {}: {:#}", pid, e);
+                }
+            });
+            info!(
+                "Lifted network limit of PID {}: {} process(es) we did not launch run the same executable",
+                pid, external
+            );
+        }
+        limit.status = LimitStatus::Shared(external);
+    }
+}
+
+/// Whether two executables have the same file name, as QoS policies compare them
+fn same_name(a: &Path, b: &Path) -> bool {
+    match (a.file_name(), b.file_name()) {
+        (Some(a), Some(b)) => a
+            .to_string_lossy()
+            .eq_ignore_ascii_case(&b.to_string_lossy()),
+        _ => false,
+    }
+}
+
+/// Running processes other than `pid` with the same executable file name
+fn sharing_processes(pid: u32, executable: &Path) -> usize {
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::new().with_exe(UpdateKind::OnlyIfNotSet),
+    );
+    system
+        .processes()
+        .iter()
+        .filter(|(other, _)| other.as_u32() != pid)
+        .filter(|(_, process)| match process.exe() {
+            Some(exe) => same_name(exe, executable),
+            None => same_name(Path::new(process.name()), executable),
+        })
+        .count()
+}
+
+/// Remove the limit of a process that is stopping or has exited
+pub fn remove(pid: u32) {
+    let Some(Limit { status, .. }) = LIMITS.lock().ok().and_then(|mut l| l.remove(&pid)) else {
+        return;
+    };
+    if status == LimitStatus::Enforced {
+        std::thread::spawn(move || {
+            if let Err(e) = platform::clear_network_limit(pid) {
+                debug!("Failed to remove network limit of PID {}: {:#}", pid, e);
+            }
+        });
+    }
+}
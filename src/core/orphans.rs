@@ -0,0 +1,97 @@
+//! Orphan detection - Processes left running in our instance data directories
+
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+use uuid::Uuid;
+
+use super::instance::InstanceId;
+
+/// Environment variables the isolation setup points into an instance data directory
+const ISOLATION_VARS: &[&str] = &[
+    "APPDATA",
+    "LOCALAPPDATA",
+    "USERPROFILE",
+    "HOME",
+    "XDG_DATA_HOME",
+    "XDG_CONFIG_HOME",
+    "XDG_CACHE_HOME",
+];
+
+/// A process using an instance data directory that no instance is tracking
+#[derive(Debug, Clone)]
+pub struct OrphanProcess {
+    pub pid: u32,
+    /// Process name
+    pub name: String,
+    /// Executable path, when readable
+    pub exe: Option<PathBuf>,
+    /// Instance data directory the process points into
+    pub data_dir: PathBuf,
+    /// Instance that owned the data directory, when it is one of ours by ID
+    pub instance_id: Option<InstanceId>,
+}
+
+/// Find untracked processes whose environment or command line points into `instances_dir`
+pub fn scan(instances_dir: &Path, tracked_pids: &[u32]) -> Vec<OrphanProcess> {
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::new()
+            .with_exe(UpdateKind::Always)
+            .with_cmd(UpdateKind::Always)
+            .with_environ(UpdateKind::Always),
+    );
+
+    let own_pid = std::process::id();
+    system
+        .processes()
+        .iter()
+        .filter(|(pid, _)| pid.as_u32() != own_pid && !tracked_pids.contains(&pid.as_u32()))
+        .filter_map(|(pid, process)| {
+            let from_env = process.environ().iter().filter_map(|entry| {
+                let (key, value) = entry.to_str()?.split_once('=')?;
+                ISOLATION_VARS
+                    .iter()
+                    .any(|v| v.eq_ignore_ascii_case(key))
+                    .then(|| PathBuf::from(value))
+            });
+            // Arguments may carry the path as `--flag=path`
+            let from_cmd = process.cmd().iter().filter_map(|arg| {
+                let arg = arg.to_str()?;
+                Some(PathBuf::from(
+                    arg.split_once('=').map(|(_, value)| value).unwrap_or(arg),
+                ))
+            });
+
+            let data_dir = from_env
+                .chain(from_cmd)
+                .find_map(|path| instance_data_dir(instances_dir, &path))?;
+
+            Some(OrphanProcess {
+                pid: pid.as_u32(),
+                name: process.name().to_string_lossy().to_string(),
+                exe: process.exe().map(Path::to_path_buf),
+                instance_id: instance_id_of(&data_dir),
+                data_dir,
+            })
+        })
+        .collect()
+}
+
+/// The instance data directory (`instances_dir/<id>`) containing `path`, if any
+fn instance_data_dir(instances_dir: &Path, path: &Path) -> Option<PathBuf> {
+    let relative = path.strip_prefix(instances_dir).ok()?;
+    let first = relative.components().next()?;
+    Some(instances_dir.join(first))
+}
+
+fn instance_id_of(data_dir: &Path) -> Option<InstanceId> {
+    data_dir
+        .file_name()
+        .and_then(OsStr::to_str)
+        .and_then(|name| Uuid::parse_str(name).ok())
+        .map(InstanceId)
+}
@@ -0,0 +1,265 @@
+//! Instance logs - Writes an instance's lifecycle events and captured stdout
+//! and stderr to rotating log files
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process::Child;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, SystemTime};
+
+use anyhow::Result;
+use tracing::warn;
+
+use super::instance::InstanceId;
+
+/// Size at which the current log is rotated
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Rotated logs kept next to the current one
+const ROTATED_LOGS: usize = 3;
+
+/// Most of the log the viewer reads from the end of the file
+const TAIL_BYTES: u64 = 512 * 1024;
+
+/// Marker after the timestamp of lines written to stderr
+pub const STDERR_MARKER: &str = "[err]";
+
+/// Marker after the timestamp of lines written to stdout
+pub const STDOUT_MARKER: &str = "[out]";
+
+/// Marker after the timestamp of the line MultiInstance writes at each launch
+pub const LAUNCH_MARKER: &str = "[launch]";
+
+/// Marker after the timestamp of lifecycle events such as `stopped` or `crashed`
+pub const EVENT_MARKER: &str = "[event]";
+
+/// Logs open for writing, so a capture and the events written meanwhile share
+/// one file handle and rotate together
+static OPEN_LOGS: Mutex<Vec<(PathBuf, Weak<Mutex<RotatingLog>>)>> = Mutex::new(Vec::new());
+
+/// Current output log of an instance
+pub fn log_path(logs_dir: &Path, id: InstanceId) -> PathBuf {
+    logs_dir.join("instances").join(format!("{}.log", id.0))
+}
+
+/// Rotated log `n` (1 = most recent) of an instance
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    path.with_extension(format!("{}.log", n))
+}
+
+/// Log file that rotates once it grows past `MAX_LOG_BYTES`
+struct RotatingLog {
+    path: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl RotatingLog {
+    fn open(path: PathBuf) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self { path, file, size })
+    }
+
+    fn write_line(&mut self, marker: &str, line: &str) -> Result<()> {
+        if self.size >= MAX_LOG_BYTES {
+            self.rotate()?;
+        }
+        let entry = format!(
+            "{} {} {}\n",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+            marker,
+            line
+        );
+        self.file.write_all(entry.as_bytes())?;
+        self.size += entry.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> Result<()> {
+        for n in (1..ROTATED_LOGS).rev() {
+            let from = rotated_path(&self.path, n);
+            if from.exists() {
+                std::fs::rename(&from, rotated_path(&self.path, n + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+/// Log at `path`, reusing the one a running capture writes to
+fn shared_log(path: &Path) -> Result<Arc<Mutex<RotatingLog>>> {
+    let mut open = OPEN_LOGS
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Open logs lock poisoned: {}", e))?;
+    open.retain(|(_, log)| log.strong_count() > 0);
+    if let Some(log) = open
+        .iter()
+        .find(|(p, _)| p == path)
+        .and_then(|(_, log)| log.upgrade())
+    {
+        return Ok(log);
+    }
+    let log = Arc::new(Mutex::new(RotatingLog::open(path.to_path_buf())?));
+    open.push((path.to_path_buf(), Arc::downgrade(&log)));
+    Ok(log)
+}
+
+/// Whether a capture is still writing to the log at `path`
+fn is_open(path: &Path) -> bool {
+    OPEN_LOGS.lock().is_ok_and(|open| {
+        open.iter()
+            .any(|(p, log)| p == path && log.strong_count() > 0)
+    })
+}
+
+/// Copy the child's piped stdout and stderr into its log on background threads,
+/// after a line recording the launch's correlation ID and PID
+pub fn capture(child: &mut Child, path: PathBuf, launch_id: &str) -> Result<()> {
+    let log = shared_log(&path)?;
+    log.lock()
+        .map_err(|e| anyhow::anyhow!("Output log lock poisoned: {}", e))?
+        .write_line(
+            LAUNCH_MARKER,
+            &format!("launch={} pid={}", launch_id, child.id()),
+        )?;
+    if let Some(stdout) = child.stdout.take() {
+        pump(stdout, STDOUT_MARKER, Arc::clone(&log));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        pump(stderr, STDERR_MARKER, log);
+    }
+    Ok(())
+}
+
+/// Read lines until the stream closes, which happens when the process exits
+fn pump(stream: impl Read + Send + 'static, marker: &'static str, log: Arc<Mutex<RotatingLog>>) {
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(stream);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) => break,
+                Ok(_) => {
+                    let line = String::from_utf8_lossy(&buf);
+                    let line = line.trim_end_matches(['\r', '\n']);
+                    let Ok(mut log) = log.lock() else {
+                        break;
+                    };
+                    if let Err(e) = log.write_line(marker, line) {
+                        warn!("Failed to write output log {}: {}", log.path.display(), e);
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+    });
+}
+
+/// Append a lifecycle event, tagged with the correlation ID of its launch
+pub fn write_event(
+    path: &Path,
+    event: &str,
+    launch_id: Option<&str>,
+    details: Option<&str>,
+) -> Result<()> {
+    let mut line = event.to_string();
+    if let Some(launch_id) = launch_id {
+        line.push_str(&format!(" launch={}", launch_id));
+    }
+    if let Some(details) = details {
+        line.push_str(&format!(" ({})", details));
+    }
+    shared_log(path)?
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Output log lock poisoned: {}", e))?
+        .write_line(EVENT_MARKER, &line)
+}
+
+/// Delete instance logs, current or rotated, last written more than `days` days
+/// ago. Logs a capture still writes to are kept.
+pub fn prune(logs_dir: &Path, days: u32) -> Result<usize> {
+    let dir = logs_dir.join("instances");
+    if !dir.exists() {
+        return Ok(0);
+    }
+    let cutoff = SystemTime::now() - Duration::from_secs(days as u64 * 24 * 60 * 60);
+
+    let mut removed = 0;
+    for entry in std::fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("log") || is_open(&path) {
+            continue;
+        }
+        let stale = std::fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .is_ok_and(|modified| modified < cutoff);
+        if stale {
+            match std::fs::remove_file(&path) {
+                Ok(()) => removed += 1,
+                Err(e) => warn!("Failed to delete old log {}: {}", path.display(), e),
+            }
+        }
+    }
+    Ok(removed)
+}
+
+/// Last lines of the current log, oldest first
+pub fn read_tail(path: &Path, max_lines: usize) -> Result<Vec<String>> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    let start = len.saturating_sub(TAIL_BYTES);
+    file.seek(SeekFrom::Start(start))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+
+    let text = String::from_utf8_lossy(&bytes);
+    let mut lines: Vec<&str> = text.lines().collect();
+    // The first line is likely cut off when reading from the middle
+    if start > 0 && !lines.is_empty() {
+        lines.remove(0);
+    }
+    let skip = lines.len().saturating_sub(max_lines);
+    Ok(lines[skip..].iter().map(|l| l.to_string()).collect())
+}
+
+/// Empty an instance's current log and delete the rotated ones. The current
+/// log is truncated rather than deleted so a running capture keeps writing to it.
+pub fn clear(path: &Path) -> Result<()> {
+    remove_rotated(path)?;
+    if path.exists() {
+        File::create(path)?;
+    }
+    Ok(())
+}
+
+/// Delete an instance's current and rotated logs
+pub fn remove(path: &Path) -> Result<()> {
+    remove_rotated(path)?;
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+fn remove_rotated(path: &Path) -> Result<()> {
+    for n in 1..=ROTATED_LOGS {
+        let rotated = rotated_path(path, n);
+        if rotated.exists() {
+            std::fs::remove_file(rotated)?;
+        }
+    }
+    Ok(())
+}
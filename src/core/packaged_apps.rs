@@ -0,0 +1,72 @@
+//! Packaged apps - Microsoft Store (UWP) apps launched by their AppUserModelID
+//!
+//! Store apps have no executable that can be started directly. They are usually
+//! opened as `explorer.exe shell:AppsFolder\<AUMID>`, which leaves only the
+//! short-lived explorer process to track, so they are activated through the
+//! application activation manager instead, which reports the PID of the app itself.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+use serde::Deserialize;
+use tracing::warn;
+
+use crate::platform;
+
+/// Prefix of the shell target that opens a Store app
+pub const APPS_FOLDER_PREFIX: &str = "shell:AppsFolder\\";
+
+/// An installed Store app
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PackagedApp {
+    #[serde(rename = "Name")]
+    pub name: String,
+    /// AppUserModelID, e.g. `Microsoft.WindowsCalculator_8wekyb3d8bbwe!App`
+    #[serde(rename = "AppID")]
+    pub app_id: String,
+}
+
+impl PackagedApp {
+    /// Executable path that launches the app
+    pub fn executable_path(&self) -> PathBuf {
+        PathBuf::from(format!("{}{}", APPS_FOLDER_PREFIX, self.app_id))
+    }
+}
+
+/// The AUMID named by a `shell:AppsFolder\<AUMID>` target
+pub fn app_id(target: &str) -> Option<&str> {
+    let prefix = target.get(..APPS_FOLDER_PREFIX.len())?;
+    if !prefix.eq_ignore_ascii_case(APPS_FOLDER_PREFIX) {
+        return None;
+    }
+    Some(&target[APPS_FOLDER_PREFIX.len()..]).filter(|id| !id.is_empty())
+}
+
+static INSTALLED: LazyLock<Mutex<Option<Vec<PackagedApp>>>> = LazyLock::new(|| Mutex::new(None));
+static LISTING: AtomicBool = AtomicBool::new(false);
+
+/// Installed Store apps sorted by name, or None while they are listed in the background
+pub fn installed() -> Option<Vec<PackagedApp>> {
+    if let Some(apps) = INSTALLED.lock().ok()?.as_ref() {
+        return Some(apps.clone());
+    }
+    if !LISTING.swap(true, Ordering::SeqCst) {
+        std::thread::spawn(|| {
+            let apps = match platform::list_packaged_apps() {
+                Ok(mut apps) => {
+                    apps.sort_by_key(|a| a.name.to_lowercase());
+                    apps
+                }
+                Err(e) => {
+                    warn!("Failed to list Store apps: {}", e);
+                    Vec::new()
+                }
+            };
+            if let Ok(mut installed) = INSTALLED.lock() {
+                *installed = Some(apps);
+            }
+        });
+    }
+    None
+}
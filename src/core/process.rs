@@ -1,14 +1,23 @@
 //! Process management - Spawning and controlling processes
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
 use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 use tracing::{error, info, warn};
 
-use super::instance::{Instance, InstanceConfig, InstanceId, InstanceStatus};
+use super::app_container::{self, IsolationLevel};
+use super::architecture;
+use super::cache_links;
+use super::cpu_throttle;
+use super::cpu_topology::{AffinityPreset, CpuTopology};
+use super::environment::{self, EnvVar};
+use super::instance::{Instance, InstanceConfig, InstanceId, InstanceStatus, WindowState};
+use super::network_limit;
+use super::output_log;
 use super::resource::ResourceLimits;
 use crate::platform;
 
@@ -18,10 +27,14 @@ pub struct ProcessManager {
     children: HashMap<InstanceId, Child>,
     /// Instance data directory base path
     instance_data_dir: PathBuf,
+    /// Directory captured output logs are written to
+    logs_dir: PathBuf,
+    /// Next core handed out by the "one P-core each" affinity preset
+    next_core_slot: usize,
 }
 
 impl ProcessManager {
-    pub fn new(instance_data_dir: PathBuf) -> Self {
+    pub fn new(instance_data_dir: PathBuf, logs_dir: PathBuf) -> Self {
         // Ensure the data directory exists
         if let Err(e) = std::fs::create_dir_all(&instance_data_dir) {
             error!("Failed to create instance data directory: {}", e);
@@ -30,17 +43,26 @@ impl ProcessManager {
         Self {
             children: HashMap::new(),
             instance_data_dir,
+            logs_dir,
+            next_core_slot: 0,
         }
     }
 
     /// Spawn a new instance
     pub fn spawn(&mut self, instance: &mut Instance) -> Result<()> {
+        let launch_id = new_launch_id();
+        instance.launch_id = Some(launch_id.clone());
+        let _launch = instance.launch_span().entered();
         let config = &instance.config;
         info!(
             "Spawning instance '{}' from {:?}",
             config.name, config.executable_path
         );
 
+        if let Some((app_id, args)) = config.packaged_app() {
+            return self.spawn_packaged_app(instance, &app_id, &args);
+        }
+
         // Validate executable exists
         if !config.executable_path.exists() {
             anyhow::bail!("Executable not found: {}", config.executable_path.display());
@@ -50,7 +72,7 @@ impl ProcessManager {
         let data_dir = self.get_or_create_instance_data_dir(instance.id, config)?;
 
         // Build the command
-        let mut cmd = Command::new(&config.executable_path);
+        let mut cmd = build_command(config)?;
 
         // Set working directory
         if let Some(ref work_dir) = config.working_directory {
@@ -60,17 +82,23 @@ impl ProcessManager {
         }
 
         // Add arguments
-        cmd.args(&config.arguments);
+        cmd.args(config.launch_arguments());
 
-        // Set up environment for isolation (only if enabled)
-        // Note: Disable this for games with anti-cheat
-        if config.bypass_single_instance && config.use_environment_isolation {
-            self.setup_isolation_env(&mut cmd, &data_dir, config);
+        // Link well-known cache folders to the shared store
+        if config.use_environment_isolation && !config.shared_cache_patterns.is_empty() {
+            let store_dir = self.shared_cache_dir(config);
+            cache_links::link_shared_caches(&data_dir, &store_dir, &config.shared_cache_patterns);
         }
 
-        // Add custom environment variables
-        for (key, value) in &config.environment {
-            cmd.env(key, value);
+        self.apply_environment(&mut cmd, &data_dir, config);
+        let spawn_environment = environment::resolve(&data_dir, config);
+
+        if config.isolation_level == IsolationLevel::AppContainer {
+            let pid =
+                self.spawn_in_app_container(instance.id, config, &data_dir, &spawn_environment)?;
+            info!("Spawned process with PID {} in its AppContainer", pid);
+            self.track_without_child(instance, pid, spawn_environment);
+            return Ok(());
         }
 
         // Detach from our process group
@@ -83,28 +111,56 @@ impl ProcessManager {
         #[cfg(unix)]
         {
             use std::os::unix::process::CommandExt;
+            #[cfg(target_os = "macos")]
+            let io_priority = config.resource_limits.io_priority;
             unsafe {
-                cmd.pre_exec(|| {
+                cmd.pre_exec(move || {
                     libc::setsid();
+                    #[cfg(target_os = "macos")]
+                    platform::macos::set_own_io_priority(io_priority);
                     Ok(())
                 });
             }
         }
 
-        // Spawn the process
-        let child = cmd
+        // Remember what has focus so a background launch can hand it back
+        let previous_focus = if config.launch_in_background {
+            platform::foreground_window()
+        } else {
+            None
+        };
+
+        // Spawn the process, piping its output into the log when captured
+        let output = || {
+            if config.capture_output {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            }
+        };
+        let mut child = cmd
             .stdin(Stdio::null())
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
+            .stdout(output())
+            .stderr(output())
             .spawn()
             .context("Failed to spawn process")?;
 
         let pid = child.id();
         info!("Spawned process with PID {}", pid);
 
+        if config.capture_output {
+            let path = output_log::log_path(&self.logs_dir, instance.id);
+            if let Err(e) = output_log::capture(&mut child, path, &launch_id) {
+                warn!("Failed to capture output: {}", e);
+            }
+        }
+
         // Apply resource limits
+        network_limit::process_started(pid, &config.executable_path);
         if config.resource_limits.has_limits() {
-            if let Err(e) = self.apply_resource_limits(pid, &config.resource_limits) {
+            if let Err(e) =
+                self.apply_resource_limits(pid, &config.executable_path, &config.resource_limits)
+            {
                 warn!("Failed to apply resource limits: {}", e);
             }
         }
@@ -114,7 +170,9 @@ impl ProcessManager {
         if config.bypass_single_instance {
             info!("Closing singleton handles for multi-instance support");
             let pid_copy = pid;
+            let span = tracing::Span::current();
             std::thread::spawn(move || {
+                let _launch = span.entered();
                 if let Err(e) = platform::windows::close_singleton_handles(pid_copy) {
                     warn!("Failed to close singleton handles: {}", e);
                 }
@@ -126,15 +184,46 @@ impl ProcessManager {
         if config.hide_from_taskbar {
             info!("Hiding instance from taskbar");
             let pid_copy = pid;
+            let span = tracing::Span::current();
             std::thread::spawn(move || {
+                let _launch = span.entered();
                 if let Err(e) = platform::windows::hide_process_from_taskbar(pid_copy) {
                     warn!("Failed to hide from taskbar: {}", e);
                 }
             });
         }
 
+        if config.start_window_state != WindowState::Normal {
+            let state = config.start_window_state;
+            let span = tracing::Span::current();
+            std::thread::spawn(move || {
+                let _launch = span.entered();
+                if let Err(e) = platform::apply_window_state(pid, state) {
+                    warn!(
+                        "Failed to start PID {} {}: {}",
+                        pid,
+                        state.label().to_lowercase(),
+                        e
+                    );
+                }
+            });
+        }
+        move_to_virtual_desktop(pid, config);
+
+        if let Some(previous) = previous_focus {
+            let span = tracing::Span::current();
+            std::thread::spawn(move || {
+                let _launch = span.entered();
+                if let Err(e) = platform::keep_focus_from(pid, previous) {
+                    warn!("Failed to keep focus from PID {}: {}", pid, e);
+                }
+            });
+        }
+
         // Update instance state
         instance.mark_starting(pid);
+        instance.process_bits = platform::process_bitness(pid);
+        instance.spawn_environment = spawn_environment;
 
         // Store child handle
         self.children.insert(instance.id, child);
@@ -142,16 +231,117 @@ impl ProcessManager {
         Ok(())
     }
 
+    /// Start the process sandboxed in the instance's AppContainer, which may
+    /// write to its data and working directories and read the executable's folder.
+    /// Output capture needs a child handle, so it does not apply.
+    fn spawn_in_app_container(
+        &self,
+        id: InstanceId,
+        config: &InstanceConfig,
+        data_dir: &Path,
+        environment: &[EnvVar],
+    ) -> Result<u32> {
+        let exe_dir = config.executable_path.parent().map(Path::to_path_buf);
+        let working_dir = config
+            .working_directory
+            .clone()
+            .or_else(|| exe_dir.clone())
+            .unwrap_or_else(|| data_dir.to_path_buf());
+
+        let mut command = vec![config.executable_path.to_string_lossy().to_string()];
+        command.extend(config.launch_arguments());
+        let environment: Vec<(String, String)> = environment
+            .iter()
+            .map(|v| (v.key.clone(), v.value.clone()))
+            .collect();
+        let capabilities: Vec<&str> = config
+            .app_container_capabilities
+            .iter()
+            .map(|c| c.sid())
+            .collect();
+        let mut writable = vec![data_dir.to_path_buf()];
+        writable.extend(config.working_directory.clone());
+
+        platform::spawn_in_app_container(
+            &app_container::container_name(id),
+            &command,
+            &working_dir,
+            &environment,
+            &capabilities,
+            &writable,
+            &exe_dir.into_iter().collect::<Vec<_>>(),
+        )
+    }
+
+    /// Activate a Store app. It runs in its own container, so the data
+    /// directory, environment and output capture do not apply to it.
+    fn spawn_packaged_app(
+        &mut self,
+        instance: &mut Instance,
+        app_id: &str,
+        args: &[String],
+    ) -> Result<()> {
+        let arguments: Vec<String> = args
+            .iter()
+            .map(|arg| {
+                if arg.contains(char::is_whitespace) {
+                    format!("\"{}\"", arg)
+                } else {
+                    arg.clone()
+                }
+            })
+            .collect();
+        let pid = platform::launch_packaged_app(app_id, &arguments.join(" "))?;
+        info!("Activated {} with PID {}", app_id, pid);
+        self.track_without_child(instance, pid, Vec::new());
+        Ok(())
+    }
+
+    /// Finish a launch made without a child handle, so exits are noticed by PID
+    fn track_without_child(&mut self, instance: &mut Instance, pid: u32, environment: Vec<EnvVar>) {
+        let config = &instance.config;
+        network_limit::process_started(pid, &config.executable_path);
+        if config.resource_limits.has_limits() {
+            if let Err(e) =
+                self.apply_resource_limits(pid, &config.executable_path, &config.resource_limits)
+            {
+                warn!("Failed to apply resource limits: {}", e);
+            }
+        }
+
+        if config.start_window_state != WindowState::Normal {
+            let state = config.start_window_state;
+            let span = tracing::Span::current();
+            std::thread::spawn(move || {
+                let _launch = span.entered();
+                if let Err(e) = platform::apply_window_state(pid, state) {
+                    warn!("Failed to apply window state to PID {}: {}", pid, e);
+                }
+            });
+        }
+        move_to_virtual_desktop(pid, config);
+
+        instance.mark_starting(pid);
+        instance.process_bits = platform::process_bitness(pid);
+        instance.spawn_environment = environment;
+    }
+
     /// Stop an instance
     pub fn stop(&mut self, instance: &mut Instance) -> Result<()> {
+        let _launch = instance.launch_span().entered();
         info!("Stopping instance '{}'", instance.config.name);
 
         if let Some(pid) = instance.pid {
-            // Try graceful termination first
-            if let Err(e) = platform::terminate_process(pid) {
-                warn!("Graceful termination failed: {}, forcing kill", e);
-                platform::kill_process(pid)?;
-            }
+            end_process(pid)?;
+        }
+        self.release(instance);
+        Ok(())
+    }
+
+    /// Drop what we hold for an instance whose process has ended and mark it stopped
+    pub fn release(&mut self, instance: &mut Instance) {
+        if let Some(pid) = instance.pid {
+            network_limit::remove(pid);
 
             // Clean up job handle on Windows
             #[cfg(windows)]
@@ -165,17 +355,53 @@ impl ProcessManager {
 
         // Update instance state
         instance.mark_stopped();
+    }
 
-        Ok(())
+    /// The instance's stop command, ready to run with the instance's working
+    /// directory and environment so it reaches the app; None without one
+    pub fn stop_command(&mut self, instance: &Instance) -> Option<Command> {
+        let config = &instance.config;
+        let pid = instance.pid?;
+        if config.stop_command.trim().is_empty() {
+            return None;
+        }
+        let command = config.stop_command.replace("{pid}", &pid.to_string());
+        info!("Running stop command for '{}': {}", config.name, command);
+
+        let mut cmd = shell_command(&command);
+        if let Some(ref work_dir) = config.working_directory {
+            cmd.current_dir(work_dir);
+        } else if let Some(parent) = config.executable_path.parent().filter(|p| p.is_dir()) {
+            cmd.current_dir(parent);
+        }
+        match self.get_or_create_instance_data_dir(instance.id, config) {
+            Ok(data_dir) => self.apply_environment(&mut cmd, &data_dir, config),
+            Err(e) => warn!("Failed to resolve instance data directory: {}", e),
+        }
+        cmd.stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        Some(cmd)
+    }
+
+    /// Whether an instance's process has exited
+    pub fn has_exited(&mut self, id: InstanceId, pid: u32) -> bool {
+        match self.children.get_mut(&id) {
+            Some(child) => !matches!(child.try_wait(), Ok(None)),
+            None => !platform::is_process_running(pid),
+        }
     }
 
     /// Force kill an instance
     pub fn kill(&mut self, instance: &mut Instance) -> Result<()> {
+        let _launch = instance.launch_span().entered();
         info!("Killing instance '{}'", instance.config.name);
 
         if let Some(pid) = instance.pid {
             platform::kill_process(pid)?;
 
+            network_limit::remove(pid);
+
             // Clean up job handle on Windows
             #[cfg(windows)]
             {
@@ -192,6 +418,8 @@ impl ProcessManager {
     /// Pause/suspend an instance (if supported)
     pub fn pause(&mut self, instance: &mut Instance) -> Result<()> {
         if let Some(pid) = instance.pid {
+            // Keep a duty-cycling CPU throttle from resuming it
+            cpu_throttle::hold(pid, true);
             platform::suspend_process(pid)?;
             instance.mark_paused();
         }
@@ -202,17 +430,57 @@ impl ProcessManager {
     pub fn resume(&mut self, instance: &mut Instance) -> Result<()> {
         if let Some(pid) = instance.pid {
             platform::resume_process(pid)?;
+            cpu_throttle::hold(pid, false);
             instance.mark_running();
         }
         Ok(())
     }
 
+    /// Write an instance to disk and stop it (experimental)
+    pub fn checkpoint(&mut self, instance: &mut Instance, images_dir: &Path) -> Result<()> {
+        let pid = instance.pid.context("Instance has no running process")?;
+        info!("Checkpointing instance '{}'", instance.config.name);
+
+        platform::checkpoint_process(pid, images_dir)?;
+
+        // The dump ends the process, reap it so it is not reported as a crash
+        if let Some(mut child) = self.children.remove(&instance.id) {
+            let _ = child.wait();
+        }
+        instance.mark_stopped();
+
+        Ok(())
+    }
+
+    /// Restore an instance from a checkpoint written by `checkpoint`
+    pub fn restore(&mut self, instance: &mut Instance, images_dir: &Path) -> Result<()> {
+        info!(
+            "Restoring instance '{}' from checkpoint",
+            instance.config.name
+        );
+
+        let pid = platform::restore_process(images_dir)?;
+        instance.mark_starting(pid);
+
+        Ok(())
+    }
+
     /// Check if a child process is still running
     pub fn check_process(&mut self, instance: &mut Instance) -> bool {
+        // Whoever runs the stop command finishes stopping instances
+        if instance.status == InstanceStatus::Stopping {
+            return true;
+        }
         if let Some(child) = self.children.get_mut(&instance.id) {
             match child.try_wait() {
                 Ok(Some(status)) => {
                     // Process has exited
+                    let _launch = instance.launch_span().entered();
+                    info!("Instance '{}' exited with {}", instance.config.name, status);
+                    if let Some(pid) = instance.pid {
+                        network_limit::remove(pid);
+                    }
+                    instance.exit_code = status.code();
                     if status.success() {
                         instance.mark_stopped();
                     } else {
@@ -235,20 +503,40 @@ impl ProcessManager {
                 }
             }
         } else {
-            // No child handle, check by PID
-            if let Some(pid) = instance.pid {
-                platform::is_process_running(pid)
-            } else {
-                false
+            // No child handle (adopted or restored), check by PID
+            let running = instance.pid.is_some_and(platform::is_process_running);
+            if !running && instance.status.is_active() {
+                let _launch = instance.launch_span().entered();
+                info!("Instance '{}' is no longer running", instance.config.name);
+                if let Some(pid) = instance.pid {
+                    network_limit::remove(pid);
+                }
+                instance.mark_stopped();
             }
+            running
         }
     }
 
     /// Apply resource limits to a process
-    fn apply_resource_limits(&self, pid: u32, limits: &ResourceLimits) -> Result<()> {
-        // Apply CPU affinity
-        if !limits.cpu_affinity.is_empty() {
-            platform::set_cpu_affinity(pid, &limits.cpu_affinity)?;
+    fn apply_resource_limits(
+        &mut self,
+        pid: u32,
+        executable: &Path,
+        limits: &ResourceLimits,
+    ) -> Result<()> {
+        // Apply CPU affinity, resolving presets against the CPU topology
+        let cores = match limits.affinity_preset {
+            Some(preset) => {
+                let slot = self.next_core_slot;
+                if preset == AffinityPreset::OnePerformanceCoreEach {
+                    self.next_core_slot += 1;
+                }
+                preset.resolve(CpuTopology::get(), slot)
+            }
+            None => limits.cpu_affinity.clone(),
+        };
+        if !cores.is_empty() {
+            platform::set_cpu_affinity(pid, &cores)?;
         }
 
         // Apply process priority
@@ -256,45 +544,69 @@ impl ProcessManager {
             platform::set_process_priority(pid, limits.priority)?;
         }
 
-        // Apply memory limit (Windows only via Job Objects)
+        // Apply memory limit (Job Objects on Windows, cgroups v2 on Linux)
         #[cfg(windows)]
         if limits.memory_mb > 0 {
             platform::windows::set_memory_limit(pid, limits.memory_mb)?;
         }
+        #[cfg(target_os = "linux")]
+        if limits.memory_mb > 0 {
+            platform::linux::set_memory_limit(pid, limits.memory_mb)?;
+        }
+
+        // Apply CPU cap (kernel-enforced where possible, duty-cycled otherwise)
+        if limits.cpu_percent > 0 {
+            cpu_throttle::set_limit(pid, limits.cpu_percent)?;
+        }
+
+        // Apply network limit in the background, as it runs slow OS tools
+        network_limit::apply(pid, executable, limits.network_kbps);
 
-        // CPU and network throttling would require more advanced techniques
-        // (e.g., cgroups on Linux, Job Objects on Windows)
+        // Apply disk I/O priority; macOS sets it in the child before exec
+        #[cfg(not(target_os = "macos"))]
+        if limits.io_priority != super::resource::IoPriority::Normal {
+            platform::set_io_priority(pid, limits.io_priority)?;
+        }
+
+        // Apply disk throughput cap (Job Objects on Windows, cgroups v2 on Linux)
+        if limits.disk_mbps > 0 {
+            platform::set_disk_rate(pid, limits.disk_mbps)?;
+        }
 
         Ok(())
     }
 
-    /// Set up environment variables for instance isolation
-    fn setup_isolation_env(&self, cmd: &mut Command, data_dir: &Path, _config: &InstanceConfig) {
-        // Set custom home/appdata directory to isolate the instance
-        #[cfg(windows)]
-        {
-            cmd.env("APPDATA", data_dir.join("AppData").join("Roaming"));
-            cmd.env("LOCALAPPDATA", data_dir.join("AppData").join("Local"));
-            cmd.env("USERPROFILE", data_dir);
+    /// Switch a running instance to `limits`, also lifting the CPU cap, priority,
+    /// affinity, I/O priority and network cap where they are unlimited. Memory
+    /// and disk caps cannot be lifted from a running process.
+    pub fn apply_limits_now(&mut self, instance: &Instance, limits: &ResourceLimits) -> Result<()> {
+        let pid = instance
+            .pid
+            .filter(|_| instance.status.is_active())
+            .context("Instance is not running")?;
+        self.apply_resource_limits(pid, &instance.config.executable_path, limits)?;
+
+        if limits.cpu_percent == 0 {
+            cpu_throttle::set_limit(pid, 0)?;
         }
-
-        #[cfg(target_os = "macos")]
-        {
-            cmd.env("HOME", data_dir);
-            cmd.env("XDG_DATA_HOME", data_dir.join("Library"));
-            cmd.env(
-                "XDG_CONFIG_HOME",
-                data_dir.join("Library").join("Preferences"),
-            );
-            cmd.env("XDG_CACHE_HOME", data_dir.join("Library").join("Caches"));
+        if limits.priority == 0 {
+            platform::set_process_priority(pid, 0)?;
+        }
+        if limits.affinity_preset.is_none() && limits.cpu_affinity.is_empty() {
+            let all: Vec<usize> = CpuTopology::get().cpus.iter().map(|c| c.index).collect();
+            platform::set_cpu_affinity(pid, &all)?;
+        }
+        #[cfg(not(target_os = "macos"))]
+        if limits.io_priority == super::resource::IoPriority::Normal {
+            platform::set_io_priority(pid, limits.io_priority)?;
         }
+        Ok(())
+    }
 
-        #[cfg(target_os = "linux")]
-        {
-            cmd.env("HOME", data_dir);
-            cmd.env("XDG_DATA_HOME", data_dir.join(".local").join("share"));
-            cmd.env("XDG_CONFIG_HOME", data_dir.join(".config"));
-            cmd.env("XDG_CACHE_HOME", data_dir.join(".cache"));
+    /// Apply isolation (if enabled) and custom environment variables to a command
+    fn apply_environment(&self, cmd: &mut Command, data_dir: &Path, config: &InstanceConfig) {
+        for var in environment::overrides(data_dir, config) {
+            cmd.env(var.key, var.value);
         }
     }
 
@@ -329,6 +641,20 @@ impl ProcessManager {
         Ok(data_dir)
     }
 
+    /// Shared cache store for an executable, next to the instance data directories
+    fn shared_cache_dir(&self, config: &InstanceConfig) -> PathBuf {
+        let app = config
+            .executable_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        self.instance_data_dir
+            .parent()
+            .unwrap_or(&self.instance_data_dir)
+            .join("shared_cache")
+            .join(app)
+    }
+
     /// Clean up an instance's data directory
     pub fn cleanup_instance_data(&self, id: InstanceId) -> Result<()> {
         let data_dir = self.instance_data_dir.join(id.to_string());
@@ -352,6 +678,95 @@ impl ProcessManager {
     pub fn remove_child(&mut self, id: InstanceId) {
         self.children.remove(&id);
     }
+
+    /// Drop the child handles of instances that are gone or no longer active,
+    /// reaping those that exited unnoticed. Handles of active instances are left
+    /// for `check_process`, which needs their exit status. Returns how many were dropped.
+    pub fn prune_children(&mut self, active: &HashSet<InstanceId>) -> usize {
+        let before = self.children.len();
+        self.children
+            .retain(|id, child| active.contains(id) || matches!(child.try_wait(), Ok(None)));
+        before - self.children.len()
+    }
+}
+
+/// Ask a process to exit, killing it if it cannot be asked
+pub fn end_process(pid: u32) -> Result<()> {
+    if let Err(e) = platform::terminate_process(pid) {
+        warn!("Graceful termination failed: {}, forcing kill", e);
+        platform::kill_process(pid)?;
+    }
+    Ok(())
+}
+
+/// Short random ID correlating one launch across history, output log and tracing
+fn new_launch_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()[..8].to_string()
+}
+
+/// Move the instance's windows to its virtual desktop once they appear
+fn move_to_virtual_desktop(pid: u32, config: &InstanceConfig) {
+    let Some(desktop) = config.virtual_desktop.clone() else {
+        return;
+    };
+    let span = tracing::Span::current();
+    std::thread::spawn(move || {
+        let _launch = span.entered();
+        if let Err(e) = platform::move_to_virtual_desktop(pid, &desktop.id) {
+            warn!(
+                "Failed to move PID {} to desktop '{}': {}",
+                pid, desktop.name, e
+            );
+        }
+    });
+}
+
+/// Create the launch command, selecting a universal binary slice if one is forced
+fn build_command(config: &InstanceConfig) -> Result<Command> {
+    let Some(arch) = config.launch_architecture.forced() else {
+        return Ok(Command::new(&config.executable_path));
+    };
+
+    let slices = architecture::executable_architectures(&config.executable_path);
+    if !slices.is_empty() && !slices.contains(&arch) {
+        anyhow::bail!("Executable has no {} build", arch.label());
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        // `arch` execs in place, so the PID we track is the app's own
+        let mut cmd = Command::new("arch");
+        cmd.arg(config.launch_architecture.arch_flag().unwrap_or_default())
+            .arg(&config.executable_path);
+        Ok(cmd)
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        warn!(
+            "Forcing the {} architecture is only supported on macOS, launching natively",
+            arch.label()
+        );
+        Ok(Command::new(&config.executable_path))
+    }
+}
+
+/// Run a command line through the platform shell
+pub fn shell_command(command: &str) -> Command {
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        // Pass the line through untouched so cmd applies its own quoting rules
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").raw_arg(command);
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        cmd
+    }
+    #[cfg(not(windows))]
+    {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    }
 }
 
 /// Thread-safe wrapper for ProcessManager
@@ -360,9 +775,12 @@ pub struct SharedProcessManager {
 }
 
 impl SharedProcessManager {
-    pub fn new(instance_data_dir: PathBuf) -> Self {
+    pub fn new(instance_data_dir: PathBuf, logs_dir: PathBuf) -> Self {
         Self {
-            inner: Arc::new(RwLock::new(ProcessManager::new(instance_data_dir))),
+            inner: Arc::new(RwLock::new(ProcessManager::new(
+                instance_data_dir,
+                logs_dir,
+            ))),
         }
     }
 
@@ -380,6 +798,78 @@ impl SharedProcessManager {
             .stop(instance)
     }
 
+    pub fn release(&self, instance: &mut Instance) -> Result<()> {
+        self.inner
+            .write()
+            .map_err(|e| anyhow::anyhow!("Lock poisoned: {}", e))?
+            .release(instance);
+        Ok(())
+    }
+
+    pub fn stop_command(&self, instance: &Instance) -> Result<Option<Command>> {
+        Ok(self
+            .inner
+            .write()
+            .map_err(|e| anyhow::anyhow!("Lock poisoned: {}", e))?
+            .stop_command(instance))
+    }
+
+    /// Run a stop command and wait up to `timeout` for the instance's process
+    /// to exit, locking the manager only to check on it. Returns false if the
+    /// command failed or the timeout passed.
+    pub fn run_stop_command(
+        &self,
+        id: InstanceId,
+        pid: u32,
+        mut command: Command,
+        timeout: Duration,
+    ) -> bool {
+        let mut stop = match command.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                warn!("Failed to run stop command: {}", e);
+                return false;
+            }
+        };
+
+        let deadline = Instant::now() + timeout;
+        let mut command_done = false;
+        while Instant::now() < deadline {
+            let exited = self
+                .inner
+                .write()
+                .map(|mut m| m.has_exited(id, pid))
+                .unwrap_or(false);
+            if exited {
+                let _ = stop.kill();
+                return true;
+            }
+            if !command_done {
+                match stop.try_wait() {
+                    Ok(Some(status)) if !status.success() => {
+                        warn!("Stop command exited with {}", status);
+                        return false;
+                    }
+                    Ok(Some(_)) => command_done = true,
+                    Ok(None) => {}
+                    Err(e) => {
+                        warn!("Failed to wait for stop command: {}", e);
+                        return false;
+                    }
+                }
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+
+        warn!(
+            "Instance {} did not exit within {}s of its stop command",
+            id,
+            timeout.as_secs()
+        );
+        let _ = stop.kill();
+        false
+    }
+
     pub fn kill(&self, instance: &mut Instance) -> Result<()> {
         self.inner
             .write()
@@ -401,6 +891,27 @@ impl SharedProcessManager {
             .resume(instance)
     }
 
+    pub fn apply_limits_now(&self, instance: &Instance, limits: &ResourceLimits) -> Result<()> {
+        self.inner
+            .write()
+            .map_err(|e| anyhow::anyhow!("Lock poisoned: {}", e))?
+            .apply_limits_now(instance, limits)
+    }
+
+    pub fn checkpoint(&self, instance: &mut Instance, images_dir: &Path) -> Result<()> {
+        self.inner
+            .write()
+            .map_err(|e| anyhow::anyhow!("Lock poisoned: {}", e))?
+            .checkpoint(instance, images_dir)
+    }
+
+    pub fn restore(&self, instance: &mut Instance, images_dir: &Path) -> Result<()> {
+        self.inner
+            .write()
+            .map_err(|e| anyhow::anyhow!("Lock poisoned: {}", e))?
+            .restore(instance, images_dir)
+    }
+
     pub fn check_process(&self, instance: &mut Instance) -> bool {
         self.inner
             .write()
@@ -412,6 +923,13 @@ impl SharedProcessManager {
         self.inner.read().map(|m| m.running_count()).unwrap_or(0)
     }
 
+    pub fn prune_children(&self, active: &HashSet<InstanceId>) -> usize {
+        self.inner
+            .write()
+            .map(|mut m| m.prune_children(active))
+            .unwrap_or(0)
+    }
+
     pub fn clone_inner(&self) -> Arc<RwLock<ProcessManager>> {
         Arc::clone(&self.inner)
     }
@@ -424,3 +942,58 @@ impl Clone for SharedProcessManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exited_child() -> Child {
+        let mut command = if cfg!(windows) {
+            let mut c = Command::new("cmd");
+            c.args(["/C", "exit", "0"]);
+            c
+        } else {
+            Command::new("true")
+        };
+        let mut child = command.spawn().expect("spawn child");
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            if matches!(child.try_wait(), Ok(Some(_))) {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        child
+    }
+
+    fn manager() -> ProcessManager {
+        let dir = std::env::temp_dir().join(format!("mi-prune-{}", uuid::Uuid::new_v4()));
+        ProcessManager::new(dir.join("data"), dir.join("logs"))
+    }
+
+    #[test]
+    fn prune_children_keeps_active_instances() {
+        let mut manager = manager();
+        let id = InstanceId::new();
+        manager.children.insert(id, exited_child());
+
+        let active = HashSet::from([id]);
+        assert_eq!(manager.prune_children(&active), 0);
+        assert!(manager.has_child(id));
+    }
+
+    #[test]
+    fn prune_children_drops_exited_inactive_instances() {
+        let mut manager = manager();
+        let id = InstanceId::new();
+        let kept = InstanceId::new();
+        manager.children.insert(id, exited_child());
+        manager.children.insert(kept, exited_child());
+
+        let active = HashSet::from([kept]);
+        assert_eq!(manager.prune_children(&active), 1);
+        assert!(!manager.has_child(id));
+        assert!(manager.has_child(kept));
+        assert_eq!(manager.running_count(), 1);
+    }
+}
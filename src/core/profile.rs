@@ -1,10 +1,16 @@
 //! Profile management - Saved launch configurations
 
+use std::collections::HashSet;
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+
+use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use super::instance::InstanceConfig;
+use super::window_layout::WindowLayout;
 
 /// Unique identifier for a profile
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -59,6 +65,139 @@ pub struct Profile {
     pub icon_path: Option<std::path::PathBuf>,
     /// Tags for organization
     pub tags: Vec<String>,
+    /// Member instances share one isolated data directory instead of one each
+    #[serde(default)]
+    pub shared_isolation: bool,
+    /// Custom shared isolation root (defaults to a folder in the profiles directory)
+    #[serde(default)]
+    pub isolation_root: Option<std::path::PathBuf>,
+    /// Outcome of the most recent launch
+    #[serde(default)]
+    pub last_run: Option<ProfileRun>,
+    /// Member indices chosen the last time only some members were launched
+    #[serde(default)]
+    pub last_partial_run: Option<Vec<usize>>,
+    /// Arrangement for the windows of this profile's instances
+    #[serde(default)]
+    pub window_layout: Option<WindowLayout>,
+    /// Members that wait for another member to be ready, at most one each
+    #[serde(default)]
+    pub dependencies: Vec<MemberDependency>,
+}
+
+/// A member launched only once another member is ready
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemberDependency {
+    /// Index of the waiting member
+    pub member: usize,
+    /// Index of the member it waits for
+    pub after: usize,
+    /// When `after` counts as ready
+    pub ready: Readiness,
+    /// Give up on the waiting member after this long
+    #[serde(default = "default_ready_timeout")]
+    pub timeout_secs: u32,
+}
+
+fn default_ready_timeout() -> u32 {
+    60
+}
+
+impl MemberDependency {
+    pub fn new(member: usize, after: usize) -> Self {
+        Self {
+            member,
+            after,
+            ready: Readiness::default(),
+            timeout_secs: default_ready_timeout(),
+        }
+    }
+}
+
+/// When a member another one depends on counts as ready
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Readiness {
+    /// A fixed time after it was launched
+    Delay { ms: u32 },
+    /// Once a TCP port on this machine accepts connections
+    PortOpen { port: u16 },
+    /// Once it shows a window
+    WindowVisible,
+}
+
+impl Default for Readiness {
+    fn default() -> Self {
+        Self::Delay { ms: 2000 }
+    }
+}
+
+impl Readiness {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Delay { .. } => "After a delay",
+            Self::PortOpen { .. } => "Port open",
+            Self::WindowVisible => "Window visible",
+        }
+    }
+
+    /// One of each kind, for pickers
+    pub fn all() -> [Self; 3] {
+        [
+            Self::default(),
+            Self::PortOpen { port: 8080 },
+            Self::WindowVisible,
+        ]
+    }
+
+    /// Whether the process `pid` is ready now; delays are waited out by the caller
+    pub fn is_met(&self, pid: u32) -> bool {
+        match self {
+            Self::Delay { .. } => true,
+            Self::PortOpen { port } => TcpStream::connect_timeout(
+                &SocketAddr::from(([127, 0, 0, 1], *port)),
+                Duration::from_millis(250),
+            )
+            .is_ok(),
+            Self::WindowVisible => {
+                crate::platform::process_windows(pid).is_ok_and(|windows| !windows.is_empty())
+            }
+        }
+    }
+}
+
+/// Outcome of one profile launch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileRun {
+    /// When the launch began
+    pub started_at: DateTime<Utc>,
+    /// Number of member instances in the profile
+    pub total: usize,
+    /// Number of members that started
+    pub started: usize,
+    /// Error messages for members that failed to start
+    pub failures: Vec<String>,
+}
+
+impl ProfileRun {
+    pub fn new(total: usize) -> Self {
+        Self {
+            started_at: Utc::now(),
+            total,
+            started: 0,
+            failures: Vec::new(),
+        }
+    }
+
+    /// Whether every member started
+    pub fn succeeded(&self) -> bool {
+        self.failures.is_empty() && self.started == self.total
+    }
+
+    /// Short "started/total" description
+    pub fn summary(&self) -> String {
+        format!("{}/{} started", self.started, self.total)
+    }
 }
 
 impl Profile {
@@ -79,6 +218,12 @@ impl Profile {
             is_favorite: false,
             icon_path: None,
             tags: Vec::new(),
+            shared_isolation: false,
+            isolation_root: None,
+            last_run: None,
+            last_partial_run: None,
+            window_layout: None,
+            dependencies: Vec::new(),
         }
     }
 
@@ -92,12 +237,110 @@ impl Profile {
     pub fn remove_instance(&mut self, index: usize) -> Option<InstanceConfig> {
         if index < self.instances.len() {
             self.modified_at = Utc::now();
+            if let Some(ref mut members) = self.last_partial_run {
+                members.retain(|&i| i != index);
+                for i in members.iter_mut().filter(|i| **i > index) {
+                    *i -= 1;
+                }
+            }
+            self.dependencies
+                .retain(|d| d.member != index && d.after != index);
+            for dependency in &mut self.dependencies {
+                if dependency.member > index {
+                    dependency.member -= 1;
+                }
+                if dependency.after > index {
+                    dependency.after -= 1;
+                }
+            }
             Some(self.instances.remove(index))
         } else {
             None
         }
     }
 
+    /// Get the shared isolation root for member instances, if enabled
+    pub fn shared_isolation_root(
+        &self,
+        profiles_dir: &std::path::Path,
+    ) -> Option<std::path::PathBuf> {
+        if !self.shared_isolation {
+            return None;
+        }
+        Some(
+            self.isolation_root
+                .clone()
+                .unwrap_or_else(|| profiles_dir.join(self.id.to_string()).join("isolation")),
+        )
+    }
+
+    /// Per member, whether it is preselected for a partial launch: the last
+    /// partial run's choice, or every member
+    pub fn partial_selection(&self) -> Vec<bool> {
+        match self.last_partial_run {
+            Some(ref members) => (0..self.instances.len())
+                .map(|i| members.contains(&i))
+                .collect(),
+            None => vec![true; self.instances.len()],
+        }
+    }
+
+    /// What a member waits for, if anything
+    pub fn dependency(&self, member: usize) -> Option<&MemberDependency> {
+        self.dependencies.iter().find(|d| d.member == member)
+    }
+
+    /// Set or clear what a member waits for, refusing dependencies that form a cycle
+    pub fn set_dependency(
+        &mut self,
+        member: usize,
+        dependency: Option<MemberDependency>,
+    ) -> Result<()> {
+        let previous = std::mem::take(&mut self.dependencies);
+        self.dependencies = previous
+            .iter()
+            .filter(|d| d.member != member)
+            .cloned()
+            .chain(dependency)
+            .collect();
+        let all: Vec<usize> = (0..self.instances.len()).collect();
+        if let Err(e) = self.launch_order(&all) {
+            self.dependencies = previous;
+            return Err(e);
+        }
+        self.modified_at = Utc::now();
+        Ok(())
+    }
+
+    /// Launch order of `members` where every member comes after the one it
+    /// waits for. Members waiting for nothing keep their order and go first.
+    pub fn launch_order(&self, members: &[usize]) -> Result<Vec<usize>> {
+        let selected: HashSet<usize> = members.iter().copied().collect();
+        // Waiting for a member that is not launched this time is ignored
+        let waits_for = |member: usize| {
+            self.dependency(member)
+                .map(|d| d.after)
+                .filter(|after| selected.contains(after) && *after != member)
+        };
+
+        let mut order = Vec::with_capacity(members.len());
+        let mut placed = HashSet::new();
+        while order.len() < members.len() {
+            let ready: Vec<usize> = members
+                .iter()
+                .copied()
+                .filter(|m| !placed.contains(m))
+                .filter(|m| waits_for(*m).is_none_or(|after| placed.contains(&after)))
+                .collect();
+            if ready.is_empty() {
+                anyhow::bail!("Profile members wait for each other in a cycle");
+            }
+            placed.extend(ready.iter().copied());
+            order.extend(ready);
+        }
+        Ok(order)
+    }
+
     /// Mark profile as used
     pub fn mark_used(&mut self) {
         self.last_used_at = Some(Utc::now());
@@ -0,0 +1,110 @@
+//! Profile templates - Ready-made profiles for common setups
+//!
+//! A template describes how its members are configured; the user only picks
+//! the executable and how many copies to run.
+
+use std::path::Path;
+
+use super::instance::InstanceConfig;
+use super::profile::Profile;
+use super::window_layout::WindowLayout;
+
+/// A built-in profile completed with an executable and a member count
+pub struct ProfileTemplate {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// What kind of executable to pick
+    pub executable_hint: &'static str,
+    /// Members created unless another count is chosen
+    pub count: usize,
+    pub max_count: usize,
+    /// Delay between member launches, 0 to launch all at once
+    pub launch_delay_ms: u32,
+    pub window_layout: Option<WindowLayout>,
+    /// Settings every member gets on top of the defaults
+    configure: fn(&mut InstanceConfig),
+}
+
+/// Templates offered by "New from Template"
+pub const TEMPLATES: &[ProfileTemplate] = &[
+    ProfileTemplate {
+        name: "Isolated browsers",
+        description: "Browser windows that each keep their own profile, cookies and logins",
+        executable_hint: "A browser such as chrome.exe, msedge.exe or firefox.exe",
+        count: 3,
+        max_count: 10,
+        launch_delay_ms: 1000,
+        window_layout: Some(WindowLayout::Tile),
+        configure: |config| {
+            config.use_environment_isolation = true;
+        },
+    },
+    ProfileTemplate {
+        name: "Dual chat clients",
+        description: "Two accounts of a chat app such as Discord, Slack or Telegram side by side",
+        executable_hint: "The chat app's executable",
+        count: 2,
+        max_count: 4,
+        launch_delay_ms: 0,
+        window_layout: Some(WindowLayout::Tile),
+        configure: |config| {
+            config.use_environment_isolation = true;
+            config.auto_restart = true;
+        },
+    },
+    ProfileTemplate {
+        name: "Game multibox",
+        description: "Several game clients launched one after another to spread the load; \
+                      isolation stays off since anti-cheat often rejects it",
+        executable_hint: "The game client's executable",
+        count: 4,
+        max_count: 8,
+        launch_delay_ms: 5000,
+        window_layout: Some(WindowLayout::Tile),
+        configure: |_| {},
+    },
+    ProfileTemplate {
+        name: "Background workers",
+        description: "Hidden copies of a command-line tool with their output captured, \
+                      restarted when they crash",
+        executable_hint: "The tool or script runner to run",
+        count: 2,
+        max_count: 16,
+        launch_delay_ms: 0,
+        window_layout: None,
+        configure: |config| {
+            config.launch_in_background = true;
+            config.capture_output = true;
+            config.auto_restart = true;
+        },
+    },
+];
+
+impl ProfileTemplate {
+    /// Profile named `name` with `count` members running `executable`
+    pub fn instantiate(&self, name: &str, executable: &Path, count: usize) -> Profile {
+        let mut profile = Profile::new(name);
+        profile.description = self.description.to_string();
+        profile.staggered_launch = self.launch_delay_ms > 0;
+        if profile.staggered_launch {
+            profile.launch_delay_ms = self.launch_delay_ms;
+        }
+        profile.window_layout = self.window_layout;
+
+        let stem = executable
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| name.to_string());
+        for n in 1..=count.clamp(1, self.max_count) {
+            let mut config = InstanceConfig {
+                name: format!("{} {}", stem, n),
+                executable_path: executable.to_path_buf(),
+                group: Some(name.to_string()),
+                ..Default::default()
+            };
+            (self.configure)(&mut config);
+            profile.add_instance(config);
+        }
+        profile
+    }
+}
@@ -0,0 +1,138 @@
+//! Self profiling - Measures what MultiInstance itself costs the machine
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Samples kept per timing
+const WINDOW: usize = 120;
+
+/// Consecutive samples over budget before warning, so short spikes pass quietly
+const SUSTAINED_SAMPLES: u32 = 3;
+
+/// Rolling window of durations for one measured operation
+#[derive(Debug, Clone, Default)]
+pub struct Timing {
+    samples: VecDeque<Duration>,
+}
+
+impl Timing {
+    pub fn record(&mut self, sample: Duration) {
+        if self.samples.len() >= WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn last(&self) -> Option<Duration> {
+        self.samples.back().copied()
+    }
+
+    pub fn average(&self) -> Option<Duration> {
+        let count = self.samples.len() as u32;
+        (count > 0).then(|| self.samples.iter().sum::<Duration>() / count)
+    }
+
+    pub fn max(&self) -> Option<Duration> {
+        self.samples.iter().max().copied()
+    }
+}
+
+/// CPU and memory used by our own process
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SelfUsage {
+    /// Percent of one core, like the instance figures
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+}
+
+/// Process handles the manager holds, from the last reconciliation
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HandleCounts {
+    /// Child process handles
+    pub children: usize,
+    /// Job object handles (Windows)
+    pub jobs: usize,
+    /// Handles dropped for processes that were gone, since startup
+    pub pruned: usize,
+}
+
+/// Timings and footprint of the app, shown in the profiler overlay
+#[derive(Debug, Clone, Default)]
+pub struct Profiler {
+    /// Time spent building each UI frame
+    pub frame: Timing,
+    /// Time spent sampling instances each frame
+    pub monitor: Timing,
+    pub usage: Option<SelfUsage>,
+    pub handles: HandleCounts,
+    /// Consecutive footprint samples over budget
+    over_budget_samples: u32,
+    /// A footprint warning was raised and usage has not dropped back yet
+    warned: bool,
+}
+
+impl Profiler {
+    /// The last footprint sample was over budget
+    pub fn over_budget(&self) -> bool {
+        self.over_budget_samples > 0
+    }
+
+    /// Record a footprint sample. Returns what the budget is exceeded by when
+    /// the overrun has lasted long enough to warn, once per overrun.
+    pub fn record_usage(&mut self, usage: SelfUsage, budget: &SelfBudget) -> Option<String> {
+        self.usage = Some(usage);
+        let Some(exceeded) = budget.exceeded(&usage) else {
+            self.over_budget_samples = 0;
+            self.warned = false;
+            return None;
+        };
+        self.over_budget_samples += 1;
+        if self.over_budget_samples < SUSTAINED_SAMPLES || self.warned {
+            return None;
+        }
+        self.warned = true;
+        Some(exceeded)
+    }
+}
+
+/// Footprint limits the app warns about exceeding
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SelfBudget {
+    /// 0 = no memory budget
+    pub memory_mb: u32,
+    /// Percent of one core; 0 = no CPU budget
+    pub cpu_percent: f32,
+}
+
+impl Default for SelfBudget {
+    fn default() -> Self {
+        Self {
+            memory_mb: 400,
+            cpu_percent: 25.0,
+        }
+    }
+}
+
+impl SelfBudget {
+    /// Description of what the usage exceeds, if anything
+    pub fn exceeded(&self, usage: &SelfUsage) -> Option<String> {
+        let memory_mb = usage.memory_bytes / (1024 * 1024);
+        let mut over = Vec::new();
+        if self.memory_mb > 0 && memory_mb > self.memory_mb as u64 {
+            over.push(format!(
+                "{} MB RAM (budget {} MB)",
+                memory_mb, self.memory_mb
+            ));
+        }
+        if self.cpu_percent > 0.0 && usage.cpu_percent > self.cpu_percent {
+            over.push(format!(
+                "{:.0}% CPU (budget {:.0}%)",
+                usage.cpu_percent, self.cpu_percent
+            ));
+        }
+        (!over.is_empty()).then(|| over.join(", "))
+    }
+}
@@ -0,0 +1,136 @@
+//! Crash recovery - Dirty shutdown marker and the report shown after an unexpected exit
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+use super::instance::{InstanceConfig, InstanceId};
+
+/// Marker file written while the app runs and removed on a clean exit
+const MARKER_FILE: &str = "running.json";
+
+/// An instance that was running when the marker was last written
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarkedInstance {
+    pub id: InstanceId,
+    pub pid: u32,
+    pub config: InstanceConfig,
+    pub started_at: Option<DateTime<Utc>>,
+}
+
+/// Contents of the dirty shutdown marker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShutdownMarker {
+    /// When the app started
+    pub started_at: DateTime<Utc>,
+    /// When the marker was last written
+    pub updated_at: DateTime<Utc>,
+    /// Instances running at that time
+    pub instances: Vec<MarkedInstance>,
+}
+
+impl ShutdownMarker {
+    pub fn new(instances: Vec<MarkedInstance>) -> Self {
+        let now = Utc::now();
+        Self {
+            started_at: now,
+            updated_at: now,
+            instances,
+        }
+    }
+
+    fn path(data_dir: &Path) -> PathBuf {
+        data_dir.join(MARKER_FILE)
+    }
+
+    /// Marker left behind by a run that did not exit cleanly, if any
+    pub fn load(data_dir: &Path) -> Option<Self> {
+        let json = std::fs::read_to_string(Self::path(data_dir)).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    pub fn save(&self, data_dir: &Path) -> Result<()> {
+        let json = serde_json::to_string(self)?;
+        std::fs::write(Self::path(data_dir), json)?;
+        Ok(())
+    }
+
+    /// Remove the marker on a clean exit
+    pub fn clear(data_dir: &Path) -> Result<()> {
+        match std::fs::remove_file(Self::path(data_dir)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// One instance from the previous run
+#[derive(Debug, Clone)]
+pub struct RecoveredInstance {
+    pub instance: MarkedInstance,
+    /// The process is still running and can be adopted
+    pub alive: bool,
+}
+
+/// What happened to the instances of a run that ended unexpectedly
+#[derive(Debug, Clone)]
+pub struct RecoveryReport {
+    /// Last time the previous run was known to be alive
+    pub last_seen: DateTime<Utc>,
+    pub instances: Vec<RecoveredInstance>,
+}
+
+impl RecoveryReport {
+    /// Check which of the marked instances are still running. A PID counts as
+    /// alive only when its executable name still matches, to rule out PID reuse.
+    pub fn build(marker: ShutdownMarker) -> Self {
+        let mut system = System::new();
+        let pids: Vec<Pid> = marker
+            .instances
+            .iter()
+            .map(|i| Pid::from_u32(i.pid))
+            .collect();
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&pids),
+            true,
+            ProcessRefreshKind::new().with_exe(UpdateKind::Always),
+        );
+
+        let instances = marker
+            .instances
+            .into_iter()
+            .map(|instance| {
+                let alive = system
+                    .process(Pid::from_u32(instance.pid))
+                    .is_some_and(|process| {
+                        let expected = instance.config.executable_path.file_name();
+                        match process.exe() {
+                            Some(exe) => exe.file_name() == expected,
+                            None => expected.is_some_and(|name| {
+                                name.to_string_lossy()
+                                    .to_lowercase()
+                                    .starts_with(&process.name().to_string_lossy().to_lowercase())
+                            }),
+                        }
+                    });
+                RecoveredInstance { instance, alive }
+            })
+            .collect();
+
+        Self {
+            last_seen: marker.updated_at,
+            instances,
+        }
+    }
+
+    pub fn alive(&self) -> impl Iterator<Item = &RecoveredInstance> {
+        self.instances.iter().filter(|i| i.alive)
+    }
+
+    pub fn lost(&self) -> impl Iterator<Item = &RecoveredInstance> {
+        self.instances.iter().filter(|i| !i.alive)
+    }
+}
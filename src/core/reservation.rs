@@ -0,0 +1,93 @@
+//! Memory reservation - Checks that a launch fits in RAM and its group's budget
+//!
+//! An instance reserves its configured memory limit. Launching it must leave
+//! that much physical memory free, and the group's running members (counted
+//! at their limit, or their current usage when they have none) must stay
+//! within the group's memory budget.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::instance::{Instance, InstanceConfig, InstanceId};
+use super::resource::{format_bytes, SystemResources};
+
+const MB: u64 = 1024 * 1024;
+
+/// What happens when a launch would not fit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ReservationPolicy {
+    /// Launch without checking
+    Off,
+    /// Launch anyway and show a warning
+    #[default]
+    Warn,
+    /// Refuse the launch
+    Deny,
+}
+
+impl ReservationPolicy {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::Warn => "Warn",
+            Self::Deny => "Deny",
+        }
+    }
+
+    pub fn all() -> [Self; 3] {
+        [Self::Off, Self::Warn, Self::Deny]
+    }
+}
+
+/// Most memory the running members of a group may reserve together
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupMemoryBudget {
+    pub group: String,
+    pub memory_mb: u64,
+}
+
+/// Why launching `config` would not fit, if it would not.
+/// `id` is the instance being started, so it is not counted twice.
+pub fn shortfall(
+    config: &InstanceConfig,
+    id: Option<InstanceId>,
+    instances: &HashMap<InstanceId, Instance>,
+    system: &SystemResources,
+    budgets: &[GroupMemoryBudget],
+) -> Option<String> {
+    let needed = config.resource_limits.memory_mb * MB;
+
+    let free = system.total_memory.saturating_sub(system.used_memory);
+    if needed > 0 && system.total_memory > 0 && needed > free {
+        return Some(format!(
+            "its {} memory limit is more than the {} of RAM free",
+            format_bytes(needed),
+            format_bytes(free)
+        ));
+    }
+
+    let group = config.group.as_deref()?;
+    let budget = budgets
+        .iter()
+        .find(|b| b.group == group && b.memory_mb > 0)?
+        .memory_mb
+        * MB;
+    let reserved: u64 = instances
+        .values()
+        .filter(|i| Some(i.id) != id && i.status.is_active())
+        .filter(|i| i.config.group.as_deref() == Some(group))
+        .map(|i| match i.config.resource_limits.memory_mb {
+            0 => i.resource_usage.memory_bytes,
+            limit => limit * MB,
+        })
+        .sum();
+    (reserved + needed > budget).then(|| {
+        format!(
+            "group '{}' would reserve {} of its {} memory budget",
+            group,
+            format_bytes(reserved + needed),
+            format_bytes(budget)
+        )
+    })
+}
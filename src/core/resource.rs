@@ -2,8 +2,10 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::cpu_topology::AffinityPreset;
+
 /// Resource limits that can be applied to an instance
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ResourceLimits {
     /// Maximum CPU usage percentage (0-100, 0 = unlimited)
     #[serde(default)]
@@ -11,6 +13,9 @@ pub struct ResourceLimits {
     /// CPU affinity - specific cores to run on (empty = all cores)
     #[serde(default)]
     pub cpu_affinity: Vec<usize>,
+    /// Affinity preset for hybrid CPUs, takes precedence over `cpu_affinity`
+    #[serde(default)]
+    pub affinity_preset: Option<AffinityPreset>,
     /// Maximum memory in MB (0 = unlimited)
     #[serde(default)]
     pub memory_mb: u64,
@@ -23,9 +28,43 @@ pub struct ResourceLimits {
     /// Maximum GPU memory in MB (0 = unlimited)
     #[serde(default)]
     pub gpu_memory_mb: u64,
+    /// Disk I/O priority
+    #[serde(default)]
+    pub io_priority: IoPriority,
+    /// Maximum disk throughput in MB/s, for reads and writes each (0 = unlimited)
+    #[serde(default)]
+    pub disk_mbps: u64,
+}
+
+/// Priority of an instance's disk reads and writes against other processes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum IoPriority {
+    /// Only gets the disk when nothing else wants it
+    VeryLow,
+    Low,
+    #[default]
+    Normal,
+}
+
+impl IoPriority {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::VeryLow => "Very Low",
+            Self::Low => "Low",
+            Self::Normal => "Normal",
+        }
+    }
+
+    pub fn all() -> [Self; 3] {
+        [Self::Normal, Self::Low, Self::VeryLow]
+    }
 }
 
 impl ResourceLimits {
+    /// Priority level below Idle that also requests power-efficient scheduling
+    /// (EcoQoS on Windows, background QoS on macOS), which favours E-cores
+    pub const EFFICIENCY_PRIORITY: i8 = -3;
+
     /// Create limits with CPU percentage cap
     pub fn with_cpu_limit(mut self, percent: u8) -> Self {
         self.cpu_percent = percent.min(100);
@@ -44,6 +83,13 @@ impl ResourceLimits {
         self
     }
 
+    /// Create limits with disk I/O priority and throughput cap
+    pub fn with_disk_limit(mut self, priority: IoPriority, mbps: u64) -> Self {
+        self.io_priority = priority;
+        self.disk_mbps = mbps;
+        self
+    }
+
     /// Create limits with process priority
     pub fn with_priority(mut self, priority: i8) -> Self {
         self.priority = priority.clamp(-20, 19);
@@ -56,14 +102,35 @@ impl ResourceLimits {
         self
     }
 
+    /// Whether the efficiency priority level is selected
+    pub fn is_efficiency_mode(&self) -> bool {
+        self.priority == Self::EFFICIENCY_PRIORITY
+    }
+
+    /// Display name for a priority level as picked in the instance dialogs
+    pub fn priority_label(priority: i8) -> String {
+        match priority {
+            Self::EFFICIENCY_PRIORITY => "Efficiency".to_string(),
+            -2 => "Idle".to_string(),
+            -1 => "Below Normal".to_string(),
+            0 => "Normal".to_string(),
+            1 => "Above Normal".to_string(),
+            2 => "High".to_string(),
+            p => p.to_string(),
+        }
+    }
+
     /// Check if any limits are set
     pub fn has_limits(&self) -> bool {
         self.cpu_percent > 0
             || !self.cpu_affinity.is_empty()
+            || self.affinity_preset.is_some()
             || self.memory_mb > 0
             || self.network_kbps > 0
             || self.priority != 0
             || self.gpu_memory_mb > 0
+            || self.io_priority != IoPriority::Normal
+            || self.disk_mbps > 0
     }
 
     /// Get Windows priority class value
@@ -87,6 +154,66 @@ impl ResourceLimits {
     }
 }
 
+/// Named set of resource limits that can be applied in one pick
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LimitPreset {
+    pub name: String,
+    pub limits: ResourceLimits,
+}
+
+impl LimitPreset {
+    /// Presets shipped with the app
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self {
+                name: "Background".to_string(),
+                limits: ResourceLimits {
+                    cpu_percent: 25,
+                    memory_mb: 2048,
+                    priority: ResourceLimits::EFFICIENCY_PRIORITY,
+                    affinity_preset: Some(AffinityPreset::EfficiencyCores),
+                    ..Default::default()
+                },
+            },
+            Self {
+                name: "Gaming".to_string(),
+                limits: ResourceLimits {
+                    priority: 1,
+                    affinity_preset: Some(AffinityPreset::PerformanceCores),
+                    ..Default::default()
+                },
+            },
+            Self {
+                name: "Benchmark".to_string(),
+                limits: ResourceLimits {
+                    priority: 2,
+                    affinity_preset: Some(AffinityPreset::OnePerformanceCoreEach),
+                    ..Default::default()
+                },
+            },
+        ]
+    }
+
+    /// One-line description, e.g. "CPU 25% · 2.0 GB · Efficiency"
+    pub fn summary(&self) -> String {
+        let limits = &self.limits;
+        let mut parts = Vec::new();
+        if limits.cpu_percent > 0 {
+            parts.push(format!("CPU {}%", limits.cpu_percent));
+        }
+        if limits.memory_mb > 0 {
+            parts.push(format_bytes(limits.memory_mb * 1024 * 1024));
+        }
+        parts.push(ResourceLimits::priority_label(limits.priority));
+        if let Some(preset) = limits.affinity_preset {
+            parts.push(preset.label().to_string());
+        } else if !limits.cpu_affinity.is_empty() {
+            parts.push(format!("{} cores", limits.cpu_affinity.len()));
+        }
+        parts.join(" · ")
+    }
+}
+
 /// Current resource usage for an instance
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ResourceUsage {
@@ -116,6 +243,12 @@ pub struct ResourceUsage {
     pub gpu_percent: f32,
     /// GPU memory usage in bytes
     pub gpu_memory_bytes: u64,
+    /// CPU time (user + kernel) used since the process started, in milliseconds
+    #[serde(default)]
+    pub cpu_time_ms: u64,
+    /// Wall-clock time since the process started, in seconds
+    #[serde(default)]
+    pub run_time_secs: u64,
 }
 
 impl ResourceUsage {
@@ -138,6 +271,18 @@ impl ResourceUsage {
     pub fn cpu_string(&self) -> String {
         format!("{:.1}%", self.cpu_percent)
     }
+
+    /// Format cumulative CPU time
+    pub fn cpu_time_string(&self) -> String {
+        format_duration_secs(self.cpu_time_ms / 1000)
+    }
+
+    /// CPU time over wall-clock time, as a percentage of one core.
+    /// Above 100% means the process kept more than one core busy on average.
+    pub fn cpu_efficiency(&self) -> Option<f32> {
+        (self.run_time_secs > 0)
+            .then(|| self.cpu_time_ms as f32 / (self.run_time_secs as f32 * 1000.0) * 100.0)
+    }
 }
 
 /// System-wide resource information
@@ -212,6 +357,19 @@ pub struct NetworkInterface {
     pub tx_rate: u64,
 }
 
+/// Format a duration in seconds as a short human-readable string
+pub fn format_duration_secs(secs: u64) -> String {
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else if secs < 86400 {
+        format!("{}h {}m", secs / 3600, (secs % 3600) / 60)
+    } else {
+        format!("{}d {}h", secs / 86400, (secs % 86400) / 3600)
+    }
+}
+
 /// Format bytes as human-readable string
 pub fn format_bytes(bytes: u64) -> String {
     const KB: u64 = 1024;
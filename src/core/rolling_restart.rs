@@ -0,0 +1,100 @@
+//! Rolling restarts - Restart a set of instances one at a time
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use super::instance::{Instance, InstanceId, InstanceStatus};
+use super::profile::ProfileId;
+
+/// How long a restarted instance must stay running before it counts as healthy
+pub const HEALTHY_AFTER: Duration = Duration::from_secs(5);
+
+/// How long to wait for a restarted instance to become healthy before giving up
+pub const HEALTH_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Which instances a rolling restart applies to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RollingRestartTarget {
+    /// All active instances in a group
+    Group(String),
+    /// All active instances launched from a profile
+    Profile(ProfileId),
+}
+
+impl RollingRestartTarget {
+    /// Check whether an instance belongs to this target
+    pub fn matches(&self, instance: &Instance) -> bool {
+        match self {
+            Self::Group(group) => instance.config.group.as_deref() == Some(group.as_str()),
+            Self::Profile(profile_id) => instance.profile_id == Some(*profile_id),
+        }
+    }
+}
+
+/// Outcome of checking the instance currently being restarted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartHealth {
+    /// Still starting up, keep waiting
+    Waiting,
+    /// Running long enough to move on to the next instance
+    Healthy,
+    /// Crashed, stopped or timed out
+    Failed,
+}
+
+/// An in-progress rolling restart
+#[derive(Debug, Clone)]
+pub struct RollingRestart {
+    /// What is being restarted
+    pub target: RollingRestartTarget,
+    /// Display label (group or profile name)
+    pub label: String,
+    /// Instances still waiting to be restarted
+    pub pending: VecDeque<InstanceId>,
+    /// Instance currently restarting and when it was restarted
+    pub current: Option<(InstanceId, Instant)>,
+    /// Total number of instances in this rollout
+    pub total: usize,
+}
+
+impl RollingRestart {
+    pub fn new(target: RollingRestartTarget, label: String, members: Vec<InstanceId>) -> Self {
+        Self {
+            target,
+            label,
+            total: members.len(),
+            pending: members.into(),
+            current: None,
+        }
+    }
+
+    /// Number of instances restarted so far, including the current one
+    pub fn completed(&self) -> usize {
+        self.total - self.pending.len()
+    }
+
+    /// Whether every member has been restarted and is healthy
+    pub fn is_finished(&self) -> bool {
+        self.current.is_none() && self.pending.is_empty()
+    }
+
+    /// Evaluate the health of the currently restarting instance
+    pub fn check_health(instance: Option<&Instance>, restarted_at: Instant) -> RestartHealth {
+        let Some(instance) = instance else {
+            return RestartHealth::Failed;
+        };
+
+        match instance.status {
+            // An instance whose status detection reports trouble is not healthy yet
+            InstanceStatus::Running
+                if restarted_at.elapsed() >= HEALTHY_AFTER
+                    && !instance.health.as_ref().is_some_and(|h| h.is_unhealthy()) =>
+            {
+                RestartHealth::Healthy
+            }
+            InstanceStatus::Stopped | InstanceStatus::Crashed => RestartHealth::Failed,
+            _ if restarted_at.elapsed() >= HEALTH_TIMEOUT => RestartHealth::Failed,
+            _ => RestartHealth::Waiting,
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! Scheduler - Launches instances and profiles at set times
+
+use chrono::{DateTime, Datelike, Local, NaiveTime, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::instance::InstanceId;
+use super::profile::ProfileId;
+
+/// Every day of the week, Monday first
+pub const EVERY_DAY: [bool; 7] = [true; 7];
+
+/// Monday to Friday
+pub const WEEKDAYS: [bool; 7] = [true, true, true, true, true, false, false];
+
+/// Saturday and Sunday
+pub const WEEKENDS: [bool; 7] = [false, false, false, false, false, true, true];
+
+/// Short day names, Monday first
+pub const DAY_NAMES: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Unique identifier for a schedule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ScheduleId(pub Uuid);
+
+impl ScheduleId {
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl Default for ScheduleId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for ScheduleId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// What a schedule launches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScheduleTarget {
+    Instance(InstanceId),
+    Profile(ProfileId),
+}
+
+/// When a schedule fires
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Recurrence {
+    /// At a local time of day on the chosen days, Monday first
+    Daily { time: NaiveTime, days: [bool; 7] },
+    /// Every `hours` hours, counted from `from`
+    Interval { hours: u32, from: DateTime<Utc> },
+}
+
+impl Default for Recurrence {
+    fn default() -> Self {
+        Self::Daily {
+            time: NaiveTime::from_hms_opt(9, 0, 0).unwrap_or_default(),
+            days: EVERY_DAY,
+        }
+    }
+}
+
+impl Recurrence {
+    /// First time the schedule fires after `after`
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        match *self {
+            Self::Daily { time, days } => {
+                // Eight days covers a week whose only enabled day is today, before `time`
+                (0..8).find_map(|offset| {
+                    let date = after.date_naive() + chrono::Duration::days(offset);
+                    if !days[date.weekday().num_days_from_monday() as usize] {
+                        return None;
+                    }
+                    // Times skipped by a daylight saving change don't fire that day
+                    Local
+                        .from_local_datetime(&date.and_time(time))
+                        .earliest()
+                        .filter(|at| *at > after)
+                })
+            }
+            Self::Interval { hours, from } => {
+                if hours == 0 {
+                    return None;
+                }
+                let step = chrono::Duration::hours(hours as i64);
+                let from = from.with_timezone(&Local);
+                if after < from {
+                    return Some(from);
+                }
+                let elapsed = after - from;
+                let steps = elapsed.num_seconds() / step.num_seconds() + 1;
+                Some(from + step * steps as i32)
+            }
+        }
+    }
+
+    /// e.g. "Weekdays at 09:00" or "Every 4 hours"
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Daily { time, days } => {
+                let days = match *days {
+                    EVERY_DAY => "Daily".to_string(),
+                    WEEKDAYS => "Weekdays".to_string(),
+                    WEEKENDS => "Weekends".to_string(),
+                    days => {
+                        let names: Vec<&str> = DAY_NAMES
+                            .iter()
+                            .zip(days)
+                            .filter(|(_, on)| *on)
+                            .map(|(name, _)| *name)
+                            .collect();
+                        if names.is_empty() {
+                            return "Never (no days selected)".to_string();
+                        }
+                        names.join(", ")
+                    }
+                };
+                format!("{} at {:02}:{:02}", days, time.hour(), time.minute())
+            }
+            Self::Interval { hours: 1, .. } => "Every hour".to_string(),
+            Self::Interval { hours, .. } => format!("Every {} hours", hours),
+        }
+    }
+}
+
+/// A launch that repeats on a schedule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Schedule {
+    pub id: ScheduleId,
+    pub name: String,
+    pub target: ScheduleTarget,
+    pub recurrence: Recurrence,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    /// When the schedule last fired
+    #[serde(default)]
+    pub last_run: Option<DateTime<Utc>>,
+    /// Why the last launch failed, if it did
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+impl Schedule {
+    pub fn new(target: ScheduleTarget) -> Self {
+        Self {
+            id: ScheduleId::new(),
+            name: String::new(),
+            target,
+            recurrence: Recurrence::default(),
+            enabled: true,
+            created_at: Utc::now(),
+            last_run: None,
+            last_error: None,
+        }
+    }
+
+    /// Next time the schedule fires, if it is enabled
+    pub fn next_run(&self) -> Option<DateTime<Local>> {
+        if !self.enabled {
+            return None;
+        }
+        self.recurrence.next_after(Local::now())
+    }
+
+    /// Whether the schedule fires in `(since, now]`
+    pub fn is_due(&self, since: DateTime<Local>, now: DateTime<Local>) -> bool {
+        self.enabled
+            && self
+                .recurrence
+                .next_after(since)
+                .is_some_and(|at| at <= now)
+    }
+}
@@ -1,7 +1,36 @@
 //! Application settings management
 
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::path::PathBuf;
+use tracing::warn;
+
+use super::alerts::AlertRule;
+use super::broadcast::BroadcastSettings;
+use super::crash_dialogs::CrashDialogSettings;
+use super::email::EmailSettings;
+use super::hotkeys::HotkeyBinding;
+use super::instance::InstanceStatus;
+use super::known_issues::KnownIssue;
+use super::profiler::SelfBudget;
+use super::reservation::{GroupMemoryBudget, ReservationPolicy};
+use super::resource::LimitPreset;
+use super::usage::UsageLimit;
+
+/// Version of the per-key settings layout; stored alongside the keys
+pub const SETTINGS_SCHEMA_VERSION: u32 = 1;
+
+/// Key holding the schema version in the settings table and in exported files
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// Keys that never leave this machine through export/import
+const LOCAL_ONLY_KEYS: &[&str] = &[
+    "settings_lock",
+    "data_directory",
+    "sync_directory",
+    "email_alerts",
+];
 
 /// Application theme
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -60,6 +89,8 @@ pub enum ViewMode {
     Grid,
     List,
     Compact,
+    /// Columns per status; drag cards between them to start, pause or stop
+    Board,
 }
 
 impl ViewMode {
@@ -68,11 +99,356 @@ impl ViewMode {
             Self::Grid => "Grid",
             Self::List => "List",
             Self::Compact => "Compact",
+            Self::Board => "Board",
         }
     }
 
     pub fn all() -> &'static [ViewMode] {
-        &[ViewMode::Grid, ViewMode::List, ViewMode::Compact]
+        &[
+            ViewMode::Grid,
+            ViewMode::List,
+            ViewMode::Compact,
+            ViewMode::Board,
+        ]
+    }
+}
+
+/// Order of the Instances view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum InstanceSort {
+    #[default]
+    Name,
+    Status,
+    Cpu,
+    Memory,
+    Uptime,
+    LastStarted,
+}
+
+impl InstanceSort {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Name => "Name",
+            Self::Status => "Status",
+            Self::Cpu => "CPU",
+            Self::Memory => "Memory",
+            Self::Uptime => "Uptime",
+            Self::LastStarted => "Last started",
+        }
+    }
+
+    pub fn all() -> &'static [InstanceSort] {
+        &[
+            Self::Name,
+            Self::Status,
+            Self::Cpu,
+            Self::Memory,
+            Self::Uptime,
+            Self::LastStarted,
+        ]
+    }
+}
+
+/// Instance states the Instances view can be narrowed to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StatusFilter {
+    /// Starting, running or paused
+    Active,
+    Running,
+    Paused,
+    Stopped,
+    Crashed,
+}
+
+impl StatusFilter {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Active => "Active",
+            Self::Running => "Running",
+            Self::Paused => "Paused",
+            Self::Stopped => "Stopped",
+            Self::Crashed => "Crashed",
+        }
+    }
+
+    pub fn all() -> &'static [StatusFilter] {
+        &[
+            Self::Active,
+            Self::Running,
+            Self::Paused,
+            Self::Stopped,
+            Self::Crashed,
+        ]
+    }
+
+    pub fn matches(&self, status: &InstanceStatus) -> bool {
+        match self {
+            Self::Active => status.is_active(),
+            Self::Running => *status == InstanceStatus::Running,
+            Self::Paused => *status == InstanceStatus::Paused,
+            Self::Stopped => !status.is_active() && *status != InstanceStatus::Crashed,
+            Self::Crashed => *status == InstanceStatus::Crashed,
+        }
+    }
+}
+
+/// Sort order and structured filters of the Instances view, applied on top
+/// of the text search
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct InstanceViewOptions {
+    pub sort: InstanceSort,
+    pub descending: bool,
+    pub status: Option<StatusFilter>,
+    pub group: Option<String>,
+    /// Executable file name, e.g. `Discord.exe`
+    pub executable: Option<String>,
+}
+
+impl InstanceViewOptions {
+    /// Whether any structured filter is set
+    pub fn is_filtered(&self) -> bool {
+        self.status.is_some() || self.group.is_some() || self.executable.is_some()
+    }
+}
+
+/// Instance card size preset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum CardSize {
+    Small,
+    #[default]
+    Medium,
+    Large,
+}
+
+impl CardSize {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Small => "Small",
+            Self::Medium => "Medium",
+            Self::Large => "Large",
+        }
+    }
+
+    pub fn all() -> &'static [CardSize] {
+        &[CardSize::Small, CardSize::Medium, CardSize::Large]
+    }
+
+    /// Content width of a grid card
+    pub fn grid_width(&self) -> f32 {
+        match self {
+            Self::Small => 190.0,
+            Self::Medium => 240.0,
+            Self::Large => 300.0,
+        }
+    }
+}
+
+/// Action that can be placed on an instance card
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuickAction {
+    Start,
+    Stop,
+    Pause,
+    Resume,
+    Restart,
+    Kill,
+    Configure,
+    Details,
+    Delete,
+}
+
+impl QuickAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Start => "Start",
+            Self::Stop => "Stop",
+            Self::Pause => "Pause",
+            Self::Resume => "Resume",
+            Self::Restart => "Restart",
+            Self::Kill => "Force kill",
+            Self::Configure => "Configure",
+            Self::Details => "Details",
+            Self::Delete => "Delete",
+        }
+    }
+
+    /// Actions that make sense for an instance in `status`
+    pub fn available(status: &InstanceStatus) -> &'static [QuickAction] {
+        match status {
+            InstanceStatus::Running => &[
+                Self::Pause,
+                Self::Stop,
+                Self::Restart,
+                Self::Kill,
+                Self::Configure,
+                Self::Details,
+            ],
+            InstanceStatus::Paused => &[
+                Self::Resume,
+                Self::Stop,
+                Self::Kill,
+                Self::Configure,
+                Self::Details,
+            ],
+            InstanceStatus::Stopped | InstanceStatus::Crashed => {
+                &[Self::Start, Self::Configure, Self::Details, Self::Delete]
+            }
+            _ => &[],
+        }
+    }
+}
+
+/// The three card buttons shown per status (crashed instances use the stopped slots)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuickActions {
+    pub running: [Option<QuickAction>; 3],
+    pub paused: [Option<QuickAction>; 3],
+    pub stopped: [Option<QuickAction>; 3],
+}
+
+impl Default for QuickActions {
+    fn default() -> Self {
+        Self {
+            running: [
+                Some(QuickAction::Pause),
+                Some(QuickAction::Stop),
+                Some(QuickAction::Restart),
+            ],
+            paused: [Some(QuickAction::Resume), Some(QuickAction::Stop), None],
+            stopped: [Some(QuickAction::Start), None, None],
+        }
+    }
+}
+
+impl QuickActions {
+    /// Configured slots for `status`, if it has any
+    pub fn slots(&self, status: &InstanceStatus) -> Option<&[Option<QuickAction>; 3]> {
+        match status {
+            InstanceStatus::Running => Some(&self.running),
+            InstanceStatus::Paused => Some(&self.paused),
+            InstanceStatus::Stopped | InstanceStatus::Crashed => Some(&self.stopped),
+            _ => None,
+        }
+    }
+
+    /// Quick actions to show for `status`, skipping ones that do not apply
+    pub fn for_status(&self, status: &InstanceStatus) -> Vec<QuickAction> {
+        let available = QuickAction::available(status);
+        self.slots(status)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .copied()
+            .filter(|a| available.contains(a))
+            .collect()
+    }
+}
+
+/// Which fields instance cards show
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CardDisplay {
+    pub size: CardSize,
+    pub show_pid: bool,
+    pub show_uptime: bool,
+    pub show_group: bool,
+    pub show_tags: bool,
+    /// Custom metadata values as badges
+    pub show_metadata: bool,
+    pub show_network: bool,
+    pub show_gpu: bool,
+    pub quick_actions: QuickActions,
+}
+
+impl Default for CardDisplay {
+    fn default() -> Self {
+        Self {
+            size: CardSize::Medium,
+            show_pid: false,
+            show_uptime: true,
+            show_group: false,
+            show_tags: false,
+            show_metadata: true,
+            show_network: false,
+            show_gpu: false,
+            quick_actions: QuickActions::default(),
+        }
+    }
+}
+
+/// Order of the sidebar navigation and which entries it leaves out
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SidebarLayout {
+    /// View labels from top to bottom; views not listed follow in their default order
+    pub order: Vec<String>,
+    /// Labels of views left out of the sidebar
+    pub hidden: Vec<String>,
+}
+
+impl SidebarLayout {
+    pub fn is_hidden(&self, view: &str) -> bool {
+        self.hidden.iter().any(|v| v == view)
+    }
+
+    pub fn set_hidden(&mut self, view: &str, hidden: bool) {
+        self.hidden.retain(|v| v != view);
+        if hidden {
+            self.hidden.push(view.to_string());
+        }
+    }
+}
+
+/// A main view shown in its own window instead of the main window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DetachedView {
+    /// Label of the view, e.g. "Dashboard"
+    pub view: String,
+    /// Outer position of the window, in points
+    pub position: Option<(f32, f32)>,
+    /// Inner size of the window, in points
+    pub size: Option<(f32, f32)>,
+}
+
+/// PIN lock guarding configuration screens (kiosk deployments)
+///
+/// This only gates the UI; anyone with access to the database can still edit settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SettingsLock {
+    /// Random salt mixed into the PIN hash
+    pub salt: String,
+    /// Hex-encoded hash of the salted PIN
+    pub pin_hash: String,
+}
+
+impl SettingsLock {
+    /// Number of hashing rounds, to slow down guessing
+    const ROUNDS: u32 = 100_000;
+
+    pub fn new(pin: &str) -> Self {
+        let salt = uuid::Uuid::new_v4().to_string();
+        let pin_hash = Self::hash(&salt, pin);
+        Self { salt, pin_hash }
+    }
+
+    /// Check a PIN against the stored hash
+    pub fn verify(&self, pin: &str) -> bool {
+        Self::hash(&self.salt, pin) == self.pin_hash
+    }
+
+    fn hash(salt: &str, pin: &str) -> String {
+        use sha2::{Digest, Sha256};
+
+        let mut digest = Sha256::digest(format!("{}:{}", salt, pin).as_bytes());
+        for _ in 1..Self::ROUNDS {
+            let mut hasher = Sha256::new();
+            hasher.update(digest);
+            hasher.update(salt.as_bytes());
+            digest = hasher.finalize();
+        }
+        digest.iter().map(|b| format!("{:02x}", b)).collect()
     }
 }
 
@@ -86,12 +462,30 @@ pub struct Settings {
     pub minimize_to_tray: bool,
     /// Automatically restore previous session
     pub auto_restore_sessions: bool,
+    /// Ask which instances to restore instead of relaunching the whole session
+    #[serde(default)]
+    pub session_restore_prompt: bool,
     /// Application theme
     pub theme: Theme,
+    /// Name of the custom theme applied instead of `theme`
+    #[serde(default)]
+    pub custom_theme: Option<String>,
     /// Default view mode
     pub view_mode: ViewMode,
     /// Show system resource overview
     pub show_system_resources: bool,
+    /// Show the Instances view as one lane per group
+    #[serde(default)]
+    pub group_lanes: bool,
+    /// Fields and size of instance cards
+    #[serde(default)]
+    pub card_display: CardDisplay,
+    /// Sort order and filters of the Instances view
+    #[serde(default)]
+    pub instance_view: InstanceViewOptions,
+    /// Order and visibility of the sidebar entries
+    #[serde(default)]
+    pub sidebar: SidebarLayout,
 
     // Default Resource Limits
     /// Default CPU limit for new instances (0 = unlimited)
@@ -102,6 +496,15 @@ pub struct Settings {
     pub default_network_limit: u64,
     /// Default process priority
     pub default_priority: i8,
+    /// Named limit sets offered in the instance and profile dialogs
+    #[serde(default)]
+    pub limit_presets: Vec<LimitPreset>,
+    /// What to do when a launch's memory limit does not fit in free RAM or its group's budget
+    #[serde(default)]
+    pub memory_reservation: ReservationPolicy,
+    /// Memory budgets per group, checked at launch
+    #[serde(default)]
+    pub group_memory_budgets: Vec<GroupMemoryBudget>,
 
     // Automation
     /// Delay between staggered instance launches (ms)
@@ -114,27 +517,81 @@ pub struct Settings {
     pub enable_health_checks: bool,
     /// Health check interval in seconds
     pub health_check_interval_secs: u32,
+    /// Daily runtime budgets per executable
+    #[serde(default)]
+    pub usage_limits: Vec<UsageLimit>,
+    /// How often pinned window regions are captured, in seconds
+    #[serde(default)]
+    pub region_capture_secs: u32,
+    /// Crash dialogs closed so crashed instances exit and can auto-restart
+    #[serde(default)]
+    pub crash_dialogs: CrashDialogSettings,
 
     // Notifications
     /// Notification level
     pub notification_level: NotificationLevel,
     /// Play sound on notifications
     pub notification_sound: bool,
+    /// Per-event, per-instance routing to notification channels; first match wins
+    #[serde(default)]
+    pub alert_rules: Vec<AlertRule>,
+    /// URL alerts routed to the webhook channel are POSTed to
+    #[serde(default)]
+    pub alert_webhook_url: String,
+    /// SMTP server and templates for the email channel
+    #[serde(default)]
+    pub email_alerts: EmailSettings,
+
+    // Hotkeys
+    /// Shortcuts that work while another window is focused
+    #[serde(default)]
+    pub hotkeys: Vec<HotkeyBinding>,
+    /// Input mirrored between the members of a group, toggled by a hotkey
+    #[serde(default)]
+    pub broadcast: BroadcastSettings,
+
+    // Known Issues
+    /// Hints shown in the New Instance dialog for specific executables
+    #[serde(default)]
+    pub known_issues: Vec<KnownIssue>,
+    /// Custom metadata fields, such as Account or Server, offered on every instance
+    #[serde(default)]
+    pub metadata_fields: Vec<String>,
 
     // Advanced
     /// Custom data directory
     pub data_directory: Option<PathBuf>,
+    /// Folder profiles and quick launch items are mirrored into, e.g. in Dropbox
+    #[serde(default)]
+    pub sync_directory: Option<PathBuf>,
     /// Enable debug logging
     pub debug_logging: bool,
     /// Maximum instances allowed (0 = unlimited)
     pub max_instances: u32,
     /// Resource monitor update interval in ms
     pub monitor_interval_ms: u32,
+    /// Minutes of per-instance usage shown in the resource graphs
+    #[serde(default)]
+    pub usage_history_minutes: u32,
     /// Keep instance history for N days (0 = forever)
     pub history_retention_days: u32,
+    /// Enable experimental checkpoint (suspend-to-disk) support
+    #[serde(default)]
+    pub experimental_checkpoints: bool,
+    /// Show frame, monitor and database timings over the UI
+    #[serde(default)]
+    pub show_profiler_overlay: bool,
+    /// Footprint MultiInstance itself warns about exceeding
+    #[serde(default)]
+    pub self_budget: SelfBudget,
+
+    // Security
+    /// PIN required to open settings and instance configuration
+    #[serde(default)]
+    pub settings_lock: Option<SettingsLock>,
 
     // UI State (not user-configurable, just persisted)
-    /// Sidebar collapsed state
+    /// Sidebar shows icons only
     pub sidebar_collapsed: bool,
     /// Last selected application path
     pub last_app_path: Option<PathBuf>,
@@ -142,6 +599,9 @@ pub struct Settings {
     pub window_position: Option<(i32, i32)>,
     /// Window size
     pub window_size: Option<(u32, u32)>,
+    /// Views torn off into their own windows, reopened at startup
+    #[serde(default)]
+    pub detached_views: Vec<DetachedView>,
 }
 
 impl Default for Settings {
@@ -151,15 +611,24 @@ impl Default for Settings {
             start_with_system: false,
             minimize_to_tray: true,
             auto_restore_sessions: false,
+            session_restore_prompt: true,
             theme: Theme::Dark,
+            custom_theme: None,
             view_mode: ViewMode::Grid,
             show_system_resources: true,
+            group_lanes: false,
+            card_display: CardDisplay::default(),
+            instance_view: InstanceViewOptions::default(),
+            sidebar: SidebarLayout::default(),
 
             // Default Resource Limits
             default_cpu_limit: 0,
             default_ram_limit: 0,
             default_network_limit: 0,
             default_priority: 0,
+            limit_presets: LimitPreset::defaults(),
+            memory_reservation: ReservationPolicy::default(),
+            group_memory_budgets: Vec::new(),
 
             // Automation
             staggered_launch_delay_ms: 1000,
@@ -167,23 +636,46 @@ impl Default for Settings {
             default_restart_delay_secs: 5,
             enable_health_checks: false,
             health_check_interval_secs: 30,
+            usage_limits: Vec::new(),
+            region_capture_secs: 5,
+            crash_dialogs: CrashDialogSettings::default(),
 
             // Notifications
             notification_level: NotificationLevel::Important,
             notification_sound: true,
+            alert_rules: Vec::new(),
+            alert_webhook_url: String::new(),
+            email_alerts: EmailSettings::default(),
+
+            // Hotkeys
+            hotkeys: Vec::new(),
+            broadcast: BroadcastSettings::default(),
+
+            // Known Issues
+            known_issues: KnownIssue::defaults(),
+            metadata_fields: Vec::new(),
 
             // Advanced
             data_directory: None,
+            sync_directory: None,
             debug_logging: false,
             max_instances: 0,
             monitor_interval_ms: 1000,
+            usage_history_minutes: super::monitor::DEFAULT_HISTORY_MINUTES,
             history_retention_days: 30,
+            experimental_checkpoints: false,
+            show_profiler_overlay: false,
+            self_budget: SelfBudget::default(),
+
+            // Security
+            settings_lock: None,
 
             // UI State
             sidebar_collapsed: false,
             last_app_path: None,
             window_position: None,
             window_size: None,
+            detached_views: Vec::new(),
         }
     }
 }
@@ -213,12 +705,120 @@ impl Settings {
         self.get_data_directory().join("logs")
     }
 
+    /// Get the checkpoints directory
+    pub fn get_checkpoints_directory(&self) -> PathBuf {
+        self.get_data_directory().join("checkpoints")
+    }
+
+    /// Get the data directory snapshots directory
+    pub fn get_snapshots_directory(&self) -> PathBuf {
+        self.get_data_directory().join("snapshots")
+    }
+
     /// Validate settings and fix any invalid values
     pub fn validate(&mut self) {
         self.default_cpu_limit = self.default_cpu_limit.min(100);
         self.default_priority = self.default_priority.clamp(-20, 19);
         self.monitor_interval_ms = self.monitor_interval_ms.max(100);
         self.health_check_interval_secs = self.health_check_interval_secs.max(5);
+        self.region_capture_secs = self.region_capture_secs.max(1);
+        self.usage_history_minutes = self.usage_history_minutes.clamp(1, 240);
+    }
+
+    /// Split settings into one JSON-encoded value per key
+    pub fn to_entries(&self) -> Result<Vec<(String, String)>> {
+        let Value::Object(map) = serde_json::to_value(self)? else {
+            anyhow::bail!("Settings did not serialize to an object");
+        };
+        map.into_iter()
+            .map(|(key, value)| Ok((key, serde_json::to_string(&value)?)))
+            .collect()
+    }
+
+    /// Rebuild settings from per-key values, starting from defaults.
+    ///
+    /// Unknown keys are dropped and a key whose value is unreadable keeps its default,
+    /// so one bad row never costs the rest of the settings.
+    pub fn from_entries(entries: impl IntoIterator<Item = (String, String)>) -> Self {
+        let values = entries.into_iter().filter_map(|(key, raw)| {
+            match serde_json::from_str::<Value>(&raw) {
+                Ok(value) => Some((key, value)),
+                Err(e) => {
+                    warn!("Ignoring unreadable setting '{}': {}", key, e);
+                    None
+                }
+            }
+        });
+        Self::merge_onto_defaults(values)
+    }
+
+    fn merge_onto_defaults(values: impl IntoIterator<Item = (String, Value)>) -> Self {
+        let Ok(Value::Object(mut merged)) = serde_json::to_value(Self::default()) else {
+            return Self::default();
+        };
+
+        for (key, value) in values {
+            if !merged.contains_key(&key) {
+                continue;
+            }
+            let previous = merged.insert(key.clone(), value);
+            if serde_json::from_value::<Self>(Value::Object(merged.clone())).is_err() {
+                warn!("Ignoring invalid value for setting '{}'", key);
+                if let Some(previous) = previous {
+                    merged.insert(key, previous);
+                }
+            }
+        }
+
+        let mut settings: Self = serde_json::from_value(Value::Object(merged)).unwrap_or_default();
+        settings.validate();
+        settings
+    }
+
+    /// Serialize settings for export, without machine-local keys
+    pub fn export_json(&self) -> Result<String> {
+        let Value::Object(mut map) = serde_json::to_value(self)? else {
+            anyhow::bail!("Settings did not serialize to an object");
+        };
+        for key in LOCAL_ONLY_KEYS {
+            map.remove(*key);
+        }
+        map.insert(
+            SCHEMA_VERSION_KEY.to_string(),
+            Value::from(SETTINGS_SCHEMA_VERSION),
+        );
+        Ok(serde_json::to_string_pretty(&Value::Object(map))?)
+    }
+
+    /// Read exported settings, keeping this machine's local keys from `current`
+    pub fn import_json(json: &str, current: &Settings) -> Result<Self> {
+        let map: Map<String, Value> =
+            serde_json::from_str(json).context("Settings file is not a JSON object")?;
+
+        let version = map
+            .get(SCHEMA_VERSION_KEY)
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+        if version > SETTINGS_SCHEMA_VERSION as u64 {
+            anyhow::bail!(
+                "Settings file is from a newer version (schema {}, supported {})",
+                version,
+                SETTINGS_SCHEMA_VERSION
+            );
+        }
+
+        // Every local key comes from `current`, whatever the file holds
+        let Value::Object(local) = serde_json::to_value(current)? else {
+            anyhow::bail!("Settings did not serialize to an object");
+        };
+        let local = local
+            .into_iter()
+            .filter(|(key, _)| LOCAL_ONLY_KEYS.contains(&key.as_str()));
+        Ok(Self::merge_onto_defaults(
+            map.into_iter()
+                .filter(|(key, _)| !LOCAL_ONLY_KEYS.contains(&key.as_str()))
+                .chain(local),
+        ))
     }
 
     /// Create default resource limits from settings
@@ -0,0 +1,171 @@
+//! Data directory snapshots - Named copies of an instance's data for rollback
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use super::instance::InstanceId;
+
+/// Name of the metadata file stored with each snapshot
+const MANIFEST_FILE: &str = "snapshot.json";
+
+/// Folder inside a snapshot holding the copied data
+const DATA_FOLDER: &str = "data";
+
+/// A named snapshot of an instance's data directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Instance the snapshot belongs to
+    pub instance_id: InstanceId,
+    /// User-provided name
+    pub name: String,
+    /// When the snapshot was taken
+    pub created_at: DateTime<Utc>,
+    /// Total size of the copied files
+    pub size_bytes: u64,
+    /// Directory holding the snapshot
+    #[serde(skip)]
+    pub directory: PathBuf,
+}
+
+impl Snapshot {
+    /// Copy `data_dir` into a new snapshot under `snapshots_dir`
+    pub fn create(
+        instance_id: InstanceId,
+        name: &str,
+        data_dir: &Path,
+        snapshots_dir: &Path,
+    ) -> Result<Self> {
+        if !data_dir.exists() {
+            anyhow::bail!("Instance has no data directory yet");
+        }
+
+        let created_at = Utc::now();
+        let slug: String = name
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        let directory =
+            snapshots_dir.join(format!("{}-{}", created_at.format("%Y%m%d%H%M%S"), slug));
+
+        let size_bytes = match copy_dir(data_dir, &directory.join(DATA_FOLDER)) {
+            Ok(size) => size,
+            Err(e) => {
+                let _ = std::fs::remove_dir_all(&directory);
+                return Err(e.context("Failed to copy data directory"));
+            }
+        };
+
+        let snapshot = Self {
+            instance_id,
+            name: name.to_string(),
+            created_at,
+            size_bytes,
+            directory,
+        };
+        let json = serde_json::to_string_pretty(&snapshot)?;
+        std::fs::write(snapshot.directory.join(MANIFEST_FILE), json)?;
+
+        info!(
+            "Created snapshot '{}' of {:?} ({} bytes)",
+            name, data_dir, size_bytes
+        );
+        Ok(snapshot)
+    }
+
+    /// Load a snapshot from its directory
+    pub fn load(directory: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(directory.join(MANIFEST_FILE))
+            .context("Failed to read snapshot manifest")?;
+        let mut snapshot: Self = serde_json::from_str(&json)?;
+        snapshot.directory = directory.to_path_buf();
+        Ok(snapshot)
+    }
+
+    /// List snapshots under a directory, newest first
+    pub fn list(snapshots_dir: &Path) -> Vec<Self> {
+        let Ok(entries) = std::fs::read_dir(snapshots_dir) else {
+            return Vec::new();
+        };
+
+        let mut snapshots: Vec<Self> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_dir())
+            .filter_map(|e| Self::load(&e.path()).ok())
+            .collect();
+        snapshots.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+        snapshots
+    }
+
+    /// Replace `data_dir` with the contents of this snapshot
+    pub fn restore(&self, data_dir: &Path) -> Result<()> {
+        let source = self.directory.join(DATA_FOLDER);
+        if !source.exists() {
+            anyhow::bail!("Snapshot data is missing");
+        }
+
+        // Keep the current data aside until the copy succeeds
+        let folder_name = data_dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let backup = data_dir.with_file_name(format!("{}.restore-backup", folder_name));
+        if backup.exists() {
+            std::fs::remove_dir_all(&backup)?;
+        }
+        if data_dir.exists() {
+            std::fs::rename(data_dir, &backup).context("Failed to move current data aside")?;
+        }
+
+        if let Err(e) = copy_dir(&source, data_dir) {
+            let _ = std::fs::remove_dir_all(data_dir);
+            if backup.exists() {
+                let _ = std::fs::rename(&backup, data_dir);
+            }
+            return Err(e.context("Failed to restore snapshot"));
+        }
+
+        if backup.exists() {
+            if let Err(e) = std::fs::remove_dir_all(&backup) {
+                warn!("Failed to remove restore backup {:?}: {}", backup, e);
+            }
+        }
+
+        info!("Restored snapshot '{}' into {:?}", self.name, data_dir);
+        Ok(())
+    }
+
+    /// Delete the snapshot from disk
+    pub fn delete(&self) -> Result<()> {
+        std::fs::remove_dir_all(&self.directory)?;
+        Ok(())
+    }
+}
+
+/// Recursively copy a directory, returning the number of bytes copied.
+///
+/// `std::fs::copy` clones files copy-on-write where the filesystem supports it
+/// (APFS, Btrfs, XFS, ReFS). Symlinks such as shared cache links are skipped
+/// since they are recreated on the next launch.
+pub fn copy_dir(source: &Path, destination: &Path) -> Result<u64> {
+    std::fs::create_dir_all(destination)?;
+
+    let mut total = 0;
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let target = destination.join(entry.file_name());
+
+        if file_type.is_symlink() {
+            continue;
+        } else if file_type.is_dir() {
+            total += copy_dir(&entry.path(), &target)?;
+        } else {
+            total += std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(total)
+}
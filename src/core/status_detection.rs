@@ -0,0 +1,184 @@
+//! Status detection - Reading an instance's on-screen state with OCR for health checks
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use image::RgbaImage;
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+
+use super::window_capture::CaptureRegion;
+
+/// Whether this build can read text from window captures
+pub const OCR_AVAILABLE: bool = cfg!(feature = "ocr");
+
+/// What a rule does when its pattern is (or is not) on screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RuleAction {
+    /// Unhealthy while the pattern is on screen (e.g. "Disconnected")
+    UnhealthyIfFound,
+    /// Unhealthy while the pattern is missing (e.g. "Online")
+    UnhealthyIfMissing,
+}
+
+impl RuleAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::UnhealthyIfFound => "Unhealthy if found",
+            Self::UnhealthyIfMissing => "Unhealthy if missing",
+        }
+    }
+
+    pub fn all() -> [RuleAction; 2] {
+        [Self::UnhealthyIfFound, Self::UnhealthyIfMissing]
+    }
+}
+
+/// Case-insensitive regex matched against the recognized text
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusRule {
+    pub pattern: String,
+    pub action: RuleAction,
+}
+
+/// Window region to read and the rules that judge its text
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusDetection {
+    pub region: CaptureRegion,
+    pub rules: Vec<StatusRule>,
+}
+
+impl Default for StatusDetection {
+    fn default() -> Self {
+        Self {
+            region: CaptureRegion::default(),
+            rules: vec![StatusRule {
+                pattern: "disconnected".to_string(),
+                action: RuleAction::UnhealthyIfFound,
+            }],
+        }
+    }
+}
+
+/// Verdict of one health check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Health {
+    Healthy,
+    /// A rule fired; holds a description of it
+    Unhealthy(String),
+    /// The check could not run (no window, OCR unavailable, bad pattern)
+    Unknown(String),
+}
+
+/// Result of the latest health check of an instance
+#[derive(Debug, Clone)]
+pub struct HealthCheck {
+    pub health: Health,
+    /// Text recognized in the region
+    pub text: String,
+    /// What the health probe printed
+    pub output: String,
+    pub checked_at: DateTime<Local>,
+}
+
+impl HealthCheck {
+    pub fn is_unhealthy(&self) -> bool {
+        matches!(self.health, Health::Unhealthy(_))
+    }
+
+    /// Merge the results of screen detection and the health probe; trouble
+    /// reported by either wins
+    pub fn combine(self, other: HealthCheck) -> HealthCheck {
+        let severity = |health: &Health| match health {
+            Health::Healthy => 0,
+            Health::Unknown(_) => 1,
+            Health::Unhealthy(_) => 2,
+        };
+        let health = if severity(&other.health) > severity(&self.health) {
+            other.health
+        } else {
+            self.health
+        };
+        HealthCheck {
+            health,
+            text: self.text + &other.text,
+            output: self.output + &other.output,
+            checked_at: other.checked_at,
+        }
+    }
+}
+
+impl StatusDetection {
+    /// Capture the region, read it and apply the rules
+    pub fn check(&self, pid: u32) -> HealthCheck {
+        let text = crate::platform::capture_window(pid)
+            .context("Failed to capture window")
+            .and_then(|window| self.region.crop(&window))
+            .and_then(|region| recognize(&region));
+        let (health, text) = match text {
+            Ok(text) => (self.evaluate(&text), text),
+            Err(e) => (Health::Unknown(format!("{:#}", e)), String::new()),
+        };
+        HealthCheck {
+            health,
+            text,
+            output: String::new(),
+            checked_at: Local::now(),
+        }
+    }
+
+    /// Apply the rules in order; the first one that fires decides
+    pub fn evaluate(&self, text: &str) -> Health {
+        for rule in &self.rules {
+            if rule.pattern.trim().is_empty() {
+                continue;
+            }
+            let regex = match RegexBuilder::new(&rule.pattern)
+                .case_insensitive(true)
+                .build()
+            {
+                Ok(regex) => regex,
+                Err(e) => {
+                    return Health::Unknown(format!("Invalid pattern '{}': {}", rule.pattern, e))
+                }
+            };
+            match (rule.action, regex.find(text)) {
+                (RuleAction::UnhealthyIfFound, Some(found)) => {
+                    return Health::Unhealthy(format!("'{}' on screen", found.as_str().trim()))
+                }
+                (RuleAction::UnhealthyIfMissing, None) => {
+                    return Health::Unhealthy(format!("'{}' not on screen", rule.pattern))
+                }
+                _ => {}
+            }
+        }
+        Health::Healthy
+    }
+}
+
+/// Read the text in an image with tesseract
+#[cfg(feature = "ocr")]
+pub fn recognize(image: &RgbaImage) -> Result<String> {
+    // Status bars are small; scaling them up makes tesseract far more reliable
+    const OCR_UPSCALE: u32 = 3;
+    let scaled = image::imageops::resize(
+        image,
+        image.width() * OCR_UPSCALE,
+        image.height() * OCR_UPSCALE,
+        image::imageops::FilterType::Triangle,
+    );
+    let mut png = Vec::new();
+    scaled.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)?;
+
+    let mut tesseract = leptess::LepTess::new(None, "eng")
+        .context("Failed to start tesseract; is the English language data installed?")?;
+    tesseract
+        .set_image_from_mem(&png)
+        .context("Tesseract could not read the capture")?;
+    Ok(tesseract.get_utf8_text()?.trim().to_string())
+}
+
+/// Read the text in an image; this build has no OCR engine
+#[cfg(not(feature = "ocr"))]
+pub fn recognize(_image: &RgbaImage) -> Result<String> {
+    anyhow::bail!("This build of MultiInstance has no OCR support (build with --features ocr)")
+}
@@ -0,0 +1,170 @@
+//! Usage limits - Daily runtime budgets per executable
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Fraction of the budget at which a warning is raised
+const WARNING_FRACTION: f64 = 0.9;
+
+/// Daily runtime budget for one executable, shared by all of its instances
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageLimit {
+    /// Executable file name, e.g. `game.exe` (matched case-insensitively)
+    pub executable: String,
+    /// Minutes per day any of its instances may run
+    pub daily_minutes: u32,
+}
+
+impl UsageLimit {
+    pub fn daily_secs(&self) -> u64 {
+        self.daily_minutes as u64 * 60
+    }
+}
+
+/// Key usage is tracked under: the lowercased executable file name
+pub fn executable_key(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Something the caller should tell the user about
+#[derive(Debug, Clone)]
+pub enum UsageEvent {
+    /// Usage crossed the warning threshold
+    Warning {
+        executable: String,
+        used_mins: u64,
+        limit_mins: u32,
+    },
+    /// The budget is used up and running instances were stopped
+    Exhausted { executable: String, stopped: usize },
+}
+
+/// Runtime accumulated today, per executable key
+#[derive(Debug)]
+pub struct UsageTracker {
+    /// Local calendar day the totals belong to
+    day: NaiveDate,
+    /// Seconds used today
+    used: HashMap<String, u64>,
+    /// Seconds not yet written to the database
+    unsaved: HashMap<String, u64>,
+    /// Keys already warned about today
+    warned: HashSet<String>,
+    /// Time of the last tick
+    last_tick: Option<Instant>,
+    /// Sub-second remainder carried between ticks
+    carry: Duration,
+}
+
+impl UsageTracker {
+    /// Start tracking with the totals already recorded for `day`
+    pub fn new(day: NaiveDate, used: HashMap<String, u64>) -> Self {
+        Self {
+            day,
+            used,
+            unsaved: HashMap::new(),
+            warned: HashSet::new(),
+            last_tick: None,
+            carry: Duration::ZERO,
+        }
+    }
+
+    /// Today's local date
+    pub fn today() -> NaiveDate {
+        Local::now().date_naive()
+    }
+
+    pub fn day(&self) -> NaiveDate {
+        self.day
+    }
+
+    /// Seconds `key` has run today
+    pub fn used_secs(&self, key: &str) -> u64 {
+        self.used.get(key).copied().unwrap_or(0)
+    }
+
+    /// All of today's totals
+    pub fn used(&self) -> &HashMap<String, u64> {
+        &self.used
+    }
+
+    /// Add the time since the last tick to every key that has a running instance.
+    /// Running time counts once per executable, however many instances it has.
+    pub fn tick(&mut self, running: &HashSet<String>) {
+        let now = Instant::now();
+        let elapsed = self
+            .last_tick
+            .replace(now)
+            .map(|last| now.duration_since(last))
+            .unwrap_or_default()
+            + self.carry;
+        let secs = elapsed.as_secs();
+        self.carry = elapsed - Duration::from_secs(secs);
+
+        if secs == 0 {
+            return;
+        }
+        for key in running {
+            *self.used.entry(key.clone()).or_insert(0) += secs;
+            *self.unsaved.entry(key.clone()).or_insert(0) += secs;
+        }
+    }
+
+    /// Start a new day when the date changed; returns the finished day's unsaved totals
+    pub fn roll_over(&mut self, today: NaiveDate) -> Option<(NaiveDate, HashMap<String, u64>)> {
+        if today == self.day {
+            return None;
+        }
+        let finished = (self.day, std::mem::take(&mut self.unsaved));
+        self.day = today;
+        self.used.clear();
+        self.warned.clear();
+        Some(finished)
+    }
+
+    /// Take the totals not yet written to the database
+    pub fn take_unsaved(&mut self) -> HashMap<String, u64> {
+        std::mem::take(&mut self.unsaved)
+    }
+
+    /// Whether the budget for `key` is used up
+    pub fn is_exhausted(&self, key: &str, limits: &[UsageLimit]) -> bool {
+        limits
+            .iter()
+            .find(|l| l.daily_minutes > 0 && l.executable.eq_ignore_ascii_case(key))
+            .map(|l| self.used_secs(key) >= l.daily_secs())
+            .unwrap_or(false)
+    }
+
+    /// Compare today's usage against the limits. Returns warnings to raise and
+    /// the keys whose instances must be stopped.
+    pub fn check(&mut self, limits: &[UsageLimit]) -> (Vec<UsageEvent>, Vec<String>) {
+        let mut warnings = Vec::new();
+        let mut over = Vec::new();
+
+        for limit in limits.iter().filter(|l| l.daily_minutes > 0) {
+            let key = limit.executable.to_lowercase();
+            let used = self.used_secs(&key);
+
+            if used >= limit.daily_secs() {
+                over.push(key);
+            } else if used as f64 >= limit.daily_secs() as f64 * WARNING_FRACTION
+                && self.warned.insert(key.clone())
+            {
+                warnings.push(UsageEvent::Warning {
+                    executable: limit.executable.clone(),
+                    used_mins: used / 60,
+                    limit_mins: limit.daily_minutes,
+                });
+            }
+        }
+
+        (warnings, over)
+    }
+}
@@ -0,0 +1,48 @@
+//! Virtual desktops - Moves an instance's windows to a chosen Windows desktop
+//!
+//! Windows has no public API that lists virtual desktops, so they are read from
+//! Explorer's state in the registry. Once the instance's windows appear they are
+//! moved with IVirtualDesktopManager.
+
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::platform;
+
+/// How long a listing is reused while a desktop menu stays open
+const LIST_TTL: Duration = Duration::from_secs(2);
+
+/// A virtual desktop an instance's windows are moved to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VirtualDesktop {
+    /// Desktop GUID, e.g. `1D5E6C4A-2F6B-4B8E-9C3D-0A1B2C3D4E5F`
+    pub id: String,
+    /// Name the desktop had when it was chosen
+    pub name: String,
+}
+
+/// Desktops from the last listing and when it was made
+type Listing = Option<(Instant, Vec<VirtualDesktop>)>;
+
+static DESKTOPS: LazyLock<Mutex<Listing>> = LazyLock::new(|| Mutex::new(None));
+
+/// Desktops in task view order; empty when they cannot be listed
+pub fn desktops() -> Vec<VirtualDesktop> {
+    let Ok(mut cached) = DESKTOPS.lock() else {
+        return Vec::new();
+    };
+    if let Some((listed_at, desktops)) = cached.as_ref() {
+        if listed_at.elapsed() < LIST_TTL {
+            return desktops.clone();
+        }
+    }
+    let desktops = platform::virtual_desktops().unwrap_or_else(|e| {
+        warn!("Failed to list virtual desktops: {}", e);
+        Vec::new()
+    });
+    *cached = Some((Instant::now(), desktops.clone()));
+    desktops
+}
@@ -0,0 +1,136 @@
+//! Window captures - Pinned regions for the dashboard, thumbnails for the quick
+//! switcher and screenshots saved into an instance's data directory
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+
+/// Rectangle of a window to capture, in pixels from its top-left corner
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Default for CaptureRegion {
+    fn default() -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            width: 240,
+            height: 40,
+        }
+    }
+}
+
+impl CaptureRegion {
+    /// Cut the region out of a full window capture, clamped to the window's size
+    pub fn crop(&self, window: &RgbaImage) -> Result<RgbaImage> {
+        let x = self.x.min(window.width());
+        let y = self.y.min(window.height());
+        let width = self.width.min(window.width() - x);
+        let height = self.height.min(window.height() - y);
+        if width == 0 || height == 0 {
+            anyhow::bail!(
+                "Region lies outside the {}x{} window",
+                window.width(),
+                window.height()
+            );
+        }
+        Ok(image::imageops::crop_imm(window, x, y, width, height).to_image())
+    }
+}
+
+/// Latest capture of an instance's pinned region
+#[derive(Debug, Clone)]
+pub struct RegionCapture {
+    /// The cropped region, or why it could not be captured
+    pub image: Result<Arc<RgbaImage>, String>,
+    pub captured_at: DateTime<Local>,
+}
+
+/// Capture an instance's main window and cut out its pinned region
+pub fn capture(pid: u32, region: &CaptureRegion) -> RegionCapture {
+    let image = crate::platform::capture_window(pid)
+        .context("Failed to capture window")
+        .and_then(|window| region.crop(&window))
+        .map(Arc::new)
+        .map_err(|e| format!("{:#}", e));
+    RegionCapture {
+        image,
+        captured_at: Local::now(),
+    }
+}
+
+/// Width window thumbnails are scaled down to
+pub const THUMBNAIL_WIDTH: u32 = 240;
+
+/// Capture an instance's main window scaled down to `THUMBNAIL_WIDTH`
+pub fn thumbnail(pid: u32) -> RegionCapture {
+    let image = crate::platform::capture_window(pid)
+        .context("Failed to capture window")
+        .map(|window| Arc::new(scale_down(&window)))
+        .map_err(|e| format!("{:#}", e));
+    RegionCapture {
+        image,
+        captured_at: Local::now(),
+    }
+}
+
+fn scale_down(window: &RgbaImage) -> RgbaImage {
+    let scale = (THUMBNAIL_WIDTH as f32 / window.width().max(1) as f32).min(1.0);
+    let height = ((window.height() as f32 * scale).round() as u32).max(1);
+    let width = ((window.width() as f32 * scale).round() as u32).max(1);
+    image::imageops::thumbnail(window, width, height)
+}
+
+/// Folder inside an instance's data directory screenshots are saved to
+pub const SCREENSHOTS_DIR: &str = "screenshots";
+
+/// Latest screenshot taken of an instance
+#[derive(Debug, Clone)]
+pub struct Screenshot {
+    /// Where the PNG was saved; the thumbnail holds the error if it wasn't
+    pub path: Option<PathBuf>,
+    pub thumbnail: RegionCapture,
+}
+
+/// Capture an instance's main window and save it as a PNG named after the
+/// current time in the screenshots folder of `data_dir`
+pub fn screenshot(pid: u32, data_dir: &Path) -> Screenshot {
+    let captured_at = Local::now();
+    let saved = crate::platform::capture_window(pid)
+        .context("Failed to capture window")
+        .and_then(|window| {
+            let dir = data_dir.join(SCREENSHOTS_DIR);
+            std::fs::create_dir_all(&dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+            let path = dir.join(format!("{}.png", captured_at.format("%Y-%m-%d_%H-%M-%S")));
+            window
+                .save_with_format(&path, image::ImageFormat::Png)
+                .with_context(|| format!("Failed to save {}", path.display()))?;
+            Ok((path, Arc::new(scale_down(&window))))
+        });
+    match saved {
+        Ok((path, image)) => Screenshot {
+            path: Some(path),
+            thumbnail: RegionCapture {
+                image: Ok(image),
+                captured_at,
+            },
+        },
+        Err(e) => Screenshot {
+            path: None,
+            thumbnail: RegionCapture {
+                image: Err(format!("{:#}", e)),
+                captured_at,
+            },
+        },
+    }
+}
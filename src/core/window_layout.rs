@@ -0,0 +1,93 @@
+//! Window layouts - Arrange the top-level windows of running instances
+//!
+//! The windows of all matching instances are collected in instance name order
+//! and placed into the work area of the primary screen, so many copies of an
+//! app can be watched side by side without dragging each one into place.
+
+use serde::{Deserialize, Serialize};
+
+/// Offset between cascaded windows, in pixels
+const CASCADE_STEP: i32 = 32;
+
+/// Share of the work area each cascaded window covers
+const CASCADE_SIZE: f32 = 0.6;
+
+/// How windows are placed in the work area
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WindowLayout {
+    /// Equal tiles in a near-square grid sized to the window count
+    Tile,
+    /// Overlapping windows, each offset from the previous one
+    Cascade,
+    /// A fixed grid; windows beyond its cells wrap around to the first one
+    Grid { columns: u32, rows: u32 },
+}
+
+impl WindowLayout {
+    pub fn label(&self) -> String {
+        match self {
+            Self::Tile => "Tile all".to_string(),
+            Self::Cascade => "Cascade".to_string(),
+            Self::Grid { columns, rows } => format!("Grid {}×{}", columns, rows),
+        }
+    }
+}
+
+/// A screen rectangle in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// Rectangles for `count` windows laid out in `area`
+pub fn arrange(layout: WindowLayout, area: WindowRect, count: usize) -> Vec<WindowRect> {
+    if count == 0 {
+        return Vec::new();
+    }
+    match layout {
+        WindowLayout::Tile => {
+            let columns = (count as f64).sqrt().ceil() as usize;
+            let rows = count.div_ceil(columns);
+            grid(area, columns, rows, count)
+        }
+        WindowLayout::Grid { columns, rows } => {
+            grid(area, columns.max(1) as usize, rows.max(1) as usize, count)
+        }
+        WindowLayout::Cascade => {
+            let width = (area.width as f32 * CASCADE_SIZE) as i32;
+            let height = (area.height as f32 * CASCADE_SIZE) as i32;
+            // Start over at the top left once a window would leave the area
+            let steps = ((area.width - width).min(area.height - height) / CASCADE_STEP).max(0) + 1;
+            (0..count as i32)
+                .map(|i| {
+                    let offset = (i % steps) * CASCADE_STEP;
+                    WindowRect {
+                        x: area.x + offset,
+                        y: area.y + offset,
+                        width,
+                        height,
+                    }
+                })
+                .collect()
+        }
+    }
+}
+
+fn grid(area: WindowRect, columns: usize, rows: usize, count: usize) -> Vec<WindowRect> {
+    let width = area.width / columns as i32;
+    let height = area.height / rows as i32;
+    (0..count)
+        .map(|i| {
+            let cell = i % (columns * rows);
+            WindowRect {
+                x: area.x + (cell % columns) as i32 * width,
+                y: area.y + (cell / columns) as i32 * height,
+                width,
+                height,
+            }
+        })
+        .collect()
+}
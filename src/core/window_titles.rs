@@ -0,0 +1,24 @@
+//! Window titles - Show the instance name in the windows of an instance
+//!
+//! Copies of one app all show the same title in the taskbar. The instance name
+//! is appended to each title, e.g. "Discord — Work". Apps set their own titles
+//! and often change them, such as to show the open channel, so the name is
+//! appended again whenever it has gone missing.
+
+use std::time::Duration;
+
+/// How often the titles of renamed instances are checked
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Between the app's own title and the instance name
+const SEPARATOR: &str = " — ";
+
+/// `title` with `name` appended, or None when it already ends with it or the
+/// window has no title to extend
+pub fn renamed(title: &str, name: &str) -> Option<String> {
+    let suffix = format!("{}{}", SEPARATOR, name);
+    if title.is_empty() || name.is_empty() || title.ends_with(&suffix) {
+        return None;
+    }
+    Some(format!("{}{}", title, suffix))
+}
@@ -6,6 +6,7 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 #![allow(dead_code)] // Many API methods are part of a comprehensive public API
 
+mod cli;
 mod core;
 mod persistence;
 mod platform;
@@ -13,9 +14,10 @@ mod ui;
 
 use anyhow::Result;
 use single_instance::SingleInstance;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
+use crate::core::ipc::{Activation, IpcCommand};
 use crate::core::AppState;
 use crate::persistence::Database;
 use crate::ui::MultiInstanceApp;
@@ -27,6 +29,15 @@ pub const APP_NAME: &str = "MultiInstance";
 pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 fn main() -> Result<()> {
+    // Arguments other than `--safe-mode`, `--profile` or an executable path are
+    // a command for the running GUI
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let safe_mode = args == [cli::SAFE_MODE_FLAG];
+    let activation = cli::activation(&args);
+    if activation.is_none() && !safe_mode {
+        std::process::exit(cli::run(&args));
+    }
+
     // Initialize logging
     init_logging();
 
@@ -35,13 +46,25 @@ fn main() -> Result<()> {
     // Ensure only one instance of MultiInstance itself is running
     let instance = SingleInstance::new(APP_NAME).expect("Failed to create single instance lock");
     if !instance.is_single() {
-        error!("Another instance of {} is already running!", APP_NAME);
-        show_already_running_dialog();
+        // Hand our command line to the running instance, which raises its window
+        let activation = activation.unwrap_or(Activation::Show);
+        if let Err(e) = core::ipc::send(IpcCommand::Activate(activation)) {
+            error!("Another instance of {} is already running: {}", APP_NAME, e);
+            show_already_running_dialog();
+        } else {
+            info!("Forwarded command line to the running instance");
+        }
         return Ok(());
     }
 
-    // Initialize database
-    let db = Database::new()?;
+    // Initialize database; safe mode starts from an empty one so a corrupted
+    // database or bad settings cannot crash startup
+    let db = if safe_mode {
+        warn!("Starting in safe mode: nothing will be saved");
+        Database::temporary()?
+    } else {
+        Database::new()?
+    };
     db.initialize()?;
     info!("Database initialized");
 
@@ -49,9 +72,34 @@ fn main() -> Result<()> {
     let app_state = AppState::new(db)?;
     info!("Application state initialized");
 
-    // Restore previous session if configured
-    if app_state.settings.read().unwrap().auto_restore_sessions {
-        if let Err(e) = app_state.restore_session() {
+    // Accept commands from `multiinstance launch/stop/list/status`
+    if let Err(e) = core::ipc::serve(app_state.clone()) {
+        error!("Failed to start CLI listener: {}", e);
+    }
+
+    // Profiles, quick launch, groups and recent apps fill in after the window shows
+    app_state.load_deferred_data();
+
+    // Handled by the UI once profiles have loaded
+    if let Some(activation) = activation.filter(|a| *a != Activation::Show) {
+        app_state.request_activation(activation);
+    }
+
+    // Restore previous session if configured, or let the user pick what to restore
+    let (auto_restore, prompt) = {
+        let settings = app_state.settings.read().unwrap();
+        (
+            settings.auto_restore_sessions,
+            settings.session_restore_prompt,
+        )
+    };
+    if auto_restore && !safe_mode {
+        let restored = if prompt {
+            app_state.prepare_session_restore()
+        } else {
+            app_state.restore_session()
+        };
+        if let Err(e) = restored {
             error!("Failed to restore previous session: {}", e);
         }
     }
@@ -65,9 +113,15 @@ fn main() -> Result<()> {
         ..Default::default()
     };
 
+    let title = if safe_mode {
+        format!("{} v{} (Safe Mode)", APP_NAME, APP_VERSION)
+    } else {
+        format!("{} v{}", APP_NAME, APP_VERSION)
+    };
+
     info!("Starting GUI...");
     eframe::run_native(
-        &format!("{} v{}", APP_NAME, APP_VERSION),
+        &title,
         native_options,
         Box::new(|cc| Ok(Box::new(MultiInstanceApp::new(cc, app_state)))),
     )
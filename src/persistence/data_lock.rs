@@ -0,0 +1,88 @@
+//! Data lock - Detects another machine using the same data directory
+//!
+//! A lock file next to the database names the machine using it and is refreshed
+//! while the app runs. When a sync service such as Dropbox or OneDrive shares
+//! the data directory between machines, a fresh lock from another machine means
+//! two copies of the app would overwrite each other's database, so the database
+//! is opened read-only instead.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Lock file in the data directory
+const LOCK_FILE: &str = "multiinstance.lock";
+
+/// How often a running app rewrites its lock
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A lock not refreshed for this long was left by a run that did not exit
+/// cleanly; it allows for the delay of the sync service
+const STALE_AFTER_MINS: i64 = 10;
+
+/// Who is using the data directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataLock {
+    pub host: String,
+    pub pid: u32,
+    /// When the lock was last refreshed
+    pub updated_at: DateTime<Utc>,
+}
+
+impl DataLock {
+    /// Lock held by this process
+    pub fn current() -> Self {
+        Self {
+            host: local_host(),
+            pid: std::process::id(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn path(data_dir: &Path) -> PathBuf {
+        data_dir.join(LOCK_FILE)
+    }
+
+    pub fn load(data_dir: &Path) -> Option<Self> {
+        let json = std::fs::read_to_string(Self::path(data_dir)).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    /// A lock another machine refreshed recently, if any
+    pub fn foreign(data_dir: &Path) -> Option<Self> {
+        Self::load(data_dir).filter(|lock| {
+            lock.host != local_host()
+                && Utc::now() - lock.updated_at < chrono::Duration::minutes(STALE_AFTER_MINS)
+        })
+    }
+
+    /// Write or refresh this process's lock
+    pub fn acquire(data_dir: &Path) -> Result<()> {
+        let json = serde_json::to_string(&Self::current())?;
+        std::fs::write(Self::path(data_dir), json)?;
+        Ok(())
+    }
+
+    /// Remove the lock on exit, unless another machine has taken it over
+    pub fn release(data_dir: &Path) -> Result<()> {
+        if Self::load(data_dir).is_some_and(|lock| lock.host != local_host()) {
+            return Ok(());
+        }
+        match std::fs::remove_file(Self::path(data_dir)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Minutes since the lock was refreshed
+    pub fn age_mins(&self) -> i64 {
+        (Utc::now() - self.updated_at).num_minutes().max(0)
+    }
+}
+
+fn local_host() -> String {
+    sysinfo::System::host_name().unwrap_or_else(|| "unknown".to_string())
+}
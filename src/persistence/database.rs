@@ -1,19 +1,45 @@
 //! SQLite database implementation for persistent storage
 
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
+use std::time::Instant;
 
 use anyhow::{Context, Result};
-use rusqlite::{params, Connection, OptionalExtension};
-use tracing::{debug, error, info};
-
+use chrono::NaiveDate;
+use rusqlite::serialize::OwnedData;
+use rusqlite::{params, Connection, DatabaseName, OpenFlags, OptionalExtension};
+use tracing::{debug, error, info, warn};
+
+use super::data_lock::{self, DataLock};
+use crate::core::history::{HistoryEntry, HistoryFilter};
+use crate::core::profile::ProfileRun;
+use crate::core::profiler::Timing;
+use crate::core::scheduler::{Schedule, ScheduleId};
+use crate::core::settings::{SCHEMA_VERSION_KEY, SETTINGS_SCHEMA_VERSION};
 use crate::core::{
-    Instance, InstanceConfig, InstanceId, InstanceStatus, Profile, ProfileId, Settings,
+    CustomTheme, Instance, InstanceConfig, InstanceId, InstanceStatus, Profile, ProfileId, Settings,
 };
 
+/// Settings key used before settings were stored one row per key
+const LEGACY_SETTINGS_KEY: &str = "app_settings";
+
 /// Database wrapper for SQLite operations
 pub struct Database {
     conn: Mutex<Connection>,
+    /// How long the frequent writes take, for the profiler overlay
+    write_timing: Mutex<Timing>,
+    /// In memory only, discarded on exit
+    temporary: bool,
+    /// Database file; None for a temporary database
+    path: Option<PathBuf>,
+    /// Lock of another machine using the same data directory; while set, the
+    /// database is open read-only
+    foreign_lock: Mutex<Option<DataLock>>,
+    /// Last time our own lock was written
+    lock_refreshed: Mutex<Instant>,
+    /// Settings values as last read or written, so saves only write changed keys
+    stored_settings: Mutex<HashMap<String, String>>,
 }
 
 impl Database {
@@ -26,18 +52,224 @@ impl Database {
             std::fs::create_dir_all(parent)?;
         }
 
-        let conn = Connection::open(&db_path)
-            .context(format!("Failed to open database at {:?}", db_path))?;
+        // Another machine syncing the same directory: writing would corrupt its database
+        let data_dir = db_path.parent().unwrap_or(Path::new("."));
+        let foreign_lock = DataLock::foreign(data_dir);
+        let conn = match &foreign_lock {
+            Some(lock) => {
+                warn!(
+                    "Data directory is in use by {} (PID {}), opening the database read-only",
+                    lock.host, lock.pid
+                );
+                Self::open_read_only(&db_path)?
+            }
+            None => {
+                if let Err(e) = DataLock::acquire(data_dir) {
+                    warn!("Failed to write data lock: {}", e);
+                }
+                let conn = Connection::open(&db_path)
+                    .context(format!("Failed to open database at {:?}", db_path))?;
 
-        // Enable WAL mode for better concurrency
-        conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA foreign_keys=ON;")?;
+                // Enable WAL mode for better concurrency
+                conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA foreign_keys=ON;")?;
+                conn
+            }
+        };
 
         info!("Database opened at {:?}", db_path);
         Ok(Self {
             conn: Mutex::new(conn),
+            write_timing: Mutex::new(Timing::default()),
+            temporary: false,
+            path: Some(db_path),
+            foreign_lock: Mutex::new(foreign_lock),
+            lock_refreshed: Mutex::new(Instant::now()),
+            stored_settings: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn open_read_only(db_path: &Path) -> Result<Connection> {
+        let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY).context(
+            format!("Failed to open database at {:?} read-only", db_path),
+        )?;
+        conn.execute_batch("PRAGMA query_only=ON;")?;
+        Ok(conn)
+    }
+
+    /// Open an empty in-memory database, discarded on exit, for safe mode
+    pub fn temporary() -> Result<Self> {
+        let conn = Connection::open_in_memory().context("Failed to open in-memory database")?;
+        conn.execute_batch("PRAGMA foreign_keys=ON;")?;
+
+        info!("Temporary in-memory database opened");
+        Ok(Self {
+            conn: Mutex::new(conn),
+            write_timing: Mutex::new(Timing::default()),
+            temporary: true,
+            path: None,
+            foreign_lock: Mutex::new(None),
+            lock_refreshed: Mutex::new(Instant::now()),
+            stored_settings: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Open a copy returned by `serialize` read-only in memory, to read what it holds
+    pub fn open_serialized(copy: &[u8]) -> Result<Self> {
+        let mut conn = Connection::open_in_memory().context("Failed to open in-memory database")?;
+        conn.deserialize(DatabaseName::Main, sqlite_owned(copy)?, true)
+            .context("Database copy is damaged")?;
+        conn.execute_batch("PRAGMA query_only=ON;")?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+            write_timing: Mutex::new(Timing::default()),
+            temporary: false,
+            path: None,
+            foreign_lock: Mutex::new(None),
+            lock_refreshed: Mutex::new(Instant::now()),
+            stored_settings: Mutex::new(HashMap::new()),
         })
     }
 
+    /// A consistent copy of the whole database, held in memory
+    pub fn serialize(&self) -> Result<Vec<u8>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Database lock poisoned: {}", e))?;
+        let mut copy = conn
+            .serialize(DatabaseName::Main)
+            .context("Failed to copy the database")?
+            .to_vec();
+        // In-memory databases cannot be opened in WAL mode; mark the copy as
+        // using a rollback journal (file format versions at offsets 18 and 19)
+        if copy.len() >= 20 {
+            copy[18] = 1;
+            copy[19] = 1;
+        }
+        Ok(copy)
+    }
+
+    /// Copy the events of `instances` and the runs of `profiles` from a copy
+    /// returned by `serialize`. Returns how many events were copied.
+    pub fn import_history(
+        &self,
+        copy: &[u8],
+        instances: &[InstanceId],
+        profiles: &[ProfileId],
+    ) -> Result<usize> {
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Database lock poisoned: {}", e))?;
+        conn.execute("ATTACH DATABASE ':memory:' AS migrated", [])?;
+        if let Err(e) = conn.deserialize(
+            DatabaseName::Attached("migrated"),
+            sqlite_owned(copy)?,
+            true,
+        ) {
+            let _ = conn.execute("DETACH DATABASE migrated", []);
+            return Err(anyhow::Error::new(e).context("Database copy is damaged"));
+        }
+        let copy_rows = || -> Result<usize> {
+            let mut events = 0;
+            for id in instances {
+                events += conn.execute(
+                    "INSERT INTO instance_history (instance_id, event_type, event_time, details, launch_id)
+                     SELECT instance_id, event_type, event_time, details, launch_id
+                     FROM migrated.instance_history WHERE instance_id = ?1",
+                    params![id.to_string()],
+                )?;
+            }
+            for id in profiles {
+                conn.execute(
+                    "INSERT INTO profile_runs (profile_id, run_time, total, started, failures)
+                     SELECT profile_id, run_time, total, started, failures
+                     FROM migrated.profile_runs WHERE profile_id = ?1",
+                    params![id.to_string()],
+                )?;
+            }
+            Ok(events)
+        };
+        let result = copy_rows();
+        if let Err(e) = conn.execute("DETACH DATABASE migrated", []) {
+            warn!("Failed to detach migrated database: {}", e);
+        }
+        result
+    }
+
+    /// Whether this is a temporary database that is not saved
+    pub fn is_temporary(&self) -> bool {
+        self.temporary
+    }
+
+    /// Lock of the other machine using the data directory, while the database
+    /// is read-only because of it
+    pub fn foreign_lock(&self) -> Option<DataLock> {
+        self.foreign_lock.lock().ok()?.clone()
+    }
+
+    /// Rewrite our lock once per refresh interval. Finding another machine's
+    /// lock instead, after both started at once, switches to read-only.
+    pub fn refresh_lock(&self) {
+        let Some(data_dir) = self.path.as_deref().and_then(Path::parent) else {
+            return;
+        };
+        let Ok(mut foreign_lock) = self.foreign_lock.lock() else {
+            return;
+        };
+        if foreign_lock.is_some() {
+            return;
+        }
+        match self.lock_refreshed.lock() {
+            Ok(mut refreshed) if refreshed.elapsed() >= data_lock::REFRESH_INTERVAL => {
+                *refreshed = Instant::now();
+            }
+            _ => return,
+        }
+
+        if let Some(lock) = DataLock::foreign(data_dir) {
+            warn!(
+                "{} (PID {}) started using the data directory, switching to read-only",
+                lock.host, lock.pid
+            );
+            let reopened = self.path.as_deref().map(Self::open_read_only);
+            match (self.conn.lock(), reopened) {
+                (Ok(mut conn), Some(Ok(read_only))) => *conn = read_only,
+                (_, Some(Err(e))) => error!("Failed to reopen database read-only: {}", e),
+                _ => {}
+            }
+            *foreign_lock = Some(lock);
+        } else if let Err(e) = DataLock::acquire(data_dir) {
+            warn!("Failed to refresh data lock: {}", e);
+        }
+    }
+
+    /// Remove our lock on a clean exit
+    pub fn release_lock(&self) {
+        if self.foreign_lock().is_some() {
+            return;
+        }
+        if let Some(data_dir) = self.path.as_deref().and_then(Path::parent) {
+            if let Err(e) = DataLock::release(data_dir) {
+                warn!("Failed to remove data lock: {}", e);
+            }
+        }
+    }
+
+    /// Timings of recent writes
+    pub fn write_timing(&self) -> Timing {
+        self.write_timing
+            .lock()
+            .map(|t| t.clone())
+            .unwrap_or_default()
+    }
+
+    fn record_write(&self, started: Instant) {
+        if let Ok(mut timing) = self.write_timing.lock() {
+            timing.record(started.elapsed());
+        }
+    }
+
     /// Get the database file path
     fn get_database_path() -> Result<PathBuf> {
         let data_dir = dirs::data_dir()
@@ -48,6 +280,10 @@ impl Database {
 
     /// Initialize database schema
     pub fn initialize(&self) -> Result<()> {
+        if self.foreign_lock().is_some() {
+            info!("Database is read-only, leaving its schema as it is");
+            return Ok(());
+        }
         let conn = self
             .conn
             .lock()
@@ -78,6 +314,12 @@ impl Database {
                 data TEXT NOT NULL
             );
 
+            -- Scheduled launches
+            CREATE TABLE IF NOT EXISTS schedules (
+                id TEXT PRIMARY KEY,
+                data TEXT NOT NULL
+            );
+
             -- Quick launch items
             CREATE TABLE IF NOT EXISTS quick_launch (
                 idx INTEGER PRIMARY KEY,
@@ -107,50 +349,162 @@ impl Database {
                 instance_id TEXT NOT NULL,
                 event_type TEXT NOT NULL,
                 event_time TEXT NOT NULL,
-                details TEXT
+                details TEXT,
+                launch_id TEXT
+            );
+
+            -- Profile launch history
+            CREATE TABLE IF NOT EXISTS profile_runs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                profile_id TEXT NOT NULL,
+                run_time TEXT NOT NULL,
+                total INTEGER NOT NULL,
+                started INTEGER NOT NULL,
+                failures TEXT NOT NULL
+            );
+
+            -- Daily runtime per executable, for usage limits
+            CREATE TABLE IF NOT EXISTS usage_daily (
+                executable TEXT NOT NULL,
+                day TEXT NOT NULL,
+                seconds INTEGER NOT NULL,
+                PRIMARY KEY (executable, day)
+            );
+
+            -- Shell icons of executables, as PNG
+            CREATE TABLE IF NOT EXISTS executable_icons (
+                executable TEXT PRIMARY KEY,
+                png BLOB NOT NULL
+            );
+
+            -- User-defined color themes
+            CREATE TABLE IF NOT EXISTS themes (
+                name TEXT PRIMARY KEY,
+                data TEXT NOT NULL
             );
             "#,
         )?;
 
+        // Added after the history table was first released
+        if conn
+            .prepare("SELECT launch_id FROM instance_history LIMIT 0")
+            .is_err()
+        {
+            conn.execute("ALTER TABLE instance_history ADD COLUMN launch_id TEXT", [])?;
+        }
+        // Added with edits queued for an instance's next launch
+        if conn
+            .prepare("SELECT pending_config FROM instances LIMIT 0")
+            .is_err()
+        {
+            conn.execute("ALTER TABLE instances ADD COLUMN pending_config TEXT", [])?;
+        }
+
         info!("Database schema initialized");
         Ok(())
     }
 
     // === Settings ===
 
-    /// Load settings from database
+    /// Load settings from database, merging stored keys onto the defaults
     pub fn load_settings(&self) -> Result<Option<Settings>> {
         let conn = self
             .conn
             .lock()
             .map_err(|e| anyhow::anyhow!("Database lock poisoned: {}", e))?;
-        let mut stmt = conn.prepare("SELECT value FROM settings WHERE key = 'app_settings'")?;
-        let result: Option<String> = stmt.query_row([], |row| row.get(0)).optional()?;
 
-        match result {
-            Some(json) => {
-                let mut settings: Settings =
-                    serde_json::from_str(&json).context("Failed to deserialize settings")?;
-                // Validate and fix any invalid values after deserialization
-                settings.validate();
-                Ok(Some(settings))
-            }
-            None => Ok(None),
+        let mut stmt = conn.prepare("SELECT key, value FROM settings WHERE key NOT IN (?1, ?2)")?;
+        let entries = stmt
+            .query_map(params![LEGACY_SETTINGS_KEY, SCHEMA_VERSION_KEY], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        if !entries.is_empty() {
+            let mut stored = self
+                .stored_settings
+                .lock()
+                .map_err(|e| anyhow::anyhow!("Settings cache lock poisoned: {}", e))?;
+            stored.clear();
+            stored.extend(entries.iter().cloned());
+            return Ok(Some(Settings::from_entries(entries)));
         }
+
+        // Settings written before the per-key layout are one JSON blob
+        let legacy: Option<String> = conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = ?1",
+                params![LEGACY_SETTINGS_KEY],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let Some(json) = legacy else {
+            return Ok(None);
+        };
+
+        let settings =
+            match serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&json) {
+                Ok(map) => Settings::from_entries(
+                    map.into_iter().map(|(key, value)| (key, value.to_string())),
+                ),
+                Err(e) => {
+                    warn!("Legacy settings are unreadable, using defaults: {}", e);
+                    Settings::default()
+                }
+            };
+
+        self.write_settings(&conn, &settings)?;
+        conn.execute(
+            "DELETE FROM settings WHERE key = ?1",
+            params![LEGACY_SETTINGS_KEY],
+        )?;
+        info!("Migrated settings to per-key storage");
+        Ok(Some(settings))
     }
 
     /// Save settings to database
     pub fn save_settings(&self, settings: &Settings) -> Result<()> {
+        let started = Instant::now();
         let conn = self
             .conn
             .lock()
             .map_err(|e| anyhow::anyhow!("Database lock poisoned: {}", e))?;
-        let json = serde_json::to_string(settings)?;
-        conn.execute(
-            "INSERT OR REPLACE INTO settings (key, value) VALUES ('app_settings', ?1)",
-            params![json],
-        )?;
+        self.write_settings(&conn, settings)?;
         debug!("Settings saved");
+        self.record_write(started);
+        Ok(())
+    }
+
+    /// Write the settings keys, and the schema version, that differ from the
+    /// stored values in one transaction
+    fn write_settings(&self, conn: &Connection, settings: &Settings) -> Result<()> {
+        let mut stored = self
+            .stored_settings
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Settings cache lock poisoned: {}", e))?;
+        let changed: Vec<(String, String)> = settings
+            .to_entries()?
+            .into_iter()
+            .chain(std::iter::once((
+                SCHEMA_VERSION_KEY.to_string(),
+                SETTINGS_SCHEMA_VERSION.to_string(),
+            )))
+            .filter(|(key, value)| stored.get(key) != Some(value))
+            .collect();
+        if changed.is_empty() {
+            return Ok(());
+        }
+
+        let tx = conn.unchecked_transaction()?;
+        {
+            let mut stmt =
+                tx.prepare("INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)")?;
+            for (key, value) in &changed {
+                stmt.execute(params![key, value])?;
+            }
+        }
+        tx.commit()?;
+        stored.extend(changed);
         Ok(())
     }
 
@@ -158,18 +512,25 @@ impl Database {
 
     /// Save an instance to database
     pub fn save_instance(&self, instance: &Instance) -> Result<()> {
+        let started = Instant::now();
         let conn = self
             .conn
             .lock()
             .map_err(|e| anyhow::anyhow!("Database lock poisoned: {}", e))?;
         let config_json = serde_json::to_string(&instance.config)?;
+        let pending_json = instance
+            .pending_config
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
         let status_str = format!("{:?}", instance.status);
 
         conn.execute(
             r#"
             INSERT OR REPLACE INTO instances
-            (id, config, status, created_at, started_at, stopped_at, restart_count, last_error)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            (id, config, status, created_at, started_at, stopped_at, restart_count, last_error,
+             pending_config)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
             "#,
             params![
                 instance.id.to_string(),
@@ -180,15 +541,18 @@ impl Database {
                 instance.stopped_at.map(|t| t.to_rfc3339()),
                 instance.restart_count,
                 instance.last_error,
+                pending_json,
             ],
         )?;
 
         debug!("Instance {} saved", instance.id);
+        self.record_write(started);
         Ok(())
     }
 
     /// Update instance status
     pub fn update_instance_status(&self, id: InstanceId, status: &InstanceStatus) -> Result<()> {
+        let started = Instant::now();
         let conn = self
             .conn
             .lock()
@@ -198,6 +562,7 @@ impl Database {
             "UPDATE instances SET status = ?1 WHERE id = ?2",
             params![status_str, id.to_string()],
         )?;
+        self.record_write(started);
         Ok(())
     }
 
@@ -207,9 +572,19 @@ impl Database {
             .conn
             .lock()
             .map_err(|e| anyhow::anyhow!("Database lock poisoned: {}", e))?;
-        let mut stmt = conn.prepare(
-            "SELECT id, config, status, created_at, started_at, stopped_at, restart_count, last_error FROM instances"
-        )?;
+        // Copies from migration packages of older versions lack the column
+        let pending = if conn
+            .prepare("SELECT pending_config FROM instances LIMIT 0")
+            .is_ok()
+        {
+            "pending_config"
+        } else {
+            "NULL"
+        };
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, config, status, created_at, started_at, stopped_at, restart_count, last_error, {} FROM instances",
+            pending
+        ))?;
 
         let instances = stmt.query_map([], |row| {
             let id_str: String = row.get(0)?;
@@ -220,6 +595,7 @@ impl Database {
             let stopped_at_str: Option<String> = row.get(5)?;
             let restart_count: u32 = row.get(6)?;
             let last_error: Option<String> = row.get(7)?;
+            let pending_json: Option<String> = row.get(8)?;
 
             Ok((
                 id_str,
@@ -229,6 +605,7 @@ impl Database {
                 stopped_at_str,
                 restart_count,
                 last_error,
+                pending_json,
             ))
         })?;
 
@@ -242,6 +619,7 @@ impl Database {
                 stopped_at_str,
                 restart_count,
                 last_error,
+                pending_json,
             ) = row?;
 
             let id = uuid::Uuid::parse_str(&id_str)
@@ -270,6 +648,11 @@ impl Database {
                 .map(|t| t.with_timezone(&chrono::Utc));
             instance.restart_count = restart_count;
             instance.last_error = last_error;
+            instance.pending_config = pending_json.and_then(|json| {
+                serde_json::from_str(&json)
+                    .map_err(|e| error!("Failed to deserialize pending instance config: {}", e))
+                    .ok()
+            });
 
             result.push(instance);
         }
@@ -342,10 +725,127 @@ impl Database {
             "DELETE FROM profiles WHERE id = ?1",
             params![id.to_string()],
         )?;
+        conn.execute(
+            "DELETE FROM profile_runs WHERE profile_id = ?1",
+            params![id.to_string()],
+        )?;
         debug!("Profile {} deleted", id);
         Ok(())
     }
 
+    /// Record the outcome of a profile launch
+    pub fn record_profile_run(&self, profile_id: ProfileId, run: &ProfileRun) -> Result<()> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Database lock poisoned: {}", e))?;
+        conn.execute(
+            r#"
+            INSERT INTO profile_runs (profile_id, run_time, total, started, failures)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+            params![
+                profile_id.to_string(),
+                run.started_at.to_rfc3339(),
+                run.total as i64,
+                run.started as i64,
+                serde_json::to_string(&run.failures)?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get the most recent launches of a profile, newest first
+    pub fn get_profile_runs(&self, profile_id: ProfileId, limit: usize) -> Result<Vec<ProfileRun>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Database lock poisoned: {}", e))?;
+        let mut stmt = conn.prepare(
+            "SELECT run_time, total, started, failures FROM profile_runs WHERE profile_id = ?1 ORDER BY run_time DESC LIMIT ?2"
+        )?;
+
+        let runs = stmt.query_map(params![profile_id.to_string(), limit as i64], |row| {
+            let run_time: String = row.get(0)?;
+            let total: i64 = row.get(1)?;
+            let started: i64 = row.get(2)?;
+            let failures: String = row.get(3)?;
+            Ok((run_time, total, started, failures))
+        })?;
+
+        let mut result = Vec::new();
+        for run in runs {
+            let (run_time, total, started, failures) = run?;
+            let Ok(started_at) = chrono::DateTime::parse_from_rfc3339(&run_time) else {
+                error!("Invalid profile run time: {}", run_time);
+                continue;
+            };
+            result.push(ProfileRun {
+                started_at: started_at.with_timezone(&chrono::Utc),
+                total: total as usize,
+                started: started as usize,
+                failures: serde_json::from_str(&failures).unwrap_or_default(),
+            });
+        }
+
+        Ok(result)
+    }
+
+    // === Schedules ===
+
+    /// Save a schedule to database
+    pub fn save_schedule(&self, schedule: &Schedule) -> Result<()> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Database lock poisoned: {}", e))?;
+        let json = serde_json::to_string(schedule)?;
+        conn.execute(
+            "INSERT OR REPLACE INTO schedules (id, data) VALUES (?1, ?2)",
+            params![schedule.id.to_string(), json],
+        )?;
+        debug!("Schedule {} saved", schedule.id);
+        Ok(())
+    }
+
+    /// Load all schedules from database
+    pub fn load_all_schedules(&self) -> Result<Vec<Schedule>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Database lock poisoned: {}", e))?;
+        let mut stmt = conn.prepare("SELECT data FROM schedules")?;
+        let schedules = stmt.query_map([], |row| {
+            let json: String = row.get(0)?;
+            Ok(json)
+        })?;
+
+        let mut result = Vec::new();
+        for json in schedules {
+            let json = json?;
+            match serde_json::from_str::<Schedule>(&json) {
+                Ok(schedule) => result.push(schedule),
+                Err(e) => error!("Failed to deserialize schedule: {}", e),
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Delete a schedule from database
+    pub fn delete_schedule(&self, id: ScheduleId) -> Result<()> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Database lock poisoned: {}", e))?;
+        conn.execute(
+            "DELETE FROM schedules WHERE id = ?1",
+            params![id.to_string()],
+        )?;
+        debug!("Schedule {} deleted", id);
+        Ok(())
+    }
+
     // === Quick Launch ===
 
     /// Load quick launch items
@@ -427,6 +927,32 @@ impl Database {
         Ok(())
     }
 
+    /// Save groups together with the instances whose group changed, in one transaction
+    pub fn save_groups_and_members(&self, groups: &[String], members: &[&Instance]) -> Result<()> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Database lock poisoned: {}", e))?;
+        let tx = conn.unchecked_transaction()?;
+        {
+            tx.execute("DELETE FROM groups", [])?;
+            let mut insert = tx.prepare("INSERT INTO groups (name) VALUES (?1)")?;
+            for group in groups {
+                insert.execute(params![group])?;
+            }
+            let mut update = tx.prepare("UPDATE instances SET config = ?2 WHERE id = ?1")?;
+            for instance in members {
+                update.execute(params![
+                    instance.id.to_string(),
+                    serde_json::to_string(&instance.config)?
+                ])?;
+            }
+        }
+        tx.commit()?;
+        debug!("Groups saved with {} member(s)", members.len());
+        Ok(())
+    }
+
     // === Recent Apps ===
 
     /// Load recent apps
@@ -479,7 +1005,7 @@ impl Database {
         conn.execute("DELETE FROM session", [])?;
 
         for instance in instances {
-            let json = serde_json::to_string(&instance.config)?;
+            let json = serde_json::to_string(instance.next_launch_config())?;
             conn.execute(
                 "INSERT INTO session (id, config) VALUES (?1, ?2)",
                 params![instance.id.to_string(), json],
@@ -524,31 +1050,178 @@ impl Database {
         Ok(())
     }
 
+    // === Usage ===
+
+    /// Add runtime seconds to each executable's total for `day`
+    pub fn add_usage(&self, day: NaiveDate, totals: &HashMap<String, u64>) -> Result<()> {
+        let started = Instant::now();
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Database lock poisoned: {}", e))?;
+        let tx = conn.unchecked_transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                r#"
+                INSERT INTO usage_daily (executable, day, seconds) VALUES (?1, ?2, ?3)
+                ON CONFLICT (executable, day) DO UPDATE SET seconds = seconds + excluded.seconds
+                "#,
+            )?;
+            for (executable, seconds) in totals {
+                stmt.execute(params![executable, day.to_string(), *seconds as i64])?;
+            }
+        }
+        tx.commit()?;
+        self.record_write(started);
+        Ok(())
+    }
+
+    /// Get each executable's runtime seconds for `day`
+    pub fn load_usage(&self, day: NaiveDate) -> Result<HashMap<String, u64>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Database lock poisoned: {}", e))?;
+        let mut stmt =
+            conn.prepare("SELECT executable, seconds FROM usage_daily WHERE day = ?1")?;
+
+        let rows = stmt.query_map(params![day.to_string()], |row| {
+            let executable: String = row.get(0)?;
+            let seconds: i64 = row.get(1)?;
+            Ok((executable, seconds.max(0) as u64))
+        })?;
+
+        let mut result = HashMap::new();
+        for row in rows {
+            let (executable, seconds) = row?;
+            result.insert(executable, seconds);
+        }
+
+        Ok(result)
+    }
+
+    // === Executable icons ===
+
+    /// Save the PNG icon of an executable, replacing an older one
+    pub fn save_executable_icon(&self, executable: &Path, png: &[u8]) -> Result<()> {
+        let started = Instant::now();
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Database lock poisoned: {}", e))?;
+        conn.execute(
+            "INSERT OR REPLACE INTO executable_icons (executable, png) VALUES (?1, ?2)",
+            params![executable.to_string_lossy(), png],
+        )?;
+        self.record_write(started);
+        Ok(())
+    }
+
+    /// Load every stored executable icon
+    pub fn load_executable_icons(&self) -> Result<Vec<(PathBuf, Vec<u8>)>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Database lock poisoned: {}", e))?;
+        let mut stmt = conn.prepare("SELECT executable, png FROM executable_icons")?;
+
+        let rows = stmt.query_map([], |row| {
+            let executable: String = row.get(0)?;
+            let png: Vec<u8> = row.get(1)?;
+            Ok((PathBuf::from(executable), png))
+        })?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+
+        Ok(result)
+    }
+
+    // === Custom themes ===
+
+    /// Save a custom theme, replacing one with the same name
+    pub fn save_theme(&self, theme: &CustomTheme) -> Result<()> {
+        let started = Instant::now();
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Database lock poisoned: {}", e))?;
+        let json = serde_json::to_string(theme)?;
+        conn.execute(
+            "INSERT OR REPLACE INTO themes (name, data) VALUES (?1, ?2)",
+            params![theme.name, json],
+        )?;
+        self.record_write(started);
+        Ok(())
+    }
+
+    /// Load all custom themes, sorted by name
+    pub fn load_themes(&self) -> Result<Vec<CustomTheme>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Database lock poisoned: {}", e))?;
+        let mut stmt = conn.prepare("SELECT data FROM themes ORDER BY name")?;
+        let themes = stmt.query_map([], |row| {
+            let json: String = row.get(0)?;
+            Ok(json)
+        })?;
+
+        let mut result = Vec::new();
+        for json in themes {
+            let json = json?;
+            match serde_json::from_str::<CustomTheme>(&json) {
+                Ok(theme) => result.push(theme),
+                Err(e) => error!("Failed to deserialize theme: {}", e),
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Delete a custom theme
+    pub fn delete_theme(&self, name: &str) -> Result<()> {
+        let started = Instant::now();
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Database lock poisoned: {}", e))?;
+        conn.execute("DELETE FROM themes WHERE name = ?1", params![name])?;
+        self.record_write(started);
+        Ok(())
+    }
+
     // === History ===
 
-    /// Record an instance event
+    /// Record an instance event, tagged with the correlation ID of its launch
     pub fn record_instance_event(
         &self,
         instance_id: InstanceId,
+        launch_id: Option<&str>,
         event_type: &str,
         details: Option<&str>,
     ) -> Result<()> {
+        let started = Instant::now();
         let conn = self
             .conn
             .lock()
             .map_err(|e| anyhow::anyhow!("Database lock poisoned: {}", e))?;
         conn.execute(
             r#"
-            INSERT INTO instance_history (instance_id, event_type, event_time, details)
-            VALUES (?1, ?2, ?3, ?4)
+            INSERT INTO instance_history (instance_id, event_type, event_time, details, launch_id)
+            VALUES (?1, ?2, ?3, ?4, ?5)
             "#,
             params![
                 instance_id.to_string(),
                 event_type,
                 chrono::Utc::now().to_rfc3339(),
                 details,
+                launch_id,
             ],
         )?;
+        self.record_write(started);
         Ok(())
     }
 
@@ -577,6 +1250,115 @@ impl Database {
         Ok(result)
     }
 
+    /// A page of instance history matching `filter`, newest first. `before` is
+    /// the (time, row ID) of the last row of the previous page.
+    pub fn query_instance_history(
+        &self,
+        filter: &HistoryFilter,
+        before: Option<(chrono::DateTime<chrono::Utc>, i64)>,
+        limit: usize,
+    ) -> Result<Vec<HistoryEntry>> {
+        let mut conditions: Vec<String> = Vec::new();
+        let mut values: Vec<String> = Vec::new();
+
+        if let Some(instance) = filter.instance {
+            values.push(instance.to_string());
+            conditions.push(format!("instance_id = ?{}", values.len()));
+        }
+        if !filter.instances.is_empty() {
+            let start = values.len() + 1;
+            values.extend(filter.instances.iter().map(|id| id.to_string()));
+            let placeholders: Vec<String> =
+                (start..=values.len()).map(|i| format!("?{}", i)).collect();
+            conditions.push(format!("instance_id IN ({})", placeholders.join(", ")));
+        }
+        if let Some(ref launch_id) = filter.launch_id {
+            values.push(launch_id.clone());
+            conditions.push(format!("launch_id = ?{}", values.len()));
+        }
+        if !filter.events.is_empty() {
+            let start = values.len() + 1;
+            values.extend(filter.events.iter().map(|e| e.as_str().to_string()));
+            let placeholders: Vec<String> =
+                (start..=values.len()).map(|i| format!("?{}", i)).collect();
+            conditions.push(format!("event_type IN ({})", placeholders.join(", ")));
+        }
+        if let Some((since, until)) = filter.time_bounds() {
+            values.push(since.to_rfc3339());
+            conditions.push(format!("event_time >= ?{}", values.len()));
+            values.push(until.to_rfc3339());
+            conditions.push(format!("event_time < ?{}", values.len()));
+        }
+        if let Some((time, id)) = before {
+            values.push(time.to_rfc3339());
+            let time = values.len();
+            values.push(id.to_string());
+            conditions.push(format!(
+                "(event_time < ?{time} OR (event_time = ?{time} AND id < CAST(?{} AS INTEGER)))",
+                values.len()
+            ));
+        }
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        let sql = format!(
+            "SELECT id, instance_id, event_type, event_time, details, launch_id FROM instance_history \
+             {} ORDER BY event_time DESC, id DESC LIMIT {}",
+            where_clause, limit
+        );
+
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Database lock poisoned: {}", e))?;
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(values.iter()), |row| {
+            let id: i64 = row.get(0)?;
+            let instance_id: String = row.get(1)?;
+            let event_type: String = row.get(2)?;
+            let event_time: String = row.get(3)?;
+            let details: Option<String> = row.get(4)?;
+            let launch_id: Option<String> = row.get(5)?;
+            Ok((id, instance_id, event_type, event_time, details, launch_id))
+        })?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            let (id, instance_id, event_type, event_time, details, launch_id) = row?;
+            let (Ok(instance_id), Ok(time)) = (
+                uuid::Uuid::parse_str(&instance_id),
+                chrono::DateTime::parse_from_rfc3339(&event_time),
+            ) else {
+                error!("Invalid instance history row {}", id);
+                continue;
+            };
+            result.push(HistoryEntry {
+                id,
+                instance_id: InstanceId(instance_id),
+                event_type,
+                time: time.with_timezone(&chrono::Utc),
+                details,
+                launch_id,
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// Delete all instance history
+    pub fn clear_instance_history(&self) -> Result<usize> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Database lock poisoned: {}", e))?;
+        let count = conn.execute("DELETE FROM instance_history", [])?;
+        info!("Cleared {} instance history entries", count);
+        Ok(count)
+    }
+
     /// Clean up old history entries
     pub fn cleanup_history(&self, retention_days: u32) -> Result<usize> {
         if retention_days == 0 {
@@ -590,12 +1372,30 @@ impl Database {
         let cutoff = chrono::Utc::now()
             - chrono::TimeDelta::try_days(retention_days as i64)
                 .unwrap_or_else(|| chrono::TimeDelta::days(30));
-        let count = conn.execute(
+        let mut count = conn.execute(
             "DELETE FROM instance_history WHERE event_time < ?1",
             params![cutoff.to_rfc3339()],
         )?;
+        count += conn.execute(
+            "DELETE FROM profile_runs WHERE run_time < ?1",
+            params![cutoff.to_rfc3339()],
+        )?;
+        count += conn.execute(
+            "DELETE FROM usage_daily WHERE day < ?1",
+            params![cutoff.date_naive().to_string()],
+        )?;
 
         debug!("Cleaned up {} old history entries", count);
         Ok(count)
     }
 }
+
+/// `bytes` in memory allocated by SQLite, which takes ownership of deserialized data
+fn sqlite_owned(bytes: &[u8]) -> Result<OwnedData> {
+    let ptr = unsafe { rusqlite::ffi::sqlite3_malloc64(bytes.len().max(1) as u64) }.cast::<u8>();
+    let ptr = std::ptr::NonNull::new(ptr).context("Out of memory")?;
+    unsafe {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr.as_ptr(), bytes.len());
+        Ok(OwnedData::from_raw_nonnull(ptr, bytes.len()))
+    }
+}
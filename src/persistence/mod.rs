@@ -1,5 +1,7 @@
 //! Persistence layer - SQLite database for storing application data
 
+pub mod data_lock;
 mod database;
+pub mod profile_sync;
 
 pub use database::Database;
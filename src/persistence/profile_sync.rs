@@ -0,0 +1,172 @@
+//! Profile sync - Mirrors profiles and quick launch items into a sync folder
+//!
+//! Each profile is written to `profiles/<id>.json` and the quick launch list to
+//! `quick_launch.json` in a folder the user picks, typically one that Dropbox or
+//! OneDrive keeps in step between machines. Deleted profiles are recorded in
+//! `deleted.json` so the deletion reaches the other machines too. Changes made
+//! elsewhere are found by comparing file modification times with the last ones seen.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::core::{InstanceConfig, Profile, ProfileId};
+
+/// How often the folder is checked for changes from other machines
+pub const SCAN_INTERVAL: Duration = Duration::from_secs(15);
+
+const PROFILES_DIR: &str = "profiles";
+const QUICK_LAUNCH_FILE: &str = "quick_launch.json";
+const DELETED_FILE: &str = "deleted.json";
+
+/// A profile deleted on some machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tombstone {
+    pub id: ProfileId,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// What changed in the folder since it was last scanned
+#[derive(Debug, Default)]
+pub struct SyncChanges {
+    pub profiles: Vec<Profile>,
+    pub deleted: Vec<Tombstone>,
+    pub quick_launch: Option<Vec<InstanceConfig>>,
+}
+
+/// The sync folder and the modification times last seen in it
+#[derive(Debug, Default)]
+pub struct ProfileSync {
+    root: Option<PathBuf>,
+    seen: HashMap<PathBuf, SystemTime>,
+    last_scan: Option<Instant>,
+}
+
+impl ProfileSync {
+    /// Point at `root`. Returns true when it changed, after which the next scan
+    /// reports everything in the folder so it can be merged.
+    pub fn set_root(&mut self, root: Option<&Path>) -> bool {
+        if self.root.as_deref() == root {
+            return false;
+        }
+        *self = Self {
+            root: root.map(Path::to_path_buf),
+            ..Self::default()
+        };
+        true
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.root.is_some()
+    }
+
+    /// Whether a scan is due
+    pub fn scan_due(&self) -> bool {
+        self.is_enabled() && self.last_scan.is_none_or(|t| t.elapsed() >= SCAN_INTERVAL)
+    }
+
+    /// Files written or changed since the last scan
+    pub fn scan(&mut self) -> Result<SyncChanges> {
+        self.last_scan = Some(Instant::now());
+        let Some(root) = self.root.clone() else {
+            return Ok(SyncChanges::default());
+        };
+        let mut changes = SyncChanges::default();
+
+        let profiles_dir = root.join(PROFILES_DIR);
+        if profiles_dir.is_dir() {
+            for entry in std::fs::read_dir(&profiles_dir)? {
+                let path = entry?.path();
+                if path.extension().is_none_or(|e| e != "json") || !self.changed(&path) {
+                    continue;
+                }
+                match read_json::<Profile>(&path) {
+                    Ok(profile) => changes.profiles.push(profile),
+                    Err(e) => warn!("Skipping synced profile {:?}: {}", path, e),
+                }
+            }
+        }
+
+        let deleted = root.join(DELETED_FILE);
+        if self.changed(&deleted) {
+            changes.deleted = read_json(&deleted)?;
+        }
+        let quick_launch = root.join(QUICK_LAUNCH_FILE);
+        if self.changed(&quick_launch) {
+            changes.quick_launch = Some(read_json(&quick_launch)?);
+        }
+        Ok(changes)
+    }
+
+    pub fn write_profile(&mut self, profile: &Profile) -> Result<()> {
+        let Some(root) = self.root.clone() else {
+            return Ok(());
+        };
+        let dir = root.join(PROFILES_DIR);
+        std::fs::create_dir_all(&dir)?;
+        self.write(&dir.join(format!("{}.json", profile.id)), profile)
+    }
+
+    /// Remove a profile's file and leave a tombstone for the other machines
+    pub fn remove_profile(&mut self, id: ProfileId) -> Result<()> {
+        let Some(root) = self.root.clone() else {
+            return Ok(());
+        };
+        let path = root.join(PROFILES_DIR).join(format!("{}.json", id));
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+        self.seen.remove(&path);
+
+        let deleted_path = root.join(DELETED_FILE);
+        let mut deleted: Vec<Tombstone> = read_json(&deleted_path).unwrap_or_default();
+        deleted.retain(|t| t.id != id);
+        deleted.push(Tombstone {
+            id,
+            deleted_at: Utc::now(),
+        });
+        self.write(&deleted_path, &deleted)
+    }
+
+    pub fn write_quick_launch(&mut self, items: &[InstanceConfig]) -> Result<()> {
+        let Some(root) = self.root.clone() else {
+            return Ok(());
+        };
+        std::fs::create_dir_all(&root)?;
+        self.write(&root.join(QUICK_LAUNCH_FILE), &items)
+    }
+
+    /// Write a file and remember its time so the write is not read back as a change
+    fn write<T: Serialize + ?Sized>(&mut self, path: &Path, value: &T) -> Result<()> {
+        let json = serde_json::to_string_pretty(value)?;
+        std::fs::write(path, json).with_context(|| format!("Failed to write {:?}", path))?;
+        if let Some(modified) = modified(path) {
+            self.seen.insert(path.to_path_buf(), modified);
+        }
+        Ok(())
+    }
+
+    /// Whether a file exists with a time other than the one last seen, noting the new one
+    fn changed(&mut self, path: &Path) -> bool {
+        let Some(modified) = modified(path) else {
+            return false;
+        };
+        self.seen.insert(path.to_path_buf(), modified) != Some(modified)
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T> {
+    let json =
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+    serde_json::from_str(&json).with_context(|| format!("{:?} is not valid", path))
+}
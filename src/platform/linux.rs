@@ -0,0 +1,940 @@
+//! Linux-specific process management
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{LazyLock, RwLock};
+use std::time::Duration;
+use tracing::{debug, info};
+
+use crate::core::inspect::{Connection, HandleKind, OpenHandle};
+use crate::core::{IoPriority, ResourceLimits};
+
+/// Prefix of the cgroups created for memory limits
+const CGROUP_PREFIX: &str = "multiinstance-";
+
+/// Send a signal to a process
+fn signal(pid: u32, signal: libc::c_int, action: &str) -> Result<()> {
+    if unsafe { libc::kill(pid as libc::pid_t, signal) } == 0 {
+        Ok(())
+    } else {
+        anyhow::bail!(
+            "Failed to {} process: {}",
+            action,
+            std::io::Error::last_os_error()
+        )
+    }
+}
+
+/// Terminate a process gracefully (SIGTERM)
+pub fn terminate_process(pid: u32) -> Result<()> {
+    signal(pid, libc::SIGTERM, "terminate")
+}
+
+/// Force kill a process (SIGKILL)
+pub fn kill_process(pid: u32) -> Result<()> {
+    signal(pid, libc::SIGKILL, "kill")
+}
+
+/// Suspend a process (SIGSTOP)
+pub fn suspend_process(pid: u32) -> Result<()> {
+    signal(pid, libc::SIGSTOP, "suspend")
+}
+
+/// Resume a suspended process (SIGCONT)
+pub fn resume_process(pid: u32) -> Result<()> {
+    signal(pid, libc::SIGCONT, "resume")
+}
+
+/// Check if a process is running. Exited children we never reaped linger as
+/// zombies, so those count as stopped.
+pub fn is_process_running(pid: u32) -> bool {
+    if unsafe { libc::kill(pid as libc::pid_t, 0) } != 0 {
+        return false;
+    }
+    process_state(pid).is_some_and(|state| state != 'Z' && state != 'X')
+}
+
+/// State letter from `/proc/<pid>/stat` (R, S, D, T, Z, ...)
+fn process_state(pid: u32) -> Option<char> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    stat.get(stat.rfind(')')? + 2..)?.chars().next()
+}
+
+/// Thread IDs of a process from `/proc/<pid>/task`
+fn threads(pid: u32) -> Vec<libc::pid_t> {
+    std::fs::read_dir(format!("/proc/{}/task", pid))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| e.file_name().to_str()?.parse().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Pin every thread of a process to the given cores with sched_setaffinity
+pub fn set_cpu_affinity(pid: u32, cores: &[usize]) -> Result<()> {
+    if cores.is_empty() {
+        return Ok(());
+    }
+
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    for &core in cores {
+        if core >= libc::CPU_SETSIZE as usize {
+            anyhow::bail!("Core {} is out of range", core);
+        }
+        unsafe { libc::CPU_SET(core, &mut set) };
+    }
+
+    // Affinity is per thread; new threads inherit it from the one that creates them
+    let mut tids = threads(pid);
+    if tids.is_empty() {
+        tids.push(pid as libc::pid_t);
+    }
+    for tid in tids {
+        let result =
+            unsafe { libc::sched_setaffinity(tid, std::mem::size_of::<libc::cpu_set_t>(), &set) };
+        if result != 0 {
+            let err = std::io::Error::last_os_error();
+            // The thread may have exited since we listed it
+            if err.raw_os_error() != Some(libc::ESRCH) {
+                anyhow::bail!("Failed to set CPU affinity: {}", err);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Nice value for a priority level picked in the instance dialogs (-2 to 2),
+/// or the value itself when it is outside that range
+fn nice_value(priority: i8) -> i32 {
+    match priority {
+        ResourceLimits::EFFICIENCY_PRIORITY | -2 => 19,
+        -1 => 10,
+        0 => 0,
+        1 => -5,
+        2 => -10,
+        p => p as i32,
+    }
+}
+
+/// Set process priority with nice; the efficiency level also moves the
+/// process to the SCHED_IDLE policy
+pub fn set_process_priority(pid: u32, priority: i8) -> Result<()> {
+    let nice = nice_value(priority);
+    for tid in threads(pid) {
+        // Each thread has its own nice value on Linux
+        unsafe {
+            libc::setpriority(libc::PRIO_PROCESS, tid as libc::id_t, nice);
+        }
+    }
+    let result = unsafe { libc::setpriority(libc::PRIO_PROCESS, pid as libc::id_t, nice) };
+    if result != 0 {
+        let err = std::io::Error::last_os_error();
+        if nice < 0 && err.raw_os_error() == Some(libc::EACCES) {
+            anyhow::bail!("Raising priority needs CAP_SYS_NICE or a higher RLIMIT_NICE");
+        }
+        anyhow::bail!("Failed to set priority: {}", err);
+    }
+
+    let param = libc::sched_param { sched_priority: 0 };
+    if priority == ResourceLimits::EFFICIENCY_PRIORITY {
+        if unsafe { libc::sched_setscheduler(pid as libc::pid_t, libc::SCHED_IDLE, &param) } != 0 {
+            anyhow::bail!(
+                "Failed to set idle scheduling: {}",
+                std::io::Error::last_os_error()
+            );
+        }
+    } else if unsafe { libc::sched_getscheduler(pid as libc::pid_t) } == libc::SCHED_IDLE {
+        // Leaving the efficiency level returns the process to normal scheduling
+        if unsafe { libc::sched_setscheduler(pid as libc::pid_t, libc::SCHED_OTHER, &param) } != 0 {
+            anyhow::bail!(
+                "Failed to restore normal scheduling: {}",
+                std::io::Error::last_os_error()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Our own cgroup v2 directory, from the `0::` line of `/proc/self/cgroup`
+fn own_cgroup() -> Result<PathBuf> {
+    let cgroups =
+        std::fs::read_to_string("/proc/self/cgroup").context("Failed to read /proc/self/cgroup")?;
+    let path = cgroups
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .context("cgroup v2 is not mounted")?;
+    Ok(Path::new("/sys/fs/cgroup").join(path.trim_start_matches('/')))
+}
+
+/// Limit a process's memory by moving it into its own cgroup v2 next to ours.
+/// Needs a delegated cgroup with the memory controller, as systemd gives user sessions.
+pub fn set_memory_limit(pid: u32, memory_mb: u64) -> Result<()> {
+    if memory_mb == 0 {
+        return Ok(());
+    }
+
+    let cgroup = instance_cgroup(pid, "memory")?;
+    let bytes = (memory_mb * 1024 * 1024).to_string();
+    std::fs::write(cgroup.join("memory.max"), &bytes).context("Failed to set memory.max")?;
+    // Without this the kernel swaps the process out instead of enforcing the limit
+    let _ = std::fs::write(cgroup.join("memory.swap.max"), "0");
+    std::fs::write(cgroup.join("cgroup.procs"), pid.to_string())
+        .context("Failed to move the process into its cgroup")?;
+
+    info!(
+        "Limited PID {} to {} MB with cgroup {}",
+        pid,
+        memory_mb,
+        cgroup.display()
+    );
+    Ok(())
+}
+
+/// Cap a process's CPU time to `percent` of the whole machine with the cgroup
+/// v2 CPU controller; 0 removes the cap
+pub fn set_cpu_rate(pid: u32, percent: u8) -> Result<()> {
+    const PERIOD: u64 = 100_000;
+
+    if percent == 0 {
+        // Only an existing cgroup can hold a cap to lift
+        let cgroup = own_cgroup()?
+            .parent()
+            .map(|parent| parent.join(format!("{}{}", CGROUP_PREFIX, pid)));
+        if let Some(cgroup) = cgroup.filter(|c| c.join("cpu.max").exists()) {
+            std::fs::write(cgroup.join("cpu.max"), format!("max {}", PERIOD))
+                .context("Failed to clear cpu.max")?;
+        }
+        return Ok(());
+    }
+
+    let cgroup = instance_cgroup(pid, "cpu")?;
+    let cores = std::thread::available_parallelism().map_or(1, |n| n.get()) as u64;
+    let quota = (PERIOD * cores * percent.min(100) as u64 / 100).max(1000);
+    std::fs::write(cgroup.join("cpu.max"), format!("{} {}", quota, PERIOD))
+        .context("Failed to set cpu.max")?;
+    std::fs::write(cgroup.join("cgroup.procs"), pid.to_string())
+        .context("Failed to move the process into its cgroup")?;
+
+    info!(
+        "Capped PID {} at {}% CPU with cgroup {}",
+        pid,
+        percent,
+        cgroup.display()
+    );
+    Ok(())
+}
+
+/// Set the I/O scheduling class of every thread of a process with ioprio_set
+pub fn set_io_priority(pid: u32, priority: IoPriority) -> Result<()> {
+    const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+    const IOPRIO_CLASS_SHIFT: u32 = 13;
+    const IOPRIO_CLASS_BE: u32 = 2;
+    const IOPRIO_CLASS_IDLE: u32 = 3;
+
+    let ioprio = match priority {
+        IoPriority::VeryLow => IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT,
+        // Best-effort levels run from 0 (highest) to 7, with 4 the default
+        IoPriority::Low => (IOPRIO_CLASS_BE << IOPRIO_CLASS_SHIFT) | 7,
+        IoPriority::Normal => (IOPRIO_CLASS_BE << IOPRIO_CLASS_SHIFT) | 4,
+    };
+    // Like nice values, I/O priorities belong to each thread
+    let mut tids = threads(pid);
+    if tids.is_empty() {
+        tids.push(pid as libc::pid_t);
+    }
+    for tid in tids {
+        let result =
+            unsafe { libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, tid, ioprio) };
+        if result != 0 {
+            anyhow::bail!(
+                "Failed to set I/O priority: {}",
+                std::io::Error::last_os_error()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Cap a process's disk throughput with the cgroup v2 I/O controller, on
+/// every disk as io.max limits are per device
+pub fn set_disk_rate(pid: u32, mbps: u64) -> Result<()> {
+    if mbps == 0 {
+        return Ok(());
+    }
+
+    let cgroup = instance_cgroup(pid, "io")?;
+    let bytes = mbps * 1024 * 1024;
+    let mut limited = 0;
+    for device in block_devices() {
+        // io.max takes one device per write
+        let line = format!("{} rbps={} wbps={}", device, bytes, bytes);
+        match std::fs::write(cgroup.join("io.max"), line) {
+            Ok(()) => limited += 1,
+            Err(e) => debug!("Cannot limit device {}: {}", device, e),
+        }
+    }
+    if limited == 0 {
+        anyhow::bail!("No disk accepted an I/O limit");
+    }
+    std::fs::write(cgroup.join("cgroup.procs"), pid.to_string())
+        .context("Failed to move the process into its cgroup")?;
+
+    info!(
+        "Capped PID {} at {} MB/s of disk I/O on {} disks with cgroup {}",
+        pid,
+        mbps,
+        limited,
+        cgroup.display()
+    );
+    Ok(())
+}
+
+/// `major:minor` numbers of the disks, skipping loop and RAM devices
+fn block_devices() -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir("/sys/block") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            !["loop", "ram", "zram"].iter().any(|p| name.starts_with(p))
+        })
+        .filter_map(|e| std::fs::read_to_string(e.path().join("dev")).ok())
+        .map(|dev| dev.trim().to_string())
+        .collect()
+}
+
+/// The cgroup holding the limits of one instance, created next to our own
+/// cgroup once `controller` is known to be available there
+fn instance_cgroup(pid: u32, controller: &str) -> Result<PathBuf> {
+    let parent = own_cgroup()?
+        .parent()
+        .map(Path::to_path_buf)
+        .context("Cannot create a cgroup next to the root cgroup")?;
+    let controllers = std::fs::read_to_string(parent.join("cgroup.subtree_control"))
+        .context("Failed to read cgroup controllers")?;
+    if !controllers.split_whitespace().any(|c| c == controller) {
+        anyhow::bail!(
+            "The {} controller is not enabled for {}",
+            controller,
+            parent.display()
+        );
+    }
+
+    remove_stale_cgroups(&parent);
+
+    let cgroup = parent.join(format!("{}{}", CGROUP_PREFIX, pid));
+    std::fs::create_dir_all(&cgroup)
+        .with_context(|| format!("Failed to create cgroup {}", cgroup.display()))?;
+    Ok(cgroup)
+}
+
+/// Remove memory-limit cgroups whose processes have all exited
+fn remove_stale_cgroups(parent: &Path) {
+    let Ok(entries) = std::fs::read_dir(parent) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if !entry
+            .file_name()
+            .to_string_lossy()
+            .starts_with(CGROUP_PREFIX)
+        {
+            continue;
+        }
+        let empty = std::fs::read_to_string(entry.path().join("cgroup.procs"))
+            .is_ok_and(|procs| procs.trim().is_empty());
+        if empty && std::fs::remove_dir(entry.path()).is_ok() {
+            debug!("Removed stale cgroup {}", entry.path().display());
+        }
+    }
+}
+
+/// Dump a process tree to disk with CRIU. The process exits once the dump completes.
+pub fn checkpoint_process(pid: u32, images_dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(images_dir)?;
+
+    let output = Command::new("criu")
+        .arg("dump")
+        .args(["--tree", &pid.to_string()])
+        .arg("--images-dir")
+        .arg(images_dir)
+        .args(["--shell-job", "--file-locks", "--tcp-established"])
+        .output()
+        .context("Failed to run criu (is it installed and are we running as root?)")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "criu dump failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    info!("Checkpointed PID {} to {:?}", pid, images_dir);
+    Ok(())
+}
+
+/// Restore a process tree previously dumped with CRIU, returning its PID
+pub fn restore_process(images_dir: &Path) -> Result<u32> {
+    let pid_file = images_dir.join("restored.pid");
+    let _ = std::fs::remove_file(&pid_file);
+
+    let output = Command::new("criu")
+        .arg("restore")
+        .arg("--images-dir")
+        .arg(images_dir)
+        .args(["--shell-job", "--file-locks", "--tcp-established"])
+        .arg("--restore-detached")
+        .arg("--pidfile")
+        .arg(&pid_file)
+        .output()
+        .context("Failed to run criu (is it installed and are we running as root?)")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "criu restore failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let pid = std::fs::read_to_string(&pid_file)
+        .context("criu did not write a pid file")?
+        .trim()
+        .parse::<u32>()
+        .context("Invalid pid in criu pid file")?;
+
+    info!("Restored checkpoint {:?} as PID {}", images_dir, pid);
+    Ok(pid)
+}
+
+/// Read the ELF class of a process's executable to get its pointer width
+pub fn process_bitness(pid: u32) -> Option<u32> {
+    use std::io::Read;
+
+    let mut header = [0u8; 5];
+    std::fs::File::open(format!("/proc/{}/exe", pid))
+        .and_then(|mut exe| exe.read_exact(&mut header))
+        .ok()?;
+    match (&header[..4], header[4]) {
+        (b"\x7fELF", 1) => Some(32),
+        (b"\x7fELF", 2) => Some(64),
+        _ => None,
+    }
+}
+
+/// Sum `utime` and `stime` from `/proc/<pid>/stat`
+pub fn process_cpu_time(pid: u32) -> Option<Duration> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // The command name may contain spaces, so count fields from its closing parenthesis
+    let fields: Vec<&str> = stat.get(stat.rfind(')')? + 2..)?.split(' ').collect();
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+
+    let ticks_per_sec = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    if ticks_per_sec <= 0 {
+        return None;
+    }
+    Some(Duration::from_secs_f64(
+        (utime + stime) as f64 / ticks_per_sec as f64,
+    ))
+}
+
+/// Read core kinds from sysfs: Intel hybrid CPUs list E-cores under `cpu_atom`,
+/// ARM big.LITTLE systems report a relative `cpu_capacity` per CPU
+pub fn cpu_efficiency_classes() -> Option<Vec<(usize, usize, u8)>> {
+    let cpu_root = Path::new("/sys/devices/system/cpu");
+    let online = parse_cpu_list(&std::fs::read_to_string(cpu_root.join("online")).ok()?);
+    let atom_cpus = std::fs::read_to_string("/sys/devices/cpu_atom/cpus")
+        .map(|list| parse_cpu_list(&list))
+        .unwrap_or_default();
+
+    let read_number = |path: std::path::PathBuf| -> Option<usize> {
+        std::fs::read_to_string(path).ok()?.trim().parse().ok()
+    };
+
+    let processors = online
+        .into_iter()
+        .map(|cpu| {
+            let cpu_dir = cpu_root.join(format!("cpu{}", cpu));
+            let package = read_number(cpu_dir.join("topology/physical_package_id")).unwrap_or(0);
+            let core = read_number(cpu_dir.join("topology/core_id")).unwrap_or(cpu);
+            let class = if !atom_cpus.is_empty() {
+                u8::from(!atom_cpus.contains(&cpu))
+            } else {
+                read_number(cpu_dir.join("cpu_capacity"))
+                    .map(|capacity| (capacity >> 2).min(255) as u8)
+                    .unwrap_or(0)
+            };
+            (cpu, package * 4096 + core, class)
+        })
+        .collect();
+    Some(processors)
+}
+
+/// Parse a kernel CPU list such as "0-3,8,10-11"
+fn parse_cpu_list(list: &str) -> Vec<usize> {
+    list.trim()
+        .split(',')
+        .filter_map(|range| match range.split_once('-') {
+            Some((start, end)) => Some(start.parse().ok()?..=end.parse().ok()?),
+            None => {
+                let cpu = range.parse().ok()?;
+                Some(cpu..=cpu)
+            }
+        })
+        .flatten()
+        .collect()
+}
+
+/// Write or remove an XDG autostart entry in ~/.config/autostart
+pub fn set_autostart(app_name: &str, executable_path: &str, enabled: bool) -> Result<()> {
+    let autostart_dir = dirs::config_dir()
+        .context("Failed to get config directory")?
+        .join("autostart");
+    let entry_path = autostart_dir.join(format!("{}.desktop", app_name.to_lowercase()));
+
+    if enabled {
+        std::fs::create_dir_all(&autostart_dir)?;
+        let entry = format!(
+            "[Desktop Entry]\nType=Application\nName={}\nExec=\"{}\"\nX-GNOME-Autostart-enabled=true\n",
+            app_name, executable_path
+        );
+        std::fs::write(&entry_path, entry)?;
+    } else if entry_path.exists() {
+        std::fs::remove_file(&entry_path)?;
+    }
+    Ok(())
+}
+
+/// Check whether a package installed a system-wide autostart entry in /etc/xdg/autostart
+pub fn machine_autostart_registered(app_name: &str) -> bool {
+    Path::new("/etc/xdg/autostart")
+        .join(format!("{}.desktop", app_name.to_lowercase()))
+        .exists()
+}
+
+/// Desktop notification through `notify-send` (libnotify)
+pub fn show_notification(title: &str, body: &str) -> Result<()> {
+    let output = Command::new("notify-send")
+        .args(["--app-name=MultiInstance", title, body])
+        .output()
+        .context("Desktop notifications need notify-send (libnotify)")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "notify-send failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Freedesktop warning sound through libcanberra, falling back to PulseAudio
+pub fn play_alert_sound() -> Result<()> {
+    let played = Command::new("canberra-gtk-play")
+        .args(["--id=dialog-warning"])
+        .status()
+        .is_ok_and(|status| status.success());
+    if played {
+        return Ok(());
+    }
+    let status = Command::new("paplay")
+        .arg("/usr/share/sounds/freedesktop/stereo/dialog-warning.oga")
+        .status()
+        .context("Alert sounds need canberra-gtk-play or paplay")?;
+    if !status.success() {
+        anyhow::bail!("paplay failed with {}", status);
+    }
+    Ok(())
+}
+
+/// SSID of the connected Wi-Fi network, via wireless-tools or NetworkManager
+pub fn current_wifi_ssid() -> Option<String> {
+    let non_empty = |output: std::process::Output| {
+        let ssid = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (output.status.success() && !ssid.is_empty()).then_some(ssid)
+    };
+
+    if let Some(ssid) = Command::new("iwgetid")
+        .arg("-r")
+        .output()
+        .ok()
+        .and_then(non_empty)
+    {
+        return Some(ssid);
+    }
+
+    let output = Command::new("nmcli")
+        .args(["-t", "-f", "active,ssid", "dev", "wifi"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix("yes:"))
+        .map(str::to_string)
+        .filter(|ssid| !ssid.is_empty())
+}
+
+/// Resolve the links in `/proc/<pid>/fd`
+pub fn open_handles(pid: u32) -> Result<Vec<OpenHandle>> {
+    let entries = std::fs::read_dir(format!("/proc/{}/fd", pid))
+        .context("Failed to read the process's file descriptors")?;
+
+    let mut handles: Vec<OpenHandle> = entries
+        .filter_map(|entry| std::fs::read_link(entry.ok()?.path()).ok())
+        .map(|target| {
+            let name = target.to_string_lossy().to_string();
+            let kind = if name.starts_with("socket:") {
+                HandleKind::Socket
+            } else if name.starts_with("pipe:") {
+                HandleKind::Pipe
+            } else if !target.is_absolute() || name.starts_with("/dev/") {
+                HandleKind::Other
+            } else if target.is_dir() {
+                HandleKind::Directory
+            } else {
+                HandleKind::File
+            };
+            OpenHandle { kind, name }
+        })
+        .collect();
+    handles.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(handles)
+}
+
+/// Match the process's socket inodes against the tables in `/proc/<pid>/net`
+pub fn connections(pid: u32) -> Result<Vec<Connection>> {
+    let inodes: std::collections::HashSet<String> = std::fs::read_dir(format!("/proc/{}/fd", pid))
+        .context("Failed to read the process's file descriptors")?
+        .filter_map(|entry| std::fs::read_link(entry.ok()?.path()).ok())
+        .filter_map(|target| {
+            let name = target.to_string_lossy().to_string();
+            Some(
+                name.strip_prefix("socket:[")?
+                    .strip_suffix(']')?
+                    .to_string(),
+            )
+        })
+        .collect();
+
+    let mut connections = Vec::new();
+    for (table, protocol) in [
+        ("tcp", "TCP"),
+        ("tcp6", "TCP6"),
+        ("udp", "UDP"),
+        ("udp6", "UDP6"),
+    ] {
+        let Ok(contents) = std::fs::read_to_string(format!("/proc/{}/net/{}", pid, table)) else {
+            continue;
+        };
+        for line in contents.lines().skip(1) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 || !inodes.contains(fields[9]) {
+                continue;
+            }
+            let (Some(local), Some(remote)) =
+                (proc_net_endpoint(fields[1]), proc_net_endpoint(fields[2]))
+            else {
+                continue;
+            };
+            let tcp = protocol.starts_with("TCP");
+            let state = if tcp { tcp_state(fields[3]) } else { "" };
+            connections.push(Connection {
+                protocol,
+                local: local.to_string(),
+                remote: (remote.port() != 0).then(|| remote.to_string()),
+                state: state.to_string(),
+                bytes_in: None,
+                bytes_out: None,
+                rx_rate: None,
+                tx_rate: None,
+            });
+        }
+    }
+    Ok(connections)
+}
+
+/// Parse `ADDR:PORT` from /proc/net, where the address is hex words in host byte order
+fn proc_net_endpoint(field: &str) -> Option<std::net::SocketAddr> {
+    let (addr, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let words: Vec<u32> = (0..addr.len() / 8)
+        .map(|i| u32::from_str_radix(&addr[i * 8..i * 8 + 8], 16).ok())
+        .collect::<Option<_>>()?;
+    let ip: std::net::IpAddr = match words.as_slice() {
+        [v4] => std::net::Ipv4Addr::from(v4.to_le_bytes()).into(),
+        [a, b, c, d] => {
+            let mut bytes = [0u8; 16];
+            for (chunk, word) in bytes.chunks_mut(4).zip([a, b, c, d]) {
+                chunk.copy_from_slice(&word.to_le_bytes());
+            }
+            std::net::Ipv6Addr::from(bytes).into()
+        }
+        _ => return None,
+    };
+    Some(std::net::SocketAddr::new(ip, port))
+}
+
+fn tcp_state(code: &str) -> &'static str {
+    match code {
+        "01" => "Established",
+        "02" => "SYN sent",
+        "03" => "SYN received",
+        "04" => "FIN wait 1",
+        "05" => "FIN wait 2",
+        "06" => "Time wait",
+        "07" => "Closed",
+        "08" => "Close wait",
+        "09" => "Last ACK",
+        "0A" => "Listen",
+        "0B" => "Closing",
+        _ => "Unknown",
+    }
+}
+
+/// X11 id of a process's most recently mapped visible window, found with `xdotool`
+fn main_window(pid: u32) -> Result<String> {
+    let output = Command::new("xdotool")
+        .args(["search", "--onlyvisible", "--pid", &pid.to_string()])
+        .output()
+        .context("Window access needs xdotool and an X11 session")?;
+    // xdotool lists windows oldest first
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .last()
+        .map(str::to_string)
+        .context("Instance has no visible window")
+}
+
+/// Capture a process's window through X11 with `xdotool` and ImageMagick's `import`
+pub fn capture_window(pid: u32) -> Result<image::RgbaImage> {
+    let window = main_window(pid)?;
+
+    let output = Command::new("import")
+        .args(["-silent", "-window", &window, "png:-"])
+        .output()
+        .context("Window capture needs ImageMagick's import")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "import failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(image::load_from_memory(&output.stdout)?.to_rgba8())
+}
+
+/// Run xdotool and fail with its error output
+fn xdotool(args: &[&str]) -> Result<()> {
+    let output = Command::new("xdotool")
+        .args(args)
+        .output()
+        .context("Input injection needs xdotool and an X11 session")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "xdotool failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// X11 id of the active window
+pub fn foreground_window() -> Option<u64> {
+    let output = Command::new("xdotool")
+        .arg("getactivewindow")
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// Reactivate the previous window whenever one of the instance's becomes active
+pub fn keep_focus_from(pid: u32, previous: u64) -> Result<()> {
+    let previous = previous.to_string();
+    let deadline = std::time::Instant::now() + super::FOCUS_GUARD;
+    while std::time::Instant::now() < deadline {
+        let output = Command::new("xdotool")
+            .args(["getactivewindow", "getwindowpid"])
+            .output()
+            .context("Keeping focus needs xdotool and an X11 session")?;
+        let active_pid = String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse::<u32>();
+        if active_pid == Ok(pid) {
+            xdotool(&["windowactivate", &previous])?;
+            debug!("Returned focus taken by PID {}", pid);
+        }
+        std::thread::sleep(std::time::Duration::from_millis(250));
+    }
+    Ok(())
+}
+
+pub fn focus_window(pid: u32) -> Result<()> {
+    let window = main_window(pid)?;
+    xdotool(&["windowactivate", "--sync", &window])
+}
+
+/// Windows unmapped by `apply_window_state`, by PID, so they can be mapped again.
+/// Unmapped windows are skipped by `main_window`, which only sees visible ones.
+static HIDDEN_WINDOWS: LazyLock<RwLock<HashMap<u32, String>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+pub fn apply_window_state(pid: u32, state: crate::core::WindowState) -> Result<()> {
+    use crate::core::WindowState;
+
+    let deadline = std::time::Instant::now() + super::WINDOW_WAIT;
+    let window = loop {
+        match main_window(pid) {
+            Ok(window) => break window,
+            Err(e) if std::time::Instant::now() >= deadline => return Err(e),
+            Err(_) => std::thread::sleep(Duration::from_millis(250)),
+        }
+    };
+    match state {
+        WindowState::Normal => Ok(()),
+        WindowState::Minimized => xdotool(&["windowminimize", &window]),
+        WindowState::Hidden => {
+            xdotool(&["windowunmap", &window])?;
+            if let Ok(mut hidden) = HIDDEN_WINDOWS.write() {
+                hidden.insert(pid, window);
+            }
+            Ok(())
+        }
+    }
+}
+
+pub fn show_window(pid: u32) -> Result<()> {
+    let hidden = HIDDEN_WINDOWS
+        .write()
+        .ok()
+        .and_then(|mut hidden| hidden.remove(&pid));
+    if let Some(window) = hidden {
+        xdotool(&["windowmap", "--sync", &window])?;
+    }
+    focus_window(pid)
+}
+
+pub fn process_windows(pid: u32) -> Result<Vec<(u64, String)>> {
+    let output = Command::new("xdotool")
+        .args(["search", "--onlyvisible", "--pid", &pid.to_string()])
+        .output()
+        .context("Window access needs xdotool and an X11 session")?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|window| {
+            let name = Command::new("xdotool")
+                .args(["getwindowname", window])
+                .output()
+                .ok()?;
+            Some((
+                window.parse().ok()?,
+                String::from_utf8_lossy(&name.stdout).trim().to_string(),
+            ))
+        })
+        .collect())
+}
+
+pub fn close_window(window: u64) -> Result<()> {
+    xdotool(&["windowclose", &window.to_string()])
+}
+
+pub fn set_window_title(window: u64, title: &str) -> Result<()> {
+    xdotool(&["set_window", "--name", title, &window.to_string()])
+}
+
+pub fn work_area() -> Result<crate::core::window_layout::WindowRect> {
+    let output = Command::new("xdotool")
+        .arg("getdisplaygeometry")
+        .output()
+        .context("Arranging windows needs xdotool and an X11 session")?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut size = text.split_whitespace().filter_map(|v| v.parse().ok());
+    match (size.next(), size.next()) {
+        (Some(width), Some(height)) => Ok(crate::core::window_layout::WindowRect {
+            x: 0,
+            y: 0,
+            width,
+            height,
+        }),
+        _ => anyhow::bail!("Failed to read the display size"),
+    }
+}
+
+pub fn set_window_bounds(window: u64, rect: crate::core::window_layout::WindowRect) -> Result<()> {
+    let window = window.to_string();
+    xdotool(&["windowmap", &window])?;
+    xdotool(&[
+        "windowsize",
+        &window,
+        &rect.width.to_string(),
+        &rect.height.to_string(),
+    ])?;
+    xdotool(&[
+        "windowmove",
+        &window,
+        &rect.x.to_string(),
+        &rect.y.to_string(),
+    ])
+}
+
+/// Translate a macro key name to an X keysym name
+fn keysym(key: &str) -> String {
+    match key {
+        "Enter" => "Return",
+        "Backspace" => "BackSpace",
+        "Space" => "space",
+        "ArrowUp" => "Up",
+        "ArrowDown" => "Down",
+        "ArrowLeft" => "Left",
+        "ArrowRight" => "Right",
+        "PageUp" => "Prior",
+        "PageDown" => "Next",
+        key if key.chars().count() == 1 => return key.to_lowercase(),
+        key => key,
+    }
+    .to_string()
+}
+
+/// Inject input into a process's window with xdotool
+pub fn send_input(pid: u32, event: &crate::core::macros::InputEvent) -> Result<()> {
+    use crate::core::macros::{InputEvent, MouseButton};
+
+    let window = main_window(pid)?;
+    match event {
+        InputEvent::Key { key, modifiers } => {
+            let mut combo = String::new();
+            for (held, name) in [
+                (modifiers.ctrl, "ctrl+"),
+                (modifiers.shift, "shift+"),
+                (modifiers.alt, "alt+"),
+                (modifiers.command, "super+"),
+            ] {
+                if held {
+                    combo.push_str(name);
+                }
+            }
+            combo.push_str(&keysym(key));
+            xdotool(&["key", "--window", &window, &combo])
+        }
+        InputEvent::Text(text) => xdotool(&["type", "--window", &window, "--", text]),
+        InputEvent::Click { x, y, button } => {
+            let button = match button {
+                MouseButton::Left => "1",
+                MouseButton::Middle => "2",
+                MouseButton::Right => "3",
+            };
+            xdotool(&[
+                "mousemove",
+                "--window",
+                &window,
+                &x.to_string(),
+                &y.to_string(),
+                "click",
+                button,
+            ])
+        }
+    }
+}
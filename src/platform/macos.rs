@@ -5,6 +5,8 @@ use std::path::PathBuf;
 use std::process::Command;
 use tracing::{debug, error, info, warn};
 
+use crate::core::inspect::{Connection, HandleKind, OpenHandle};
+
 /// Terminate a process gracefully (SIGTERM)
 pub fn terminate_process(pid: u32) -> Result<()> {
     unsafe {
@@ -73,6 +75,32 @@ pub fn is_process_running(pid: u32) -> bool {
     }
 }
 
+/// Total user + system time from `proc_pidinfo`, converted from Mach time units
+pub fn process_cpu_time(pid: u32) -> Option<std::time::Duration> {
+    unsafe {
+        let mut info: libc::proc_taskinfo = std::mem::zeroed();
+        let size = std::mem::size_of::<libc::proc_taskinfo>() as i32;
+        let written = libc::proc_pidinfo(
+            pid as i32,
+            libc::PROC_PIDTASKINFO,
+            0,
+            &mut info as *mut _ as *mut libc::c_void,
+            size,
+        );
+        if written != size {
+            return None;
+        }
+
+        let mut timebase: libc::mach_timebase_info = std::mem::zeroed();
+        if libc::mach_timebase_info(&mut timebase) != 0 || timebase.denom == 0 {
+            return None;
+        }
+        let mach = (info.pti_total_user + info.pti_total_system) as u128;
+        let nanos = mach * timebase.numer as u128 / timebase.denom as u128;
+        Some(std::time::Duration::from_nanos(nanos as u64))
+    }
+}
+
 /// Set CPU affinity for a process
 /// Note: macOS doesn't have direct CPU affinity APIs like Linux/Windows
 /// We use thread affinity tags as a hint to the scheduler
@@ -97,6 +125,30 @@ pub fn set_cpu_affinity(pid: u32, cores: &[usize]) -> Result<()> {
 
 /// Set process priority (nice value)
 pub fn set_process_priority(pid: u32, priority: i8) -> Result<()> {
+    // Background QoS keeps the process on E-cores on Apple Silicon
+    if priority == crate::core::ResourceLimits::EFFICIENCY_PRIORITY {
+        let status = Command::new("taskpolicy")
+            .args(["-b", "-p", &pid.to_string()])
+            .status()
+            .context("Failed to run taskpolicy")?;
+        if !status.success() {
+            anyhow::bail!("taskpolicy exited with {}", status);
+        }
+        return Ok(());
+    }
+
+    // Leave background QoS in case the efficiency level was set before
+    match Command::new("taskpolicy")
+        .args(["-B", "-p", &pid.to_string()])
+        .status()
+    {
+        Ok(status) if !status.success() => {
+            warn!("taskpolicy -B exited with {} for PID {}", status, pid)
+        }
+        Err(e) => warn!("Failed to run taskpolicy for PID {}: {}", pid, e),
+        Ok(_) => {}
+    }
+
     // Convert our -20 to 19 range to nice value
     let nice_value = priority as i32;
 
@@ -146,6 +198,168 @@ pub fn set_resource_limits(memory_mb: u64, cpu_percent: u8) -> Result<()> {
     Ok(())
 }
 
+extern "C" {
+    fn setiopolicy_np(iotype: libc::c_int, scope: libc::c_int, policy: libc::c_int) -> libc::c_int;
+}
+
+/// Set the disk I/O policy of the calling process. macOS cannot change another
+/// process's policy, so this runs in the child between fork and exec, which
+/// keeps the policy.
+pub fn set_own_io_priority(priority: crate::core::IoPriority) {
+    use crate::core::IoPriority;
+
+    const IOPOL_TYPE_DISK: libc::c_int = 0;
+    const IOPOL_SCOPE_PROCESS: libc::c_int = 0;
+    const IOPOL_THROTTLE: libc::c_int = 3;
+    const IOPOL_UTILITY: libc::c_int = 4;
+
+    let policy = match priority {
+        IoPriority::VeryLow => IOPOL_THROTTLE,
+        IoPriority::Low => IOPOL_UTILITY,
+        IoPriority::Normal => return,
+    };
+    unsafe {
+        setiopolicy_np(IOPOL_TYPE_DISK, IOPOL_SCOPE_PROCESS, policy);
+    }
+}
+
+/// Processes whose traffic goes through a dummynet pipe
+static NETWORK_LIMITED: std::sync::LazyLock<std::sync::Mutex<std::collections::HashSet<u32>>> =
+    std::sync::LazyLock::new(Default::default);
+
+/// How often pf rules are updated for the ports a limited process opened since
+const NETWORK_PORT_REFRESH: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// dummynet pipe carrying a process's traffic
+fn network_pipe(pid: u32) -> u32 {
+    1000 + pid % 60000
+}
+
+/// pf anchor with a process's rules; `/etc/pf.conf` loads dummynet rules from `com.apple/*`
+fn network_anchor(pid: u32) -> String {
+    format!("com.apple/multiinstance-{}", pid)
+}
+
+/// Send a process's traffic through a dummynet pipe of `kbps` KB/s. pf cannot
+/// match a process, so the rules cover its local ports and follow new sockets.
+pub fn set_network_limit(pid: u32, kbps: u64) -> Result<()> {
+    if unsafe { libc::geteuid() } != 0 {
+        anyhow::bail!("Network limits on macOS need MultiInstance to run as root");
+    }
+
+    let output = Command::new("dnctl")
+        .args(["pipe", &network_pipe(pid).to_string(), "config", "bw"])
+        .arg(format!("{}KByte/s", kbps))
+        .output()
+        .context("Failed to run dnctl")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "dnctl failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    // Rules only apply while pf is enabled; this fails harmlessly if it already is
+    let _ = Command::new("pfctl").arg("-E").output();
+    load_network_rules(pid)?;
+
+    let first = NETWORK_LIMITED
+        .lock()
+        .map(|mut limited| limited.insert(pid))
+        .unwrap_or(false);
+    if first {
+        std::thread::spawn(move || loop {
+            std::thread::sleep(NETWORK_PORT_REFRESH);
+            let limited = NETWORK_LIMITED
+                .lock()
+                .map(|l| l.contains(&pid))
+                .unwrap_or(false);
+            if !limited {
+                return;
+            }
+            if !is_process_running(pid) {
+                let _ = clear_network_limit(pid);
+                return;
+            }
+            if let Err(e) = load_network_rules(pid) {
+                debug!("Failed to update network rules of PID {}: {}", pid, e);
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Remove a process's pf rules and dummynet pipe
+pub fn clear_network_limit(pid: u32) -> Result<()> {
+    if let Ok(mut limited) = NETWORK_LIMITED.lock() {
+        limited.remove(&pid);
+    }
+    let _ = Command::new("pfctl")
+        .args(["-a", &network_anchor(pid), "-F", "all"])
+        .output();
+    Command::new("dnctl")
+        .args(["pipe", "delete", &network_pipe(pid).to_string()])
+        .output()
+        .context("Failed to run dnctl")?;
+    Ok(())
+}
+
+/// Replace the pf rules sending a process's ports through its pipe
+fn load_network_rules(pid: u32) -> Result<()> {
+    use std::io::Write;
+
+    let ports = local_ports(pid)?;
+    let rules = if ports.is_empty() {
+        String::new()
+    } else {
+        let ports: Vec<String> = ports.iter().map(u16::to_string).collect();
+        let ports = ports.join(" ");
+        let pipe = network_pipe(pid);
+        format!(
+            "dummynet out proto {{ tcp udp }} from any port {{ {ports} }} to any pipe {pipe}\n\
+             dummynet in proto {{ tcp udp }} from any to any port {{ {ports} }} pipe {pipe}\n"
+        )
+    };
+
+    let mut child = Command::new("pfctl")
+        .args(["-a", &network_anchor(pid), "-f", "-"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .context("Failed to run pfctl")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(rules.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "pfctl failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Local TCP and UDP ports a process has open, from lsof
+fn local_ports(pid: u32) -> Result<Vec<u16>> {
+    let output = Command::new("lsof")
+        .args(["-a", "-p", &pid.to_string(), "-i", "-n", "-P", "-Fn"])
+        .output()
+        .context("Failed to run lsof")?;
+
+    let mut ports: Vec<u16> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.strip_prefix('n'))
+        .filter_map(|name| {
+            let local = name.split("->").next()?;
+            local.rsplit_once(':')?.1.parse().ok()
+        })
+        .collect();
+    ports.sort_unstable();
+    ports.dedup();
+    Ok(ports)
+}
+
 /// Get file locks held by a process using lsof
 pub fn get_process_locks(pid: u32) -> Result<Vec<PathBuf>> {
     let output = Command::new("lsof")
@@ -168,6 +382,405 @@ pub fn get_process_locks(pid: u32) -> Result<Vec<PathBuf>> {
     Ok(locks)
 }
 
+/// Open file descriptors of a process, from lsof's type and name fields
+pub fn open_handles(pid: u32) -> Result<Vec<OpenHandle>> {
+    let output = Command::new("lsof")
+        .args(["-p", &pid.to_string(), "-Ftn"])
+        .output()
+        .context("Failed to run lsof")?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut handles = Vec::new();
+    let mut numbered = false;
+    let mut kind = HandleKind::Other;
+
+    // Each descriptor is an `f` line followed by its `t` and `n` fields
+    for line in stdout.lines() {
+        let (field, value) = line.split_at(line.len().min(1));
+        match field {
+            "f" => numbered = value.parse::<u32>().is_ok(),
+            "t" => {
+                kind = match value {
+                    "REG" => HandleKind::File,
+                    "DIR" => HandleKind::Directory,
+                    "IPv4" | "IPv6" | "unix" | "sock" | "systm" => HandleKind::Socket,
+                    "PIPE" | "FIFO" => HandleKind::Pipe,
+                    _ => HandleKind::Other,
+                }
+            }
+            // Memory-mapped libraries and the cwd have no descriptor number
+            "n" if numbered => handles.push(OpenHandle {
+                kind,
+                name: value.to_string(),
+            }),
+            _ => {}
+        }
+    }
+
+    if handles.is_empty() && !output.status.success() {
+        anyhow::bail!(
+            "lsof failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(handles)
+}
+
+/// TCP and UDP flows of a process from one `nettop` sample, with byte counters
+pub fn connections(pid: u32) -> Result<Vec<Connection>> {
+    let output = Command::new("nettop")
+        .args(["-L", "1", "-n", "-x", "-p", &pid.to_string()])
+        .output()
+        .context("Failed to run nettop")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "nettop failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    let header: Vec<&str> = lines.next().unwrap_or_default().split(',').collect();
+    let column = |name: &str| header.iter().position(|h| *h == name);
+    let (state_col, in_col, out_col) = (column("state"), column("bytes_in"), column("bytes_out"));
+
+    let mut connections = Vec::new();
+    for line in lines {
+        let fields: Vec<&str> = line.split(',').collect();
+        // Flow rows look like `tcp4 10.0.0.2:50123<->1.2.3.4:443`; process rows are `name.pid`
+        let Some((kind, endpoints)) = fields.get(1).and_then(|f| f.split_once(' ')) else {
+            continue;
+        };
+        let protocol = match kind {
+            "tcp4" => "TCP",
+            "tcp6" => "TCP6",
+            "udp4" => "UDP",
+            "udp6" => "UDP6",
+            _ => continue,
+        };
+        let (local, remote) = endpoints.split_once("<->").unwrap_or((endpoints, "*:*"));
+        let field = |col: Option<usize>| col.and_then(|c| fields.get(c)).copied();
+
+        connections.push(Connection {
+            protocol,
+            local: local.to_string(),
+            remote: (remote != "*:*" && remote != "*.*").then(|| remote.to_string()),
+            state: field(state_col).unwrap_or_default().to_string(),
+            bytes_in: field(in_col).and_then(|v| v.parse().ok()),
+            bytes_out: field(out_col).and_then(|v| v.parse().ok()),
+            rx_rate: None,
+            tx_rate: None,
+        });
+    }
+    Ok(connections)
+}
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGWindowListCopyWindowInfo(
+        option: u32,
+        relative_to_window: u32,
+    ) -> core_foundation::array::CFArrayRef;
+}
+
+/// Frontmost on-screen window of a process at the normal window layer
+fn main_window(pid: u32) -> Option<i64> {
+    use core_foundation::array::CFArray;
+    use core_foundation::base::{CFType, TCFType};
+    use core_foundation::dictionary::CFDictionary;
+    use core_foundation::number::CFNumber;
+    use core_foundation::string::CFString;
+
+    fn number(window: &CFDictionary<CFString, CFType>, key: &'static str) -> Option<i64> {
+        window
+            .find(&CFString::from_static_string(key))?
+            .downcast::<CFNumber>()?
+            .to_i64()
+    }
+
+    // kCGWindowListOptionOnScreenOnly | kCGWindowListExcludeDesktopElements
+    let list = unsafe { CGWindowListCopyWindowInfo(1 | 16, 0) };
+    if list.is_null() {
+        return None;
+    }
+    let windows: CFArray<CFDictionary<CFString, CFType>> =
+        unsafe { CFArray::wrap_under_create_rule(list) };
+    let found = windows
+        .iter()
+        .filter(|w| number(w, "kCGWindowOwnerPID") == Some(pid as i64))
+        .filter(|w| number(w, "kCGWindowLayer") == Some(0))
+        .find_map(|w| number(&w, "kCGWindowNumber"));
+    found
+}
+
+/// Capture a process's window with `screencapture`, which needs Screen Recording permission
+pub fn capture_window(pid: u32) -> Result<image::RgbaImage> {
+    let window = main_window(pid).context("Instance has no visible window")?;
+    let path = std::env::temp_dir().join(format!("multiinstance-capture-{}.png", pid));
+    let output = Command::new("screencapture")
+        .args(["-x", "-o", &format!("-l{}", window)])
+        .arg(&path)
+        .output()
+        .context("Failed to run screencapture")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "screencapture failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let image = image::open(&path);
+    let _ = std::fs::remove_file(&path);
+    Ok(image?.to_rgba8())
+}
+
+/// Icon Finder shows for a file or app bundle, from NSWorkspace
+pub fn executable_icon(path: &std::path::Path) -> Result<image::RgbaImage> {
+    use objc::runtime::Object;
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::os::unix::ffi::OsStrExt;
+
+    /// NSBitmapImageFileTypePNG
+    const PNG_FILE_TYPE: u64 = 4;
+
+    let path_c = std::ffi::CString::new(path.as_os_str().as_bytes())?;
+    let png = objc::rc::autoreleasepool(|| unsafe {
+        let path: *mut Object = msg_send![class!(NSString), stringWithUTF8String: path_c.as_ptr()];
+        let workspace: *mut Object = msg_send![class!(NSWorkspace), sharedWorkspace];
+        let image: *mut Object = msg_send![workspace, iconForFile: path];
+        if image.is_null() {
+            return None;
+        }
+        let tiff: *mut Object = msg_send![image, TIFFRepresentation];
+        if tiff.is_null() {
+            return None;
+        }
+        let bitmap: *mut Object = msg_send![class!(NSBitmapImageRep), imageRepWithData: tiff];
+        if bitmap.is_null() {
+            return None;
+        }
+        let properties: *mut Object = msg_send![class!(NSDictionary), dictionary];
+        let data: *mut Object =
+            msg_send![bitmap, representationUsingType: PNG_FILE_TYPE properties: properties];
+        if data.is_null() {
+            return None;
+        }
+        let bytes: *const u8 = msg_send![data, bytes];
+        let length: usize = msg_send![data, length];
+        Some(std::slice::from_raw_parts(bytes, length).to_vec())
+    })
+    .with_context(|| format!("No icon found for {}", path.display()))?;
+    Ok(image::load_from_memory(&png)?.to_rgba8())
+}
+
+/// Process ID of the frontmost application
+pub fn foreground_window() -> Option<u64> {
+    objc::rc::autoreleasepool(|| unsafe { frontmost_pid() }).map(|pid| pid as u64)
+}
+
+/// Reactivate the previous application (`previous` is its process ID)
+/// whenever the instance makes itself frontmost
+pub fn keep_focus_from(pid: u32, previous: u64) -> Result<()> {
+    use objc::runtime::Object;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    /// NSApplicationActivateIgnoringOtherApps
+    const ACTIVATE_IGNORING_OTHER_APPS: u64 = 1 << 1;
+
+    let deadline = std::time::Instant::now() + super::FOCUS_GUARD;
+    while std::time::Instant::now() < deadline {
+        objc::rc::autoreleasepool(|| unsafe {
+            if frontmost_pid() == Some(pid as i32) {
+                let app: *mut Object = msg_send![
+                    class!(NSRunningApplication),
+                    runningApplicationWithProcessIdentifier: previous as i32
+                ];
+                if !app.is_null() {
+                    let _: objc::runtime::BOOL =
+                        msg_send![app, activateWithOptions: ACTIVATE_IGNORING_OTHER_APPS];
+                    debug!("Returned focus taken by PID {}", pid);
+                }
+            }
+        });
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    Ok(())
+}
+
+unsafe fn frontmost_pid() -> Option<i32> {
+    use objc::runtime::Object;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    let workspace: *mut Object = msg_send![class!(NSWorkspace), sharedWorkspace];
+    let app: *mut Object = msg_send![workspace, frontmostApplication];
+    if app.is_null() {
+        return None;
+    }
+    let pid: i32 = msg_send![app, processIdentifier];
+    Some(pid)
+}
+
+/// Run an AppleScript, which needs Accessibility permission for System Events
+fn osascript(script: &str) -> Result<()> {
+    let output = Command::new("osascript")
+        .args(["-e", script])
+        .output()
+        .context("Failed to run osascript")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "osascript failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Quote a string for AppleScript
+fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+pub fn focus_window(pid: u32) -> Result<()> {
+    osascript(&format!(
+        "tell application \"System Events\" to set frontmost of \
+         (first process whose unix id is {}) to true",
+        pid
+    ))
+}
+
+pub fn apply_window_state(pid: u32, state: crate::core::WindowState) -> Result<()> {
+    use crate::core::WindowState;
+
+    let deadline = std::time::Instant::now() + super::WINDOW_WAIT;
+    while main_window(pid).is_none() {
+        if std::time::Instant::now() >= deadline {
+            anyhow::bail!("No window appeared within {:?}", super::WINDOW_WAIT);
+        }
+        std::thread::sleep(std::time::Duration::from_millis(250));
+    }
+    match state {
+        WindowState::Normal => Ok(()),
+        WindowState::Minimized => osascript(&format!(
+            "tell application \"System Events\" to set value of attribute \"AXMinimized\" \
+             of windows of (first process whose unix id is {}) to true",
+            pid
+        )),
+        // macOS hides whole applications rather than single windows
+        WindowState::Hidden => osascript(&format!(
+            "tell application \"System Events\" to set visible of \
+             (first process whose unix id is {}) to false",
+            pid
+        )),
+    }
+}
+
+pub fn show_window(pid: u32) -> Result<()> {
+    osascript(&format!(
+        "tell application \"System Events\"\n\
+         set target to first process whose unix id is {}\n\
+         set visible of target to true\n\
+         set value of attribute \"AXMinimized\" of windows of target to false\n\
+         end tell",
+        pid
+    ))?;
+    focus_window(pid)
+}
+
+/// Crash reports on macOS appear after the process has exited, so no
+/// window listing is needed to let a crashed instance restart
+pub fn process_windows(_pid: u32) -> Result<Vec<(u64, String)>> {
+    Ok(Vec::new())
+}
+
+pub fn close_window(_window: u64) -> Result<()> {
+    anyhow::bail!("Closing windows is not supported on macOS")
+}
+
+/// Virtual key code for keys that `keystroke` cannot type
+fn key_code(key: &str) -> Option<u16> {
+    Some(match key {
+        "Enter" => 36,
+        "Tab" => 48,
+        "Space" => 49,
+        "Backspace" => 51,
+        "Escape" => 53,
+        "Home" => 115,
+        "PageUp" => 116,
+        "Delete" => 117,
+        "End" => 119,
+        "PageDown" => 121,
+        "ArrowLeft" => 123,
+        "ArrowRight" => 124,
+        "ArrowDown" => 125,
+        "ArrowUp" => 126,
+        "F1" => 122,
+        "F2" => 120,
+        "F3" => 99,
+        "F4" => 118,
+        "F5" => 96,
+        "F6" => 97,
+        "F7" => 98,
+        "F8" => 100,
+        "F9" => 101,
+        "F10" => 109,
+        "F11" => 103,
+        "F12" => 111,
+        _ => return None,
+    })
+}
+
+/// Inject input into a process's frontmost window through System Events
+pub fn send_input(pid: u32, event: &crate::core::macros::InputEvent) -> Result<()> {
+    use crate::core::macros::{InputEvent, MouseButton};
+
+    let process = format!("first process whose unix id is {}", pid);
+    let script = match event {
+        InputEvent::Key { key, modifiers } => {
+            let held: Vec<&str> = [
+                (modifiers.ctrl, "control down"),
+                (modifiers.shift, "shift down"),
+                (modifiers.alt, "option down"),
+                (modifiers.command, "command down"),
+            ]
+            .iter()
+            .filter(|(on, _)| *on)
+            .map(|(_, name)| *name)
+            .collect();
+            let using = if held.is_empty() {
+                String::new()
+            } else {
+                format!(" using {{{}}}", held.join(", "))
+            };
+            let press = match key_code(key) {
+                Some(code) => format!("key code {}", code),
+                None => format!("keystroke {}", applescript_string(&key.to_lowercase())),
+            };
+            format!(
+                "tell application \"System Events\" to tell ({}) to {}{}",
+                process, press, using
+            )
+        }
+        InputEvent::Text(text) => format!(
+            "tell application \"System Events\" to tell ({}) to keystroke {}",
+            process,
+            applescript_string(text)
+        ),
+        InputEvent::Click { x, y, button } => {
+            if *button != MouseButton::Left {
+                anyhow::bail!("Only left clicks can be sent on macOS");
+            }
+            format!(
+                "tell application \"System Events\" to tell ({})\n\
+                 set {{wx, wy}} to position of window 1\n\
+                 click at {{wx + {}, wy + {}}}\n\
+                 end tell",
+                process, x, y
+            )
+        }
+    };
+    osascript(&script)
+}
+
 /// Check if an application bundle has a single-instance lock file
 pub fn find_app_lock_file(app_path: &std::path::Path) -> Option<PathBuf> {
     // Common locations for app lock files on macOS
@@ -361,3 +974,46 @@ pub fn remove_launch_agent(app_name: &str) -> Result<()> {
 
     Ok(())
 }
+
+/// Check whether an installer placed a machine-wide launch agent in /Library/LaunchAgents
+pub fn machine_autostart_registered(app_name: &str) -> bool {
+    PathBuf::from("/Library/LaunchAgents")
+        .join(format!(
+            "com.multiinstance.{}.plist",
+            app_name.to_lowercase()
+        ))
+        .exists()
+}
+
+/// Notification Center banner posted through AppleScript
+pub fn show_notification(title: &str, body: &str) -> Result<()> {
+    osascript(&format!(
+        "display notification {} with title {}",
+        applescript_string(body),
+        applescript_string(title)
+    ))
+}
+
+/// Play the system "Sosumi" sound
+pub fn play_alert_sound() -> Result<()> {
+    let status = Command::new("afplay")
+        .arg("/System/Library/Sounds/Sosumi.aiff")
+        .status()
+        .context("Failed to run afplay")?;
+    if !status.success() {
+        anyhow::bail!("afplay failed with {}", status);
+    }
+    Ok(())
+}
+
+/// SSID of the connected Wi-Fi network on the primary wireless interface
+pub fn current_wifi_ssid() -> Option<String> {
+    let output = Command::new("networksetup")
+        .args(["-getairportnetwork", "en0"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .strip_prefix("Current Wi-Fi Network: ")
+        .map(str::to_string)
+}
@@ -1,4 +1,4 @@
-//! Platform-specific implementations for Windows and macOS
+//! Platform-specific implementations for Windows, macOS and Linux
 
 #[cfg(windows)]
 pub mod windows;
@@ -6,6 +6,9 @@ pub mod windows;
 #[cfg(target_os = "macos")]
 pub mod macos;
 
+#[cfg(target_os = "linux")]
+pub mod linux;
+
 use anyhow::Result;
 
 /// Terminate a process gracefully
@@ -18,7 +21,11 @@ pub fn terminate_process(pid: u32) -> Result<()> {
     {
         macos::terminate_process(pid)
     }
-    #[cfg(not(any(windows, target_os = "macos")))]
+    #[cfg(target_os = "linux")]
+    {
+        linux::terminate_process(pid)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
     {
         let _ = pid;
         anyhow::bail!("Unsupported platform")
@@ -35,7 +42,11 @@ pub fn kill_process(pid: u32) -> Result<()> {
     {
         macos::kill_process(pid)
     }
-    #[cfg(not(any(windows, target_os = "macos")))]
+    #[cfg(target_os = "linux")]
+    {
+        linux::kill_process(pid)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
     {
         let _ = pid;
         anyhow::bail!("Unsupported platform")
@@ -52,7 +63,11 @@ pub fn suspend_process(pid: u32) -> Result<()> {
     {
         macos::suspend_process(pid)
     }
-    #[cfg(not(any(windows, target_os = "macos")))]
+    #[cfg(target_os = "linux")]
+    {
+        linux::suspend_process(pid)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
     {
         let _ = pid;
         anyhow::bail!("Unsupported platform")
@@ -69,7 +84,11 @@ pub fn resume_process(pid: u32) -> Result<()> {
     {
         macos::resume_process(pid)
     }
-    #[cfg(not(any(windows, target_os = "macos")))]
+    #[cfg(target_os = "linux")]
+    {
+        linux::resume_process(pid)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
     {
         let _ = pid;
         anyhow::bail!("Unsupported platform")
@@ -86,13 +105,179 @@ pub fn is_process_running(pid: u32) -> bool {
     {
         macos::is_process_running(pid)
     }
-    #[cfg(not(any(windows, target_os = "macos")))]
+    #[cfg(target_os = "linux")]
+    {
+        linux::is_process_running(pid)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
     {
         let _ = pid;
         false
     }
 }
 
+/// Pointer width of a running process (32 or 64)
+pub fn process_bitness(pid: u32) -> Option<u32> {
+    #[cfg(windows)]
+    {
+        windows::process_bitness(pid).ok()
+    }
+    #[cfg(target_os = "macos")]
+    {
+        // macOS has not run 32-bit processes since Catalina
+        let _ = pid;
+        Some(64)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::process_bitness(pid)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+/// Cap a process's CPU time to `percent` of the whole machine in the kernel;
+/// 0 removes the cap. Fails where the OS offers no such cap.
+pub fn set_cpu_rate(pid: u32, percent: u8) -> Result<()> {
+    #[cfg(windows)]
+    {
+        windows::set_cpu_rate(pid, percent)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let _ = (pid, percent);
+        anyhow::bail!("macOS has no per-process CPU cap")
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::set_cpu_rate(pid, percent)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        let _ = (pid, percent);
+        anyhow::bail!("Unsupported platform")
+    }
+}
+
+/// Set the priority of a process's disk reads and writes
+pub fn set_io_priority(pid: u32, priority: crate::core::IoPriority) -> Result<()> {
+    #[cfg(windows)]
+    {
+        windows::set_io_priority(pid, priority)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let _ = (pid, priority);
+        anyhow::bail!("macOS sets disk I/O priority only at launch")
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::set_io_priority(pid, priority)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        let _ = (pid, priority);
+        anyhow::bail!("Unsupported platform")
+    }
+}
+
+/// Cap a process's disk throughput at `mbps` MB/s for reads and writes each
+pub fn set_disk_rate(pid: u32, mbps: u64) -> Result<()> {
+    #[cfg(windows)]
+    {
+        windows::set_disk_rate(pid, mbps)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let _ = (pid, mbps);
+        anyhow::bail!("macOS has no per-process disk throughput cap")
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::set_disk_rate(pid, mbps)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        let _ = (pid, mbps);
+        anyhow::bail!("Unsupported platform")
+    }
+}
+
+/// Cap a process's network traffic at `kbps` KB/s; needs administrator rights
+pub fn set_network_limit(pid: u32, executable: &std::path::Path, kbps: u64) -> Result<()> {
+    #[cfg(windows)]
+    {
+        windows::set_network_limit(pid, executable, kbps)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let _ = executable;
+        macos::set_network_limit(pid, kbps)
+    }
+    #[cfg(not(any(windows, target_os = "macos")))]
+    {
+        let _ = (pid, executable, kbps);
+        anyhow::bail!("Network limits are not supported on this platform")
+    }
+}
+
+/// Remove the network limit set by `set_network_limit`
+pub fn clear_network_limit(pid: u32) -> Result<()> {
+    #[cfg(windows)]
+    {
+        windows::clear_network_limit(pid)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::clear_network_limit(pid)
+    }
+    #[cfg(not(any(windows, target_os = "macos")))]
+    {
+        let _ = pid;
+        Ok(())
+    }
+}
+
+/// Total CPU time (user + kernel) a process has used since it started
+pub fn process_cpu_time(pid: u32) -> Option<std::time::Duration> {
+    #[cfg(windows)]
+    {
+        windows::process_cpu_time(pid).ok()
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::process_cpu_time(pid)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::process_cpu_time(pid)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+/// Logical processors as (index, physical core, efficiency class); higher classes are faster
+pub fn cpu_efficiency_classes() -> Option<Vec<(usize, usize, u8)>> {
+    #[cfg(windows)]
+    {
+        windows::cpu_efficiency_classes().ok()
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::cpu_efficiency_classes()
+    }
+    #[cfg(not(any(windows, target_os = "linux")))]
+    {
+        None
+    }
+}
+
 /// Set CPU affinity for a process
 pub fn set_cpu_affinity(pid: u32, cores: &[usize]) -> Result<()> {
     #[cfg(windows)]
@@ -103,7 +288,11 @@ pub fn set_cpu_affinity(pid: u32, cores: &[usize]) -> Result<()> {
     {
         macos::set_cpu_affinity(pid, cores)
     }
-    #[cfg(not(any(windows, target_os = "macos")))]
+    #[cfg(target_os = "linux")]
+    {
+        linux::set_cpu_affinity(pid, cores)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
     {
         let _ = (pid, cores);
         anyhow::bail!("Unsupported platform")
@@ -120,13 +309,621 @@ pub fn set_process_priority(pid: u32, priority: i8) -> Result<()> {
     {
         macos::set_process_priority(pid, priority)
     }
-    #[cfg(not(any(windows, target_os = "macos")))]
+    #[cfg(target_os = "linux")]
+    {
+        linux::set_process_priority(pid, priority)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
     {
         let _ = (pid, priority);
         anyhow::bail!("Unsupported platform")
     }
 }
 
+/// Write a process to disk so it can be restored later (experimental)
+pub fn checkpoint_process(pid: u32, images_dir: &std::path::Path) -> Result<()> {
+    #[cfg(target_os = "linux")]
+    {
+        linux::checkpoint_process(pid, images_dir)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (pid, images_dir);
+        anyhow::bail!(
+            "Checkpointing to disk requires CRIU and is only available on Linux; \
+             use Pause to freeze an instance in memory instead"
+        )
+    }
+}
+
+/// Restore a process from a checkpoint, returning the new PID (experimental)
+pub fn restore_process(images_dir: &std::path::Path) -> Result<u32> {
+    #[cfg(target_os = "linux")]
+    {
+        linux::restore_process(images_dir)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = images_dir;
+        anyhow::bail!("Restoring checkpoints requires CRIU and is only available on Linux")
+    }
+}
+
+/// Launch a process inside an AppContainer, returning its PID. `writable` paths
+/// are granted full access and `readable` ones read and execute access.
+pub fn spawn_in_app_container(
+    container: &str,
+    command: &[String],
+    working_dir: &std::path::Path,
+    environment: &[(String, String)],
+    capabilities: &[&str],
+    writable: &[std::path::PathBuf],
+    readable: &[std::path::PathBuf],
+) -> Result<u32> {
+    #[cfg(windows)]
+    {
+        windows::spawn_in_app_container(
+            container,
+            command,
+            working_dir,
+            environment,
+            capabilities,
+            writable,
+            readable,
+        )
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = (
+            container,
+            command,
+            working_dir,
+            environment,
+            capabilities,
+            writable,
+            readable,
+        );
+        anyhow::bail!("AppContainer isolation is only available on Windows")
+    }
+}
+
+/// Remove an instance's AppContainer profile
+pub fn delete_app_container(container: &str) -> Result<()> {
+    #[cfg(windows)]
+    {
+        windows::delete_app_container(container)
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = container;
+        Ok(())
+    }
+}
+
+/// Activate a Store app by its AppUserModelID, returning the app's PID
+pub fn launch_packaged_app(app_id: &str, arguments: &str) -> Result<u32> {
+    #[cfg(windows)]
+    {
+        windows::launch_packaged_app(app_id, arguments)
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = (app_id, arguments);
+        anyhow::bail!("Store apps are only available on Windows")
+    }
+}
+
+/// Installed Store apps
+pub fn list_packaged_apps() -> Result<Vec<crate::core::packaged_apps::PackagedApp>> {
+    #[cfg(windows)]
+    {
+        windows::list_packaged_apps()
+    }
+    #[cfg(not(windows))]
+    {
+        Ok(Vec::new())
+    }
+}
+
+/// Crash dumps and error reports Windows Error Reporting wrote for a process
+/// since `since`
+pub fn crash_dumps(
+    executable: &std::path::Path,
+    pid: u32,
+    since: std::time::SystemTime,
+) -> Vec<std::path::PathBuf> {
+    #[cfg(windows)]
+    {
+        windows::crash_dumps(executable, pid, since)
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = (executable, pid, since);
+        Vec::new()
+    }
+}
+
+/// Close OS handles kept for processes that have exited, returning how many
+pub fn prune_job_handles() -> usize {
+    #[cfg(windows)]
+    {
+        windows::prune_job_handles()
+    }
+    #[cfg(not(windows))]
+    {
+        0
+    }
+}
+
+/// Number of OS handles kept for launched processes
+pub fn job_handle_count() -> usize {
+    #[cfg(windows)]
+    {
+        windows::job_handle_count()
+    }
+    #[cfg(not(windows))]
+    {
+        0
+    }
+}
+
+/// Show a desktop notification
+pub fn show_notification(title: &str, body: &str) -> Result<()> {
+    #[cfg(windows)]
+    {
+        windows::show_notification(title, body)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::show_notification(title, body)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::show_notification(title, body)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        let _ = (title, body);
+        anyhow::bail!("Unsupported platform")
+    }
+}
+
+/// Play the system alert sound
+pub fn play_alert_sound() -> Result<()> {
+    #[cfg(windows)]
+    {
+        windows::play_alert_sound()
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::play_alert_sound()
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::play_alert_sound()
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        anyhow::bail!("Unsupported platform")
+    }
+}
+
+/// Start MultiInstance when the current user logs in
+pub fn set_autostart(app_name: &str, executable_path: &str, enabled: bool) -> Result<()> {
+    #[cfg(windows)]
+    {
+        windows::set_autostart(app_name, executable_path, enabled)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        if enabled {
+            macos::setup_launch_agent(app_name, executable_path).map(|_| ())
+        } else {
+            macos::remove_launch_agent(app_name)
+        }
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::set_autostart(app_name, executable_path, enabled)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        let _ = (app_name, executable_path, enabled);
+        anyhow::bail!("Unsupported platform")
+    }
+}
+
+/// Check whether an installer already starts MultiInstance for every user
+pub fn machine_autostart_registered(app_name: &str) -> bool {
+    #[cfg(windows)]
+    {
+        windows::machine_autostart_registered(app_name)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::machine_autostart_registered(app_name)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::machine_autostart_registered(app_name)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        let _ = app_name;
+        false
+    }
+}
+
+/// SSID of the Wi-Fi network the machine is connected to, if any
+pub fn current_wifi_ssid() -> Option<String> {
+    #[cfg(windows)]
+    {
+        windows::current_wifi_ssid()
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::current_wifi_ssid()
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::current_wifi_ssid()
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        None
+    }
+}
+
+/// Files, sockets and pipes a process holds open
+pub fn open_handles(pid: u32) -> Result<Vec<crate::core::inspect::OpenHandle>> {
+    #[cfg(windows)]
+    {
+        windows::open_handles(pid)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::open_handles(pid)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::open_handles(pid)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        let _ = pid;
+        anyhow::bail!("Unsupported platform")
+    }
+}
+
+/// TCP and UDP sockets owned by a process
+pub fn connections(pid: u32) -> Result<Vec<crate::core::inspect::Connection>> {
+    #[cfg(windows)]
+    {
+        windows::connections(pid)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::connections(pid)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::connections(pid)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        let _ = pid;
+        anyhow::bail!("Unsupported platform")
+    }
+}
+
+/// Capture the main window of a process as it currently looks
+pub fn capture_window(pid: u32) -> Result<image::RgbaImage> {
+    #[cfg(windows)]
+    {
+        windows::capture_window(pid)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::capture_window(pid)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::capture_window(pid)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        let _ = pid;
+        anyhow::bail!("Unsupported platform")
+    }
+}
+
+/// Icon the shell shows for an executable or app bundle
+pub fn executable_icon(path: &std::path::Path) -> Result<image::RgbaImage> {
+    #[cfg(windows)]
+    {
+        windows::executable_icon(path)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::executable_icon(path)
+    }
+    #[cfg(not(any(windows, target_os = "macos")))]
+    {
+        let _ = path;
+        anyhow::bail!("Executable icons are only available on Windows and macOS")
+    }
+}
+
+/// Bring a process's main window to the front so it receives injected input
+pub fn focus_window(pid: u32) -> Result<()> {
+    #[cfg(windows)]
+    {
+        windows::focus_window(pid)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::focus_window(pid)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::focus_window(pid)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        let _ = pid;
+        anyhow::bail!("Unsupported platform")
+    }
+}
+
+/// How long after a background launch focus is handed back if the instance takes it
+pub const FOCUS_GUARD: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Window (or, on macOS, application) that has keyboard focus right now
+pub fn foreground_window() -> Option<u64> {
+    #[cfg(windows)]
+    {
+        windows::foreground_window()
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::foreground_window()
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::foreground_window()
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        None
+    }
+}
+
+/// Hand focus back to `previous` whenever a window of `pid` takes it, until
+/// `FOCUS_GUARD` has passed. Blocks, so run it on its own thread.
+pub fn keep_focus_from(pid: u32, previous: u64) -> Result<()> {
+    #[cfg(windows)]
+    {
+        windows::keep_focus_from(pid, previous)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::keep_focus_from(pid, previous)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::keep_focus_from(pid, previous)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        let _ = (pid, previous);
+        anyhow::bail!("Unsupported platform")
+    }
+}
+
+/// How long to wait for a new instance's main window before giving up on its start state
+pub const WINDOW_WAIT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Wait for a process's main window to appear, then minimize or hide it.
+/// Blocks, so run it on its own thread.
+pub fn apply_window_state(pid: u32, state: crate::core::WindowState) -> Result<()> {
+    if state == crate::core::WindowState::Normal {
+        return Ok(());
+    }
+    #[cfg(windows)]
+    {
+        windows::apply_window_state(pid, state)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::apply_window_state(pid, state)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::apply_window_state(pid, state)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        let _ = pid;
+        anyhow::bail!("Unsupported platform")
+    }
+}
+
+/// Virtual desktops in task view order
+pub fn virtual_desktops() -> Result<Vec<crate::core::virtual_desktop::VirtualDesktop>> {
+    #[cfg(windows)]
+    {
+        windows::virtual_desktops()
+    }
+    #[cfg(not(windows))]
+    {
+        Ok(Vec::new())
+    }
+}
+
+/// Wait for a process's main window to appear, then move its windows to a
+/// virtual desktop. Blocks, so run it on its own thread.
+pub fn move_to_virtual_desktop(pid: u32, desktop_id: &str) -> Result<()> {
+    #[cfg(windows)]
+    {
+        windows::move_to_virtual_desktop(pid, desktop_id)
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = (pid, desktop_id);
+        anyhow::bail!("Virtual desktops are only available on Windows")
+    }
+}
+
+/// Restore a process's minimized or hidden window and bring it to the front
+pub fn show_window(pid: u32) -> Result<()> {
+    #[cfg(windows)]
+    {
+        windows::show_window(pid)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::show_window(pid)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::show_window(pid)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        let _ = pid;
+        anyhow::bail!("Unsupported platform")
+    }
+}
+
+/// Visible top-level windows of a process, with their titles
+pub fn process_windows(pid: u32) -> Result<Vec<(u64, String)>> {
+    #[cfg(windows)]
+    {
+        windows::process_windows(pid)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::process_windows(pid)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::process_windows(pid)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        let _ = pid;
+        anyhow::bail!("Unsupported platform")
+    }
+}
+
+/// Ask a window from `process_windows` to close, as its close button would
+pub fn close_window(window: u64) -> Result<()> {
+    #[cfg(windows)]
+    {
+        windows::close_window(window)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::close_window(window)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::close_window(window)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        let _ = window;
+        anyhow::bail!("Unsupported platform")
+    }
+}
+
+/// Mirror input from the focused window of one of `targets` to the windows of
+/// the others, replacing the previous targets; None stops mirroring
+pub fn broadcast_input(targets: Option<&crate::core::broadcast::BroadcastTargets>) -> Result<()> {
+    #[cfg(windows)]
+    {
+        windows::broadcast_input(targets)
+    }
+    #[cfg(not(windows))]
+    {
+        if targets.is_some() {
+            anyhow::bail!("Input broadcasting is only available on Windows");
+        }
+        Ok(())
+    }
+}
+
+/// Change the title of a window from `process_windows`
+pub fn set_window_title(window: u64, title: &str) -> Result<()> {
+    #[cfg(windows)]
+    {
+        windows::set_window_title(window, title)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::set_window_title(window, title)
+    }
+    #[cfg(not(any(windows, target_os = "linux")))]
+    {
+        let _ = (window, title);
+        anyhow::bail!("Renaming windows is only available on Windows and Linux")
+    }
+}
+
+/// Usable area of the primary screen, without the taskbar
+pub fn work_area() -> Result<crate::core::window_layout::WindowRect> {
+    #[cfg(windows)]
+    {
+        windows::work_area()
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::work_area()
+    }
+    #[cfg(not(any(windows, target_os = "linux")))]
+    {
+        anyhow::bail!("Arranging windows is only available on Windows and Linux")
+    }
+}
+
+/// Restore a window from `process_windows` and move it to `rect`
+pub fn set_window_bounds(window: u64, rect: crate::core::window_layout::WindowRect) -> Result<()> {
+    #[cfg(windows)]
+    {
+        windows::set_window_bounds(window, rect)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::set_window_bounds(window, rect)
+    }
+    #[cfg(not(any(windows, target_os = "linux")))]
+    {
+        let _ = (window, rect);
+        anyhow::bail!("Arranging windows is only available on Windows and Linux")
+    }
+}
+
+/// Send a key press, typed text or click to a process's main window
+pub fn send_input(pid: u32, event: &crate::core::macros::InputEvent) -> Result<()> {
+    #[cfg(windows)]
+    {
+        windows::send_input(pid, event)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        macos::send_input(pid, event)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux::send_input(pid, event)
+    }
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    {
+        let _ = (pid, event);
+        anyhow::bail!("Unsupported platform")
+    }
+}
+
 /// Release/manipulate mutex to allow multiple instances (Windows-specific)
 #[cfg(windows)]
 pub fn release_app_mutex(process_name: &str) -> Result<()> {
@@ -10,7 +10,7 @@ use tracing::{debug, info, warn};
 
 use windows::Win32::Foundation::{
     CloseHandle, DuplicateHandle, BOOL, DUPLICATE_CLOSE_SOURCE, DUPLICATE_SAME_ACCESS, FALSE,
-    HANDLE, HWND,
+    HANDLE, HWND, TRUE,
 };
 use windows::Win32::System::JobObjects::*;
 use windows::Win32::System::ProcessStatus::*;
@@ -44,6 +44,41 @@ pub fn cleanup_job_handle(pid: u32) {
     }
 }
 
+/// Close the job handles of jobs with no process left, such as those of
+/// processes that died without being stopped through us. Returns how many were closed.
+pub fn prune_job_handles() -> usize {
+    let Ok(mut handles) = JOB_HANDLES.write() else {
+        return 0;
+    };
+    let before = handles.len();
+    handles.retain(|_, handle_value| {
+        let job = HANDLE(*handle_value as *mut std::ffi::c_void);
+        let mut info = JOBOBJECT_BASIC_ACCOUNTING_INFORMATION::default();
+        let queried = unsafe {
+            QueryInformationJobObject(
+                job,
+                JobObjectBasicAccountingInformation,
+                &mut info as *mut _ as *mut std::ffi::c_void,
+                mem::size_of::<JOBOBJECT_BASIC_ACCOUNTING_INFORMATION>() as u32,
+                None,
+            )
+        };
+        let empty = queried.is_ok() && info.ActiveProcesses == 0;
+        if empty {
+            unsafe {
+                let _ = CloseHandle(job);
+            }
+        }
+        !empty
+    });
+    before - handles.len()
+}
+
+/// Number of job handles held
+pub fn job_handle_count() -> usize {
+    JOB_HANDLES.read().map(|h| h.len()).unwrap_or(0)
+}
+
 /// Terminate a process gracefully (WM_CLOSE equivalent)
 pub fn terminate_process(pid: u32) -> Result<()> {
     unsafe {
@@ -183,19 +218,183 @@ pub fn is_process_running(pid: u32) -> bool {
     }
 }
 
+/// Check whether a process runs under WOW64 (32-bit process on 64-bit Windows)
+pub fn is_wow64_process(pid: u32) -> Result<bool> {
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, FALSE, pid)
+            .context("Failed to open process")?;
+
+        let mut wow64 = FALSE;
+        let result = IsWow64Process(handle, &mut wow64);
+        let _ = CloseHandle(handle);
+
+        result.context("Failed to query WOW64 status")?;
+        Ok(wow64.as_bool())
+    }
+}
+
+/// Kernel + user time from GetProcessTimes
+pub fn process_cpu_time(pid: u32) -> Result<Duration> {
+    use windows::Win32::Foundation::FILETIME;
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, FALSE, pid)
+            .context("Failed to open process")?;
+
+        let mut creation = FILETIME::default();
+        let mut exit = FILETIME::default();
+        let mut kernel = FILETIME::default();
+        let mut user = FILETIME::default();
+        let result = GetProcessTimes(handle, &mut creation, &mut exit, &mut kernel, &mut user);
+        let _ = CloseHandle(handle);
+        result.context("Failed to query process times")?;
+
+        // FILETIME counts 100 ns intervals
+        let ticks = |t: FILETIME| ((t.dwHighDateTime as u64) << 32) | t.dwLowDateTime as u64;
+        Ok(Duration::from_nanos((ticks(kernel) + ticks(user)) * 100))
+    }
+}
+
+/// Pointer width of a process (32 or 64)
+pub fn process_bitness(pid: u32) -> Result<u32> {
+    if is_wow64_process(pid)? {
+        return Ok(32);
+    }
+    // Not WOW64 means the process matches the OS, which is 64-bit if we are
+    let os_is_64bit =
+        cfg!(target_pointer_width = "64") || is_wow64_process(std::process::id()).unwrap_or(false);
+    Ok(if os_is_64bit { 64 } else { 32 })
+}
+
+/// A CPU set as reported by GetSystemCpuSetInformation
+struct CpuSet {
+    id: u32,
+    logical_index: usize,
+    core_index: usize,
+    efficiency_class: u8,
+}
+
+/// Enumerate the system's CPU sets (Windows 10+)
+fn system_cpu_sets() -> Result<Vec<CpuSet>> {
+    use windows::Win32::System::SystemInformation::{
+        CpuSetInformation, GetSystemCpuSetInformation, SYSTEM_CPU_SET_INFORMATION,
+    };
+
+    unsafe {
+        let mut length = 0u32;
+        let _ = GetSystemCpuSetInformation(None, 0, &mut length, HANDLE::default(), 0);
+        if length == 0 {
+            anyhow::bail!("CPU sets are not available");
+        }
+
+        // A u64 buffer keeps the variable-sized entries 8-byte aligned
+        let mut buffer = vec![0u64; (length as usize).div_ceil(8)];
+        if !GetSystemCpuSetInformation(
+            Some(buffer.as_mut_ptr() as *mut SYSTEM_CPU_SET_INFORMATION),
+            length,
+            &mut length,
+            HANDLE::default(),
+            0,
+        )
+        .as_bool()
+        {
+            anyhow::bail!("Failed to query CPU sets");
+        }
+
+        let base = buffer.as_ptr() as *const u8;
+        let mut sets = Vec::new();
+        let mut offset = 0usize;
+        while offset < length as usize {
+            let info = &*(base.add(offset) as *const SYSTEM_CPU_SET_INFORMATION);
+            if info.Size == 0 {
+                break;
+            }
+            if info.Type == CpuSetInformation {
+                let set = &info.Anonymous.CpuSet;
+                // Flatten processor groups (64 logical processors each) into one index space
+                let group_base = set.Group as usize * 64;
+                sets.push(CpuSet {
+                    id: set.Id,
+                    logical_index: group_base + set.LogicalProcessorIndex as usize,
+                    core_index: group_base + set.CoreIndex as usize,
+                    efficiency_class: set.EfficiencyClass,
+                });
+            }
+            offset += info.Size as usize;
+        }
+        Ok(sets)
+    }
+}
+
+/// Logical processors with their physical core and efficiency class
+pub fn cpu_efficiency_classes() -> Result<Vec<(usize, usize, u8)>> {
+    Ok(system_cpu_sets()?
+        .into_iter()
+        .map(|set| (set.logical_index, set.core_index, set.efficiency_class))
+        .collect())
+}
+
+/// Restrict a process to CPU sets. Unlike affinity masks these span processor
+/// groups and let the OS keep using other cores for its own work.
+fn set_default_cpu_sets(pid: u32, cores: &[usize]) -> Result<()> {
+    let ids: Vec<u32> = system_cpu_sets()?
+        .into_iter()
+        .filter(|set| cores.contains(&set.logical_index))
+        .map(|set| set.id)
+        .collect();
+    if ids.is_empty() {
+        anyhow::bail!("No CPU sets match the selected cores");
+    }
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_SET_LIMITED_INFORMATION, FALSE, pid)
+            .context("Failed to open process")?;
+        let result = SetProcessDefaultCpuSets(handle, Some(&ids));
+        let _ = CloseHandle(handle);
+
+        if !result.as_bool() {
+            anyhow::bail!("Failed to set default CPU sets");
+        }
+    }
+
+    debug!("Assigned CPU sets {:?} to process {}", ids, pid);
+    Ok(())
+}
+
 /// Set CPU affinity for a process
 pub fn set_cpu_affinity(pid: u32, cores: &[usize]) -> Result<()> {
     if cores.is_empty() {
         return Ok(());
     }
 
+    match set_default_cpu_sets(pid, cores) {
+        Ok(()) => return Ok(()),
+        Err(e) => debug!("CPU sets unavailable ({}), using affinity mask", e),
+    }
+
+    // WOW64 processes only see the first 32 logical processors
+    let wow64 = is_wow64_process(pid).unwrap_or(false);
+    let usable_cores = if wow64 { 32 } else { usize::BITS as usize };
+
     let mut mask: usize = 0;
     for &core in cores {
-        if core < std::mem::size_of::<usize>() * 8 {
+        if core < usable_cores {
             mask |= 1 << core;
+        } else {
+            warn!(
+                "Core {} is outside the {}-core affinity range of process {}, skipping",
+                core, usable_cores, pid
+            );
         }
     }
 
+    if mask == 0 {
+        anyhow::bail!(
+            "None of the selected cores can be assigned to process {}",
+            pid
+        );
+    }
+
     unsafe {
         let handle = OpenProcess(
             PROCESS_SET_INFORMATION | PROCESS_QUERY_INFORMATION,
@@ -218,12 +417,8 @@ pub fn set_cpu_affinity(pid: u32, cores: &[usize]) -> Result<()> {
 /// Set process priority
 /// priority: -2 (Idle) to 2 (High), 0 = Normal
 pub fn set_process_priority(pid: u32, priority: i8) -> Result<()> {
-    // Skip if normal priority (0)
-    if priority == 0 {
-        return Ok(());
-    }
-
     let priority_class = match priority {
+        crate::core::ResourceLimits::EFFICIENCY_PRIORITY => IDLE_PRIORITY_CLASS,
         -2 => IDLE_PRIORITY_CLASS,
         -1 => BELOW_NORMAL_PRIORITY_CLASS,
         1 => ABOVE_NORMAL_PRIORITY_CLASS,
@@ -246,6 +441,17 @@ pub fn set_process_priority(pid: u32, priority: i8) -> Result<()> {
         let result = SetPriorityClass(handle, priority_class);
         let _ = CloseHandle(handle);
 
+        // Switching away from the efficiency level must lift EcoQoS again
+        let efficiency = priority == crate::core::ResourceLimits::EFFICIENCY_PRIORITY;
+        if let Err(e) = set_efficiency_mode(pid, efficiency) {
+            warn!(
+                "Could not {} EcoQoS for process {}: {}",
+                if efficiency { "enable" } else { "disable" },
+                pid,
+                e
+            );
+        }
+
         if result.is_ok() {
             debug!("Set process {} priority to {:?}", pid, priority_class);
             Ok(())
@@ -256,6 +462,74 @@ pub fn set_process_priority(pid: u32, priority: i8) -> Result<()> {
     }
 }
 
+/// Toggle EcoQoS (execution speed power throttling) for a process.
+/// Windows 11 schedules throttled processes on E-cores at lower clock speeds.
+pub fn set_efficiency_mode(pid: u32, enabled: bool) -> Result<()> {
+    let state = PROCESS_POWER_THROTTLING_STATE {
+        Version: PROCESS_POWER_THROTTLING_CURRENT_VERSION,
+        ControlMask: PROCESS_POWER_THROTTLING_EXECUTION_SPEED,
+        StateMask: if enabled {
+            PROCESS_POWER_THROTTLING_EXECUTION_SPEED
+        } else {
+            0
+        },
+    };
+
+    unsafe {
+        let handle =
+            OpenProcess(PROCESS_SET_INFORMATION, FALSE, pid).context("Failed to open process")?;
+
+        let result = SetProcessInformation(
+            handle,
+            ProcessPowerThrottling,
+            &state as *const _ as *const _,
+            mem::size_of::<PROCESS_POWER_THROTTLING_STATE>() as u32,
+        );
+        let _ = CloseHandle(handle);
+
+        result.context("Failed to set power throttling")?;
+    }
+
+    debug!(
+        "EcoQoS {} for process {}",
+        if enabled { "enabled" } else { "disabled" },
+        pid
+    );
+    Ok(())
+}
+
+/// Job object holding a process, created and assigned on first use so memory
+/// and CPU limits share one job. Fails for processes already in a job that
+/// cannot be nested (like Chrome, some games, etc.)
+fn job_for(pid: u32) -> Result<HANDLE> {
+    if let Some(handle) = JOB_HANDLES.read().ok().and_then(|h| h.get(&pid).copied()) {
+        return Ok(HANDLE(handle as *mut std::ffi::c_void));
+    }
+
+    unsafe {
+        let job = CreateJobObjectW(None, None).context("Could not create job object")?;
+
+        let handle = match OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, FALSE, pid) {
+            Ok(h) => h,
+            Err(e) => {
+                let _ = CloseHandle(job);
+                return Err(e).context("Could not open process for its job object");
+            }
+        };
+        let result = AssignProcessToJobObject(job, handle);
+        let _ = CloseHandle(handle);
+
+        if let Err(e) = result {
+            let _ = CloseHandle(job);
+            return Err(e).context("Could not assign process to a job object");
+        }
+
+        // The handle must stay open for limits to remain in effect
+        store_job_handle(pid, job);
+        Ok(job)
+    }
+}
+
 /// Set memory limit for a process using Job Objects
 /// Note: This may fail for processes already in a Job Object (like Chrome, some games, etc.)
 /// The function returns Ok even if it fails, logging a warning instead of failing the launch.
@@ -265,17 +539,25 @@ pub fn set_memory_limit(pid: u32, memory_mb: u64) -> Result<()> {
         return Ok(());
     }
 
-    unsafe {
-        // Create a job object
-        let job = match CreateJobObjectW(None, None) {
-            Ok(j) => j,
-            Err(e) => {
-                warn!("Could not create job object for memory limit: {}. Process will run without memory limit.", e);
-                return Ok(());
-            }
-        };
+    // A 32-bit process cannot address more than 4 GB, so a higher limit never triggers
+    if memory_mb > 4096 && is_wow64_process(pid).unwrap_or(false) {
+        warn!(
+            "Memory limit of {} MB exceeds the 4 GB address space of 32-bit process {}; \
+             the limit will have no effect",
+            memory_mb, pid
+        );
+    }
+
+    let job = match job_for(pid) {
+        Ok(job) => job,
+        Err(e) => {
+            // This is common for processes already in a Job Object (Chrome, some games, etc.)
+            warn!("{:#}. Process will run without memory limit.", e);
+            return Ok(());
+        }
+    };
 
-        // Set memory limit
+    unsafe {
         let mut limit_info = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
         limit_info.BasicLimitInformation.LimitFlags =
             JOB_OBJECT_LIMIT_PROCESS_MEMORY | JOB_OBJECT_LIMIT_JOB_MEMORY;
@@ -292,136 +574,561 @@ pub fn set_memory_limit(pid: u32, memory_mb: u64) -> Result<()> {
                 "Could not set job object limits: {}. Process will run without memory limit.",
                 e
             );
-            let _ = CloseHandle(job);
             return Ok(());
         }
-
-        // Try to assign process to job - this often fails if process is already in a Job Object
-        let handle = match OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, FALSE, pid) {
-            Ok(h) => h,
-            Err(e) => {
-                warn!("Could not open process for memory limit: {}. Process will run without memory limit.", e);
-                let _ = CloseHandle(job);
-                return Ok(());
-            }
-        };
-
-        let result = AssignProcessToJobObject(job, handle);
-        let _ = CloseHandle(handle);
-
-        if result.is_err() {
-            // This is common for processes already in a Job Object (Chrome, some games, etc.)
-            debug!("Could not assign process to job object - process may already be in a job object. Memory limits not applied.");
-            let _ = CloseHandle(job);
-        } else {
-            debug!(
-                "Memory limit of {} MB applied to process {}",
-                memory_mb, pid
-            );
-            // Store the job handle so it can be cleaned up later when the process exits
-            // The handle must stay open for limits to remain in effect
-            store_job_handle(pid, job);
-        }
-
-        Ok(())
     }
-}
-
-/// Attempt to release/close a mutex held by applications to allow multiple instances
-/// This is a best-effort approach and may not work for all applications
-pub fn release_app_mutex(process_name: &str) -> Result<()> {
-    // This is a simplified implementation
-    // A full implementation would:
-    // 1. Enumerate all handles in the system
-    // 2. Find mutex handles with names matching common patterns
-    // 3. Duplicate and close them
 
-    // For now, we rely on profile isolation which is more reliable
-    warn!(
-        "Mutex release for '{}' - using profile isolation instead",
-        process_name
+    debug!(
+        "Memory limit of {} MB applied to process {}",
+        memory_mb, pid
     );
     Ok(())
 }
 
-/// Get process memory information
-pub fn get_process_memory_info(pid: u32) -> Result<(u64, u64)> {
+/// Hard-cap a process's CPU time to `percent` of the whole machine with Job
+/// Object CPU rate control; 0 removes the cap
+pub fn set_cpu_rate(pid: u32, percent: u8) -> Result<()> {
+    let has_job = JOB_HANDLES
+        .read()
+        .map(|h| h.contains_key(&pid))
+        .unwrap_or(false);
+    if percent == 0 && !has_job {
+        return Ok(());
+    }
+    let job = job_for(pid)?;
+
+    let mut info = JOBOBJECT_CPU_RATE_CONTROL_INFORMATION::default();
+    if percent > 0 {
+        info.ControlFlags =
+            JOB_OBJECT_CPU_RATE_CONTROL_ENABLE | JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP;
+        // In hundredths of a percent
+        info.Anonymous.CpuRate = percent.min(100) as u32 * 100;
+    }
     unsafe {
-        let handle = OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ, FALSE, pid)
-            .context("Failed to open process")?;
+        SetInformationJobObject(
+            job,
+            JobObjectCpuRateControlInformation,
+            &info as *const _ as *const _,
+            mem::size_of::<JOBOBJECT_CPU_RATE_CONTROL_INFORMATION>() as u32,
+        )
+        .context("Could not set job object CPU rate")?;
+    }
 
-        let mut mem_counters = PROCESS_MEMORY_COUNTERS {
-            cb: mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32,
-            ..Default::default()
-        };
+    debug!("CPU rate of process {} capped at {}%", pid, percent);
+    Ok(())
+}
 
-        let result = K32GetProcessMemoryInfo(
-            handle,
-            &mut mem_counters,
-            mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32,
-        );
+/// `ProcessIoPriority` class for NtSetInformationProcess
+const PROCESS_IO_PRIORITY: u32 = 33;
 
-        CloseHandle(handle)?;
+/// Set a process's I/O priority hint. Only Very Low to Normal can be set on
+/// another process; High needs a privilege we do not hold.
+pub fn set_io_priority(pid: u32, priority: crate::core::IoPriority) -> Result<()> {
+    use crate::core::IoPriority;
 
-        if result.as_bool() {
-            Ok((
-                mem_counters.WorkingSetSize as u64,
-                mem_counters.PagefileUsage as u64,
-            ))
-        } else {
-            anyhow::bail!("Failed to get memory info")
+    // IO_PRIORITY_HINT values
+    let hint: u32 = match priority {
+        IoPriority::VeryLow => 0,
+        IoPriority::Low => 1,
+        IoPriority::Normal => 2,
+    };
+    unsafe {
+        let handle = OpenProcess(PROCESS_SET_INFORMATION, FALSE, pid)
+            .context("Could not open process for I/O priority change")?;
+        let status = NtSetInformationProcess(
+            handle,
+            PROCESS_IO_PRIORITY,
+            &hint as *const u32 as *const std::ffi::c_void,
+            mem::size_of::<u32>() as u32,
+        );
+        let _ = CloseHandle(handle);
+        if status != 0 {
+            anyhow::bail!("NtSetInformationProcess failed: {:#x}", status);
         }
     }
+
+    debug!("I/O priority of process {} set to {:?}", pid, priority);
+    Ok(())
 }
 
-/// Enumerate all processes and find ones matching a name
-pub fn find_processes_by_name(name: &str) -> Result<Vec<u32>> {
-    use windows::Win32::System::Diagnostics::ToolHelp::*;
+/// Cap a process's disk throughput with Job Object I/O rate control on every
+/// fixed volume, as the limit is set per volume
+pub fn set_disk_rate(pid: u32, mbps: u64) -> Result<()> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::{GetDriveTypeW, GetLogicalDrives, QueryDosDeviceW};
 
-    unsafe {
-        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0)?;
+    const DRIVE_FIXED: u32 = 3;
 
-        let mut entry = PROCESSENTRY32W {
-            dwSize: mem::size_of::<PROCESSENTRY32W>() as u32,
-            ..Default::default()
+    if mbps == 0 {
+        return Ok(());
+    }
+    let job = job_for(pid)?;
+
+    let mut limited = 0;
+    let drives = unsafe { GetLogicalDrives() };
+    for letter in (0..26u8).filter(|i| drives & (1 << i) != 0) {
+        let letter = (b'A' + letter) as char;
+        let root: Vec<u16> = format!("{}:\\", letter).encode_utf16().chain([0]).collect();
+        if unsafe { GetDriveTypeW(PCWSTR(root.as_ptr())) } != DRIVE_FIXED {
+            continue;
+        }
+        // The policy names volumes by NT device path, e.g. \Device\HarddiskVolume3
+        let drive: Vec<u16> = format!("{}:", letter).encode_utf16().chain([0]).collect();
+        let mut device = [0u16; 260];
+        if unsafe { QueryDosDeviceW(PCWSTR(drive.as_ptr()), Some(&mut device)) } == 0 {
+            continue;
+        }
+
+        let info = JOBOBJECT_IO_RATE_CONTROL_INFORMATION {
+            MaxIops: 0,
+            MaxBandwidth: (mbps * 1024 * 1024) as i64,
+            ReservationIops: 0,
+            VolumeName: PCWSTR(device.as_ptr()),
+            BaseIoSize: 0,
+            ControlFlags: JOB_OBJECT_IO_RATE_CONTROL_ENABLE,
         };
+        if unsafe { SetIoRateControlInformationJobObject(job, &info) } == 0 {
+            debug!(
+                "Cannot limit disk I/O on {}: {}",
+                letter,
+                std::io::Error::last_os_error()
+            );
+            continue;
+        }
+        limited += 1;
+    }
+    if limited == 0 {
+        anyhow::bail!("No volume accepted an I/O rate limit");
+    }
 
-        let mut pids = Vec::new();
-        let name_lower = name.to_lowercase();
+    debug!(
+        "Disk I/O of process {} capped at {} MB/s on {} volumes",
+        pid, mbps, limited
+    );
+    Ok(())
+}
 
-        if Process32FirstW(snapshot, &mut entry).is_ok() {
-            loop {
-                let exe_name = String::from_utf16_lossy(
-                    &entry.szExeFile[..entry
-                        .szExeFile
-                        .iter()
-                        .position(|&c| c == 0)
-                        .unwrap_or(entry.szExeFile.len())],
-                );
+/// `SystemExtendedHandleInformation` class for NtQuerySystemInformation
+const SYSTEM_EXTENDED_HANDLE_INFORMATION: u32 = 64;
+
+/// NTSTATUS returned while the handle table buffer is too small
+const STATUS_INFO_LENGTH_MISMATCH: i32 = 0xC000_0004_u32 as i32;
+
+/// SYSTEM_HANDLE_TABLE_ENTRY_INFO_EX
+#[repr(C)]
+#[allow(dead_code)]
+struct SystemHandleEntry {
+    object: *mut std::ffi::c_void,
+    unique_process_id: usize,
+    handle_value: usize,
+    granted_access: u32,
+    creator_back_trace_index: u16,
+    object_type_index: u16,
+    handle_attributes: u32,
+    reserved: u32,
+}
 
-                if exe_name.to_lowercase().contains(&name_lower) {
-                    pids.push(entry.th32ProcessID);
-                }
+#[link(name = "ntdll")]
+extern "system" {
+    fn NtSetInformationProcess(
+        process: HANDLE,
+        class: u32,
+        information: *const std::ffi::c_void,
+        length: u32,
+    ) -> i32;
+    fn NtQuerySystemInformation(
+        class: u32,
+        information: *mut std::ffi::c_void,
+        length: u32,
+        return_length: *mut u32,
+    ) -> i32;
+}
 
-                if Process32NextW(snapshot, &mut entry).is_err() {
-                    break;
-                }
+/// Snapshot the system handle table
+fn system_handles() -> Result<Vec<u8>> {
+    let mut buffer = vec![0u8; 1 << 20];
+    loop {
+        let mut needed = 0u32;
+        let status = unsafe {
+            NtQuerySystemInformation(
+                SYSTEM_EXTENDED_HANDLE_INFORMATION,
+                buffer.as_mut_ptr().cast(),
+                buffer.len() as u32,
+                &mut needed,
+            )
+        };
+        match status {
+            0 => return Ok(buffer),
+            STATUS_INFO_LENGTH_MISMATCH if buffer.len() < 1 << 30 => {
+                // The table grows between calls, so leave some headroom
+                buffer.resize((needed as usize).max(buffer.len()) * 2, 0);
             }
+            _ => anyhow::bail!("NtQuerySystemInformation failed: {:#x}", status),
         }
-
-        CloseHandle(snapshot)?;
-        Ok(pids)
     }
 }
 
-/// Create an isolated environment by virtualizing registry access
-pub fn setup_registry_virtualization(instance_id: &str) -> Result<()> {
-    // This would set up registry virtualization for the instance
-    // Real implementation would use registry redirection techniques
-    debug!(
-        "Setting up registry virtualization for instance {}",
-        instance_id
-    );
+/// Files and pipes a process holds open, from the system handle table.
+/// Only disk files are resolved to paths; querying pipe names can block.
+pub fn open_handles(pid: u32) -> Result<Vec<crate::core::inspect::OpenHandle>> {
+    use crate::core::inspect::{HandleKind, OpenHandle};
+    use windows::Win32::Storage::FileSystem::{
+        GetFileType, GetFinalPathNameByHandleW, FILE_NAME_NORMALIZED, FILE_TYPE_DISK,
+        FILE_TYPE_PIPE,
+    };
+
+    let table = system_handles()?;
+    unsafe {
+        let process = OpenProcess(PROCESS_DUP_HANDLE, FALSE, pid)
+            .context("Failed to open process for handle inspection")?;
+
+        let count = *(table.as_ptr() as *const usize);
+        let entries = table.as_ptr().add(2 * mem::size_of::<usize>()) as *const SystemHandleEntry;
+
+        let mut handles = Vec::new();
+        for index in 0..count {
+            let entry = &*entries.add(index);
+            if entry.unique_process_id != pid as usize {
+                continue;
+            }
+
+            let mut local = HANDLE::default();
+            if DuplicateHandle(
+                process,
+                HANDLE(entry.handle_value as *mut std::ffi::c_void),
+                GetCurrentProcess(),
+                &mut local,
+                0,
+                FALSE,
+                DUPLICATE_SAME_ACCESS,
+            )
+            .is_err()
+            {
+                continue;
+            }
+
+            let handle = match GetFileType(local) {
+                FILE_TYPE_DISK => {
+                    let mut path = [0u16; 1024];
+                    let len =
+                        GetFinalPathNameByHandleW(local, &mut path, FILE_NAME_NORMALIZED) as usize;
+                    (len > 0 && len < path.len()).then(|| {
+                        let name = String::from_utf16_lossy(&path[..len]);
+                        let name = name.strip_prefix(r"\\?\").unwrap_or(&name).to_string();
+                        let kind = if std::path::Path::new(&name).is_dir() {
+                            HandleKind::Directory
+                        } else {
+                            HandleKind::File
+                        };
+                        OpenHandle { kind, name }
+                    })
+                }
+                // Sockets are AFD handles, which report as pipes too
+                FILE_TYPE_PIPE => Some(OpenHandle {
+                    kind: HandleKind::Pipe,
+                    name: format!("Pipe or socket (handle {:#x})", entry.handle_value),
+                }),
+                _ => None,
+            };
+            let _ = CloseHandle(local);
+            handles.extend(handle);
+        }
+
+        let _ = CloseHandle(process);
+        handles.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(handles)
+    }
+}
+
+/// Fetch an IP Helper table, growing the buffer until it fits
+fn ip_helper_table(query: impl Fn(*mut std::ffi::c_void, &mut u32) -> u32) -> Result<Vec<u8>> {
+    use windows::Win32::Foundation::{ERROR_INSUFFICIENT_BUFFER, NO_ERROR};
+
+    let mut size = 0u32;
+    let _ = query(std::ptr::null_mut(), &mut size);
+    loop {
+        let mut buffer = vec![0u8; size.max(4) as usize];
+        match query(buffer.as_mut_ptr().cast(), &mut size) {
+            code if code == NO_ERROR.0 => return Ok(buffer),
+            code if code == ERROR_INSUFFICIENT_BUFFER.0 => continue,
+            code => anyhow::bail!("IP Helper query failed with error {}", code),
+        }
+    }
+}
+
+/// Rows of an IP Helper table: a u32 count followed by the row array
+fn ip_helper_rows<Row: Copy>(table: &[u8]) -> Vec<Row> {
+    unsafe {
+        let count = (table.as_ptr() as *const u32).read_unaligned() as usize;
+        let first = table.as_ptr().add(mem::size_of::<u32>()) as *const Row;
+        (0..count)
+            .take_while(|i| mem::size_of::<u32>() + (i + 1) * mem::size_of::<Row>() <= table.len())
+            .map(|i| first.add(i).read_unaligned())
+            .collect()
+    }
+}
+
+fn tcp_state(state: u32) -> &'static str {
+    match state {
+        1 => "Closed",
+        2 => "Listen",
+        3 => "SYN sent",
+        4 => "SYN received",
+        5 => "Established",
+        6 => "FIN wait 1",
+        7 => "FIN wait 2",
+        8 => "Close wait",
+        9 => "Closing",
+        10 => "Last ACK",
+        11 => "Time wait",
+        12 => "Delete TCB",
+        _ => "Unknown",
+    }
+}
+
+/// Bytes received and sent over an IPv4 TCP connection, from its extended
+/// statistics. Collection is switched on per connection, which needs
+/// administrator rights, and counts only from then on.
+fn tcp_data_bytes(
+    row: &windows::Win32::NetworkManagement::IpHelper::MIB_TCPROW_OWNER_PID,
+) -> Option<(u64, u64)> {
+    use windows::Win32::Foundation::BOOLEAN;
+    use windows::Win32::NetworkManagement::IpHelper::*;
+
+    let tcp_row = MIB_TCPROW_LH {
+        Anonymous: MIB_TCPROW_LH_0 {
+            dwState: row.dwState,
+        },
+        dwLocalAddr: row.dwLocalAddr,
+        dwLocalPort: row.dwLocalPort,
+        dwRemoteAddr: row.dwRemoteAddr,
+        dwRemotePort: row.dwRemotePort,
+    };
+
+    let rw = TCP_ESTATS_DATA_RW_v0 {
+        EnableCollection: BOOLEAN(1),
+    };
+    let mut rod = TCP_ESTATS_DATA_ROD_v0::default();
+    unsafe {
+        let rw = std::slice::from_raw_parts(
+            &rw as *const _ as *const u8,
+            mem::size_of::<TCP_ESTATS_DATA_RW_v0>(),
+        );
+        // Already enabled connections keep counting if this fails
+        let _ = SetPerTcpConnectionEStats(&tcp_row, TcpConnectionEstatsData, rw, 0, 0);
+
+        let rod_bytes = std::slice::from_raw_parts_mut(
+            &mut rod as *mut _ as *mut u8,
+            mem::size_of::<TCP_ESTATS_DATA_ROD_v0>(),
+        );
+        let result = GetPerTcpConnectionEStats(
+            &tcp_row,
+            TcpConnectionEstatsData,
+            None,
+            0,
+            None,
+            0,
+            Some(rod_bytes),
+            0,
+        );
+        if result != 0 {
+            return None;
+        }
+    }
+    Some((rod.DataBytesIn, rod.DataBytesOut))
+}
+
+/// TCP and UDP sockets of a process from GetExtendedTcpTable / GetExtendedUdpTable.
+/// Byte counters come from extended statistics, for IPv4 TCP only.
+pub fn connections(pid: u32) -> Result<Vec<crate::core::inspect::Connection>> {
+    use crate::core::inspect::Connection;
+    use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+    use windows::Win32::NetworkManagement::IpHelper::*;
+    use windows::Win32::Networking::WinSock::{AF_INET, AF_INET6};
+
+    let v4 = |addr: u32, port: u32| {
+        SocketAddr::new(
+            Ipv4Addr::from(addr.to_ne_bytes()).into(),
+            u16::from_be(port as u16),
+        )
+    };
+    let v6 = |addr: [u8; 16], port: u32| {
+        SocketAddr::new(Ipv6Addr::from(addr).into(), u16::from_be(port as u16))
+    };
+    let connection =
+        |protocol, local: SocketAddr, remote: Option<SocketAddr>, state: &str| Connection {
+            protocol,
+            local: local.to_string(),
+            remote: remote.filter(|r| r.port() != 0).map(|r| r.to_string()),
+            state: state.to_string(),
+            bytes_in: None,
+            bytes_out: None,
+            rx_rate: None,
+            tx_rate: None,
+        };
+
+    let tcp = |family: u32| {
+        ip_helper_table(|buffer, size| unsafe {
+            GetExtendedTcpTable(
+                Some(buffer),
+                size,
+                FALSE,
+                family,
+                TCP_TABLE_OWNER_PID_ALL,
+                0,
+            )
+        })
+    };
+    let udp = |family: u32| {
+        ip_helper_table(|buffer, size| unsafe {
+            GetExtendedUdpTable(Some(buffer), size, FALSE, family, UDP_TABLE_OWNER_PID, 0)
+        })
+    };
+
+    let mut connections = Vec::new();
+    for row in ip_helper_rows::<MIB_TCPROW_OWNER_PID>(&tcp(AF_INET.0 as u32)?) {
+        if row.dwOwningPid == pid {
+            let mut tcp = connection(
+                "TCP",
+                v4(row.dwLocalAddr, row.dwLocalPort),
+                Some(v4(row.dwRemoteAddr, row.dwRemotePort)),
+                tcp_state(row.dwState),
+            );
+            if let Some((bytes_in, bytes_out)) = tcp_data_bytes(&row) {
+                tcp.bytes_in = Some(bytes_in);
+                tcp.bytes_out = Some(bytes_out);
+            }
+            connections.push(tcp);
+        }
+    }
+    for row in ip_helper_rows::<MIB_TCP6ROW_OWNER_PID>(&tcp(AF_INET6.0 as u32)?) {
+        if row.dwOwningPid == pid {
+            connections.push(connection(
+                "TCP6",
+                v6(row.ucLocalAddr, row.dwLocalPort),
+                Some(v6(row.ucRemoteAddr, row.dwRemotePort)),
+                tcp_state(row.dwState),
+            ));
+        }
+    }
+    for row in ip_helper_rows::<MIB_UDPROW_OWNER_PID>(&udp(AF_INET.0 as u32)?) {
+        if row.dwOwningPid == pid {
+            connections.push(connection(
+                "UDP",
+                v4(row.dwLocalAddr, row.dwLocalPort),
+                None,
+                "",
+            ));
+        }
+    }
+    for row in ip_helper_rows::<MIB_UDP6ROW_OWNER_PID>(&udp(AF_INET6.0 as u32)?) {
+        if row.dwOwningPid == pid {
+            connections.push(connection(
+                "UDP6",
+                v6(row.ucLocalAddr, row.dwLocalPort),
+                None,
+                "",
+            ));
+        }
+    }
+    Ok(connections)
+}
+
+/// Attempt to release/close a mutex held by applications to allow multiple instances
+/// This is a best-effort approach and may not work for all applications
+pub fn release_app_mutex(process_name: &str) -> Result<()> {
+    // This is a simplified implementation
+    // A full implementation would:
+    // 1. Enumerate all handles in the system
+    // 2. Find mutex handles with names matching common patterns
+    // 3. Duplicate and close them
+
+    // For now, we rely on profile isolation which is more reliable
+    warn!(
+        "Mutex release for '{}' - using profile isolation instead",
+        process_name
+    );
+    Ok(())
+}
+
+/// Get process memory information
+pub fn get_process_memory_info(pid: u32) -> Result<(u64, u64)> {
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ, FALSE, pid)
+            .context("Failed to open process")?;
+
+        let mut mem_counters = PROCESS_MEMORY_COUNTERS {
+            cb: mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32,
+            ..Default::default()
+        };
+
+        let result = K32GetProcessMemoryInfo(
+            handle,
+            &mut mem_counters,
+            mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32,
+        );
+
+        CloseHandle(handle)?;
+
+        if result.as_bool() {
+            Ok((
+                mem_counters.WorkingSetSize as u64,
+                mem_counters.PagefileUsage as u64,
+            ))
+        } else {
+            anyhow::bail!("Failed to get memory info")
+        }
+    }
+}
+
+/// Enumerate all processes and find ones matching a name
+pub fn find_processes_by_name(name: &str) -> Result<Vec<u32>> {
+    use windows::Win32::System::Diagnostics::ToolHelp::*;
+
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0)?;
+
+        let mut entry = PROCESSENTRY32W {
+            dwSize: mem::size_of::<PROCESSENTRY32W>() as u32,
+            ..Default::default()
+        };
+
+        let mut pids = Vec::new();
+        let name_lower = name.to_lowercase();
+
+        if Process32FirstW(snapshot, &mut entry).is_ok() {
+            loop {
+                let exe_name = String::from_utf16_lossy(
+                    &entry.szExeFile[..entry
+                        .szExeFile
+                        .iter()
+                        .position(|&c| c == 0)
+                        .unwrap_or(entry.szExeFile.len())],
+                );
+
+                if exe_name.to_lowercase().contains(&name_lower) {
+                    pids.push(entry.th32ProcessID);
+                }
+
+                if Process32NextW(snapshot, &mut entry).is_err() {
+                    break;
+                }
+            }
+        }
+
+        CloseHandle(snapshot)?;
+        Ok(pids)
+    }
+}
+
+/// Create an isolated environment by virtualizing registry access
+pub fn setup_registry_virtualization(instance_id: &str) -> Result<()> {
+    // This would set up registry virtualization for the instance
+    // Real implementation would use registry redirection techniques
+    debug!(
+        "Setting up registry virtualization for instance {}",
+        instance_id
+    );
     Ok(())
 }
 
@@ -555,7 +1262,19 @@ pub fn close_singleton_handles(pid: u32) -> Result<()> {
         let mut closed_count = 0;
         const MAX_HANDLES_TO_CLOSE: u32 = 50; // Limit how many handles we'll close
 
-        for handle_value in (4..=0x100).step_by(4) {
+        // The WOW64 layer opens its own handles (KnownDlls32 section, \Device objects)
+        // before the app's code runs; closing those crashes 32-bit processes
+        let first_handle = if is_wow64_process(pid).unwrap_or(false) {
+            debug!(
+                "PID {} is a WOW64 process, skipping WOW64 bootstrap handles",
+                pid
+            );
+            0x40
+        } else {
+            4
+        };
+
+        for handle_value in (first_handle..=0x100).step_by(4) {
             if closed_count >= MAX_HANDLES_TO_CLOSE {
                 debug!("Reached maximum handle close limit");
                 break;
@@ -684,3 +1403,1320 @@ pub fn hide_process_from_taskbar(pid: u32) -> Result<()> {
 
     Ok(())
 }
+
+/// Largest visible top-level window owned by a process
+fn main_window(pid: u32) -> Option<HWND> {
+    use windows::Win32::Foundation::{LPARAM, RECT};
+
+    unsafe extern "system" fn enum_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let windows = &mut *(lparam.0 as *mut Vec<HWND>);
+        windows.push(hwnd);
+        BOOL::from(true)
+    }
+
+    let mut all: Vec<HWND> = Vec::new();
+    unsafe {
+        let _ = EnumWindows(
+            Some(enum_callback),
+            LPARAM(&mut all as *mut Vec<HWND> as isize),
+        );
+    }
+
+    all.into_iter()
+        .filter(|&hwnd| unsafe {
+            let mut window_pid: u32 = 0;
+            GetWindowThreadProcessId(hwnd, Some(&mut window_pid));
+            window_pid == pid && IsWindowVisible(hwnd).as_bool()
+        })
+        .max_by_key(|&hwnd| {
+            let mut rect = RECT::default();
+            unsafe {
+                let _ = GetWindowRect(hwnd, &mut rect);
+            }
+            (rect.right - rect.left) as i64 * (rect.bottom - rect.top) as i64
+        })
+}
+
+/// Render a process's main window into a bitmap with PrintWindow, even when it is covered
+pub fn capture_window(pid: u32) -> Result<image::RgbaImage> {
+    use windows::Win32::Foundation::RECT;
+    use windows::Win32::Graphics::Gdi::*;
+    use windows::Win32::Storage::Xps::{PrintWindow, PRINT_WINDOW_FLAGS};
+
+    /// Also capture windows drawn with DirectX or other GPU surfaces
+    const PW_RENDERFULLCONTENT: u32 = 2;
+
+    let hwnd = main_window(pid).context("Instance has no visible window")?;
+
+    unsafe {
+        let mut rect = RECT::default();
+        GetWindowRect(hwnd, &mut rect).context("Failed to get window bounds")?;
+        let width = rect.right - rect.left;
+        let height = rect.bottom - rect.top;
+        if width <= 0 || height <= 0 || IsIconic(hwnd).as_bool() {
+            anyhow::bail!("Window is minimized");
+        }
+
+        let window_dc = GetWindowDC(hwnd);
+        let memory_dc = CreateCompatibleDC(window_dc);
+        let bitmap = CreateCompatibleBitmap(window_dc, width, height);
+        let previous = SelectObject(memory_dc, HGDIOBJ(bitmap.0));
+        let printed = PrintWindow(hwnd, memory_dc, PRINT_WINDOW_FLAGS(PW_RENDERFULLCONTENT));
+
+        // Negative height asks for top-down rows
+        let mut info = BITMAPINFO {
+            bmiHeader: BITMAPINFOHEADER {
+                biSize: mem::size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: width,
+                biHeight: -height,
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut pixels = vec![0u8; width as usize * height as usize * 4];
+        let lines = GetDIBits(
+            memory_dc,
+            bitmap,
+            0,
+            height as u32,
+            Some(pixels.as_mut_ptr() as *mut std::ffi::c_void),
+            &mut info,
+            DIB_RGB_COLORS,
+        );
+
+        SelectObject(memory_dc, previous);
+        let _ = DeleteObject(HGDIOBJ(bitmap.0));
+        let _ = DeleteDC(memory_dc);
+        ReleaseDC(hwnd, window_dc);
+
+        if !printed.as_bool() || lines == 0 {
+            anyhow::bail!("PrintWindow failed");
+        }
+
+        // GDI hands back BGRA with an undefined alpha channel
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+            pixel[3] = 255;
+        }
+        image::RgbaImage::from_raw(width as u32, height as u32, pixels)
+            .context("Unexpected bitmap size")
+    }
+}
+
+/// Large shell icon of a file, as SHGetFileInfo shows it in Explorer
+pub fn executable_icon(path: &std::path::Path) -> Result<image::RgbaImage> {
+    use windows::core::HSTRING;
+    use windows::Win32::Graphics::Gdi::{DeleteObject, HGDIOBJ};
+    use windows::Win32::Storage::FileSystem::FILE_FLAGS_AND_ATTRIBUTES;
+    use windows::Win32::UI::Shell::{SHGetFileInfoW, SHFILEINFOW, SHGFI_ICON, SHGFI_LARGEICON};
+
+    unsafe {
+        let mut file_info = SHFILEINFOW::default();
+        let found = SHGetFileInfoW(
+            &HSTRING::from(path.as_os_str()),
+            FILE_FLAGS_AND_ATTRIBUTES(0),
+            Some(&mut file_info as *mut SHFILEINFOW),
+            mem::size_of::<SHFILEINFOW>() as u32,
+            SHGFI_ICON | SHGFI_LARGEICON,
+        );
+        if found == 0 || file_info.hIcon.is_invalid() {
+            anyhow::bail!("No icon found for {}", path.display());
+        }
+        let mut icon = ICONINFO::default();
+        let read = GetIconInfo(file_info.hIcon, &mut icon);
+        let _ = DestroyIcon(file_info.hIcon);
+        read.context("Failed to read icon")?;
+
+        let color = bitmap_pixels(icon.hbmColor);
+        let mask = bitmap_pixels(icon.hbmMask);
+        let _ = DeleteObject(HGDIOBJ(icon.hbmColor.0));
+        let _ = DeleteObject(HGDIOBJ(icon.hbmMask.0));
+        let (width, height, mut pixels) = color?;
+
+        // Icons without an alpha channel mark transparent pixels in the mask instead
+        let has_alpha = pixels.chunks_exact(4).any(|p| p[3] != 0);
+        let mask = mask.ok().filter(|(w, h, _)| (*w, *h) == (width, height));
+        for (i, pixel) in pixels.chunks_exact_mut(4).enumerate() {
+            pixel.swap(0, 2);
+            if !has_alpha {
+                pixel[3] = match &mask {
+                    Some((_, _, mask)) => 255 - mask[i * 4],
+                    None => 255,
+                };
+            }
+        }
+        image::RgbaImage::from_raw(width, height, pixels).context("Unexpected icon size")
+    }
+}
+
+/// Width, height and top-down BGRA rows of a bitmap
+unsafe fn bitmap_pixels(
+    bitmap: windows::Win32::Graphics::Gdi::HBITMAP,
+) -> Result<(u32, u32, Vec<u8>)> {
+    use windows::Win32::Graphics::Gdi::*;
+
+    if bitmap.is_invalid() {
+        anyhow::bail!("Icon has no color bitmap");
+    }
+    let mut header = BITMAP::default();
+    if GetObjectW(
+        HGDIOBJ(bitmap.0),
+        mem::size_of::<BITMAP>() as i32,
+        Some(&mut header as *mut BITMAP as *mut std::ffi::c_void),
+    ) == 0
+    {
+        anyhow::bail!("Failed to read bitmap size");
+    }
+    let (width, height) = (header.bmWidth, header.bmHeight);
+
+    // Negative height asks for top-down rows
+    let mut info = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width,
+            biHeight: -height,
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut pixels = vec![0u8; width as usize * height as usize * 4];
+    let dc = CreateCompatibleDC(HDC::default());
+    let lines = GetDIBits(
+        dc,
+        bitmap,
+        0,
+        height as u32,
+        Some(pixels.as_mut_ptr() as *mut std::ffi::c_void),
+        &mut info,
+        DIB_RGB_COLORS,
+    );
+    let _ = DeleteDC(dc);
+    if lines == 0 {
+        anyhow::bail!("Failed to read bitmap");
+    }
+    Ok((width as u32, height as u32, pixels))
+}
+
+pub fn focus_window(pid: u32) -> Result<()> {
+    let hwnd = main_window(pid).context("Instance has no visible window")?;
+    unsafe {
+        if IsIconic(hwnd).as_bool() {
+            let _ = ShowWindow(hwnd, SW_RESTORE);
+        }
+        if !SetForegroundWindow(hwnd).as_bool() {
+            anyhow::bail!("Windows refused to bring the instance's window to the front");
+        }
+    }
+    Ok(())
+}
+
+pub fn foreground_window() -> Option<u64> {
+    let hwnd = unsafe { GetForegroundWindow() };
+    (!hwnd.is_invalid()).then_some(hwnd.0 as u64)
+}
+
+/// Launching with `STARTF_USESHOWWINDOW` and `SW_SHOWNOACTIVATE` is not possible
+/// through `std::process::Command`, so windows that grab focus are sent back
+/// behind the previous one instead
+pub fn keep_focus_from(pid: u32, previous: u64) -> Result<()> {
+    let previous = HWND(previous as *mut std::ffi::c_void);
+    let deadline = std::time::Instant::now() + super::FOCUS_GUARD;
+
+    while std::time::Instant::now() < deadline {
+        unsafe {
+            if !IsWindow(previous).as_bool() {
+                break;
+            }
+            let foreground = GetForegroundWindow();
+            let mut owner: u32 = 0;
+            let window_thread = GetWindowThreadProcessId(foreground, Some(&mut owner));
+            if owner == pid {
+                // Share the instance's input state so Windows lets us move focus
+                let current_thread = GetCurrentThreadId();
+                let _ = AttachThreadInput(current_thread, window_thread, TRUE);
+                let _ = SetWindowPos(
+                    foreground,
+                    previous,
+                    0,
+                    0,
+                    0,
+                    0,
+                    SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+                );
+                let _ = SetForegroundWindow(previous);
+                let _ = AttachThreadInput(current_thread, window_thread, FALSE);
+                debug!("Returned focus taken by PID {}", pid);
+            }
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    Ok(())
+}
+
+/// Windows hidden by `apply_window_state`, by PID, so they can be shown again.
+/// Hidden windows are skipped by `main_window`, which only sees visible ones.
+static HIDDEN_WINDOWS: LazyLock<RwLock<HashMap<u32, usize>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+pub fn apply_window_state(pid: u32, state: crate::core::WindowState) -> Result<()> {
+    use crate::core::WindowState;
+
+    let hwnd = wait_for_main_window(pid)?;
+    unsafe {
+        match state {
+            WindowState::Normal => {}
+            WindowState::Minimized => {
+                let _ = ShowWindow(hwnd, SW_SHOWMINNOACTIVE);
+            }
+            WindowState::Hidden => {
+                let _ = ShowWindow(hwnd, SW_HIDE);
+                if let Ok(mut hidden) = HIDDEN_WINDOWS.write() {
+                    hidden.insert(pid, hwnd.0 as usize);
+                }
+            }
+        }
+    }
+    debug!("Applied {} window state to PID {}", state.label(), pid);
+    Ok(())
+}
+
+/// Poll for a process's main window for up to `WINDOW_WAIT`
+fn wait_for_main_window(pid: u32) -> Result<HWND> {
+    let deadline = std::time::Instant::now() + super::WINDOW_WAIT;
+    loop {
+        if let Some(hwnd) = main_window(pid) {
+            return Ok(hwnd);
+        }
+        if std::time::Instant::now() >= deadline {
+            anyhow::bail!("No window appeared within {:?}", super::WINDOW_WAIT);
+        }
+        thread::sleep(Duration::from_millis(250));
+    }
+}
+
+/// Registry key where Explorer keeps the virtual desktop list and names
+const VIRTUAL_DESKTOPS_KEY: &str =
+    r"Software\Microsoft\Windows\CurrentVersion\Explorer\VirtualDesktops";
+
+pub fn virtual_desktops() -> Result<Vec<crate::core::virtual_desktop::VirtualDesktop>> {
+    use crate::core::virtual_desktop::VirtualDesktop;
+    use winreg::enums::HKEY_CURRENT_USER;
+    use winreg::RegKey;
+
+    let key = RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey(VIRTUAL_DESKTOPS_KEY)
+        .context("No virtual desktops have been created")?;
+    // The GUIDs of all desktops, 16 bytes each in memory layout
+    let ids = key
+        .get_raw_value("VirtualDesktopIDs")
+        .context("Failed to read the virtual desktop list")?;
+
+    Ok(ids
+        .bytes
+        .chunks_exact(16)
+        .enumerate()
+        .map(|(i, b)| {
+            let id = format!(
+                "{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}",
+                u32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+                u16::from_le_bytes([b[4], b[5]]),
+                u16::from_le_bytes([b[6], b[7]]),
+                b[8],
+                b[9],
+                b[10],
+                b[11],
+                b[12],
+                b[13],
+                b[14],
+                b[15]
+            );
+            // Only desktops the user renamed have a stored name
+            let name = key
+                .open_subkey(format!("Desktops\\{{{}}}", id))
+                .and_then(|desktop| desktop.get_value::<String, _>("Name"))
+                .ok()
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| format!("Desktop {}", i + 1));
+            VirtualDesktop { id, name }
+        })
+        .collect())
+}
+
+pub fn move_to_virtual_desktop(pid: u32, desktop_id: &str) -> Result<()> {
+    use windows::core::GUID;
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED,
+    };
+    use windows::Win32::UI::Shell::{IVirtualDesktopManager, VirtualDesktopManager};
+
+    let desktop = u128::from_str_radix(&desktop_id.replace('-', ""), 16)
+        .map(GUID::from_u128)
+        .with_context(|| format!("Invalid virtual desktop ID {}", desktop_id))?;
+    wait_for_main_window(pid)?;
+
+    unsafe {
+        // Fails harmlessly when the thread already joined an apartment
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+        let manager: IVirtualDesktopManager =
+            CoCreateInstance(&VirtualDesktopManager, None, CLSCTX_INPROC_SERVER)
+                .context("Failed to create the virtual desktop manager")?;
+        for (window, _) in process_windows(pid)? {
+            manager
+                .MoveWindowToDesktop(HWND(window as *mut std::ffi::c_void), &desktop)
+                .context("Failed to move window to the virtual desktop")?;
+        }
+    }
+    debug!("Moved windows of PID {} to desktop {}", pid, desktop_id);
+    Ok(())
+}
+
+pub fn show_window(pid: u32) -> Result<()> {
+    let hidden = HIDDEN_WINDOWS
+        .write()
+        .ok()
+        .and_then(|mut hidden| hidden.remove(&pid));
+    if let Some(hwnd) = hidden {
+        let hwnd = HWND(hwnd as *mut std::ffi::c_void);
+        unsafe {
+            if IsWindow(hwnd).as_bool() {
+                let _ = ShowWindow(hwnd, SW_SHOW);
+            }
+        }
+    }
+    focus_window(pid)
+}
+
+pub fn process_windows(pid: u32) -> Result<Vec<(u64, String)>> {
+    use windows::Win32::Foundation::LPARAM;
+
+    unsafe extern "system" fn enum_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let windows = &mut *(lparam.0 as *mut Vec<HWND>);
+        windows.push(hwnd);
+        BOOL::from(true)
+    }
+
+    let mut all: Vec<HWND> = Vec::new();
+    unsafe {
+        EnumWindows(
+            Some(enum_callback),
+            LPARAM(&mut all as *mut Vec<HWND> as isize),
+        )
+        .context("Failed to enumerate windows")?;
+    }
+
+    Ok(all
+        .into_iter()
+        .filter(|&hwnd| unsafe {
+            let mut window_pid: u32 = 0;
+            GetWindowThreadProcessId(hwnd, Some(&mut window_pid));
+            window_pid == pid && IsWindowVisible(hwnd).as_bool()
+        })
+        .map(|hwnd| {
+            let mut title = [0u16; 512];
+            let len = unsafe { GetWindowTextW(hwnd, &mut title) };
+            let title = String::from_utf16_lossy(&title[..len.max(0) as usize]);
+            (hwnd.0 as u64, title)
+        })
+        .collect())
+}
+
+pub fn close_window(window: u64) -> Result<()> {
+    use windows::Win32::Foundation::{LPARAM, WPARAM};
+
+    let hwnd = HWND(window as *mut std::ffi::c_void);
+    unsafe {
+        PostMessageW(hwnd, WM_CLOSE, WPARAM(0), LPARAM(0)).context("Failed to close window")?;
+    }
+    Ok(())
+}
+
+pub fn set_window_title(window: u64, title: &str) -> Result<()> {
+    use windows::core::HSTRING;
+
+    let hwnd = HWND(window as *mut std::ffi::c_void);
+    unsafe { SetWindowTextW(hwnd, &HSTRING::from(title)) }.context("Failed to set window title")
+}
+
+pub fn work_area() -> Result<crate::core::window_layout::WindowRect> {
+    use windows::Win32::Foundation::RECT;
+
+    let mut rect = RECT::default();
+    unsafe {
+        SystemParametersInfoW(
+            SPI_GETWORKAREA,
+            0,
+            Some(&mut rect as *mut RECT as *mut std::ffi::c_void),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+        .context("Failed to read the work area")?;
+    }
+    Ok(crate::core::window_layout::WindowRect {
+        x: rect.left,
+        y: rect.top,
+        width: rect.right - rect.left,
+        height: rect.bottom - rect.top,
+    })
+}
+
+pub fn set_window_bounds(window: u64, rect: crate::core::window_layout::WindowRect) -> Result<()> {
+    let hwnd = HWND(window as *mut std::ffi::c_void);
+    unsafe {
+        // Minimized and maximized windows ignore a new position until restored
+        if IsIconic(hwnd).as_bool() || IsZoomed(hwnd).as_bool() {
+            let _ = ShowWindow(hwnd, SW_RESTORE);
+        }
+        SetWindowPos(
+            hwnd,
+            HWND::default(),
+            rect.x,
+            rect.y,
+            rect.width,
+            rect.height,
+            SWP_NOZORDER | SWP_NOACTIVATE,
+        )
+        .context("Failed to move window")?;
+    }
+    Ok(())
+}
+
+/// Virtual-key code for a macro key name
+fn virtual_key(key: &str) -> Option<u16> {
+    use windows::Win32::UI::Input::KeyboardAndMouse::VkKeyScanW;
+
+    Some(match key {
+        "Enter" => 0x0D,
+        "Tab" => 0x09,
+        "Backspace" => 0x08,
+        "Escape" => 0x1B,
+        "Space" => 0x20,
+        "PageUp" => 0x21,
+        "PageDown" => 0x22,
+        "End" => 0x23,
+        "Home" => 0x24,
+        "ArrowLeft" => 0x25,
+        "ArrowUp" => 0x26,
+        "ArrowRight" => 0x27,
+        "ArrowDown" => 0x28,
+        "Insert" => 0x2D,
+        "Delete" => 0x2E,
+        key if key.len() > 1 && key.starts_with('F') => {
+            let n: u16 = key[1..].parse().ok().filter(|n| (1..=24).contains(n))?;
+            0x70 + n - 1
+        }
+        key => {
+            let mut chars = key.chars();
+            let (Some(c), None) = (chars.next(), chars.next()) else {
+                return None;
+            };
+            match c.to_ascii_uppercase() {
+                c @ ('A'..='Z' | '0'..='9') => c as u16,
+                // Low byte of VkKeyScan is the key; -1 means no key types this character
+                c => {
+                    let scan = unsafe { VkKeyScanW(c as u16) };
+                    if scan == -1 {
+                        return None;
+                    }
+                    (scan as u16) & 0xFF
+                }
+            }
+        }
+    })
+}
+
+/// Inject input with SendInput; it goes to whichever window has focus, so
+/// the instance's window is brought to the front first
+pub fn send_input(pid: u32, event: &crate::core::macros::InputEvent) -> Result<()> {
+    use crate::core::macros::{InputEvent, MouseButton};
+    use windows::Win32::Foundation::RECT;
+    use windows::Win32::UI::Input::KeyboardAndMouse::*;
+
+    fn key(vk: u16, scan: u16, flags: KEYBD_EVENT_FLAGS) -> INPUT {
+        INPUT {
+            r#type: INPUT_KEYBOARD,
+            Anonymous: INPUT_0 {
+                ki: KEYBDINPUT {
+                    wVk: VIRTUAL_KEY(vk),
+                    wScan: scan,
+                    dwFlags: flags,
+                    time: 0,
+                    dwExtraInfo: 0,
+                },
+            },
+        }
+    }
+
+    fn mouse(flags: MOUSE_EVENT_FLAGS) -> INPUT {
+        INPUT {
+            r#type: INPUT_MOUSE,
+            Anonymous: INPUT_0 {
+                mi: MOUSEINPUT {
+                    dwFlags: flags,
+                    ..Default::default()
+                },
+            },
+        }
+    }
+
+    let hwnd = main_window(pid).context("Instance has no visible window")?;
+    let inputs: Vec<INPUT> = match event {
+        InputEvent::Key {
+            key: name,
+            modifiers,
+        } => {
+            let vk = virtual_key(name).with_context(|| format!("Unknown key '{}'", name))?;
+            let held: Vec<u16> = [
+                (modifiers.ctrl, 0x11),
+                (modifiers.shift, 0x10),
+                (modifiers.alt, 0x12),
+                (modifiers.command, 0x5B),
+            ]
+            .iter()
+            .filter(|(on, _)| *on)
+            .map(|(_, vk)| *vk)
+            .collect();
+
+            let mut inputs: Vec<INPUT> = held
+                .iter()
+                .map(|&m| key(m, 0, KEYBD_EVENT_FLAGS(0)))
+                .collect();
+            inputs.push(key(vk, 0, KEYBD_EVENT_FLAGS(0)));
+            inputs.push(key(vk, 0, KEYEVENTF_KEYUP));
+            inputs.extend(held.iter().rev().map(|&m| key(m, 0, KEYEVENTF_KEYUP)));
+            inputs
+        }
+        InputEvent::Text(text) => text
+            .encode_utf16()
+            .flat_map(|unit| {
+                [
+                    key(0, unit, KEYEVENTF_UNICODE),
+                    key(0, unit, KEYEVENTF_UNICODE | KEYEVENTF_KEYUP),
+                ]
+            })
+            .collect(),
+        InputEvent::Click { x, y, button } => {
+            let mut rect = RECT::default();
+            unsafe {
+                GetWindowRect(hwnd, &mut rect).context("Failed to get window bounds")?;
+                SetCursorPos(rect.left + x, rect.top + y).context("Failed to move the cursor")?;
+            }
+            let (down, up) = match button {
+                MouseButton::Left => (MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP),
+                MouseButton::Right => (MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP),
+                MouseButton::Middle => (MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP),
+            };
+            vec![mouse(down), mouse(up)]
+        }
+    };
+
+    let sent = unsafe { SendInput(&inputs, mem::size_of::<INPUT>() as i32) };
+    if sent as usize != inputs.len() {
+        anyhow::bail!("SendInput was blocked (is the instance running elevated?)");
+    }
+    Ok(())
+}
+
+/// Input mirrored by the low-level hooks of `broadcast_input`
+struct Broadcast {
+    pids: Vec<u32>,
+    /// Virtual-key codes to mirror; empty mirrors every key
+    keys: std::collections::HashSet<u32>,
+    mouse: bool,
+}
+
+static BROADCAST: LazyLock<RwLock<Option<Broadcast>>> = LazyLock::new(|| RwLock::new(None));
+
+/// Thread running the message loop the low-level hooks are called from
+static BROADCAST_THREAD: LazyLock<RwLock<Option<u32>>> = LazyLock::new(|| RwLock::new(None));
+
+/// Mirror input with low-level keyboard and mouse hooks. Input typed into the
+/// focused window of a target is posted to the main windows of the others, so
+/// they don't have to be in the foreground.
+pub fn broadcast_input(targets: Option<&crate::core::broadcast::BroadcastTargets>) -> Result<()> {
+    use windows::Win32::Foundation::{HINSTANCE, LPARAM, WPARAM};
+
+    let Some(targets) = targets else {
+        if let Ok(mut broadcast) = BROADCAST.write() {
+            *broadcast = None;
+        }
+        if let Some(thread_id) = BROADCAST_THREAD.write().ok().and_then(|mut t| t.take()) {
+            unsafe {
+                let _ = PostThreadMessageW(thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+            }
+        }
+        return Ok(());
+    };
+
+    let keys = targets
+        .keys
+        .iter()
+        .map(|key| {
+            virtual_key(key)
+                .map(u32::from)
+                .with_context(|| format!("Unknown key '{}'", key))
+        })
+        .collect::<Result<_>>()?;
+    *BROADCAST
+        .write()
+        .map_err(|e| anyhow::anyhow!("Broadcast lock poisoned: {}", e))? = Some(Broadcast {
+        pids: targets.pids.clone(),
+        keys,
+        mouse: targets.mouse,
+    });
+
+    let mut hook_thread = BROADCAST_THREAD
+        .write()
+        .map_err(|e| anyhow::anyhow!("Broadcast lock poisoned: {}", e))?;
+    if hook_thread.is_none() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        thread::spawn(move || unsafe {
+            let keyboard =
+                SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_hook), HINSTANCE::default(), 0);
+            let mouse = SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_hook), HINSTANCE::default(), 0);
+            let installed = keyboard.is_ok() && mouse.is_ok();
+            let _ = tx.send(installed.then(|| GetCurrentThreadId()));
+            if installed {
+                // Low-level hooks are only called while this thread pumps messages
+                let mut msg = MSG::default();
+                while GetMessageW(&mut msg, HWND::default(), 0, 0).0 > 0 {}
+            }
+            for hook in [keyboard, mouse].into_iter().flatten() {
+                let _ = UnhookWindowsHookEx(hook);
+            }
+        });
+        let thread_id = rx.recv().ok().flatten();
+        if thread_id.is_none() {
+            if let Ok(mut broadcast) = BROADCAST.write() {
+                *broadcast = None;
+            }
+        }
+        *hook_thread = Some(thread_id.context("Failed to install input hooks")?);
+    }
+    Ok(())
+}
+
+/// Post a message to the main windows of the other targets when the focused
+/// window belongs to one of them
+unsafe fn mirror(mouse: bool, key: Option<u32>, post: impl Fn(HWND, HWND)) {
+    let Ok(broadcast) = BROADCAST.read() else {
+        return;
+    };
+    let Some(broadcast) = broadcast.as_ref() else {
+        return;
+    };
+    if mouse && !broadcast.mouse {
+        return;
+    }
+    if let Some(key) = key {
+        if !broadcast.keys.is_empty() && !broadcast.keys.contains(&key) {
+            return;
+        }
+    }
+
+    let source = GetForegroundWindow();
+    let mut source_pid: u32 = 0;
+    GetWindowThreadProcessId(source, Some(&mut source_pid));
+    if !broadcast.pids.contains(&source_pid) {
+        return;
+    }
+    for &pid in broadcast.pids.iter().filter(|&&pid| pid != source_pid) {
+        if let Some(target) = main_window(pid) {
+            post(source, target);
+        }
+    }
+}
+
+unsafe extern "system" fn keyboard_hook(
+    code: i32,
+    wparam: windows::Win32::Foundation::WPARAM,
+    lparam: windows::Win32::Foundation::LPARAM,
+) -> windows::Win32::Foundation::LRESULT {
+    use windows::Win32::Foundation::{LPARAM, WPARAM};
+
+    if code >= 0 {
+        let event = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
+        // Skip our own and other programs' injected input
+        if event.flags.0 & LLKHF_INJECTED.0 == 0 {
+            let message = wparam.0 as u32;
+            let up = matches!(message, WM_KEYUP | WM_SYSKEYUP);
+            // Repeat count 1, the scan code, and the previous/transition bits for key up
+            let key_lparam = 1 | ((event.scanCode & 0xFF) << 16) | if up { 0xC000_0000 } else { 0 };
+            mirror(false, Some(event.vkCode), |_, target| {
+                let _ = PostMessageW(
+                    target,
+                    message,
+                    WPARAM(event.vkCode as usize),
+                    LPARAM(key_lparam as isize),
+                );
+            });
+        }
+    }
+    CallNextHookEx(HHOOK::default(), code, wparam, lparam)
+}
+
+unsafe extern "system" fn mouse_hook(
+    code: i32,
+    wparam: windows::Win32::Foundation::WPARAM,
+    lparam: windows::Win32::Foundation::LPARAM,
+) -> windows::Win32::Foundation::LRESULT {
+    use windows::Win32::Foundation::{LPARAM, POINT, RECT, WPARAM};
+    use windows::Win32::Graphics::Gdi::ScreenToClient;
+
+    if code >= 0 {
+        let event = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+        let message = wparam.0 as u32;
+        let buttons = match message {
+            WM_LBUTTONDOWN => Some(0x0001),
+            WM_RBUTTONDOWN => Some(0x0002),
+            WM_MBUTTONDOWN => Some(0x0010),
+            WM_LBUTTONUP | WM_RBUTTONUP | WM_MBUTTONUP => Some(0),
+            _ => None,
+        };
+        if let (Some(buttons), true) = (buttons, event.flags & LLMHF_INJECTED == 0) {
+            mirror(true, None, |source, target| {
+                // Click at the same relative spot in windows of a different size
+                let mut point = POINT {
+                    x: event.pt.x,
+                    y: event.pt.y,
+                };
+                let (mut from, mut to) = (RECT::default(), RECT::default());
+                if !ScreenToClient(source, &mut point).as_bool()
+                    || GetClientRect(source, &mut from).is_err()
+                    || GetClientRect(target, &mut to).is_err()
+                    || from.right == 0
+                    || from.bottom == 0
+                {
+                    return;
+                }
+                let x = point.x * to.right / from.right;
+                let y = point.y * to.bottom / from.bottom;
+                let position = ((y as u32 & 0xFFFF) << 16) | (x as u32 & 0xFFFF);
+                let _ = PostMessageW(target, message, WPARAM(buttons), LPARAM(position as isize));
+            });
+        }
+    }
+    CallNextHookEx(HHOOK::default(), code, wparam, lparam)
+}
+
+/// Registry key the shell reads at logon to start programs
+const RUN_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Run";
+
+/// Register or remove a per-user logon entry in HKCU\...\Run
+pub fn set_autostart(app_name: &str, executable_path: &str, enabled: bool) -> Result<()> {
+    use winreg::enums::HKEY_CURRENT_USER;
+    use winreg::RegKey;
+
+    let (run_key, _) = RegKey::predef(HKEY_CURRENT_USER)
+        .create_subkey(RUN_KEY)
+        .context("Failed to open Run key")?;
+
+    if enabled {
+        run_key.set_value(app_name, &format!("\"{}\"", executable_path))?;
+    } else if run_key.get_value::<String, _>(app_name).is_ok() {
+        run_key.delete_value(app_name)?;
+    }
+    Ok(())
+}
+
+/// Check whether an installer registered a machine-wide logon entry in HKLM\...\Run
+pub fn machine_autostart_registered(app_name: &str) -> bool {
+    use winreg::enums::HKEY_LOCAL_MACHINE;
+    use winreg::RegKey;
+
+    RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey(RUN_KEY)
+        .and_then(|key| key.get_value::<String, _>(app_name))
+        .is_ok()
+}
+
+/// SID of an AppContainer profile, creating the profile on first use
+fn app_container_sid(container: &str) -> Result<windows::Win32::Security::PSID> {
+    use windows::core::HSTRING;
+    use windows::Win32::Security::Isolation::{
+        CreateAppContainerProfile, DeriveAppContainerSidFromAppContainerName,
+    };
+
+    let name = HSTRING::from(container);
+    unsafe {
+        match CreateAppContainerProfile(&name, &name, &HSTRING::from("MultiInstance sandbox"), None)
+        {
+            Ok(sid) => Ok(sid),
+            // The profile exists from an earlier launch
+            Err(_) => DeriveAppContainerSidFromAppContainerName(&name)
+                .with_context(|| format!("Failed to create AppContainer {}", container)),
+        }
+    }
+}
+
+/// Grant an AppContainer access to a folder and everything below it
+fn grant_app_container_access(sid: &str, path: &std::path::Path, rights: &str) -> Result<()> {
+    use std::os::windows::process::CommandExt;
+
+    let output = std::process::Command::new("icacls")
+        .arg(path)
+        .args(["/grant", &format!("*{}:(OI)(CI){}", sid, rights), "/Q"])
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .output()
+        .context("Failed to run icacls")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to grant access to {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stdout).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Replace a file's DACL with one granting access to its owner only, without
+/// inheriting entries from the parent folder
+pub fn restrict_to_owner(path: &std::path::Path) -> Result<()> {
+    use windows::core::{w, HSTRING};
+    use windows::Win32::Foundation::{LocalFree, BOOL, HLOCAL};
+    use windows::Win32::Security::Authorization::{
+        ConvertStringSecurityDescriptorToSecurityDescriptorW, SetNamedSecurityInfoW,
+        SDDL_REVISION_1, SE_FILE_OBJECT,
+    };
+    use windows::Win32::Security::{
+        GetSecurityDescriptorDacl, ACL, DACL_SECURITY_INFORMATION,
+        PROTECTED_DACL_SECURITY_INFORMATION, PSECURITY_DESCRIPTOR, PSID,
+    };
+
+    unsafe {
+        // Protected DACL with one entry: full access for the owner
+        let mut descriptor = PSECURITY_DESCRIPTOR::default();
+        ConvertStringSecurityDescriptorToSecurityDescriptorW(
+            w!("D:P(A;;FA;;;OW)"),
+            SDDL_REVISION_1,
+            &mut descriptor,
+            None,
+        )
+        .context("Failed to build the security descriptor")?;
+
+        let result = (|| -> Result<()> {
+            let mut present = BOOL::default();
+            let mut defaulted = BOOL::default();
+            let mut dacl: *mut ACL = std::ptr::null_mut();
+            GetSecurityDescriptorDacl(descriptor, &mut present, &mut dacl, &mut defaulted)
+                .context("Failed to read the DACL")?;
+            SetNamedSecurityInfoW(
+                &HSTRING::from(path.as_os_str()),
+                SE_FILE_OBJECT,
+                DACL_SECURITY_INFORMATION | PROTECTED_DACL_SECURITY_INFORMATION,
+                PSID::default(),
+                PSID::default(),
+                Some(dacl),
+                None,
+            )
+            .ok()
+            .with_context(|| format!("Failed to restrict access to {}", path.display()))
+        })();
+        let _ = LocalFree(HLOCAL(descriptor.0));
+        result
+    }
+}
+
+/// Quote one command line argument the way CommandLineToArgvW splits them
+fn quote_argument(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"']) {
+        return arg.to_string();
+    }
+    let mut quoted = String::from('"');
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                // Backslashes before a quote are doubled and the quote escaped
+                quoted.push_str(&"\\".repeat(backslashes + 1));
+                backslashes = 0;
+            }
+            _ => backslashes = 0,
+        }
+        quoted.push(c);
+    }
+    // As are those before the closing quote
+    quoted.push_str(&"\\".repeat(backslashes));
+    quoted.push('"');
+    quoted
+}
+
+/// Start a process in a per-instance AppContainer with the given capabilities.
+/// std::process cannot pass the security capabilities attribute, so the
+/// process is created directly and only its PID is returned.
+pub fn spawn_in_app_container(
+    container: &str,
+    command: &[String],
+    working_dir: &std::path::Path,
+    environment: &[(String, String)],
+    capabilities: &[&str],
+    writable: &[std::path::PathBuf],
+    readable: &[std::path::PathBuf],
+) -> Result<u32> {
+    use windows::core::{HSTRING, PWSTR};
+    use windows::Win32::Foundation::{LocalFree, HLOCAL};
+    use windows::Win32::Security::Authorization::{ConvertSidToStringSidW, ConvertStringSidToSidW};
+    use windows::Win32::Security::{FreeSid, PSID, SECURITY_CAPABILITIES, SID_AND_ATTRIBUTES};
+
+    const SE_GROUP_ENABLED: u32 = 0x4;
+    const PROC_THREAD_ATTRIBUTE_SECURITY_CAPABILITIES: usize = 0x20009;
+
+    let container_sid = app_container_sid(container)?;
+    let result = (|| -> Result<u32> {
+        let sid_string = unsafe {
+            let mut text = PWSTR::null();
+            ConvertSidToStringSidW(container_sid, &mut text)
+                .context("Failed to read the AppContainer SID")?;
+            let sid_string = text.to_string();
+            let _ = LocalFree(HLOCAL(text.0 as *mut std::ffi::c_void));
+            sid_string?
+        };
+        for path in writable {
+            grant_app_container_access(&sid_string, path, "(F)")?;
+        }
+        for path in readable {
+            grant_app_container_access(&sid_string, path, "(RX)")?;
+        }
+
+        let mut capability_sids = Vec::new();
+        for capability in capabilities {
+            let mut sid = PSID::default();
+            unsafe { ConvertStringSidToSidW(&HSTRING::from(*capability), &mut sid) }
+                .with_context(|| format!("Invalid capability SID {}", capability))?;
+            capability_sids.push(SID_AND_ATTRIBUTES {
+                Sid: sid,
+                Attributes: SE_GROUP_ENABLED,
+            });
+        }
+        let spawned = create_contained_process(
+            SECURITY_CAPABILITIES {
+                AppContainerSid: container_sid,
+                Capabilities: capability_sids.as_mut_ptr(),
+                CapabilityCount: capability_sids.len() as u32,
+                Reserved: 0,
+            },
+            command,
+            working_dir,
+            environment,
+            PROC_THREAD_ATTRIBUTE_SECURITY_CAPABILITIES,
+        );
+        for capability in capability_sids {
+            unsafe {
+                let _ = LocalFree(HLOCAL(capability.Sid.0));
+            }
+        }
+        spawned
+    })();
+    unsafe {
+        FreeSid(container_sid);
+    }
+    result
+}
+
+fn create_contained_process(
+    capabilities: windows::Win32::Security::SECURITY_CAPABILITIES,
+    command: &[String],
+    working_dir: &std::path::Path,
+    environment: &[(String, String)],
+    attribute: usize,
+) -> Result<u32> {
+    use windows::core::{HSTRING, PWSTR};
+
+    let mut size = 0usize;
+    unsafe {
+        // Fails by design, reporting the size the list needs
+        let _ = InitializeProcThreadAttributeList(
+            LPPROC_THREAD_ATTRIBUTE_LIST(std::ptr::null_mut()),
+            1,
+            0,
+            &mut size,
+        );
+    }
+    let mut list_buffer = vec![0u8; size];
+    let list = LPPROC_THREAD_ATTRIBUTE_LIST(list_buffer.as_mut_ptr() as *mut std::ffi::c_void);
+    unsafe {
+        InitializeProcThreadAttributeList(list, 1, 0, &mut size)
+            .context("Failed to initialize the attribute list")?;
+    }
+
+    let spawned = (|| -> Result<u32> {
+        unsafe {
+            UpdateProcThreadAttribute(
+                list,
+                0,
+                attribute,
+                Some(&capabilities as *const _ as *const std::ffi::c_void),
+                mem::size_of_val(&capabilities),
+                None,
+                None,
+            )
+            .context("Failed to set the AppContainer attribute")?;
+        }
+
+        let mut command_line: Vec<u16> = command
+            .iter()
+            .map(String::as_str)
+            .map(quote_argument)
+            .collect::<Vec<_>>()
+            .join(" ")
+            .encode_utf16()
+            .chain([0])
+            .collect();
+        let environment_block: Vec<u16> = environment
+            .iter()
+            .flat_map(|(key, value)| format!("{}={}", key, value).encode_utf16().chain([0]))
+            .chain([0])
+            .collect();
+
+        let mut startup = STARTUPINFOEXW::default();
+        startup.StartupInfo.cb = mem::size_of::<STARTUPINFOEXW>() as u32;
+        startup.lpAttributeList = list;
+        let mut info = PROCESS_INFORMATION::default();
+        unsafe {
+            CreateProcessW(
+                None,
+                PWSTR(command_line.as_mut_ptr()),
+                None,
+                None,
+                FALSE,
+                EXTENDED_STARTUPINFO_PRESENT | CREATE_UNICODE_ENVIRONMENT | DETACHED_PROCESS,
+                Some(environment_block.as_ptr() as *const std::ffi::c_void),
+                &HSTRING::from(working_dir.as_os_str()),
+                &startup.StartupInfo,
+                &mut info,
+            )
+            .context("Failed to start the process in its AppContainer")?;
+            let _ = CloseHandle(info.hThread);
+            let _ = CloseHandle(info.hProcess);
+        }
+        Ok(info.dwProcessId)
+    })();
+
+    unsafe {
+        DeleteProcThreadAttributeList(list);
+    }
+    spawned
+}
+
+/// Remove an AppContainer profile and the storage Windows kept for it
+pub fn delete_app_container(container: &str) -> Result<()> {
+    use windows::core::HSTRING;
+    use windows::Win32::Security::Isolation::DeleteAppContainerProfile;
+
+    unsafe { DeleteAppContainerProfile(&HSTRING::from(container)) }
+        .with_context(|| format!("Failed to delete AppContainer {}", container))
+}
+
+/// Activate a Store app through the application activation manager, which
+/// returns the PID of the app rather than of a launcher
+pub fn launch_packaged_app(app_id: &str, arguments: &str) -> Result<u32> {
+    use windows::core::HSTRING;
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CLSCTX_LOCAL_SERVER, COINIT_APARTMENTTHREADED,
+    };
+    use windows::Win32::UI::Shell::{
+        ApplicationActivationManager, IApplicationActivationManager, AO_NONE,
+    };
+
+    unsafe {
+        // Fails harmlessly when the thread already joined an apartment
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+        let manager: IApplicationActivationManager =
+            CoCreateInstance(&ApplicationActivationManager, None, CLSCTX_LOCAL_SERVER)
+                .context("Failed to create the application activation manager")?;
+        let pid = manager
+            .ActivateApplication(&HSTRING::from(app_id), &HSTRING::from(arguments), AO_NONE)
+            .with_context(|| format!("Failed to activate {}", app_id))?;
+        Ok(pid)
+    }
+}
+
+/// Store apps from the Start menu; only packaged apps have a `!` in their ID
+pub fn list_packaged_apps() -> Result<Vec<crate::core::packaged_apps::PackagedApp>> {
+    use std::os::windows::process::CommandExt;
+
+    let script = "ConvertTo-Json -Compress -InputObject @(Get-StartApps | \
+                  Where-Object { $_.AppID -like '*!*' } | Select-Object Name, AppID)";
+    let output = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .output()
+        .context("Failed to run PowerShell")?;
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    serde_json::from_slice(&output.stdout).context("Unexpected Get-StartApps output")
+}
+
+/// Toast notification shown through PowerShell's WinRT bindings, under
+/// PowerShell's app ID since ours is not registered with the Start menu
+pub fn show_notification(title: &str, body: &str) -> Result<()> {
+    use std::os::windows::process::CommandExt;
+
+    let quote = |text: &str| format!("'{}'", text.replace('\'', "''"));
+    let script = format!(
+        "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] > $null; \
+         $xml = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02); \
+         $text = $xml.GetElementsByTagName('text'); \
+         $text.Item(0).AppendChild($xml.CreateTextNode({})) > $null; \
+         $text.Item(1).AppendChild($xml.CreateTextNode({})) > $null; \
+         $appId = '{{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}}\\WindowsPowerShell\\v1.0\\powershell.exe'; \
+         [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier($appId).Show([Windows.UI.Notifications.ToastNotification]::new($xml))",
+        quote(&format!("MultiInstance: {}", title)),
+        quote(body)
+    );
+    let output = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .output()
+        .context("Failed to run PowerShell")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Toast notification failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Name of the QoS policy limiting a process
+fn qos_policy_name(pid: u32) -> String {
+    format!("MultiInstance-{}", pid)
+}
+
+/// Run a PowerShell command without a console window, failing with its error output
+fn run_powershell(script: &str) -> Result<()> {
+    use std::os::windows::process::CommandExt;
+
+    let output = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .output()
+        .context("Failed to run PowerShell")?;
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Throttle outbound traffic with a QoS policy in the active store, which is
+/// gone after a reboot, replacing the policy an earlier call created for `pid`.
+/// Policies match the executable name, not the process. Needs administrator rights.
+pub fn set_network_limit(pid: u32, executable: &std::path::Path, kbps: u64) -> Result<()> {
+    let quote = |text: &str| format!("'{}'", text.replace('\'', "''"));
+    let name = executable
+        .file_name()
+        .context("Executable path has no file name")?
+        .to_string_lossy();
+    let policy = quote(&qos_policy_name(pid));
+    run_powershell(&format!(
+        "Remove-NetQosPolicy -Name {policy} -PolicyStore ActiveStore -Confirm:$false \
+         -ErrorAction SilentlyContinue; \
+         New-NetQosPolicy -Name {policy} -AppPathNameMatchCondition {} \
+         -ThrottleRateActionBitsPerSecond {} -PolicyStore ActiveStore -Confirm:$false | Out-Null",
+        quote(&name),
+        kbps * 1024 * 8
+    ))
+    .context("Failed to create QoS policy (needs administrator rights)")
+}
+
+/// Remove the QoS policy created by `set_network_limit`
+pub fn clear_network_limit(pid: u32) -> Result<()> {
+    run_powershell(&format!(
+        "Remove-NetQosPolicy -Name '{}' -PolicyStore ActiveStore -Confirm:$false",
+        qos_policy_name(pid)
+    ))
+    .context("Failed to remove QoS policy")
+}
+
+/// Play the system warning sound
+pub fn play_alert_sound() -> Result<()> {
+    unsafe { MessageBeep(MB_ICONWARNING)? };
+    Ok(())
+}
+
+/// SSID of the connected Wi-Fi network, as reported by `netsh wlan`
+pub fn current_wifi_ssid() -> Option<String> {
+    use std::os::windows::process::CommandExt;
+
+    let output = std::process::Command::new("netsh")
+        .args(["wlan", "show", "interfaces"])
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            (key.trim() == "SSID")
+                .then(|| value.trim().to_string())
+                .filter(|ssid| !ssid.is_empty())
+        })
+}
+
+/// Crash dumps and error reports Windows Error Reporting wrote for a process
+/// since `since`: the `<exe>.<pid>.dmp` that LocalDumps saves to CrashDumps, and
+/// the AppCrash report folders of the executable
+pub fn crash_dumps(
+    executable: &std::path::Path,
+    pid: u32,
+    since: std::time::SystemTime,
+) -> Vec<std::path::PathBuf> {
+    let (Some(local), Some(exe)) = (
+        dirs::data_local_dir(),
+        executable
+            .file_name()
+            .map(|n| n.to_string_lossy().to_lowercase()),
+    ) else {
+        return Vec::new();
+    };
+
+    let mut found = Vec::new();
+    let dump = local
+        .join("CrashDumps")
+        .join(format!("{}.{}.dmp", exe, pid));
+    if dump.exists() {
+        found.push(dump);
+    }
+
+    let prefix = format!("appcrash_{}", exe);
+    let wer = local.join("Microsoft").join("Windows").join("WER");
+    for folder in ["ReportQueue", "ReportArchive"] {
+        let Ok(entries) = std::fs::read_dir(wer.join(folder)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_lowercase();
+            let recent = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .is_ok_and(|modified| modified >= since);
+            if name.starts_with(&prefix) && recent {
+                found.push(entry.path());
+            }
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prune_job_handles_closes_empty_jobs() {
+        // Not a real process ID, so no other test touches this entry
+        let pid = u32::MAX - 7;
+        let job = unsafe { CreateJobObjectW(None, None) }.expect("create job object");
+        store_job_handle(pid, job);
+        assert!(JOB_HANDLES.read().unwrap().contains_key(&pid));
+
+        assert!(prune_job_handles() >= 1);
+        assert!(!JOB_HANDLES.read().unwrap().contains_key(&pid));
+    }
+}
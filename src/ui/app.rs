@@ -1,14 +1,33 @@
 //! Main application UI
 
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use anyhow::Context as _;
 use egui::{CentralPanel, Context, SidePanel, TopBottomPanel};
 use tracing::{error, info};
 
+use super::dialogs::migration::MigrationWizard;
 use super::dialogs::{self, DialogState};
 use super::panels;
+use super::panels::group_dashboard::GroupDashboard;
+use super::panels::history::HistoryView;
+use super::search::InstanceSearch;
+use super::switcher::QuickSwitcher;
 use super::theme::Theme;
-use crate::core::{AppState, InstanceConfig, InstanceId};
+use crate::core::alerts::{Alert, AlertEvent};
+use crate::core::hotkeys::HotkeyService;
+use crate::core::ipc::{self, Activation};
+use crate::core::resource::format_bytes;
+use crate::core::settings::{DetachedView, SidebarLayout};
+use crate::core::usage::UsageEvent;
+use crate::core::window_layout::WindowLayout;
+use crate::core::{
+    AppState, HealthEvent, InstanceConfig, InstanceId, InstanceStatus, ProfileId, RunLimitEvent,
+};
+
+/// Id of the top bar search box, focused by Ctrl+F
+const SEARCH_BOX_ID: &str = "top_bar_search";
 
 /// Active view/tab in the main panel
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -17,6 +36,7 @@ pub enum ActiveView {
     Dashboard,
     Instances,
     Profiles,
+    Schedules,
     Settings,
     History,
 }
@@ -27,10 +47,87 @@ impl ActiveView {
             Self::Dashboard => "Dashboard",
             Self::Instances => "Instances",
             Self::Profiles => "Profiles",
+            Self::Schedules => "Schedules",
             Self::Settings => "Settings",
             Self::History => "History",
         }
     }
+
+    pub fn all() -> [Self; 6] {
+        [
+            Self::Dashboard,
+            Self::Instances,
+            Self::Profiles,
+            Self::Schedules,
+            Self::Settings,
+            Self::History,
+        ]
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Self::Dashboard => "◉",
+            Self::Instances => "▣",
+            Self::Profiles => "▤",
+            Self::Schedules => "◴",
+            Self::Settings => "⚙",
+            Self::History => "◷",
+        }
+    }
+
+    /// Every view in the sidebar order of `layout`, hidden ones included
+    pub fn ordered(layout: &SidebarLayout) -> Vec<Self> {
+        let mut views: Vec<Self> = Vec::new();
+        let listed = layout
+            .order
+            .iter()
+            .filter_map(|label| Self::from_label(label));
+        for view in listed.chain(Self::all()) {
+            if !views.contains(&view) {
+                views.push(view);
+            }
+        }
+        views
+    }
+
+    /// Views shown in the sidebar, in its order
+    pub fn visible(layout: &SidebarLayout) -> Vec<Self> {
+        Self::ordered(layout)
+            .into_iter()
+            .filter(|v| !v.hideable() || !layout.is_hidden(v.label()))
+            .collect()
+    }
+
+    /// Settings always stays in the sidebar, as that is where hidden entries
+    /// are shown again
+    pub fn hideable(&self) -> bool {
+        *self != Self::Settings
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::all().into_iter().find(|v| v.label() == label)
+    }
+
+    /// Whether the view can be opened in its own window. Settings stays in the
+    /// main window, behind its PIN lock.
+    pub fn detachable(&self) -> bool {
+        *self != Self::Settings
+    }
+
+    /// Viewport of the view's own window
+    fn viewport_id(&self) -> egui::ViewportId {
+        egui::ViewportId::from_hash_of(("detached_view", self.label()))
+    }
+}
+
+/// A view open in its own window
+struct DetachedWindow {
+    view: ActiveView,
+    /// Geometry the window opened with; egui moves the window whenever this
+    /// changes, so it stays fixed while the window is open
+    opened: DetachedView,
+    /// Outer position and inner size now, saved with the settings
+    placed: Option<(egui::Pos2, egui::Vec2)>,
 }
 
 /// Main application struct
@@ -41,8 +138,8 @@ pub struct MultiInstanceApp {
     active_view: ActiveView,
     /// Dialog state
     dialog: DialogState,
-    /// Search filter text
-    search_query: String,
+    /// Search box and the instances it matches
+    search: InstanceSearch,
     /// Selected instance for details panel
     selected_instance: Option<InstanceId>,
     /// Last resource update time
@@ -57,6 +154,26 @@ pub struct MultiInstanceApp {
     new_instance_config: Option<InstanceConfig>,
     /// First frame flag
     first_frame: bool,
+    /// Settings PIN was entered this session
+    settings_unlocked: bool,
+    /// The orphaned process cleanup dialog was already shown
+    orphans_prompted: bool,
+    /// Global hotkeys, if the platform supports them
+    hotkeys: Option<HotkeyService>,
+    /// History panel filters and loaded events
+    history: HistoryView,
+    /// Group dashboard shown in place of the instance list
+    group_page: Option<GroupDashboard>,
+    /// Open migration export or import wizard
+    migration: Option<MigrationWizard>,
+    /// Columns and rows of the custom window grid
+    layout_grid: (u32, u32),
+    /// Window layout applied last, offered for saving to a profile
+    last_layout: WindowLayout,
+    /// Views shown in their own windows
+    detached: Vec<DetachedWindow>,
+    /// Hotkey-invoked picker of running instances
+    switcher: QuickSwitcher,
 }
 
 /// Notification message
@@ -79,23 +196,49 @@ impl MultiInstanceApp {
     pub fn new(cc: &eframe::CreationContext<'_>, state: AppState) -> Self {
         // Apply theme
         let settings = state.settings.read().unwrap();
-        match settings.theme {
-            crate::core::settings::Theme::Dark => Theme::apply_dark(&cc.egui_ctx),
-            crate::core::settings::Theme::Light => Theme::apply_light(&cc.egui_ctx),
-            crate::core::settings::Theme::System => {
-                // Default to dark for now
-                Theme::apply_dark(&cc.egui_ctx);
-            }
-        }
+        let custom_theme = settings
+            .custom_theme
+            .as_deref()
+            .and_then(|name| state.custom_theme(name));
+        Theme::apply(&cc.egui_ctx, settings.theme, custom_theme.as_ref());
         let show_system_resources = settings.show_system_resources;
         let update_interval = Duration::from_millis(settings.monitor_interval_ms as u64);
+        let detached: Vec<DetachedWindow> = settings
+            .detached_views
+            .iter()
+            .filter_map(|saved| {
+                let view = ActiveView::from_label(&saved.view).filter(|v| v.detachable())?;
+                Some(DetachedWindow {
+                    view,
+                    opened: saved.clone(),
+                    placed: None,
+                })
+            })
+            .collect();
         drop(settings);
 
-        Self {
+        // Ask what to restore when the previous session is waiting for a choice
+        let dialog = match state.pending_session.read() {
+            Ok(pending) if !pending.is_empty() => DialogState::SessionRestore {
+                selected: pending.iter().map(|c| c.executable_exists()).collect(),
+                remember: false,
+            },
+            _ => DialogState::None,
+        };
+
+        let hotkeys = match HotkeyService::new(state.clone(), cc.egui_ctx.clone()) {
+            Ok(service) => Some(service),
+            Err(e) => {
+                error!("{}", e);
+                None
+            }
+        };
+
+        let mut app = Self {
             state,
             active_view: ActiveView::Dashboard,
-            dialog: DialogState::None,
-            search_query: String::new(),
+            dialog,
+            search: InstanceSearch::default(),
             selected_instance: None,
             last_update: Instant::now(),
             update_interval,
@@ -103,6 +246,78 @@ impl MultiInstanceApp {
             notifications: Vec::new(),
             new_instance_config: None,
             first_frame: true,
+            settings_unlocked: false,
+            orphans_prompted: false,
+            hotkeys,
+            history: HistoryView::default(),
+            group_page: None,
+            migration: None,
+            layout_grid: (2, 2),
+            last_layout: WindowLayout::Tile,
+            detached,
+            switcher: QuickSwitcher::default(),
+        };
+        if app.is_detached(app.active_view) {
+            app.active_view = app.first_attached_view();
+        }
+        app
+    }
+
+    fn is_detached(&self, view: ActiveView) -> bool {
+        self.detached.iter().any(|d| d.view == view)
+    }
+
+    /// First view still shown in the main window; Settings never leaves it
+    fn first_attached_view(&self) -> ActiveView {
+        ActiveView::all()
+            .into_iter()
+            .find(|v| !self.is_detached(*v))
+            .unwrap_or(ActiveView::Settings)
+    }
+
+    /// Move a view into its own window
+    fn detach_view(&mut self, view: ActiveView) {
+        if !view.detachable() || self.is_detached(view) {
+            return;
+        }
+        self.detached.push(DetachedWindow {
+            view,
+            opened: DetachedView {
+                view: view.label().to_string(),
+                position: None,
+                size: None,
+            },
+            placed: None,
+        });
+        if self.active_view == view {
+            self.active_view = self.first_attached_view();
+        }
+        self.store_detached_views();
+    }
+
+    /// Close a view's own window and show it in the main window again
+    fn attach_view(&mut self, view: ActiveView) {
+        self.detached.retain(|d| d.view != view);
+        self.active_view = view;
+        self.store_detached_views();
+    }
+
+    /// Copy the detached views and where their windows are into the settings
+    fn store_detached_views(&self) {
+        let views = self
+            .detached
+            .iter()
+            .map(|d| match d.placed {
+                Some((position, size)) => DetachedView {
+                    view: d.view.label().to_string(),
+                    position: Some((position.x, position.y)),
+                    size: Some((size.x, size.y)),
+                },
+                None => d.opened.clone(),
+            })
+            .collect();
+        if let Ok(mut settings) = self.state.settings.write() {
+            settings.detached_views = views;
         }
     }
 
@@ -115,16 +330,169 @@ impl MultiInstanceApp {
         });
     }
 
+    /// Route an alert through the notification rules, showing a toast if one is wanted
+    fn alert(&mut self, alert: Alert) {
+        let level = match alert.event {
+            AlertEvent::Crashed | AlertEvent::CrashLoop | AlertEvent::HostMemory => {
+                NotificationLevel::Error
+            }
+            AlertEvent::Unhealthy | AlertEvent::RunLimit | AlertEvent::UsageLimit => {
+                NotificationLevel::Warning
+            }
+            AlertEvent::Exited | AlertEvent::Recovered => NotificationLevel::Info,
+        };
+        let message = alert.message.clone();
+        if self.state.raise_alert(alert) {
+            self.notify(message, level);
+        }
+    }
+
     /// Update resources if needed
     fn update_resources(&mut self) {
+        // The monitor schedules each instance at its own interval, so it is polled every frame
+        self.state.update_resources();
+        for result in self.state.take_bundle_results() {
+            match result {
+                Ok(message) => self.notify(message, NotificationLevel::Success),
+                Err(message) => self.notify(message, NotificationLevel::Error),
+            }
+        }
+        for exit in self.state.take_exit_events() {
+            if exit.crash_loop {
+                self.alert(Alert::new(
+                    AlertEvent::CrashLoop,
+                    Some(exit.id),
+                    format!("'{}' keeps crashing", exit.name),
+                ));
+            }
+            let alert = if exit.crashed {
+                let reason = exit.error.unwrap_or_else(|| "unknown error".to_string());
+                Alert::new(
+                    AlertEvent::Crashed,
+                    Some(exit.id),
+                    format!("'{}' crashed: {}", exit.name, reason),
+                )
+            } else {
+                Alert::new(
+                    AlertEvent::Exited,
+                    Some(exit.id),
+                    format!("'{}' exited", exit.name),
+                )
+            };
+            self.alert(alert);
+        }
+
         let now = Instant::now();
         if now.duration_since(self.last_update) >= self.update_interval {
-            self.state.update_resources();
+            self.state.discover_system_copies();
+            self.state.prune_logs();
+            self.apply_hotkeys();
+            if let Some(message) = self.state.check_self_budget() {
+                self.notify(message, NotificationLevel::Warning);
+            }
+            if let Some(percent) = self.state.check_host_memory() {
+                self.alert(Alert::new(
+                    AlertEvent::HostMemory,
+                    None,
+                    format!("Host memory is {:.0}% used", percent),
+                ));
+            }
+            self.state.check_executable_updates();
+            self.state.dismiss_crash_dialogs();
+            self.state.rename_windows();
             self.state.handle_auto_restarts();
+            self.state.check_pending_launches();
+            self.state.capture_pinned_regions();
+            self.state.run_health_checks();
+            self.state.verify_isolation();
+            self.state.reconcile_handles();
+            self.state.run_due_macros();
+            self.state.run_due_schedules();
+            self.state.apply_limit_schedules();
+            if let Err(e) = self.state.update_broadcast() {
+                self.notify(
+                    format!("Input broadcasting stopped: {}", e),
+                    NotificationLevel::Error,
+                );
+            }
+            for event in self.state.take_health_events() {
+                let alert = match event {
+                    HealthEvent::Unhealthy { id, name, reason } => Alert::new(
+                        AlertEvent::Unhealthy,
+                        Some(id),
+                        format!("'{}' looks unhealthy: {}", name, reason),
+                    ),
+                    HealthEvent::Recovered { id, name } => Alert::new(
+                        AlertEvent::Recovered,
+                        Some(id),
+                        format!("'{}' is healthy again", name),
+                    ),
+                };
+                self.alert(alert);
+            }
+            self.state.advance_rolling_restarts();
+            self.state.update_shutdown_marker();
+            self.state.refresh_data_lock();
+            self.state.sync_profiles();
+            for event in self.state.enforce_run_limits() {
+                let alert = match event {
+                    RunLimitEvent::Expiring {
+                        id,
+                        name,
+                        remaining_secs,
+                    } => Alert::new(
+                        AlertEvent::RunLimit,
+                        Some(id),
+                        format!("'{}' will stop in {}s (run limit)", name, remaining_secs),
+                    ),
+                    RunLimitEvent::Expired { id, name } => Alert::new(
+                        AlertEvent::RunLimit,
+                        Some(id),
+                        format!("'{}' stopped: run limit reached", name),
+                    ),
+                };
+                self.alert(alert);
+            }
+            for event in self.state.enforce_usage_limits() {
+                let message = match event {
+                    UsageEvent::Warning {
+                        executable,
+                        used_mins,
+                        limit_mins,
+                    } => format!(
+                        "{} has used {} of {} minutes today",
+                        executable, used_mins, limit_mins
+                    ),
+                    UsageEvent::Exhausted {
+                        executable,
+                        stopped,
+                    } => format!(
+                        "Daily limit for {} reached: stopped {} instance(s)",
+                        executable, stopped
+                    ),
+                };
+                self.alert(Alert::new(AlertEvent::UsageLimit, None, message));
+            }
             self.last_update = now;
         }
     }
 
+    /// Register the hotkeys in settings when they have changed
+    fn apply_hotkeys(&mut self) {
+        let Some(service) = self.hotkeys.as_mut() else {
+            return;
+        };
+        let bindings = match self.state.settings.read() {
+            Ok(settings) => settings.hotkeys.clone(),
+            Err(_) => return,
+        };
+        if let Some(errors) = service.apply(&bindings) {
+            if let Ok(mut current) = self.state.hotkey_errors.write() {
+                *current = errors;
+            }
+        }
+    }
+
     /// Clean up old notifications
     fn cleanup_notifications(&mut self) {
         let timeout = Duration::from_secs(5);
@@ -134,55 +502,76 @@ impl MultiInstanceApp {
 
     /// Render the sidebar navigation
     fn render_sidebar(&mut self, ctx: &Context) {
+        let (mut collapsed, layout) = match self.state.settings.read() {
+            Ok(settings) => (settings.sidebar_collapsed, settings.sidebar.clone()),
+            Err(_) => (false, SidebarLayout::default()),
+        };
+        let mut layout_changed = None;
+
         SidePanel::left("sidebar")
             .resizable(false)
-            .default_width(220.0)
+            .exact_width(if collapsed { 64.0 } else { 220.0 })
             .frame(
                 egui::Frame::none()
-                    .fill(Theme::BG_SECONDARY)
-                    .stroke(egui::Stroke::new(1.0, Theme::BORDER_LIGHT)),
+                    .fill(Theme::bg_secondary())
+                    .stroke(egui::Stroke::new(1.0, Theme::border_light())),
             )
             .show(ctx, |ui| {
                 ui.add_space(20.0);
 
                 // Logo/Title with icon
                 ui.horizontal(|ui| {
-                    ui.add_space(16.0);
-                    ui.label(egui::RichText::new("◈").size(24.0).color(Theme::PRIMARY));
-                    ui.add_space(8.0);
-                    ui.label(
-                        egui::RichText::new("MultiInstance")
-                            .size(18.0)
-                            .strong()
-                            .color(Theme::TEXT_PRIMARY),
-                    );
+                    ui.add_space(if collapsed { 20.0 } else { 16.0 });
+                    ui.label(egui::RichText::new("◈").size(24.0).color(Theme::primary()));
+                    if !collapsed {
+                        ui.add_space(8.0);
+                        ui.label(
+                            egui::RichText::new("MultiInstance")
+                                .size(18.0)
+                                .strong()
+                                .color(Theme::text_primary()),
+                        );
+                    }
                 });
 
-                ui.add_space(24.0);
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    ui.add_space(if collapsed { 20.0 } else { 16.0 });
+                    let (icon, hint) = if collapsed {
+                        ("»", "Expand sidebar")
+                    } else {
+                        ("«", "Collapse to icons")
+                    };
+                    if ui.small_button(icon).on_hover_text(hint).clicked() {
+                        collapsed = !collapsed;
+                        if let Ok(mut settings) = self.state.settings.write() {
+                            settings.sidebar_collapsed = collapsed;
+                        }
+                    }
+                });
+
+                ui.add_space(16.0);
 
-                // Navigation items with custom styling
-                let views = [
-                    (ActiveView::Dashboard, "◉", "Dashboard"),
-                    (ActiveView::Instances, "▣", "Instances"),
-                    (ActiveView::Profiles, "▤", "Profiles"),
-                    (ActiveView::Settings, "⚙", "Settings"),
-                    (ActiveView::History, "◷", "History"),
-                ];
+                // Navigation items with custom styling, in the user's order
+                let ordered = ActiveView::ordered(&layout);
+                let visible = ActiveView::visible(&layout);
 
                 ui.add_space(4.0);
-                for (view, icon, label) in views {
+                for (position, &view) in visible.iter().enumerate() {
+                    let (icon, label) = (view.icon(), view.label());
                     let selected = self.active_view == view;
+                    let detached = self.is_detached(view);
 
                     let bg_color = if selected {
-                        Theme::PRIMARY.linear_multiply(0.15)
+                        Theme::primary().linear_multiply(0.15)
                     } else {
                         egui::Color32::TRANSPARENT
                     };
 
                     let text_color = if selected {
-                        Theme::PRIMARY_LIGHT
+                        Theme::primary_light()
                     } else {
-                        Theme::TEXT_SECONDARY
+                        Theme::text_secondary()
                     };
 
                     let frame = egui::Frame::none()
@@ -202,27 +591,98 @@ impl MultiInstanceApp {
                                 ui.painter().rect_filled(
                                     rect,
                                     egui::Rounding::same(2.0),
-                                    Theme::PRIMARY,
+                                    Theme::primary(),
                                 );
                                 ui.add_space(8.0);
                             }
                             ui.label(egui::RichText::new(icon).size(16.0).color(text_color));
+                            if collapsed {
+                                return;
+                            }
                             ui.add_space(12.0);
                             ui.label(egui::RichText::new(label).size(14.0).color(text_color));
+                            if detached {
+                                ui.add_space(6.0);
+                                ui.label(
+                                    egui::RichText::new("⧉")
+                                        .size(12.0)
+                                        .color(Theme::text_muted()),
+                                )
+                                .on_hover_text("Open in its own window");
+                            }
                         });
                     });
 
-                    if response.response.interact(egui::Sense::click()).clicked() {
-                        self.active_view = view;
+                    let mut response = response.response.interact(egui::Sense::click());
+                    if collapsed {
+                        response = response.on_hover_text(label);
+                    }
+                    if response.clicked() {
+                        self.open_view(ctx, view);
                     }
+                    response.context_menu(|ui| {
+                        if view.detachable() {
+                            if self.is_detached(view) {
+                                if ui.button("Return to Main Window").clicked() {
+                                    self.attach_view(view);
+                                    ui.close_menu();
+                                }
+                            } else if ui.button("Open in New Window").clicked() {
+                                self.detach_view(view);
+                                ui.close_menu();
+                            }
+                            ui.separator();
+                        }
+
+                        // Swap with the visible neighbour, keeping hidden entries in place
+                        let mut neighbour = None;
+                        if ui
+                            .add_enabled(position > 0, egui::Button::new("Move Up"))
+                            .clicked()
+                        {
+                            neighbour = Some(visible[position - 1]);
+                        }
+                        if ui
+                            .add_enabled(
+                                position + 1 < visible.len(),
+                                egui::Button::new("Move Down"),
+                            )
+                            .clicked()
+                        {
+                            neighbour = Some(visible[position + 1]);
+                        }
+                        if let Some(other) = neighbour {
+                            let mut order = ordered.clone();
+                            if let (Some(a), Some(b)) = (
+                                order.iter().position(|v| *v == view),
+                                order.iter().position(|v| *v == other),
+                            ) {
+                                order.swap(a, b);
+                            }
+                            let mut changed = layout.clone();
+                            changed.order = order.iter().map(|v| v.label().to_string()).collect();
+                            layout_changed = Some(changed);
+                            ui.close_menu();
+                        }
+                        if view.hideable() && ui.button("Hide from Sidebar").clicked() {
+                            let mut changed = layout.clone();
+                            changed.set_hidden(label, true);
+                            layout_changed = Some(changed);
+                            ui.close_menu();
+                        }
+                    });
 
-                    if response.response.interact(egui::Sense::hover()).hovered() && !selected {
+                    if response.hovered() && !selected {
                         ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
                     }
 
                     ui.add_space(2.0);
                 }
 
+                if collapsed {
+                    return;
+                }
+
                 ui.add_space(20.0);
 
                 // Divider line
@@ -232,7 +692,7 @@ impl MultiInstanceApp {
                         egui::vec2(ui.available_width() - 32.0, 1.0),
                         egui::Sense::hover(),
                     );
-                    ui.painter().rect_filled(rect, 0.0, Theme::BORDER_LIGHT);
+                    ui.painter().rect_filled(rect, 0.0, Theme::border_light());
                 });
 
                 ui.add_space(16.0);
@@ -243,7 +703,7 @@ impl MultiInstanceApp {
                     ui.label(
                         egui::RichText::new("QUICK STATS")
                             .small()
-                            .color(Theme::TEXT_MUTED),
+                            .color(Theme::text_muted()),
                     );
                 });
                 ui.add_space(12.0);
@@ -254,7 +714,7 @@ impl MultiInstanceApp {
 
                 // Stats cards
                 egui::Frame::none()
-                    .fill(Theme::BG_TERTIARY.linear_multiply(0.5))
+                    .fill(Theme::bg_tertiary().linear_multiply(0.5))
                     .rounding(egui::Rounding::same(8.0))
                     .inner_margin(egui::Margin::same(12.0))
                     .outer_margin(egui::Margin::symmetric(16.0, 0.0))
@@ -265,12 +725,12 @@ impl MultiInstanceApp {
                                     egui::RichText::new(format!("{}", active))
                                         .size(20.0)
                                         .strong()
-                                        .color(Theme::SUCCESS),
+                                        .color(Theme::success()),
                                 );
                                 ui.label(
                                     egui::RichText::new("Running")
                                         .small()
-                                        .color(Theme::TEXT_MUTED),
+                                        .color(Theme::text_muted()),
                                 );
                             });
                             ui.add_space(24.0);
@@ -279,12 +739,12 @@ impl MultiInstanceApp {
                                     egui::RichText::new(format!("{}", total))
                                         .size(20.0)
                                         .strong()
-                                        .color(Theme::TEXT_PRIMARY),
+                                        .color(Theme::text_primary()),
                                 );
                                 ui.label(
                                     egui::RichText::new("Total")
                                         .small()
-                                        .color(Theme::TEXT_MUTED),
+                                        .color(Theme::text_muted()),
                                 );
                             });
                             ui.add_space(24.0);
@@ -293,12 +753,12 @@ impl MultiInstanceApp {
                                     egui::RichText::new(format!("{}", profiles))
                                         .size(20.0)
                                         .strong()
-                                        .color(Theme::INFO),
+                                        .color(Theme::info()),
                                 );
                                 ui.label(
                                     egui::RichText::new("Profiles")
                                         .small()
-                                        .color(Theme::TEXT_MUTED),
+                                        .color(Theme::text_muted()),
                                 );
                             });
                         });
@@ -312,12 +772,24 @@ impl MultiInstanceApp {
                         ui.label(
                             egui::RichText::new(format!("v{}", crate::APP_VERSION))
                                 .small()
-                                .color(Theme::TEXT_MUTED),
+                                .color(Theme::text_muted()),
                         );
                     });
                     ui.add_space(8.0);
                 });
             });
+
+        if let Some(layout) = layout_changed {
+            if layout.is_hidden(self.active_view.label()) && self.active_view.hideable() {
+                self.active_view = ActiveView::ordered(&layout)
+                    .into_iter()
+                    .find(|v| !layout.is_hidden(v.label()) && !self.is_detached(*v))
+                    .unwrap_or(ActiveView::Settings);
+            }
+            if let Ok(mut settings) = self.state.settings.write() {
+                settings.sidebar = layout;
+            }
+        }
     }
 
     /// Render the top bar with actions
@@ -325,8 +797,8 @@ impl MultiInstanceApp {
         TopBottomPanel::top("top_bar")
             .frame(
                 egui::Frame::none()
-                    .fill(Theme::BG_PRIMARY)
-                    .stroke(egui::Stroke::new(1.0, Theme::BORDER_LIGHT))
+                    .fill(Theme::bg_primary())
+                    .stroke(egui::Stroke::new(1.0, Theme::border_light()))
                     .inner_margin(egui::Margin::symmetric(20.0, 12.0)),
             )
             .show(ctx, |ui| {
@@ -336,9 +808,32 @@ impl MultiInstanceApp {
                         egui::RichText::new(self.active_view.label())
                             .size(24.0)
                             .strong()
-                            .color(Theme::TEXT_PRIMARY),
+                            .color(Theme::text_primary()),
                     );
 
+                    if self.active_view.detachable()
+                        && ui
+                            .small_button("⧉")
+                            .on_hover_text("Open in a new window, e.g. on another monitor")
+                            .clicked()
+                    {
+                        self.detach_view(self.active_view);
+                    }
+
+                    if self.state.safe_mode() {
+                        ui.add_space(12.0);
+                        ui.label(
+                            egui::RichText::new("\u{26A0} Safe Mode")
+                                .strong()
+                                .color(Theme::warning()),
+                        )
+                        .on_hover_text(
+                            "Started with --safe-mode: using a temporary database, so your \
+                             instances and settings are untouched and nothing here is saved. \
+                             Restart normally to return to them.",
+                        );
+                    }
+
                     ui.add_space(24.0);
 
                     // Search box (for instances/profiles views)
@@ -347,24 +842,28 @@ impl MultiInstanceApp {
                         ActiveView::Instances | ActiveView::Profiles
                     ) {
                         egui::Frame::none()
-                            .fill(Theme::BG_SECONDARY)
+                            .fill(Theme::bg_secondary())
                             .rounding(egui::Rounding::same(8.0))
-                            .stroke(egui::Stroke::new(1.0, Theme::BORDER_LIGHT))
+                            .stroke(egui::Stroke::new(1.0, Theme::border_light()))
                             .inner_margin(egui::Margin::symmetric(12.0, 8.0))
                             .show(ui, |ui| {
                                 ui.horizontal(|ui| {
                                     ui.label(
                                         egui::RichText::new("⌕")
                                             .size(14.0)
-                                            .color(Theme::TEXT_MUTED),
+                                            .color(Theme::text_muted()),
                                     );
                                     ui.add_space(8.0);
-                                    ui.add(
-                                        egui::TextEdit::singleline(&mut self.search_query)
+                                    let response = ui.add(
+                                        egui::TextEdit::singleline(&mut self.search.input)
+                                            .id(egui::Id::new(SEARCH_BOX_ID))
                                             .hint_text("Search instances...")
                                             .desired_width(180.0)
                                             .frame(false),
                                     );
+                                    if response.changed() {
+                                        self.search.mark_edited();
+                                    }
                                 });
                             });
                     }
@@ -375,7 +874,7 @@ impl MultiInstanceApp {
                         let new_btn = egui::Button::new(
                             egui::RichText::new("+ New Instance").color(egui::Color32::WHITE),
                         )
-                        .fill(Theme::PRIMARY)
+                        .fill(Theme::primary())
                         .rounding(egui::Rounding::same(8.0))
                         .min_size(egui::vec2(130.0, 36.0));
 
@@ -389,9 +888,9 @@ impl MultiInstanceApp {
                         // Quick actions (secondary buttons)
                         if self.state.active_instance_count() > 0 {
                             let pause_btn = egui::Button::new(
-                                egui::RichText::new("⏸ Pause All").color(Theme::TEXT_PRIMARY),
+                                egui::RichText::new("⏸ Pause All").color(Theme::text_primary()),
                             )
-                            .fill(Theme::BG_TERTIARY)
+                            .fill(Theme::bg_tertiary())
                             .rounding(egui::Rounding::same(8.0))
                             .min_size(egui::vec2(100.0, 36.0));
 
@@ -406,19 +905,87 @@ impl MultiInstanceApp {
 
                             ui.add_space(8.0);
 
+                            // Split button: the arrow offers narrower scopes
+                            ui.spacing_mut().item_spacing.x = 1.0;
+                            let scope_btn = egui::Button::new(
+                                egui::RichText::new("⏷").color(Theme::text_primary()),
+                            )
+                            .fill(Theme::bg_tertiary())
+                            .rounding(egui::Rounding {
+                                ne: 8.0,
+                                se: 8.0,
+                                ..Default::default()
+                            })
+                            .min_size(egui::vec2(24.0, 36.0));
+                            let scoped = egui::menu::menu_custom_button(ui, scope_btn, |ui| {
+                                self.render_stop_scopes(ui)
+                            })
+                            .inner
+                            .flatten();
+
                             let stop_btn = egui::Button::new(
-                                egui::RichText::new("⏹ Stop All").color(Theme::TEXT_PRIMARY),
+                                egui::RichText::new("⏹ Stop All").color(Theme::text_primary()),
                             )
-                            .fill(Theme::BG_TERTIARY)
+                            .fill(Theme::bg_tertiary())
+                            .rounding(egui::Rounding {
+                                nw: 8.0,
+                                sw: 8.0,
+                                ..Default::default()
+                            })
+                            .min_size(egui::vec2(100.0, 36.0));
+
+                            let result = if ui.add(stop_btn).clicked() {
+                                Some(self.state.stop_all())
+                            } else {
+                                scoped
+                            };
+                            if let Some(Err(e)) = result {
+                                self.notify(
+                                    format!("Failed to stop: {}", e),
+                                    NotificationLevel::Error,
+                                );
+                            }
+
+                            ui.add_space(8.0);
+                            let windows_btn = egui::Button::new(
+                                egui::RichText::new("▦ Windows").color(Theme::text_primary()),
+                            )
+                            .fill(Theme::bg_tertiary())
                             .rounding(egui::Rounding::same(8.0))
                             .min_size(egui::vec2(100.0, 36.0));
+                            let arranged = egui::menu::menu_custom_button(ui, windows_btn, |ui| {
+                                self.render_window_layouts(ui)
+                            })
+                            .inner
+                            .flatten();
+                            if let Some(Err(e)) = arranged {
+                                self.notify(
+                                    format!("Failed to arrange windows: {}", e),
+                                    NotificationLevel::Error,
+                                );
+                            }
 
-                            if ui.add(stop_btn).clicked() {
-                                if let Err(e) = self.state.stop_all() {
-                                    self.notify(
-                                        format!("Failed to stop: {}", e),
-                                        NotificationLevel::Error,
-                                    );
+                            if self.state.is_broadcasting() {
+                                ui.add_space(8.0);
+                                let broadcast_btn = egui::Button::new(
+                                    egui::RichText::new("📡 Broadcasting").color(Theme::warning()),
+                                )
+                                .fill(Theme::bg_tertiary())
+                                .rounding(egui::Rounding::same(8.0))
+                                .min_size(egui::vec2(120.0, 36.0));
+                                if ui
+                                    .add(broadcast_btn)
+                                    .on_hover_text(
+                                        "Input is mirrored to the broadcast group. Click to stop.",
+                                    )
+                                    .clicked()
+                                {
+                                    if let Err(e) = self.state.toggle_broadcast() {
+                                        self.notify(
+                                            format!("Failed to stop broadcasting: {}", e),
+                                            NotificationLevel::Error,
+                                        );
+                                    }
                                 }
                             }
                         }
@@ -427,9 +994,327 @@ impl MultiInstanceApp {
             });
     }
 
+    /// Show a view in the main window, or focus its own window when detached
+    fn open_view(&mut self, ctx: &Context, view: ActiveView) {
+        if view == ActiveView::History {
+            self.history.refresh();
+        }
+        if self.is_detached(view) {
+            ctx.send_viewport_cmd_to(view.viewport_id(), egui::ViewportCommand::Focus);
+        } else {
+            self.active_view = view;
+        }
+    }
+
+    /// In-app keyboard shortcuts, listed in `dialogs::shortcuts`. Single keys
+    /// are left alone while a text field has focus.
+    fn handle_shortcuts(&mut self, ctx: &Context) {
+        use egui::{Key, Modifiers};
+
+        let idle = matches!(self.dialog, DialogState::None);
+        let typing = ctx.wants_keyboard_input();
+
+        if idle && ctx.input_mut(|i| i.consume_key(Modifiers::COMMAND, Key::N)) {
+            self.dialog = DialogState::NewInstance;
+            self.new_instance_config = Some(InstanceConfig::default());
+        }
+        if ctx.input_mut(|i| i.consume_key(Modifiers::COMMAND, Key::F)) {
+            if !matches!(
+                self.active_view,
+                ActiveView::Instances | ActiveView::Profiles
+            ) {
+                self.open_view(ctx, ActiveView::Instances);
+            }
+            ctx.memory_mut(|m| m.request_focus(egui::Id::new(SEARCH_BOX_ID)));
+        }
+        if ctx.input_mut(|i| i.consume_key(Modifiers::NONE, Key::F1)) {
+            self.dialog = DialogState::Shortcuts;
+        }
+        if typing {
+            return;
+        }
+
+        if idle {
+            let views = self
+                .state
+                .settings
+                .read()
+                .map(|s| ActiveView::visible(&s.sidebar))
+                .unwrap_or_default();
+            let keys = [
+                Key::Num1,
+                Key::Num2,
+                Key::Num3,
+                Key::Num4,
+                Key::Num5,
+                Key::Num6,
+            ];
+            for (key, view) in keys.into_iter().zip(views) {
+                if ctx.input_mut(|i| i.consume_key(Modifiers::NONE, key)) {
+                    self.open_view(ctx, view);
+                }
+            }
+        }
+
+        // The selected instance acts from the main view or its details dialog
+        let selected = self.selected_instance.filter(|id| {
+            matches!(self.dialog, DialogState::None)
+                || matches!(self.dialog, DialogState::InstanceDetails(open) if open == *id)
+        });
+        let Some(id) = selected else {
+            return;
+        };
+        let Some(status) = self
+            .state
+            .instances
+            .read()
+            .ok()
+            .and_then(|instances| instances.get(&id).map(|i| i.status))
+        else {
+            self.selected_instance = None;
+            return;
+        };
+
+        if ctx.input_mut(|i| i.consume_key(Modifiers::NONE, Key::Space)) {
+            let result = match status {
+                InstanceStatus::Stopped | InstanceStatus::Crashed => self.state.start_instance(id),
+                status if status.is_active() || status == InstanceStatus::Pending => {
+                    self.state.stop_instance(id)
+                }
+                _ => Ok(()),
+            };
+            if let Err(e) = result {
+                self.notify(format!("{}", e), NotificationLevel::Error);
+            }
+        }
+        if ctx.input_mut(|i| i.consume_key(Modifiers::NONE, Key::Delete)) {
+            let state = self.state.clone();
+            self.dialog = DialogState::Confirm {
+                title: "Delete Instance".to_string(),
+                message: "Are you sure you want to delete this instance?".to_string(),
+                on_confirm: Arc::new(move || {
+                    if let Err(e) = state.remove_instance(id, true) {
+                        error!("Failed to delete instance: {}", e);
+                    }
+                }),
+            };
+        }
+    }
+
+    /// Bring the window to the front and act on a forwarded command line
+    fn activate(&mut self, ctx: &Context, activation: Activation) {
+        ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+        ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+        ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+
+        match activation {
+            Activation::Show => {}
+            Activation::OpenExecutable(path) => {
+                let name = path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                self.new_instance_config = Some(InstanceConfig {
+                    name,
+                    executable_path: path,
+                    ..Default::default()
+                });
+                self.dialog = DialogState::NewInstance;
+            }
+            Activation::LaunchProfile(target) => {
+                let launched = ipc::find_profile(&self.state, &target).and_then(|profile| {
+                    let (id, name) =
+                        profile.with_context(|| format!("No profile named '{}'", target))?;
+                    self.state.launch_profile(id)?;
+                    Ok(name)
+                });
+                match launched {
+                    Ok(name) => self.notify(
+                        format!("Launched profile '{}'", name),
+                        NotificationLevel::Success,
+                    ),
+                    Err(e) => self.notify(
+                        format!("Failed to launch profile: {:#}", e),
+                        NotificationLevel::Error,
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Scoped variants of Stop All. Returns the result of the one picked.
+    /// Window layouts for all running instances or the members of one profile
+    fn render_window_layouts(&mut self, ui: &mut egui::Ui) -> Option<anyhow::Result<()>> {
+        ui.set_min_width(220.0);
+        let mut chosen = None;
+
+        for layout in [WindowLayout::Tile, WindowLayout::Cascade] {
+            if ui.button(layout.label()).clicked() {
+                chosen = Some((layout, None));
+            }
+        }
+        ui.horizontal(|ui| {
+            let (columns, rows) = &mut self.layout_grid;
+            ui.add(egui::DragValue::new(columns).range(1..=8));
+            ui.label("×");
+            ui.add(egui::DragValue::new(rows).range(1..=8));
+            if ui.button("Grid").clicked() {
+                chosen = Some((
+                    WindowLayout::Grid {
+                        columns: *columns,
+                        rows: *rows,
+                    },
+                    None,
+                ));
+            }
+        });
+
+        let mut profiles: Vec<(ProfileId, String, Option<WindowLayout>)> = self
+            .state
+            .profiles
+            .read()
+            .map(|p| {
+                p.values()
+                    .map(|p| (p.id, p.name.clone(), p.window_layout))
+                    .collect()
+            })
+            .unwrap_or_default();
+        profiles.sort_by_key(|(_, name, _)| name.to_lowercase());
+
+        let saved: Vec<_> = profiles
+            .iter()
+            .filter_map(|(id, name, layout)| Some((*id, name, (*layout)?)))
+            .collect();
+        if !saved.is_empty() {
+            ui.separator();
+            for (id, name, layout) in saved {
+                if ui
+                    .button(format!("{} ({})", name, layout.label()))
+                    .on_hover_text("Arrange this profile's instances with its saved layout")
+                    .clicked()
+                {
+                    chosen = Some((layout, Some(id)));
+                }
+            }
+        }
+
+        let mut result = None;
+        if !profiles.is_empty() {
+            ui.separator();
+            ui.menu_button(
+                format!("Save \"{}\" for profile", self.last_layout.label()),
+                |ui| {
+                    for (id, name, layout) in &profiles {
+                        if ui.button(name).clicked() {
+                            result = Some(
+                                self.state
+                                    .set_profile_window_layout(*id, Some(self.last_layout)),
+                            );
+                            ui.close_menu();
+                        }
+                        if layout.is_some() {
+                            ui.indent(id, |ui| {
+                                if ui.small_button("Forget saved layout").clicked() {
+                                    result = Some(self.state.set_profile_window_layout(*id, None));
+                                    ui.close_menu();
+                                }
+                            });
+                        }
+                    }
+                },
+            );
+        }
+
+        if let Some((layout, profile)) = chosen {
+            self.last_layout = layout;
+            result = Some(self.state.arrange_windows(layout, profile));
+            ui.close_menu();
+        }
+        result
+    }
+
+    fn render_stop_scopes(&self, ui: &mut egui::Ui) -> Option<anyhow::Result<()>> {
+        ui.set_min_width(200.0);
+        let mut result = None;
+
+        let filtered = self.active_view == ActiveView::Instances
+            && (self.search.is_filtered()
+                || self
+                    .state
+                    .settings
+                    .read()
+                    .is_ok_and(|s| s.instance_view.is_filtered()));
+        let visible = panels::instances::visible_instances(&self.state, &self.search);
+        if ui
+            .add_enabled(
+                filtered,
+                egui::Button::new(format!("Stop all in current view ({})", visible.len())),
+            )
+            .on_disabled_hover_text("Filter the Instances view with a search, tag or filter first")
+            .clicked()
+        {
+            result = Some(self.state.stop_instances(&visible));
+            ui.close_menu();
+        }
+        if ui
+            .button("Stop all except pinned")
+            .on_hover_text("Pin instances from their right-click menu")
+            .clicked()
+        {
+            result = Some(self.state.stop_all_except_pinned());
+            ui.close_menu();
+        }
+
+        let groups = self.state.active_groups();
+        if !groups.is_empty() {
+            ui.separator();
+            for group in groups {
+                let label = match &group {
+                    Some(name) => format!("Stop group \"{}\"", name),
+                    None => "Stop ungrouped".to_string(),
+                };
+                if ui.button(label).clicked() {
+                    result = Some(self.state.stop_group(group.as_deref()));
+                    ui.close_menu();
+                }
+            }
+        }
+        result
+    }
+
+    /// Warn that the database is read-only while another machine uses the data directory
+    fn render_data_lock_banner(&self, ctx: &Context) {
+        let Some(lock) = self.state.data_lock_conflict() else {
+            return;
+        };
+        TopBottomPanel::top("data_lock_banner")
+            .frame(
+                egui::Frame::none()
+                    .fill(Theme::warning().linear_multiply(0.15))
+                    .inner_margin(egui::Margin::symmetric(16.0, 8.0)),
+            )
+            .show(ctx, |ui| {
+                ui.label(
+                    egui::RichText::new(format!(
+                        "\u{26A0} Read-only: MultiInstance on {} is using this data folder \
+                         (seen {} min ago), likely through a sync service. Changes made here \
+                         are not saved. Close it there and restart MultiInstance to make changes.",
+                        lock.host,
+                        lock.age_mins()
+                    ))
+                    .color(Theme::warning()),
+                );
+            });
+    }
+
     /// Render the main content area
     fn render_main_content(&mut self, ctx: &Context) {
-        CentralPanel::default().show(ctx, |ui| match self.active_view {
+        CentralPanel::default().show(ctx, |ui| self.render_view(ui, self.active_view));
+    }
+
+    /// Render the panel of a view, in the main window or its own
+    fn render_view(&mut self, ui: &mut egui::Ui, view: ActiveView) {
+        match view {
             ActiveView::Dashboard => {
                 panels::dashboard::render(ui, &mut self.state, self.show_system_resources);
             }
@@ -437,21 +1322,90 @@ impl MultiInstanceApp {
                 panels::instances::render(
                     ui,
                     &mut self.state,
-                    &self.search_query,
+                    &mut self.search,
                     &mut self.selected_instance,
                     &mut self.dialog,
+                    &mut self.group_page,
                 );
             }
             ActiveView::Profiles => {
-                panels::profiles::render(ui, &mut self.state, &self.search_query, &mut self.dialog);
+                panels::profiles::render(
+                    ui,
+                    &mut self.state,
+                    self.search.query(),
+                    &mut self.dialog,
+                );
+            }
+            ActiveView::Schedules => {
+                if self.config_locked() {
+                    panels::settings::render_locked(
+                        ui,
+                        &self.state,
+                        &mut self.settings_unlocked,
+                        "Schedules",
+                    );
+                } else {
+                    panels::schedules::render(ui, &self.state);
+                }
             }
             ActiveView::Settings => {
-                panels::settings::render(ui, &mut self.state, ctx);
+                if self.config_locked() {
+                    panels::settings::render_locked(
+                        ui,
+                        &self.state,
+                        &mut self.settings_unlocked,
+                        "Settings",
+                    );
+                } else {
+                    let ctx = ui.ctx().clone();
+                    panels::settings::render(
+                        ui,
+                        &mut self.state,
+                        &ctx,
+                        &mut self.settings_unlocked,
+                        &mut self.migration,
+                    );
+                }
             }
             ActiveView::History => {
-                panels::history::render(ui, &self.state);
+                panels::history::render(ui, &self.state, &mut self.history);
             }
-        });
+        }
+    }
+
+    /// Show each detached view in its own OS window; closing one returns the
+    /// view to the main window
+    fn render_detached_views(&mut self, ctx: &Context) {
+        let mut closed = Vec::new();
+        for index in 0..self.detached.len() {
+            let view = self.detached[index].view;
+            let opened = &self.detached[index].opened;
+            let mut builder = egui::ViewportBuilder::default()
+                .with_title(format!("{} - {}", view.label(), crate::APP_NAME))
+                .with_min_inner_size([480.0, 360.0])
+                .with_inner_size(opened.size.unwrap_or((1000.0, 700.0)));
+            if let Some(position) = opened.position {
+                builder = builder.with_position(position);
+            }
+
+            ctx.show_viewport_immediate(view.viewport_id(), builder, |ctx, _class| {
+                CentralPanel::default().show(ctx, |ui| self.render_view(ui, view));
+
+                let (outer, inner, close) = ctx.input(|i| {
+                    let info = i.viewport();
+                    (info.outer_rect, info.inner_rect, info.close_requested())
+                });
+                if let (Some(outer), Some(inner)) = (outer, inner) {
+                    self.detached[index].placed = Some((outer.min, inner.size()));
+                }
+                if close {
+                    closed.push(view);
+                }
+            });
+        }
+        for view in closed {
+            self.attach_view(view);
+        }
     }
 
     /// Render notifications
@@ -465,10 +1419,10 @@ impl MultiInstanceApp {
             .show(ctx, |ui| {
                 for notification in &self.notifications {
                     let (bg_color, icon, border_color) = match notification.level {
-                        NotificationLevel::Info => (Theme::BG_ELEVATED, "ℹ", Theme::INFO),
-                        NotificationLevel::Success => (Theme::BG_ELEVATED, "✓", Theme::SUCCESS),
-                        NotificationLevel::Warning => (Theme::BG_ELEVATED, "⚠", Theme::WARNING),
-                        NotificationLevel::Error => (Theme::BG_ELEVATED, "✕", Theme::ERROR),
+                        NotificationLevel::Info => (Theme::bg_elevated(), "ℹ", Theme::info()),
+                        NotificationLevel::Success => (Theme::bg_elevated(), "✓", Theme::success()),
+                        NotificationLevel::Warning => (Theme::bg_elevated(), "⚠", Theme::warning()),
+                        NotificationLevel::Error => (Theme::bg_elevated(), "✕", Theme::error()),
                     };
 
                     egui::Frame::none()
@@ -502,7 +1456,7 @@ impl MultiInstanceApp {
                                     ui.label(
                                         egui::RichText::new(&notification.message)
                                             .size(13.0)
-                                            .color(Theme::TEXT_PRIMARY),
+                                            .color(Theme::text_primary()),
                                     );
                                 });
                             });
@@ -513,8 +1467,139 @@ impl MultiInstanceApp {
             });
     }
 
+    /// Frame, monitor and database timings with our own footprint, in a corner
+    fn render_profiler_overlay(&self, ctx: &Context) {
+        let shown = self
+            .state
+            .settings
+            .read()
+            .map(|s| s.show_profiler_overlay)
+            .unwrap_or(false);
+        if !shown {
+            return;
+        }
+        let Ok(profiler) = self.state.profiler.read().map(|p| p.clone()) else {
+            return;
+        };
+        let db_write = self.state.database.write_timing();
+
+        let ms = |d: Option<Duration>| {
+            d.map_or("-".to_string(), |d| {
+                format!("{:.1} ms", d.as_secs_f64() * 1000.0)
+            })
+        };
+        let rows = [
+            ("Frame", &profiler.frame),
+            ("Monitor", &profiler.monitor),
+            ("DB write", &db_write),
+        ];
+
+        egui::Area::new(egui::Id::new("profiler_overlay"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-12.0, -12.0])
+            .order(egui::Order::Foreground)
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(Theme::bg_elevated().gamma_multiply(0.9))
+                    .rounding(egui::Rounding::same(8.0))
+                    .stroke(egui::Stroke::new(1.0, Theme::border_light()))
+                    .inner_margin(egui::Margin::same(10.0))
+                    .show(ui, |ui| {
+                        egui::Grid::new("profiler_overlay_grid")
+                            .num_columns(4)
+                            .spacing([12.0, 2.0])
+                            .show(ui, |ui| {
+                                for header in ["", "Last", "Avg", "Max"] {
+                                    ui.label(
+                                        egui::RichText::new(header)
+                                            .size(11.0)
+                                            .color(Theme::text_muted()),
+                                    );
+                                }
+                                ui.end_row();
+                                for (label, timing) in rows {
+                                    ui.label(
+                                        egui::RichText::new(label)
+                                            .size(11.0)
+                                            .color(Theme::text_secondary()),
+                                    );
+                                    for value in [timing.last(), timing.average(), timing.max()] {
+                                        ui.label(
+                                            egui::RichText::new(ms(value))
+                                                .size(11.0)
+                                                .monospace()
+                                                .color(Theme::text_primary()),
+                                        );
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+
+                        let footprint = profiler.usage.map_or("-".to_string(), |u| {
+                            format!(
+                                "{} RAM · {:.1}% CPU",
+                                format_bytes(u.memory_bytes),
+                                u.cpu_percent
+                            )
+                        });
+                        let handles = profiler.handles;
+                        ui.add_space(4.0);
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "Handles: {} children · {} jobs · {} released",
+                                handles.children, handles.jobs, handles.pruned
+                            ))
+                            .size(11.0)
+                            .monospace()
+                            .color(Theme::text_primary()),
+                        );
+                        ui.add_space(4.0);
+                        ui.label(
+                            egui::RichText::new(format!("Self: {}", footprint))
+                                .size(11.0)
+                                .monospace()
+                                .color(if profiler.over_budget() {
+                                    Theme::warning()
+                                } else {
+                                    Theme::text_primary()
+                                }),
+                        );
+                    });
+            });
+    }
+
+    /// Whether configuration screens are behind the settings PIN
+    fn config_locked(&self) -> bool {
+        !self.settings_unlocked
+            && self
+                .state
+                .settings
+                .read()
+                .map(|s| s.settings_lock.is_some())
+                .unwrap_or(false)
+    }
+
     /// Render dialogs
     fn render_dialogs(&mut self, ctx: &Context) {
+        let needs_unlock = matches!(
+            self.dialog,
+            DialogState::NewInstance
+                | DialogState::EditInstance(_)
+                | DialogState::NewProfile
+                | DialogState::NewFromTemplate { .. }
+                | DialogState::EditProfile(_)
+                | DialogState::ManageGroups
+        );
+        if needs_unlock && self.config_locked() {
+            dialogs::unlock::render(
+                ctx,
+                &self.state,
+                &mut self.settings_unlocked,
+                &mut self.dialog,
+            );
+            return;
+        }
+
         match &self.dialog {
             DialogState::None => {}
             DialogState::NewInstance => {
@@ -533,6 +1618,9 @@ impl MultiInstanceApp {
             DialogState::NewProfile => {
                 dialogs::new_profile::render(ctx, &mut self.state, &mut self.dialog);
             }
+            DialogState::NewFromTemplate { .. } => {
+                dialogs::profile_template::render(ctx, &self.state, &mut self.dialog);
+            }
             DialogState::EditProfile(id) => {
                 let id = *id;
                 dialogs::edit_profile::render(ctx, id, &mut self.state, &mut self.dialog);
@@ -549,14 +1637,42 @@ impl MultiInstanceApp {
             }
             DialogState::InstanceDetails(id) => {
                 let id = *id;
-                dialogs::instance_details::render(ctx, id, &mut self.state, &mut self.dialog);
+                let locked = self.config_locked();
+                dialogs::instance_details::render(
+                    ctx,
+                    id,
+                    &mut self.state,
+                    &mut self.dialog,
+                    locked,
+                );
+            }
+            DialogState::ManageGroups => {
+                dialogs::manage_groups::render(ctx, &self.state, &mut self.dialog);
+            }
+            DialogState::Shortcuts => {
+                dialogs::shortcuts::render(ctx, &mut self.dialog);
+            }
+            DialogState::Orphans => {
+                dialogs::orphans::render(ctx, &self.state, &mut self.dialog);
+            }
+            DialogState::PartialLaunch { .. } => {
+                dialogs::partial_launch::render(ctx, &self.state, &mut self.dialog);
+            }
+            DialogState::Recovery => {
+                dialogs::recovery::render(ctx, &self.state, &mut self.dialog);
+            }
+            DialogState::SessionRestore { .. } => {
+                dialogs::session_restore::render(ctx, &self.state, &mut self.dialog);
             }
         }
+        dialogs::migration::render(ctx, &self.state, &mut self.migration);
     }
 }
 
 impl eframe::App for MultiInstanceApp {
     fn update(&mut self, ctx: &Context, _frame: &mut eframe::Frame) {
+        let frame_started = Instant::now();
+
         // First frame setup
         if self.first_frame {
             self.first_frame = false;
@@ -564,33 +1680,107 @@ impl eframe::App for MultiInstanceApp {
         }
 
         // Update resources periodically
+        let monitor_started = Instant::now();
         self.update_resources();
+        if let Ok(mut profiler) = self.state.profiler.write() {
+            profiler.monitor.record(monitor_started.elapsed());
+        }
+
+        // Report what happened to the instances of a run that ended unexpectedly
+        if matches!(self.dialog, DialogState::None)
+            && self.state.recovery.read().is_ok_and(|r| r.is_some())
+        {
+            self.dialog = DialogState::Recovery;
+        }
+
+        // Offer to clean up processes left over from a crash, once
+        if !self.orphans_prompted
+            && matches!(self.dialog, DialogState::None)
+            && self.state.orphans.read().is_ok_and(|o| !o.is_empty())
+        {
+            self.orphans_prompted = true;
+            self.dialog = DialogState::Orphans;
+        }
 
         // Clean up old notifications
         self.cleanup_notifications();
 
+        for warning in self.state.take_launch_warnings() {
+            self.notify(warning, NotificationLevel::Warning);
+        }
+
+        // The switcher hotkey brings this window to the front to show it
+        if self.state.take_switcher_request() {
+            if !self.switcher.is_open() {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+                ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+                ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+            }
+            self.switcher.open_or_advance();
+        }
+
+        // Command lines from startup or a second start, once profiles are loaded
+        if self.state.is_data_loaded() {
+            for activation in self.state.take_activations() {
+                self.activate(ctx, activation);
+            }
+        }
+
+        // Apply the search query once typing pauses
+        let revision = self.state.instance_revision();
+        if let Ok(instances) = self.state.instances.read() {
+            self.search.update(ctx, &instances, revision);
+        }
+
+        self.handle_shortcuts(ctx);
+
         // Request repaint for animations
         ctx.request_repaint_after(Duration::from_millis(100));
 
+        dialogs::unlock::publish_locked(ctx, self.config_locked());
+
         // Render UI components
         self.render_sidebar(ctx);
+        self.render_data_lock_banner(ctx);
         self.render_top_bar(ctx);
         self.render_main_content(ctx);
+        self.render_detached_views(ctx);
         self.render_notifications(ctx);
         self.render_dialogs(ctx);
+        if let Some(id) = self.switcher.show(ctx, &self.state) {
+            if let Err(e) = self.state.focus_instance(id) {
+                self.notify(
+                    format!("Could not switch to instance: {}", e),
+                    NotificationLevel::Error,
+                );
+            }
+        }
+        self.render_profiler_overlay(ctx);
+
+        if let Ok(mut profiler) = self.state.profiler.write() {
+            profiler.frame.record(frame_started.elapsed());
+        }
     }
 
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.store_detached_views();
+
         // Save session on exit
         if let Err(e) = self.state.save_session() {
             error!("Failed to save session: {}", e);
         }
 
+        self.state.flush_usage();
+
         // Save settings
         if let Err(e) = self.state.save_settings() {
             error!("Failed to save settings: {}", e);
         }
 
+        // A clean exit leaves no dirty shutdown marker behind
+        self.state.clear_shutdown_marker();
+        self.state.release_data_lock();
+
         info!("Application exiting");
     }
 }
@@ -0,0 +1,64 @@
+//! CPU affinity picker component
+
+use egui::Ui;
+
+use crate::core::cpu_topology::{AffinityPreset, CpuTopology};
+use crate::core::ResourceLimits;
+use crate::ui::theme::Theme;
+
+pub struct AffinityPicker;
+
+impl AffinityPicker {
+    /// Render a preset dropdown for the affinity of `limits`
+    pub fn show(ui: &mut Ui, limits: &mut ResourceLimits, id_salt: &str) {
+        let topology = CpuTopology::get();
+
+        let selected = match limits.affinity_preset {
+            Some(preset) => preset.label().to_string(),
+            None if limits.cpu_affinity.is_empty() => "All cores".to_string(),
+            None => format!("Custom ({} cores)", limits.cpu_affinity.len()),
+        };
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt(id_salt)
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    if ui
+                        .selectable_label(
+                            limits.affinity_preset.is_none() && limits.cpu_affinity.is_empty(),
+                            "All cores",
+                        )
+                        .clicked()
+                    {
+                        limits.affinity_preset = None;
+                        limits.cpu_affinity.clear();
+                    }
+
+                    for preset in AffinityPreset::all() {
+                        // P/E presets only make sense on hybrid CPUs
+                        if *preset != AffinityPreset::OnePerformanceCoreEach
+                            && !topology.is_hybrid()
+                        {
+                            continue;
+                        }
+                        if ui
+                            .selectable_label(
+                                limits.affinity_preset == Some(*preset),
+                                preset.label(),
+                            )
+                            .clicked()
+                        {
+                            limits.affinity_preset = Some(*preset);
+                            limits.cpu_affinity.clear();
+                        }
+                    }
+                });
+
+            ui.label(
+                egui::RichText::new(topology.summary())
+                    .size(11.0)
+                    .color(Theme::text_muted()),
+            );
+        });
+    }
+}
@@ -0,0 +1,35 @@
+//! Shell icon of an instance's executable
+
+use std::path::Path;
+
+use egui::Ui;
+use image::RgbaImage;
+
+pub struct ExecutableIcon;
+
+impl ExecutableIcon {
+    /// Draw an executable's icon `size` points wide
+    pub fn show(ui: &mut Ui, path: &Path, icon: &RgbaImage, size: f32) {
+        let texture = Self::texture(ui.ctx(), path, icon);
+        ui.image((texture.id(), egui::Vec2::splat(size)));
+    }
+
+    /// Upload an icon to the GPU once per executable
+    fn texture(ctx: &egui::Context, path: &Path, icon: &RgbaImage) -> egui::TextureHandle {
+        let key = egui::Id::new(("executable_icon", path));
+        if let Some(texture) = ctx.data(|d| d.get_temp::<egui::TextureHandle>(key)) {
+            return texture;
+        }
+        let pixels = egui::ColorImage::from_rgba_unmultiplied(
+            [icon.width() as usize, icon.height() as usize],
+            icon.as_raw(),
+        );
+        let texture = ctx.load_texture(
+            format!("executable_icon_{}", path.display()),
+            pixels,
+            egui::TextureOptions::LINEAR,
+        );
+        ctx.data_mut(|d| d.insert_temp(key, texture.clone()));
+        texture
+    }
+}
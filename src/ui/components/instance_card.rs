@@ -1,11 +1,20 @@
 //! Instance card component for grid/list views
 
 use egui::{Color32, Ui};
+use image::RgbaImage;
 
-use crate::core::{Instance, InstanceStatus};
+use crate::core::isolation_check::IsolationCheck;
+use crate::core::resource::format_bytes;
+use crate::core::settings::{CardDisplay, CardSize, QuickAction};
+use crate::core::status_detection::Health;
+use crate::core::{Instance, InstanceStatus, WindowState};
+use crate::ui::dialogs::unlock;
+use crate::ui::search;
 use crate::ui::theme::{Icons, Theme};
 
+use super::executable_icon::ExecutableIcon;
 use super::resource_bar::ResourceBar;
+use super::shape_cache::ShapeCache;
 use super::status_badge::StatusBadge;
 
 pub struct InstanceCard;
@@ -14,56 +23,540 @@ impl InstanceCard {
     /// Styled action button for cards
     fn action_button(ui: &mut Ui, icon: &str, tooltip: &str, color: Color32) -> bool {
         let btn = egui::Button::new(egui::RichText::new(icon).size(13.0).color(color))
-            .fill(Theme::BG_TERTIARY)
+            .fill(Theme::bg_tertiary())
             .rounding(egui::Rounding::same(6.0))
             .min_size(egui::vec2(32.0, 28.0));
 
         ui.add(btn).on_hover_text(tooltip).clicked()
     }
 
+    /// Badge shown when the executable changed on disk. Instances in a group
+    /// can also restart the whole group one at a time.
+    fn update_badge(ui: &mut Ui, instance: &Instance) -> Option<CardAction> {
+        let text = egui::RichText::new("⬆ Update")
+            .size(11.0)
+            .color(Theme::info());
+
+        let Some(group) = instance.config.group.clone() else {
+            let btn = egui::Button::new(text)
+                .fill(Theme::info().linear_multiply(0.15))
+                .rounding(egui::Rounding::same(4.0));
+            return ui
+                .add(btn)
+                .on_hover_text("Executable changed on disk. Click to restart and update.")
+                .clicked()
+                .then_some(CardAction::Restart);
+        };
+
+        let mut action = None;
+        ui.menu_button(text, |ui| {
+            if ui.button("Restart instance").clicked() {
+                action = Some(CardAction::Restart);
+                ui.close_menu();
+            }
+            if ui
+                .button(format!("Restart group \"{}\"", group))
+                .on_hover_text("Restart the group one instance at a time")
+                .clicked()
+            {
+                action = Some(CardAction::RestartGroup(group));
+                ui.close_menu();
+            }
+        })
+        .response
+        .on_hover_text("Executable changed on disk. Restart to update.");
+        action
+    }
+
+    fn quick_action_button(ui: &mut Ui, action: QuickAction) -> bool {
+        let (icon, color) = match action {
+            QuickAction::Start | QuickAction::Resume => (Icons::PLAY, Theme::success()),
+            QuickAction::Stop => (Icons::STOP, Theme::error_light()),
+            QuickAction::Pause => (Icons::PAUSE, Theme::warning()),
+            QuickAction::Restart => (Icons::RESTART, Theme::info()),
+            QuickAction::Kill => (Icons::ERROR, Theme::error()),
+            QuickAction::Configure => (Icons::SETTINGS, Theme::text_muted()),
+            QuickAction::Details => (Icons::INFO, Theme::text_secondary()),
+            QuickAction::Delete => (Icons::TRASH, Theme::error_light()),
+        };
+        Self::action_button(ui, icon, action.label(), color)
+    }
+
+    fn card_action(action: QuickAction) -> CardAction {
+        match action {
+            QuickAction::Start => CardAction::Start,
+            QuickAction::Stop => CardAction::Stop,
+            QuickAction::Pause => CardAction::Pause,
+            QuickAction::Resume => CardAction::Resume,
+            QuickAction::Restart => CardAction::Restart,
+            QuickAction::Kill => CardAction::Kill,
+            QuickAction::Configure => CardAction::Configure,
+            QuickAction::Details => CardAction::Select,
+            QuickAction::Delete => CardAction::Delete,
+        }
+    }
+
+    /// Right-click menu with the actions that are not on the card
+    fn context_menu(
+        ui: &mut Ui,
+        instance: &Instance,
+        display: &CardDisplay,
+        response: &mut CardResponse,
+    ) {
+        let shown = display.quick_actions.for_status(&instance.status);
+        let rest: Vec<QuickAction> = QuickAction::available(&instance.status)
+            .iter()
+            .copied()
+            .filter(|a| !shown.contains(a))
+            .collect();
+        let macros = if instance.status == InstanceStatus::Running {
+            instance.config.macros.as_slice()
+        } else {
+            &[]
+        };
+        let show_window = instance.status == InstanceStatus::Running
+            && instance.config.start_window_state != WindowState::Normal;
+        let data_action = match instance.status {
+            InstanceStatus::Pending => None,
+            status if status.is_active() => {
+                Some(("Stop and compress data", CardAction::StopAndCompress))
+            }
+            _ if instance.compressed_bytes.is_some() => {
+                Some(("Decompress data", CardAction::DecompressData))
+            }
+            _ => Some(("Compress data", CardAction::CompressData)),
+        };
+        // Interact through the Ui's own id, which sits behind the card's buttons
+        ui.interact(ui.min_rect(), ui.unique_id(), egui::Sense::click())
+            .context_menu(|ui| {
+                for action in rest {
+                    if ui.button(action.label()).clicked() {
+                        response.action = Some(Self::card_action(action));
+                        ui.close_menu();
+                    }
+                }
+                if show_window && ui.button("Show window").clicked() {
+                    response.action = Some(CardAction::ShowWindow);
+                    ui.close_menu();
+                }
+                if instance.status == InstanceStatus::Running
+                    && ui
+                        .button("Capture screenshot")
+                        .on_hover_text("Save the main window as a PNG in the data directory")
+                        .clicked()
+                {
+                    response.action = Some(CardAction::CaptureScreenshot);
+                    ui.close_menu();
+                }
+                let pinned = instance.config.pinned;
+                if ui
+                    .button(if pinned { "Unpin" } else { "Pin" })
+                    .on_hover_text(
+                        "Pinned instances keep running through \"Stop all except pinned\"",
+                    )
+                    .clicked()
+                {
+                    response.action = Some(CardAction::SetPinned(!pinned));
+                    ui.close_menu();
+                }
+                if let Some((label, action)) = data_action {
+                    if ui
+                        .button(label)
+                        .on_hover_text(
+                            "Pack the data directory into a zstd archive until the next launch",
+                        )
+                        .clicked()
+                    {
+                        response.action = Some(action);
+                        ui.close_menu();
+                    }
+                }
+                if !macros.is_empty() {
+                    ui.menu_button("Run macro", |ui| {
+                        for (index, recorded) in macros.iter().enumerate() {
+                            if ui.button(&recorded.name).clicked() {
+                                response.action = Some(CardAction::RunMacro(index));
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                }
+            });
+    }
+
+    /// Instance name; double-click edits it in place unless configuration is locked
+    fn name_label(
+        ui: &mut Ui,
+        instance: &Instance,
+        highlight: &str,
+        size: f32,
+        response: &mut CardResponse,
+    ) {
+        let edit_id = egui::Id::new(("rename_instance", instance.id));
+        let field_id = edit_id.with("field");
+
+        // Renaming changes the config, which the settings PIN guards
+        if unlock::is_locked(ui.ctx()) {
+            ui.data_mut(|d| d.remove::<String>(edit_id));
+            ui.label(search::highlighted(
+                instance.display_name(),
+                highlight,
+                size,
+                Theme::text_primary(),
+            ));
+            return;
+        }
+
+        let Some(mut name) = ui.data(|d| d.get_temp::<String>(edit_id)) else {
+            let label = ui
+                .add(
+                    egui::Label::new(search::highlighted(
+                        instance.display_name(),
+                        highlight,
+                        size,
+                        Theme::text_primary(),
+                    ))
+                    .sense(egui::Sense::click()),
+                )
+                .on_hover_text("Double-click to rename");
+            if label.double_clicked() {
+                ui.data_mut(|d| d.insert_temp(edit_id, instance.display_name().to_string()));
+                ui.memory_mut(|m| m.request_focus(field_id));
+            }
+            return;
+        };
+
+        let edit = ui.add(
+            egui::TextEdit::singleline(&mut name)
+                .id(field_id)
+                .font(egui::FontId::proportional(size))
+                .desired_width(180.0),
+        );
+        if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+            ui.data_mut(|d| d.remove::<String>(edit_id));
+        } else if edit.lost_focus() {
+            ui.data_mut(|d| d.remove::<String>(edit_id));
+            let name = name.trim();
+            if !name.is_empty() && name != instance.config.name {
+                response.action = Some(CardAction::Rename(name.to_string()));
+            }
+        } else {
+            ui.data_mut(|d| d.insert_temp(edit_id, name));
+        }
+    }
+
+    /// Dropdown choosing the argument variant for the next launch
+    fn variant_picker(ui: &mut Ui, instance: &Instance, response: &mut CardResponse) {
+        let variants = &instance.config.argument_variants;
+        if variants.is_empty() || instance.status.is_active() {
+            return;
+        }
+        let current = instance.config.variant().map(|v| v.name.as_str());
+        egui::ComboBox::from_id_salt(("argument_variant", instance.id))
+            .width(120.0)
+            .selected_text(current.unwrap_or("Default arguments"))
+            .show_ui(ui, |ui| {
+                if ui
+                    .selectable_label(current.is_none(), "Default arguments")
+                    .clicked()
+                {
+                    response.action = Some(CardAction::SelectVariant(None));
+                }
+                for (index, variant) in variants.iter().enumerate() {
+                    if ui
+                        .selectable_label(current == Some(variant.name.as_str()), &variant.name)
+                        .on_hover_text(variant.arguments.join(" "))
+                        .clicked()
+                    {
+                        response.action = Some(CardAction::SelectVariant(Some(index)));
+                    }
+                }
+            })
+            .response
+            .on_hover_text("Arguments used for the next launch");
+    }
+
+    /// Muted badge for a single piece of instance information
+    fn info_badge(ui: &mut Ui, text: &str) {
+        egui::Frame::none()
+            .fill(Theme::bg_tertiary().linear_multiply(0.6))
+            .rounding(egui::Rounding::same(4.0))
+            .inner_margin(egui::Margin::symmetric(8.0, 4.0))
+            .show(ui, |ui| {
+                ui.label(
+                    egui::RichText::new(text)
+                        .size(11.0)
+                        .color(Theme::text_secondary()),
+                );
+            });
+    }
+
+    fn network_string(instance: &Instance) -> String {
+        format!(
+            "↓ {} ↑ {}",
+            instance.resource_usage.rx_rate_string(),
+            instance.resource_usage.tx_rate_string()
+        )
+    }
+
+    fn has_labels(instance: &Instance, display: &CardDisplay) -> bool {
+        (display.show_group && instance.config.group.is_some())
+            || (display.show_tags && !instance.config.tags.is_empty())
+            || (display.show_metadata && !instance.config.metadata.is_empty())
+            || instance.compressed_bytes.is_some()
+            || instance.config.pinned
+            || instance.pending_config.is_some()
+            || instance.limit_window.is_some()
+            || matches!(
+                instance.isolation_check,
+                Some(IsolationCheck::Ignored { .. })
+            )
+    }
+
+    /// Group and tag chips, as enabled in the card display settings, and
+    /// whether the instance is pinned, has changes waiting for a restart, runs
+    /// under a limit window or has its data compressed. Returns the action of a clicked chip.
+    fn labels(ui: &mut Ui, instance: &Instance, display: &CardDisplay) -> Option<CardAction> {
+        let chip = |ui: &mut Ui, text: &str, color: Color32| {
+            egui::Frame::none()
+                .fill(color.linear_multiply(0.15))
+                .rounding(egui::Rounding::same(8.0))
+                .inner_margin(egui::Margin::symmetric(6.0, 2.0))
+                .show(ui, |ui| {
+                    ui.label(egui::RichText::new(text).size(10.0).color(color));
+                })
+                .response
+        };
+
+        let mut action = None;
+        if instance.pending_config.is_some()
+            && chip(ui, "⟳ Restart to apply", Theme::warning())
+                .interact(egui::Sense::click())
+                .on_hover_text("Edited while running; click to restart and apply the changes")
+                .clicked()
+        {
+            action = Some(CardAction::Restart);
+        }
+        if let Some(check @ IsolationCheck::Ignored { .. }) = &instance.isolation_check {
+            chip(ui, "⚠ Not isolated", Theme::warning()).on_hover_text(format!(
+                "The process dropped its isolated environment and may be using the real user profile:\n{}",
+                check.details()
+            ));
+        }
+        if let Some(window) = &instance.limit_window {
+            chip(ui, &format!("⏱ {}", window.name), Theme::info()).on_hover_text(format!(
+                "Running with the limits of \"{}\" until {}",
+                window.name,
+                window.end.format("%H:%M")
+            ));
+        }
+        if instance.config.pinned {
+            chip(ui, "📌 Pinned", Theme::primary_light())
+                .on_hover_text("Kept running by \"Stop all except pinned\"");
+        }
+        if let Some(bytes) = instance.compressed_bytes {
+            chip(ui, "🗜 Compressed", Theme::info()).on_hover_text(format!(
+                "Data compressed to {}; it is unpacked before the next launch",
+                format_bytes(bytes)
+            ));
+        }
+
+        if display.show_group {
+            if let Some(group) = &instance.config.group {
+                chip(ui, group, Theme::group_color(group));
+            }
+        }
+        if display.show_tags {
+            for tag in &instance.config.tags {
+                chip(ui, &format!("#{}", tag), Theme::text_secondary());
+            }
+        }
+        if display.show_metadata {
+            for (field, value) in &instance.config.metadata {
+                chip(ui, value, Theme::info()).on_hover_text(field);
+            }
+        }
+        action
+    }
+
+    /// Time left before a time-boxed instance is stopped
+    /// Warning badge while status detection reports a problem
+    fn health_badge(ui: &mut Ui, instance: &Instance) {
+        let Some(Health::Unhealthy(reason)) = instance.health.as_ref().map(|h| &h.health) else {
+            return;
+        };
+        egui::Frame::none()
+            .fill(Theme::error().linear_multiply(0.15))
+            .rounding(egui::Rounding::same(4.0))
+            .inner_margin(egui::Margin::symmetric(8.0, 4.0))
+            .show(ui, |ui| {
+                ui.label(
+                    egui::RichText::new("⚠ Unhealthy")
+                        .size(11.0)
+                        .color(Theme::error_light()),
+                );
+            })
+            .response
+            .on_hover_text(reason);
+    }
+
+    fn countdown_badge(ui: &mut Ui, instance: &Instance) {
+        let (Some(countdown), Some(remaining)) =
+            (instance.countdown_string(), instance.time_remaining())
+        else {
+            return;
+        };
+        let color = if remaining.num_seconds() <= 60 {
+            Theme::warning()
+        } else {
+            Theme::text_secondary()
+        };
+
+        egui::Frame::none()
+            .fill(color.linear_multiply(0.15))
+            .rounding(egui::Rounding::same(4.0))
+            .inner_margin(egui::Margin::symmetric(8.0, 4.0))
+            .show(ui, |ui| {
+                ui.label(
+                    egui::RichText::new(format!("⏳ {} left", countdown))
+                        .size(11.0)
+                        .color(color),
+                );
+            })
+            .response
+            .on_hover_text("Stops automatically when the run limit is reached");
+    }
+
+    /// System-wide running copies of the executable, highlighted when some are not ours
+    fn copies_badge(ui: &mut Ui, instance: &Instance) {
+        if instance.system_copies == 0 {
+            return;
+        }
+        let color = if instance.external_copies > 0 {
+            Theme::warning()
+        } else {
+            Theme::text_secondary()
+        };
+        let exe = instance
+            .config
+            .executable_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        egui::Frame::none()
+            .fill(color.linear_multiply(0.15))
+            .rounding(egui::Rounding::same(4.0))
+            .inner_margin(egui::Margin::symmetric(8.0, 4.0))
+            .show(ui, |ui| {
+                ui.label(
+                    egui::RichText::new(format!("⧉ {}", instance.system_copies))
+                        .size(11.0)
+                        .color(color),
+                );
+            })
+            .response
+            .on_hover_text(format!(
+                "{} {} process(es) running system-wide, {} not launched by MultiInstance",
+                instance.system_copies, exe, instance.external_copies
+            ));
+    }
+
+    /// Render a card, reserving only its last known size while it is scrolled out of view.
+    /// Large instance lists then only lay out and paint the cards on screen.
+    pub fn culled(
+        ui: &mut Ui,
+        instance: &Instance,
+        layout: &'static str,
+        render: impl FnOnce(&mut Ui, &Instance) -> CardResponse,
+    ) -> CardResponse {
+        let id_salt = ("instance_card", instance.id);
+        let size_id = egui::Id::new(("instance_card_size", layout, instance.id));
+
+        let Some(size) = ui.data(|d| d.get_temp::<egui::Vec2>(size_id)) else {
+            let inner = ui.push_id(id_salt, |ui| render(ui, instance));
+            ui.data_mut(|d| d.insert_temp(size_id, inner.response.rect.size()));
+            return inner.inner;
+        };
+
+        let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+        if !ui.is_rect_visible(rect) {
+            return CardResponse::default();
+        }
+
+        let mut child = ui.new_child(egui::UiBuilder::new().id_salt(id_salt).max_rect(rect));
+        let response = render(&mut child, instance);
+
+        // Content changed size (e.g. the instance started); lay out again next frame
+        let actual = child.min_rect().size();
+        if actual != size {
+            ui.data_mut(|d| d.insert_temp(size_id, actual));
+            ui.ctx().request_repaint();
+        }
+        response
+    }
+
     /// Render instance as a grid card
-    pub fn grid(ui: &mut Ui, instance: &Instance) -> CardResponse {
+    pub fn grid(
+        ui: &mut Ui,
+        instance: &Instance,
+        highlight: &str,
+        display: &CardDisplay,
+        icon: Option<&RgbaImage>,
+    ) -> CardResponse {
         let mut response = CardResponse::default();
 
         let status_color = Theme::status_color(&instance.status);
         let is_active = instance.status.is_active();
 
-        egui::Frame::none()
-            .fill(Theme::BG_SECONDARY)
-            .rounding(egui::Rounding::same(12.0))
-            .stroke(egui::Stroke::new(
+        ShapeCache::frame(
+            ui,
+            Theme::bg_secondary(),
+            12.0,
+            egui::Stroke::new(
                 1.0,
                 if is_active {
                     status_color.linear_multiply(0.4)
                 } else {
-                    Theme::BORDER_LIGHT
+                    Theme::border_light()
                 },
-            ))
-            .inner_margin(egui::Margin::same(16.0))
-            .show(ui, |ui| {
-                ui.set_width(240.0);
+            ),
+            egui::Margin::same(16.0),
+            |ui| {
+                ui.set_width(display.size.grid_width());
 
                 // Header: Status indicator and name
                 ui.horizontal(|ui| {
                     StatusBadge::dot(ui, &instance.status);
                     ui.add_space(10.0);
+                    if let Some(icon) = icon {
+                        ExecutableIcon::show(ui, &instance.config.executable_path, icon, 32.0);
+                        ui.add_space(8.0);
+                    }
                     ui.vertical(|ui| {
-                        ui.label(
-                            egui::RichText::new(instance.display_name())
-                                .strong()
-                                .size(15.0)
-                                .color(Theme::TEXT_PRIMARY),
-                        );
-                        if let Some(path) = instance.config.executable_path.file_name() {
-                            ui.label(
-                                egui::RichText::new(path.to_string_lossy())
-                                    .size(12.0)
-                                    .color(Theme::TEXT_MUTED),
-                            );
+                        Self::name_label(ui, instance, highlight, 15.0, &mut response);
+                        if display.size != CardSize::Small {
+                            if let Some(path) = instance.config.executable_path.file_name() {
+                                ui.label(
+                                    egui::RichText::new(path.to_string_lossy())
+                                        .size(12.0)
+                                        .color(Theme::text_muted()),
+                                );
+                            }
                         }
                     });
                 });
 
+                if Self::has_labels(instance, display) {
+                    ui.add_space(8.0);
+                    if let Some(action) = ui
+                        .horizontal_wrapped(|ui| Self::labels(ui, instance, display))
+                        .inner
+                    {
+                        response.action = Some(action);
+                    }
+                }
+
                 ui.add_space(14.0);
 
                 // Resource usage (if running)
@@ -73,7 +566,7 @@ impl InstanceCard {
                         ui.label(
                             egui::RichText::new("CPU")
                                 .size(11.0)
-                                .color(Theme::TEXT_MUTED),
+                                .color(Theme::text_muted()),
                         );
                         ui.add_space(8.0);
                         ResourceBar::mini(ui, instance.resource_usage.cpu_percent / 100.0);
@@ -83,7 +576,7 @@ impl InstanceCard {
                                 instance.resource_usage.cpu_percent
                             ))
                             .size(11.0)
-                            .color(Theme::TEXT_SECONDARY),
+                            .color(Theme::text_secondary()),
                         );
                     });
 
@@ -94,52 +587,108 @@ impl InstanceCard {
                         ui.label(
                             egui::RichText::new("MEM")
                                 .size(11.0)
-                                .color(Theme::TEXT_MUTED),
+                                .color(Theme::text_muted()),
                         );
                         ui.add_space(4.0);
                         ResourceBar::mini(ui, 0.3); // Placeholder ratio
                         ui.label(
                             egui::RichText::new(instance.resource_usage.memory_string())
                                 .size(11.0)
-                                .color(Theme::TEXT_SECONDARY),
+                                .color(Theme::text_secondary()),
                         );
                     });
 
-                    ui.add_space(10.0);
+                    if display.show_gpu {
+                        ui.add_space(6.0);
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                egui::RichText::new("GPU")
+                                    .size(11.0)
+                                    .color(Theme::text_muted()),
+                            );
+                            ui.add_space(8.0);
+                            ResourceBar::mini(ui, instance.resource_usage.gpu_percent / 100.0);
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "{:.0}%",
+                                    instance.resource_usage.gpu_percent
+                                ))
+                                .size(11.0)
+                                .color(Theme::text_secondary()),
+                            );
+                        });
+                    }
 
-                    // Uptime badge
-                    egui::Frame::none()
-                        .fill(Theme::BG_TERTIARY.linear_multiply(0.6))
-                        .rounding(egui::Rounding::same(4.0))
-                        .inner_margin(egui::Margin::symmetric(8.0, 4.0))
-                        .show(ui, |ui| {
+                    if display.show_network {
+                        ui.add_space(6.0);
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                egui::RichText::new("NET")
+                                    .size(11.0)
+                                    .color(Theme::text_muted()),
+                            );
+                            ui.add_space(8.0);
                             ui.label(
-                                egui::RichText::new(format!("⏱ {}", instance.uptime_string()))
+                                egui::RichText::new(Self::network_string(instance))
                                     .size(11.0)
-                                    .color(Theme::TEXT_SECONDARY),
+                                    .color(Theme::text_secondary()),
                             );
                         });
+                    }
+
+                    ui.add_space(10.0);
+
+                    ui.horizontal_wrapped(|ui| {
+                        if display.show_uptime {
+                            Self::info_badge(ui, &format!("⏱ {}", instance.uptime_string()));
+                        }
+                        if display.show_pid {
+                            if let Some(pid) = instance.pid {
+                                Self::info_badge(ui, &format!("PID {}", pid));
+                            }
+                        }
+
+                        Self::copies_badge(ui, instance);
+                        Self::countdown_badge(ui, instance);
+                        Self::health_badge(ui, instance);
+
+                        if instance.update_available {
+                            if let Some(action) = Self::update_badge(ui, instance) {
+                                response.action = Some(action);
+                            }
+                        }
+                    });
                 } else if instance.status == InstanceStatus::Crashed {
                     if let Some(ref error) = instance.last_error {
                         egui::Frame::none()
-                            .fill(Theme::ERROR.linear_multiply(0.15))
+                            .fill(Theme::error().linear_multiply(0.15))
                             .rounding(egui::Rounding::same(6.0))
                             .inner_margin(egui::Margin::same(8.0))
                             .show(ui, |ui| {
                                 ui.label(
                                     egui::RichText::new(error)
                                         .size(11.0)
-                                        .color(Theme::ERROR_LIGHT),
+                                        .color(Theme::error_light()),
                                 );
                             });
                     }
+                } else if let (InstanceStatus::Pending, Some(condition)) =
+                    (&instance.status, &instance.config.network_condition)
+                {
+                    ui.add_space(20.0);
+                    ui.label(
+                        egui::RichText::new(format!("Waiting for {}", condition.describe()))
+                            .size(12.0)
+                            .color(Theme::primary()),
+                    );
+                    ui.add_space(10.0);
                 } else {
                     // Show placeholder for stopped instances
                     ui.add_space(20.0);
                     ui.label(
                         egui::RichText::new("Instance stopped")
                             .size(12.0)
-                            .color(Theme::TEXT_MUTED),
+                            .color(Theme::text_muted()),
                     );
                     ui.add_space(10.0);
                 }
@@ -148,99 +697,119 @@ impl InstanceCard {
 
                 // Action buttons row
                 ui.horizontal(|ui| {
-                    match instance.status {
-                        InstanceStatus::Running => {
-                            if Self::action_button(ui, Icons::PAUSE, "Pause", Theme::WARNING) {
-                                response.action = Some(CardAction::Pause);
-                            }
-                            ui.add_space(4.0);
-                            if Self::action_button(ui, Icons::STOP, "Stop", Theme::ERROR_LIGHT) {
-                                response.action = Some(CardAction::Stop);
-                            }
-                            ui.add_space(4.0);
-                            if Self::action_button(ui, Icons::RESTART, "Restart", Theme::INFO) {
-                                response.action = Some(CardAction::Restart);
-                            }
+                    if instance.status == InstanceStatus::Pending {
+                        if Self::action_button(
+                            ui,
+                            Icons::STOP,
+                            "Cancel launch",
+                            Theme::error_light(),
+                        ) {
+                            response.action = Some(CardAction::Stop);
                         }
-                        InstanceStatus::Paused => {
-                            if Self::action_button(ui, Icons::PLAY, "Resume", Theme::SUCCESS) {
-                                response.action = Some(CardAction::Resume);
-                            }
-                            ui.add_space(4.0);
-                            if Self::action_button(ui, Icons::STOP, "Stop", Theme::ERROR_LIGHT) {
-                                response.action = Some(CardAction::Stop);
+                    } else {
+                        for (index, action) in display
+                            .quick_actions
+                            .for_status(&instance.status)
+                            .into_iter()
+                            .enumerate()
+                        {
+                            if index > 0 {
+                                ui.add_space(4.0);
                             }
-                        }
-                        InstanceStatus::Stopped | InstanceStatus::Crashed => {
-                            if Self::action_button(ui, Icons::PLAY, "Start", Theme::SUCCESS) {
-                                response.action = Some(CardAction::Start);
+                            if Self::quick_action_button(ui, action) {
+                                response.action = Some(Self::card_action(action));
                             }
                         }
-                        _ => {}
                     }
 
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        if Self::action_button(ui, Icons::SETTINGS, "Configure", Theme::TEXT_MUTED)
-                        {
+                        if Self::action_button(
+                            ui,
+                            Icons::SETTINGS,
+                            "Configure",
+                            Theme::text_muted(),
+                        ) {
                             response.action = Some(CardAction::Configure);
                         }
+                        if instance.status != InstanceStatus::Pending {
+                            Self::variant_picker(ui, instance, &mut response);
+                        }
                     });
                 });
-            });
+
+                Self::context_menu(ui, instance, display, &mut response);
+            },
+        );
 
         response
     }
 
     /// Render instance as a list row
-    pub fn list(ui: &mut Ui, instance: &Instance) -> CardResponse {
+    pub fn list(
+        ui: &mut Ui,
+        instance: &Instance,
+        highlight: &str,
+        display: &CardDisplay,
+        icon: Option<&RgbaImage>,
+    ) -> CardResponse {
         let mut response = CardResponse::default();
 
         let status_color = Theme::status_color(&instance.status);
         let is_active = instance.status.is_active();
 
-        let row_response = egui::Frame::none()
-            .fill(Theme::BG_SECONDARY)
-            .rounding(egui::Rounding::same(10.0))
-            .stroke(egui::Stroke::new(
+        let row_response = ShapeCache::frame(
+            ui,
+            Theme::bg_secondary(),
+            10.0,
+            egui::Stroke::new(
                 1.0,
                 if is_active {
                     status_color.linear_multiply(0.3)
                 } else {
-                    Theme::BORDER_LIGHT
+                    Theme::border_light()
                 },
-            ))
-            .inner_margin(egui::Margin::symmetric(16.0, 12.0))
-            .show(ui, |ui| {
+            ),
+            egui::Margin::symmetric(16.0, 12.0),
+            |ui| {
                 ui.horizontal(|ui| {
                     // Status dot with glow effect for active
                     StatusBadge::dot(ui, &instance.status);
 
                     ui.add_space(12.0);
 
+                    if let Some(icon) = icon {
+                        ExecutableIcon::show(ui, &instance.config.executable_path, icon, 28.0);
+                        ui.add_space(8.0);
+                    }
+
                     // Name and executable in a column
                     ui.vertical(|ui| {
-                        ui.label(
-                            egui::RichText::new(instance.display_name())
-                                .strong()
-                                .size(14.0)
-                                .color(Theme::TEXT_PRIMARY),
-                        );
-                        if let Some(path) = instance.config.executable_path.file_name() {
-                            ui.label(
-                                egui::RichText::new(path.to_string_lossy())
-                                    .size(12.0)
-                                    .color(Theme::TEXT_MUTED),
-                            );
+                        Self::name_label(ui, instance, highlight, 14.0, &mut response);
+                        if display.size != CardSize::Small {
+                            if let Some(path) = instance.config.executable_path.file_name() {
+                                ui.label(
+                                    egui::RichText::new(path.to_string_lossy())
+                                        .size(12.0)
+                                        .color(Theme::text_muted()),
+                                );
+                            }
                         }
                     });
 
+                    if Self::has_labels(instance, display) {
+                        ui.add_space(12.0);
+                        if let Some(action) = Self::labels(ui, instance, display) {
+                            response.action = Some(action);
+                        }
+                    }
+
                     ui.add_space(24.0);
 
                     // Resource usage badges
                     if is_active {
                         // CPU badge
                         egui::Frame::none()
-                            .fill(Theme::BG_TERTIARY.linear_multiply(0.6))
+                            .fill(Theme::bg_tertiary().linear_multiply(0.6))
                             .rounding(egui::Rounding::same(4.0))
                             .inner_margin(egui::Margin::symmetric(8.0, 4.0))
                             .show(ui, |ui| {
@@ -250,7 +819,7 @@ impl InstanceCard {
                                         instance.resource_usage.cpu_percent
                                     ))
                                     .size(11.0)
-                                    .color(Theme::TEXT_SECONDARY),
+                                    .color(Theme::text_secondary()),
                                 );
                             });
 
@@ -258,77 +827,103 @@ impl InstanceCard {
 
                         // RAM badge
                         egui::Frame::none()
-                            .fill(Theme::BG_TERTIARY.linear_multiply(0.6))
+                            .fill(Theme::bg_tertiary().linear_multiply(0.6))
                             .rounding(egui::Rounding::same(4.0))
                             .inner_margin(egui::Margin::symmetric(8.0, 4.0))
                             .show(ui, |ui| {
                                 ui.label(
                                     egui::RichText::new(instance.resource_usage.memory_string())
                                         .size(11.0)
-                                        .color(Theme::TEXT_SECONDARY),
+                                        .color(Theme::text_secondary()),
                                 );
                             });
 
+                        if display.show_gpu {
+                            ui.add_space(8.0);
+                            Self::info_badge(
+                                ui,
+                                &format!("GPU {:.0}%", instance.resource_usage.gpu_percent),
+                            );
+                        }
+
+                        if display.show_network {
+                            ui.add_space(8.0);
+                            Self::info_badge(ui, &Self::network_string(instance));
+                        }
+
+                        if display.show_uptime {
+                            ui.add_space(8.0);
+                            Self::info_badge(ui, &format!("⏱ {}", instance.uptime_string()));
+                        }
+
+                        if display.show_pid {
+                            if let Some(pid) = instance.pid {
+                                ui.add_space(8.0);
+                                Self::info_badge(ui, &format!("PID {}", pid));
+                            }
+                        }
+
                         ui.add_space(8.0);
+                        Self::copies_badge(ui, instance);
+                        Self::countdown_badge(ui, instance);
+                        Self::health_badge(ui, instance);
 
-                        // Uptime badge
-                        egui::Frame::none()
-                            .fill(Theme::BG_TERTIARY.linear_multiply(0.6))
-                            .rounding(egui::Rounding::same(4.0))
-                            .inner_margin(egui::Margin::symmetric(8.0, 4.0))
-                            .show(ui, |ui| {
-                                ui.label(
-                                    egui::RichText::new(format!("⏱ {}", instance.uptime_string()))
-                                        .size(11.0)
-                                        .color(Theme::TEXT_SECONDARY),
-                                );
-                            });
+                        if instance.update_available {
+                            ui.add_space(8.0);
+                            if let Some(action) = Self::update_badge(ui, instance) {
+                                response.action = Some(action);
+                            }
+                        }
                     }
 
                     // Right-aligned actions
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        if Self::action_button(ui, Icons::SETTINGS, "Configure", Theme::TEXT_MUTED)
-                        {
+                        if Self::action_button(
+                            ui,
+                            Icons::SETTINGS,
+                            "Configure",
+                            Theme::text_muted(),
+                        ) {
                             response.action = Some(CardAction::Configure);
                         }
 
                         ui.add_space(6.0);
 
-                        match instance.status {
-                            InstanceStatus::Running => {
-                                if Self::action_button(ui, Icons::RESTART, "Restart", Theme::INFO) {
-                                    response.action = Some(CardAction::Restart);
-                                }
-                                ui.add_space(4.0);
-                                if Self::action_button(ui, Icons::STOP, "Stop", Theme::ERROR_LIGHT)
-                                {
-                                    response.action = Some(CardAction::Stop);
-                                }
-                                ui.add_space(4.0);
-                                if Self::action_button(ui, Icons::PAUSE, "Pause", Theme::WARNING) {
-                                    response.action = Some(CardAction::Pause);
-                                }
+                        if instance.status == InstanceStatus::Pending {
+                            if Self::action_button(
+                                ui,
+                                Icons::STOP,
+                                "Cancel launch",
+                                Theme::error_light(),
+                            ) {
+                                response.action = Some(CardAction::Stop);
                             }
-                            InstanceStatus::Paused => {
-                                if Self::action_button(ui, Icons::STOP, "Stop", Theme::ERROR_LIGHT)
-                                {
-                                    response.action = Some(CardAction::Stop);
-                                }
-                                ui.add_space(4.0);
-                                if Self::action_button(ui, Icons::PLAY, "Resume", Theme::SUCCESS) {
-                                    response.action = Some(CardAction::Resume);
+                        } else {
+                            // Right-to-left, so the first slot ends up leftmost
+                            for (index, action) in display
+                                .quick_actions
+                                .for_status(&instance.status)
+                                .into_iter()
+                                .rev()
+                                .enumerate()
+                            {
+                                if index > 0 {
+                                    ui.add_space(4.0);
                                 }
-                            }
-                            InstanceStatus::Stopped | InstanceStatus::Crashed => {
-                                if Self::action_button(ui, Icons::PLAY, "Start", Theme::SUCCESS) {
-                                    response.action = Some(CardAction::Start);
+                                if Self::quick_action_button(ui, action) {
+                                    response.action = Some(Self::card_action(action));
                                 }
                             }
-                            _ => {}
+
+                            ui.add_space(6.0);
+                            Self::variant_picker(ui, instance, &mut response);
                         }
                     });
                 });
-            });
+
+                Self::context_menu(ui, instance, display, &mut response);
+            },
+        );
 
         if row_response.response.clicked() {
             response.action = Some(CardAction::Select);
@@ -338,7 +933,12 @@ impl InstanceCard {
     }
 
     /// Render instance as a compact row
-    pub fn compact(ui: &mut Ui, instance: &Instance) -> CardResponse {
+    pub fn compact(
+        ui: &mut Ui,
+        instance: &Instance,
+        highlight: &str,
+        icon: Option<&RgbaImage>,
+    ) -> CardResponse {
         let mut response = CardResponse::default();
 
         let is_active = instance.status.is_active();
@@ -351,11 +951,17 @@ impl InstanceCard {
                     StatusBadge::dot(ui, &instance.status);
                     ui.add_space(10.0);
 
-                    ui.label(
-                        egui::RichText::new(instance.display_name())
-                            .size(13.0)
-                            .color(Theme::TEXT_PRIMARY),
-                    );
+                    if let Some(icon) = icon {
+                        ExecutableIcon::show(ui, &instance.config.executable_path, icon, 16.0);
+                        ui.add_space(6.0);
+                    }
+
+                    ui.label(search::highlighted(
+                        instance.display_name(),
+                        highlight,
+                        13.0,
+                        Theme::text_primary(),
+                    ));
 
                     if is_active {
                         ui.add_space(12.0);
@@ -365,16 +971,16 @@ impl InstanceCard {
                                 instance.resource_usage.cpu_percent
                             ))
                             .size(11.0)
-                            .color(Theme::TEXT_MUTED),
+                            .color(Theme::text_muted()),
                         );
                     }
 
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                         if is_active {
-                            if Self::action_button(ui, Icons::STOP, "Stop", Theme::ERROR_LIGHT) {
+                            if Self::action_button(ui, Icons::STOP, "Stop", Theme::error_light()) {
                                 response.action = Some(CardAction::Stop);
                             }
-                        } else if Self::action_button(ui, Icons::PLAY, "Start", Theme::SUCCESS) {
+                        } else if Self::action_button(ui, Icons::PLAY, "Start", Theme::success()) {
                             response.action = Some(CardAction::Start);
                         }
                     });
@@ -392,14 +998,33 @@ pub struct CardResponse {
 }
 
 /// Actions that can be triggered from a card
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum CardAction {
     Start,
     Stop,
     Pause,
     Resume,
     Restart,
+    /// Rolling restart of the instance's group
+    RestartGroup(String),
+    Kill,
     Configure,
     Select,
     Delete,
+    /// Play the macro at this index
+    RunMacro(usize),
+    /// Launch with the argument variant at this index (None = base arguments only)
+    SelectVariant(Option<usize>),
+    /// Rename the instance
+    Rename(String),
+    /// Restore a window started minimized or hidden
+    ShowWindow,
+    /// Save a PNG of the main window into the data directory
+    CaptureScreenshot,
+    /// Compress the data directory of a stopped instance
+    CompressData,
+    StopAndCompress,
+    DecompressData,
+    /// Pin or unpin the instance
+    SetPinned(bool),
 }
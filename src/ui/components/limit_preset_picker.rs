@@ -0,0 +1,54 @@
+//! Resource limit preset picker component
+
+use egui::Ui;
+
+use crate::core::{LimitPreset, ResourceLimits};
+use crate::ui::theme::Theme;
+
+pub struct LimitPresetPicker;
+
+impl LimitPresetPicker {
+    /// Render a dropdown that overwrites `limits` with the picked preset.
+    /// Returns true when a preset was applied this frame.
+    pub fn show(
+        ui: &mut Ui,
+        presets: &[LimitPreset],
+        limits: &mut ResourceLimits,
+        id_salt: impl std::hash::Hash,
+    ) -> bool {
+        let current = presets.iter().find(|p| p.limits == *limits);
+        let mut applied = false;
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt(id_salt)
+                .selected_text(current.map_or("Custom", |p| p.name.as_str()))
+                .show_ui(ui, |ui| {
+                    if presets.is_empty() {
+                        ui.label(
+                            egui::RichText::new("No presets; add some in Settings")
+                                .color(Theme::text_muted()),
+                        );
+                    }
+                    for preset in presets {
+                        if ui
+                            .selectable_label(current == Some(preset), &preset.name)
+                            .on_hover_text(preset.summary())
+                            .clicked()
+                        {
+                            *limits = preset.limits.clone();
+                            applied = true;
+                        }
+                    }
+                });
+
+            if let Some(preset) = current {
+                ui.label(
+                    egui::RichText::new(preset.summary())
+                        .size(11.0)
+                        .color(Theme::text_muted()),
+                );
+            }
+        });
+        applied
+    }
+}
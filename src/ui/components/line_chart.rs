@@ -0,0 +1,202 @@
+//! Line chart component - Scrollable time series
+
+use chrono::{DateTime, Local};
+use egui::{Color32, Id, Pos2, Response, Rounding, Sense, Shape, Stroke, Ui, Vec2};
+
+use crate::ui::theme::Theme;
+
+/// Seconds of history visible at once
+const VISIBLE_SECS: f64 = 300.0;
+
+const HEIGHT: f32 = 90.0;
+
+/// One line of a chart, oldest point first
+pub struct ChartSeries<'a> {
+    pub label: &'a str,
+    pub color: Color32,
+    pub points: Vec<(DateTime<Local>, f64)>,
+}
+
+pub struct LineChart;
+
+impl LineChart {
+    /// Render the last few minutes of `series`. Charts sharing `scroll_id` scroll
+    /// together; dragging scrolls back in time and a double-click returns to now.
+    /// `y_max` fixes the top of the scale, otherwise it follows the visible values.
+    pub fn show(
+        ui: &mut Ui,
+        scroll_id: Id,
+        series: &[ChartSeries],
+        y_max: Option<f64>,
+        format: impl Fn(f64) -> String,
+    ) -> Response {
+        let (rect, response) = ui.allocate_exact_size(
+            Vec2::new(ui.available_width(), HEIGHT),
+            Sense::click_and_drag(),
+        );
+        let now = Local::now();
+
+        // Seconds the right edge lies before now
+        let oldest = series
+            .iter()
+            .filter_map(|s| s.points.first())
+            .map(|(time, _)| *time)
+            .min();
+        let max_offset = oldest.map_or(0.0, |t| (secs_between(t, now) - VISIBLE_SECS).max(0.0));
+        let mut offset: f64 = ui.data(|d| d.get_temp(scroll_id)).unwrap_or(0.0);
+        let secs_per_px = VISIBLE_SECS / rect.width().max(1.0) as f64;
+        if response.dragged() {
+            offset += response.drag_delta().x as f64 * secs_per_px;
+        }
+        if response.hovered() {
+            offset += ui.input(|i| i.smooth_scroll_delta.x) as f64 * secs_per_px;
+        }
+        if response.double_clicked() {
+            offset = 0.0;
+        }
+        let offset = offset.clamp(0.0, max_offset);
+        ui.data_mut(|d| d.insert_temp(scroll_id, offset));
+
+        if !ui.is_rect_visible(rect) {
+            return response;
+        }
+
+        let right = now - chrono::Duration::milliseconds((offset * 1000.0) as i64);
+        let left = right - chrono::Duration::milliseconds((VISIBLE_SECS * 1000.0) as i64);
+        let visible: Vec<&[(DateTime<Local>, f64)]> = series
+            .iter()
+            .map(|s| {
+                // One point past each edge so lines run to the border
+                let start = s
+                    .points
+                    .partition_point(|(t, _)| *t < left)
+                    .saturating_sub(1);
+                let end = (s.points.partition_point(|(t, _)| *t <= right) + 1).min(s.points.len());
+                &s.points[start.min(end)..end]
+            })
+            .collect();
+        let top = y_max
+            .unwrap_or_else(|| {
+                visible
+                    .iter()
+                    .flat_map(|points| points.iter().map(|(_, v)| *v))
+                    .fold(0.0, f64::max)
+                    * 1.1
+            })
+            .max(1.0);
+
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, Rounding::same(6.0), Theme::bg_tertiary());
+        for fraction in [0.25, 0.5, 0.75] {
+            let y = rect.bottom() - rect.height() * fraction;
+            painter.hline(rect.x_range(), y, Stroke::new(1.0, Theme::border_light()));
+        }
+
+        let to_screen = |time: DateTime<Local>, value: f64| {
+            let x = rect.left() + (secs_between(left, time) / VISIBLE_SECS) as f32 * rect.width();
+            let y = rect.bottom() - (value / top).clamp(0.0, 1.0) as f32 * rect.height();
+            Pos2::new(x, y)
+        };
+        for (s, points) in series.iter().zip(&visible) {
+            let line: Vec<Pos2> = points.iter().map(|(t, v)| to_screen(*t, *v)).collect();
+            if line.len() > 1 {
+                painter.add(Shape::line(line, Stroke::new(1.5, s.color)));
+            }
+        }
+
+        // Scale and time axis
+        let small = egui::FontId::proportional(10.0);
+        let inset = Vec2::new(6.0, 4.0);
+        painter.text(
+            rect.left_top() + inset,
+            egui::Align2::LEFT_TOP,
+            format(top),
+            small.clone(),
+            Theme::text_muted(),
+        );
+        painter.text(
+            rect.left_bottom() + Vec2::new(inset.x, -inset.y),
+            egui::Align2::LEFT_BOTTOM,
+            left.format("%H:%M:%S").to_string(),
+            small.clone(),
+            Theme::text_muted(),
+        );
+        let right_label = if offset < 1.0 {
+            "now".to_string()
+        } else {
+            right.format("%H:%M:%S").to_string()
+        };
+        painter.text(
+            rect.right_bottom() - inset,
+            egui::Align2::RIGHT_BOTTOM,
+            right_label,
+            small.clone(),
+            Theme::text_muted(),
+        );
+        if series.len() > 1 {
+            let mut pos = rect.right_top() + Vec2::new(-inset.x, inset.y);
+            for s in series.iter().rev() {
+                let label = painter.text(
+                    pos,
+                    egui::Align2::RIGHT_TOP,
+                    s.label,
+                    small.clone(),
+                    s.color,
+                );
+                pos.x = label.left() - 8.0;
+            }
+        }
+
+        // Values under the pointer
+        let Some(pointer) = response.hover_pos() else {
+            return response;
+        };
+        let at = left
+            + chrono::Duration::milliseconds(
+                (((pointer.x - rect.left()) / rect.width()) as f64 * VISIBLE_SECS * 1000.0) as i64,
+            );
+        let nearest: Vec<Option<(DateTime<Local>, f64)>> = visible
+            .iter()
+            .map(|points| {
+                points
+                    .iter()
+                    .min_by_key(|(t, _)| (*t - at).num_milliseconds().abs())
+                    .copied()
+            })
+            .collect();
+        let Some(time) = nearest.iter().flatten().map(|(t, _)| *t).next() else {
+            return response;
+        };
+
+        painter.vline(
+            to_screen(time, 0.0).x,
+            rect.y_range(),
+            Stroke::new(1.0, Theme::border()),
+        );
+        for (s, point) in series.iter().zip(&nearest) {
+            if let Some((t, v)) = point {
+                painter.circle_filled(to_screen(*t, *v), 3.0, s.color);
+            }
+        }
+
+        response.on_hover_ui_at_pointer(|ui| {
+            ui.label(egui::RichText::new(time.format("%H:%M:%S").to_string()).strong());
+            for (s, point) in series.iter().zip(&nearest) {
+                if let Some((_, v)) = point {
+                    ui.label(
+                        egui::RichText::new(format!("{}: {}", s.label, format(*v))).color(s.color),
+                    );
+                }
+            }
+            ui.label(
+                egui::RichText::new("Drag to scroll back, double-click for now")
+                    .size(10.0)
+                    .color(Theme::text_muted()),
+            );
+        })
+    }
+}
+
+fn secs_between(from: DateTime<Local>, to: DateTime<Local>) -> f64 {
+    (to - from).num_milliseconds() as f64 / 1000.0
+}
@@ -0,0 +1,418 @@
+//! Macro editor component - List, edit, record and run an instance's macros
+
+use egui::Ui;
+
+use crate::core::macros::{InputEvent, Macro, MacroStep, MacroTrigger, Modifiers, MouseButton};
+use crate::core::{AppState, Instance, InstanceId, InstanceStatus};
+use crate::ui::theme::Theme;
+
+/// Gaps between recorded inputs shorter than this are not kept as waits
+const MIN_RECORDED_WAIT_MS: u32 = 200;
+
+/// Widest the window screenshot is drawn while recording
+const MAX_RECORDER_WIDTH: f32 = 720.0;
+
+/// A recording in progress, kept in egui memory between frames
+#[derive(Clone)]
+struct Recording {
+    /// Macro the steps will be appended to
+    index: usize,
+    steps: Vec<MacroStep>,
+    /// egui time of the previous recorded input
+    last_input: Option<f64>,
+    /// Screenshot of the window and its size in pixels
+    screenshot: Result<(egui::TextureHandle, egui::Vec2), String>,
+}
+
+impl Recording {
+    fn push(&mut self, step: MacroStep, now: f64) {
+        if let Some(last) = self.last_input {
+            let gap = ((now - last) * 1000.0) as u32;
+            if gap >= MIN_RECORDED_WAIT_MS {
+                self.steps.push(MacroStep::Wait { ms: gap / 50 * 50 });
+            }
+        }
+        self.last_input = Some(now);
+        self.steps.push(step);
+    }
+
+    /// Add typed text, extending the previous step when it was text too
+    fn push_text(&mut self, text: &str, now: f64) {
+        if let Some(MacroStep::Input(InputEvent::Text(previous))) = self.steps.last_mut() {
+            previous.push_str(text);
+            self.last_input = Some(now);
+        } else {
+            self.push(MacroStep::Input(InputEvent::Text(text.to_string())), now);
+        }
+    }
+}
+
+pub struct MacroEditor;
+
+impl MacroEditor {
+    pub fn show(ui: &mut Ui, state: &AppState, instance: &Instance) {
+        let id = instance.id;
+        let running = instance.status == InstanceStatus::Running;
+        let busy = state.is_macro_running(id);
+        let mut macros = instance.config.macros.clone();
+        let mut run = None;
+        let mut record = None;
+        let mut remove = None;
+
+        ui.label(
+            egui::RichText::new(
+                "Macros replay keystrokes and clicks into the instance's window. \
+                 The window is brought to the front while a macro plays.",
+            )
+            .size(11.0)
+            .color(Theme::text_muted()),
+        );
+        if let Some(ref outcome) = instance.macro_outcome {
+            ui.label(
+                egui::RichText::new(format!("Last run: {}", outcome))
+                    .size(11.0)
+                    .color(Theme::text_secondary()),
+            );
+        }
+        ui.add_space(8.0);
+
+        egui::ScrollArea::vertical()
+            .max_height(420.0)
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                for (index, recorded) in macros.iter_mut().enumerate() {
+                    egui::Frame::none()
+                        .fill(Theme::bg_secondary())
+                        .rounding(egui::Rounding::same(8.0))
+                        .inner_margin(egui::Margin::same(10.0))
+                        .show(ui, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.add(
+                                    egui::TextEdit::singleline(&mut recorded.name)
+                                        .hint_text("Macro name")
+                                        .desired_width(160.0),
+                                );
+                                Self::trigger_picker(ui, &mut recorded.trigger, (id, index));
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| {
+                                        if ui.small_button("✕").on_hover_text("Delete").clicked()
+                                        {
+                                            remove = Some(index);
+                                        }
+                                        if ui
+                                            .add_enabled(running, egui::Button::new("⏺ Record"))
+                                            .clicked()
+                                        {
+                                            record = Some(index);
+                                        }
+                                        let can_run =
+                                            running && !busy && !recorded.steps.is_empty();
+                                        if ui
+                                            .add_enabled(can_run, egui::Button::new("▶ Run"))
+                                            .clicked()
+                                        {
+                                            run = Some(index);
+                                        }
+                                    },
+                                );
+                            });
+                            ui.add_space(4.0);
+                            Self::steps(ui, &mut recorded.steps);
+                        });
+                    ui.add_space(6.0);
+                }
+            });
+
+        if ui.button("+ New macro").clicked() {
+            macros.push(Macro::new(format!("Macro {}", macros.len() + 1)));
+        }
+        if !running {
+            ui.label(
+                egui::RichText::new("Start the instance to record or run macros.")
+                    .size(11.0)
+                    .color(Theme::text_muted()),
+            );
+        }
+
+        if let Some(index) = remove {
+            macros.remove(index);
+        }
+        if macros != instance.config.macros {
+            if let Err(e) = state.set_macros(id, macros.clone()) {
+                tracing::error!("Failed to save macros: {}", e);
+            }
+        }
+
+        if let Some(index) = run {
+            if let Err(e) = state.run_macro(id, index) {
+                tracing::error!("Failed to run macro: {}", e);
+            }
+        }
+
+        let recording_id = egui::Id::new(("macro_recording", id.0));
+        if let (Some(index), Some(pid)) = (record, instance.pid) {
+            let recording = Recording {
+                index,
+                steps: Vec::new(),
+                last_input: None,
+                screenshot: Self::screenshot(ui.ctx(), id, pid),
+            };
+            ui.data_mut(|d| d.insert_temp(recording_id, recording));
+        }
+        if let Some(mut recording) = ui.data(|d| d.get_temp::<Recording>(recording_id)) {
+            let name = macros
+                .get(recording.index)
+                .map(|m| m.name.clone())
+                .unwrap_or_default();
+            match Self::recorder(ui.ctx(), &mut recording, &name, instance) {
+                None => ui.data_mut(|d| d.insert_temp(recording_id, recording)),
+                Some(save) => {
+                    ui.data_mut(|d| d.remove::<Recording>(recording_id));
+                    if let (true, Some(recorded)) = (save, macros.get_mut(recording.index)) {
+                        recorded.steps.extend(recording.steps);
+                        if let Err(e) = state.set_macros(id, macros) {
+                            tracing::error!("Failed to save macros: {}", e);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn trigger_picker(ui: &mut Ui, trigger: &mut MacroTrigger, salt: (InstanceId, usize)) {
+        egui::ComboBox::from_id_salt(("macro_trigger", salt.0 .0, salt.1))
+            .width(120.0)
+            .selected_text(trigger.label())
+            .show_ui(ui, |ui| {
+                for option in MacroTrigger::all() {
+                    let selected =
+                        std::mem::discriminant(trigger) == std::mem::discriminant(&option);
+                    if ui.selectable_label(selected, option.label()).clicked() && !selected {
+                        *trigger = option;
+                    }
+                }
+            });
+        match trigger {
+            MacroTrigger::Manual => {}
+            MacroTrigger::LaunchComplete { delay_secs } => {
+                ui.add(
+                    egui::DragValue::new(delay_secs)
+                        .range(0..=600)
+                        .prefix("after ")
+                        .suffix(" s"),
+                );
+            }
+            MacroTrigger::Every { minutes } => {
+                ui.add(
+                    egui::DragValue::new(minutes)
+                        .range(1..=1440)
+                        .prefix("every ")
+                        .suffix(" min"),
+                );
+            }
+        }
+    }
+
+    /// Step list; text and waits can be edited in place
+    fn steps(ui: &mut Ui, steps: &mut Vec<MacroStep>) {
+        if steps.is_empty() {
+            ui.label(
+                egui::RichText::new("No steps yet. Record some or add them below.")
+                    .size(11.0)
+                    .color(Theme::text_muted()),
+            );
+        }
+        let mut remove = None;
+        for (index, step) in steps.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(
+                    egui::RichText::new(format!("{}.", index + 1))
+                        .size(11.0)
+                        .color(Theme::text_muted()),
+                );
+                match step {
+                    MacroStep::Input(InputEvent::Text(text)) => {
+                        ui.label("Type");
+                        ui.add(egui::TextEdit::singleline(text).desired_width(200.0));
+                    }
+                    MacroStep::Wait { ms } => {
+                        ui.label("Wait");
+                        ui.add(
+                            egui::DragValue::new(ms)
+                                .range(0..=600_000)
+                                .speed(50)
+                                .suffix(" ms"),
+                        );
+                    }
+                    step => {
+                        ui.label(step.describe());
+                    }
+                }
+                if ui.small_button("✕").clicked() {
+                    remove = Some(index);
+                }
+            });
+        }
+        if let Some(index) = remove {
+            steps.remove(index);
+        }
+        ui.horizontal(|ui| {
+            if ui.small_button("+ Type text").clicked() {
+                steps.push(MacroStep::Input(InputEvent::Text(String::new())));
+            }
+            if ui.small_button("+ Wait").clicked() {
+                steps.push(MacroStep::Wait { ms: 1000 });
+            }
+        });
+    }
+
+    /// Capture the instance's window for the recorder to click on
+    fn screenshot(
+        ctx: &egui::Context,
+        id: InstanceId,
+        pid: u32,
+    ) -> Result<(egui::TextureHandle, egui::Vec2), String> {
+        let image = crate::platform::capture_window(pid).map_err(|e| e.to_string())?;
+        let size = egui::vec2(image.width() as f32, image.height() as f32);
+        let pixels = egui::ColorImage::from_rgba_unmultiplied(
+            [image.width() as usize, image.height() as usize],
+            image.as_raw(),
+        );
+        let texture = ctx.load_texture(
+            format!("macro_recorder_{}", id.0),
+            pixels,
+            egui::TextureOptions::LINEAR,
+        );
+        Ok((texture, size))
+    }
+
+    /// Recorder window. Returns Some(true) to keep the steps, Some(false) to discard them.
+    fn recorder(
+        ctx: &egui::Context,
+        recording: &mut Recording,
+        name: &str,
+        instance: &Instance,
+    ) -> Option<bool> {
+        let mut result = None;
+        let mut open = true;
+        egui::Window::new(format!("Recording: {}", name))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(
+                    egui::RichText::new(
+                        "Click the screenshot where the macro should click, and type \
+                         while this window is focused. Pauses are kept as waits.",
+                    )
+                    .size(11.0)
+                    .color(Theme::text_muted()),
+                );
+                ui.add_space(6.0);
+
+                let now = ui.input(|i| i.time);
+                match &recording.screenshot {
+                    Ok((texture, size)) => {
+                        let scale = (MAX_RECORDER_WIDTH / size.x).min(1.0);
+                        let response = ui.add(
+                            egui::Image::new((texture.id(), *size * scale))
+                                .sense(egui::Sense::click()),
+                        );
+                        let button = if response.clicked() {
+                            Some(MouseButton::Left)
+                        } else if response.secondary_clicked() {
+                            Some(MouseButton::Right)
+                        } else if response.middle_clicked() {
+                            Some(MouseButton::Middle)
+                        } else {
+                            None
+                        };
+                        if let (Some(button), Some(pos)) = (button, response.interact_pointer_pos())
+                        {
+                            let local = (pos - response.rect.min) / scale;
+                            recording.push(
+                                MacroStep::Input(InputEvent::Click {
+                                    x: local.x.round() as i32,
+                                    y: local.y.round() as i32,
+                                    button,
+                                }),
+                                now,
+                            );
+                        }
+                    }
+                    Err(e) => {
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "Could not capture the window ({}). Keystrokes are still recorded.",
+                                e
+                            ))
+                            .size(11.0)
+                            .color(Theme::warning()),
+                        );
+                    }
+                }
+
+                let events = ui.input(|i| i.events.clone());
+                for event in events {
+                    match event {
+                        egui::Event::Text(text) => recording.push_text(&text, now),
+                        egui::Event::Key {
+                            key,
+                            pressed: true,
+                            repeat: false,
+                            modifiers,
+                            ..
+                        } => {
+                            // Plain printable keys arrive as Text events as well
+                            let shortcut = modifiers.ctrl || modifiers.alt || modifiers.mac_cmd;
+                            if shortcut || !is_printable(key) {
+                                recording.push(
+                                    MacroStep::Input(InputEvent::Key {
+                                        key: key.name().to_string(),
+                                        modifiers: Modifiers {
+                                            ctrl: modifiers.ctrl,
+                                            shift: modifiers.shift,
+                                            alt: modifiers.alt,
+                                            command: modifiers.mac_cmd,
+                                        },
+                                    }),
+                                    now,
+                                );
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+
+                ui.add_space(6.0);
+                ui.label(
+                    egui::RichText::new(format!("{} steps recorded", recording.steps.len()))
+                        .size(11.0)
+                        .color(Theme::text_secondary()),
+                );
+                ui.horizontal(|ui| {
+                    if ui.button("Stop & Save").clicked() {
+                        result = Some(true);
+                    }
+                    if ui.button("Refresh screenshot").clicked() {
+                        if let Some(pid) = instance.pid {
+                            recording.screenshot = Self::screenshot(ctx, instance.id, pid);
+                        }
+                    }
+                    if ui.button("Discard").clicked() {
+                        result = Some(false);
+                    }
+                });
+            });
+        if !open {
+            result = Some(false);
+        }
+        result
+    }
+}
+
+/// Keys whose press also produces a Text event
+fn is_printable(key: egui::Key) -> bool {
+    key == egui::Key::Space || key.name().chars().count() == 1
+}
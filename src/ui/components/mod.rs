@@ -1,9 +1,22 @@
 //! Reusable UI components
 
+mod affinity_picker;
+mod executable_icon;
 pub mod instance_card;
+mod limit_preset_picker;
+mod line_chart;
+mod macro_editor;
 pub mod profile_card;
 mod resource_bar;
+mod shape_cache;
+mod skeleton;
 mod status_badge;
 
+pub use affinity_picker::AffinityPicker;
+pub use executable_icon::ExecutableIcon;
 pub use instance_card::{CardAction, InstanceCard};
+pub use limit_preset_picker::LimitPresetPicker;
+pub use line_chart::{ChartSeries, LineChart};
+pub use macro_editor::MacroEditor;
 pub use resource_bar::ResourceBar;
+pub use skeleton::Skeleton;
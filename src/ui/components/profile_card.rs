@@ -2,6 +2,7 @@
 
 use egui::Ui;
 
+use crate::core::profile::ProfileRun;
 use crate::core::Profile;
 use crate::ui::theme::{Icons, Theme};
 
@@ -13,9 +14,9 @@ impl ProfileCard {
         let mut response = ProfileCardResponse::default();
 
         egui::Frame::none()
-            .fill(Theme::BG_SECONDARY)
+            .fill(Theme::bg_secondary())
             .rounding(egui::Rounding::same(8.0))
-            .stroke(egui::Stroke::new(1.0, Theme::BORDER_LIGHT))
+            .stroke(egui::Stroke::new(1.0, Theme::border_light()))
             .inner_margin(egui::Margin::same(12.0))
             .show(ui, |ui| {
                 ui.set_width(250.0);
@@ -30,7 +31,7 @@ impl ProfileCard {
                             Icons::STAR_EMPTY
                         };
                         if ui
-                            .button(egui::RichText::new(star_icon).color(Theme::WARNING))
+                            .button(egui::RichText::new(star_icon).color(Theme::warning()))
                             .clicked()
                         {
                             response.action = Some(ProfileAction::ToggleFavorite);
@@ -45,7 +46,7 @@ impl ProfileCard {
                     ui.label(
                         egui::RichText::new(&profile.description)
                             .small()
-                            .color(Theme::TEXT_SECONDARY),
+                            .color(Theme::text_secondary()),
                     );
                     ui.add_space(4.0);
                 }
@@ -54,21 +55,21 @@ impl ProfileCard {
                 ui.label(
                     egui::RichText::new(format!("{} instances", profile.instance_count()))
                         .small()
-                        .color(Theme::TEXT_MUTED),
+                        .color(Theme::text_muted()),
                 );
 
                 // Category/tags
                 if let Some(ref category) = profile.category {
                     ui.horizontal(|ui| {
                         egui::Frame::none()
-                            .fill(Theme::PRIMARY.linear_multiply(0.2))
+                            .fill(Theme::primary().linear_multiply(0.2))
                             .rounding(egui::Rounding::same(4.0))
                             .inner_margin(egui::Margin::symmetric(6.0, 2.0))
                             .show(ui, |ui| {
                                 ui.label(
                                     egui::RichText::new(category)
                                         .small()
-                                        .color(Theme::PRIMARY_LIGHT),
+                                        .color(Theme::primary_light()),
                                 );
                             });
                     });
@@ -81,10 +82,15 @@ impl ProfileCard {
                     ui.label(
                         egui::RichText::new(format!("Launched {} times", profile.launch_count))
                             .small()
-                            .color(Theme::TEXT_MUTED),
+                            .color(Theme::text_muted()),
                     );
                 });
 
+                // Last run result
+                if let Some(ref run) = profile.last_run {
+                    Self::last_run(ui, run);
+                }
+
                 ui.add_space(8.0);
 
                 // Action buttons
@@ -92,6 +98,20 @@ impl ProfileCard {
                     if ui.button(format!("{} Launch", Icons::PLAY)).clicked() {
                         response.action = Some(ProfileAction::Launch);
                     }
+                    if ui
+                        .button("☑")
+                        .on_hover_text("Launch only some members")
+                        .clicked()
+                    {
+                        response.action = Some(ProfileAction::LaunchPartial);
+                    }
+                    if ui
+                        .button(Icons::RESTART)
+                        .on_hover_text("Rolling restart: restart running instances one at a time")
+                        .clicked()
+                    {
+                        response.action = Some(ProfileAction::RollingRestart);
+                    }
 
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                         if ui.small_button(Icons::TRASH).clicked() {
@@ -110,21 +130,45 @@ impl ProfileCard {
         response
     }
 
+    /// One-line outcome of the most recent launch, with failures on hover
+    fn last_run(ui: &mut Ui, run: &ProfileRun) {
+        let (icon, color) = if run.succeeded() {
+            ("✓", Theme::success())
+        } else if run.started > 0 {
+            ("⚠", Theme::warning())
+        } else {
+            ("✕", Theme::error())
+        };
+        let when = run
+            .started_at
+            .with_timezone(&chrono::Local)
+            .format("%b %d %H:%M");
+
+        let label = ui.label(
+            egui::RichText::new(format!("{} Last run {} · {}", icon, when, run.summary()))
+                .small()
+                .color(color),
+        );
+        if !run.failures.is_empty() {
+            label.on_hover_text(run.failures.join("\n"));
+        }
+    }
+
     /// Render profile as a list row
     pub fn list_row(ui: &mut Ui, profile: &Profile) -> ProfileCardResponse {
         let mut response = ProfileCardResponse::default();
 
         egui::Frame::none()
-            .fill(Theme::BG_SECONDARY)
+            .fill(Theme::bg_secondary())
             .rounding(egui::Rounding::same(4.0))
             .inner_margin(egui::Margin::symmetric(12.0, 8.0))
             .show(ui, |ui| {
                 ui.horizontal(|ui| {
                     // Favorite star
                     let star_color = if profile.is_favorite {
-                        Theme::WARNING
+                        Theme::warning()
                     } else {
-                        Theme::TEXT_MUTED
+                        Theme::text_muted()
                     };
                     let star_icon = if profile.is_favorite {
                         Icons::STAR
@@ -148,21 +192,21 @@ impl ProfileCard {
                     // Instance count
                     ui.label(
                         egui::RichText::new(format!("{} instances", profile.instance_count()))
-                            .color(Theme::TEXT_SECONDARY),
+                            .color(Theme::text_secondary()),
                     );
 
                     // Category
                     if let Some(ref category) = profile.category {
                         ui.add_space(8.0);
                         egui::Frame::none()
-                            .fill(Theme::PRIMARY.linear_multiply(0.2))
+                            .fill(Theme::primary().linear_multiply(0.2))
                             .rounding(egui::Rounding::same(4.0))
                             .inner_margin(egui::Margin::symmetric(6.0, 2.0))
                             .show(ui, |ui| {
                                 ui.label(
                                     egui::RichText::new(category)
                                         .small()
-                                        .color(Theme::PRIMARY_LIGHT),
+                                        .color(Theme::primary_light()),
                                 );
                             });
                     }
@@ -175,6 +219,22 @@ impl ProfileCard {
                         if ui.small_button(Icons::EDIT).clicked() {
                             response.action = Some(ProfileAction::Edit);
                         }
+                        if ui
+                            .small_button(Icons::RESTART)
+                            .on_hover_text(
+                                "Rolling restart: restart running instances one at a time",
+                            )
+                            .clicked()
+                        {
+                            response.action = Some(ProfileAction::RollingRestart);
+                        }
+                        if ui
+                            .small_button("☑")
+                            .on_hover_text("Launch only some members")
+                            .clicked()
+                        {
+                            response.action = Some(ProfileAction::LaunchPartial);
+                        }
                         if ui.button(format!("{} Launch", Icons::PLAY)).clicked() {
                             response.action = Some(ProfileAction::Launch);
                         }
@@ -196,6 +256,9 @@ pub struct ProfileCardResponse {
 #[derive(Debug, Clone, Copy)]
 pub enum ProfileAction {
     Launch,
+    /// Choose which members to launch
+    LaunchPartial,
+    RollingRestart,
     Edit,
     Delete,
     Export,
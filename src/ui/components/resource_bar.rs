@@ -1,9 +1,11 @@
 //! Resource usage bar component
 
-use egui::{Color32, Rect, Response, Rounding, Ui, Vec2};
+use egui::{Color32, Pos2, Rect, Response, Rounding, Shape, Ui, Vec2};
 
 use crate::ui::theme::Theme;
 
+use super::shape_cache::ShapeCache;
+
 pub struct ResourceBar;
 
 impl ResourceBar {
@@ -24,11 +26,11 @@ impl ResourceBar {
             let clamped_value = value.clamp(0.0, 1.0);
 
             // Background with subtle border
-            painter.rect_filled(rect, Rounding::same(6.0), Theme::BG_TERTIARY);
+            painter.rect_filled(rect, Rounding::same(6.0), Theme::bg_tertiary());
             painter.rect_stroke(
                 rect,
                 Rounding::same(6.0),
-                egui::Stroke::new(1.0, Theme::BORDER_LIGHT),
+                egui::Stroke::new(1.0, Theme::border_light()),
             );
 
             // Fill with gradient effect
@@ -81,7 +83,7 @@ impl ResourceBar {
                 egui::Align2::CENTER_CENTER,
                 text,
                 egui::FontId::proportional(11.0),
-                Theme::TEXT_PRIMARY,
+                Theme::text_primary(),
             );
         }
 
@@ -99,7 +101,7 @@ impl ResourceBar {
             let clamped_value = value.clamp(0.0, 1.0);
 
             // Background
-            painter.rect_filled(rect, Rounding::same(3.0), Theme::BG_TERTIARY);
+            painter.rect_filled(rect, Rounding::same(3.0), Theme::bg_tertiary());
 
             // Fill from bottom
             let fill_height = rect.height() * clamped_value;
@@ -124,7 +126,7 @@ impl ResourceBar {
             let clamped_value = value.clamp(0.0, 1.0);
 
             // Background
-            painter.rect_filled(rect, Rounding::same(3.0), Theme::BG_TERTIARY);
+            painter.rect_filled(rect, Rounding::same(3.0), Theme::bg_tertiary());
 
             // Fill
             let fill_width = rect.width() * clamped_value;
@@ -149,41 +151,59 @@ impl ResourceBar {
             let stroke_width = size * 0.12;
             let clamped_value = value.clamp(0.0, 1.0);
 
-            // Background circle
-            painter.circle_stroke(
-                center,
-                radius,
-                egui::Stroke::new(stroke_width, Theme::BG_TERTIARY),
+            // Ring and arc only change with the whole percentage and the theme,
+            // so reuse their mesh
+            let percent = (clamped_value * 100.0).round() as u8;
+            let fill_color = Self::color_for_value(clamped_value);
+            let ring_color = Theme::bg_tertiary();
+            ShapeCache::paint(
+                ui,
+                (
+                    "circular_bar",
+                    size.to_bits(),
+                    percent,
+                    fill_color,
+                    ring_color,
+                ),
+                center.to_vec2(),
+                || {
+                    let value = percent as f32 / 100.0;
+                    let mut shapes = vec![Shape::circle_stroke(
+                        Pos2::ZERO,
+                        radius,
+                        egui::Stroke::new(stroke_width, ring_color),
+                    )];
+
+                    // Progress arc
+                    if value > 0.0 {
+                        let n_points = (32.0 * value).max(2.0) as usize;
+                        let start_angle = -std::f32::consts::FRAC_PI_2;
+                        let end_angle = start_angle + std::f32::consts::TAU * value;
+
+                        let points: Vec<Pos2> = (0..=n_points)
+                            .map(|i| {
+                                let t = i as f32 / n_points as f32;
+                                let angle = start_angle + (end_angle - start_angle) * t;
+                                Pos2::ZERO + Vec2::new(angle.cos(), angle.sin()) * radius
+                            })
+                            .collect();
+
+                        shapes.push(Shape::line(
+                            points,
+                            egui::Stroke::new(stroke_width, fill_color),
+                        ));
+                    }
+                    shapes
+                },
             );
 
-            // Progress arc
-            if clamped_value > 0.0 {
-                let fill_color = Self::color_for_value(clamped_value);
-                let n_points = (32.0 * clamped_value).max(2.0) as usize;
-                let start_angle = -std::f32::consts::FRAC_PI_2;
-                let end_angle = start_angle + std::f32::consts::TAU * clamped_value;
-
-                let points: Vec<egui::Pos2> = (0..=n_points)
-                    .map(|i| {
-                        let t = i as f32 / n_points as f32;
-                        let angle = start_angle + (end_angle - start_angle) * t;
-                        center + Vec2::new(angle.cos(), angle.sin()) * radius
-                    })
-                    .collect();
-
-                painter.add(egui::Shape::line(
-                    points,
-                    egui::Stroke::new(stroke_width, fill_color),
-                ));
-            }
-
             // Center text
             painter.text(
                 center,
                 egui::Align2::CENTER_CENTER,
                 format!("{:.0}", clamped_value * 100.0),
                 egui::FontId::proportional(size * 0.25),
-                Theme::TEXT_PRIMARY,
+                Theme::text_primary(),
             );
         }
 
@@ -194,18 +214,18 @@ impl ResourceBar {
     fn color_for_value(value: f32) -> Color32 {
         if value < 0.5 {
             // Green zone
-            Theme::SUCCESS
+            Theme::success()
         } else if value < 0.75 {
             // Transition to warning
             let t = (value - 0.5) / 0.25;
-            Self::lerp_color(Theme::SUCCESS, Theme::WARNING, t)
+            Self::lerp_color(Theme::success(), Theme::warning(), t)
         } else if value < 0.9 {
             // Warning zone
-            Theme::WARNING
+            Theme::warning()
         } else {
             // Critical zone
             let t = (value - 0.9) / 0.1;
-            Self::lerp_color(Theme::WARNING, Theme::ERROR, t.min(1.0))
+            Self::lerp_color(Theme::warning(), Theme::error(), t.min(1.0))
         }
     }
 
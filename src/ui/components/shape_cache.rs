@@ -0,0 +1,120 @@
+//! Shape cache - Tessellate static decorations once and reuse the mesh every frame
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+
+use egui::epaint::{Mesh, Tessellator};
+use egui::layers::ShapeIdx;
+use egui::{Color32, InnerResponse, Margin, Pos2, Rect, Rounding, Shape, Stroke, Ui, Vec2};
+
+/// Cached meshes are dropped wholesale once this many accumulate
+const MAX_ENTRIES: usize = 512;
+
+type MeshMap = HashMap<egui::Id, Arc<Mesh>>;
+
+pub struct ShapeCache;
+
+impl ShapeCache {
+    /// Paint shapes built around the origin at `offset`. The shapes are only
+    /// built and tessellated when `key` (plus the display scale) is new.
+    pub fn paint(
+        ui: &Ui,
+        key: impl Hash,
+        offset: Vec2,
+        build: impl FnOnce() -> Vec<Shape>,
+    ) -> ShapeIdx {
+        let shape = Self::shape(ui, key, offset, build);
+        ui.painter().add(shape)
+    }
+
+    /// Like [`Self::paint`], but fills a placeholder reserved earlier with `Shape::Noop`
+    pub fn set(
+        ui: &Ui,
+        idx: ShapeIdx,
+        key: impl Hash,
+        offset: Vec2,
+        build: impl FnOnce() -> Vec<Shape>,
+    ) {
+        let shape = Self::shape(ui, key, offset, build);
+        ui.painter().set(idx, shape);
+    }
+
+    /// A frame whose background and border are drawn from the cache.
+    /// Equivalent to `Frame::none().fill(..).rounding(..).stroke(..).inner_margin(..)`.
+    pub fn frame<R>(
+        ui: &mut Ui,
+        fill: Color32,
+        rounding: f32,
+        stroke: Stroke,
+        margin: Margin,
+        add_contents: impl FnOnce(&mut Ui) -> R,
+    ) -> InnerResponse<R> {
+        let background = ui.painter().add(Shape::Noop);
+        let inner = egui::Frame::none()
+            .inner_margin(margin)
+            .show(ui, add_contents);
+
+        let rect = inner.response.rect;
+        let size = rect.size();
+        let key = (
+            "frame",
+            size.x.round() as i32,
+            size.y.round() as i32,
+            fill,
+            rounding.to_bits(),
+            stroke.width.to_bits(),
+            stroke.color,
+        );
+        Self::set(ui, background, key, rect.min.to_vec2(), || {
+            let local = Rect::from_min_size(Pos2::ZERO, Vec2::new(size.x.round(), size.y.round()));
+            vec![
+                Shape::rect_filled(local, Rounding::same(rounding), fill),
+                Shape::rect_stroke(local, Rounding::same(rounding), stroke),
+            ]
+        });
+
+        inner
+    }
+
+    fn shape(ui: &Ui, key: impl Hash, offset: Vec2, build: impl FnOnce() -> Vec<Shape>) -> Shape {
+        let ctx = ui.ctx();
+        let pixels_per_point = ctx.pixels_per_point();
+        let key = egui::Id::new(("shape_cache", key, pixels_per_point.to_bits()));
+        let cache_id = egui::Id::new("shape_cache");
+
+        let cached = ctx.data_mut(|d| {
+            d.get_temp_mut_or_default::<MeshMap>(cache_id)
+                .get(&key)
+                .cloned()
+        });
+        let mesh = cached.unwrap_or_else(|| {
+            let mesh = Arc::new(tessellate(ctx, pixels_per_point, build()));
+            ctx.data_mut(|d| {
+                let map = d.get_temp_mut_or_default::<MeshMap>(cache_id);
+                if map.len() >= MAX_ENTRIES {
+                    map.clear();
+                }
+                map.insert(key, Arc::clone(&mesh));
+            });
+            mesh
+        });
+
+        let mut mesh = (*mesh).clone();
+        mesh.translate(offset);
+        Shape::mesh(mesh)
+    }
+}
+
+/// Tessellate shapes without the font atlas, so cached meshes never point
+/// into a texture that egui may rebuild
+fn tessellate(ctx: &egui::Context, pixels_per_point: f32, shapes: Vec<Shape>) -> Mesh {
+    let options = ctx.tessellation_options(|o| *o);
+    let mut tessellator = Tessellator::new(pixels_per_point, options, [1, 1], Vec::new());
+
+    let mut mesh = Mesh::default();
+    for shape in shapes {
+        tessellator.tessellate_shape(shape, &mut mesh);
+    }
+    mesh
+}
@@ -0,0 +1,26 @@
+//! Skeleton placeholders shown while data is still loading
+
+use egui::{Rounding, Ui, Vec2};
+
+use crate::ui::theme::Theme;
+
+pub struct Skeleton;
+
+impl Skeleton {
+    /// Render a row of pulsing placeholder cards
+    pub fn cards(ui: &mut Ui, count: usize, size: Vec2) {
+        // Slow pulse between the tertiary background and a slightly lighter tone
+        let time = ui.input(|i| i.time);
+        let pulse = ((time * 2.0).sin() * 0.5 + 0.5) as f32;
+        let color = Theme::bg_tertiary().linear_multiply(0.6 + pulse * 0.4);
+
+        ui.horizontal_wrapped(|ui| {
+            for _ in 0..count {
+                let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+                if ui.is_rect_visible(rect) {
+                    ui.painter().rect_filled(rect, Rounding::same(12.0), color);
+                }
+            }
+        });
+    }
+}
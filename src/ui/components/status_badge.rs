@@ -1,10 +1,12 @@
 //! Status badge component
 
-use egui::{Color32, Response, Rounding, Ui, Vec2};
+use egui::{Color32, Pos2, Response, Rounding, Shape, Ui, Vec2};
 
 use crate::core::InstanceStatus;
 use crate::ui::theme::Theme;
 
+use super::shape_cache::ShapeCache;
+
 pub struct StatusBadge;
 
 impl StatusBadge {
@@ -59,27 +61,39 @@ impl StatusBadge {
         let (rect, response) = ui.allocate_exact_size(Vec2::new(size, size), egui::Sense::hover());
 
         if ui.is_rect_visible(rect) {
-            let painter = ui.painter();
-            let center = rect.center();
-
-            if is_active {
-                // Outer glow ring for active statuses
-                painter.circle_filled(center, 7.0, color.linear_multiply(0.25));
-                painter.circle_stroke(
-                    center,
-                    6.0,
-                    egui::Stroke::new(1.0, color.linear_multiply(0.4)),
-                );
-            }
-
-            // Main dot
-            painter.circle_filled(center, 5.0, color);
-
-            // Inner highlight
-            painter.circle_filled(
-                center + Vec2::new(-1.0, -1.0),
-                2.0,
-                Color32::from_white_alpha(40),
+            ShapeCache::paint(
+                ui,
+                ("status_dot", color, is_active),
+                rect.center().to_vec2(),
+                || {
+                    let center = Pos2::ZERO;
+                    let mut shapes = Vec::new();
+
+                    if is_active {
+                        // Outer glow ring for active statuses
+                        shapes.push(Shape::circle_filled(
+                            center,
+                            7.0,
+                            color.linear_multiply(0.25),
+                        ));
+                        shapes.push(Shape::circle_stroke(
+                            center,
+                            6.0,
+                            egui::Stroke::new(1.0, color.linear_multiply(0.4)),
+                        ));
+                    }
+
+                    // Main dot
+                    shapes.push(Shape::circle_filled(center, 5.0, color));
+
+                    // Inner highlight
+                    shapes.push(Shape::circle_filled(
+                        center + Vec2::new(-1.0, -1.0),
+                        2.0,
+                        Color32::from_white_alpha(40),
+                    ));
+                    shapes
+                },
             );
         }
 
@@ -28,7 +28,7 @@ pub fn render(
 
             ui.horizontal(|ui| {
                 if ui
-                    .button(egui::RichText::new("Confirm").color(Theme::ERROR))
+                    .button(egui::RichText::new("Confirm").color(Theme::error()))
                     .clicked()
                 {
                     on_confirm();
@@ -1,9 +1,25 @@
 //! Edit instance dialog
 
+use chrono::{NaiveTime, Timelike};
 use egui::{Color32, Context};
 
-use crate::core::{AppState, InstanceId};
-use crate::ui::dialogs::DialogState;
+use crate::core::app_container::{AppContainerCapability, IsolationLevel};
+use crate::core::architecture::LaunchArchitecture;
+use crate::core::environment;
+use crate::core::health_probe::HealthProbe;
+use crate::core::limit_schedule::LimitWindow;
+use crate::core::network_condition::NetworkCondition;
+use crate::core::scheduler::DAY_NAMES;
+use crate::core::status_detection::{self, RuleAction, StatusDetection, StatusRule};
+use crate::core::virtual_desktop;
+use crate::core::window_capture::CaptureRegion;
+use crate::core::{
+    AppState, ArgumentVariant, InstanceConfig, InstanceId, IoPriority, ResourceLimits, WindowState,
+    MIN_MONITOR_INTERVAL_MS,
+};
+use crate::ui::components::{AffinityPicker, LimitPresetPicker};
+use crate::ui::dialogs::new_instance::render_shared_caches;
+use crate::ui::dialogs::{architecture_info, DialogState};
 use crate::ui::theme::Theme;
 
 /// Helper for section headers
@@ -13,14 +29,14 @@ fn section_header(ui: &mut egui::Ui, icon: &str, title: &str) {
         ui.label(
             egui::RichText::new(icon)
                 .size(16.0)
-                .color(Theme::PRIMARY_LIGHT),
+                .color(Theme::primary_light()),
         );
         ui.add_space(8.0);
         ui.label(
             egui::RichText::new(title)
                 .size(15.0)
                 .strong()
-                .color(Color32::WHITE),
+                .color(Theme::text_primary()),
         );
     });
     ui.add_space(12.0);
@@ -34,7 +50,13 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
     };
     drop(instances);
 
-    let mut config = instance.config.clone();
+    let mut config = instance.next_launch_config().clone();
+    let running = instance.status.is_active();
+    let (presets, metadata_fields) = state
+        .settings
+        .read()
+        .map(|s| (s.limit_presets.clone(), s.metadata_fields.clone()))
+        .unwrap_or_default();
     let mut open = true;
 
     egui::Window::new("Edit Instance")
@@ -45,9 +67,9 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
         .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
         .frame(
             egui::Frame::window(&ctx.style())
-                .fill(Theme::BG_ELEVATED)
+                .fill(Theme::bg_elevated())
                 .rounding(egui::Rounding::same(12.0))
-                .stroke(egui::Stroke::new(1.0, Theme::BORDER))
+                .stroke(egui::Stroke::new(1.0, Theme::border()))
                 .inner_margin(egui::Margin::same(24.0)),
         )
         .show(ctx, |ui| {
@@ -57,14 +79,14 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                     egui::RichText::new(format!("Edit: {}", instance.display_name()))
                         .size(20.0)
                         .strong()
-                        .color(Color32::WHITE),
+                        .color(Theme::text_primary()),
                 );
             });
             ui.add_space(6.0);
             ui.label(
                 egui::RichText::new("Modify instance configuration")
                     .size(13.0)
-                    .color(Theme::TEXT_MUTED),
+                    .color(Theme::text_muted()),
             );
             ui.add_space(20.0);
 
@@ -75,7 +97,7 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                     section_header(ui, "◈", "Basic Information");
 
                     egui::Frame::none()
-                        .fill(Theme::BG_SECONDARY)
+                        .fill(Theme::bg_secondary())
                         .rounding(egui::Rounding::same(10.0))
                         .inner_margin(egui::Margin::same(16.0))
                         .show(ui, |ui| {
@@ -83,7 +105,7 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                             ui.label(
                                 egui::RichText::new("Instance Name")
                                     .size(12.0)
-                                    .color(Theme::TEXT_MUTED),
+                                    .color(Theme::text_muted()),
                             );
                             ui.add_space(4.0);
                             ui.add(
@@ -97,11 +119,11 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                             ui.label(
                                 egui::RichText::new("Executable Path")
                                     .size(12.0)
-                                    .color(Theme::TEXT_MUTED),
+                                    .color(Theme::text_muted()),
                             );
                             ui.add_space(4.0);
                             egui::Frame::none()
-                                .fill(Theme::BG_TERTIARY.linear_multiply(0.5))
+                                .fill(Theme::bg_tertiary().linear_multiply(0.5))
                                 .rounding(egui::Rounding::same(6.0))
                                 .inner_margin(egui::Margin::symmetric(12.0, 8.0))
                                 .show(ui, |ui| {
@@ -110,7 +132,7 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                                             config.executable_path.to_string_lossy(),
                                         )
                                         .size(12.0)
-                                        .color(Theme::TEXT_SECONDARY),
+                                        .color(Theme::text_secondary()),
                                     );
                                 });
 
@@ -120,7 +142,7 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                             ui.label(
                                 egui::RichText::new("Arguments")
                                     .size(12.0)
-                                    .color(Theme::TEXT_MUTED),
+                                    .color(Theme::text_muted()),
                             );
                             ui.add_space(4.0);
                             let mut args_str = config.arguments.join(" ");
@@ -135,20 +157,38 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                                 config.arguments =
                                     args_str.split_whitespace().map(|s| s.to_string()).collect();
                             }
+
+                            ui.add_space(12.0);
+                            render_argument_variants(ui, &mut config);
                         });
 
                     // Resource Limits Section
                     section_header(ui, "⚡", "Resource Limits");
 
                     egui::Frame::none()
-                        .fill(Theme::BG_SECONDARY)
+                        .fill(Theme::bg_secondary())
                         .rounding(egui::Rounding::same(10.0))
                         .inner_margin(egui::Margin::same(16.0))
                         .show(ui, |ui| {
+                            // Preset
+                            ui.label(
+                                egui::RichText::new("Preset")
+                                    .size(12.0)
+                                    .color(Theme::text_muted()),
+                            );
+                            ui.add_space(4.0);
+                            LimitPresetPicker::show(
+                                ui,
+                                &presets,
+                                &mut config.resource_limits,
+                                "edit_limit_preset_select",
+                            );
+
+                            ui.add_space(12.0);
                             ui.label(
                                 egui::RichText::new("Leave at 0 for unlimited/default values")
                                     .size(11.0)
-                                    .color(Theme::TEXT_MUTED),
+                                    .color(Theme::text_muted()),
                             );
                             ui.add_space(12.0);
 
@@ -156,7 +196,7 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                             ui.label(
                                 egui::RichText::new("CPU Limit")
                                     .size(12.0)
-                                    .color(Theme::TEXT_MUTED),
+                                    .color(Theme::text_muted()),
                             );
                             ui.add_space(4.0);
                             ui.add(
@@ -177,7 +217,7 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                             ui.label(
                                 egui::RichText::new("Memory Limit")
                                     .size(12.0)
-                                    .color(Theme::TEXT_MUTED),
+                                    .color(Theme::text_muted()),
                             );
                             ui.add_space(4.0);
                             let mut mem = config.resource_limits.memory_mb.min(16384) as u32;
@@ -203,20 +243,77 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                             ui.label(
                                 egui::RichText::new("Process Priority")
                                     .size(12.0)
-                                    .color(Theme::TEXT_MUTED),
+                                    .color(Theme::text_muted()),
                             );
                             ui.add_space(4.0);
                             ui.add(
-                                egui::Slider::new(&mut config.resource_limits.priority, -2..=2)
-                                    .custom_formatter(|n, _| match n as i8 {
-                                        -2 => "Idle".to_string(),
-                                        -1 => "Below Normal".to_string(),
-                                        0 => "Normal".to_string(),
-                                        1 => "Above Normal".to_string(),
-                                        2 => "High".to_string(),
-                                        _ => format!("{}", n),
-                                    }),
+                                egui::Slider::new(
+                                    &mut config.resource_limits.priority,
+                                    ResourceLimits::EFFICIENCY_PRIORITY..=2,
+                                )
+                                .custom_formatter(|n, _| ResourceLimits::priority_label(n as i8)),
                             );
+                            if config.resource_limits.is_efficiency_mode() {
+                                ui.label(
+                                    egui::RichText::new(
+                                        "Lowest priority with power throttling; prefers E-cores where supported",
+                                    )
+                                    .size(11.0)
+                                    .color(Theme::text_muted()),
+                                );
+                            }
+
+                            ui.add_space(12.0);
+
+                            // Disk I/O
+                            ui.label(
+                                egui::RichText::new("Disk I/O")
+                                    .size(12.0)
+                                    .color(Theme::text_muted()),
+                            );
+                            ui.add_space(4.0);
+                            ui.horizontal(|ui| {
+                                egui::ComboBox::from_id_salt("edit_io_priority_select")
+                                    .selected_text(config.resource_limits.io_priority.label())
+                                    .show_ui(ui, |ui| {
+                                        for priority in IoPriority::all() {
+                                            ui.selectable_value(
+                                                &mut config.resource_limits.io_priority,
+                                                priority,
+                                                priority.label(),
+                                            );
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text("Priority of disk reads and writes against other programs");
+                                ui.add_space(8.0);
+                                ui.add(
+                                    egui::DragValue::new(&mut config.resource_limits.disk_mbps)
+                                        .range(0..=10_000)
+                                        .custom_formatter(|n, _| {
+                                            if n == 0.0 {
+                                                "Unlimited".to_string()
+                                            } else {
+                                                format!("{:.0} MB/s", n)
+                                            }
+                                        }),
+                                )
+                                .on_hover_text("Cap on reads and writes, each; not available on macOS");
+                            });
+
+                            ui.add_space(12.0);
+
+                            // CPU Affinity
+                            ui.label(
+                                egui::RichText::new("CPU Affinity")
+                                    .size(12.0)
+                                    .color(Theme::text_muted()),
+                            );
+                            ui.add_space(4.0);
+                            AffinityPicker::show(ui, &mut config.resource_limits, "edit_affinity_preset_select");
+
+                            ui.add_space(12.0);
+                            render_limit_schedule(ui, &mut config);
                         });
 
                     ui.add_space(20.0);
@@ -225,7 +322,7 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                     section_header(ui, "🔒", "Instance Isolation");
 
                     egui::Frame::none()
-                        .fill(Theme::BG_SECONDARY)
+                        .fill(Theme::bg_secondary())
                         .rounding(egui::Rounding::same(10.0))
                         .inner_margin(egui::Margin::same(16.0))
                         .show(ui, |ui| {
@@ -235,14 +332,14 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                                     ui.label(
                                         egui::RichText::new("Bypass single-instance check")
                                             .size(13.0)
-                                            .color(Theme::TEXT_PRIMARY),
+                                            .color(Theme::text_primary()),
                                     );
                                     ui.label(
                                         egui::RichText::new(
                                             "Allows running multiple instances of the same app",
                                         )
                                         .size(11.0)
-                                        .color(Theme::TEXT_MUTED),
+                                        .color(Theme::text_muted()),
                                     );
                                 });
                             });
@@ -255,12 +352,12 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                                     ui.label(
                                         egui::RichText::new("Use environment isolation")
                                             .size(13.0)
-                                            .color(Theme::TEXT_PRIMARY),
+                                            .color(Theme::text_primary()),
                                     );
                                     ui.label(
                                         egui::RichText::new("Sets custom APPDATA/profile paths")
                                             .size(11.0)
-                                            .color(Theme::TEXT_MUTED),
+                                            .color(Theme::text_muted()),
                                     );
                                 });
                             });
@@ -273,17 +370,103 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                                     ui.label(
                                         egui::RichText::new("Hide from taskbar")
                                             .size(13.0)
-                                            .color(Theme::TEXT_PRIMARY),
+                                            .color(Theme::text_primary()),
                                     );
                                     ui.label(
                                         egui::RichText::new(
                                             "Hides the instance window from the Windows taskbar",
                                         )
                                         .size(11.0)
-                                        .color(Theme::TEXT_MUTED),
+                                        .color(Theme::text_muted()),
+                                    );
+                                });
+                            });
+
+                            ui.add_space(12.0);
+
+                            ui.horizontal(|ui| {
+                                ui.checkbox(&mut config.launch_in_background, "");
+                                ui.vertical(|ui| {
+                                    ui.label(
+                                        egui::RichText::new("Launch in background")
+                                            .size(13.0)
+                                            .color(Theme::text_primary()),
+                                    );
+                                    ui.label(
+                                        egui::RichText::new(
+                                            "Keeps focus on the window you are using when the instance opens",
+                                        )
+                                        .size(11.0)
+                                        .color(Theme::text_muted()),
                                     );
                                 });
                             });
+
+                            ui.add_space(12.0);
+
+                            ui.horizontal(|ui| {
+                                egui::ComboBox::from_id_salt("start_window_state")
+                                    .width(100.0)
+                                    .selected_text(config.start_window_state.label())
+                                    .show_ui(ui, |ui| {
+                                        for state in WindowState::all() {
+                                            ui.selectable_value(
+                                                &mut config.start_window_state,
+                                                *state,
+                                                state.label(),
+                                            );
+                                        }
+                                    });
+                                ui.vertical(|ui| {
+                                    ui.label(
+                                        egui::RichText::new("Window at launch")
+                                            .size(13.0)
+                                            .color(Theme::text_primary()),
+                                    );
+                                    ui.label(
+                                        egui::RichText::new(
+                                            "Minimize or hide the main window once it appears; show a hidden one from the instance's right-click menu",
+                                        )
+                                        .size(11.0)
+                                        .color(Theme::text_muted()),
+                                    );
+                                });
+                            });
+                            ui.add_space(8.0);
+                            ui.horizontal(|ui| {
+                                ui.checkbox(&mut config.rename_windows, "");
+                                ui.vertical(|ui| {
+                                    ui.label(
+                                        egui::RichText::new("Show name in window titles")
+                                            .size(13.0)
+                                            .color(Theme::text_primary()),
+                                    );
+                                    ui.label(
+                                        egui::RichText::new(
+                                            "Appends the instance name to the app's titles, e.g. \"Discord — Work\", to tell copies apart in the taskbar",
+                                        )
+                                        .size(11.0)
+                                        .color(Theme::text_muted()),
+                                    );
+                                });
+                            });
+                            render_virtual_desktop(ui, &mut config, "edit_virtual_desktop");
+
+                            ui.add_space(12.0);
+
+                            render_shared_caches(ui, &mut config, "edit_shared_caches");
+
+                            ui.add_space(12.0);
+                            render_architecture(ui, &mut config, "edit_launch_arch_select");
+
+                            ui.add_space(12.0);
+                            render_isolation_level(ui, &mut config, "edit_isolation_level");
+
+                            ui.add_space(12.0);
+                            render_exclusive_resources(ui, &mut config, "edit_exclusive");
+
+                            ui.add_space(12.0);
+                            render_environment_vars(ui, &mut config);
                         });
 
                     ui.add_space(20.0);
@@ -292,7 +475,7 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                     section_header(ui, "↻", "Automation");
 
                     egui::Frame::none()
-                        .fill(Theme::BG_SECONDARY)
+                        .fill(Theme::bg_secondary())
                         .rounding(egui::Rounding::same(10.0))
                         .inner_margin(egui::Margin::same(16.0))
                         .show(ui, |ui| {
@@ -302,14 +485,14 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                                     ui.label(
                                         egui::RichText::new("Auto-restart on crash")
                                             .size(13.0)
-                                            .color(Theme::TEXT_PRIMARY),
+                                            .color(Theme::text_primary()),
                                     );
                                     ui.label(
                                         egui::RichText::new(
                                             "Automatically restart if the instance crashes",
                                         )
                                         .size(11.0)
-                                        .color(Theme::TEXT_MUTED),
+                                        .color(Theme::text_muted()),
                                     );
                                 });
                             });
@@ -320,7 +503,7 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                                     ui.label(
                                         egui::RichText::new("Restart delay:")
                                             .size(12.0)
-                                            .color(Theme::TEXT_MUTED),
+                                            .color(Theme::text_muted()),
                                     );
                                     ui.add_space(8.0);
                                     let mut delay = config.restart_delay_secs as i32;
@@ -332,6 +515,30 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                                     config.restart_delay_secs = delay as u32;
                                 });
                             }
+
+                            ui.add_space(12.0);
+                            render_network_condition(ui, &mut config, "edit_network");
+
+                            ui.add_space(12.0);
+                            render_run_limit(ui, &mut config);
+
+                            ui.add_space(8.0);
+                            render_monitor_interval(ui, &mut config);
+
+                            ui.add_space(12.0);
+                            render_pinned_region(ui, &mut config);
+
+                            ui.add_space(12.0);
+                            render_status_detection(ui, &mut config);
+
+                            ui.add_space(12.0);
+                            render_health_probe(ui, &mut config);
+
+                            ui.add_space(12.0);
+                            render_stop_command(ui, &mut config);
+
+                            ui.add_space(12.0);
+                            render_output_capture(ui, &mut config);
                         });
 
                     ui.add_space(20.0);
@@ -340,7 +547,7 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                     section_header(ui, "📋", "Organization");
 
                     egui::Frame::none()
-                        .fill(Theme::BG_SECONDARY)
+                        .fill(Theme::bg_secondary())
                         .rounding(egui::Rounding::same(10.0))
                         .inner_margin(egui::Margin::same(16.0))
                         .show(ui, |ui| {
@@ -348,7 +555,7 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                             ui.label(
                                 egui::RichText::new("Group")
                                     .size(12.0)
-                                    .color(Theme::TEXT_MUTED),
+                                    .color(Theme::text_muted()),
                             );
                             ui.add_space(4.0);
                             let groups = state.groups.read().unwrap();
@@ -379,11 +586,17 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
 
                             ui.add_space(16.0);
 
+                            render_metadata(ui, &mut config, &metadata_fields, "edit_metadata");
+
+                            render_tags(ui, &mut config, "edit_tags");
+
+                            ui.add_space(16.0);
+
                             // Notes
                             ui.label(
                                 egui::RichText::new("Notes")
                                     .size(12.0)
-                                    .color(Theme::TEXT_MUTED),
+                                    .color(Theme::text_muted()),
                             );
                             ui.add_space(4.0);
                             ui.add(
@@ -402,26 +615,39 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
             // Divider before action buttons
             let (rect, _) =
                 ui.allocate_exact_size(egui::vec2(ui.available_width(), 1.0), egui::Sense::hover());
-            ui.painter().rect_filled(rect, 0.0, Theme::BORDER_LIGHT);
+            ui.painter().rect_filled(rect, 0.0, Theme::border_light());
+
+            if running {
+                ui.add_space(12.0);
+                ui.label(
+                    egui::RichText::new(
+                        "The instance is running: names, labels, stop behavior, macros and the \
+                         CPU cap apply now; other changes wait for its next restart.",
+                    )
+                    .size(12.0)
+                    .color(Theme::warning()),
+                );
+            }
 
             ui.add_space(16.0);
 
             // Action buttons
             ui.horizontal(|ui| {
                 // Save button (primary)
+                let can_save = environment::validate(&config.environment).is_none();
                 let save_btn =
                     egui::Button::new(egui::RichText::new("Save Changes").color(Color32::WHITE))
-                        .fill(Theme::PRIMARY)
+                        .fill(if can_save {
+                            Theme::primary()
+                        } else {
+                            Theme::bg_tertiary()
+                        })
                         .rounding(egui::Rounding::same(8.0))
                         .min_size(egui::vec2(120.0, 38.0));
 
-                if ui.add(save_btn).clicked() {
-                    let mut instances = state.instances.write().unwrap();
-                    if let Some(inst) = instances.get_mut(&id) {
-                        inst.config = config;
-                        let inst = inst.clone();
-                        drop(instances);
-                        let _ = state.database.save_instance(&inst);
+                if ui.add_enabled(can_save, save_btn).clicked() {
+                    if let Err(e) = state.update_instance_config(id, config) {
+                        tracing::error!("Failed to save instance: {}", e);
                     }
                     *dialog = DialogState::None;
                 }
@@ -430,7 +656,7 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
 
                 // Cancel button
                 let cancel_btn =
-                    egui::Button::new(egui::RichText::new("Cancel").color(Theme::TEXT_SECONDARY))
+                    egui::Button::new(egui::RichText::new("Cancel").color(Theme::text_secondary()))
                         .fill(Color32::TRANSPARENT)
                         .rounding(egui::Rounding::same(8.0))
                         .min_size(egui::vec2(80.0, 38.0));
@@ -442,8 +668,8 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     // Delete button (danger)
                     let delete_btn =
-                        egui::Button::new(egui::RichText::new("Delete").color(Theme::ERROR))
-                            .fill(Theme::ERROR.linear_multiply(0.15))
+                        egui::Button::new(egui::RichText::new("Delete").color(Theme::error()))
+                            .fill(Theme::error().linear_multiply(0.15))
                             .rounding(egui::Rounding::same(8.0))
                             .min_size(egui::vec2(80.0, 38.0));
 
@@ -470,3 +696,879 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
         *dialog = DialogState::None;
     }
 }
+
+/// Desktop the instance's windows are moved to (Windows only)
+fn render_virtual_desktop(ui: &mut egui::Ui, config: &mut InstanceConfig, id_salt: &str) {
+    if !cfg!(windows) {
+        return;
+    }
+    ui.add_space(12.0);
+    ui.horizontal(|ui| {
+        let selected_name = config
+            .virtual_desktop
+            .as_ref()
+            .map_or("Current", |d| d.name.as_str())
+            .to_string();
+        egui::ComboBox::from_id_salt(id_salt)
+            .width(100.0)
+            .selected_text(selected_name)
+            .show_ui(ui, |ui| {
+                if ui
+                    .selectable_label(config.virtual_desktop.is_none(), "Current")
+                    .clicked()
+                {
+                    config.virtual_desktop = None;
+                }
+                for desktop in virtual_desktop::desktops() {
+                    let selected = config
+                        .virtual_desktop
+                        .as_ref()
+                        .is_some_and(|d| d.id == desktop.id);
+                    if ui.selectable_label(selected, &desktop.name).clicked() {
+                        config.virtual_desktop = Some(desktop);
+                    }
+                }
+            });
+        ui.vertical(|ui| {
+            ui.label(
+                egui::RichText::new("Virtual desktop")
+                    .size(13.0)
+                    .color(Theme::text_primary()),
+            );
+            ui.label(
+                egui::RichText::new(
+                    "Moves the instance's windows to this desktop once they appear",
+                )
+                .size(11.0)
+                .color(Theme::text_muted()),
+            );
+        });
+    });
+}
+
+/// Time windows whose limits replace the instance's own while they are open
+fn render_limit_schedule(ui: &mut egui::Ui, config: &mut InstanceConfig) {
+    ui.label(
+        egui::RichText::new("Scheduled limits")
+            .size(12.0)
+            .color(Theme::text_muted()),
+    );
+    ui.label(
+        egui::RichText::new(
+            "Limits used instead of the ones above while a time window is open, e.g. lower caps during work hours",
+        )
+        .size(11.0)
+        .color(Theme::text_muted()),
+    );
+    ui.add_space(4.0);
+
+    let mut remove = None;
+    for (index, window) in config.limit_schedule.iter_mut().enumerate() {
+        ui.push_id(("limit_window", index), |ui| {
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut window.name)
+                        .hint_text("Name")
+                        .desired_width(120.0),
+                );
+                render_time(ui, &mut window.start);
+                ui.label("to");
+                render_time(ui, &mut window.end);
+                if ui.small_button("✕").clicked() {
+                    remove = Some(index);
+                }
+            });
+            ui.horizontal(|ui| {
+                for (day, name) in window.days.iter_mut().zip(DAY_NAMES) {
+                    if ui.selectable_label(*day, name).clicked() {
+                        *day = !*day;
+                    }
+                }
+            });
+            ui.horizontal(|ui| {
+                let unlimited = |n: f64, suffix: &str| {
+                    if n == 0.0 {
+                        "Unlimited".to_string()
+                    } else {
+                        format!("{:.0}{}", n, suffix)
+                    }
+                };
+                ui.label("CPU");
+                ui.add(
+                    egui::DragValue::new(&mut window.limits.cpu_percent)
+                        .range(0..=100)
+                        .custom_formatter(|n, _| unlimited(n, "%")),
+                );
+                ui.label("Memory");
+                ui.add(
+                    egui::DragValue::new(&mut window.limits.memory_mb)
+                        .range(0..=65536)
+                        .speed(16.0)
+                        .custom_formatter(|n, _| unlimited(n, " MB")),
+                );
+                ui.label("Priority");
+                ui.add(
+                    egui::DragValue::new(&mut window.limits.priority)
+                        .range(ResourceLimits::EFFICIENCY_PRIORITY..=2)
+                        .custom_formatter(|n, _| ResourceLimits::priority_label(n as i8)),
+                );
+            });
+            ui.add_space(4.0);
+        });
+    }
+    if let Some(index) = remove {
+        config.limit_schedule.remove(index);
+    }
+    if ui.small_button("+ Add time window").clicked() {
+        config
+            .limit_schedule
+            .push(LimitWindow::work_hours(config.resource_limits.clone()));
+    }
+}
+
+/// Hour and minute fields for a time of day
+fn render_time(ui: &mut egui::Ui, time: &mut NaiveTime) {
+    let mut hour = time.hour();
+    let mut minute = time.minute();
+    ui.add(
+        egui::DragValue::new(&mut hour)
+            .range(0..=23)
+            .custom_formatter(|v, _| format!("{:02}", v as u32)),
+    );
+    ui.label(":");
+    ui.add(
+        egui::DragValue::new(&mut minute)
+            .range(0..=59)
+            .custom_formatter(|v, _| format!("{:02}", v as u32)),
+    );
+    if let Some(changed) = NaiveTime::from_hms_opt(hour, minute, 0) {
+        *time = changed;
+    }
+}
+
+/// Architecture row: launch slice picker on macOS and an emulation warning
+/// AppContainer sandbox and what it may reach (Windows only)
+fn render_isolation_level(ui: &mut egui::Ui, config: &mut InstanceConfig, id_salt: &str) {
+    if !cfg!(windows) {
+        return;
+    }
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new("Isolation")
+                .size(13.0)
+                .color(Theme::text_primary()),
+        );
+        egui::ComboBox::from_id_salt(id_salt)
+            .selected_text(config.isolation_level.label())
+            .show_ui(ui, |ui| {
+                for level in IsolationLevel::all() {
+                    ui.selectable_value(&mut config.isolation_level, level, level.label());
+                }
+            });
+    });
+    if config.isolation_level != IsolationLevel::AppContainer {
+        return;
+    }
+
+    ui.label(
+        egui::RichText::new(
+            "The sandbox can only write to the instance's data and working directories",
+        )
+        .size(11.0)
+        .color(Theme::text_muted()),
+    );
+    ui.horizontal_wrapped(|ui| {
+        for capability in AppContainerCapability::all() {
+            let mut enabled = config.app_container_capabilities.contains(&capability);
+            if ui.checkbox(&mut enabled, capability.label()).changed() {
+                if enabled {
+                    config.app_container_capabilities.push(capability);
+                } else {
+                    config
+                        .app_container_capabilities
+                        .retain(|c| *c != capability);
+                }
+            }
+        }
+    });
+    if config.capture_output {
+        ui.label(
+            egui::RichText::new("Output is not captured from sandboxed instances")
+                .size(11.0)
+                .color(Theme::warning()),
+        );
+    }
+}
+
+fn render_architecture(ui: &mut egui::Ui, config: &mut InstanceConfig, id_salt: &str) {
+    if cfg!(target_os = "macos") {
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new("Architecture")
+                    .size(13.0)
+                    .color(Theme::text_primary()),
+            );
+            egui::ComboBox::from_id_salt(id_salt)
+                .selected_text(config.launch_architecture.label())
+                .show_ui(ui, |ui| {
+                    for arch in LaunchArchitecture::all() {
+                        ui.selectable_value(&mut config.launch_architecture, *arch, arch.label());
+                    }
+                });
+        });
+    }
+
+    if let Some(info) = architecture_info(
+        ui.ctx(),
+        id_salt,
+        &config.executable_path,
+        config.launch_architecture,
+    ) {
+        if info.is_emulated() {
+            ui.label(
+                egui::RichText::new(format!(
+                    "{} build runs under {} on this machine and may be slower",
+                    info.runs_as.label(),
+                    info.emulation_label()
+                ))
+                .size(11.0)
+                .color(Theme::warning()),
+            );
+        }
+    }
+}
+
+/// Comma-separated labels for filtering and display
+/// A text box per metadata field from settings, plus any the instance still
+/// has a value for after its field was removed
+fn render_metadata(
+    ui: &mut egui::Ui,
+    config: &mut InstanceConfig,
+    fields: &[String],
+    id_salt: &str,
+) {
+    let mut names: Vec<String> = fields
+        .iter()
+        .map(|f| f.trim().to_string())
+        .filter(|f| !f.is_empty())
+        .collect();
+    for name in config.metadata.keys() {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+    if names.is_empty() {
+        return;
+    }
+
+    ui.label(
+        egui::RichText::new("Metadata")
+            .size(12.0)
+            .color(Theme::text_muted()),
+    );
+    ui.add_space(4.0);
+    egui::Grid::new((id_salt, "metadata"))
+        .num_columns(2)
+        .spacing([12.0, 6.0])
+        .show(ui, |ui| {
+            for name in names {
+                ui.label(egui::RichText::new(&name).color(Theme::text_secondary()));
+                let mut value = config.metadata.get(&name).cloned().unwrap_or_default();
+                if ui
+                    .add(egui::TextEdit::singleline(&mut value).desired_width(f32::INFINITY))
+                    .changed()
+                {
+                    if value.trim().is_empty() {
+                        config.metadata.remove(&name);
+                    } else {
+                        config.metadata.insert(name, value);
+                    }
+                }
+                ui.end_row();
+            }
+        });
+    ui.add_space(16.0);
+}
+
+fn render_tags(ui: &mut egui::Ui, config: &mut InstanceConfig, id_salt: &str) {
+    ui.label(
+        egui::RichText::new("Tags")
+            .size(12.0)
+            .color(Theme::text_muted()),
+    );
+    ui.add_space(4.0);
+
+    let parse_tags = |text: &str| -> Vec<String> {
+        text.split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+
+    let id = egui::Id::new((id_salt, "tags"));
+    let mut text = ui
+        .data(|d| d.get_temp::<String>(id))
+        .filter(|text| parse_tags(text) == config.tags)
+        .unwrap_or_else(|| config.tags.join(", "));
+    if ui
+        .add(
+            egui::TextEdit::singleline(&mut text)
+                .hint_text("e.g. testing, eu-west")
+                .desired_width(f32::INFINITY),
+        )
+        .changed()
+    {
+        config.tags = parse_tags(&text);
+    }
+    ui.data_mut(|d| d.insert_temp(id, text));
+}
+
+/// Network state to wait for before launching
+fn render_network_condition(ui: &mut egui::Ui, config: &mut InstanceConfig, id_salt: &str) {
+    let mut enabled = config.network_condition.is_some();
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut enabled, "");
+        ui.vertical(|ui| {
+            ui.label(
+                egui::RichText::new("Wait for network")
+                    .size(13.0)
+                    .color(Theme::text_primary()),
+            );
+            ui.label(
+                egui::RichText::new("Stay pending and launch once the condition holds")
+                    .size(11.0)
+                    .color(Theme::text_muted()),
+            );
+        });
+    });
+    if enabled != config.network_condition.is_some() {
+        config.network_condition = enabled.then(|| NetworkCondition::VpnConnected(String::new()));
+    }
+
+    let Some(condition) = config.network_condition.as_mut() else {
+        return;
+    };
+    ui.add_space(8.0);
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_salt(("network_condition", id_salt))
+            .width(130.0)
+            .selected_text(condition.label())
+            .show_ui(ui, |ui| {
+                for kind in NetworkCondition::all() {
+                    let selected = condition.same_kind(&kind);
+                    if ui.selectable_label(selected, kind.label()).clicked() && !selected {
+                        let value = std::mem::take(condition.value_mut());
+                        *condition = kind;
+                        *condition.value_mut() = value;
+                    }
+                }
+            });
+        let hint = condition.hint();
+        ui.add(
+            egui::TextEdit::singleline(condition.value_mut())
+                .hint_text(hint)
+                .desired_width(f32::INFINITY),
+        );
+    });
+}
+
+/// Per-launch time box; 0 means run until stopped
+fn render_run_limit(ui: &mut egui::Ui, config: &mut InstanceConfig) {
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new("Stop after:")
+                .size(12.0)
+                .color(Theme::text_muted()),
+        );
+        ui.add_space(8.0);
+        ui.add(
+            egui::DragValue::new(&mut config.run_limit_mins)
+                .range(0..=1440)
+                .custom_formatter(|mins, _| {
+                    if mins == 0.0 {
+                        "No limit".to_string()
+                    } else {
+                        format!("{} min", mins)
+                    }
+                }),
+        )
+        .on_hover_text("Stop the instance automatically this long after each launch");
+    });
+}
+
+/// Per-instance resource sampling interval
+fn render_monitor_interval(ui: &mut egui::Ui, config: &mut InstanceConfig) {
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new("Monitor every:")
+                .size(12.0)
+                .color(Theme::text_muted()),
+        );
+        ui.add_space(8.0);
+        let mut interval = config.monitor_interval_ms;
+        ui.add(
+            egui::DragValue::new(&mut interval)
+                .range(0..=60_000)
+                .speed(50)
+                .custom_formatter(|ms, _| {
+                    if ms == 0.0 {
+                        "Default".to_string()
+                    } else {
+                        format!("{} ms", ms)
+                    }
+                }),
+        )
+        .on_hover_text("How often to sample CPU and memory for this instance");
+        // Anything but "Default" is held to the monitor's finest tick
+        config.monitor_interval_ms = if interval == 0 {
+            0
+        } else {
+            interval.max(MIN_MONITOR_INTERVAL_MS)
+        };
+    });
+}
+
+/// Named argument sets appended to the base arguments, picked on the card
+fn render_argument_variants(ui: &mut egui::Ui, config: &mut InstanceConfig) {
+    ui.label(
+        egui::RichText::new("Argument variants")
+            .size(12.0)
+            .color(Theme::text_muted()),
+    );
+    ui.label(
+        egui::RichText::new("Extra arguments chosen from the instance card, e.g. EU server")
+            .size(11.0)
+            .color(Theme::text_muted()),
+    );
+    ui.add_space(4.0);
+
+    let mut remove = None;
+    for (index, variant) in config.argument_variants.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            let previous = variant.name.clone();
+            if ui
+                .add(
+                    egui::TextEdit::singleline(&mut variant.name)
+                        .hint_text("Name")
+                        .desired_width(120.0),
+                )
+                .changed()
+                && config.active_variant.as_ref() == Some(&previous)
+            {
+                config.active_variant = Some(variant.name.clone());
+            }
+            let mut args_str = variant.arguments.join(" ");
+            if ui
+                .add(
+                    egui::TextEdit::singleline(&mut args_str)
+                        .hint_text("Extra arguments")
+                        .desired_width(260.0),
+                )
+                .changed()
+            {
+                variant.arguments = args_str.split_whitespace().map(|s| s.to_string()).collect();
+            }
+            if ui.small_button("✕").clicked() {
+                remove = Some(index);
+            }
+        });
+    }
+    if let Some(index) = remove {
+        let removed = config.argument_variants.remove(index);
+        if config.active_variant.as_ref() == Some(&removed.name) {
+            config.active_variant = None;
+        }
+    }
+    if ui.small_button("+ Add variant").clicked() {
+        config.argument_variants.push(ArgumentVariant {
+            name: format!("Variant {}", config.argument_variants.len() + 1),
+            arguments: Vec::new(),
+        });
+    }
+}
+
+/// Window region captured periodically for the dashboard
+fn render_pinned_region(ui: &mut egui::Ui, config: &mut InstanceConfig) {
+    let mut enabled = config.pinned_region.is_some();
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut enabled, "");
+        ui.vertical(|ui| {
+            ui.label(
+                egui::RichText::new("Pin window region")
+                    .size(13.0)
+                    .color(Theme::text_primary()),
+            );
+            ui.label(
+                egui::RichText::new("Show a live capture of part of the window on the dashboard")
+                    .size(11.0)
+                    .color(Theme::text_muted()),
+            );
+        });
+    });
+    if enabled != config.pinned_region.is_some() {
+        config.pinned_region = enabled.then(CaptureRegion::default);
+    }
+
+    let Some(region) = config.pinned_region.as_mut() else {
+        return;
+    };
+    ui.add_space(8.0);
+    render_region_fields(ui, region);
+}
+
+/// Position and size of a window region
+fn render_region_fields(ui: &mut egui::Ui, region: &mut CaptureRegion) {
+    ui.horizontal(|ui| {
+        for (label, value) in [("X", &mut region.x), ("Y", &mut region.y)] {
+            ui.label(
+                egui::RichText::new(label)
+                    .size(12.0)
+                    .color(Theme::text_muted()),
+            );
+            ui.add(egui::DragValue::new(value).range(0..=16_384).suffix(" px"));
+            ui.add_space(8.0);
+        }
+        for (label, value) in [("W", &mut region.width), ("H", &mut region.height)] {
+            ui.label(
+                egui::RichText::new(label)
+                    .size(12.0)
+                    .color(Theme::text_muted()),
+            );
+            ui.add(egui::DragValue::new(value).range(1..=4_096).suffix(" px"));
+            ui.add_space(8.0);
+        }
+    })
+    .response
+    .on_hover_text("Pixels from the top-left corner of the captured window");
+}
+
+/// OCR rules that feed health checks
+fn render_status_detection(ui: &mut egui::Ui, config: &mut InstanceConfig) {
+    let mut enabled = config.status_detection.is_some();
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut enabled, "");
+        ui.vertical(|ui| {
+            ui.label(
+                egui::RichText::new("Detect status on screen")
+                    .size(13.0)
+                    .color(Theme::text_primary()),
+            );
+            ui.label(
+                egui::RichText::new("Read part of the window during health checks and match rules")
+                    .size(11.0)
+                    .color(Theme::text_muted()),
+            );
+        });
+    });
+    if enabled != config.status_detection.is_some() {
+        config.status_detection = enabled.then(StatusDetection::default);
+    }
+
+    let Some(detection) = config.status_detection.as_mut() else {
+        return;
+    };
+    if !status_detection::OCR_AVAILABLE {
+        ui.add_space(4.0);
+        ui.label(
+            egui::RichText::new("This build has no OCR support; checks will report Unknown")
+                .size(11.0)
+                .color(Theme::warning()),
+        );
+    }
+    ui.add_space(8.0);
+    render_region_fields(ui, &mut detection.region);
+
+    ui.add_space(8.0);
+    let mut remove = None;
+    for (index, rule) in detection.rules.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt(("status_rule_action", index))
+                .width(150.0)
+                .selected_text(rule.action.label())
+                .show_ui(ui, |ui| {
+                    for action in RuleAction::all() {
+                        ui.selectable_value(&mut rule.action, action, action.label());
+                    }
+                });
+            ui.add(
+                egui::TextEdit::singleline(&mut rule.pattern)
+                    .hint_text("Pattern, e.g. disconnected|lost connection")
+                    .desired_width(220.0),
+            )
+            .on_hover_text("Case-insensitive regular expression");
+            if ui.small_button("✕").clicked() {
+                remove = Some(index);
+            }
+        });
+    }
+    if let Some(index) = remove {
+        detection.rules.remove(index);
+    }
+    if ui.small_button("+ Add rule").clicked() {
+        detection.rules.push(StatusRule {
+            pattern: String::new(),
+            action: RuleAction::UnhealthyIfFound,
+        });
+    }
+}
+
+/// Command run during health checks and how long it may take
+fn render_health_probe(ui: &mut egui::Ui, config: &mut InstanceConfig) {
+    ui.label(
+        egui::RichText::new("Health probe")
+            .size(13.0)
+            .color(Theme::text_primary()),
+    );
+    ui.label(
+        egui::RichText::new(
+            "Run during health checks with the instance's environment; exit code 0 means healthy, e.g. curl -f http://localhost:8080/health ({pid} is the process ID)",
+        )
+        .size(11.0)
+        .color(Theme::text_muted()),
+    );
+    ui.add_space(4.0);
+    let probe = config.health_probe.get_or_insert_with(HealthProbe::default);
+    ui.add(
+        egui::TextEdit::singleline(&mut probe.command)
+            .hint_text("None")
+            .desired_width(f32::INFINITY),
+    );
+
+    if probe.is_set() {
+        ui.add_space(8.0);
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new("Timeout:")
+                    .size(12.0)
+                    .color(Theme::text_muted()),
+            );
+            ui.add_space(8.0);
+            ui.add(
+                egui::DragValue::new(&mut probe.timeout_secs)
+                    .range(1..=120)
+                    .suffix(" sec"),
+            );
+        });
+    } else {
+        config.health_probe = None;
+    }
+}
+
+/// Graceful shutdown command and how long to wait for it
+fn render_stop_command(ui: &mut egui::Ui, config: &mut InstanceConfig) {
+    ui.label(
+        egui::RichText::new("Stop command")
+            .size(13.0)
+            .color(Theme::text_primary()),
+    );
+    ui.label(
+        egui::RichText::new(
+            "Run on stop before terminating the process, e.g. app.exe --quit or curl http://localhost:8080/shutdown ({pid} is the process ID)",
+        )
+        .size(11.0)
+        .color(Theme::text_muted()),
+    );
+    ui.add_space(4.0);
+    ui.add(
+        egui::TextEdit::singleline(&mut config.stop_command)
+            .hint_text("None (terminate directly)")
+            .desired_width(f32::INFINITY),
+    );
+
+    if !config.stop_command.trim().is_empty() {
+        ui.add_space(8.0);
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new("Wait for exit:")
+                    .size(12.0)
+                    .color(Theme::text_muted()),
+            );
+            ui.add_space(8.0);
+            ui.add(
+                egui::DragValue::new(&mut config.stop_timeout_secs)
+                    .range(1..=300)
+                    .suffix(" sec"),
+            );
+        });
+    }
+}
+
+fn render_output_capture(ui: &mut egui::Ui, config: &mut InstanceConfig) {
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut config.capture_output, "");
+        ui.vertical(|ui| {
+            ui.label(
+                egui::RichText::new("Capture output")
+                    .size(13.0)
+                    .color(Theme::text_primary()),
+            );
+            ui.label(
+                egui::RichText::new(
+                    "Save stdout and stderr to a rotating log, shown in the Logs tab of the instance details",
+                )
+                .size(11.0)
+                .color(Theme::text_muted()),
+            );
+        });
+    });
+}
+
+/// Ports and devices the instance must not share with another running instance
+fn render_exclusive_resources(ui: &mut egui::Ui, config: &mut InstanceConfig, id_salt: &str) {
+    ui.label(
+        egui::RichText::new("Exclusive resources")
+            .size(13.0)
+            .color(Theme::text_primary()),
+    );
+    ui.label(
+        egui::RichText::new("Launching is blocked while another instance holds one of these")
+            .size(11.0)
+            .color(Theme::text_muted()),
+    );
+    ui.add_space(4.0);
+
+    let parse_ports = |text: &str| -> Vec<u16> {
+        text.split(',')
+            .filter_map(|p| p.trim().parse::<u16>().ok())
+            .filter(|p| *p > 0)
+            .collect()
+    };
+    let parse_devices = |text: &str| -> Vec<String> {
+        text.split(',')
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty())
+            .collect()
+    };
+
+    // Keep the raw text so partially typed lists survive between frames,
+    // unless it no longer describes the config (another instance was opened)
+    let ports_id = egui::Id::new((id_salt, "ports"));
+    let mut ports = ui
+        .data(|d| d.get_temp::<String>(ports_id))
+        .filter(|text| parse_ports(text) == config.ports)
+        .unwrap_or_else(|| {
+            config
+                .ports
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        });
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new("Ports")
+                .size(12.0)
+                .color(Theme::text_muted()),
+        );
+        if ui
+            .add(
+                egui::TextEdit::singleline(&mut ports)
+                    .hint_text("e.g. 8080, 9000")
+                    .desired_width(f32::INFINITY),
+            )
+            .changed()
+        {
+            config.ports = parse_ports(&ports);
+        }
+    });
+    ui.data_mut(|d| d.insert_temp(ports_id, ports));
+
+    let devices_id = egui::Id::new((id_salt, "devices"));
+    let mut devices = ui
+        .data(|d| d.get_temp::<String>(devices_id))
+        .filter(|text| parse_devices(text) == config.exclusive_devices)
+        .unwrap_or_else(|| config.exclusive_devices.join(", "));
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new("Devices")
+                .size(12.0)
+                .color(Theme::text_muted()),
+        );
+        if ui
+            .add(
+                egui::TextEdit::singleline(&mut devices)
+                    .hint_text("e.g. COM3, /dev/ttyUSB0")
+                    .desired_width(f32::INFINITY),
+            )
+            .changed()
+        {
+            config.exclusive_devices = parse_devices(&devices);
+        }
+    });
+    ui.data_mut(|d| d.insert_temp(devices_id, devices));
+}
+
+/// Custom environment variables with a preview of their expanded values
+fn render_environment_vars(ui: &mut egui::Ui, config: &mut InstanceConfig) {
+    ui.label(
+        egui::RichText::new("Environment variables")
+            .size(13.0)
+            .color(Theme::text_primary()),
+    );
+    ui.label(
+        egui::RichText::new(
+            "Applied after the isolation variables; use $NAME or ${NAME} to reference others",
+        )
+        .size(11.0)
+        .color(Theme::text_muted()),
+    );
+    ui.add_space(4.0);
+
+    let expanded = environment::expand_all(&config.environment, &[]);
+    let duplicates: Vec<bool> = config
+        .environment
+        .iter()
+        .map(|(key, _)| environment::is_duplicate(&config.environment, key))
+        .collect();
+
+    let mut remove = None;
+    for (index, (key, value)) in config.environment.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            let key_color = if duplicates[index] {
+                Theme::error()
+            } else {
+                Theme::text_primary()
+            };
+            ui.add(
+                egui::TextEdit::singleline(key)
+                    .hint_text("NAME")
+                    .text_color(key_color)
+                    .desired_width(140.0),
+            );
+            ui.add(
+                egui::TextEdit::singleline(value)
+                    .hint_text("Value")
+                    .desired_width(260.0),
+            );
+            if ui.small_button("✕").clicked() {
+                remove = Some(index);
+            }
+        });
+
+        let (preview, missing) = &expanded[index];
+        if preview != value {
+            ui.label(
+                egui::RichText::new(format!("→ {}", preview))
+                    .size(11.0)
+                    .color(Theme::text_muted()),
+            );
+        }
+        if !missing.is_empty() {
+            ui.label(
+                egui::RichText::new(format!("Not defined: {}", missing.join(", ")))
+                    .size(11.0)
+                    .color(Theme::warning()),
+            );
+        }
+    }
+    if let Some(index) = remove {
+        config.environment.remove(index);
+    }
+    if ui.small_button("+ Add variable").clicked() {
+        config.environment.push((String::new(), String::new()));
+    }
+    if let Some(error) = environment::validate(&config.environment) {
+        ui.label(egui::RichText::new(error).size(11.0).color(Theme::error()));
+    }
+}
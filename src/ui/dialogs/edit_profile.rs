@@ -2,7 +2,10 @@
 
 use egui::Context;
 
+use crate::core::deployment::{self, InstallScope};
+use crate::core::profile::{MemberDependency, Readiness};
 use crate::core::{AppState, ProfileId};
+use crate::ui::components::LimitPresetPicker;
 use crate::ui::dialogs::DialogState;
 use crate::ui::theme::Theme;
 
@@ -15,6 +18,11 @@ pub fn render(ctx: &Context, id: ProfileId, state: &mut AppState, dialog: &mut D
     drop(profiles);
 
     let mut profile = profile;
+    let presets = state
+        .settings
+        .read()
+        .map(|s| s.limit_presets.clone())
+        .unwrap_or_default();
     let mut open = true;
 
     egui::Window::new(format!("Edit Profile: {}", profile.name))
@@ -70,6 +78,41 @@ pub fn render(ctx: &Context, id: ProfileId, state: &mut AppState, dialog: &mut D
                     });
                 }
 
+                ui.add_space(8.0);
+                ui.checkbox(&mut profile.shared_isolation, "Shared isolation root");
+                ui.label(
+                    egui::RichText::new(
+                        "Instances share one sandboxed data folder so they can see each other",
+                    )
+                    .small()
+                    .color(Theme::text_muted()),
+                );
+
+                if profile.shared_isolation {
+                    ui.horizontal(|ui| {
+                        ui.label("Root:");
+                        let mut root = profile
+                            .isolation_root
+                            .as_ref()
+                            .map(|p| p.to_string_lossy().to_string())
+                            .unwrap_or_default();
+                        ui.add(
+                            egui::TextEdit::singleline(&mut root)
+                                .hint_text("Default (profile folder)"),
+                        );
+                        if ui.button("Browse").clicked() {
+                            if let Some(path) = rfd::FileDialog::new().pick_folder() {
+                                root = path.to_string_lossy().to_string();
+                            }
+                        }
+                        profile.isolation_root = if root.is_empty() {
+                            None
+                        } else {
+                            Some(root.into())
+                        };
+                    });
+                }
+
                 ui.add_space(16.0);
                 ui.separator();
                 ui.add_space(8.0);
@@ -79,28 +122,32 @@ pub fn render(ctx: &Context, id: ProfileId, state: &mut AppState, dialog: &mut D
                     ui.label(egui::RichText::new("Instances").strong());
                     ui.label(
                         egui::RichText::new(format!("({})", profile.instances.len()))
-                            .color(Theme::TEXT_MUTED),
+                            .color(Theme::text_muted()),
                     );
                 });
                 ui.add_space(8.0);
 
                 if profile.instances.is_empty() {
                     egui::Frame::none()
-                        .fill(Theme::BG_TERTIARY)
+                        .fill(Theme::bg_tertiary())
                         .rounding(egui::Rounding::same(4.0))
                         .inner_margin(egui::Margin::same(12.0))
                         .show(ui, |ui| {
                             ui.label(
                                 egui::RichText::new("No instances in this profile")
-                                    .color(Theme::TEXT_MUTED),
+                                    .color(Theme::text_muted()),
                             );
                         });
                 } else {
                     let mut to_remove = None;
+                    let mut preset_applied = None;
+                    let mut dependency_changed = None;
+                    let names: Vec<String> =
+                        profile.instances.iter().map(|c| c.name.clone()).collect();
 
-                    for (idx, config) in profile.instances.iter().enumerate() {
+                    for (idx, config) in profile.instances.iter_mut().enumerate() {
                         egui::Frame::none()
-                            .fill(Theme::BG_TERTIARY)
+                            .fill(Theme::bg_tertiary())
                             .rounding(egui::Rounding::same(4.0))
                             .inner_margin(egui::Margin::same(8.0))
                             .show(ui, |ui| {
@@ -115,7 +162,7 @@ pub fn render(ctx: &Context, id: ProfileId, state: &mut AppState, dialog: &mut D
                                                 .unwrap_or_default(),
                                         )
                                         .small()
-                                        .color(Theme::TEXT_SECONDARY),
+                                        .color(Theme::text_secondary()),
                                     );
 
                                     ui.with_layout(
@@ -124,9 +171,29 @@ pub fn render(ctx: &Context, id: ProfileId, state: &mut AppState, dialog: &mut D
                                             if ui.small_button("✕").clicked() {
                                                 to_remove = Some(idx);
                                             }
+                                            if LimitPresetPicker::show(
+                                                ui,
+                                                &presets,
+                                                &mut config.resource_limits,
+                                                ("profile_member_preset", idx),
+                                            ) {
+                                                preset_applied =
+                                                    Some((idx, config.resource_limits.clone()));
+                                            }
                                         },
                                     );
                                 });
+
+                                let current = profile
+                                    .dependencies
+                                    .iter()
+                                    .find(|d| d.member == idx)
+                                    .cloned();
+                                let mut edited = current.clone();
+                                render_dependency(ui, idx, &names, &mut edited);
+                                if edited != current {
+                                    dependency_changed = Some((idx, edited));
+                                }
                             });
                         ui.add_space(4.0);
                     }
@@ -134,6 +201,17 @@ pub fn render(ctx: &Context, id: ProfileId, state: &mut AppState, dialog: &mut D
                     if let Some(idx) = to_remove {
                         profile.remove_instance(idx);
                     }
+                    // Presets are saved right away, like picking a variant on a card
+                    if let Some((idx, limits)) = preset_applied {
+                        if let Err(e) = state.set_profile_member_limits(id, idx, limits) {
+                            tracing::error!("Failed to apply limit preset: {}", e);
+                        }
+                    }
+                    if let Some((idx, dependency)) = dependency_changed {
+                        if let Err(e) = state.set_profile_member_dependency(id, idx, dependency) {
+                            tracing::error!("Failed to set launch dependency: {}", e);
+                        }
+                    }
                 }
 
                 ui.add_space(8.0);
@@ -161,7 +239,7 @@ pub fn render(ctx: &Context, id: ProfileId, state: &mut AppState, dialog: &mut D
                 ui.label(
                     egui::RichText::new(format!("Launched {} times", profile.launch_count))
                         .small()
-                        .color(Theme::TEXT_MUTED),
+                        .color(Theme::text_muted()),
                 );
                 if let Some(last_used) = profile.last_used_at {
                     ui.label(
@@ -170,7 +248,7 @@ pub fn render(ctx: &Context, id: ProfileId, state: &mut AppState, dialog: &mut D
                             last_used.format("%Y-%m-%d %H:%M")
                         ))
                         .small()
-                        .color(Theme::TEXT_MUTED),
+                        .color(Theme::text_muted()),
                     );
                 }
 
@@ -180,6 +258,7 @@ pub fn render(ctx: &Context, id: ProfileId, state: &mut AppState, dialog: &mut D
                 let profile_name = profile.name.clone();
                 let mut should_save = false;
                 let mut should_delete = false;
+                let mut should_publish = false;
 
                 ui.horizontal(|ui| {
                     if ui.button("Save").clicked() {
@@ -190,9 +269,19 @@ pub fn render(ctx: &Context, id: ProfileId, state: &mut AppState, dialog: &mut D
                         *dialog = DialogState::None;
                     }
 
+                    if InstallScope::detect() == InstallScope::PerMachine
+                        && ui
+                            .button("Share with All Users")
+                            .on_hover_text("Publish as a template every user of this machine gets")
+                            .clicked()
+                    {
+                        should_save = true;
+                        should_publish = true;
+                    }
+
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                         if ui
-                            .button(egui::RichText::new("Delete").color(Theme::ERROR))
+                            .button(egui::RichText::new("Delete").color(Theme::error()))
                             .clicked()
                         {
                             should_delete = true;
@@ -202,6 +291,11 @@ pub fn render(ctx: &Context, id: ProfileId, state: &mut AppState, dialog: &mut D
 
                 if should_save {
                     profile.mark_modified();
+                    if should_publish {
+                        if let Err(e) = deployment::publish_template(&profile) {
+                            tracing::error!("Failed to publish profile template: {}", e);
+                        }
+                    }
                     if let Err(e) = state.save_profile(profile) {
                         tracing::error!("Failed to save profile: {}", e);
                     }
@@ -225,3 +319,82 @@ pub fn render(ctx: &Context, id: ProfileId, state: &mut AppState, dialog: &mut D
         *dialog = DialogState::None;
     }
 }
+
+/// Which member `idx` waits for before it is launched, and until when
+fn render_dependency(
+    ui: &mut egui::Ui,
+    idx: usize,
+    names: &[String],
+    dependency: &mut Option<MemberDependency>,
+) {
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new("Waits for")
+                .small()
+                .color(Theme::text_muted()),
+        );
+        egui::ComboBox::from_id_salt(("profile_member_after", idx))
+            .width(140.0)
+            .selected_text(
+                dependency
+                    .as_ref()
+                    .and_then(|d| names.get(d.after))
+                    .map_or("Nothing", String::as_str),
+            )
+            .show_ui(ui, |ui| {
+                if ui
+                    .selectable_label(dependency.is_none(), "Nothing")
+                    .clicked()
+                {
+                    *dependency = None;
+                }
+                for (other, name) in names.iter().enumerate().filter(|(o, _)| *o != idx) {
+                    let selected = dependency.as_ref().is_some_and(|d| d.after == other);
+                    if ui.selectable_label(selected, name).clicked() {
+                        *dependency = Some(match dependency.take() {
+                            Some(d) => MemberDependency { after: other, ..d },
+                            None => MemberDependency::new(idx, other),
+                        });
+                    }
+                }
+            });
+
+        let Some(dependency) = dependency.as_mut() else {
+            return;
+        };
+        egui::ComboBox::from_id_salt(("profile_member_ready", idx))
+            .width(120.0)
+            .selected_text(dependency.ready.label())
+            .show_ui(ui, |ui| {
+                for ready in Readiness::all() {
+                    let selected = ready.label() == dependency.ready.label();
+                    if ui.selectable_label(selected, ready.label()).clicked() && !selected {
+                        dependency.ready = ready;
+                    }
+                }
+            });
+        match &mut dependency.ready {
+            Readiness::Delay { ms } => {
+                ui.add(
+                    egui::DragValue::new(ms)
+                        .range(0..=600_000)
+                        .speed(100.0)
+                        .suffix(" ms"),
+                );
+            }
+            Readiness::PortOpen { port } => {
+                ui.add(egui::DragValue::new(port).range(1..=65535).prefix("port "));
+            }
+            Readiness::WindowVisible => {}
+        }
+        if !matches!(dependency.ready, Readiness::Delay { .. }) {
+            ui.add(
+                egui::DragValue::new(&mut dependency.timeout_secs)
+                    .range(1..=3600)
+                    .prefix("give up after ")
+                    .suffix(" s"),
+            )
+            .on_hover_text("The member is skipped if it is still not ready by then");
+        }
+    });
+}
@@ -1,13 +1,68 @@
 //! Instance details dialog
 
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
 use egui::Context;
 
-use crate::core::{resource::format_bytes, AppState, InstanceId};
-use crate::ui::components::ResourceBar;
-use crate::ui::dialogs::DialogState;
-use crate::ui::theme::Theme;
+use crate::core::bundle::BUNDLE_EXTENSION;
+use crate::core::environment::{self, EnvChange, EnvSource};
+use crate::core::inspect::{Connection, Inspection, OpenHandle};
+use crate::core::isolation_check::IsolationCheck;
+use crate::core::network_limit::{self, LimitStatus};
+use crate::core::output_log;
+use crate::core::rolling_restart::RollingRestartTarget;
+use crate::core::status_detection::Health;
+use crate::core::{resource::format_bytes, AppState, Instance, InstanceId, ResourceLimits};
+use crate::ui::components::{ChartSeries, LineChart, MacroEditor, ResourceBar};
+use crate::ui::dialogs::{architecture_info, DialogState};
+use crate::ui::panels::dashboard::capture_texture;
+use crate::ui::theme::{Icons, Theme};
+
+/// Sections of the details dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum DetailsTab {
+    #[default]
+    Overview,
+    OpenFiles,
+    Connections,
+    Environment,
+    Macros,
+    Logs,
+}
+
+impl DetailsTab {
+    fn all() -> [DetailsTab; 6] {
+        [
+            Self::Overview,
+            Self::OpenFiles,
+            Self::Connections,
+            Self::Environment,
+            Self::Macros,
+            Self::Logs,
+        ]
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Overview => "Overview",
+            Self::OpenFiles => "Open files",
+            Self::Connections => "Connections",
+            Self::Environment => "Environment",
+            Self::Macros => "Macros",
+            Self::Logs => "Logs",
+        }
+    }
+}
 
-pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut DialogState) {
+/// `locked` is set while the settings PIN guards configuration changes
+pub fn render(
+    ctx: &Context,
+    id: InstanceId,
+    state: &mut AppState,
+    dialog: &mut DialogState,
+    locked: bool,
+) {
     let instances = state.instances.read().unwrap();
     let Some(instance) = instances.get(&id).cloned() else {
         *dialog = DialogState::None;
@@ -15,6 +70,19 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
     };
     drop(instances);
 
+    let checkpoints_enabled = state
+        .settings
+        .read()
+        .map(|s| s.experimental_checkpoints)
+        .unwrap_or(false);
+
+    let architecture = architecture_info(
+        ctx,
+        "instance_details",
+        &instance.config.executable_path,
+        instance.config.launch_architecture,
+    );
+
     let mut open = true;
 
     egui::Window::new(format!("Details: {}", instance.display_name()))
@@ -24,6 +92,42 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
         .default_width(550.0)
         .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
         .show(ctx, |ui| {
+            let tab_id = egui::Id::new(("instance_details_tab", id.0));
+            let mut tab: DetailsTab = ui.data(|d| d.get_temp(tab_id)).unwrap_or_default();
+            ui.horizontal(|ui| {
+                for option in DetailsTab::all() {
+                    if ui.selectable_label(tab == option, option.label()).clicked() {
+                        tab = option;
+                    }
+                }
+            });
+            ui.data_mut(|d| d.insert_temp(tab_id, tab));
+            ui.separator();
+
+            match tab {
+                DetailsTab::Overview => {}
+                DetailsTab::OpenFiles => {
+                    render_open_files(ui, state, &instance);
+                    return;
+                }
+                DetailsTab::Connections => {
+                    render_connections(ui, state, &instance);
+                    return;
+                }
+                DetailsTab::Environment => {
+                    render_environment(ui, &instance);
+                    return;
+                }
+                DetailsTab::Macros => {
+                    MacroEditor::show(ui, state, &instance);
+                    return;
+                }
+                DetailsTab::Logs => {
+                    render_logs(ui, state, &instance);
+                    return;
+                }
+            }
+
             egui::ScrollArea::vertical().show(ui, |ui| {
                 // Status header
                 ui.horizontal(|ui| {
@@ -37,16 +141,47 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                     if instance.status.is_active() {
                         ui.label(
                             egui::RichText::new(format!("Uptime: {}", instance.uptime_string()))
-                                .color(Theme::TEXT_SECONDARY),
+                                .color(Theme::text_secondary()),
                         );
                     }
                 });
 
+                if instance.update_available {
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new("⬆ Executable changed on disk since launch")
+                                .color(Theme::info()),
+                        );
+                        if ui.button("Restart to Update").clicked() {
+                            if let Err(e) = state.restart_instance(id) {
+                                tracing::error!("Failed to restart instance: {}", e);
+                            }
+                        }
+                        if let Some(group) = instance.config.group.clone() {
+                            if ui
+                                .button("Restart Group")
+                                .on_hover_text(format!(
+                                    "Restart \"{}\" one instance at a time",
+                                    group
+                                ))
+                                .clicked()
+                            {
+                                if let Err(e) = state
+                                    .start_rolling_restart(RollingRestartTarget::Group(group))
+                                {
+                                    tracing::error!("Failed to start rolling restart: {}", e);
+                                }
+                            }
+                        }
+                    });
+                }
+
                 ui.add_space(16.0);
 
                 // Basic info
                 egui::Frame::none()
-                    .fill(Theme::BG_SECONDARY)
+                    .fill(Theme::bg_secondary())
                     .rounding(egui::Rounding::same(8.0))
                     .inner_margin(egui::Margin::same(12.0))
                     .show(ui, |ui| {
@@ -61,7 +196,7 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                                 ui.label(
                                     egui::RichText::new(instance.id.to_string())
                                         .small()
-                                        .color(Theme::TEXT_MUTED),
+                                        .color(Theme::text_muted()),
                                 );
                                 ui.end_row();
 
@@ -74,12 +209,122 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                                 );
                                 ui.end_row();
 
+                                if let Some(ref info) = architecture {
+                                    ui.label("Architecture:");
+                                    let color = if info.is_emulated() {
+                                        Theme::warning()
+                                    } else {
+                                        Theme::text_primary()
+                                    };
+                                    ui.label(egui::RichText::new(info.summary()).color(color))
+                                        .on_hover_text(format!(
+                                            "This machine is {}",
+                                            info.host.label()
+                                        ));
+                                    ui.end_row();
+                                }
+
                                 if let Some(pid) = instance.pid {
                                     ui.label("PID:");
                                     ui.label(egui::RichText::new(pid.to_string()));
                                     ui.end_row();
                                 }
 
+                                if let Some(ref launch_id) = instance.launch_id {
+                                    ui.label("Launch ID:");
+                                    if ui
+                                        .add(
+                                            egui::Label::new(
+                                                egui::RichText::new(launch_id).monospace(),
+                                            )
+                                            .sense(egui::Sense::click()),
+                                        )
+                                        .on_hover_text(
+                                            "Tags this launch in the history, output log and \
+                                             MultiInstance's log. Click to copy.",
+                                        )
+                                        .clicked()
+                                    {
+                                        ui.ctx().copy_text(launch_id.clone());
+                                    }
+                                    ui.end_row();
+                                }
+
+                                if let Some(bits) = instance.process_bits {
+                                    ui.label("Bitness:");
+                                    let label = if bits == 32 && cfg!(windows) {
+                                        "32-bit (WOW64)".to_string()
+                                    } else {
+                                        format!("{}-bit", bits)
+                                    };
+                                    let response = ui.label(label);
+                                    if bits == 32 {
+                                        response.on_hover_text(
+                                            "32-bit processes can use at most 32 cores and 4 GB of memory",
+                                        );
+                                    }
+                                    ui.end_row();
+                                }
+
+                                let data_busy = state.is_data_busy(id);
+                                if data_busy || instance.compressed_bytes.is_some() {
+                                    ui.label("Data:");
+                                    ui.horizontal(|ui| match instance.compressed_bytes {
+                                        Some(_) if data_busy => {
+                                            ui.spinner();
+                                            ui.label("Decompressing…");
+                                        }
+                                        Some(bytes) => {
+                                            ui.label(format!(
+                                                "Compressed to {}",
+                                                format_bytes(bytes)
+                                            ))
+                                            .on_hover_text("Unpacked before the next launch");
+                                            if ui.small_button("Decompress").clicked() {
+                                                if let Err(e) = state.decompress_instance_data(id)
+                                                {
+                                                    tracing::error!(
+                                                        "Failed to decompress instance data: {}",
+                                                        e
+                                                    );
+                                                }
+                                            }
+                                        }
+                                        None => {
+                                            ui.spinner();
+                                            ui.label("Compressing…");
+                                        }
+                                    });
+                                    ui.end_row();
+                                }
+
+                                if instance.pending_config.is_some() {
+                                    ui.label("Changes:");
+                                    ui.horizontal(|ui| {
+                                        ui.label(
+                                            egui::RichText::new("Pending, applies on next restart")
+                                                .color(Theme::warning()),
+                                        );
+                                        if ui.small_button("Restart to apply").clicked() {
+                                            if let Err(e) = state.restart_instance(id) {
+                                                tracing::error!(
+                                                    "Failed to restart instance: {}",
+                                                    e
+                                                );
+                                            }
+                                        }
+                                        if ui.small_button("Discard").clicked() {
+                                            if let Err(e) = state.discard_pending_config(id) {
+                                                tracing::error!(
+                                                    "Failed to discard changes: {}",
+                                                    e
+                                                );
+                                            }
+                                        }
+                                    });
+                                    ui.end_row();
+                                }
+
                                 if !instance.config.arguments.is_empty() {
                                     ui.label("Arguments:");
                                     ui.label(
@@ -89,6 +334,19 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                                     ui.end_row();
                                 }
 
+                                if let Some(variant) = instance.config.variant() {
+                                    ui.label("Variant:");
+                                    ui.label(
+                                        egui::RichText::new(format!(
+                                            "{} ({})",
+                                            variant.name,
+                                            variant.arguments.join(" ")
+                                        ))
+                                        .small(),
+                                    );
+                                    ui.end_row();
+                                }
+
                                 if let Some(ref group) = instance.config.group {
                                     ui.label("Group:");
                                     ui.label(group);
@@ -107,6 +365,53 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                                 ui.label("Restarts:");
                                 ui.label(instance.restart_count.to_string());
                                 ui.end_row();
+
+                                if let Some(ref check) = instance.health {
+                                    ui.label("Health:");
+                                    let (label, color) = match &check.health {
+                                        Health::Healthy => ("Healthy".to_string(), Theme::success()),
+                                        Health::Unhealthy(reason) => {
+                                            (format!("Unhealthy: {}", reason), Theme::error())
+                                        }
+                                        Health::Unknown(reason) => {
+                                            (format!("Unknown: {}", reason), Theme::warning())
+                                        }
+                                    };
+                                    let mut text = match (&check.text, &check.output) {
+                                        (t, o) if t.is_empty() && !o.is_empty() => String::new(),
+                                        (t, _) if t.is_empty() => "No text recognized".to_string(),
+                                        (t, _) => format!("Read on screen: {}", t),
+                                    };
+                                    if !check.output.is_empty() {
+                                        if !text.is_empty() {
+                                            text.push('\n');
+                                        }
+                                        text.push_str(&format!("Probe output: {}", check.output));
+                                    }
+                                    ui.label(egui::RichText::new(label).color(color))
+                                        .on_hover_text(format!(
+                                            "{}\nChecked at {}",
+                                            text,
+                                            check.checked_at.format("%H:%M:%S")
+                                        ));
+                                    ui.end_row();
+                                }
+
+                                if let Some(ref check) = instance.isolation_check {
+                                    ui.label("Isolation:");
+                                    let (label, color) = match check {
+                                        IsolationCheck::Verified => ("Verified", Theme::success()),
+                                        IsolationCheck::Ignored { .. } => {
+                                            ("Ignored by the process", Theme::warning())
+                                        }
+                                        IsolationCheck::Unreadable => {
+                                            ("Could not be checked", Theme::text_muted())
+                                        }
+                                    };
+                                    ui.label(egui::RichText::new(label).color(color))
+                                        .on_hover_text(check.details());
+                                    ui.end_row();
+                                }
                             });
                     });
 
@@ -115,7 +420,7 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                 // Resource usage (if active)
                 if instance.status.is_active() {
                     egui::Frame::none()
-                        .fill(Theme::BG_SECONDARY)
+                        .fill(Theme::bg_secondary())
                         .rounding(egui::Rounding::same(8.0))
                         .inner_margin(egui::Margin::same(12.0))
                         .show(ui, |ui| {
@@ -143,11 +448,13 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                                     ui.label(
                                         egui::RichText::new(format_bytes(usage.memory_bytes))
                                             .size(18.0)
-                                            .color(Theme::PRIMARY_LIGHT),
+                                            .color(Theme::primary_light()),
                                     );
                                 });
                             });
 
+                            ui.add_space(8.0);
+                            render_usage_charts(ui, state, &instance);
                             ui.add_space(8.0);
 
                             egui::Grid::new("resource_grid")
@@ -173,6 +480,21 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                                     ui.label("Disk Write:");
                                     ui.label(format_bytes(usage.disk_write_bytes));
                                     ui.end_row();
+
+                                    ui.label("CPU Time:");
+                                    ui.label(usage.cpu_time_string());
+                                    ui.end_row();
+
+                                    ui.label("CPU Efficiency:");
+                                    ui.label(match usage.cpu_efficiency() {
+                                        Some(efficiency) => format!(
+                                            "{:.1}% of one core since launch",
+                                            efficiency
+                                        ),
+                                        None => "-".to_string(),
+                                    })
+                                    .on_hover_text("CPU time divided by wall-clock time");
+                                    ui.end_row();
                                 });
                         });
 
@@ -181,7 +503,7 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
 
                 // Resource limits
                 egui::Frame::none()
-                    .fill(Theme::BG_SECONDARY)
+                    .fill(Theme::bg_secondary())
                     .rounding(egui::Rounding::same(8.0))
                     .inner_margin(egui::Margin::same(12.0))
                     .show(ui, |ui| {
@@ -195,11 +517,53 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                             .spacing([16.0, 4.0])
                             .show(ui, |ui| {
                                 ui.label("CPU Limit:");
-                                ui.label(if limits.cpu_percent == 0 {
-                                    "Unlimited".to_string()
+                                if instance.status.is_active() {
+                                    // Adjustable while running; applied once the drag
+                                    // ends rather than on every frame of it
+                                    let drag_id = egui::Id::new(("cpu_limit_drag", id));
+                                    let mut percent = ui
+                                        .data(|d| d.get_temp::<u8>(drag_id))
+                                        .unwrap_or(limits.cpu_percent);
+                                    let slider = ui
+                                        .add_enabled(
+                                            !locked,
+                                            egui::Slider::new(&mut percent, 0..=100)
+                                                .suffix("%")
+                                                .custom_formatter(|n, _| {
+                                                    if n == 0.0 {
+                                                        "Unlimited".to_string()
+                                                    } else {
+                                                        format!("{:.0}%", n)
+                                                    }
+                                                }),
+                                        )
+                                        .on_hover_text("Share of the whole machine's CPU time")
+                                        .on_disabled_hover_text(
+                                            "Unlock settings to change the CPU limit",
+                                        );
+                                    let done = slider.drag_stopped()
+                                        || slider.lost_focus()
+                                        || (slider.changed() && !slider.dragged());
+                                    if done {
+                                        ui.data_mut(|d| d.remove::<u8>(drag_id));
+                                        if percent != limits.cpu_percent {
+                                            if let Err(e) = state.set_cpu_limit(id, percent) {
+                                                tracing::warn!(
+                                                    "Failed to change CPU limit: {}",
+                                                    e
+                                                );
+                                            }
+                                        }
+                                    } else if slider.changed() {
+                                        ui.data_mut(|d| d.insert_temp(drag_id, percent));
+                                    }
                                 } else {
-                                    format!("{}%", limits.cpu_percent)
-                                });
+                                    ui.label(if limits.cpu_percent == 0 {
+                                        "Unlimited".to_string()
+                                    } else {
+                                        format!("{}%", limits.cpu_percent)
+                                    });
+                                }
                                 ui.end_row();
 
                                 ui.label("Memory Limit:");
@@ -211,40 +575,214 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                                 ui.end_row();
 
                                 ui.label("Network Limit:");
-                                ui.label(if limits.network_kbps == 0 {
-                                    "Unlimited".to_string()
+                                if limits.network_kbps == 0 {
+                                    ui.label("Unlimited");
                                 } else {
-                                    format!("{} KB/s", limits.network_kbps)
+                                    ui.vertical(|ui| render_network_limit(ui, state, &instance));
+                                }
+                                ui.end_row();
+
+                                ui.label("Disk I/O:");
+                                ui.label(if limits.disk_mbps == 0 {
+                                    format!("{} priority", limits.io_priority.label())
+                                } else {
+                                    format!(
+                                        "{} priority, {} MB/s",
+                                        limits.io_priority.label(),
+                                        limits.disk_mbps
+                                    )
                                 });
                                 ui.end_row();
 
                                 ui.label("Priority:");
-                                ui.label(limits.priority.to_string());
+                                ui.label(ResourceLimits::priority_label(limits.priority));
                                 ui.end_row();
                             });
                     });
 
                 ui.add_space(16.0);
 
+                // Data snapshots
+                egui::Frame::none()
+                    .fill(Theme::bg_secondary())
+                    .rounding(egui::Rounding::same(8.0))
+                    .inner_margin(egui::Margin::same(12.0))
+                    .show(ui, |ui| {
+                        ui.label(egui::RichText::new("Data Snapshots").strong());
+                        ui.add_space(8.0);
+
+                        let name_id = egui::Id::new(("snapshot_name", id));
+                        let error_id = egui::Id::new(("snapshot_error", id));
+                        let mut name: String =
+                            ui.data_mut(|d| d.get_temp(name_id).unwrap_or_default());
+                        let busy = state.is_data_busy(id);
+                        let stopped = !instance.status.is_active()
+                            && instance.status != crate::core::InstanceStatus::Pending;
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::TextEdit::singleline(&mut name)
+                                    .hint_text("Snapshot name")
+                                    .desired_width(200.0),
+                            );
+                            if ui
+                                .add_enabled(
+                                    !name.trim().is_empty() && stopped && !busy,
+                                    egui::Button::new("Take Snapshot"),
+                                )
+                                .on_disabled_hover_text(if stopped {
+                                    "Enter a name for the snapshot"
+                                } else {
+                                    "Stop the instance first, so no file is copied half-written"
+                                })
+                                .clicked()
+                            {
+                                match state.create_snapshot(id, name.trim()) {
+                                    Ok(()) => {
+                                        name.clear();
+                                        ui.data_mut(|d| d.remove::<String>(error_id));
+                                    }
+                                    Err(e) => ui.data_mut(|d| {
+                                        d.insert_temp(error_id, format!("{:#}", e))
+                                    }),
+                                }
+                            }
+                            if busy {
+                                ui.spinner();
+                                ui.ctx().request_repaint_after(Duration::from_millis(250));
+                            }
+                        });
+                        ui.data_mut(|d| d.insert_temp(name_id, name));
+                        if let Some(error) = ui.data(|d| d.get_temp::<String>(error_id)) {
+                            ui.label(egui::RichText::new(error).small().color(Theme::error()));
+                        }
+
+                        let snapshots = state.list_snapshots(id);
+                        if snapshots.is_empty() {
+                            ui.label(
+                                egui::RichText::new("No snapshots yet")
+                                    .small()
+                                    .color(Theme::text_muted()),
+                            );
+                        }
+
+                        for snapshot in snapshots {
+                            ui.horizontal(|ui| {
+                                ui.label(&snapshot.name);
+                                ui.label(
+                                    egui::RichText::new(format!(
+                                        "{} · {}",
+                                        snapshot
+                                            .created_at
+                                            .with_timezone(&chrono::Local)
+                                            .format("%Y-%m-%d %H:%M"),
+                                        format_bytes(snapshot.size_bytes)
+                                    ))
+                                    .small()
+                                    .color(Theme::text_muted()),
+                                );
+
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| {
+                                        // Both replace data, which the settings PIN guards
+                                        if ui
+                                            .add_enabled(
+                                                !busy && !locked,
+                                                egui::Button::new(Icons::TRASH).small(),
+                                            )
+                                            .on_disabled_hover_text(if locked {
+                                                "Unlock settings to delete snapshots"
+                                            } else {
+                                                "A snapshot is being taken or restored"
+                                            })
+                                            .clicked()
+                                        {
+                                            if let Err(e) = state.delete_snapshot(id, &snapshot) {
+                                                ui.data_mut(|d| {
+                                                    d.insert_temp(error_id, format!("{:#}", e))
+                                                });
+                                            }
+                                        }
+                                        if ui
+                                            .add_enabled(
+                                                stopped && !busy && !locked,
+                                                egui::Button::new("Restore").small(),
+                                            )
+                                            .on_disabled_hover_text(if locked {
+                                                "Unlock settings to restore snapshots"
+                                            } else {
+                                                "Stop the instance first"
+                                            })
+                                            .clicked()
+                                        {
+                                            *dialog = DialogState::Confirm {
+                                                title: "Restore Snapshot".to_string(),
+                                                message: format!(
+                                                    "Replace the current data of '{}' with snapshot '{}'?",
+                                                    instance.display_name(),
+                                                    snapshot.name
+                                                ),
+                                                on_confirm: std::sync::Arc::new({
+                                                    let state = state.clone();
+                                                    let snapshot = snapshot.clone();
+                                                    let ctx = ui.ctx().clone();
+                                                    move || {
+                                                        if let Err(e) =
+                                                            state.restore_snapshot(id, &snapshot)
+                                                        {
+                                                            ctx.data_mut(|d| {
+                                                                d.insert_temp(
+                                                                    error_id,
+                                                                    format!("{:#}", e),
+                                                                )
+                                                            });
+                                                        }
+                                                    }
+                                                }),
+                                            };
+                                        }
+                                    },
+                                );
+                            });
+                        }
+                    });
+
+                ui.add_space(16.0);
+
                 // Error info
                 if let Some(ref error) = instance.last_error {
                     egui::Frame::none()
-                        .fill(Theme::ERROR.linear_multiply(0.2))
+                        .fill(Theme::error().linear_multiply(0.2))
                         .rounding(egui::Rounding::same(8.0))
                         .inner_margin(egui::Margin::same(12.0))
                         .show(ui, |ui| {
                             ui.label(
                                 egui::RichText::new("Last Error")
                                     .strong()
-                                    .color(Theme::ERROR),
+                                    .color(Theme::error()),
                             );
                             ui.add_space(4.0);
-                            ui.label(egui::RichText::new(error).color(Theme::TEXT_PRIMARY));
+                            ui.label(egui::RichText::new(error).color(Theme::text_primary()));
+                            let report = (instance.status == crate::core::InstanceStatus::Crashed)
+                                .then(|| state.latest_crash_report(id))
+                                .flatten();
+                            if let Some(report) = report {
+                                ui.add_space(4.0);
+                                if ui
+                                    .button("Open crash report")
+                                    .on_hover_text(report.display().to_string())
+                                    .clicked()
+                                {
+                                    let _ = open::that(&report);
+                                }
+                            }
                         });
 
                     ui.add_space(16.0);
                 }
 
+                render_screenshot(ui, state, id);
+
                 // Action buttons
                 ui.horizontal(|ui| {
                     match instance.status {
@@ -268,18 +806,73 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
                             }
                         }
                         crate::core::InstanceStatus::Stopped
-                        | crate::core::InstanceStatus::Crashed => {
-                            if ui.button("Start").clicked() {
-                                let _ = state.start_instance(id);
-                            }
+                        | crate::core::InstanceStatus::Crashed
+                            if ui.button("Start").clicked() =>
+                        {
+                            let _ = state.start_instance(id);
+                        }
+                        crate::core::InstanceStatus::Pending
+                            if ui.button("Cancel launch").clicked() =>
+                        {
+                            let _ = state.stop_instance(id);
                         }
                         _ => {}
                     }
 
+                    if checkpoints_enabled
+                        && instance.status.is_active()
+                        && ui
+                            .button("Checkpoint")
+                            .on_hover_text("Experimental: save this instance to disk and stop it")
+                            .clicked()
+                    {
+                        if let Err(e) = state.checkpoint_instance(id) {
+                            tracing::error!("Failed to checkpoint instance: {}", e);
+                        }
+                    }
+
+                    if instance.status == crate::core::InstanceStatus::Running
+                        && ui
+                            .button("Screenshot")
+                            .on_hover_text("Save the main window as a PNG in the data directory")
+                            .clicked()
+                    {
+                        if let Err(e) = state.capture_screenshot(id) {
+                            tracing::error!("Failed to capture screenshot: {}", e);
+                        }
+                    }
+
                     if ui.button("Edit").clicked() {
                         *dialog = DialogState::EditInstance(id);
                     }
 
+                    let exportable =
+                        !state.is_data_busy(id) && instance.compressed_bytes.is_none();
+                    if ui
+                        .add_enabled(exportable, egui::Button::new("Export"))
+                        .on_hover_text("Save config and data as a bundle for another machine")
+                        .on_disabled_hover_text(if instance.compressed_bytes.is_some() {
+                            "Decompress the data first"
+                        } else {
+                            "Another operation on the instance data is still running"
+                        })
+                        .clicked()
+                    {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("Instance bundle", &[BUNDLE_EXTENSION])
+                            .set_file_name(format!(
+                                "{}.{}",
+                                instance.display_name(),
+                                BUNDLE_EXTENSION
+                            ))
+                            .save_file()
+                        {
+                            if let Err(e) = state.export_instance(id, &path) {
+                                tracing::error!("Failed to export instance: {}", e);
+                            }
+                        }
+                    }
+
                     if ui.button("Close").clicked() {
                         *dialog = DialogState::None;
                     }
@@ -291,3 +884,662 @@ pub fn render(ctx: &Context, id: InstanceId, state: &mut AppState, dialog: &mut
         *dialog = DialogState::None;
     }
 }
+
+/// Thumbnail of the latest screenshot taken this session, if any
+fn render_screenshot(ui: &mut egui::Ui, state: &AppState, id: InstanceId) {
+    let Some(screenshot) = state
+        .screenshots
+        .read()
+        .ok()
+        .and_then(|s| s.get(&id).cloned())
+    else {
+        return;
+    };
+
+    egui::Frame::none()
+        .fill(Theme::bg_tertiary())
+        .rounding(egui::Rounding::same(8.0))
+        .inner_margin(egui::Margin::same(12.0))
+        .show(ui, |ui| {
+            ui.label(
+                egui::RichText::new("Latest Screenshot")
+                    .strong()
+                    .color(Theme::text_primary()),
+            );
+            ui.add_space(4.0);
+            if let Err(ref e) = screenshot.thumbnail.image {
+                ui.label(egui::RichText::new(e).color(Theme::error()));
+                return;
+            }
+            if let Some(texture) =
+                capture_texture(ui.ctx(), "screenshot", id, &screenshot.thumbnail)
+            {
+                ui.image((texture.id(), texture.size_vec2()));
+            }
+            if let Some(ref path) = screenshot.path {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "Saved {}",
+                            screenshot.thumbnail.captured_at.format("%H:%M:%S")
+                        ))
+                        .color(Theme::text_muted()),
+                    )
+                    .on_hover_text(path.display().to_string());
+                    if ui.small_button("Open Folder").clicked() {
+                        if let Some(dir) = path.parent() {
+                            let _ = open::that(dir);
+                        }
+                    }
+                });
+            }
+        });
+
+    ui.add_space(16.0);
+}
+
+/// Files, sockets and pipes the instance holds open, refreshed on demand
+/// CPU, memory and network of the last few minutes
+fn render_usage_charts(ui: &mut egui::Ui, state: &AppState, instance: &Instance) {
+    let Some(history) = instance
+        .pid
+        .and_then(|pid| state.resource_monitor.usage_history(pid))
+        .filter(|h| !h.is_empty())
+    else {
+        return;
+    };
+    let samples = history.samples();
+    let scroll_id = egui::Id::new(("usage_charts", instance.id));
+    let points = |value: fn(&crate::core::UsageSample) -> f64| {
+        samples
+            .iter()
+            .map(|s| (s.time, value(s)))
+            .collect::<Vec<_>>()
+    };
+
+    ui.label(
+        egui::RichText::new("CPU")
+            .size(12.0)
+            .color(Theme::text_secondary()),
+    );
+    let cpu = [ChartSeries {
+        label: "CPU",
+        color: Theme::primary_light(),
+        points: points(|s| s.cpu_percent as f64),
+    }];
+    // 100% is one core, so scale to the next whole core above the peak
+    let peak = cpu[0].points.iter().map(|(_, v)| *v).fold(0.0, f64::max);
+    let cpu_max = (peak / 100.0).ceil().max(1.0) * 100.0;
+    LineChart::show(ui, scroll_id, &cpu, Some(cpu_max), |v| format!("{:.0}%", v));
+
+    ui.add_space(6.0);
+    ui.label(
+        egui::RichText::new("Memory")
+            .size(12.0)
+            .color(Theme::text_secondary()),
+    );
+    let memory = [ChartSeries {
+        label: "Memory",
+        color: Theme::success_light(),
+        points: points(|s| s.memory_bytes as f64),
+    }];
+    LineChart::show(ui, scroll_id, &memory, None, |v| format_bytes(v as u64));
+
+    ui.add_space(6.0);
+    ui.label(
+        egui::RichText::new("Network")
+            .size(12.0)
+            .color(Theme::text_secondary()),
+    );
+    if history.has_network() {
+        let network = [
+            ChartSeries {
+                label: "↓ RX",
+                color: Theme::info(),
+                points: points(|s| s.network_rx_rate as f64),
+            },
+            ChartSeries {
+                label: "↑ TX",
+                color: Theme::warning_light(),
+                points: points(|s| s.network_tx_rate as f64),
+            },
+        ];
+        LineChart::show(ui, scroll_id, &network, None, |v| {
+            format!("{}/s", format_bytes(v as u64))
+        });
+    } else {
+        ui.label(
+            egui::RichText::new("No per-process network traffic measured")
+                .size(11.0)
+                .color(Theme::text_muted()),
+        );
+    }
+}
+
+fn render_open_files(ui: &mut egui::Ui, state: &AppState, instance: &Instance) {
+    if instance.pid.is_none() {
+        ui.label(egui::RichText::new("The instance is not running").color(Theme::text_muted()));
+        return;
+    }
+
+    let inspection = state
+        .open_files
+        .read()
+        .ok()
+        .and_then(|o| o.get(&instance.id).cloned());
+    if inspection.is_none() {
+        if let Err(e) = state.inspect_open_files(instance.id) {
+            tracing::error!("Failed to inspect open files: {}", e);
+        }
+    }
+
+    let filter_id = egui::Id::new(("open_files_filter", instance.id.0));
+    let mut filter: String = ui.data(|d| d.get_temp(filter_id)).unwrap_or_default();
+
+    ui.horizontal(|ui| {
+        let loading = matches!(inspection, None | Some(Inspection::Loading));
+        if ui
+            .add_enabled(!loading, egui::Button::new("Refresh"))
+            .clicked()
+        {
+            if let Err(e) = state.inspect_open_files(instance.id) {
+                tracing::error!("Failed to inspect open files: {}", e);
+            }
+        }
+        ui.add(
+            egui::TextEdit::singleline(&mut filter)
+                .hint_text("Filter by path or name")
+                .desired_width(220.0),
+        );
+        if let Some(Inspection::Ready { taken_at, .. }) = &inspection {
+            ui.label(
+                egui::RichText::new(format!("as of {}", taken_at.format("%H:%M:%S")))
+                    .size(11.0)
+                    .color(Theme::text_muted()),
+            );
+        }
+    });
+    ui.data_mut(|d| d.insert_temp(filter_id, filter.clone()));
+    ui.add_space(8.0);
+
+    let items = match inspection {
+        None | Some(Inspection::Loading) => {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Inspecting handles...");
+            });
+            return;
+        }
+        Some(Inspection::Failed(error)) => {
+            ui.label(egui::RichText::new(error).color(Theme::error()));
+            return;
+        }
+        Some(Inspection::Ready { items, .. }) => items,
+    };
+
+    let filter = filter.to_lowercase();
+    let shown: Vec<&OpenHandle> = items
+        .iter()
+        .filter(|h| filter.is_empty() || h.name.to_lowercase().contains(&filter))
+        .collect();
+    ui.label(
+        egui::RichText::new(format!("{} of {} handles", shown.len(), items.len()))
+            .size(11.0)
+            .color(Theme::text_muted()),
+    );
+    ui.add_space(4.0);
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        egui::Grid::new("open_files_grid")
+            .num_columns(2)
+            .spacing([16.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                for handle in shown {
+                    ui.label(
+                        egui::RichText::new(handle.kind.label())
+                            .size(11.0)
+                            .color(Theme::text_secondary()),
+                    );
+                    ui.label(&handle.name).context_menu(|ui| {
+                        if ui.button("Copy").clicked() {
+                            ui.output_mut(|o| o.copied_text = handle.name.clone());
+                            ui.close_menu();
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+    });
+}
+
+/// How often the connections tab re-lists sockets while it is open
+const CONNECTIONS_REFRESH: chrono::TimeDelta = chrono::TimeDelta::seconds(2);
+
+/// TCP/UDP sockets of the instance, re-listed every couple of seconds while shown
+fn render_connections(ui: &mut egui::Ui, state: &AppState, instance: &Instance) {
+    if instance.pid.is_none() {
+        ui.label(egui::RichText::new("The instance is not running").color(Theme::text_muted()));
+        return;
+    }
+
+    let inspection = fresh_connections(ui, state, instance);
+
+    let hide_id = egui::Id::new(("connections_hide_listening", instance.id.0));
+    let mut hide_listening: bool = ui.data(|d| d.get_temp(hide_id)).unwrap_or(false);
+
+    ui.horizontal(|ui| {
+        if ui.button("Refresh").clicked() {
+            if let Err(e) = state.inspect_connections(instance.id) {
+                tracing::error!("Failed to list connections: {}", e);
+            }
+        }
+        ui.checkbox(&mut hide_listening, "Hide listening");
+        if let Some(Inspection::Ready { taken_at, .. }) = &inspection {
+            ui.label(
+                egui::RichText::new(format!("as of {}", taken_at.format("%H:%M:%S")))
+                    .size(11.0)
+                    .color(Theme::text_muted()),
+            );
+        }
+    });
+    ui.data_mut(|d| d.insert_temp(hide_id, hide_listening));
+    ui.add_space(8.0);
+
+    let items = match inspection {
+        None | Some(Inspection::Loading) => {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Listing connections...");
+            });
+            return;
+        }
+        Some(Inspection::Failed(error)) => {
+            ui.label(egui::RichText::new(error).color(Theme::error()));
+            return;
+        }
+        Some(Inspection::Ready { items, .. }) => items,
+    };
+
+    let shown: Vec<&Connection> = items
+        .iter()
+        .filter(|c| !hide_listening || c.remote.is_some())
+        .collect();
+    if shown.is_empty() {
+        ui.label(egui::RichText::new("No connections").color(Theme::text_muted()));
+        return;
+    }
+
+    let rate = |rate: Option<u64>| {
+        rate.map(|r| format!("{}/s", format_bytes(r)))
+            .unwrap_or_else(|| "-".to_string())
+    };
+    egui::ScrollArea::both().show(ui, |ui| {
+        egui::Grid::new("connections_grid")
+            .num_columns(6)
+            .spacing([16.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                for header in ["Protocol", "Local", "Remote", "State", "In", "Out"] {
+                    ui.label(
+                        egui::RichText::new(header)
+                            .size(11.0)
+                            .color(Theme::text_muted()),
+                    );
+                }
+                ui.end_row();
+
+                for connection in shown {
+                    ui.label(connection.protocol);
+                    ui.label(&connection.local);
+                    ui.label(connection.remote.as_deref().unwrap_or("-"));
+                    ui.label(&connection.state);
+                    ui.label(rate(connection.rx_rate));
+                    ui.label(rate(connection.tx_rate));
+                    ui.end_row();
+                }
+            });
+    });
+}
+
+/// The latest connections snapshot, listing them again once it is stale
+fn fresh_connections(
+    ui: &egui::Ui,
+    state: &AppState,
+    instance: &Instance,
+) -> Option<Inspection<Connection>> {
+    let inspection = state
+        .connections
+        .read()
+        .ok()
+        .and_then(|c| c.get(&instance.id).cloned());
+    let stale = match &inspection {
+        None => true,
+        Some(Inspection::Loading | Inspection::Failed(_)) => false,
+        Some(Inspection::Ready { taken_at, .. }) => {
+            chrono::Local::now() - *taken_at >= CONNECTIONS_REFRESH
+        }
+    };
+    if stale {
+        if let Err(e) = state.inspect_connections(instance.id) {
+            tracing::error!("Failed to list connections: {}", e);
+        }
+    }
+    ui.ctx()
+        .request_repaint_after(CONNECTIONS_REFRESH.to_std().unwrap_or_default());
+    inspection
+}
+
+/// The network limit, whether it is enforced, and current throughput against it
+fn render_network_limit(ui: &mut egui::Ui, state: &AppState, instance: &Instance) {
+    let limit_kbps = instance.config.resource_limits.network_kbps;
+    let status = instance
+        .pid
+        .filter(|_| instance.status.is_active())
+        .and_then(network_limit::status);
+
+    ui.horizontal(|ui| {
+        let label = ui.label(format!("{} KB/s", limit_kbps));
+        if cfg!(windows) {
+            label.on_hover_text(
+                "Windows limits bandwidth by executable name, so the limit would throttle \
+                 every process of this executable, including copies started outside \
+                 MultiInstance. It is lifted while any other copy runs.",
+            );
+        }
+        match &status {
+            None => {}
+            Some((_, LimitStatus::Applying)) => {
+                ui.spinner();
+            }
+            Some((_, LimitStatus::Enforced)) => {
+                // Throughput from per-connection byte counters, where the platform has them
+                let rates = match fresh_connections(ui, state, instance) {
+                    Some(Inspection::Ready { items, .. }) => {
+                        let sum = |rate: fn(&Connection) -> Option<u64>| {
+                            items.iter().filter_map(rate).reduce(|a, b| a + b)
+                        };
+                        sum(|c| c.rx_rate).zip(sum(|c| c.tx_rate))
+                    }
+                    _ => None,
+                };
+                match rates {
+                    Some((rx, tx)) => {
+                        let peak = rx.max(tx);
+                        let color = if peak >= limit_kbps * 1024 * 9 / 10 {
+                            Theme::warning()
+                        } else {
+                            Theme::success()
+                        };
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "\u{2193} {}/s  \u{2191} {}/s",
+                                format_bytes(rx),
+                                format_bytes(tx)
+                            ))
+                            .color(color),
+                        )
+                        .on_hover_text("Current TCP throughput against the limit");
+                    }
+                    None => {
+                        ui.label(egui::RichText::new("enforced").color(Theme::success()))
+                            .on_hover_text("Current throughput is not measured for this instance");
+                    }
+                }
+            }
+            Some((_, LimitStatus::Failed(error))) => {
+                ui.label(egui::RichText::new("not enforced").color(Theme::warning()))
+                    .on_hover_text(error);
+            }
+            Some((_, LimitStatus::Shared(_))) => {
+                ui.label(egui::RichText::new("not enforced").color(Theme::warning()));
+            }
+        }
+    });
+    if let Some((_, LimitStatus::Shared(count))) = &status {
+        ui.label(
+            egui::RichText::new(format!(
+                "{} other process(es) run this executable. Windows limits bandwidth by \
+                 executable name, so the limit would throttle them too.",
+                count
+            ))
+            .small()
+            .color(Theme::text_muted()),
+        );
+    }
+}
+
+/// Launch-time environment compared with MultiInstance's environment now
+/// Lines the Logs tab shows at most
+const LOG_TAIL_LINES: usize = 5000;
+
+/// How often the Logs tab re-reads the log
+const LOG_REFRESH: Duration = Duration::from_secs(1);
+
+fn render_logs(ui: &mut egui::Ui, state: &AppState, instance: &Instance) {
+    let path = state.output_log_path(instance.id);
+    if !instance.config.capture_output && !path.exists() {
+        ui.label(
+            egui::RichText::new(
+                "Nothing logged yet. Starts, stops and crashes are logged here; turn on \
+                 \"Capture output\" in the instance settings to record stdout and stderr too.",
+            )
+            .color(Theme::text_muted()),
+        );
+        return;
+    }
+
+    let cache_id = egui::Id::new(("instance_logs", instance.id.0));
+    let search_id = egui::Id::new(("instance_logs_search", instance.id.0));
+    let follow_id = egui::Id::new(("instance_logs_follow", instance.id.0));
+    let errors_id = egui::Id::new(("instance_logs_errors", instance.id.0));
+    let mut search: String = ui.data(|d| d.get_temp(search_id)).unwrap_or_default();
+    let mut follow: bool = ui.data(|d| d.get_temp(follow_id)).unwrap_or(true);
+    let mut errors_only: bool = ui.data(|d| d.get_temp(errors_id)).unwrap_or(false);
+    let mut cleared = false;
+
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(&mut search)
+                .hint_text("Search output")
+                .desired_width(200.0),
+        );
+        ui.checkbox(&mut errors_only, "stderr only");
+        ui.checkbox(&mut follow, "Follow");
+        if ui.button("Clear").clicked() {
+            match output_log::clear(&path) {
+                Ok(()) => cleared = true,
+                Err(e) => tracing::error!("Failed to clear output log: {}", e),
+            }
+        }
+        if ui.button("Open Folder").clicked() {
+            if let Some(dir) = path.parent() {
+                let _ = open::that(dir);
+            }
+        }
+    });
+    ui.data_mut(|d| {
+        d.insert_temp(search_id, search.clone());
+        d.insert_temp(follow_id, follow);
+        d.insert_temp(errors_id, errors_only);
+    });
+    ui.separator();
+
+    // Re-read the tail at most once a second while the dialog is open
+    let cached: Option<(Instant, Arc<Vec<String>>)> = ui.data(|d| d.get_temp(cache_id));
+    let lines = match cached {
+        Some((read_at, lines)) if !cleared && read_at.elapsed() < LOG_REFRESH => lines,
+        _ => {
+            let lines = Arc::new(output_log::read_tail(&path, LOG_TAIL_LINES).unwrap_or_default());
+            ui.data_mut(|d| d.insert_temp(cache_id, (Instant::now(), Arc::clone(&lines))));
+            lines
+        }
+    };
+    if instance.status.is_active() {
+        ui.ctx().request_repaint_after(LOG_REFRESH);
+    }
+
+    let query = search.trim().to_lowercase();
+    let shown: Vec<&String> = lines
+        .iter()
+        .filter(|line| !errors_only || line.contains(output_log::STDERR_MARKER))
+        .filter(|line| query.is_empty() || line.to_lowercase().contains(&query))
+        .collect();
+
+    if shown.is_empty() {
+        let message = if lines.is_empty() {
+            "No output yet"
+        } else {
+            "No lines match"
+        };
+        ui.label(egui::RichText::new(message).color(Theme::text_muted()));
+        return;
+    }
+
+    let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+    egui::ScrollArea::both()
+        .max_height(420.0)
+        .auto_shrink([false, true])
+        .stick_to_bottom(follow)
+        .show_rows(ui, row_height, shown.len(), |ui, range| {
+            for line in &shown[range] {
+                let color = if line.contains(output_log::STDERR_MARKER) {
+                    Theme::error()
+                } else if line.contains(output_log::LAUNCH_MARKER)
+                    || line.contains(output_log::EVENT_MARKER)
+                {
+                    Theme::primary_light()
+                } else {
+                    Theme::text_secondary()
+                };
+                ui.add(
+                    egui::Label::new(egui::RichText::new(*line).monospace().color(color)).extend(),
+                );
+            }
+        });
+    ui.label(
+        egui::RichText::new(format!(
+            "{} of {} lines · {}",
+            shown.len(),
+            lines.len(),
+            path.display()
+        ))
+        .size(11.0)
+        .color(Theme::text_muted()),
+    );
+}
+
+fn render_environment(ui: &mut egui::Ui, instance: &Instance) {
+    if instance.spawn_environment.is_empty() {
+        ui.label(
+            egui::RichText::new(
+                "The environment is recorded when MultiInstance launches the instance. \
+                 Start it from here to see it.",
+            )
+            .color(Theme::text_muted()),
+        );
+        return;
+    }
+
+    let rows = environment::diff(&instance.spawn_environment);
+
+    let changes_id = egui::Id::new(("environment_changes_only", instance.id.0));
+    let filter_id = egui::Id::new(("environment_filter", instance.id.0));
+    let mut changes_only: bool = ui.data(|d| d.get_temp(changes_id)).unwrap_or(true);
+    let mut filter: String = ui.data(|d| d.get_temp(filter_id)).unwrap_or_default();
+
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut changes_only, "Only differences and overrides");
+        ui.add(
+            egui::TextEdit::singleline(&mut filter)
+                .hint_text("Filter variables")
+                .desired_width(180.0),
+        );
+    });
+    ui.data_mut(|d| {
+        d.insert_temp(changes_id, changes_only);
+        d.insert_temp(filter_id, filter.clone());
+    });
+
+    let stale = rows
+        .iter()
+        .filter(|r| {
+            matches!(
+                r.change,
+                EnvChange::Changed | EnvChange::Added | EnvChange::Removed
+            )
+        })
+        .count();
+    ui.label(
+        egui::RichText::new(if stale == 0 {
+            "The instance sees the same inherited variables MultiInstance has now.".to_string()
+        } else {
+            format!(
+                "{} variables changed in MultiInstance's environment since launch. \
+                 Restart the instance to pick them up; changes made outside MultiInstance \
+                 also need MultiInstance itself restarted.",
+                stale
+            )
+        })
+        .size(11.0)
+        .color(Theme::text_muted()),
+    );
+    ui.add_space(8.0);
+
+    let filter = filter.to_lowercase();
+    let value_text = |value: Option<&str>| {
+        let value = value.unwrap_or("(not set)");
+        let mut text: String = value.chars().take(120).collect();
+        if value.chars().count() > 120 {
+            text.push('…');
+        }
+        text
+    };
+
+    egui::ScrollArea::both().show(ui, |ui| {
+        egui::Grid::new("environment_grid")
+            .num_columns(4)
+            .spacing([16.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                for header in ["Variable", "At launch", "Now", ""] {
+                    ui.label(
+                        egui::RichText::new(header)
+                            .size(11.0)
+                            .color(Theme::text_muted()),
+                    );
+                }
+                ui.end_row();
+
+                for row in rows.iter().filter(|r| {
+                    (!changes_only || r.change != EnvChange::Unchanged)
+                        && (filter.is_empty() || r.key.to_lowercase().contains(&filter))
+                }) {
+                    let spawned = row.spawned.as_ref().map(|v| v.value.as_str());
+                    let (badge, color) = match row.change {
+                        EnvChange::Unchanged => (String::new(), Theme::text_muted()),
+                        EnvChange::Overridden => (
+                            row.spawned
+                                .as_ref()
+                                .map(|v| v.source)
+                                .unwrap_or(EnvSource::Custom)
+                                .label()
+                                .to_string(),
+                            Theme::primary_light(),
+                        ),
+                        EnvChange::Changed => ("Changed".to_string(), Theme::warning()),
+                        EnvChange::Added => ("New".to_string(), Theme::warning()),
+                        EnvChange::Removed => ("Removed".to_string(), Theme::warning()),
+                    };
+
+                    ui.label(egui::RichText::new(&row.key).strong());
+                    ui.label(value_text(spawned))
+                        .on_hover_text(spawned.unwrap_or("(not set)"));
+                    ui.label(value_text(row.current.as_deref()))
+                        .on_hover_text(row.current.as_deref().unwrap_or("(not set)"));
+                    ui.label(egui::RichText::new(badge).size(11.0).color(color));
+                    ui.end_row();
+                }
+            });
+    });
+}
@@ -0,0 +1,165 @@
+//! Group management dialog - Add, rename, merge and remove instance groups
+
+use std::collections::HashMap;
+
+use egui::Context;
+
+use crate::core::AppState;
+use crate::ui::dialogs::DialogState;
+use crate::ui::theme::Theme;
+
+/// Change picked in the dialog this frame
+enum GroupChange {
+    Rename { from: String, to: String },
+    Merge { from: String, into: String },
+    Remove(String),
+    Add(String),
+}
+
+pub fn render(ctx: &Context, state: &AppState, dialog: &mut DialogState) {
+    let groups = state.group_names();
+    let mut members: HashMap<String, usize> = HashMap::new();
+    if let Ok(instances) = state.instances.read() {
+        for group in instances.values().filter_map(|i| i.config.group.clone()) {
+            *members.entry(group).or_default() += 1;
+        }
+    }
+
+    let rename_id = egui::Id::new("manage_groups_rename");
+    let new_group_id = egui::Id::new("manage_groups_new");
+    let mut renaming: Option<(String, String)> = ctx.data(|d| d.get_temp(rename_id));
+    let mut new_group: String = ctx.data(|d| d.get_temp(new_group_id)).unwrap_or_default();
+
+    let mut open = true;
+    let mut change = None;
+
+    egui::Window::new("Groups")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(460.0)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            if groups.is_empty() {
+                ui.label(egui::RichText::new("No groups yet").color(Theme::text_muted()));
+            }
+
+            for group in &groups {
+                let count = members.get(group).copied().unwrap_or(0);
+                egui::Frame::none()
+                    .fill(Theme::bg_secondary())
+                    .rounding(egui::Rounding::same(8.0))
+                    .inner_margin(egui::Margin::symmetric(12.0, 8.0))
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            match renaming.as_mut().filter(|(from, _)| from == group) {
+                                Some((_, to)) => {
+                                    let edit =
+                                        ui.add(egui::TextEdit::singleline(to).desired_width(180.0));
+                                    edit.request_focus();
+                                    if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                                        renaming = None;
+                                    } else if edit.lost_focus() || ui.button("Save").clicked() {
+                                        change = Some(GroupChange::Rename {
+                                            from: group.clone(),
+                                            to: to.clone(),
+                                        });
+                                    }
+                                }
+                                None => {
+                                    ui.label(
+                                        egui::RichText::new(group)
+                                            .strong()
+                                            .color(Theme::group_color(group)),
+                                    );
+                                    ui.label(
+                                        egui::RichText::new(format!("{} instance(s)", count))
+                                            .size(11.0)
+                                            .color(Theme::text_muted()),
+                                    );
+                                }
+                            }
+
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    if count == 0
+                                        && ui
+                                            .button(
+                                                egui::RichText::new("Remove").color(Theme::error()),
+                                            )
+                                            .clicked()
+                                    {
+                                        change = Some(GroupChange::Remove(group.clone()));
+                                    }
+                                    if groups.len() > 1 {
+                                        ui.menu_button("Merge into", |ui| {
+                                            for into in groups.iter().filter(|g| *g != group) {
+                                                if ui.button(into).clicked() {
+                                                    change = Some(GroupChange::Merge {
+                                                        from: group.clone(),
+                                                        into: into.clone(),
+                                                    });
+                                                    ui.close_menu();
+                                                }
+                                            }
+                                        });
+                                    }
+                                    if ui.button("Rename").clicked() {
+                                        renaming = Some((group.clone(), group.clone()));
+                                    }
+                                },
+                            );
+                        });
+                    });
+                ui.add_space(4.0);
+            }
+
+            ui.add_space(12.0);
+            ui.horizontal(|ui| {
+                let edit = ui.add(
+                    egui::TextEdit::singleline(&mut new_group)
+                        .hint_text("New group")
+                        .desired_width(220.0),
+                );
+                let submitted = edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                if (ui.button("Add").clicked() || submitted) && !new_group.trim().is_empty() {
+                    change = Some(GroupChange::Add(new_group.trim().to_string()));
+                }
+            });
+        });
+
+    let result = match change {
+        Some(GroupChange::Rename { from, to }) => {
+            renaming = None;
+            if from == to.trim() {
+                Ok(())
+            } else {
+                state.rename_group(&from, &to)
+            }
+        }
+        Some(GroupChange::Merge { from, into }) => state.merge_group(&from, &into),
+        Some(GroupChange::Remove(group)) => state.remove_group(&group),
+        Some(GroupChange::Add(group)) => {
+            new_group.clear();
+            state.add_group(group)
+        }
+        None => Ok(()),
+    };
+    if let Err(e) = result {
+        tracing::error!("Failed to update groups: {}", e);
+    }
+
+    if !open {
+        *dialog = DialogState::None;
+        renaming = None;
+        new_group.clear();
+    }
+    ctx.data_mut(|d| {
+        match renaming {
+            Some(renaming) => d.insert_temp(rename_id, renaming),
+            None => d.remove::<(String, String)>(rename_id),
+        }
+        d.insert_temp(new_group_id, new_group);
+    });
+}
@@ -0,0 +1,403 @@
+//! Migration wizard - Export everything to another machine, or import it here
+
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+
+use egui::Context;
+
+use crate::core::migration::{
+    self, ImportOptions, ImportSummary, MigrationPackage, PathRewrite, MIGRATION_EXTENSION,
+};
+use crate::core::AppState;
+use crate::ui::theme::Theme;
+
+/// Where the wizard is; the slow steps run on a background thread
+enum Step {
+    Export,
+    Exporting(Receiver<anyhow::Result<()>>),
+    Unlock,
+    Opening(Receiver<anyhow::Result<MigrationPackage>>),
+    Review(Box<MigrationPackage>),
+    Importing(Receiver<anyhow::Result<ImportSummary>>),
+    Done(String),
+}
+
+/// The open migration wizard and what has been entered in it
+pub struct MigrationWizard {
+    step: Step,
+    passphrase: String,
+    confirm: String,
+    include_data: bool,
+    archive: Option<PathBuf>,
+    rewrites: Vec<PathRewrite>,
+    replace_settings: bool,
+    import_data: bool,
+    error: Option<String>,
+}
+
+impl MigrationWizard {
+    fn new(step: Step) -> Self {
+        Self {
+            step,
+            passphrase: String::new(),
+            confirm: String::new(),
+            include_data: true,
+            archive: None,
+            rewrites: Vec::new(),
+            replace_settings: true,
+            import_data: true,
+            error: None,
+        }
+    }
+
+    pub fn export() -> Self {
+        Self::new(Step::Export)
+    }
+
+    pub fn import() -> Self {
+        Self::new(Step::Unlock)
+    }
+}
+
+/// Render the wizard; it is closed by setting `wizard` to None
+pub fn render(ctx: &Context, state: &AppState, wizard: &mut Option<MigrationWizard>) {
+    let Some(w) = wizard.as_mut() else {
+        return;
+    };
+    poll(ctx, state, w);
+
+    let title = match w.step {
+        Step::Export | Step::Exporting(_) => "Migrate to Another Machine",
+        _ => "Import from Another Machine",
+    };
+    let mut open = true;
+    let mut close = false;
+
+    egui::Window::new(title)
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(520.0)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            match w.step {
+                Step::Export => render_export(ui, state, w),
+                Step::Unlock => render_unlock(ui, w),
+                Step::Review(_) => render_review(ui, state, w),
+                Step::Exporting(_) => working(ui, "Writing the encrypted archive..."),
+                Step::Opening(_) => working(ui, "Decrypting and unpacking the archive..."),
+                Step::Importing(_) => working(ui, "Importing instances and their data..."),
+                Step::Done(ref message) => {
+                    ui.label(egui::RichText::new(message).color(Theme::success()));
+                    ui.add_space(12.0);
+                    if ui.button("Close").clicked() {
+                        close = true;
+                    }
+                }
+            }
+            if let Some(ref error) = w.error {
+                ui.add_space(8.0);
+                ui.label(egui::RichText::new(error).color(Theme::error()));
+            }
+        });
+
+    let busy = matches!(
+        w.step,
+        Step::Exporting(_) | Step::Opening(_) | Step::Importing(_)
+    );
+    // A running job finishes in the background; its result is just not shown
+    if close || (!open && !busy) {
+        *wizard = None;
+    }
+}
+
+/// Move on when a background step has finished
+fn poll(ctx: &Context, state: &AppState, w: &mut MigrationWizard) {
+    let finished = match &w.step {
+        Step::Exporting(rx) => match rx.try_recv() {
+            Ok(result) => Some(result.map(|()| {
+                Step::Done("Archive written. Copy it to the new machine and import it from Settings there.".to_string())
+            })),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(anyhow::anyhow!("Export stopped"))),
+        },
+        Step::Opening(rx) => match rx.try_recv() {
+            Ok(result) => Some(result.map(|package| Step::Review(Box::new(package)))),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(anyhow::anyhow!("Import stopped"))),
+        },
+        Step::Importing(rx) => match rx.try_recv() {
+            Ok(result) => Some(result.map(|summary| Step::Done(summary.describe()))),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(anyhow::anyhow!("Import stopped"))),
+        },
+        _ => return,
+    };
+
+    match finished {
+        None => ctx.request_repaint_after(std::time::Duration::from_millis(200)),
+        Some(Ok(step)) => {
+            if let Step::Review(ref package) = step {
+                let instances_directory = state
+                    .settings
+                    .read()
+                    .map(|s| s.get_instances_directory())
+                    .unwrap_or_default();
+                w.rewrites = package.default_rewrites(&instances_directory);
+                w.import_data = package.manifest.includes_data;
+            }
+            w.step = step;
+        }
+        Some(Err(e)) => {
+            w.error = Some(e.to_string());
+            w.step = match w.step {
+                Step::Exporting(_) => Step::Export,
+                _ => Step::Unlock,
+            };
+        }
+    }
+}
+
+fn working(ui: &mut egui::Ui, text: &str) {
+    ui.horizontal(|ui| {
+        ui.spinner();
+        ui.label(text);
+    });
+}
+
+fn passphrase_field(ui: &mut egui::Ui, label: &str, value: &mut String) {
+    ui.horizontal(|ui| {
+        ui.add_sized([110.0, 20.0], egui::Label::new(label));
+        ui.add(
+            egui::TextEdit::singleline(value)
+                .password(true)
+                .desired_width(260.0),
+        );
+    });
+}
+
+fn render_export(ui: &mut egui::Ui, state: &AppState, w: &mut MigrationWizard) {
+    ui.label(
+        "Write instances, profiles, schedules, quick launch items, groups, settings and \
+         history to one archive, encrypted with a passphrase, to import on the new machine.",
+    );
+    ui.add_space(12.0);
+    passphrase_field(ui, "Passphrase", &mut w.passphrase);
+    passphrase_field(ui, "Confirm", &mut w.confirm);
+    ui.add_space(8.0);
+    ui.checkbox(&mut w.include_data, "Include instance data directories");
+    ui.label(
+        egui::RichText::new("Data holds saved logins and can be large. Stop instances first so it is copied in a consistent state.")
+            .size(11.0)
+            .color(Theme::text_muted()),
+    );
+    if state.active_instance_count() > 0 && w.include_data {
+        ui.label(
+            egui::RichText::new(format!(
+                "{} instances are running",
+                state.active_instance_count()
+            ))
+            .size(11.0)
+            .color(Theme::warning()),
+        );
+    }
+    ui.add_space(12.0);
+
+    let ready = !w.passphrase.is_empty() && w.passphrase == w.confirm;
+    let clicked = ui
+        .add_enabled(
+            ready,
+            egui::Button::new(egui::RichText::new("Export...").color(egui::Color32::WHITE))
+                .fill(Theme::primary())
+                .min_size(egui::vec2(120.0, 32.0)),
+        )
+        .on_disabled_hover_text("Enter the same passphrase twice")
+        .clicked();
+    if !clicked {
+        return;
+    }
+    let Some(destination) = rfd::FileDialog::new()
+        .add_filter("Migration archive", &[MIGRATION_EXTENSION])
+        .set_file_name(format!("multiinstance.{}", MIGRATION_EXTENSION))
+        .save_file()
+    else {
+        return;
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let state = state.clone();
+    let passphrase = w.passphrase.clone();
+    let include_data = w.include_data;
+    std::thread::spawn(move || {
+        let _ = tx.send(state.export_migration(&destination, &passphrase, include_data));
+    });
+    w.error = None;
+    w.step = Step::Exporting(rx);
+}
+
+fn render_unlock(ui: &mut egui::Ui, w: &mut MigrationWizard) {
+    ui.label("Import an archive written by \"Migrate to Another Machine\" on the old machine.");
+    ui.add_space(12.0);
+    ui.horizontal(|ui| {
+        ui.add_sized([110.0, 20.0], egui::Label::new("Archive"));
+        let name = w
+            .archive
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|| "None chosen".to_string());
+        ui.label(egui::RichText::new(name).color(Theme::text_secondary()));
+        if ui.button("Choose...").clicked() {
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("Migration archive", &[MIGRATION_EXTENSION])
+                .pick_file()
+            {
+                w.archive = Some(path);
+            }
+        }
+    });
+    passphrase_field(ui, "Passphrase", &mut w.passphrase);
+    ui.add_space(12.0);
+
+    let ready = w.archive.is_some() && !w.passphrase.is_empty();
+    if !ui
+        .add_enabled(
+            ready,
+            egui::Button::new("Open").min_size(egui::vec2(120.0, 32.0)),
+        )
+        .clicked()
+    {
+        return;
+    }
+    let Some(archive) = w.archive.clone() else {
+        return;
+    };
+    let (tx, rx) = mpsc::channel();
+    let passphrase = w.passphrase.clone();
+    std::thread::spawn(move || {
+        let _ = tx.send(migration::open(&archive, &passphrase));
+    });
+    w.error = None;
+    w.step = Step::Opening(rx);
+}
+
+fn render_review(ui: &mut egui::Ui, state: &AppState, w: &mut MigrationWizard) {
+    let Step::Review(ref package) = w.step else {
+        return;
+    };
+    let manifest = &package.manifest;
+    ui.label(format!(
+        "Exported from {} on {}",
+        manifest.host,
+        manifest
+            .exported_at
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M")
+    ));
+    ui.label(
+        egui::RichText::new(format!(
+            "{} instances, {} profiles, {} schedules, {} quick launch items",
+            package.instances.len(),
+            package.profiles.len(),
+            package.schedules.len(),
+            package.quick_launch.len()
+        ))
+        .color(Theme::text_secondary()),
+    );
+    ui.add_space(12.0);
+
+    ui.label(egui::RichText::new("Path rewrites").strong());
+    ui.label(
+        egui::RichText::new("Paths starting with the old prefix are moved to the new one")
+            .size(11.0)
+            .color(Theme::text_muted()),
+    );
+    let mut remove = None;
+    egui::Grid::new("migration_rewrites")
+        .num_columns(4)
+        .spacing([6.0, 4.0])
+        .show(ui, |ui| {
+            for (index, rewrite) in w.rewrites.iter_mut().enumerate() {
+                ui.add(egui::TextEdit::singleline(&mut rewrite.from).desired_width(190.0));
+                ui.label("→");
+                ui.add(egui::TextEdit::singleline(&mut rewrite.to).desired_width(190.0));
+                if ui.small_button("✕").clicked() {
+                    remove = Some(index);
+                }
+                ui.end_row();
+            }
+        });
+    if let Some(index) = remove {
+        w.rewrites.remove(index);
+    }
+    if ui.small_button("+ Add rewrite").clicked() {
+        w.rewrites.push(PathRewrite {
+            from: String::new(),
+            to: String::new(),
+        });
+    }
+
+    let missing = package.missing_executables(&w.rewrites);
+    if !missing.is_empty() {
+        ui.add_space(8.0);
+        ui.label(
+            egui::RichText::new(format!(
+                "{} executables are not on this machine; add a rewrite or install them",
+                missing.len()
+            ))
+            .color(Theme::warning()),
+        );
+        egui::ScrollArea::vertical()
+            .max_height(100.0)
+            .show(ui, |ui| {
+                for (name, path) in &missing {
+                    ui.label(
+                        egui::RichText::new(format!("{}: {}", name, path.to_string_lossy()))
+                            .size(11.0)
+                            .color(Theme::text_muted()),
+                    );
+                }
+            });
+    }
+
+    ui.add_space(8.0);
+    ui.checkbox(
+        &mut w.replace_settings,
+        "Replace settings (the PIN lock and data directory stay as they are)",
+    );
+    ui.add_enabled_ui(manifest.includes_data, |ui| {
+        ui.checkbox(&mut w.import_data, "Copy instance data directories");
+    });
+    ui.add_space(12.0);
+
+    let import = ui
+        .add(
+            egui::Button::new(egui::RichText::new("Import").color(egui::Color32::WHITE))
+                .fill(Theme::primary())
+                .min_size(egui::vec2(120.0, 32.0)),
+        )
+        .clicked();
+    if !import {
+        return;
+    }
+
+    let options = ImportOptions {
+        rewrites: w
+            .rewrites
+            .iter()
+            .filter(|r| !r.from.trim().is_empty())
+            .cloned()
+            .collect(),
+        settings: w.replace_settings,
+        data: w.import_data,
+    };
+    let Step::Review(package) = std::mem::replace(&mut w.step, Step::Unlock) else {
+        return;
+    };
+    let (tx, rx) = mpsc::channel();
+    let state = state.clone();
+    std::thread::spawn(move || {
+        let _ = tx.send(state.import_migration(&package, &options));
+    });
+    w.error = None;
+    w.step = Step::Importing(rx);
+}
@@ -4,12 +4,47 @@ pub mod confirm;
 pub mod edit_instance;
 pub mod edit_profile;
 pub mod instance_details;
+pub mod manage_groups;
+pub mod migration;
 pub mod new_instance;
 pub mod new_profile;
+pub mod orphans;
+pub mod partial_launch;
+pub mod profile_template;
+pub mod recovery;
+pub mod session_restore;
+pub mod shortcuts;
+pub mod unlock;
 
+use crate::core::architecture::{ArchitectureInfo, LaunchArchitecture};
 use crate::core::{InstanceId, ProfileId};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+/// Architecture of an executable, inspected again only when the path or the
+/// forced launch architecture changes rather than every frame
+pub(crate) fn architecture_info(
+    ctx: &egui::Context,
+    id_salt: &str,
+    executable: &Path,
+    launch: LaunchArchitecture,
+) -> Option<ArchitectureInfo> {
+    type Inspected = (PathBuf, LaunchArchitecture, Option<ArchitectureInfo>);
+
+    let id = egui::Id::new((id_salt, "architecture_info"));
+    if let Some((_, _, info)) = ctx
+        .data(|d| d.get_temp::<Inspected>(id))
+        .filter(|(path, forced, _)| path == executable && *forced == launch)
+    {
+        return info;
+    }
+    let info = ArchitectureInfo::inspect(executable, launch);
+    ctx.data_mut(|d| {
+        d.insert_temp::<Inspected>(id, (executable.to_path_buf(), launch, info.clone()))
+    });
+    info
+}
+
 /// State for dialog windows
 #[derive(Default)]
 pub enum DialogState {
@@ -18,8 +53,36 @@ pub enum DialogState {
     NewInstance,
     EditInstance(InstanceId),
     NewProfile,
+    /// Create a profile from one of the built-in templates
+    NewFromTemplate {
+        /// Index into the template list
+        template: usize,
+        name: String,
+        executable: String,
+        count: usize,
+    },
     EditProfile(ProfileId),
     InstanceDetails(InstanceId),
+    /// Add, rename, merge and remove groups
+    ManageGroups,
+    Orphans,
+    /// Pick which members of a profile to launch
+    PartialLaunch {
+        profile: ProfileId,
+        /// Per profile member, whether to launch it
+        selected: Vec<bool>,
+    },
+    /// Summary of instances from a run that ended unexpectedly
+    Recovery,
+    /// Pick which instances of the previous session to relaunch
+    SessionRestore {
+        /// Per pending session entry, whether to restore it
+        selected: Vec<bool>,
+        /// Skip this prompt next time
+        remember: bool,
+    },
+    /// Cheat sheet of the in-app keyboard shortcuts
+    Shortcuts,
     Confirm {
         title: String,
         message: String,
@@ -27,6 +90,18 @@ pub enum DialogState {
     },
 }
 
+impl DialogState {
+    /// Template dialog with the first template chosen
+    pub fn new_from_template() -> Self {
+        Self::NewFromTemplate {
+            template: 0,
+            name: String::new(),
+            executable: String::new(),
+            count: crate::core::profile_template::TEMPLATES[0].count,
+        }
+    }
+}
+
 impl Clone for DialogState {
     fn clone(&self) -> Self {
         match self {
@@ -34,8 +109,31 @@ impl Clone for DialogState {
             Self::NewInstance => Self::NewInstance,
             Self::EditInstance(id) => Self::EditInstance(*id),
             Self::NewProfile => Self::NewProfile,
+            Self::NewFromTemplate {
+                template,
+                name,
+                executable,
+                count,
+            } => Self::NewFromTemplate {
+                template: *template,
+                name: name.clone(),
+                executable: executable.clone(),
+                count: *count,
+            },
             Self::EditProfile(id) => Self::EditProfile(*id),
             Self::InstanceDetails(id) => Self::InstanceDetails(*id),
+            Self::ManageGroups => Self::ManageGroups,
+            Self::Orphans => Self::Orphans,
+            Self::PartialLaunch { profile, selected } => Self::PartialLaunch {
+                profile: *profile,
+                selected: selected.clone(),
+            },
+            Self::Recovery => Self::Recovery,
+            Self::SessionRestore { selected, remember } => Self::SessionRestore {
+                selected: selected.clone(),
+                remember: *remember,
+            },
+            Self::Shortcuts => Self::Shortcuts,
             Self::Confirm {
                 title,
                 message,
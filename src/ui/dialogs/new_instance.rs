@@ -1,10 +1,24 @@
 //! New instance dialog
 
+use chrono::{NaiveTime, Timelike};
 use egui::{Color32, Context};
 
-use crate::core::{AppState, InstanceConfig};
+use crate::core::app_container::{AppContainerCapability, IsolationLevel};
+use crate::core::architecture::LaunchArchitecture;
+use crate::core::cache_links;
+use crate::core::environment;
+use crate::core::known_issues;
+use crate::core::limit_schedule::LimitWindow;
+use crate::core::network_condition::NetworkCondition;
+use crate::core::packaged_apps;
+use crate::core::scheduler::DAY_NAMES;
+use crate::core::virtual_desktop;
+use crate::core::{
+    AppState, InstanceConfig, IoPriority, ResourceLimits, WindowState, MIN_MONITOR_INTERVAL_MS,
+};
 use crate::ui::app::{Notification, NotificationLevel};
-use crate::ui::dialogs::DialogState;
+use crate::ui::components::{AffinityPicker, ExecutableIcon, LimitPresetPicker};
+use crate::ui::dialogs::{architecture_info, DialogState};
 use crate::ui::theme::Theme;
 
 /// Helper to render a form field with label and input
@@ -14,7 +28,7 @@ fn form_field(ui: &mut egui::Ui, label: &str, add_input: impl FnOnce(&mut egui::
         ui.label(
             egui::RichText::new(label)
                 .size(13.0)
-                .color(Theme::TEXT_SECONDARY),
+                .color(Theme::text_secondary()),
         );
         ui.add_space(8.0);
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
@@ -32,14 +46,14 @@ fn section_header(ui: &mut egui::Ui, icon: &str, title: &str) {
         ui.label(
             egui::RichText::new(icon)
                 .size(16.0)
-                .color(Theme::PRIMARY_LIGHT),
+                .color(Theme::primary_light()),
         );
         ui.add_space(8.0);
         ui.label(
             egui::RichText::new(title)
                 .size(15.0)
                 .strong()
-                .color(Color32::WHITE),
+                .color(Theme::text_primary()),
         );
     });
     ui.add_space(12.0);
@@ -56,6 +70,17 @@ pub fn render(
         *dialog = DialogState::None;
         return;
     };
+    let (presets, known_issues, metadata_fields) = state
+        .settings
+        .read()
+        .map(|s| {
+            (
+                s.limit_presets.clone(),
+                s.known_issues.clone(),
+                s.metadata_fields.clone(),
+            )
+        })
+        .unwrap_or_default();
 
     let mut open = true;
 
@@ -67,9 +92,9 @@ pub fn render(
         .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
         .frame(
             egui::Frame::window(&ctx.style())
-                .fill(Theme::BG_ELEVATED)
+                .fill(Theme::bg_elevated())
                 .rounding(egui::Rounding::same(12.0))
-                .stroke(egui::Stroke::new(1.0, Theme::BORDER))
+                .stroke(egui::Stroke::new(1.0, Theme::border()))
                 .inner_margin(egui::Margin::same(24.0)),
         )
         .show(ctx, |ui| {
@@ -79,14 +104,14 @@ pub fn render(
                     egui::RichText::new("Create New Instance")
                         .size(20.0)
                         .strong()
-                        .color(Color32::WHITE),
+                        .color(Theme::text_primary()),
                 );
             });
             ui.add_space(6.0);
             ui.label(
                 egui::RichText::new("Configure and launch a new application instance")
                     .size(13.0)
-                    .color(Theme::TEXT_MUTED),
+                    .color(Theme::text_muted()),
             );
             ui.add_space(20.0);
 
@@ -97,7 +122,7 @@ pub fn render(
                     section_header(ui, "◈", "Basic Information");
 
                     egui::Frame::none()
-                        .fill(Theme::BG_SECONDARY)
+                        .fill(Theme::bg_secondary())
                         .rounding(egui::Rounding::same(10.0))
                         .inner_margin(egui::Margin::same(16.0))
                         .show(ui, |ui| {
@@ -105,7 +130,7 @@ pub fn render(
                             ui.label(
                                 egui::RichText::new("Instance Name")
                                     .size(12.0)
-                                    .color(Theme::TEXT_MUTED),
+                                    .color(Theme::text_muted()),
                             );
                             ui.add_space(4.0);
                             ui.add(
@@ -120,17 +145,27 @@ pub fn render(
                             ui.label(
                                 egui::RichText::new("Executable Path")
                                     .size(12.0)
-                                    .color(Theme::TEXT_MUTED),
+                                    .color(Theme::text_muted()),
                             );
                             ui.add_space(4.0);
                             ui.horizontal(|ui| {
+                                // Only ask for icons of paths that exist, not every prefix typed
+                                if let Some(icon) = Some(&config.executable_path)
+                                    .filter(|path| path.exists())
+                                    .and_then(|path| state.executable_icon(path))
+                                {
+                                    ExecutableIcon::show(ui, &config.executable_path, &icon, 24.0);
+                                }
                                 let path_str = config.executable_path.to_string_lossy().to_string();
                                 let mut path_edit = path_str.clone();
                                 if ui
                                     .add(
                                         egui::TextEdit::singleline(&mut path_edit)
                                             .hint_text("Path to executable")
-                                            .desired_width(ui.available_width() - 90.0),
+                                            .desired_width(
+                                                ui.available_width()
+                                                    - if cfg!(windows) { 190.0 } else { 90.0 },
+                                            ),
                                     )
                                     .changed()
                                 {
@@ -138,7 +173,7 @@ pub fn render(
                                 }
 
                                 let browse_btn = egui::Button::new("Browse...")
-                                    .fill(Theme::BG_TERTIARY)
+                                    .fill(Theme::bg_tertiary())
                                     .rounding(egui::Rounding::same(6.0));
                                 if ui.add(browse_btn).clicked() {
                                     if let Some(path) = rfd::FileDialog::new()
@@ -155,15 +190,29 @@ pub fn render(
                                         }
                                     }
                                 }
+                                if cfg!(windows) {
+                                    render_store_apps(ui, config);
+                                }
                             });
 
+                            // Known issues with running this executable more than once
+                            for hint in known_issues::hints_for(&known_issues, &config.executable_path)
+                            {
+                                ui.add_space(6.0);
+                                ui.label(
+                                    egui::RichText::new(format!("\u{1F4A1} {}", hint))
+                                        .size(12.0)
+                                        .color(Theme::warning()),
+                                );
+                            }
+
                             ui.add_space(16.0);
 
                             // Arguments
                             ui.label(
                                 egui::RichText::new("Arguments")
                                     .size(12.0)
-                                    .color(Theme::TEXT_MUTED),
+                                    .color(Theme::text_muted()),
                             );
                             ui.add_space(4.0);
                             let mut args_str = config.arguments.join(" ");
@@ -185,7 +234,7 @@ pub fn render(
                             ui.label(
                                 egui::RichText::new("Working Directory")
                                     .size(12.0)
-                                    .color(Theme::TEXT_MUTED),
+                                    .color(Theme::text_muted()),
                             );
                             ui.add_space(4.0);
                             ui.horizontal(|ui| {
@@ -209,7 +258,7 @@ pub fn render(
                                     };
                                 }
                                 let browse_btn = egui::Button::new("Browse...")
-                                    .fill(Theme::BG_TERTIARY)
+                                    .fill(Theme::bg_tertiary())
                                     .rounding(egui::Rounding::same(6.0));
                                 if ui.add(browse_btn).clicked() {
                                     if let Some(path) = rfd::FileDialog::new().pick_folder() {
@@ -223,7 +272,7 @@ pub fn render(
                     section_header(ui, "🔒", "Instance Isolation");
 
                     egui::Frame::none()
-                        .fill(Theme::BG_SECONDARY)
+                        .fill(Theme::bg_secondary())
                         .rounding(egui::Rounding::same(10.0))
                         .inner_margin(egui::Margin::same(16.0))
                         .show(ui, |ui| {
@@ -234,14 +283,14 @@ pub fn render(
                                     ui.label(
                                         egui::RichText::new("Bypass single-instance check")
                                             .size(13.0)
-                                            .color(Theme::TEXT_PRIMARY),
+                                            .color(Theme::text_primary()),
                                     );
                                     ui.label(
                                         egui::RichText::new(
                                             "Allows running multiple instances of the same app",
                                         )
                                         .size(11.0)
-                                        .color(Theme::TEXT_MUTED),
+                                        .color(Theme::text_muted()),
                                     );
                                 });
                             });
@@ -254,12 +303,12 @@ pub fn render(
                                     ui.label(
                                         egui::RichText::new("Use environment isolation")
                                             .size(13.0)
-                                            .color(Theme::TEXT_PRIMARY),
+                                            .color(Theme::text_primary()),
                                     );
                                     ui.label(
                                         egui::RichText::new("Sets custom APPDATA/profile paths")
                                             .size(11.0)
-                                            .color(Theme::TEXT_MUTED),
+                                            .color(Theme::text_muted()),
                                     );
                                 });
                             });
@@ -272,17 +321,103 @@ pub fn render(
                                     ui.label(
                                         egui::RichText::new("Hide from taskbar")
                                             .size(13.0)
-                                            .color(Theme::TEXT_PRIMARY),
+                                            .color(Theme::text_primary()),
                                     );
                                     ui.label(
                                         egui::RichText::new(
                                             "Hides the instance window from the Windows taskbar",
                                         )
                                         .size(11.0)
-                                        .color(Theme::TEXT_MUTED),
+                                        .color(Theme::text_muted()),
+                                    );
+                                });
+                            });
+
+                            ui.add_space(12.0);
+
+                            ui.horizontal(|ui| {
+                                ui.checkbox(&mut config.launch_in_background, "");
+                                ui.vertical(|ui| {
+                                    ui.label(
+                                        egui::RichText::new("Launch in background")
+                                            .size(13.0)
+                                            .color(Theme::text_primary()),
+                                    );
+                                    ui.label(
+                                        egui::RichText::new(
+                                            "Keeps focus on the window you are using when the instance opens",
+                                        )
+                                        .size(11.0)
+                                        .color(Theme::text_muted()),
                                     );
                                 });
                             });
+
+                            ui.add_space(12.0);
+
+                            ui.horizontal(|ui| {
+                                egui::ComboBox::from_id_salt("start_window_state")
+                                    .width(100.0)
+                                    .selected_text(config.start_window_state.label())
+                                    .show_ui(ui, |ui| {
+                                        for state in WindowState::all() {
+                                            ui.selectable_value(
+                                                &mut config.start_window_state,
+                                                *state,
+                                                state.label(),
+                                            );
+                                        }
+                                    });
+                                ui.vertical(|ui| {
+                                    ui.label(
+                                        egui::RichText::new("Window at launch")
+                                            .size(13.0)
+                                            .color(Theme::text_primary()),
+                                    );
+                                    ui.label(
+                                        egui::RichText::new(
+                                            "Minimize or hide the main window once it appears; show a hidden one from the instance's right-click menu",
+                                        )
+                                        .size(11.0)
+                                        .color(Theme::text_muted()),
+                                    );
+                                });
+                            });
+                            ui.add_space(8.0);
+                            ui.horizontal(|ui| {
+                                ui.checkbox(&mut config.rename_windows, "");
+                                ui.vertical(|ui| {
+                                    ui.label(
+                                        egui::RichText::new("Show name in window titles")
+                                            .size(13.0)
+                                            .color(Theme::text_primary()),
+                                    );
+                                    ui.label(
+                                        egui::RichText::new(
+                                            "Appends the instance name to the app's titles, e.g. \"Discord — Work\", to tell copies apart in the taskbar",
+                                        )
+                                        .size(11.0)
+                                        .color(Theme::text_muted()),
+                                    );
+                                });
+                            });
+                            render_virtual_desktop(ui, config, "new_virtual_desktop");
+
+                            ui.add_space(12.0);
+
+                            render_shared_caches(ui, config, "new_shared_caches");
+
+                            ui.add_space(12.0);
+                            render_architecture(ui, config, "launch_arch_select");
+
+                            ui.add_space(12.0);
+                            render_isolation_level(ui, config, "new_isolation_level");
+
+                            ui.add_space(12.0);
+                            render_exclusive_resources(ui, config, "new_exclusive");
+
+                            ui.add_space(12.0);
+                            render_environment_vars(ui, config);
                         });
 
                     ui.add_space(20.0);
@@ -291,14 +426,29 @@ pub fn render(
                     section_header(ui, "⚡", "Resource Limits");
 
                     egui::Frame::none()
-                        .fill(Theme::BG_SECONDARY)
+                        .fill(Theme::bg_secondary())
                         .rounding(egui::Rounding::same(10.0))
                         .inner_margin(egui::Margin::same(16.0))
                         .show(ui, |ui| {
+                            // Preset
+                            ui.label(
+                                egui::RichText::new("Preset")
+                                    .size(12.0)
+                                    .color(Theme::text_muted()),
+                            );
+                            ui.add_space(4.0);
+                            LimitPresetPicker::show(
+                                ui,
+                                &presets,
+                                &mut config.resource_limits,
+                                "new_limit_preset_select",
+                            );
+
+                            ui.add_space(12.0);
                             ui.label(
                                 egui::RichText::new("Leave at 0 for unlimited/default values")
                                     .size(11.0)
-                                    .color(Theme::TEXT_MUTED),
+                                    .color(Theme::text_muted()),
                             );
                             ui.add_space(12.0);
 
@@ -306,7 +456,7 @@ pub fn render(
                             ui.label(
                                 egui::RichText::new("CPU Limit")
                                     .size(12.0)
-                                    .color(Theme::TEXT_MUTED),
+                                    .color(Theme::text_muted()),
                             );
                             ui.add_space(4.0);
                             ui.horizontal(|ui| {
@@ -334,7 +484,7 @@ pub fn render(
                             ui.label(
                                 egui::RichText::new("Memory Limit")
                                     .size(12.0)
-                                    .color(Theme::TEXT_MUTED),
+                                    .color(Theme::text_muted()),
                             );
                             ui.add_space(4.0);
                             let mut mem = config.resource_limits.memory_mb.min(16384) as u32;
@@ -362,20 +512,77 @@ pub fn render(
                             ui.label(
                                 egui::RichText::new("Process Priority")
                                     .size(12.0)
-                                    .color(Theme::TEXT_MUTED),
+                                    .color(Theme::text_muted()),
                             );
                             ui.add_space(4.0);
                             ui.add(
-                                egui::Slider::new(&mut config.resource_limits.priority, -2..=2)
-                                    .custom_formatter(|n, _| match n as i8 {
-                                        -2 => "Idle".to_string(),
-                                        -1 => "Below Normal".to_string(),
-                                        0 => "Normal".to_string(),
-                                        1 => "Above Normal".to_string(),
-                                        2 => "High".to_string(),
-                                        _ => format!("{}", n),
-                                    }),
+                                egui::Slider::new(
+                                    &mut config.resource_limits.priority,
+                                    ResourceLimits::EFFICIENCY_PRIORITY..=2,
+                                )
+                                .custom_formatter(|n, _| ResourceLimits::priority_label(n as i8)),
+                            );
+                            if config.resource_limits.is_efficiency_mode() {
+                                ui.label(
+                                    egui::RichText::new(
+                                        "Lowest priority with power throttling; prefers E-cores where supported",
+                                    )
+                                    .size(11.0)
+                                    .color(Theme::text_muted()),
+                                );
+                            }
+
+                            ui.add_space(12.0);
+
+                            // Disk I/O
+                            ui.label(
+                                egui::RichText::new("Disk I/O")
+                                    .size(12.0)
+                                    .color(Theme::text_muted()),
                             );
+                            ui.add_space(4.0);
+                            ui.horizontal(|ui| {
+                                egui::ComboBox::from_id_salt("io_priority_select")
+                                    .selected_text(config.resource_limits.io_priority.label())
+                                    .show_ui(ui, |ui| {
+                                        for priority in IoPriority::all() {
+                                            ui.selectable_value(
+                                                &mut config.resource_limits.io_priority,
+                                                priority,
+                                                priority.label(),
+                                            );
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text("Priority of disk reads and writes against other programs");
+                                ui.add_space(8.0);
+                                ui.add(
+                                    egui::DragValue::new(&mut config.resource_limits.disk_mbps)
+                                        .range(0..=10_000)
+                                        .custom_formatter(|n, _| {
+                                            if n == 0.0 {
+                                                "Unlimited".to_string()
+                                            } else {
+                                                format!("{:.0} MB/s", n)
+                                            }
+                                        }),
+                                )
+                                .on_hover_text("Cap on reads and writes, each; not available on macOS");
+                            });
+
+                            ui.add_space(12.0);
+
+                            // CPU Affinity
+                            ui.label(
+                                egui::RichText::new("CPU Affinity")
+                                    .size(12.0)
+                                    .color(Theme::text_muted()),
+                            );
+                            ui.add_space(4.0);
+                            AffinityPicker::show(ui, &mut config.resource_limits, "affinity_preset_select");
+
+                            ui.add_space(12.0);
+                            render_limit_schedule(ui, config);
                         });
 
                     ui.add_space(20.0);
@@ -384,7 +591,7 @@ pub fn render(
                     section_header(ui, "↻", "Automation");
 
                     egui::Frame::none()
-                        .fill(Theme::BG_SECONDARY)
+                        .fill(Theme::bg_secondary())
                         .rounding(egui::Rounding::same(10.0))
                         .inner_margin(egui::Margin::same(16.0))
                         .show(ui, |ui| {
@@ -394,14 +601,14 @@ pub fn render(
                                     ui.label(
                                         egui::RichText::new("Auto-restart on crash")
                                             .size(13.0)
-                                            .color(Theme::TEXT_PRIMARY),
+                                            .color(Theme::text_primary()),
                                     );
                                     ui.label(
                                         egui::RichText::new(
                                             "Automatically restart if the instance crashes",
                                         )
                                         .size(11.0)
-                                        .color(Theme::TEXT_MUTED),
+                                        .color(Theme::text_muted()),
                                     );
                                 });
                             });
@@ -412,7 +619,7 @@ pub fn render(
                                     ui.label(
                                         egui::RichText::new("Restart delay:")
                                             .size(12.0)
-                                            .color(Theme::TEXT_MUTED),
+                                            .color(Theme::text_muted()),
                                     );
                                     ui.add_space(8.0);
                                     let mut delay = config.restart_delay_secs as i32;
@@ -424,6 +631,21 @@ pub fn render(
                                     config.restart_delay_secs = delay as u32;
                                 });
                             }
+
+                            ui.add_space(12.0);
+                            render_network_condition(ui, config, "new_network");
+
+                            ui.add_space(12.0);
+                            render_run_limit(ui, config);
+
+                            ui.add_space(8.0);
+                            render_monitor_interval(ui, config);
+
+                            ui.add_space(12.0);
+                            render_stop_command(ui, config);
+
+                            ui.add_space(12.0);
+                            render_output_capture(ui, config);
                         });
 
                     ui.add_space(20.0);
@@ -432,7 +654,7 @@ pub fn render(
                     section_header(ui, "📋", "Organization");
 
                     egui::Frame::none()
-                        .fill(Theme::BG_SECONDARY)
+                        .fill(Theme::bg_secondary())
                         .rounding(egui::Rounding::same(10.0))
                         .inner_margin(egui::Margin::same(16.0))
                         .show(ui, |ui| {
@@ -440,7 +662,7 @@ pub fn render(
                             ui.label(
                                 egui::RichText::new("Group")
                                     .size(12.0)
-                                    .color(Theme::TEXT_MUTED),
+                                    .color(Theme::text_muted()),
                             );
                             ui.add_space(4.0);
                             let groups = state.groups.read().unwrap();
@@ -471,11 +693,17 @@ pub fn render(
 
                             ui.add_space(16.0);
 
+                            render_metadata(ui, config, &metadata_fields, "new_metadata");
+
+                            render_tags(ui, config, "new_tags");
+
+                            ui.add_space(16.0);
+
                             // Notes
                             ui.label(
                                 egui::RichText::new("Notes")
                                     .size(12.0)
-                                    .color(Theme::TEXT_MUTED),
+                                    .color(Theme::text_muted()),
                             );
                             ui.add_space(4.0);
                             ui.add(
@@ -494,26 +722,51 @@ pub fn render(
             // Divider before action buttons
             let (rect, _) =
                 ui.allocate_exact_size(egui::vec2(ui.available_width(), 1.0), egui::Sense::hover());
-            ui.painter().rect_filled(rect, 0.0, Theme::BORDER_LIGHT);
+            ui.painter().rect_filled(rect, 0.0, Theme::border_light());
 
             ui.add_space(16.0);
 
+            // Conflicts with running instances
+            let conflicts = if config.executable_path.as_os_str().is_empty() {
+                Vec::new()
+            } else {
+                state.launch_conflicts(config, None)
+            };
+            let launch_blocked = conflicts.iter().any(|c| c.is_blocking());
+            for conflict in &conflicts {
+                let (icon, color) = if conflict.is_blocking() {
+                    ("✕", Theme::error())
+                } else {
+                    ("⚠", Theme::warning())
+                };
+                ui.label(
+                    egui::RichText::new(format!("{} {}", icon, conflict.message()))
+                        .size(12.0)
+                        .color(color),
+                );
+            }
+            if !conflicts.is_empty() {
+                ui.add_space(12.0);
+            }
+
             // Action buttons
             ui.horizontal(|ui| {
-                let can_create = !config.executable_path.as_os_str().is_empty();
+                let can_create = !config.executable_path.as_os_str().is_empty()
+                    && environment::validate(&config.environment).is_none();
+                let can_launch = can_create && !launch_blocked;
 
                 // Primary action button
                 let create_launch_btn =
                     egui::Button::new(egui::RichText::new("Create & Launch").color(Color32::WHITE))
-                        .fill(if can_create {
-                            Theme::PRIMARY
+                        .fill(if can_launch {
+                            Theme::primary()
                         } else {
-                            Theme::BG_TERTIARY
+                            Theme::bg_tertiary()
                         })
                         .rounding(egui::Rounding::same(8.0))
                         .min_size(egui::vec2(130.0, 38.0));
 
-                if ui.add_enabled(can_create, create_launch_btn).clicked() {
+                if ui.add_enabled(can_launch, create_launch_btn).clicked() {
                     match state.create_instance(config.clone(), true) {
                         Ok(_) => {
                             notifications.push(Notification {
@@ -537,7 +790,7 @@ pub fn render(
 
                 // Secondary action button
                 let create_btn = egui::Button::new("Create Only")
-                    .fill(Theme::BG_TERTIARY)
+                    .fill(Theme::bg_tertiary())
                     .rounding(egui::Rounding::same(8.0))
                     .min_size(egui::vec2(100.0, 38.0));
 
@@ -563,7 +816,7 @@ pub fn render(
 
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     let cancel_btn = egui::Button::new(
-                        egui::RichText::new("Cancel").color(Theme::TEXT_SECONDARY),
+                        egui::RichText::new("Cancel").color(Theme::text_secondary()),
                     )
                     .fill(Color32::TRANSPARENT)
                     .rounding(egui::Rounding::same(8.0))
@@ -580,3 +833,753 @@ pub fn render(
         *dialog = DialogState::None;
     }
 }
+
+/// Architecture row: launch slice picker on macOS and an emulation warning
+/// Menu of installed Store apps, which have no executable to browse for
+fn render_store_apps(ui: &mut egui::Ui, config: &mut InstanceConfig) {
+    ui.menu_button("Store app...", |ui| {
+        ui.set_max_height(320.0);
+        let Some(apps) = packaged_apps::installed() else {
+            ui.label(egui::RichText::new("Loading apps…").color(Theme::text_muted()));
+            ui.ctx().request_repaint();
+            return;
+        };
+        if apps.is_empty() {
+            ui.label(egui::RichText::new("No Store apps found").color(Theme::text_muted()));
+            return;
+        }
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for app in &apps {
+                if ui.button(&app.name).on_hover_text(&app.app_id).clicked() {
+                    config.executable_path = app.executable_path();
+                    if config.name.is_empty() {
+                        config.name = app.name.clone();
+                    }
+                    ui.close_menu();
+                }
+            }
+        });
+    });
+}
+
+/// Desktop the instance's windows are moved to (Windows only)
+fn render_virtual_desktop(ui: &mut egui::Ui, config: &mut InstanceConfig, id_salt: &str) {
+    if !cfg!(windows) {
+        return;
+    }
+    ui.add_space(12.0);
+    ui.horizontal(|ui| {
+        let selected_name = config
+            .virtual_desktop
+            .as_ref()
+            .map_or("Current", |d| d.name.as_str())
+            .to_string();
+        egui::ComboBox::from_id_salt(id_salt)
+            .width(100.0)
+            .selected_text(selected_name)
+            .show_ui(ui, |ui| {
+                if ui
+                    .selectable_label(config.virtual_desktop.is_none(), "Current")
+                    .clicked()
+                {
+                    config.virtual_desktop = None;
+                }
+                for desktop in virtual_desktop::desktops() {
+                    let selected = config
+                        .virtual_desktop
+                        .as_ref()
+                        .is_some_and(|d| d.id == desktop.id);
+                    if ui.selectable_label(selected, &desktop.name).clicked() {
+                        config.virtual_desktop = Some(desktop);
+                    }
+                }
+            });
+        ui.vertical(|ui| {
+            ui.label(
+                egui::RichText::new("Virtual desktop")
+                    .size(13.0)
+                    .color(Theme::text_primary()),
+            );
+            ui.label(
+                egui::RichText::new(
+                    "Moves the instance's windows to this desktop once they appear",
+                )
+                .size(11.0)
+                .color(Theme::text_muted()),
+            );
+        });
+    });
+}
+
+/// Time windows whose limits replace the instance's own while they are open
+fn render_limit_schedule(ui: &mut egui::Ui, config: &mut InstanceConfig) {
+    ui.label(
+        egui::RichText::new("Scheduled limits")
+            .size(12.0)
+            .color(Theme::text_muted()),
+    );
+    ui.label(
+        egui::RichText::new(
+            "Limits used instead of the ones above while a time window is open, e.g. lower caps during work hours",
+        )
+        .size(11.0)
+        .color(Theme::text_muted()),
+    );
+    ui.add_space(4.0);
+
+    let mut remove = None;
+    for (index, window) in config.limit_schedule.iter_mut().enumerate() {
+        ui.push_id(("limit_window", index), |ui| {
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut window.name)
+                        .hint_text("Name")
+                        .desired_width(120.0),
+                );
+                render_time(ui, &mut window.start);
+                ui.label("to");
+                render_time(ui, &mut window.end);
+                if ui.small_button("✕").clicked() {
+                    remove = Some(index);
+                }
+            });
+            ui.horizontal(|ui| {
+                for (day, name) in window.days.iter_mut().zip(DAY_NAMES) {
+                    if ui.selectable_label(*day, name).clicked() {
+                        *day = !*day;
+                    }
+                }
+            });
+            ui.horizontal(|ui| {
+                let unlimited = |n: f64, suffix: &str| {
+                    if n == 0.0 {
+                        "Unlimited".to_string()
+                    } else {
+                        format!("{:.0}{}", n, suffix)
+                    }
+                };
+                ui.label("CPU");
+                ui.add(
+                    egui::DragValue::new(&mut window.limits.cpu_percent)
+                        .range(0..=100)
+                        .custom_formatter(|n, _| unlimited(n, "%")),
+                );
+                ui.label("Memory");
+                ui.add(
+                    egui::DragValue::new(&mut window.limits.memory_mb)
+                        .range(0..=65536)
+                        .speed(16.0)
+                        .custom_formatter(|n, _| unlimited(n, " MB")),
+                );
+                ui.label("Priority");
+                ui.add(
+                    egui::DragValue::new(&mut window.limits.priority)
+                        .range(ResourceLimits::EFFICIENCY_PRIORITY..=2)
+                        .custom_formatter(|n, _| ResourceLimits::priority_label(n as i8)),
+                );
+            });
+            ui.add_space(4.0);
+        });
+    }
+    if let Some(index) = remove {
+        config.limit_schedule.remove(index);
+    }
+    if ui.small_button("+ Add time window").clicked() {
+        config
+            .limit_schedule
+            .push(LimitWindow::work_hours(config.resource_limits.clone()));
+    }
+}
+
+/// Hour and minute fields for a time of day
+fn render_time(ui: &mut egui::Ui, time: &mut NaiveTime) {
+    let mut hour = time.hour();
+    let mut minute = time.minute();
+    ui.add(
+        egui::DragValue::new(&mut hour)
+            .range(0..=23)
+            .custom_formatter(|v, _| format!("{:02}", v as u32)),
+    );
+    ui.label(":");
+    ui.add(
+        egui::DragValue::new(&mut minute)
+            .range(0..=59)
+            .custom_formatter(|v, _| format!("{:02}", v as u32)),
+    );
+    if let Some(changed) = NaiveTime::from_hms_opt(hour, minute, 0) {
+        *time = changed;
+    }
+}
+
+/// AppContainer sandbox and what it may reach (Windows only)
+fn render_isolation_level(ui: &mut egui::Ui, config: &mut InstanceConfig, id_salt: &str) {
+    if !cfg!(windows) {
+        return;
+    }
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new("Isolation")
+                .size(13.0)
+                .color(Theme::text_primary()),
+        );
+        egui::ComboBox::from_id_salt(id_salt)
+            .selected_text(config.isolation_level.label())
+            .show_ui(ui, |ui| {
+                for level in IsolationLevel::all() {
+                    ui.selectable_value(&mut config.isolation_level, level, level.label());
+                }
+            });
+    });
+    if config.isolation_level != IsolationLevel::AppContainer {
+        return;
+    }
+
+    ui.label(
+        egui::RichText::new(
+            "The sandbox can only write to the instance's data and working directories",
+        )
+        .size(11.0)
+        .color(Theme::text_muted()),
+    );
+    ui.horizontal_wrapped(|ui| {
+        for capability in AppContainerCapability::all() {
+            let mut enabled = config.app_container_capabilities.contains(&capability);
+            if ui.checkbox(&mut enabled, capability.label()).changed() {
+                if enabled {
+                    config.app_container_capabilities.push(capability);
+                } else {
+                    config
+                        .app_container_capabilities
+                        .retain(|c| *c != capability);
+                }
+            }
+        }
+    });
+    if config.capture_output {
+        ui.label(
+            egui::RichText::new("Output is not captured from sandboxed instances")
+                .size(11.0)
+                .color(Theme::warning()),
+        );
+    }
+}
+
+fn render_architecture(ui: &mut egui::Ui, config: &mut InstanceConfig, id_salt: &str) {
+    if cfg!(target_os = "macos") {
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new("Architecture")
+                    .size(13.0)
+                    .color(Theme::text_primary()),
+            );
+            egui::ComboBox::from_id_salt(id_salt)
+                .selected_text(config.launch_architecture.label())
+                .show_ui(ui, |ui| {
+                    for arch in LaunchArchitecture::all() {
+                        ui.selectable_value(&mut config.launch_architecture, *arch, arch.label());
+                    }
+                });
+        });
+    }
+
+    if let Some(info) = architecture_info(
+        ui.ctx(),
+        id_salt,
+        &config.executable_path,
+        config.launch_architecture,
+    ) {
+        if info.is_emulated() {
+            ui.label(
+                egui::RichText::new(format!(
+                    "{} build runs under {} on this machine and may be slower",
+                    info.runs_as.label(),
+                    info.emulation_label()
+                ))
+                .size(11.0)
+                .color(Theme::warning()),
+            );
+        }
+    }
+}
+
+/// Comma-separated labels for filtering and display
+/// A text box per metadata field from settings, plus any the instance still
+/// has a value for after its field was removed
+fn render_metadata(
+    ui: &mut egui::Ui,
+    config: &mut InstanceConfig,
+    fields: &[String],
+    id_salt: &str,
+) {
+    let mut names: Vec<String> = fields
+        .iter()
+        .map(|f| f.trim().to_string())
+        .filter(|f| !f.is_empty())
+        .collect();
+    for name in config.metadata.keys() {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+    if names.is_empty() {
+        return;
+    }
+
+    ui.label(
+        egui::RichText::new("Metadata")
+            .size(12.0)
+            .color(Theme::text_muted()),
+    );
+    ui.add_space(4.0);
+    egui::Grid::new((id_salt, "metadata"))
+        .num_columns(2)
+        .spacing([12.0, 6.0])
+        .show(ui, |ui| {
+            for name in names {
+                ui.label(egui::RichText::new(&name).color(Theme::text_secondary()));
+                let mut value = config.metadata.get(&name).cloned().unwrap_or_default();
+                if ui
+                    .add(egui::TextEdit::singleline(&mut value).desired_width(f32::INFINITY))
+                    .changed()
+                {
+                    if value.trim().is_empty() {
+                        config.metadata.remove(&name);
+                    } else {
+                        config.metadata.insert(name, value);
+                    }
+                }
+                ui.end_row();
+            }
+        });
+    ui.add_space(16.0);
+}
+
+fn render_tags(ui: &mut egui::Ui, config: &mut InstanceConfig, id_salt: &str) {
+    ui.label(
+        egui::RichText::new("Tags")
+            .size(12.0)
+            .color(Theme::text_muted()),
+    );
+    ui.add_space(4.0);
+
+    let parse_tags = |text: &str| -> Vec<String> {
+        text.split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+
+    let id = egui::Id::new((id_salt, "tags"));
+    let mut text = ui
+        .data(|d| d.get_temp::<String>(id))
+        .filter(|text| parse_tags(text) == config.tags)
+        .unwrap_or_else(|| config.tags.join(", "));
+    if ui
+        .add(
+            egui::TextEdit::singleline(&mut text)
+                .hint_text("e.g. testing, eu-west")
+                .desired_width(f32::INFINITY),
+        )
+        .changed()
+    {
+        config.tags = parse_tags(&text);
+    }
+    ui.data_mut(|d| d.insert_temp(id, text));
+}
+
+/// Cache sharing toggle and its folder patterns. The pattern text is kept
+/// as typed so blank lines survive while editing
+pub(super) fn render_shared_caches(ui: &mut egui::Ui, config: &mut InstanceConfig, id_salt: &str) {
+    let parse_patterns = |text: &str| -> Vec<String> {
+        text.lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+
+    let id = egui::Id::new((id_salt, "cache_patterns"));
+    let mut text = ui
+        .data(|d| d.get_temp::<String>(id))
+        .filter(|text| parse_patterns(text) == config.shared_cache_patterns);
+    // Cleared text keeps sharing on until the box is unticked
+    let mut share_caches = !config.shared_cache_patterns.is_empty() || text.is_some();
+
+    ui.horizontal(|ui| {
+        if ui.checkbox(&mut share_caches, "").changed() {
+            config.shared_cache_patterns = if share_caches {
+                cache_links::default_patterns()
+            } else {
+                Vec::new()
+            };
+            text = None;
+        }
+        ui.vertical(|ui| {
+            ui.label(
+                egui::RichText::new("Share cache folders")
+                    .size(13.0)
+                    .color(Theme::text_primary()),
+            );
+            ui.label(
+                egui::RichText::new("Links caches to a store shared by instances of this app")
+                    .size(11.0)
+                    .color(Theme::text_muted()),
+            );
+        });
+    });
+
+    if !share_caches {
+        ui.data_mut(|d| d.remove::<String>(id));
+        return;
+    }
+
+    ui.add_space(8.0);
+    ui.label(
+        egui::RichText::new("Cache folders, one per line (* matches any folder name)")
+            .size(11.0)
+            .color(Theme::text_muted()),
+    );
+    let mut text = text.unwrap_or_else(|| config.shared_cache_patterns.join("\n"));
+    if ui
+        .add(
+            egui::TextEdit::multiline(&mut text)
+                .desired_width(f32::INFINITY)
+                .desired_rows(3),
+        )
+        .changed()
+    {
+        config.shared_cache_patterns = parse_patterns(&text);
+    }
+    ui.data_mut(|d| d.insert_temp(id, text));
+    if !config.use_environment_isolation {
+        ui.label(
+            egui::RichText::new("Requires environment isolation to take effect")
+                .size(11.0)
+                .color(Theme::warning()),
+        );
+    }
+}
+
+/// Network state to wait for before launching
+fn render_network_condition(ui: &mut egui::Ui, config: &mut InstanceConfig, id_salt: &str) {
+    let mut enabled = config.network_condition.is_some();
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut enabled, "");
+        ui.vertical(|ui| {
+            ui.label(
+                egui::RichText::new("Wait for network")
+                    .size(13.0)
+                    .color(Theme::text_primary()),
+            );
+            ui.label(
+                egui::RichText::new("Stay pending and launch once the condition holds")
+                    .size(11.0)
+                    .color(Theme::text_muted()),
+            );
+        });
+    });
+    if enabled != config.network_condition.is_some() {
+        config.network_condition = enabled.then(|| NetworkCondition::VpnConnected(String::new()));
+    }
+
+    let Some(condition) = config.network_condition.as_mut() else {
+        return;
+    };
+    ui.add_space(8.0);
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_salt(("network_condition", id_salt))
+            .width(130.0)
+            .selected_text(condition.label())
+            .show_ui(ui, |ui| {
+                for kind in NetworkCondition::all() {
+                    let selected = condition.same_kind(&kind);
+                    if ui.selectable_label(selected, kind.label()).clicked() && !selected {
+                        let value = std::mem::take(condition.value_mut());
+                        *condition = kind;
+                        *condition.value_mut() = value;
+                    }
+                }
+            });
+        let hint = condition.hint();
+        ui.add(
+            egui::TextEdit::singleline(condition.value_mut())
+                .hint_text(hint)
+                .desired_width(f32::INFINITY),
+        );
+    });
+}
+
+/// Per-launch time box; 0 means run until stopped
+fn render_run_limit(ui: &mut egui::Ui, config: &mut InstanceConfig) {
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new("Stop after:")
+                .size(12.0)
+                .color(Theme::text_muted()),
+        );
+        ui.add_space(8.0);
+        ui.add(
+            egui::DragValue::new(&mut config.run_limit_mins)
+                .range(0..=1440)
+                .custom_formatter(|mins, _| {
+                    if mins == 0.0 {
+                        "No limit".to_string()
+                    } else {
+                        format!("{} min", mins)
+                    }
+                }),
+        )
+        .on_hover_text("Stop the instance automatically this long after each launch");
+    });
+}
+
+/// Per-instance resource sampling interval
+fn render_monitor_interval(ui: &mut egui::Ui, config: &mut InstanceConfig) {
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new("Monitor every:")
+                .size(12.0)
+                .color(Theme::text_muted()),
+        );
+        ui.add_space(8.0);
+        let mut interval = config.monitor_interval_ms;
+        ui.add(
+            egui::DragValue::new(&mut interval)
+                .range(0..=60_000)
+                .speed(50)
+                .custom_formatter(|ms, _| {
+                    if ms == 0.0 {
+                        "Default".to_string()
+                    } else {
+                        format!("{} ms", ms)
+                    }
+                }),
+        )
+        .on_hover_text("How often to sample CPU and memory for this instance");
+        // Anything but "Default" is held to the monitor's finest tick
+        config.monitor_interval_ms = if interval == 0 {
+            0
+        } else {
+            interval.max(MIN_MONITOR_INTERVAL_MS)
+        };
+    });
+}
+
+/// Graceful shutdown command and how long to wait for it
+fn render_stop_command(ui: &mut egui::Ui, config: &mut InstanceConfig) {
+    ui.label(
+        egui::RichText::new("Stop command")
+            .size(13.0)
+            .color(Theme::text_primary()),
+    );
+    ui.label(
+        egui::RichText::new(
+            "Run on stop before terminating the process, e.g. app.exe --quit or curl http://localhost:8080/shutdown ({pid} is the process ID)",
+        )
+        .size(11.0)
+        .color(Theme::text_muted()),
+    );
+    ui.add_space(4.0);
+    ui.add(
+        egui::TextEdit::singleline(&mut config.stop_command)
+            .hint_text("None (terminate directly)")
+            .desired_width(f32::INFINITY),
+    );
+
+    if !config.stop_command.trim().is_empty() {
+        ui.add_space(8.0);
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new("Wait for exit:")
+                    .size(12.0)
+                    .color(Theme::text_muted()),
+            );
+            ui.add_space(8.0);
+            ui.add(
+                egui::DragValue::new(&mut config.stop_timeout_secs)
+                    .range(1..=300)
+                    .suffix(" sec"),
+            );
+        });
+    }
+}
+
+fn render_output_capture(ui: &mut egui::Ui, config: &mut InstanceConfig) {
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut config.capture_output, "");
+        ui.vertical(|ui| {
+            ui.label(
+                egui::RichText::new("Capture output")
+                    .size(13.0)
+                    .color(Theme::text_primary()),
+            );
+            ui.label(
+                egui::RichText::new(
+                    "Save stdout and stderr to a rotating log, shown in the Logs tab of the instance details",
+                )
+                .size(11.0)
+                .color(Theme::text_muted()),
+            );
+        });
+    });
+}
+
+/// Ports and devices the instance must not share with another running instance
+fn render_exclusive_resources(ui: &mut egui::Ui, config: &mut InstanceConfig, id_salt: &str) {
+    ui.label(
+        egui::RichText::new("Exclusive resources")
+            .size(13.0)
+            .color(Theme::text_primary()),
+    );
+    ui.label(
+        egui::RichText::new("Launching is blocked while another instance holds one of these")
+            .size(11.0)
+            .color(Theme::text_muted()),
+    );
+    ui.add_space(4.0);
+
+    let parse_ports = |text: &str| -> Vec<u16> {
+        text.split(',')
+            .filter_map(|p| p.trim().parse::<u16>().ok())
+            .filter(|p| *p > 0)
+            .collect()
+    };
+    let parse_devices = |text: &str| -> Vec<String> {
+        text.split(',')
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty())
+            .collect()
+    };
+
+    // Keep the raw text so partially typed lists survive between frames,
+    // unless it no longer describes the config (another instance was opened)
+    let ports_id = egui::Id::new((id_salt, "ports"));
+    let mut ports = ui
+        .data(|d| d.get_temp::<String>(ports_id))
+        .filter(|text| parse_ports(text) == config.ports)
+        .unwrap_or_else(|| {
+            config
+                .ports
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        });
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new("Ports")
+                .size(12.0)
+                .color(Theme::text_muted()),
+        );
+        if ui
+            .add(
+                egui::TextEdit::singleline(&mut ports)
+                    .hint_text("e.g. 8080, 9000")
+                    .desired_width(f32::INFINITY),
+            )
+            .changed()
+        {
+            config.ports = parse_ports(&ports);
+        }
+    });
+    ui.data_mut(|d| d.insert_temp(ports_id, ports));
+
+    let devices_id = egui::Id::new((id_salt, "devices"));
+    let mut devices = ui
+        .data(|d| d.get_temp::<String>(devices_id))
+        .filter(|text| parse_devices(text) == config.exclusive_devices)
+        .unwrap_or_else(|| config.exclusive_devices.join(", "));
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new("Devices")
+                .size(12.0)
+                .color(Theme::text_muted()),
+        );
+        if ui
+            .add(
+                egui::TextEdit::singleline(&mut devices)
+                    .hint_text("e.g. COM3, /dev/ttyUSB0")
+                    .desired_width(f32::INFINITY),
+            )
+            .changed()
+        {
+            config.exclusive_devices = parse_devices(&devices);
+        }
+    });
+    ui.data_mut(|d| d.insert_temp(devices_id, devices));
+}
+
+/// Custom environment variables with a preview of their expanded values
+fn render_environment_vars(ui: &mut egui::Ui, config: &mut InstanceConfig) {
+    ui.label(
+        egui::RichText::new("Environment variables")
+            .size(13.0)
+            .color(Theme::text_primary()),
+    );
+    ui.label(
+        egui::RichText::new(
+            "Applied after the isolation variables; use $NAME or ${NAME} to reference others",
+        )
+        .size(11.0)
+        .color(Theme::text_muted()),
+    );
+    ui.add_space(4.0);
+
+    let expanded = environment::expand_all(&config.environment, &[]);
+    let duplicates: Vec<bool> = config
+        .environment
+        .iter()
+        .map(|(key, _)| environment::is_duplicate(&config.environment, key))
+        .collect();
+
+    let mut remove = None;
+    for (index, (key, value)) in config.environment.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            let key_color = if duplicates[index] {
+                Theme::error()
+            } else {
+                Theme::text_primary()
+            };
+            ui.add(
+                egui::TextEdit::singleline(key)
+                    .hint_text("NAME")
+                    .text_color(key_color)
+                    .desired_width(140.0),
+            );
+            ui.add(
+                egui::TextEdit::singleline(value)
+                    .hint_text("Value")
+                    .desired_width(260.0),
+            );
+            if ui.small_button("✕").clicked() {
+                remove = Some(index);
+            }
+        });
+
+        let (preview, missing) = &expanded[index];
+        if preview != value {
+            ui.label(
+                egui::RichText::new(format!("→ {}", preview))
+                    .size(11.0)
+                    .color(Theme::text_muted()),
+            );
+        }
+        if !missing.is_empty() {
+            ui.label(
+                egui::RichText::new(format!("Not defined: {}", missing.join(", ")))
+                    .size(11.0)
+                    .color(Theme::warning()),
+            );
+        }
+    }
+    if let Some(index) = remove {
+        config.environment.remove(index);
+    }
+    if ui.small_button("+ Add variable").clicked() {
+        config.environment.push((String::new(), String::new()));
+    }
+    if let Some(error) = environment::validate(&config.environment) {
+        ui.label(egui::RichText::new(error).size(11.0).color(Theme::error()));
+    }
+}
@@ -55,7 +55,7 @@ pub fn render(ctx: &Context, state: &mut AppState, dialog: &mut DialogState) {
             ui.label(
                 egui::RichText::new("Launch instances one by one with a delay")
                     .small()
-                    .color(Theme::TEXT_MUTED),
+                    .color(Theme::text_muted()),
             );
 
             if profile.staggered_launch {
@@ -67,11 +67,45 @@ pub fn render(ctx: &Context, state: &mut AppState, dialog: &mut DialogState) {
                 });
             }
 
+            ui.add_space(8.0);
+            ui.checkbox(&mut profile.shared_isolation, "Shared isolation root");
+            ui.label(
+                egui::RichText::new(
+                    "Instances share one sandboxed data folder so they can see each other",
+                )
+                .small()
+                .color(Theme::text_muted()),
+            );
+
+            if profile.shared_isolation {
+                ui.horizontal(|ui| {
+                    ui.label("Root:");
+                    let mut root = profile
+                        .isolation_root
+                        .as_ref()
+                        .map(|p| p.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    ui.add(
+                        egui::TextEdit::singleline(&mut root).hint_text("Default (profile folder)"),
+                    );
+                    if ui.button("Browse").clicked() {
+                        if let Some(path) = rfd::FileDialog::new().pick_folder() {
+                            root = path.to_string_lossy().to_string();
+                        }
+                    }
+                    profile.isolation_root = if root.is_empty() {
+                        None
+                    } else {
+                        Some(root.into())
+                    };
+                });
+            }
+
             ui.add_space(16.0);
 
             // Note about instances
             egui::Frame::none()
-                .fill(Theme::BG_TERTIARY)
+                .fill(Theme::bg_tertiary())
                 .rounding(egui::Rounding::same(4.0))
                 .inner_margin(egui::Margin::same(8.0))
                 .show(ui, |ui| {
@@ -80,7 +114,7 @@ pub fn render(ctx: &Context, state: &mut AppState, dialog: &mut DialogState) {
                             "After creating the profile, you can add instances to it from the Instances view.",
                         )
                         .small()
-                        .color(Theme::TEXT_SECONDARY),
+                        .color(Theme::text_secondary()),
                     );
                 });
 
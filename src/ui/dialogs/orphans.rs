@@ -0,0 +1,104 @@
+//! Orphan cleanup dialog - Adopt or terminate processes left over from a crash
+
+use egui::Context;
+
+use crate::core::AppState;
+use crate::ui::dialogs::DialogState;
+use crate::ui::theme::Theme;
+
+pub fn render(ctx: &Context, state: &AppState, dialog: &mut DialogState) {
+    let orphans = state.orphans.read().map(|o| o.clone()).unwrap_or_default();
+    if orphans.is_empty() {
+        *dialog = DialogState::None;
+        return;
+    }
+
+    let mut open = true;
+    let mut adopt = None;
+    let mut terminate = Vec::new();
+
+    egui::Window::new("Leftover Processes")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(460.0)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.label(
+                "These processes are using instance data directories but are not tracked, \
+                 probably left over from a crash. Adopt them to manage them again, or terminate them.",
+            );
+            ui.add_space(12.0);
+
+            for orphan in &orphans {
+                egui::Frame::none()
+                    .fill(Theme::bg_secondary())
+                    .rounding(egui::Rounding::same(8.0))
+                    .inner_margin(egui::Margin::symmetric(12.0, 8.0))
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.vertical(|ui| {
+                                ui.label(
+                                    egui::RichText::new(format!(
+                                        "{} (PID {})",
+                                        orphan.name, orphan.pid
+                                    ))
+                                    .strong()
+                                    .color(Theme::text_primary()),
+                                );
+                                ui.label(
+                                    egui::RichText::new(orphan.data_dir.to_string_lossy())
+                                        .size(11.0)
+                                        .color(Theme::text_muted()),
+                                );
+                            });
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    if ui
+                                        .button(egui::RichText::new("Terminate").color(Theme::error()))
+                                        .clicked()
+                                    {
+                                        terminate.push(orphan.pid);
+                                    }
+                                    if ui.button("Adopt").clicked() {
+                                        adopt = Some(orphan.pid);
+                                    }
+                                },
+                            );
+                        });
+                    });
+                ui.add_space(4.0);
+            }
+
+            ui.add_space(12.0);
+            ui.horizontal(|ui| {
+                if ui
+                    .button(egui::RichText::new("Terminate All").color(Theme::error()))
+                    .clicked()
+                {
+                    terminate.extend(orphans.iter().map(|o| o.pid));
+                }
+                if ui.button("Leave Running").clicked() {
+                    state.dismiss_orphans();
+                    *dialog = DialogState::None;
+                }
+            });
+        });
+
+    if let Some(pid) = adopt {
+        if let Err(e) = state.adopt_orphan(pid) {
+            tracing::error!("Failed to adopt process {}: {}", pid, e);
+        }
+    }
+    for pid in terminate {
+        if let Err(e) = state.terminate_orphan(pid) {
+            tracing::error!("Failed to terminate process {}: {}", pid, e);
+        }
+    }
+
+    if !open {
+        state.dismiss_orphans();
+        *dialog = DialogState::None;
+    }
+}
@@ -0,0 +1,96 @@
+//! Partial launch dialog - Start only some members of a profile
+
+use egui::Context;
+
+use crate::core::AppState;
+use crate::ui::dialogs::DialogState;
+use crate::ui::theme::Theme;
+
+pub fn render(ctx: &Context, state: &AppState, dialog: &mut DialogState) {
+    let DialogState::PartialLaunch { profile, selected } = dialog else {
+        return;
+    };
+    let profile_id = *profile;
+    let Some((name, configs)) = state.profiles.read().ok().and_then(|p| {
+        p.get(&profile_id)
+            .map(|p| (p.name.clone(), p.instances.clone()))
+    }) else {
+        *dialog = DialogState::None;
+        return;
+    };
+    selected.resize(configs.len(), false);
+
+    let mut open = true;
+    let mut launch = false;
+
+    egui::Window::new(format!("Launch {}", name))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(420.0)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.label("Choose the members to start. The choice is remembered for next time.");
+            ui.add_space(12.0);
+
+            egui::ScrollArea::vertical()
+                .max_height(320.0)
+                .show(ui, |ui| {
+                    for (config, chosen) in configs.iter().zip(selected.iter_mut()) {
+                        egui::Frame::none()
+                            .fill(Theme::bg_secondary())
+                            .rounding(egui::Rounding::same(8.0))
+                            .inner_margin(egui::Margin::symmetric(12.0, 8.0))
+                            .show(ui, |ui| {
+                                ui.horizontal(|ui| {
+                                    ui.checkbox(chosen, "");
+                                    ui.vertical(|ui| {
+                                        ui.label(
+                                            egui::RichText::new(&config.name)
+                                                .strong()
+                                                .color(Theme::text_primary()),
+                                        );
+                                        ui.label(
+                                            egui::RichText::new(
+                                                config.executable_path.to_string_lossy(),
+                                            )
+                                            .size(11.0)
+                                            .color(Theme::text_muted()),
+                                        );
+                                    });
+                                });
+                            });
+                        ui.add_space(4.0);
+                    }
+                });
+
+            ui.add_space(12.0);
+            ui.horizontal(|ui| {
+                let count = selected.iter().filter(|chosen| **chosen).count();
+                if ui
+                    .add_enabled(
+                        count > 0,
+                        egui::Button::new(format!("Launch Selected ({})", count)),
+                    )
+                    .clicked()
+                {
+                    launch = true;
+                }
+                if ui.button("Select All").clicked() {
+                    selected.iter_mut().for_each(|chosen| *chosen = true);
+                }
+                if ui.button("Select None").clicked() {
+                    selected.iter_mut().for_each(|chosen| *chosen = false);
+                }
+            });
+        });
+
+    if launch {
+        if let Err(e) = state.launch_profile_partial(profile_id, selected) {
+            tracing::error!("Failed to launch profile: {}", e);
+        }
+        *dialog = DialogState::None;
+    } else if !open {
+        *dialog = DialogState::None;
+    }
+}
@@ -0,0 +1,145 @@
+//! Profile template dialog - Create a profile from a built-in template
+
+use std::path::Path;
+
+use egui::Context;
+
+use crate::core::profile_template::TEMPLATES;
+use crate::core::AppState;
+use crate::ui::dialogs::DialogState;
+use crate::ui::theme::Theme;
+
+pub fn render(ctx: &Context, state: &AppState, dialog: &mut DialogState) {
+    let DialogState::NewFromTemplate {
+        template,
+        name,
+        executable,
+        count,
+    } = dialog
+    else {
+        return;
+    };
+
+    let mut open = true;
+    let mut create = false;
+    let mut cancel = false;
+
+    egui::Window::new("New from Template")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(460.0)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.label(egui::RichText::new("Template").strong());
+            ui.add_space(8.0);
+
+            for (idx, candidate) in TEMPLATES.iter().enumerate() {
+                let chosen = *template == idx;
+                let response = egui::Frame::none()
+                    .fill(if chosen {
+                        Theme::bg_tertiary()
+                    } else {
+                        Theme::bg_secondary()
+                    })
+                    .stroke(if chosen {
+                        egui::Stroke::new(1.0, Theme::primary())
+                    } else {
+                        egui::Stroke::NONE
+                    })
+                    .rounding(egui::Rounding::same(8.0))
+                    .inner_margin(egui::Margin::symmetric(12.0, 8.0))
+                    .show(ui, |ui| {
+                        ui.set_width(ui.available_width());
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "{} ({} members)",
+                                candidate.name, candidate.count
+                            ))
+                            .strong()
+                            .color(Theme::text_primary()),
+                        );
+                        ui.label(
+                            egui::RichText::new(candidate.description)
+                                .size(11.0)
+                                .color(Theme::text_muted()),
+                        );
+                    })
+                    .response
+                    .interact(egui::Sense::click());
+                if response.clicked() && !chosen {
+                    *template = idx;
+                    *count = candidate.count;
+                }
+                ui.add_space(4.0);
+            }
+
+            let Some(selected) = TEMPLATES.get(*template) else {
+                return;
+            };
+
+            ui.add_space(12.0);
+            ui.separator();
+            ui.add_space(8.0);
+
+            ui.horizontal(|ui| {
+                ui.label("Name:");
+                ui.add(egui::TextEdit::singleline(name).hint_text(selected.name));
+            });
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                ui.label("Executable:");
+                ui.add(egui::TextEdit::singleline(executable).desired_width(260.0));
+                if ui.button("Browse").clicked() {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("Executable", &["exe", "app", ""])
+                        .pick_file()
+                    {
+                        *executable = path.to_string_lossy().to_string();
+                    }
+                }
+            });
+            ui.label(
+                egui::RichText::new(selected.executable_hint)
+                    .small()
+                    .color(Theme::text_muted()),
+            );
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                ui.label("Instances:");
+                ui.add(egui::DragValue::new(count).range(1..=selected.max_count));
+            });
+
+            ui.add_space(16.0);
+            ui.horizontal(|ui| {
+                let can_create = !executable.trim().is_empty();
+                if ui
+                    .add_enabled(can_create, egui::Button::new("Create"))
+                    .clicked()
+                {
+                    create = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancel = true;
+                }
+            });
+        });
+
+    if create {
+        if let Some(selected) = TEMPLATES.get(*template) {
+            let name = match name.trim() {
+                "" => selected.name,
+                name => name,
+            };
+            let profile = selected.instantiate(name, Path::new(executable.trim()), *count);
+            if let Err(e) = state.save_profile(profile) {
+                tracing::error!("Failed to create profile from template: {}", e);
+            }
+        }
+        *dialog = DialogState::None;
+    } else if cancel || !open {
+        *dialog = DialogState::None;
+    }
+}
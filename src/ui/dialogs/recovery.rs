@@ -0,0 +1,147 @@
+//! Crash recovery dialog - What happened to the instances of a run that ended unexpectedly
+
+use egui::Context;
+
+use crate::core::recovery::RecoveredInstance;
+use crate::core::AppState;
+use crate::ui::dialogs::DialogState;
+use crate::ui::theme::Theme;
+
+pub fn render(ctx: &Context, state: &AppState, dialog: &mut DialogState) {
+    let Some(report) = state.recovery.read().ok().and_then(|r| r.clone()) else {
+        *dialog = DialogState::None;
+        return;
+    };
+    if report.instances.is_empty() {
+        state.dismiss_recovery();
+        *dialog = DialogState::None;
+        return;
+    }
+
+    let mut open = true;
+    let mut adopt = Vec::new();
+    let mut close = false;
+
+    egui::Window::new("Recovered From Unexpected Exit")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(480.0)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.label(format!(
+                "MultiInstance did not shut down cleanly. It was last seen running at {}.",
+                report
+                    .last_seen
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+            ));
+            ui.add_space(12.0);
+
+            let alive: Vec<_> = report.alive().collect();
+            if !alive.is_empty() {
+                ui.label(
+                    egui::RichText::new("Still running")
+                        .strong()
+                        .color(Theme::success()),
+                );
+                ui.label(
+                    egui::RichText::new("Adopt these to manage them again.")
+                        .size(11.0)
+                        .color(Theme::text_muted()),
+                );
+                ui.add_space(4.0);
+                for recovered in &alive {
+                    if recovered_row(ui, recovered, Some("Adopt")) {
+                        adopt.push(recovered.instance.id);
+                    }
+                }
+                ui.add_space(8.0);
+            }
+
+            let lost: Vec<_> = report.lost().collect();
+            if !lost.is_empty() {
+                ui.label(egui::RichText::new("Lost").strong().color(Theme::error()));
+                ui.label(
+                    egui::RichText::new(
+                        "These are no longer running. Any unsaved work in them is gone.",
+                    )
+                    .size(11.0)
+                    .color(Theme::text_muted()),
+                );
+                ui.add_space(4.0);
+                for recovered in &lost {
+                    recovered_row(ui, recovered, None);
+                }
+                ui.add_space(8.0);
+            }
+
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                if !alive.is_empty() && ui.button("Adopt All").clicked() {
+                    adopt.extend(alive.iter().map(|r| r.instance.id));
+                }
+                if ui.button("Close").clicked() {
+                    close = true;
+                }
+            });
+        });
+
+    for id in adopt {
+        if let Err(e) = state.adopt_recovered(id) {
+            tracing::error!("Failed to adopt instance {}: {}", id.0, e);
+        }
+    }
+
+    if close || !open {
+        state.dismiss_recovery();
+        *dialog = DialogState::None;
+    }
+}
+
+/// One instance row; returns true when the action button was clicked
+fn recovered_row(ui: &mut egui::Ui, recovered: &RecoveredInstance, action: Option<&str>) -> bool {
+    let instance = &recovered.instance;
+    let mut clicked = false;
+    egui::Frame::none()
+        .fill(Theme::bg_secondary())
+        .rounding(egui::Rounding::same(8.0))
+        .inner_margin(egui::Margin::symmetric(12.0, 8.0))
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.vertical(|ui| {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{} (PID {})",
+                            instance.config.name, instance.pid
+                        ))
+                        .strong()
+                        .color(Theme::text_primary()),
+                    );
+                    let mut detail = instance
+                        .config
+                        .executable_path
+                        .to_string_lossy()
+                        .to_string();
+                    if let Some(started) = instance.started_at {
+                        detail.push_str(&format!(
+                            " · started {}",
+                            started.with_timezone(&chrono::Local).format("%b %d %H:%M")
+                        ));
+                    }
+                    ui.label(
+                        egui::RichText::new(detail)
+                            .size(11.0)
+                            .color(Theme::text_muted()),
+                    );
+                });
+                if let Some(action) = action {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        clicked = ui.button(action).clicked();
+                    });
+                }
+            });
+        });
+    ui.add_space(4.0);
+    clicked
+}
@@ -0,0 +1,154 @@
+//! Session restore dialog - Pick which instances from the last session to relaunch
+
+use egui::Context;
+
+use crate::core::AppState;
+use crate::ui::dialogs::DialogState;
+use crate::ui::theme::Theme;
+
+/// What the user chose to do with the pending session
+#[derive(Clone, Copy)]
+enum Choice {
+    Selected,
+    All,
+    None,
+}
+
+pub fn render(ctx: &Context, state: &AppState, dialog: &mut DialogState) {
+    let DialogState::SessionRestore { selected, remember } = dialog else {
+        return;
+    };
+    let configs = state
+        .pending_session
+        .read()
+        .map(|p| p.clone())
+        .unwrap_or_default();
+    if configs.is_empty() {
+        *dialog = DialogState::None;
+        return;
+    }
+
+    let mut open = true;
+    let mut choice = None;
+
+    egui::Window::new("Restore Previous Session")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(460.0)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.label("These instances were running when MultiInstance last closed.");
+            ui.add_space(12.0);
+
+            egui::ScrollArea::vertical()
+                .max_height(320.0)
+                .show(ui, |ui| {
+                    for (index, config) in configs.iter().enumerate() {
+                        let exists = config.executable_exists();
+                        egui::Frame::none()
+                            .fill(Theme::bg_secondary())
+                            .rounding(egui::Rounding::same(8.0))
+                            .inner_margin(egui::Margin::symmetric(12.0, 8.0))
+                            .show(ui, |ui| {
+                                ui.horizontal(|ui| {
+                                    let mut checked = exists && selected[index];
+                                    ui.add_enabled_ui(exists, |ui| {
+                                        if ui.checkbox(&mut checked, "").changed() {
+                                            selected[index] = checked;
+                                        }
+                                    });
+                                    ui.vertical(|ui| {
+                                        ui.label(
+                                            egui::RichText::new(&config.name)
+                                                .strong()
+                                                .color(Theme::text_primary()),
+                                        );
+                                        ui.label(
+                                            egui::RichText::new(
+                                                config.executable_path.to_string_lossy(),
+                                            )
+                                            .size(11.0)
+                                            .color(Theme::text_muted()),
+                                        );
+                                        if !exists {
+                                            ui.label(
+                                                egui::RichText::new(
+                                                    "Executable not found, will be skipped",
+                                                )
+                                                .size(11.0)
+                                                .color(Theme::warning()),
+                                            );
+                                        }
+                                    });
+                                });
+                            });
+                        ui.add_space(4.0);
+                    }
+                });
+
+            ui.add_space(8.0);
+            ui.checkbox(
+                remember,
+                "Remember my choice for Restore All / Restore None",
+            );
+            ui.add_space(12.0);
+
+            ui.horizontal(|ui| {
+                let count = configs
+                    .iter()
+                    .zip(selected.iter())
+                    .filter(|(config, chosen)| **chosen && config.executable_exists())
+                    .count();
+                if ui
+                    .add_enabled(
+                        count > 0,
+                        egui::Button::new(format!("Restore Selected ({})", count)),
+                    )
+                    .clicked()
+                {
+                    choice = Some(Choice::Selected);
+                }
+                if ui.button("Restore All").clicked() {
+                    choice = Some(Choice::All);
+                }
+                if ui.button("Restore None").clicked() {
+                    choice = Some(Choice::None);
+                }
+            });
+        });
+
+    if !open {
+        choice = Some(Choice::None);
+    }
+    let Some(choice) = choice else {
+        return;
+    };
+
+    let remember = open && *remember && !matches!(choice, Choice::Selected);
+    let result = match choice {
+        Choice::Selected => state.restore_pending_session(selected),
+        Choice::All => state.restore_pending_session(&vec![true; configs.len()]),
+        Choice::None => {
+            state.dismiss_pending_session();
+            Ok(0)
+        }
+    };
+    if let Err(e) = result {
+        tracing::error!("Failed to restore previous session: {}", e);
+    }
+
+    if remember {
+        if let Ok(mut settings) = state.settings.write() {
+            match choice {
+                Choice::All => settings.session_restore_prompt = false,
+                _ => settings.auto_restore_sessions = false,
+            }
+        }
+        if let Err(e) = state.save_settings() {
+            tracing::error!("Failed to save settings: {}", e);
+        }
+    }
+
+    *dialog = DialogState::None;
+}
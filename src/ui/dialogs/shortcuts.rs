@@ -0,0 +1,60 @@
+//! Keyboard shortcuts cheat sheet
+
+use egui::Context;
+
+use crate::ui::dialogs::DialogState;
+use crate::ui::theme::Theme;
+
+/// In-app shortcuts, as listed in the cheat sheet
+pub const SHORTCUTS: &[(&str, &str)] = &[
+    ("Ctrl+N", "New instance"),
+    ("Ctrl+F", "Search instances"),
+    ("1 – 6", "Switch to the view at that sidebar position"),
+    ("Space", "Start or stop the selected instance"),
+    ("Delete", "Remove the selected instance"),
+    ("F1", "Show this list"),
+];
+
+pub fn render(ctx: &Context, dialog: &mut DialogState) {
+    let mut open = true;
+
+    egui::Window::new("Keyboard Shortcuts")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(360.0)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            egui::Grid::new("shortcuts_grid")
+                .num_columns(2)
+                .spacing([24.0, 8.0])
+                .show(ui, |ui| {
+                    for (keys, action) in SHORTCUTS {
+                        ui.label(
+                            egui::RichText::new(*keys)
+                                .monospace()
+                                .color(Theme::text_primary()),
+                        );
+                        ui.label(egui::RichText::new(*action).color(Theme::text_secondary()));
+                        ui.end_row();
+                    }
+                });
+            ui.add_space(8.0);
+            ui.label(
+                egui::RichText::new(
+                    "Single keys are ignored while typing. The selected instance is the one \
+                     whose details were opened last.",
+                )
+                .size(12.0)
+                .color(Theme::text_muted()),
+            );
+            ui.add_space(8.0);
+            if ui.button("Close").clicked() {
+                *dialog = DialogState::None;
+            }
+        });
+
+    if !open {
+        *dialog = DialogState::None;
+    }
+}
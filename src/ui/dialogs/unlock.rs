@@ -0,0 +1,99 @@
+//! Unlock dialog - PIN prompt guarding configuration dialogs
+
+use egui::Context;
+
+use crate::core::AppState;
+use crate::ui::dialogs::DialogState;
+use crate::ui::theme::Theme;
+
+/// Ask for the settings PIN. Sets `unlocked` on success so the requested dialog opens.
+pub fn render(ctx: &Context, state: &AppState, unlocked: &mut bool, dialog: &mut DialogState) {
+    let mut open = true;
+
+    egui::Window::new("Locked")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(320.0)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.label("Configuration is locked. Enter the PIN to continue.");
+            ui.add_space(12.0);
+
+            if pin_prompt(ui, state) {
+                *unlocked = true;
+            }
+
+            ui.add_space(8.0);
+            if ui.button("Cancel").clicked() {
+                *dialog = DialogState::None;
+            }
+        });
+
+    if !open {
+        *dialog = DialogState::None;
+    }
+}
+
+/// Record for this frame whether configuration is locked, for widgets that
+/// change it in place rather than through a dialog
+pub fn publish_locked(ctx: &Context, locked: bool) {
+    ctx.data_mut(|d| d.insert_temp(egui::Id::new("config_locked"), locked));
+}
+
+/// Whether configuration is locked, as recorded by `publish_locked`
+pub fn is_locked(ctx: &Context) -> bool {
+    ctx.data(|d| d.get_temp(egui::Id::new("config_locked")))
+        .unwrap_or(false)
+}
+
+/// PIN entry field with an Unlock button. Returns true when the PIN is correct.
+pub fn pin_prompt(ui: &mut egui::Ui, state: &AppState) -> bool {
+    let pin_id = egui::Id::new("unlock_pin");
+    let error_id = egui::Id::new("unlock_pin_error");
+    let mut pin: String = ui.data_mut(|d| d.get_temp(pin_id).unwrap_or_default());
+    let mut failed: bool = ui.data_mut(|d| d.get_temp(error_id).unwrap_or_default());
+    let mut unlocked = false;
+
+    ui.horizontal(|ui| {
+        let response = ui.add(
+            egui::TextEdit::singleline(&mut pin)
+                .password(true)
+                .hint_text("PIN")
+                .desired_width(160.0),
+        );
+        let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+
+        if ui.button("Unlock").clicked() || submitted {
+            let valid = state
+                .settings
+                .read()
+                .map(|s| {
+                    s.settings_lock
+                        .as_ref()
+                        .is_none_or(|lock| lock.verify(&pin))
+                })
+                .unwrap_or(false);
+            if valid {
+                unlocked = true;
+            } else {
+                failed = true;
+            }
+            pin.clear();
+        }
+    });
+
+    if failed {
+        ui.label(egui::RichText::new("Incorrect PIN").color(Theme::error()));
+    }
+
+    if unlocked {
+        failed = false;
+    }
+    ui.data_mut(|d| {
+        d.insert_temp(pin_id, pin);
+        d.insert_temp(error_id, failed);
+    });
+
+    unlocked
+}
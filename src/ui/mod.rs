@@ -4,6 +4,8 @@ mod app;
 mod components;
 mod dialogs;
 mod panels;
+mod search;
+mod switcher;
 mod theme;
 
 pub use app::MultiInstanceApp;
@@ -1,10 +1,13 @@
 //! Dashboard panel - Overview of system resources and instances
 
-use egui::{Color32, Ui};
+use egui::Ui;
 
 use crate::core::resource::format_bytes;
-use crate::core::AppState;
-use crate::ui::components::{InstanceCard, ResourceBar};
+use crate::core::rolling_restart::RollingRestartTarget;
+use crate::core::settings::ViewMode;
+use crate::core::window_capture::RegionCapture;
+use crate::core::{AppState, InstanceId};
+use crate::ui::components::{ExecutableIcon, InstanceCard, ResourceBar, Skeleton};
 use crate::ui::theme::Theme;
 
 /// Section header helper
@@ -13,14 +16,14 @@ fn section_header(ui: &mut Ui, icon: &str, title: &str) {
         ui.label(
             egui::RichText::new(icon)
                 .size(18.0)
-                .color(Theme::PRIMARY_LIGHT),
+                .color(Theme::primary_light()),
         );
         ui.add_space(10.0);
         ui.label(
             egui::RichText::new(title)
                 .size(18.0)
                 .strong()
-                .color(Color32::WHITE),
+                .color(Theme::text_primary()),
         );
     });
     ui.add_space(14.0);
@@ -42,6 +45,9 @@ pub fn render(ui: &mut Ui, state: &mut AppState, show_system_resources: bool) {
             render_quick_launch(ui, state);
             ui.add_space(24.0);
 
+            // Pinned window regions, when any running instance has one
+            render_pinned_regions(ui, state);
+
             // Active Instances Grid
             render_active_instances(ui, state);
 
@@ -58,9 +64,9 @@ fn render_system_resources(ui: &mut Ui, state: &AppState) {
     ui.horizontal(|ui| {
         // CPU Card
         egui::Frame::none()
-            .fill(Theme::BG_SECONDARY)
+            .fill(Theme::bg_secondary())
             .rounding(egui::Rounding::same(12.0))
-            .stroke(egui::Stroke::new(1.0, Theme::BORDER_LIGHT))
+            .stroke(egui::Stroke::new(1.0, Theme::border_light()))
             .inner_margin(egui::Margin::same(20.0))
             .show(ui, |ui| {
                 ui.set_width(280.0);
@@ -76,19 +82,19 @@ fn render_system_resources(ui: &mut Ui, state: &AppState) {
                             egui::RichText::new("CPU")
                                 .size(16.0)
                                 .strong()
-                                .color(Theme::TEXT_PRIMARY),
+                                .color(Theme::text_primary()),
                         );
                         ui.add_space(4.0);
                         ui.label(
                             egui::RichText::new(&resources.cpu_name)
                                 .size(11.0)
-                                .color(Theme::TEXT_MUTED),
+                                .color(Theme::text_muted()),
                         );
                         ui.add_space(8.0);
                         ui.label(
                             egui::RichText::new(format!("{} cores", resources.cpu_cores))
                                 .size(12.0)
-                                .color(Theme::TEXT_SECONDARY),
+                                .color(Theme::text_secondary()),
                         );
                     });
                 });
@@ -105,7 +111,7 @@ fn render_system_resources(ui: &mut Ui, state: &AppState) {
                         ui.label(
                             egui::RichText::new(format!("+{}", resources.cpu_per_core.len() - 12))
                                 .size(10.0)
-                                .color(Theme::TEXT_MUTED),
+                                .color(Theme::text_muted()),
                         );
                     }
                 });
@@ -115,9 +121,9 @@ fn render_system_resources(ui: &mut Ui, state: &AppState) {
 
         // Memory Card
         egui::Frame::none()
-            .fill(Theme::BG_SECONDARY)
+            .fill(Theme::bg_secondary())
             .rounding(egui::Rounding::same(12.0))
-            .stroke(egui::Stroke::new(1.0, Theme::BORDER_LIGHT))
+            .stroke(egui::Stroke::new(1.0, Theme::border_light()))
             .inner_margin(egui::Margin::same(20.0))
             .show(ui, |ui| {
                 ui.set_width(260.0);
@@ -133,7 +139,7 @@ fn render_system_resources(ui: &mut Ui, state: &AppState) {
                             egui::RichText::new("Memory")
                                 .size(16.0)
                                 .strong()
-                                .color(Theme::TEXT_PRIMARY),
+                                .color(Theme::text_primary()),
                         );
                         ui.add_space(4.0);
                         ui.label(
@@ -143,7 +149,7 @@ fn render_system_resources(ui: &mut Ui, state: &AppState) {
                                 resources.total_memory_string()
                             ))
                             .size(12.0)
-                            .color(Theme::TEXT_SECONDARY),
+                            .color(Theme::text_secondary()),
                         );
                         ui.add_space(4.0);
                         ui.label(
@@ -152,7 +158,7 @@ fn render_system_resources(ui: &mut Ui, state: &AppState) {
                                 resources.available_memory_string()
                             ))
                             .size(11.0)
-                            .color(Theme::TEXT_MUTED),
+                            .color(Theme::text_muted()),
                         );
                     });
                 });
@@ -164,7 +170,7 @@ fn render_system_resources(ui: &mut Ui, state: &AppState) {
                         ui.label(
                             egui::RichText::new("Swap:")
                                 .size(11.0)
-                                .color(Theme::TEXT_MUTED),
+                                .color(Theme::text_muted()),
                         );
                         ui.add_space(8.0);
                         ResourceBar::mini(ui, resources.swap_percent() / 100.0);
@@ -176,9 +182,9 @@ fn render_system_resources(ui: &mut Ui, state: &AppState) {
 
         // Network Card
         egui::Frame::none()
-            .fill(Theme::BG_SECONDARY)
+            .fill(Theme::bg_secondary())
             .rounding(egui::Rounding::same(12.0))
-            .stroke(egui::Stroke::new(1.0, Theme::BORDER_LIGHT))
+            .stroke(egui::Stroke::new(1.0, Theme::border_light()))
             .inner_margin(egui::Margin::same(20.0))
             .show(ui, |ui| {
                 ui.set_min_width(200.0);
@@ -187,7 +193,7 @@ fn render_system_resources(ui: &mut Ui, state: &AppState) {
                     egui::RichText::new("Network")
                         .size(16.0)
                         .strong()
-                        .color(Theme::TEXT_PRIMARY),
+                        .color(Theme::text_primary()),
                 );
                 ui.add_space(12.0);
 
@@ -195,21 +201,21 @@ fn render_system_resources(ui: &mut Ui, state: &AppState) {
                     ui.label(
                         egui::RichText::new(&iface.name)
                             .size(12.0)
-                            .color(Theme::TEXT_SECONDARY),
+                            .color(Theme::text_secondary()),
                     );
                     ui.horizontal(|ui| {
-                        ui.label(egui::RichText::new("↓").size(12.0).color(Theme::SUCCESS));
+                        ui.label(egui::RichText::new("↓").size(12.0).color(Theme::success()));
                         ui.label(
                             egui::RichText::new(format!("{}/s", format_bytes(iface.rx_rate)))
                                 .size(11.0)
-                                .color(Theme::TEXT_MUTED),
+                                .color(Theme::text_muted()),
                         );
                         ui.add_space(12.0);
-                        ui.label(egui::RichText::new("↑").size(12.0).color(Theme::INFO));
+                        ui.label(egui::RichText::new("↑").size(12.0).color(Theme::info()));
                         ui.label(
                             egui::RichText::new(format!("{}/s", format_bytes(iface.tx_rate)))
                                 .size(11.0)
-                                .color(Theme::TEXT_MUTED),
+                                .color(Theme::text_muted()),
                         );
                     });
                     ui.add_space(8.0);
@@ -230,7 +236,7 @@ fn render_system_resources(ui: &mut Ui, state: &AppState) {
                 ui.label(
                     egui::RichText::new(format!("Uptime: {}", uptime_str))
                         .size(11.0)
-                        .color(Theme::TEXT_MUTED),
+                        .color(Theme::text_muted()),
                 );
             });
     });
@@ -241,18 +247,18 @@ fn render_quick_launch(ui: &mut Ui, state: &mut AppState) {
         ui.label(
             egui::RichText::new("⚡")
                 .size(18.0)
-                .color(Theme::PRIMARY_LIGHT),
+                .color(Theme::primary_light()),
         );
         ui.add_space(10.0);
         ui.label(
             egui::RichText::new("Quick Launch")
                 .size(18.0)
                 .strong()
-                .color(Color32::WHITE),
+                .color(Theme::text_primary()),
         );
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
             let add_btn = egui::Button::new("+ Add")
-                .fill(Theme::BG_TERTIARY)
+                .fill(Theme::bg_tertiary())
                 .rounding(egui::Rounding::same(6.0));
             if ui.add(add_btn).clicked() {
                 // Would open file picker
@@ -266,29 +272,31 @@ fn render_quick_launch(ui: &mut Ui, state: &mut AppState) {
         quick_launch.iter().cloned().collect()
     };
 
-    if quick_launch_items.is_empty() {
+    if quick_launch_items.is_empty() && !state.is_data_loaded() {
+        Skeleton::cards(ui, 4, egui::vec2(160.0, 60.0));
+    } else if quick_launch_items.is_empty() {
         egui::Frame::none()
-            .fill(Theme::BG_SECONDARY)
+            .fill(Theme::bg_secondary())
             .rounding(egui::Rounding::same(12.0))
-            .stroke(egui::Stroke::new(1.0, Theme::BORDER_LIGHT))
+            .stroke(egui::Stroke::new(1.0, Theme::border_light()))
             .inner_margin(egui::Margin::same(32.0))
             .show(ui, |ui| {
                 ui.vertical_centered(|ui| {
                     ui.label(
                         egui::RichText::new("⚡")
                             .size(32.0)
-                            .color(Theme::TEXT_MUTED),
+                            .color(Theme::text_muted()),
                     );
                     ui.add_space(12.0);
                     ui.label(
                         egui::RichText::new("No quick launch items")
                             .size(14.0)
-                            .color(Theme::TEXT_SECONDARY),
+                            .color(Theme::text_secondary()),
                     );
                     ui.label(
                         egui::RichText::new("Add your favorite apps for one-click launching")
                             .size(12.0)
-                            .color(Theme::TEXT_MUTED),
+                            .color(Theme::text_muted()),
                     );
                 });
             });
@@ -297,22 +305,26 @@ fn render_quick_launch(ui: &mut Ui, state: &mut AppState) {
         ui.horizontal_wrapped(|ui| {
             for (idx, config) in quick_launch_items.iter().enumerate() {
                 egui::Frame::none()
-                    .fill(Theme::BG_SECONDARY)
+                    .fill(Theme::bg_secondary())
                     .rounding(egui::Rounding::same(10.0))
-                    .stroke(egui::Stroke::new(1.0, Theme::BORDER_LIGHT))
+                    .stroke(egui::Stroke::new(1.0, Theme::border_light()))
                     .inner_margin(egui::Margin::same(16.0))
                     .show(ui, |ui| {
                         ui.horizontal(|ui| {
+                            if let Some(icon) = state.executable_icon(&config.executable_path) {
+                                ExecutableIcon::show(ui, &config.executable_path, &icon, 24.0);
+                                ui.add_space(6.0);
+                            }
                             ui.label(
                                 egui::RichText::new(&config.name)
                                     .size(14.0)
                                     .strong()
-                                    .color(Theme::TEXT_PRIMARY),
+                                    .color(Theme::text_primary()),
                             );
                             ui.add_space(12.0);
                             let launch_btn =
-                                egui::Button::new(egui::RichText::new("▶").color(Theme::SUCCESS))
-                                    .fill(Theme::SUCCESS.linear_multiply(0.15))
+                                egui::Button::new(egui::RichText::new("▶").color(Theme::success()))
+                                    .fill(Theme::success().linear_multiply(0.15))
                                     .rounding(egui::Rounding::same(6.0))
                                     .min_size(egui::vec2(32.0, 28.0));
                             if ui.add(launch_btn).on_hover_text("Launch").clicked() {
@@ -333,6 +345,120 @@ fn render_quick_launch(ui: &mut Ui, state: &mut AppState) {
     }
 }
 
+/// Widest a pinned region thumbnail is drawn before it is scaled down
+const MAX_THUMBNAIL_WIDTH: f32 = 320.0;
+
+/// Live thumbnails of each running instance's pinned window region
+fn render_pinned_regions(ui: &mut Ui, state: &AppState) {
+    let mut pinned: Vec<(InstanceId, String)> = state
+        .instances
+        .read()
+        .map(|instances| {
+            instances
+                .values()
+                .filter(|i| i.status.is_active() && i.config.pinned_region.is_some())
+                .map(|i| (i.id, i.display_name().to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+    if pinned.is_empty() {
+        return;
+    }
+    pinned.sort_by_key(|(_, name)| name.to_lowercase());
+    let captures = state
+        .region_captures
+        .read()
+        .map(|c| c.clone())
+        .unwrap_or_default();
+
+    section_header(ui, "📌", "Pinned Regions");
+
+    ui.horizontal_wrapped(|ui| {
+        for (id, name) in pinned {
+            egui::Frame::none()
+                .fill(Theme::bg_secondary())
+                .rounding(egui::Rounding::same(8.0))
+                .stroke(egui::Stroke::new(1.0, Theme::border_light()))
+                .inner_margin(egui::Margin::same(8.0))
+                .show(ui, |ui| {
+                    ui.vertical(|ui| {
+                        ui.label(
+                            egui::RichText::new(&name)
+                                .size(12.0)
+                                .strong()
+                                .color(Theme::text_primary()),
+                        );
+                        ui.add_space(4.0);
+                        match captures.get(&id) {
+                            None => {
+                                ui.label(
+                                    egui::RichText::new("Waiting for first capture…")
+                                        .size(11.0)
+                                        .color(Theme::text_muted()),
+                                );
+                            }
+                            Some(RegionCapture {
+                                image: Err(error), ..
+                            }) => {
+                                ui.label(
+                                    egui::RichText::new(error)
+                                        .size(11.0)
+                                        .color(Theme::warning()),
+                                );
+                            }
+                            Some(capture) => {
+                                if let Some(texture) =
+                                    capture_texture(ui.ctx(), "pinned_region", id, capture)
+                                {
+                                    let size = texture.size_vec2();
+                                    let scale = (MAX_THUMBNAIL_WIDTH / size.x).min(1.0);
+                                    ui.image((texture.id(), size * scale))
+                                        .on_hover_text(format!(
+                                            "Captured at {}",
+                                            capture.captured_at.format("%H:%M:%S")
+                                        ));
+                                }
+                            }
+                        }
+                    });
+                });
+            ui.add_space(8.0);
+        }
+    });
+    ui.add_space(24.0);
+}
+
+/// Upload a capture to the GPU once and reuse the texture until a newer one arrives.
+/// `kind` keeps the textures of different captures of an instance apart.
+pub fn capture_texture(
+    ctx: &egui::Context,
+    kind: &str,
+    id: InstanceId,
+    capture: &RegionCapture,
+) -> Option<egui::TextureHandle> {
+    let image = capture.image.as_ref().ok()?;
+    let key = egui::Id::new((kind, "texture", id.0));
+    let cached =
+        ctx.data(|d| d.get_temp::<(chrono::DateTime<chrono::Local>, egui::TextureHandle)>(key));
+    if let Some((captured_at, texture)) = cached {
+        if captured_at == capture.captured_at {
+            return Some(texture);
+        }
+    }
+
+    let pixels = egui::ColorImage::from_rgba_unmultiplied(
+        [image.width() as usize, image.height() as usize],
+        image.as_raw(),
+    );
+    let texture = ctx.load_texture(
+        format!("{}_{}", kind, id.0),
+        pixels,
+        egui::TextureOptions::LINEAR,
+    );
+    ctx.data_mut(|d| d.insert_temp(key, (capture.captured_at, texture.clone())));
+    Some(texture)
+}
+
 fn render_active_instances(ui: &mut Ui, state: &mut AppState) {
     let active_count = {
         let instances = state.instances.read().unwrap();
@@ -343,27 +469,27 @@ fn render_active_instances(ui: &mut Ui, state: &mut AppState) {
         ui.label(
             egui::RichText::new("▣")
                 .size(18.0)
-                .color(Theme::PRIMARY_LIGHT),
+                .color(Theme::primary_light()),
         );
         ui.add_space(10.0);
         ui.label(
             egui::RichText::new("Active Instances")
                 .size(18.0)
                 .strong()
-                .color(Color32::WHITE),
+                .color(Theme::text_primary()),
         );
         ui.add_space(8.0);
 
         // Count badge
         egui::Frame::none()
-            .fill(Theme::PRIMARY.linear_multiply(0.2))
+            .fill(Theme::primary().linear_multiply(0.2))
             .rounding(egui::Rounding::same(10.0))
             .inner_margin(egui::Margin::symmetric(10.0, 4.0))
             .show(ui, |ui| {
                 ui.label(
                     egui::RichText::new(format!("{}", active_count))
                         .size(13.0)
-                        .color(Theme::PRIMARY_LIGHT),
+                        .color(Theme::primary_light()),
                 );
             });
     });
@@ -371,9 +497,9 @@ fn render_active_instances(ui: &mut Ui, state: &mut AppState) {
 
     if active_count == 0 {
         egui::Frame::none()
-            .fill(Theme::BG_SECONDARY)
+            .fill(Theme::bg_secondary())
             .rounding(egui::Rounding::same(12.0))
-            .stroke(egui::Stroke::new(1.0, Theme::BORDER_LIGHT))
+            .stroke(egui::Stroke::new(1.0, Theme::border_light()))
             .inner_margin(egui::Margin::same(40.0))
             .show(ui, |ui| {
                 ui.vertical_centered(|ui| {
@@ -382,24 +508,26 @@ fn render_active_instances(ui: &mut Ui, state: &mut AppState) {
                     ui.label(
                         egui::RichText::new("No active instances")
                             .size(16.0)
-                            .color(Theme::TEXT_SECONDARY),
+                            .color(Theme::text_secondary()),
                     );
                     ui.add_space(4.0);
                     ui.label(
                         egui::RichText::new("Create a new instance to get started")
                             .size(13.0)
-                            .color(Theme::TEXT_MUTED),
+                            .color(Theme::text_muted()),
                     );
                 });
             });
     } else {
-        // Grid layout - collect IDs first to avoid borrow issues
+        // Cards in the chosen view mode - collect IDs first to avoid borrow issues
         let mut pending_action: Option<(
             crate::core::InstanceId,
             crate::ui::components::CardAction,
         )> = None;
+        // The board needs every status, so active instances show as a grid there
+        let view_mode = state.settings.read().unwrap().view_mode;
 
-        ui.horizontal_wrapped(|ui| {
+        let render_cards = |ui: &mut Ui| {
             let instances = state.instances.read().unwrap();
             let active_ids: Vec<_> = instances
                 .iter()
@@ -407,6 +535,7 @@ fn render_active_instances(ui: &mut Ui, state: &mut AppState) {
                 .map(|(id, _)| *id)
                 .collect();
             drop(instances);
+            let display = state.settings.read().unwrap().card_display;
 
             for id in active_ids {
                 let instances = state.instances.read().unwrap();
@@ -414,7 +543,18 @@ fn render_active_instances(ui: &mut Ui, state: &mut AppState) {
                     let instance = instance.clone();
                     drop(instances);
 
-                    let card_response = InstanceCard::grid(ui, &instance);
+                    let icon = state.executable_icon(&instance.config.executable_path);
+                    let card_response = match view_mode {
+                        ViewMode::List => {
+                            InstanceCard::list(ui, &instance, "", &display, icon.as_deref())
+                        }
+                        ViewMode::Compact => {
+                            InstanceCard::compact(ui, &instance, "", icon.as_deref())
+                        }
+                        ViewMode::Grid | ViewMode::Board => {
+                            InstanceCard::grid(ui, &instance, "", &display, icon.as_deref())
+                        }
+                    };
 
                     if let Some(action) = card_response.action {
                         pending_action = Some((id, action));
@@ -423,7 +563,15 @@ fn render_active_instances(ui: &mut Ui, state: &mut AppState) {
                 }
                 ui.add_space(8.0);
             }
-        });
+        };
+        match view_mode {
+            ViewMode::List | ViewMode::Compact => {
+                ui.vertical(render_cards);
+            }
+            ViewMode::Grid | ViewMode::Board => {
+                ui.horizontal_wrapped(render_cards);
+            }
+        }
 
         if let Some((id, action)) = pending_action {
             use crate::ui::components::CardAction;
@@ -453,6 +601,62 @@ fn render_active_instances(ui: &mut Ui, state: &mut AppState) {
                         tracing::error!("Failed to restart instance: {}", e);
                     }
                 }
+                CardAction::RestartGroup(group) => {
+                    if let Err(e) = state.start_rolling_restart(RollingRestartTarget::Group(group))
+                    {
+                        tracing::error!("Failed to start rolling restart: {}", e);
+                    }
+                }
+                CardAction::Kill => {
+                    if let Err(e) = state.kill_instance(id) {
+                        tracing::error!("Failed to kill instance: {}", e);
+                    }
+                }
+                CardAction::RunMacro(index) => {
+                    if let Err(e) = state.run_macro(id, index) {
+                        tracing::error!("Failed to run macro: {}", e);
+                    }
+                }
+                CardAction::SelectVariant(variant) => {
+                    if let Err(e) = state.set_active_variant(id, variant) {
+                        tracing::error!("Failed to select argument variant: {}", e);
+                    }
+                }
+                CardAction::Rename(name) => {
+                    if let Err(e) = state.rename_instance(id, name) {
+                        tracing::error!("Failed to rename instance: {}", e);
+                    }
+                }
+                CardAction::ShowWindow => {
+                    if let Err(e) = state.show_instance_window(id) {
+                        tracing::error!("Failed to show instance window: {}", e);
+                    }
+                }
+                CardAction::CaptureScreenshot => {
+                    if let Err(e) = state.capture_screenshot(id) {
+                        tracing::error!("Failed to capture screenshot: {}", e);
+                    }
+                }
+                CardAction::CompressData => {
+                    if let Err(e) = state.compress_instance_data(id) {
+                        tracing::error!("Failed to compress instance data: {}", e);
+                    }
+                }
+                CardAction::StopAndCompress => {
+                    if let Err(e) = state.stop_and_compress(id) {
+                        tracing::error!("Failed to stop and compress instance: {}", e);
+                    }
+                }
+                CardAction::DecompressData => {
+                    if let Err(e) = state.decompress_instance_data(id) {
+                        tracing::error!("Failed to decompress instance data: {}", e);
+                    }
+                }
+                CardAction::SetPinned(pinned) => {
+                    if let Err(e) = state.set_pinned(id, pinned) {
+                        tracing::error!("Failed to pin instance: {}", e);
+                    }
+                }
                 _ => {}
             }
         }
@@ -475,7 +679,7 @@ fn render_active_instances(ui: &mut Ui, state: &mut AppState) {
 
         // Summary bar
         egui::Frame::none()
-            .fill(Theme::BG_SECONDARY.linear_multiply(0.6))
+            .fill(Theme::bg_secondary().linear_multiply(0.6))
             .rounding(egui::Rounding::same(8.0))
             .inner_margin(egui::Margin::symmetric(16.0, 10.0))
             .show(ui, |ui| {
@@ -483,20 +687,20 @@ fn render_active_instances(ui: &mut Ui, state: &mut AppState) {
                     ui.label(
                         egui::RichText::new("Total Resource Usage:")
                             .size(12.0)
-                            .color(Theme::TEXT_MUTED),
+                            .color(Theme::text_muted()),
                     );
                     ui.add_space(16.0);
 
                     // CPU badge
                     egui::Frame::none()
-                        .fill(Theme::BG_TERTIARY)
+                        .fill(Theme::bg_tertiary())
                         .rounding(egui::Rounding::same(4.0))
                         .inner_margin(egui::Margin::symmetric(8.0, 4.0))
                         .show(ui, |ui| {
                             ui.label(
                                 egui::RichText::new(format!("CPU {:.1}%", total_cpu))
                                     .size(11.0)
-                                    .color(Theme::TEXT_SECONDARY),
+                                    .color(Theme::text_secondary()),
                             );
                         });
 
@@ -504,7 +708,7 @@ fn render_active_instances(ui: &mut Ui, state: &mut AppState) {
 
                     // Memory badge
                     egui::Frame::none()
-                        .fill(Theme::BG_TERTIARY)
+                        .fill(Theme::bg_tertiary())
                         .rounding(egui::Rounding::same(4.0))
                         .inner_margin(egui::Margin::symmetric(8.0, 4.0))
                         .show(ui, |ui| {
@@ -514,7 +718,7 @@ fn render_active_instances(ui: &mut Ui, state: &mut AppState) {
                                     format_bytes(total_memory)
                                 ))
                                 .size(11.0)
-                                .color(Theme::TEXT_SECONDARY),
+                                .color(Theme::text_secondary()),
                             );
                         });
                 });
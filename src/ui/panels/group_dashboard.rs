@@ -0,0 +1,379 @@
+//! Group dashboard - Operations page for the instances of one group
+
+use std::time::{Duration, Instant};
+
+use chrono::Local;
+use egui::Ui;
+
+use crate::core::history::HistoryEntry;
+use crate::core::resource::format_bytes;
+use crate::core::rolling_restart::RollingRestartTarget;
+use crate::core::{AppState, InstanceId, InstanceStatus};
+use crate::ui::components::{ChartSeries, LineChart};
+use crate::ui::dialogs::DialogState;
+use crate::ui::panels::history::event_color;
+use crate::ui::theme::{Icons, Theme};
+
+/// Number of recent events listed
+const EVENT_LIMIT: usize = 20;
+
+/// How often the recent events are read again
+const EVENT_REFRESH: Duration = Duration::from_secs(5);
+
+/// The group whose dashboard is open, with its recently loaded events
+pub struct GroupDashboard {
+    pub group: String,
+    events: Vec<HistoryEntry>,
+    loaded_at: Option<Instant>,
+}
+
+impl GroupDashboard {
+    pub fn new(group: String) -> Self {
+        Self {
+            group,
+            events: Vec::new(),
+            loaded_at: None,
+        }
+    }
+}
+
+/// One member of the group, read once per frame
+struct Member {
+    id: InstanceId,
+    name: String,
+    status: InstanceStatus,
+    cpu_percent: f32,
+    memory_bytes: u64,
+    unhealthy: bool,
+    restarts: u32,
+}
+
+/// Render the dashboard of `page.group`. Returns false when the user goes
+/// back to the instance list.
+pub fn render(
+    ui: &mut Ui,
+    state: &mut AppState,
+    page: &mut GroupDashboard,
+    dialog: &mut DialogState,
+) -> bool {
+    let group = page.group.clone();
+    let mut members: Vec<Member> = state
+        .instances
+        .read()
+        .map(|instances| {
+            instances
+                .values()
+                .filter(|i| i.config.group.as_deref() == Some(group.as_str()))
+                .map(|i| Member {
+                    id: i.id,
+                    name: i.display_name().to_string(),
+                    status: i.status,
+                    cpu_percent: i.resource_usage.cpu_percent,
+                    memory_bytes: i.resource_usage.memory_bytes,
+                    unhealthy: i.health.as_ref().is_some_and(|h| h.is_unhealthy()),
+                    restarts: i.restart_count,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    members.sort_by_key(|m| m.name.to_lowercase());
+    let running = members.iter().filter(|m| m.status.is_active()).count();
+
+    if page.loaded_at.is_none_or(|t| t.elapsed() >= EVENT_REFRESH) {
+        page.loaded_at = Some(Instant::now());
+        match state.group_history(&group, EVENT_LIMIT) {
+            Ok(events) => page.events = events,
+            Err(e) => tracing::warn!("Failed to load history of group '{}': {}", group, e),
+        }
+    }
+
+    let mut open = true;
+    ui.horizontal(|ui| {
+        if ui.button("← Instances").clicked() {
+            open = false;
+        }
+        ui.add_space(8.0);
+        ui.label(
+            egui::RichText::new(&group)
+                .size(20.0)
+                .strong()
+                .color(Theme::group_color(&group)),
+        );
+        ui.label(
+            egui::RichText::new(format!("{}/{} running", running, members.len()))
+                .color(Theme::text_secondary()),
+        );
+
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui
+                .add_enabled(
+                    running > 0,
+                    egui::Button::new(format!("{} Rolling restart", Icons::RESTART)),
+                )
+                .on_hover_text("Restart the running members one at a time")
+                .clicked()
+            {
+                if let Err(e) =
+                    state.start_rolling_restart(RollingRestartTarget::Group(group.clone()))
+                {
+                    tracing::error!("Failed to restart group '{}': {}", group, e);
+                }
+            }
+            if ui
+                .add_enabled(
+                    running > 0,
+                    egui::Button::new(format!("{} Stop all", Icons::STOP)),
+                )
+                .clicked()
+            {
+                if let Err(e) = state.stop_group(Some(&group)) {
+                    tracing::error!("Failed to stop group '{}': {}", group, e);
+                }
+            }
+            if ui
+                .add_enabled(
+                    running < members.len(),
+                    egui::Button::new(format!("{} Start all", Icons::PLAY)),
+                )
+                .clicked()
+            {
+                if let Err(e) = state.start_group(Some(&group)) {
+                    tracing::error!("Failed to start group '{}': {}", group, e);
+                }
+            }
+        });
+    });
+    ui.add_space(12.0);
+
+    if members.is_empty() {
+        ui.label(
+            egui::RichText::new("No instances are in this group anymore")
+                .color(Theme::text_muted()),
+        );
+        return open;
+    }
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        render_health_summary(ui, &members);
+        ui.add_space(12.0);
+        card(ui, "Resources", |ui| {
+            render_usage_charts(ui, state, &group, &members)
+        });
+        ui.add_space(12.0);
+        card(ui, "Members", |ui| render_members(ui, &members, dialog));
+        ui.add_space(12.0);
+        card(ui, "Recent events", |ui| {
+            render_events(ui, &page.events, &members)
+        });
+    });
+    open
+}
+
+fn card(ui: &mut Ui, title: &str, add_contents: impl FnOnce(&mut Ui)) {
+    egui::Frame::none()
+        .fill(Theme::bg_secondary())
+        .rounding(egui::Rounding::same(10.0))
+        .inner_margin(egui::Margin::same(14.0))
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            ui.label(
+                egui::RichText::new(title)
+                    .size(15.0)
+                    .strong()
+                    .color(Theme::text_primary()),
+            );
+            ui.add_space(8.0);
+            add_contents(ui);
+        });
+}
+
+/// Counts of members by state, with crashes and failed health checks highlighted
+fn render_health_summary(ui: &mut Ui, members: &[Member]) {
+    let count = |f: fn(&Member) -> bool| members.iter().filter(|m| f(m)).count();
+    let running = count(|m| m.status.is_active());
+    let crashed = count(|m| m.status == InstanceStatus::Crashed);
+    let unhealthy = count(|m| m.unhealthy);
+    let stopped = members.len() - running - crashed;
+    let restarts: u32 = members.iter().map(|m| m.restarts).sum();
+
+    ui.horizontal_wrapped(|ui| {
+        stat(ui, "Running", running.to_string(), Theme::success());
+        stat(ui, "Stopped", stopped.to_string(), Theme::text_secondary());
+        stat(
+            ui,
+            "Crashed",
+            crashed.to_string(),
+            if crashed > 0 {
+                Theme::error()
+            } else {
+                Theme::text_secondary()
+            },
+        );
+        stat(
+            ui,
+            "Unhealthy",
+            unhealthy.to_string(),
+            if unhealthy > 0 {
+                Theme::warning()
+            } else {
+                Theme::text_secondary()
+            },
+        );
+        stat(
+            ui,
+            "Restarts",
+            restarts.to_string(),
+            Theme::text_secondary(),
+        );
+    });
+}
+
+fn stat(ui: &mut Ui, label: &str, value: String, color: egui::Color32) {
+    egui::Frame::none()
+        .fill(Theme::bg_secondary())
+        .rounding(egui::Rounding::same(8.0))
+        .inner_margin(egui::Margin::symmetric(16.0, 10.0))
+        .show(ui, |ui| {
+            ui.vertical(|ui| {
+                ui.label(egui::RichText::new(value).size(22.0).strong().color(color));
+                ui.label(
+                    egui::RichText::new(label)
+                        .size(12.0)
+                        .color(Theme::text_muted()),
+                );
+            });
+        });
+}
+
+/// CPU and memory of all running members added up
+fn render_usage_charts(ui: &mut Ui, state: &AppState, group: &str, members: &[Member]) {
+    let cpu_now: f32 = members.iter().map(|m| m.cpu_percent).sum();
+    let memory_now: u64 = members.iter().map(|m| m.memory_bytes).sum();
+    let history = state.group_usage_history(group);
+    if history.is_empty() {
+        ui.label(
+            egui::RichText::new("No running members to chart")
+                .size(12.0)
+                .color(Theme::text_muted()),
+        );
+        return;
+    }
+    let samples = history.samples();
+    let scroll_id = egui::Id::new(("group_usage_charts", group));
+
+    ui.label(
+        egui::RichText::new(format!("CPU · {:.0}% now", cpu_now))
+            .size(12.0)
+            .color(Theme::text_secondary()),
+    );
+    let cpu = [ChartSeries {
+        label: "CPU",
+        color: Theme::primary_light(),
+        points: samples
+            .iter()
+            .map(|s| (s.time, s.cpu_percent as f64))
+            .collect(),
+    }];
+    LineChart::show(ui, scroll_id, &cpu, None, |v| format!("{:.0}%", v));
+
+    ui.add_space(6.0);
+    ui.label(
+        egui::RichText::new(format!("Memory · {} now", format_bytes(memory_now)))
+            .size(12.0)
+            .color(Theme::text_secondary()),
+    );
+    let memory = [ChartSeries {
+        label: "Memory",
+        color: Theme::success_light(),
+        points: samples
+            .iter()
+            .map(|s| (s.time, s.memory_bytes as f64))
+            .collect(),
+    }];
+    LineChart::show(ui, scroll_id, &memory, None, |v| format_bytes(v as u64));
+}
+
+fn render_members(ui: &mut Ui, members: &[Member], dialog: &mut DialogState) {
+    egui::Grid::new("group_members_grid")
+        .num_columns(4)
+        .spacing([16.0, 6.0])
+        .striped(true)
+        .show(ui, |ui| {
+            for member in members {
+                if ui
+                    .link(&member.name)
+                    .on_hover_text("Show instance details")
+                    .clicked()
+                {
+                    *dialog = DialogState::InstanceDetails(member.id);
+                }
+                ui.label(
+                    egui::RichText::new(member.status.label())
+                        .color(Theme::status_color(&member.status)),
+                );
+                if member.status.is_active() {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{} {:.0}%  {} {}",
+                            Icons::CPU,
+                            member.cpu_percent,
+                            Icons::MEMORY,
+                            format_bytes(member.memory_bytes)
+                        ))
+                        .color(Theme::text_secondary()),
+                    );
+                } else {
+                    ui.label("");
+                }
+                if member.unhealthy {
+                    ui.label(egui::RichText::new("Unhealthy").color(Theme::warning()));
+                } else {
+                    ui.label("");
+                }
+                ui.end_row();
+            }
+        });
+}
+
+fn render_events(ui: &mut Ui, events: &[HistoryEntry], members: &[Member]) {
+    if events.is_empty() {
+        ui.label(
+            egui::RichText::new("No events recorded yet")
+                .size(12.0)
+                .color(Theme::text_muted()),
+        );
+        return;
+    }
+    egui::Grid::new("group_events_grid")
+        .num_columns(4)
+        .spacing([16.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            for entry in events {
+                ui.label(
+                    egui::RichText::new(
+                        entry
+                            .time
+                            .with_timezone(&Local)
+                            .format("%Y-%m-%d %H:%M:%S")
+                            .to_string(),
+                    )
+                    .color(Theme::text_secondary()),
+                );
+                let name = members
+                    .iter()
+                    .find(|m| m.id == entry.instance_id)
+                    .map_or("Deleted instance", |m| m.name.as_str());
+                ui.label(name);
+                ui.label(
+                    egui::RichText::new(entry.event_label()).color(event_color(entry.event())),
+                );
+                ui.label(
+                    egui::RichText::new(entry.details.as_deref().unwrap_or(""))
+                        .small()
+                        .color(Theme::text_muted()),
+                );
+                ui.end_row();
+            }
+        });
+}
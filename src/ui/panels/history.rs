@@ -1,148 +1,112 @@
 //! History panel - View instance history and events
 
+use std::collections::HashMap;
+
+use chrono::{Datelike, Local};
 use egui::Ui;
 
-use crate::core::AppState;
+use crate::core::history::{self, HistoryEntry, HistoryEvent, HistoryFilter};
+use crate::core::resource::format_duration_secs;
+use crate::core::{AppState, InstanceId};
 use crate::ui::theme::Theme;
 
-pub fn render(ui: &mut Ui, state: &AppState) {
-    ui.heading("Instance History");
-    ui.add_space(8.0);
+/// Filters and the history rows loaded for them
+#[derive(Default)]
+pub struct HistoryView {
+    filter: HistoryFilter,
+    /// Filter the loaded rows match; None forces a reload
+    loaded_for: Option<HistoryFilter>,
+    entries: Vec<HistoryEntry>,
+    /// Every matching row is loaded
+    exhausted: bool,
+    /// Result of the last load, export or clear
+    message: Option<(String, bool)>,
+    confirm_clear: bool,
+}
+
+impl HistoryView {
+    /// Load the first page again, picking up new events
+    pub fn refresh(&mut self) {
+        self.loaded_for = None;
+    }
+
+    fn load_more(&mut self, state: &AppState) {
+        match state.history_page(&self.filter, self.entries.last()) {
+            Ok(page) => {
+                self.exhausted = page.len() < history::PAGE_SIZE;
+                self.entries.extend(page);
+            }
+            Err(e) => {
+                self.exhausted = true;
+                self.message = Some((format!("Failed to load history: {}", e), true));
+            }
+        }
+    }
+}
+
+pub fn render(ui: &mut Ui, state: &AppState, view: &mut HistoryView) {
+    let names: HashMap<InstanceId, String> = state
+        .instances
+        .read()
+        .map(|instances| {
+            instances
+                .values()
+                .map(|i| (i.id, i.display_name().to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
 
-    // This is a placeholder - would need to implement actual history tracking
     egui::Frame::none()
-        .fill(Theme::BG_SECONDARY)
+        .fill(Theme::bg_secondary())
         .rounding(egui::Rounding::same(8.0))
         .inner_margin(egui::Margin::same(16.0))
         .show(ui, |ui| {
-            ui.label(
-                egui::RichText::new("Recent Activity")
-                    .strong()
-                    .color(Theme::TEXT_PRIMARY),
-            );
+            ui.horizontal(|ui| {
+                ui.label(
+                    egui::RichText::new("Instance History")
+                        .strong()
+                        .color(Theme::text_primary()),
+                );
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    render_actions(ui, state, view);
+                });
+            });
 
-            ui.add_space(16.0);
+            ui.add_space(8.0);
+            render_filters(ui, &names, &mut view.filter);
 
-            // Get instances and show recent activity
-            let instances = state.instances.read().unwrap();
+            if view.loaded_for.as_ref() != Some(&view.filter) {
+                view.entries.clear();
+                view.exhausted = false;
+                view.loaded_for = Some(view.filter.clone());
+                view.load_more(state);
+            }
 
-            if instances.is_empty() {
-                ui.vertical_centered(|ui| {
-                    ui.label(egui::RichText::new("No history yet").color(Theme::TEXT_MUTED));
-                    ui.label(
-                        egui::RichText::new("Instance events will appear here")
-                            .small()
-                            .color(Theme::TEXT_MUTED),
-                    );
-                });
-            } else {
-                egui::ScrollArea::vertical()
-                    .max_height(400.0)
-                    .show(ui, |ui| {
-                        // Sort instances by last activity
-                        let mut sorted: Vec<_> = instances.values().collect();
-                        sorted.sort_by(|a, b| {
-                            let a_time = a.started_at.or(a.stopped_at);
-                            let b_time = b.started_at.or(b.stopped_at);
-                            b_time.cmp(&a_time)
-                        });
-
-                        for instance in sorted.iter().take(20) {
-                            egui::Frame::none()
-                                .fill(Theme::BG_TERTIARY)
-                                .rounding(egui::Rounding::same(4.0))
-                                .inner_margin(egui::Margin::same(8.0))
-                                .show(ui, |ui| {
-                                    ui.horizontal(|ui| {
-                                        // Status indicator
-                                        let color = Theme::status_color(&instance.status);
-                                        let (rect, _) = ui.allocate_exact_size(
-                                            egui::vec2(8.0, 8.0),
-                                            egui::Sense::hover(),
-                                        );
-                                        ui.painter().circle_filled(rect.center(), 4.0, color);
-
-                                        ui.add_space(8.0);
-
-                                        // Instance name
-                                        ui.label(
-                                            egui::RichText::new(instance.display_name()).strong(),
-                                        );
-
-                                        ui.add_space(8.0);
-
-                                        // Status
-                                        ui.label(
-                                            egui::RichText::new(instance.status.label())
-                                                .small()
-                                                .color(color),
-                                        );
-
-                                        // Timestamp
-                                        ui.with_layout(
-                                            egui::Layout::right_to_left(egui::Align::Center),
-                                            |ui| {
-                                                let time =
-                                                    instance.started_at.or(instance.stopped_at);
-                                                if let Some(time) = time {
-                                                    ui.label(
-                                                        egui::RichText::new(
-                                                            time.format("%Y-%m-%d %H:%M")
-                                                                .to_string(),
-                                                        )
-                                                        .small()
-                                                        .color(Theme::TEXT_MUTED),
-                                                    );
-                                                }
-                                            },
-                                        );
-                                    });
-
-                                    // Additional details
-                                    if instance.restart_count > 0 {
-                                        ui.horizontal(|ui| {
-                                            ui.add_space(16.0);
-                                            ui.label(
-                                                egui::RichText::new(format!(
-                                                    "Restarted {} times",
-                                                    instance.restart_count
-                                                ))
-                                                .small()
-                                                .color(Theme::TEXT_MUTED),
-                                            );
-                                        });
-                                    }
-
-                                    if let Some(ref error) = instance.last_error {
-                                        ui.horizontal(|ui| {
-                                            ui.add_space(16.0);
-                                            ui.label(
-                                                egui::RichText::new(error)
-                                                    .small()
-                                                    .color(Theme::ERROR),
-                                            );
-                                        });
-                                    }
-                                });
-
-                            ui.add_space(4.0);
-                        }
-                    });
+            if let Some((message, is_error)) = &view.message {
+                ui.add_space(4.0);
+                ui.label(egui::RichText::new(message).small().color(if *is_error {
+                    Theme::error()
+                } else {
+                    Theme::text_muted()
+                }));
             }
+
+            ui.add_space(12.0);
+            render_entries(ui, state, view, &names);
         });
 
     ui.add_space(16.0);
 
     // Statistics
     egui::Frame::none()
-        .fill(Theme::BG_SECONDARY)
+        .fill(Theme::bg_secondary())
         .rounding(egui::Rounding::same(8.0))
         .inner_margin(egui::Margin::same(16.0))
         .show(ui, |ui| {
             ui.label(
                 egui::RichText::new("Statistics")
                     .strong()
-                    .color(Theme::TEXT_PRIMARY),
+                    .color(Theme::text_primary()),
             );
 
             ui.add_space(16.0);
@@ -173,27 +137,61 @@ pub fn render(ui: &mut Ui, state: &AppState) {
                 stat_item(ui, "Profiles", &state.profile_count().to_string());
                 stat_item(ui, "Profile Launches", &total_launches.to_string());
             });
-        });
 
-    ui.add_space(16.0);
+            // How heavy each running instance really is, beyond momentary CPU
+            let mut heaviest: Vec<_> = instances
+                .values()
+                .filter(|i| i.status.is_active() && i.resource_usage.run_time_secs > 0)
+                .collect();
+            if !heaviest.is_empty() {
+                heaviest.sort_by_key(|i| std::cmp::Reverse(i.resource_usage.cpu_time_ms));
+                let total_cpu_ms: u64 = heaviest.iter().map(|i| i.resource_usage.cpu_time_ms).sum();
 
-    // Clear history button
-    ui.horizontal(|ui| {
-        if ui.button("Clear History").clicked() {
-            // Would clear history from database
-            tracing::info!("Clear history requested");
-        }
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    stat_item(
+                        ui,
+                        "Total CPU Time",
+                        &format_duration_secs(total_cpu_ms / 1000),
+                    );
+                });
 
-        if ui.button("Export History").clicked() {
-            // Would export history to file
-            tracing::info!("Export history requested");
-        }
-    });
+                ui.add_space(12.0);
+                egui::Grid::new("cpu_time_grid")
+                    .num_columns(4)
+                    .spacing([24.0, 4.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for header in ["Instance", "CPU Time", "Uptime", "Efficiency"] {
+                            ui.label(
+                                egui::RichText::new(header)
+                                    .small()
+                                    .color(Theme::text_muted()),
+                            );
+                        }
+                        ui.end_row();
+
+                        for instance in heaviest {
+                            let usage = &instance.resource_usage;
+                            ui.label(instance.display_name());
+                            ui.label(usage.cpu_time_string());
+                            ui.label(format_duration_secs(usage.run_time_secs));
+                            ui.label(
+                                usage
+                                    .cpu_efficiency()
+                                    .map(|e| format!("{:.1}%", e))
+                                    .unwrap_or_else(|| "-".to_string()),
+                            );
+                            ui.end_row();
+                        }
+                    });
+            }
+        });
 }
 
 fn stat_item(ui: &mut Ui, label: &str, value: &str) {
     egui::Frame::none()
-        .fill(Theme::BG_TERTIARY)
+        .fill(Theme::bg_tertiary())
         .rounding(egui::Rounding::same(4.0))
         .inner_margin(egui::Margin::symmetric(16.0, 8.0))
         .show(ui, |ui| {
@@ -202,9 +200,263 @@ fn stat_item(ui: &mut Ui, label: &str, value: &str) {
                     egui::RichText::new(value)
                         .size(24.0)
                         .strong()
-                        .color(Theme::PRIMARY_LIGHT),
+                        .color(Theme::primary_light()),
+                );
+                ui.label(
+                    egui::RichText::new(label)
+                        .small()
+                        .color(Theme::text_muted()),
                 );
-                ui.label(egui::RichText::new(label).small().color(Theme::TEXT_MUTED));
             });
         });
 }
+
+/// Refresh, export and clear buttons, right to left
+fn render_actions(ui: &mut Ui, state: &AppState, view: &mut HistoryView) {
+    if view.confirm_clear {
+        if ui.button("Cancel").clicked() {
+            view.confirm_clear = false;
+        }
+        let delete = egui::Button::new(
+            egui::RichText::new("Delete all history").color(egui::Color32::WHITE),
+        )
+        .fill(Theme::error());
+        if ui.add(delete).clicked() {
+            view.confirm_clear = false;
+            view.message = Some(match state.clear_history() {
+                Ok(()) => ("History cleared".to_string(), false),
+                Err(e) => (format!("Failed to clear history: {}", e), true),
+            });
+            view.refresh();
+        }
+        return;
+    }
+
+    if ui.button("Clear History").clicked() {
+        view.confirm_clear = true;
+    }
+    if ui
+        .button("Export CSV")
+        .on_hover_text("Save every event matching the filters")
+        .clicked()
+    {
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("CSV", &["csv"])
+            .set_file_name(format!("history-{}.csv", Local::now().format("%Y%m%d")))
+            .save_file()
+        {
+            view.message = Some(match state.export_history(&view.filter, &path) {
+                Ok(count) => (
+                    format!("Exported {} events to {}", count, path.display()),
+                    false,
+                ),
+                Err(e) => (format!("Failed to export history: {}", e), true),
+            });
+        }
+    }
+    if ui
+        .button("\u{27F3} Refresh")
+        .on_hover_text("Load events recorded since")
+        .clicked()
+    {
+        view.refresh();
+    }
+}
+
+fn render_filters(ui: &mut Ui, names: &HashMap<InstanceId, String>, filter: &mut HistoryFilter) {
+    ui.horizontal_wrapped(|ui| {
+        // Instance
+        let mut instances: Vec<(&InstanceId, &String)> = names.iter().collect();
+        instances.sort_by_key(|(_, name)| name.to_lowercase());
+        let selected = match filter.instance {
+            Some(id) => names
+                .get(&id)
+                .map_or("Deleted instance", |name| name.as_str()),
+            None => "All instances",
+        };
+        egui::ComboBox::from_id_salt("history_instance")
+            .width(180.0)
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut filter.instance, None, "All instances");
+                for (id, name) in instances {
+                    ui.selectable_value(&mut filter.instance, Some(*id), name);
+                }
+            });
+
+        ui.add_space(12.0);
+
+        // Date range
+        let today = Local::now().date_naive();
+        let mut ranged = filter.dates.is_some();
+        if ui.checkbox(&mut ranged, "Dates").changed() {
+            filter.dates = ranged.then_some((today, today));
+        }
+        if let Some((from, to)) = &mut filter.dates {
+            ui.add(egui_extras::DatePickerButton::new(from).id_salt("history_from"));
+            ui.label("to");
+            ui.add(egui_extras::DatePickerButton::new(to).id_salt("history_to"));
+        }
+        if ui
+            .small_button("Last night")
+            .on_hover_text("Yesterday and today")
+            .clicked()
+        {
+            filter.dates = Some((today.pred_opt().unwrap_or(today), today));
+        }
+        if ui.small_button("Last 7 days").clicked() {
+            let week_ago = today - chrono::Duration::days(6);
+            filter.dates = Some((week_ago, today));
+        }
+        if ui.small_button("This month").clicked() {
+            filter.dates = Some((today.with_day(1).unwrap_or(today), today));
+        }
+
+        // Set by clicking a launch ID in the list
+        if let Some(launch_id) = filter.launch_id.clone() {
+            ui.add_space(12.0);
+            ui.label(egui::RichText::new(format!("Launch {}", launch_id)).monospace());
+            if ui
+                .small_button("\u{2715}")
+                .on_hover_text("Show all launches")
+                .clicked()
+            {
+                filter.launch_id = None;
+            }
+        }
+    });
+
+    // Event types
+    ui.horizontal_wrapped(|ui| {
+        if ui
+            .selectable_label(filter.events.is_empty(), "All events")
+            .clicked()
+        {
+            filter.events.clear();
+        }
+        for event in HistoryEvent::all() {
+            let on = filter.events.contains(&event);
+            if ui.selectable_label(on, event.label()).clicked() {
+                if on {
+                    filter.events.retain(|e| *e != event);
+                } else {
+                    filter.events.push(event);
+                }
+            }
+        }
+    });
+}
+
+/// Rows loaded so far; scrolling to the end loads the next page
+fn render_entries(
+    ui: &mut Ui,
+    state: &AppState,
+    view: &mut HistoryView,
+    names: &HashMap<InstanceId, String>,
+) {
+    if view.entries.is_empty() {
+        ui.vertical_centered(|ui| {
+            ui.label(egui::RichText::new("No events").color(Theme::text_muted()));
+            ui.label(
+                egui::RichText::new(
+                    "Starts, stops, crashes and restarts matching the filters appear here",
+                )
+                .small()
+                .color(Theme::text_muted()),
+            );
+        });
+        return;
+    }
+
+    let mut load_more = false;
+    let mut show_launch = None;
+    egui::ScrollArea::vertical()
+        .max_height(420.0)
+        .auto_shrink([false, true])
+        .show(ui, |ui| {
+            egui::Grid::new("history_grid")
+                .num_columns(5)
+                .spacing([16.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    for header in ["Time", "Instance", "Launch", "Event", "Details"] {
+                        ui.label(
+                            egui::RichText::new(header)
+                                .small()
+                                .color(Theme::text_muted()),
+                        );
+                    }
+                    ui.end_row();
+
+                    for entry in &view.entries {
+                        ui.label(
+                            egui::RichText::new(
+                                entry
+                                    .time
+                                    .with_timezone(&Local)
+                                    .format("%Y-%m-%d %H:%M:%S")
+                                    .to_string(),
+                            )
+                            .color(Theme::text_secondary()),
+                        );
+                        match names.get(&entry.instance_id) {
+                            Some(name) => ui.label(name),
+                            None => ui.label(
+                                egui::RichText::new("Deleted instance").color(Theme::text_muted()),
+                            ),
+                        };
+                        match entry.launch_id {
+                            Some(ref launch_id) => {
+                                if ui
+                                    .link(egui::RichText::new(launch_id).monospace().small())
+                                    .on_hover_text(
+                                        "Show only this launch; search the output and \
+                                         MultiInstance logs for the same ID",
+                                    )
+                                    .clicked()
+                                {
+                                    show_launch = Some(launch_id.clone());
+                                }
+                            }
+                            None => {
+                                ui.label("");
+                            }
+                        }
+                        ui.label(
+                            egui::RichText::new(entry.event_label())
+                                .color(event_color(entry.event())),
+                        );
+                        ui.label(
+                            egui::RichText::new(entry.details.as_deref().unwrap_or(""))
+                                .small()
+                                .color(Theme::text_muted()),
+                        );
+                        ui.end_row();
+                    }
+                });
+
+            if !view.exhausted {
+                let loading = ui.label(
+                    egui::RichText::new("Loading more...")
+                        .small()
+                        .color(Theme::text_muted()),
+                );
+                load_more = ui.is_rect_visible(loading.rect);
+            }
+        });
+
+    if let Some(launch_id) = show_launch {
+        view.filter.launch_id = Some(launch_id);
+    } else if load_more {
+        view.load_more(state);
+    }
+}
+
+pub fn event_color(event: Option<HistoryEvent>) -> egui::Color32 {
+    match event {
+        Some(HistoryEvent::Crashed) => Theme::error(),
+        Some(HistoryEvent::Restarted | HistoryEvent::Killed) => Theme::warning(),
+        Some(HistoryEvent::Started | HistoryEvent::Resumed) => Theme::success(),
+        _ => Theme::text_secondary(),
+    }
+}
@@ -2,58 +2,95 @@
 
 use egui::Ui;
 
-use crate::core::{AppState, InstanceId};
+use crate::core::bundle::BUNDLE_EXTENSION;
+use crate::core::resource::format_bytes;
+use crate::core::rolling_restart::RollingRestartTarget;
+use crate::core::settings::{InstanceSort, StatusFilter, ViewMode};
+use crate::core::{AppState, Instance, InstanceId, InstanceStatus};
 use crate::ui::components::instance_card::{CardAction, InstanceCard};
 use crate::ui::dialogs::DialogState;
+use crate::ui::panels::group_dashboard::{self, GroupDashboard};
+use crate::ui::search::InstanceSearch;
 use crate::ui::theme::{Icons, Theme};
 
 pub fn render(
     ui: &mut Ui,
     state: &mut AppState,
-    search_query: &str,
+    search: &mut InstanceSearch,
     selected_instance: &mut Option<InstanceId>,
     dialog: &mut DialogState,
+    group_page: &mut Option<GroupDashboard>,
 ) {
+    if let Some(page) = group_page {
+        if !group_dashboard::render(ui, state, page, dialog) {
+            *group_page = None;
+        }
+        return;
+    }
+
     let settings = state.settings.read().unwrap();
     let view_mode = settings.view_mode;
+    let group_lanes = settings.group_lanes;
+    let view_options = settings.instance_view.clone();
     drop(settings);
 
-    // Filter instances based on search - clone to avoid borrow issues
-    let filtered_count = {
-        let instances = state.instances.read().unwrap();
-        instances
-            .values()
-            .filter(|i| {
-                if search_query.is_empty() {
-                    true
-                } else {
-                    let query = search_query.to_lowercase();
-                    i.display_name().to_lowercase().contains(&query)
-                        || i.config
-                            .executable_path
-                            .to_string_lossy()
-                            .to_lowercase()
-                            .contains(&query)
-                        || i.config
-                            .group
-                            .as_ref()
-                            .map(|g| g.to_lowercase().contains(&query))
-                            .unwrap_or(false)
-                }
-            })
-            .count()
-    };
+    let ids = &visible_instances(state, search)[..];
+    let query = search.query();
+    let filtered_count = ids.len();
 
     // View mode toggle
     ui.horizontal(|ui| {
         ui.label(
             egui::RichText::new(format!("{} instances", filtered_count))
-                .color(Theme::TEXT_SECONDARY),
+                .color(Theme::text_secondary()),
         );
 
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            let importing = state.is_importing_bundle();
+            if ui
+                .add_enabled(
+                    !importing,
+                    egui::Button::new(format!("{} Import", Icons::FOLDER)),
+                )
+                .on_hover_text("Import an instance bundle exported from another machine")
+                .clicked()
+            {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("Instance bundle", &[BUNDLE_EXTENSION])
+                    .pick_file()
+                {
+                    if let Err(e) = state.import_instance(&path) {
+                        tracing::error!("Failed to import instance: {}", e);
+                    }
+                }
+            }
+            if importing {
+                ui.spinner().on_hover_text("Importing bundle");
+                ui.ctx()
+                    .request_repaint_after(std::time::Duration::from_millis(250));
+            }
+
+            if ui
+                .button("🗂 Groups")
+                .on_hover_text("Add, rename and merge groups")
+                .clicked()
+            {
+                *dialog = DialogState::ManageGroups;
+            }
+
+            render_rolling_restart_menu(ui, state);
+
             let mut settings = state.settings.write().unwrap();
 
+            if ui
+                .selectable_label(settings.group_lanes, "Lanes")
+                .on_hover_text("Group instances into lanes by group")
+                .clicked()
+            {
+                settings.group_lanes = !settings.group_lanes;
+            }
+            ui.add_space(8.0);
+
             if ui
                 .selectable_label(
                     settings.view_mode == crate::core::settings::ViewMode::Grid,
@@ -81,14 +118,36 @@ pub fn render(
             {
                 settings.view_mode = crate::core::settings::ViewMode::Compact;
             }
+            if ui
+                .selectable_label(settings.view_mode == ViewMode::Board, Icons::BOARD)
+                .on_hover_text("Status board")
+                .clicked()
+            {
+                settings.view_mode = ViewMode::Board;
+            }
         });
     });
 
     ui.add_space(8.0);
 
+    render_view_options(ui, state);
+    let tag_click = render_tag_filter(ui, state, search.tags());
+
+    // Keep the layout, sort and filters chosen here across restarts
+    let changed = state.settings.read().is_ok_and(|s| {
+        s.view_mode != view_mode || s.group_lanes != group_lanes || s.instance_view != view_options
+    });
+    if changed {
+        if let Err(e) = state.save_settings() {
+            tracing::error!("Failed to save view settings: {}", e);
+        }
+    }
+
+    render_rolling_restart_progress(ui, state);
+
     if filtered_count == 0 {
         egui::Frame::none()
-            .fill(Theme::BG_SECONDARY)
+            .fill(Theme::bg_secondary())
             .rounding(egui::Rounding::same(8.0))
             .inner_margin(egui::Margin::same(32.0))
             .show(ui, |ui| {
@@ -96,57 +155,555 @@ pub fn render(
                     ui.label(egui::RichText::new("📦").size(48.0));
                     ui.add_space(16.0);
                     ui.label(
-                        egui::RichText::new(if search_query.is_empty() {
+                        egui::RichText::new(if query.is_empty() {
                             "No instances yet"
                         } else {
                             "No instances match your search"
                         })
                         .size(16.0)
-                        .color(Theme::TEXT_SECONDARY),
+                        .color(Theme::text_secondary()),
                     );
                     ui.add_space(8.0);
-                    if search_query.is_empty() {
+                    if query.is_empty() && search.tags().is_empty() {
                         ui.label(
                             egui::RichText::new("Click '+ New Instance' to create one")
-                                .color(Theme::TEXT_MUTED),
+                                .color(Theme::text_muted()),
                         );
                     }
                 });
             });
+        apply_tag_click(search, tag_click);
         return;
     }
 
-    egui::ScrollArea::vertical().show(ui, |ui| match view_mode {
-        crate::core::settings::ViewMode::Grid => {
-            render_grid_view(ui, state, selected_instance, dialog);
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        if group_lanes {
+            let opened = render_lanes(ui, state, view_mode, ids, query, selected_instance, dialog);
+            if let Some(group) = opened {
+                *group_page = Some(GroupDashboard::new(group));
+            }
+        } else {
+            render_view(ui, state, view_mode, ids, query, selected_instance, dialog);
         }
-        crate::core::settings::ViewMode::List => {
-            render_list_view(ui, state, selected_instance, dialog);
+    });
+    apply_tag_click(search, tag_click);
+}
+
+/// Instances matching the search and the structured filters, in the chosen order
+pub fn visible_instances(state: &AppState, search: &InstanceSearch) -> Vec<InstanceId> {
+    let options = state
+        .settings
+        .read()
+        .map(|s| s.instance_view.clone())
+        .unwrap_or_default();
+    let Ok(instances) = state.instances.read() else {
+        return Vec::new();
+    };
+    let mut visible: Vec<&Instance> = search
+        .matches()
+        .iter()
+        .filter_map(|id| instances.get(id))
+        .filter(|i| {
+            options
+                .status
+                .is_none_or(|status| status.matches(&i.status))
+        })
+        .filter(|i| {
+            options.group.is_none() || i.config.group.as_deref() == options.group.as_deref()
+        })
+        .filter(|i| {
+            options.executable.as_ref().is_none_or(|name| {
+                i.config
+                    .executable_path
+                    .file_name()
+                    .is_some_and(|file| file.to_string_lossy() == *name)
+            })
+        })
+        .collect();
+
+    visible.sort_by_cached_key(|i| i.display_name().to_lowercase());
+    match options.sort {
+        InstanceSort::Name => {}
+        InstanceSort::Status => visible.sort_by_key(|i| status_rank(&i.status)),
+        InstanceSort::Cpu => visible.sort_by(|a, b| {
+            a.resource_usage
+                .cpu_percent
+                .total_cmp(&b.resource_usage.cpu_percent)
+        }),
+        InstanceSort::Memory => visible.sort_by_key(|i| i.resource_usage.memory_bytes),
+        InstanceSort::Uptime => visible.sort_by_key(|i| i.uptime()),
+        InstanceSort::LastStarted => visible.sort_by_key(|i| i.started_at),
+    }
+    if options.descending {
+        visible.reverse();
+    }
+    visible.into_iter().map(|i| i.id).collect()
+}
+
+/// Position of a status when sorting by status, busiest first
+fn status_rank(status: &InstanceStatus) -> u8 {
+    match status {
+        InstanceStatus::Running => 0,
+        InstanceStatus::Starting => 1,
+        InstanceStatus::Paused => 2,
+        InstanceStatus::Pending => 3,
+        InstanceStatus::Stopping => 4,
+        InstanceStatus::Crashed => 5,
+        InstanceStatus::Stopped => 6,
+        InstanceStatus::Unknown => 7,
+    }
+}
+
+/// Sort and filter controls, saved with the settings
+fn render_view_options(ui: &mut Ui, state: &AppState) {
+    let groups = state.group_names();
+    let mut executables: Vec<String> = state
+        .instances
+        .read()
+        .map(|instances| {
+            instances
+                .values()
+                .filter_map(|i| i.config.executable_path.file_name())
+                .map(|name| name.to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    executables.sort_by_key(|name| name.to_lowercase());
+    executables.dedup();
+
+    let mut settings = state.settings.write().unwrap();
+    let options = &mut settings.instance_view;
+    ui.horizontal_wrapped(|ui| {
+        ui.label(egui::RichText::new("Sort").color(Theme::text_muted()));
+        egui::ComboBox::from_id_salt("instance_sort")
+            .width(110.0)
+            .selected_text(options.sort.label())
+            .show_ui(ui, |ui| {
+                for sort in InstanceSort::all() {
+                    ui.selectable_value(&mut options.sort, *sort, sort.label());
+                }
+            });
+        if ui
+            .button(if options.descending { "⬇" } else { "⬆" })
+            .on_hover_text(if options.descending {
+                "Descending"
+            } else {
+                "Ascending"
+            })
+            .clicked()
+        {
+            options.descending = !options.descending;
         }
-        crate::core::settings::ViewMode::Compact => {
-            render_compact_view(ui, state, selected_instance, dialog);
+
+        ui.add_space(12.0);
+        ui.label(egui::RichText::new("Status").color(Theme::text_muted()));
+        egui::ComboBox::from_id_salt("instance_status_filter")
+            .width(90.0)
+            .selected_text(options.status.map(|s| s.label()).unwrap_or("Any"))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut options.status, None, "Any");
+                for status in StatusFilter::all() {
+                    ui.selectable_value(&mut options.status, Some(*status), status.label());
+                }
+            });
+
+        ui.add_space(12.0);
+        ui.label(egui::RichText::new("Group").color(Theme::text_muted()));
+        egui::ComboBox::from_id_salt("instance_group_filter")
+            .width(110.0)
+            .selected_text(options.group.as_deref().unwrap_or("Any"))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut options.group, None, "Any");
+                for group in &groups {
+                    ui.selectable_value(&mut options.group, Some(group.clone()), group);
+                }
+            });
+
+        ui.add_space(12.0);
+        ui.label(egui::RichText::new("Executable").color(Theme::text_muted()));
+        egui::ComboBox::from_id_salt("instance_executable_filter")
+            .width(140.0)
+            .selected_text(options.executable.as_deref().unwrap_or("Any"))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut options.executable, None, "Any");
+                for name in &executables {
+                    ui.selectable_value(&mut options.executable, Some(name.clone()), name);
+                }
+            });
+
+        if options.is_filtered() && ui.small_button("Clear filters").clicked() {
+            options.status = None;
+            options.group = None;
+            options.executable = None;
         }
     });
+    ui.add_space(4.0);
 }
 
-fn render_grid_view(
+/// A clicked tag filter chip
+enum TagClick {
+    Toggle(String),
+    Clear,
+}
+
+/// One chip per tag used by any instance, with how many carry it. Selected
+/// chips narrow the instances shown to those with all of their tags.
+fn render_tag_filter(ui: &mut Ui, state: &AppState, selected: &[String]) -> Option<TagClick> {
+    let mut tags: Vec<(String, usize)> = Vec::new();
+    if let Ok(instances) = state.instances.read() {
+        for tag in instances.values().flat_map(|i| &i.config.tags) {
+            match tags.iter_mut().find(|(t, _)| t.eq_ignore_ascii_case(tag)) {
+                Some((_, count)) => *count += 1,
+                None => tags.push((tag.clone(), 1)),
+            }
+        }
+    }
+    // Keep selected tags clickable after the last instance lost them
+    for tag in selected {
+        if !tags.iter().any(|(t, _)| t.eq_ignore_ascii_case(tag)) {
+            tags.push((tag.clone(), 0));
+        }
+    }
+    if tags.is_empty() {
+        return None;
+    }
+    tags.sort_by_key(|(tag, _)| tag.to_lowercase());
+
+    let mut click = None;
+    ui.horizontal_wrapped(|ui| {
+        ui.label(egui::RichText::new("Tags").color(Theme::text_muted()));
+        for (tag, count) in tags {
+            let active = selected.iter().any(|t| t.eq_ignore_ascii_case(&tag));
+            let color = if active {
+                Theme::primary()
+            } else {
+                Theme::text_secondary()
+            };
+            let chip = egui::Button::new(
+                egui::RichText::new(format!("{} {}", tag, count))
+                    .size(12.0)
+                    .color(color),
+            )
+            .fill(color.linear_multiply(0.15))
+            .rounding(egui::Rounding::same(10.0));
+            if ui.add(chip).clicked() {
+                click = Some(TagClick::Toggle(tag));
+            }
+        }
+        if !selected.is_empty() && ui.small_button("Clear").clicked() {
+            click = Some(TagClick::Clear);
+        }
+    });
+    ui.add_space(8.0);
+    click
+}
+
+fn apply_tag_click(search: &mut InstanceSearch, click: Option<TagClick>) {
+    match click {
+        Some(TagClick::Toggle(tag)) => search.toggle_tag(&tag),
+        Some(TagClick::Clear) => search.clear_tags(),
+        None => {}
+    }
+}
+
+fn render_view(
     ui: &mut Ui,
     state: &mut AppState,
+    view_mode: ViewMode,
+    ids: &[InstanceId],
+    query: &str,
     selected_instance: &mut Option<InstanceId>,
     dialog: &mut DialogState,
 ) {
-    ui.horizontal_wrapped(|ui| {
+    match view_mode {
+        ViewMode::Grid => {
+            render_grid_view(ui, state, ids, query, selected_instance, dialog);
+        }
+        ViewMode::List => {
+            render_list_view(ui, state, ids, query, selected_instance, dialog);
+        }
+        ViewMode::Compact => {
+            render_compact_view(ui, state, ids, query, selected_instance, dialog);
+        }
+        ViewMode::Board => {
+            render_board_view(ui, state, ids, query, selected_instance, dialog);
+        }
+    }
+}
+
+/// Instances in one group, with aggregate usage for the lane header
+struct Lane {
+    group: Option<String>,
+    ids: Vec<InstanceId>,
+    running: usize,
+    cpu_percent: f32,
+    memory_bytes: u64,
+}
+
+/// Split the matching instances into lanes by group, ungrouped last
+fn build_lanes(state: &AppState, ids: &[InstanceId]) -> Vec<Lane> {
+    let instances = state.instances.read().unwrap();
+    let mut lanes: Vec<Lane> = Vec::new();
+
+    for id in ids {
+        let Some(instance) = instances.get(id) else {
+            continue;
+        };
+        let lane = match lanes.iter().position(|l| l.group == instance.config.group) {
+            Some(index) => &mut lanes[index],
+            None => {
+                lanes.push(Lane {
+                    group: instance.config.group.clone(),
+                    ids: Vec::new(),
+                    running: 0,
+                    cpu_percent: 0.0,
+                    memory_bytes: 0,
+                });
+                lanes.last_mut().unwrap()
+            }
+        };
+        lane.ids.push(*id);
+        if instance.status.is_active() {
+            lane.running += 1;
+            lane.cpu_percent += instance.resource_usage.cpu_percent;
+            lane.memory_bytes += instance.resource_usage.memory_bytes;
+        }
+    }
+
+    lanes.sort_by(|a, b| match (&a.group, &b.group) {
+        (Some(a), Some(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+    lanes
+}
+
+/// Collapsible, color-coded lane per group with aggregate usage and lane-wide
+/// actions. Returns the group whose name was clicked, to open its dashboard.
+fn render_lanes(
+    ui: &mut Ui,
+    state: &mut AppState,
+    view_mode: ViewMode,
+    ids: &[InstanceId],
+    query: &str,
+    selected_instance: &mut Option<InstanceId>,
+    dialog: &mut DialogState,
+) -> Option<String> {
+    let mut opened = None;
+    for lane in build_lanes(state, ids) {
+        let name = lane.group.as_deref().unwrap_or("Ungrouped");
+        let color = lane
+            .group
+            .as_deref()
+            .map(Theme::group_color)
+            .unwrap_or(Theme::text_muted());
+
+        let frame = egui::Frame::none()
+            .fill(Theme::bg_secondary())
+            .rounding(egui::Rounding::same(8.0))
+            .inner_margin(egui::Margin {
+                left: 16.0,
+                right: 12.0,
+                top: 8.0,
+                bottom: 8.0,
+            })
+            .show(ui, |ui| {
+                ui.set_width(ui.available_width());
+                let id = ui.make_persistent_id(("instance_lane", name));
+                egui::collapsing_header::CollapsingState::load_with_default_open(
+                    ui.ctx(),
+                    id,
+                    true,
+                )
+                .show_header(ui, |ui| {
+                    let title = egui::RichText::new(name).strong().color(color);
+                    match &lane.group {
+                        Some(group) => {
+                            if ui
+                                .add(egui::Label::new(title).sense(egui::Sense::click()))
+                                .on_hover_cursor(egui::CursorIcon::PointingHand)
+                                .on_hover_text("Open the group dashboard")
+                                .clicked()
+                            {
+                                opened = Some(group.clone());
+                            }
+                        }
+                        None => {
+                            ui.label(title);
+                        }
+                    }
+                    ui.label(
+                        egui::RichText::new(format!("{}/{} running", lane.running, lane.ids.len()))
+                            .size(12.0)
+                            .color(Theme::text_secondary()),
+                    );
+                    if lane.running > 0 {
+                        lane_badge(ui, &format!("{} {:.0}%", Icons::CPU, lane.cpu_percent));
+                        lane_badge(
+                            ui,
+                            &format!("{} {}", Icons::MEMORY, format_bytes(lane.memory_bytes)),
+                        );
+                    }
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let group = lane.group.as_deref();
+                        if ui
+                            .add_enabled(lane.running > 0, egui::Button::new(Icons::STOP))
+                            .on_hover_text(format!("Stop all in {}", name))
+                            .clicked()
+                        {
+                            if let Err(e) = state.stop_group(group) {
+                                tracing::error!("Failed to stop lane '{}': {}", name, e);
+                            }
+                        }
+                        if ui
+                            .add_enabled(
+                                lane.running < lane.ids.len(),
+                                egui::Button::new(Icons::PLAY),
+                            )
+                            .on_hover_text(format!("Start all in {}", name))
+                            .clicked()
+                        {
+                            if let Err(e) = state.start_group(group) {
+                                tracing::error!("Failed to start lane '{}': {}", name, e);
+                            }
+                        }
+                    });
+                })
+                .body(|ui| {
+                    render_view(
+                        ui,
+                        state,
+                        view_mode,
+                        &lane.ids,
+                        query,
+                        selected_instance,
+                        dialog,
+                    );
+                });
+            });
+
+        // Lane color stripe along the left edge
+        let rect = frame.response.rect;
+        ui.painter().rect_filled(
+            egui::Rect::from_min_size(rect.min, egui::vec2(4.0, rect.height())),
+            egui::Rounding {
+                nw: 8.0,
+                sw: 8.0,
+                ne: 0.0,
+                se: 0.0,
+            },
+            color,
+        );
+        ui.add_space(8.0);
+    }
+    opened
+}
+
+fn lane_badge(ui: &mut Ui, text: &str) {
+    egui::Frame::none()
+        .fill(Theme::bg_tertiary().linear_multiply(0.6))
+        .rounding(egui::Rounding::same(4.0))
+        .inner_margin(egui::Margin::symmetric(6.0, 2.0))
+        .show(ui, |ui| {
+            ui.label(
+                egui::RichText::new(text)
+                    .size(11.0)
+                    .color(Theme::text_secondary()),
+            );
+        });
+}
+
+/// Menu for restarting the running members of a group one at a time
+fn render_rolling_restart_menu(ui: &mut Ui, state: &AppState) {
+    let groups: Vec<String> = {
         let instances = state.instances.read().unwrap();
-        let ids: Vec<_> = instances.keys().copied().collect();
-        drop(instances);
+        let mut groups: Vec<String> = instances
+            .values()
+            .filter(|i| i.status.is_active())
+            .filter_map(|i| i.config.group.clone())
+            .collect();
+        groups.sort();
+        groups.dedup();
+        groups
+    };
+
+    ui.add_enabled_ui(!groups.is_empty(), |ui| {
+        ui.menu_button(format!("{} Rolling Restart", Icons::RESTART), |ui| {
+            for group in groups {
+                if ui.button(&group).clicked() {
+                    if let Err(e) = state.start_rolling_restart(RollingRestartTarget::Group(group))
+                    {
+                        tracing::error!("Failed to start rolling restart: {}", e);
+                    }
+                    ui.close_menu();
+                }
+            }
+        })
+        .response
+        .on_hover_text("Restart a group one instance at a time");
+    });
+}
+
+/// Progress rows for rolling restarts in progress
+fn render_rolling_restart_progress(ui: &mut Ui, state: &AppState) {
+    let rollouts = state.rolling_restarts.read().unwrap().clone();
+    if rollouts.is_empty() {
+        return;
+    }
+
+    for rollout in rollouts {
+        egui::Frame::none()
+            .fill(Theme::info().linear_multiply(0.1))
+            .rounding(egui::Rounding::same(6.0))
+            .inner_margin(egui::Margin::symmetric(12.0, 6.0))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "Rolling restart of '{}': {}/{}",
+                            rollout.label,
+                            rollout.completed(),
+                            rollout.total
+                        ))
+                        .color(Theme::info()),
+                    );
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button("Cancel").clicked() {
+                            state.cancel_rolling_restart(&rollout.target);
+                        }
+                    });
+                });
+            });
+        ui.add_space(4.0);
+    }
+
+    ui.add_space(4.0);
+}
 
-        for id in ids {
+fn render_grid_view(
+    ui: &mut Ui,
+    state: &mut AppState,
+    ids: &[InstanceId],
+    query: &str,
+    selected_instance: &mut Option<InstanceId>,
+    dialog: &mut DialogState,
+) {
+    let display = state.settings.read().unwrap().card_display;
+    ui.horizontal_wrapped(|ui| {
+        for &id in ids {
             let instances = state.instances.read().unwrap();
             if let Some(instance) = instances.get(&id) {
                 let instance = instance.clone();
                 drop(instances);
 
-                let response = InstanceCard::grid(ui, &instance);
+                let icon = state.executable_icon(&instance.config.executable_path);
+                let response = InstanceCard::culled(ui, &instance, "grid", |ui, i| {
+                    InstanceCard::grid(ui, i, query, &display, icon.as_deref())
+                });
                 handle_card_action(response.action, id, state, selected_instance, dialog);
             }
         }
@@ -156,20 +713,22 @@ fn render_grid_view(
 fn render_list_view(
     ui: &mut Ui,
     state: &mut AppState,
+    ids: &[InstanceId],
+    query: &str,
     selected_instance: &mut Option<InstanceId>,
     dialog: &mut DialogState,
 ) {
-    let instances = state.instances.read().unwrap();
-    let ids: Vec<_> = instances.keys().copied().collect();
-    drop(instances);
-
-    for id in ids {
+    let display = state.settings.read().unwrap().card_display;
+    for &id in ids {
         let instances = state.instances.read().unwrap();
         if let Some(instance) = instances.get(&id) {
             let instance = instance.clone();
             drop(instances);
 
-            let response = InstanceCard::list(ui, &instance);
+            let icon = state.executable_icon(&instance.config.executable_path);
+            let response = InstanceCard::culled(ui, &instance, "list", |ui, i| {
+                InstanceCard::list(ui, i, query, &display, icon.as_deref())
+            });
             handle_card_action(response.action, id, state, selected_instance, dialog);
 
             ui.add_space(4.0);
@@ -180,25 +739,26 @@ fn render_list_view(
 fn render_compact_view(
     ui: &mut Ui,
     state: &mut AppState,
+    ids: &[InstanceId],
+    query: &str,
     selected_instance: &mut Option<InstanceId>,
     dialog: &mut DialogState,
 ) {
     egui::Frame::none()
-        .fill(Theme::BG_SECONDARY)
+        .fill(Theme::bg_secondary())
         .rounding(egui::Rounding::same(8.0))
         .inner_margin(egui::Margin::same(12.0))
         .show(ui, |ui| {
-            let instances = state.instances.read().unwrap();
-            let ids: Vec<_> = instances.keys().copied().collect();
-            drop(instances);
-
-            for id in ids {
+            for &id in ids {
                 let instances = state.instances.read().unwrap();
                 if let Some(instance) = instances.get(&id) {
                     let instance = instance.clone();
                     drop(instances);
 
-                    let response = InstanceCard::compact(ui, &instance);
+                    let icon = state.executable_icon(&instance.config.executable_path);
+                    let response = InstanceCard::culled(ui, &instance, "compact", |ui, i| {
+                        InstanceCard::compact(ui, i, query, icon.as_deref())
+                    });
                     handle_card_action(response.action, id, state, selected_instance, dialog);
 
                     ui.separator();
@@ -207,6 +767,161 @@ fn render_compact_view(
         });
 }
 
+/// Status columns of the board view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BoardColumn {
+    Running,
+    Paused,
+    Stopped,
+    Crashed,
+}
+
+impl BoardColumn {
+    const ALL: [BoardColumn; 4] = [
+        BoardColumn::Running,
+        BoardColumn::Paused,
+        BoardColumn::Stopped,
+        BoardColumn::Crashed,
+    ];
+
+    fn of(status: &InstanceStatus) -> Self {
+        match status {
+            InstanceStatus::Pending | InstanceStatus::Starting | InstanceStatus::Running => {
+                Self::Running
+            }
+            InstanceStatus::Paused => Self::Paused,
+            InstanceStatus::Crashed => Self::Crashed,
+            InstanceStatus::Stopping | InstanceStatus::Stopped | InstanceStatus::Unknown => {
+                Self::Stopped
+            }
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Running => "Running",
+            Self::Paused => "Paused",
+            Self::Stopped => "Stopped",
+            Self::Crashed => "Crashed",
+        }
+    }
+
+    fn color(&self) -> egui::Color32 {
+        match self {
+            Self::Running => Theme::success(),
+            Self::Paused => Theme::info(),
+            Self::Stopped => Theme::text_muted(),
+            Self::Crashed => Theme::error(),
+        }
+    }
+
+    /// Action that moves an instance from column `from` into this one
+    fn drop_action(&self, from: BoardColumn) -> Option<CardAction> {
+        match (from, self) {
+            (from, to) if from == *to => None,
+            (Self::Paused, Self::Running) => Some(CardAction::Resume),
+            (_, Self::Running) => Some(CardAction::Start),
+            (Self::Running, Self::Paused) => Some(CardAction::Pause),
+            (Self::Running | Self::Paused, Self::Stopped) => Some(CardAction::Stop),
+            _ => None,
+        }
+    }
+}
+
+/// Columns per status; dropping a card on a column runs the matching action
+fn render_board_view(
+    ui: &mut Ui,
+    state: &mut AppState,
+    ids: &[InstanceId],
+    query: &str,
+    selected_instance: &mut Option<InstanceId>,
+    dialog: &mut DialogState,
+) {
+    let instances: Vec<_> = {
+        let instances = state.instances.read().unwrap();
+        ids.iter()
+            .filter_map(|id| instances.get(id).cloned())
+            .collect()
+    };
+
+    let mut dropped = None;
+    let mut actions = Vec::new();
+    let spacing = ui.spacing().item_spacing.x;
+    let column_width = (ui.available_width() - spacing * (BoardColumn::ALL.len() - 1) as f32)
+        / BoardColumn::ALL.len() as f32;
+
+    ui.horizontal_top(|ui| {
+        for column in BoardColumn::ALL {
+            let members: Vec<_> = instances
+                .iter()
+                .filter(|i| BoardColumn::of(&i.status) == column)
+                .collect();
+
+            let frame = egui::Frame::none()
+                .fill(Theme::bg_secondary())
+                .rounding(egui::Rounding::same(8.0))
+                .inner_margin(egui::Margin::same(8.0));
+            let (_, payload) = ui.dnd_drop_zone::<InstanceId, ()>(frame, |ui| {
+                ui.set_width(column_width - 16.0);
+                ui.set_min_height(240.0);
+
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(column.label())
+                            .strong()
+                            .color(column.color()),
+                    );
+                    ui.label(
+                        egui::RichText::new(members.len().to_string())
+                            .size(12.0)
+                            .color(Theme::text_muted()),
+                    );
+                });
+                ui.add_space(6.0);
+
+                for instance in members {
+                    let id = instance.id;
+                    let response = ui
+                        .dnd_drag_source(egui::Id::new(("board_card", id)), id, |ui| {
+                            egui::Frame::none()
+                                .fill(Theme::bg_tertiary())
+                                .rounding(egui::Rounding::same(6.0))
+                                .inner_margin(egui::Margin::same(6.0))
+                                .show(ui, |ui| {
+                                    let icon =
+                                        state.executable_icon(&instance.config.executable_path);
+                                    InstanceCard::compact(ui, instance, query, icon.as_deref())
+                                })
+                                .inner
+                        })
+                        .inner;
+                    if let Some(action) = response.action {
+                        actions.push((id, action));
+                    }
+                    ui.add_space(4.0);
+                }
+            });
+
+            if let Some(id) = payload {
+                dropped = Some((*id, column));
+            }
+        }
+    });
+
+    if let Some((id, column)) = dropped {
+        let from = instances
+            .iter()
+            .find(|i| i.id == id)
+            .map(|i| BoardColumn::of(&i.status));
+        if let Some(action) = from.and_then(|from| column.drop_action(from)) {
+            actions.push((id, action));
+        }
+    }
+    for (id, action) in actions {
+        handle_card_action(Some(action), id, state, selected_instance, dialog);
+    }
+}
+
 fn handle_card_action(
     action: Option<CardAction>,
     id: InstanceId,
@@ -241,6 +956,16 @@ fn handle_card_action(
                     tracing::error!("Failed to restart instance: {}", e);
                 }
             }
+            CardAction::RestartGroup(group) => {
+                if let Err(e) = state.start_rolling_restart(RollingRestartTarget::Group(group)) {
+                    tracing::error!("Failed to start rolling restart: {}", e);
+                }
+            }
+            CardAction::Kill => {
+                if let Err(e) = state.kill_instance(id) {
+                    tracing::error!("Failed to kill instance: {}", e);
+                }
+            }
             CardAction::Configure => {
                 *dialog = DialogState::EditInstance(id);
             }
@@ -263,6 +988,51 @@ fn handle_card_action(
                     }),
                 };
             }
+            CardAction::RunMacro(index) => {
+                if let Err(e) = state.run_macro(id, index) {
+                    tracing::error!("Failed to run macro: {}", e);
+                }
+            }
+            CardAction::SelectVariant(variant) => {
+                if let Err(e) = state.set_active_variant(id, variant) {
+                    tracing::error!("Failed to select argument variant: {}", e);
+                }
+            }
+            CardAction::Rename(name) => {
+                if let Err(e) = state.rename_instance(id, name) {
+                    tracing::error!("Failed to rename instance: {}", e);
+                }
+            }
+            CardAction::ShowWindow => {
+                if let Err(e) = state.show_instance_window(id) {
+                    tracing::error!("Failed to show instance window: {}", e);
+                }
+            }
+            CardAction::CaptureScreenshot => {
+                if let Err(e) = state.capture_screenshot(id) {
+                    tracing::error!("Failed to capture screenshot: {}", e);
+                }
+            }
+            CardAction::CompressData => {
+                if let Err(e) = state.compress_instance_data(id) {
+                    tracing::error!("Failed to compress instance data: {}", e);
+                }
+            }
+            CardAction::StopAndCompress => {
+                if let Err(e) = state.stop_and_compress(id) {
+                    tracing::error!("Failed to stop and compress instance: {}", e);
+                }
+            }
+            CardAction::DecompressData => {
+                if let Err(e) = state.decompress_instance_data(id) {
+                    tracing::error!("Failed to decompress instance data: {}", e);
+                }
+            }
+            CardAction::SetPinned(pinned) => {
+                if let Err(e) = state.set_pinned(id, pinned) {
+                    tracing::error!("Failed to pin instance: {}", e);
+                }
+            }
         }
     }
 }
@@ -1,7 +1,9 @@
 //! Main panel views
 
 pub mod dashboard;
+pub mod group_dashboard;
 pub mod history;
 pub mod instances;
 pub mod profiles;
+pub mod schedules;
 pub mod settings;
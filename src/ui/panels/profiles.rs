@@ -2,8 +2,10 @@
 
 use egui::Ui;
 
+use crate::core::rolling_restart::RollingRestartTarget;
 use crate::core::AppState;
 use crate::ui::components::profile_card::{ProfileAction, ProfileCard};
+use crate::ui::components::Skeleton;
 use crate::ui::dialogs::DialogState;
 use crate::ui::theme::Theme;
 
@@ -35,18 +37,30 @@ pub fn render(ui: &mut Ui, state: &mut AppState, search_query: &str, dialog: &mu
     ui.horizontal(|ui| {
         ui.label(
             egui::RichText::new(format!("{} profiles", filtered_count))
-                .color(Theme::TEXT_SECONDARY),
+                .color(Theme::text_secondary()),
         );
 
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
             if ui.button("+ New Profile").clicked() {
                 *dialog = DialogState::NewProfile;
             }
+            if ui
+                .button("📑 From Template")
+                .on_hover_text("Create a ready-made profile for a common setup")
+                .clicked()
+            {
+                *dialog = DialogState::new_from_template();
+            }
         });
     });
 
     ui.add_space(8.0);
 
+    if filtered_count == 0 && !state.is_data_loaded() {
+        Skeleton::cards(ui, 3, egui::vec2(274.0, 150.0));
+        return;
+    }
+
     if filtered_count == 0 {
         render_empty_state(ui, search_query.is_empty(), dialog);
         return;
@@ -107,7 +121,7 @@ pub fn render(ui: &mut Ui, state: &mut AppState, search_query: &str, dialog: &mu
 
 fn render_empty_state(ui: &mut Ui, no_profiles: bool, dialog: &mut DialogState) {
     egui::Frame::none()
-        .fill(Theme::BG_SECONDARY)
+        .fill(Theme::bg_secondary())
         .rounding(egui::Rounding::same(8.0))
         .inner_margin(egui::Margin::same(32.0))
         .show(ui, |ui| {
@@ -119,25 +133,30 @@ fn render_empty_state(ui: &mut Ui, no_profiles: bool, dialog: &mut DialogState)
                     ui.label(
                         egui::RichText::new("No profiles yet")
                             .size(16.0)
-                            .color(Theme::TEXT_SECONDARY),
+                            .color(Theme::text_secondary()),
                     );
                     ui.add_space(8.0);
                     ui.label(
                         egui::RichText::new(
                             "Create a profile to save your instance configurations",
                         )
-                        .color(Theme::TEXT_MUTED),
+                        .color(Theme::text_muted()),
                     );
                     ui.add_space(16.0);
 
-                    if ui.button("+ Create Profile").clicked() {
-                        *dialog = DialogState::NewProfile;
-                    }
+                    ui.horizontal(|ui| {
+                        if ui.button("+ Create Profile").clicked() {
+                            *dialog = DialogState::NewProfile;
+                        }
+                        if ui.button("📑 From Template").clicked() {
+                            *dialog = DialogState::new_from_template();
+                        }
+                    });
                 } else {
                     ui.label(
                         egui::RichText::new("No profiles match your search")
                             .size(16.0)
-                            .color(Theme::TEXT_SECONDARY),
+                            .color(Theme::text_secondary()),
                     );
                 }
             });
@@ -157,6 +176,22 @@ fn handle_profile_action(
                     tracing::error!("Failed to launch profile: {}", e);
                 }
             }
+            ProfileAction::LaunchPartial => {
+                let profiles = state.profiles.read().unwrap();
+                if let Some(profile) = profiles.get(&profile_id) {
+                    *dialog = DialogState::PartialLaunch {
+                        profile: profile_id,
+                        selected: profile.partial_selection(),
+                    };
+                }
+            }
+            ProfileAction::RollingRestart => {
+                if let Err(e) =
+                    state.start_rolling_restart(RollingRestartTarget::Profile(profile_id))
+                {
+                    tracing::error!("Failed to start rolling restart: {}", e);
+                }
+            }
             ProfileAction::Edit => {
                 *dialog = DialogState::EditProfile(profile_id);
             }
@@ -189,7 +224,7 @@ fn handle_profile_action(
                     profile.toggle_favorite();
                     let profile = profile.clone();
                     drop(profiles);
-                    if let Err(e) = state.database.save_profile(&profile) {
+                    if let Err(e) = state.save_profile(profile) {
                         tracing::error!("Failed to save profile: {}", e);
                     }
                 }
@@ -0,0 +1,315 @@
+//! Schedules panel - Launch instances and profiles at set times
+
+use chrono::{Local, NaiveTime, Timelike};
+use egui::Ui;
+
+use crate::core::scheduler::{
+    Recurrence, Schedule, ScheduleId, ScheduleTarget, DAY_NAMES, EVERY_DAY, WEEKDAYS,
+};
+use crate::core::AppState;
+use crate::ui::theme::Theme;
+
+pub fn render(ui: &mut Ui, state: &AppState) {
+    let targets = schedule_targets(state);
+    let mut schedules: Vec<Schedule> = state
+        .schedules
+        .read()
+        .map(|s| s.values().cloned().collect())
+        .unwrap_or_default();
+    schedules.sort_by_key(|s| s.created_at);
+
+    // Header with create button
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new(format!("{} schedules", schedules.len()))
+                .color(Theme::text_secondary()),
+        );
+
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            let add = ui
+                .add_enabled(!targets.is_empty(), egui::Button::new("+ New Schedule"))
+                .on_disabled_hover_text("Create an instance or profile to schedule first");
+            if add.clicked() {
+                if let Some((target, _)) = targets.first() {
+                    let mut schedule = Schedule::new(*target);
+                    schedule.name = format!("Schedule {}", schedules.len() + 1);
+                    if let Err(e) = state.save_schedule(schedule) {
+                        tracing::error!("Failed to save schedule: {}", e);
+                    }
+                }
+            }
+        });
+    });
+
+    ui.add_space(8.0);
+
+    if schedules.is_empty() {
+        render_empty_state(ui);
+        return;
+    }
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        for schedule in schedules {
+            let mut edited = schedule.clone();
+            let mut remove = false;
+            render_schedule(ui, state, &mut edited, &targets, &mut remove);
+            ui.add_space(8.0);
+
+            let result = if remove {
+                state.delete_schedule(schedule.id)
+            } else if edited != schedule {
+                state.save_schedule(edited)
+            } else {
+                Ok(())
+            };
+            if let Err(e) = result {
+                tracing::error!("Failed to update schedule: {}", e);
+            }
+        }
+    });
+}
+
+/// Everything a schedule can launch, with display labels
+fn schedule_targets(state: &AppState) -> Vec<(ScheduleTarget, String)> {
+    let mut targets: Vec<(ScheduleTarget, String)> = state
+        .profiles
+        .read()
+        .map(|profiles| {
+            profiles
+                .values()
+                .map(|p| {
+                    (
+                        ScheduleTarget::Profile(p.id),
+                        format!("Profile: {}", p.name),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if let Ok(instances) = state.instances.read() {
+        targets.extend(
+            instances
+                .values()
+                .map(|i| (ScheduleTarget::Instance(i.id), i.display_name().to_string())),
+        );
+    }
+    targets.sort_by_key(|(_, label)| label.to_lowercase());
+    targets
+}
+
+fn render_schedule(
+    ui: &mut Ui,
+    state: &AppState,
+    schedule: &mut Schedule,
+    targets: &[(ScheduleTarget, String)],
+    remove: &mut bool,
+) {
+    let id = schedule.id;
+    egui::Frame::none()
+        .fill(Theme::bg_secondary())
+        .rounding(egui::Rounding::same(8.0))
+        .stroke(egui::Stroke::new(1.0, Theme::border_light()))
+        .inner_margin(egui::Margin::same(12.0))
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut schedule.enabled, "")
+                    .on_hover_text("Enable this schedule");
+                ui.add(
+                    egui::TextEdit::singleline(&mut schedule.name)
+                        .id_salt(("schedule_name", id))
+                        .desired_width(180.0),
+                );
+
+                let target_label = targets
+                    .iter()
+                    .find(|(target, _)| *target == schedule.target)
+                    .map_or("Removed instance or profile", |(_, label)| label.as_str());
+                egui::ComboBox::from_id_salt(("schedule_target", id))
+                    .width(200.0)
+                    .selected_text(target_label)
+                    .show_ui(ui, |ui| {
+                        for (target, label) in targets {
+                            ui.selectable_value(&mut schedule.target, *target, label);
+                        }
+                    });
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui
+                        .small_button("\u{2715}")
+                        .on_hover_text("Delete schedule")
+                        .clicked()
+                    {
+                        *remove = true;
+                    }
+                    if ui
+                        .small_button("\u{25B6} Run now")
+                        .on_hover_text("Launch the target now")
+                        .clicked()
+                    {
+                        run_now(state, id);
+                    }
+                });
+            });
+
+            ui.add_space(6.0);
+            render_recurrence(ui, id, &mut schedule.recurrence);
+            ui.add_space(6.0);
+            render_status(ui, schedule);
+        });
+}
+
+fn render_recurrence(ui: &mut Ui, id: ScheduleId, recurrence: &mut Recurrence) {
+    ui.horizontal_wrapped(|ui| {
+        let is_daily = matches!(recurrence, Recurrence::Daily { .. });
+        egui::ComboBox::from_id_salt(("schedule_kind", id))
+            .width(120.0)
+            .selected_text(if is_daily { "At a time" } else { "Repeating" })
+            .show_ui(ui, |ui| {
+                if ui.selectable_label(is_daily, "At a time").clicked() && !is_daily {
+                    *recurrence = Recurrence::default();
+                }
+                if ui.selectable_label(!is_daily, "Repeating").clicked() && is_daily {
+                    *recurrence = Recurrence::Interval {
+                        hours: 4,
+                        from: chrono::Utc::now(),
+                    };
+                }
+            });
+
+        match recurrence {
+            Recurrence::Daily { time, days } => {
+                let mut hour = time.hour();
+                let mut minute = time.minute();
+                ui.add(
+                    egui::DragValue::new(&mut hour)
+                        .range(0..=23)
+                        .custom_formatter(|v, _| format!("{:02}", v as u32)),
+                );
+                ui.label(":");
+                ui.add(
+                    egui::DragValue::new(&mut minute)
+                        .range(0..=59)
+                        .custom_formatter(|v, _| format!("{:02}", v as u32)),
+                );
+                if let Some(changed) = NaiveTime::from_hms_opt(hour, minute, 0) {
+                    *time = changed;
+                }
+
+                ui.add_space(8.0);
+                for (day, name) in days.iter_mut().zip(DAY_NAMES) {
+                    if ui.selectable_label(*day, name).clicked() {
+                        *day = !*day;
+                    }
+                }
+
+                ui.add_space(8.0);
+                if ui.small_button("Every day").clicked() {
+                    *days = EVERY_DAY;
+                }
+                if ui.small_button("Weekdays").clicked() {
+                    *days = WEEKDAYS;
+                }
+            }
+            Recurrence::Interval { hours, from } => {
+                ui.label("every");
+                ui.add(egui::DragValue::new(hours).range(1..=168).suffix(" h"));
+                ui.label(
+                    egui::RichText::new(format!(
+                        "counted from {}",
+                        from.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+                    ))
+                    .size(11.0)
+                    .color(Theme::text_muted()),
+                );
+                if ui
+                    .small_button("Restart count")
+                    .on_hover_text("Count the interval from now")
+                    .clicked()
+                {
+                    *from = chrono::Utc::now();
+                }
+            }
+        }
+    });
+}
+
+/// Next run and the outcome of the last one
+fn render_status(ui: &mut Ui, schedule: &Schedule) {
+    ui.horizontal(|ui| {
+        let next = match schedule.next_run() {
+            Some(at) => format!(
+                "{} \u{2022} next {}",
+                schedule.recurrence.describe(),
+                at.format("%a %Y-%m-%d %H:%M")
+            ),
+            None if !schedule.enabled => {
+                format!("{} \u{2022} paused", schedule.recurrence.describe())
+            }
+            None => schedule.recurrence.describe(),
+        };
+        ui.label(
+            egui::RichText::new(next)
+                .size(12.0)
+                .color(Theme::text_secondary()),
+        );
+
+        if let Some(last_run) = schedule.last_run {
+            ui.label(egui::RichText::new("\u{2022}").color(Theme::text_muted()));
+            let last_run = last_run.with_timezone(&Local).format("%Y-%m-%d %H:%M");
+            match &schedule.last_error {
+                Some(error) => {
+                    ui.label(
+                        egui::RichText::new(format!("last run {} failed", last_run))
+                            .size(12.0)
+                            .color(Theme::error()),
+                    )
+                    .on_hover_text(error);
+                }
+                None => {
+                    ui.label(
+                        egui::RichText::new(format!("last run {}", last_run))
+                            .size(12.0)
+                            .color(Theme::text_muted()),
+                    );
+                }
+            }
+        }
+    });
+}
+
+/// Launch on a background thread, as staggered profile launches sleep
+fn run_now(state: &AppState, id: ScheduleId) {
+    let state = state.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = state.run_schedule(id) {
+            tracing::warn!("Schedule {} failed to run: {}", id, e);
+        }
+    });
+}
+
+fn render_empty_state(ui: &mut Ui) {
+    egui::Frame::none()
+        .fill(Theme::bg_secondary())
+        .rounding(egui::Rounding::same(8.0))
+        .inner_margin(egui::Margin::same(32.0))
+        .show(ui, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.label(egui::RichText::new("\u{23F0}").size(48.0));
+                ui.add_space(16.0);
+                ui.label(
+                    egui::RichText::new("No schedules yet")
+                        .size(16.0)
+                        .color(Theme::text_secondary()),
+                );
+                ui.add_space(8.0);
+                ui.label(
+                    egui::RichText::new(
+                        "Launch an instance or profile every day at a set time, on weekdays only, or every few hours",
+                    )
+                    .color(Theme::text_muted()),
+                );
+            });
+        });
+}
@@ -1,10 +1,30 @@
 //! Settings panel
 
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
 use egui::{Color32, Context, Ui, Vec2};
 
-use crate::core::settings::{NotificationLevel, Theme as SettingsTheme, ViewMode};
-use crate::core::AppState;
-use crate::ui::theme::Theme;
+use crate::core::alerts::{AlertChannel, AlertEvent, AlertRule, AlertTarget};
+use crate::core::broadcast::BroadcastSettings;
+use crate::core::crash_dialogs::CrashDialogSettings;
+use crate::core::deployment::{self, InstallScope};
+use crate::core::email::{self, EmailSettings, SmtpSecurity};
+use crate::core::hotkeys::{HotkeyAction, HotkeyBinding};
+use crate::core::known_issues::KnownIssue;
+use crate::core::reservation::{GroupMemoryBudget, ReservationPolicy};
+use crate::core::settings::{
+    CardDisplay, CardSize, NotificationLevel, QuickAction, SettingsLock, SidebarLayout,
+    Theme as SettingsTheme, ViewMode,
+};
+use crate::core::usage::UsageLimit;
+use crate::core::{AppState, CustomTheme, InstanceStatus, LimitPreset, ResourceLimits};
+use crate::ui::app::ActiveView;
+use crate::ui::components::AffinityPicker;
+use crate::ui::dialogs::migration::MigrationWizard;
+use crate::ui::dialogs::unlock;
+use crate::ui::theme::{Palette, Theme};
 
 /// Custom toggle switch widget for better UX
 fn toggle_switch(ui: &mut Ui, on: &mut bool) -> egui::Response {
@@ -22,9 +42,9 @@ fn toggle_switch(ui: &mut Ui, on: &mut bool) -> egui::Response {
 
         // Track background
         let track_color = if *on {
-            Theme::SUCCESS.linear_multiply(0.9 + 0.1 * how_on)
+            Theme::success().linear_multiply(0.9 + 0.1 * how_on)
         } else {
-            Theme::BG_TERTIARY
+            Theme::bg_tertiary()
         };
 
         let track_rect = rect;
@@ -32,7 +52,14 @@ fn toggle_switch(ui: &mut Ui, on: &mut bool) -> egui::Response {
             track_rect,
             egui::Rounding::same(12.0),
             track_color,
-            egui::Stroke::new(1.0, if *on { Theme::SUCCESS } else { Theme::BORDER }),
+            egui::Stroke::new(
+                1.0,
+                if *on {
+                    Theme::success()
+                } else {
+                    Theme::border()
+                },
+            ),
         );
 
         // Sliding circle
@@ -67,14 +94,14 @@ fn section_header(ui: &mut Ui, icon: &str, title: &str) {
         ui.label(
             egui::RichText::new(icon)
                 .size(20.0)
-                .color(Theme::PRIMARY_LIGHT),
+                .color(Theme::primary_light()),
         );
         ui.add_space(8.0);
         ui.label(
             egui::RichText::new(title)
                 .size(17.0)
                 .strong()
-                .color(Color32::WHITE),
+                .color(Theme::text_primary()),
         );
     });
     ui.add_space(12.0);
@@ -86,11 +113,15 @@ fn toggle_setting(ui: &mut Ui, value: &mut bool, label: &str, description: &str)
         ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
             ui.vertical(|ui| {
                 ui.add_space(2.0);
-                ui.label(egui::RichText::new(label).size(14.0).color(Color32::WHITE));
+                ui.label(
+                    egui::RichText::new(label)
+                        .size(14.0)
+                        .color(Theme::text_primary()),
+                );
                 ui.label(
                     egui::RichText::new(description)
                         .size(12.0)
-                        .color(Theme::TEXT_SECONDARY),
+                        .color(Theme::text_secondary()),
                 );
             });
         });
@@ -107,11 +138,15 @@ fn setting_row(ui: &mut Ui, label: &str, description: &str, add_widget: impl FnO
         ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
             ui.vertical(|ui| {
                 ui.add_space(2.0);
-                ui.label(egui::RichText::new(label).size(14.0).color(Color32::WHITE));
+                ui.label(
+                    egui::RichText::new(label)
+                        .size(14.0)
+                        .color(Theme::text_primary()),
+                );
                 ui.label(
                     egui::RichText::new(description)
                         .size(12.0)
-                        .color(Theme::TEXT_SECONDARY),
+                        .color(Theme::text_secondary()),
                 );
             });
         });
@@ -125,9 +160,9 @@ fn setting_row(ui: &mut Ui, label: &str, description: &str, add_widget: impl FnO
 /// Styled section frame with better visual design
 fn section_frame(ui: &mut Ui, add_contents: impl FnOnce(&mut Ui)) {
     egui::Frame::none()
-        .fill(Theme::BG_SECONDARY)
+        .fill(Theme::bg_secondary())
         .rounding(egui::Rounding::same(12.0))
-        .stroke(egui::Stroke::new(1.0, Theme::BORDER_LIGHT))
+        .stroke(egui::Stroke::new(1.0, Theme::border_light()))
         .inner_margin(egui::Margin::same(20.0))
         .outer_margin(egui::Margin::symmetric(0.0, 4.0))
         .show(ui, |ui| {
@@ -136,7 +171,1348 @@ fn section_frame(ui: &mut Ui, add_contents: impl FnOnce(&mut Ui)) {
         });
 }
 
-pub fn render(ui: &mut Ui, state: &mut AppState, ctx: &Context) {
+/// Lock screen shown in place of a configuration view, such as settings or
+/// schedules, while the settings PIN is required
+pub fn render_locked(ui: &mut Ui, state: &AppState, unlocked: &mut bool, view: &str) {
+    ui.vertical_centered(|ui| {
+        ui.add_space(80.0);
+        ui.label(egui::RichText::new("\u{1F512}").size(48.0));
+        ui.add_space(16.0);
+        ui.label(
+            egui::RichText::new(format!("{} are locked", view))
+                .size(20.0)
+                .strong()
+                .color(Theme::text_primary()),
+        );
+        ui.add_space(6.0);
+        ui.label(
+            egui::RichText::new("Enter the PIN to change configuration")
+                .size(14.0)
+                .color(Theme::text_secondary()),
+        );
+        ui.add_space(16.0);
+
+        ui.scope(|ui| {
+            ui.set_max_width(260.0);
+            if unlock::pin_prompt(ui, state) {
+                *unlocked = true;
+            }
+        });
+    });
+}
+
+/// Security section: set, change or remove the settings PIN
+fn render_security(ui: &mut Ui, settings: &mut crate::core::Settings, unlocked: &mut bool) {
+    let pin_id = egui::Id::new("settings_new_pin");
+    let confirm_id = egui::Id::new("settings_confirm_pin");
+    let mut pin: String = ui.data_mut(|d| d.get_temp(pin_id).unwrap_or_default());
+    let mut confirm: String = ui.data_mut(|d| d.get_temp(confirm_id).unwrap_or_default());
+
+    let locked = settings.settings_lock.is_some();
+    let description = if locked {
+        "A PIN is required to open settings and schedules and edit instances or profiles"
+    } else {
+        "Require a PIN to open settings and schedules and edit instances or profiles"
+    };
+    setting_row(ui, "Settings PIN", description, |ui| {
+        if locked {
+            if ui.button("Lock Now").clicked() {
+                *unlocked = false;
+            }
+            if ui.button("Remove PIN").clicked() {
+                settings.settings_lock = None;
+            }
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(&mut pin)
+                .password(true)
+                .hint_text(if locked { "New PIN" } else { "PIN" })
+                .desired_width(140.0),
+        );
+        ui.add(
+            egui::TextEdit::singleline(&mut confirm)
+                .password(true)
+                .hint_text("Confirm PIN")
+                .desired_width(140.0),
+        );
+
+        let valid = pin.len() >= 4 && pin == confirm;
+        if ui
+            .add_enabled(
+                valid,
+                egui::Button::new(if locked { "Change PIN" } else { "Set PIN" }),
+            )
+            .on_disabled_hover_text("PINs must match and be at least 4 characters")
+            .clicked()
+        {
+            settings.settings_lock = Some(SettingsLock::new(&pin));
+            *unlocked = true;
+            pin.clear();
+            confirm.clear();
+        }
+    });
+    ui.add_space(6.0);
+    ui.label(
+        egui::RichText::new("Remember to save settings after changing the PIN")
+            .size(12.0)
+            .color(Theme::text_muted()),
+    );
+
+    ui.data_mut(|d| {
+        d.insert_temp(pin_id, pin);
+        d.insert_temp(confirm_id, confirm);
+    });
+}
+
+/// Card size preset and which fields instance cards show
+fn render_card_display(ui: &mut Ui, display: &mut CardDisplay) {
+    setting_row(
+        ui,
+        "Card size",
+        "Width of instance cards in the grid",
+        |ui| {
+            egui::ComboBox::from_id_salt("card_size_select")
+                .width(130.0)
+                .selected_text(display.size.label())
+                .show_ui(ui, |ui| {
+                    for size in CardSize::all() {
+                        let selected = display.size == *size;
+                        if ui.selectable_label(selected, size.label()).clicked() {
+                            display.size = *size;
+                        }
+                    }
+                });
+        },
+    );
+
+    setting_row(
+        ui,
+        "Card fields",
+        "Information shown on instance cards",
+        |ui| {
+            // Right-to-left layout, so listed in reverse
+            ui.checkbox(&mut display.show_gpu, "GPU");
+            ui.checkbox(&mut display.show_network, "Network");
+            ui.checkbox(&mut display.show_metadata, "Metadata");
+            ui.checkbox(&mut display.show_tags, "Tags");
+            ui.checkbox(&mut display.show_group, "Group");
+            ui.checkbox(&mut display.show_uptime, "Uptime");
+            ui.checkbox(&mut display.show_pid, "PID");
+        },
+    );
+
+    let quick_actions = &mut display.quick_actions;
+    for (label, status, slots) in [
+        (
+            "Running quick actions",
+            InstanceStatus::Running,
+            &mut quick_actions.running,
+        ),
+        (
+            "Paused quick actions",
+            InstanceStatus::Paused,
+            &mut quick_actions.paused,
+        ),
+        (
+            "Stopped quick actions",
+            InstanceStatus::Stopped,
+            &mut quick_actions.stopped,
+        ),
+    ] {
+        setting_row(
+            ui,
+            label,
+            "Card buttons; other actions are in the right-click menu",
+            |ui| {
+                // Right-to-left layout, so slots are added last to first
+                for (index, slot) in slots.iter_mut().enumerate().rev() {
+                    egui::ComboBox::from_id_salt(("quick_action", label, index))
+                        .width(90.0)
+                        .selected_text(slot.map(|a| a.label()).unwrap_or("None"))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(slot, None, "None");
+                            for action in QuickAction::available(&status) {
+                                ui.selectable_value(slot, Some(*action), action.label());
+                            }
+                        });
+                }
+            },
+        );
+    }
+}
+
+/// Sidebar collapse, entry order and which entries are shown
+fn render_sidebar_layout(ui: &mut Ui, settings: &mut crate::core::Settings) {
+    toggle_setting(
+        ui,
+        &mut settings.sidebar_collapsed,
+        "Compact sidebar",
+        "Show navigation icons only, leaving more room for the views",
+    );
+
+    setting_row(
+        ui,
+        "Sidebar entries",
+        "Order and visibility; also in each entry's right-click menu",
+        |ui| {
+            if ui.button("Reset").clicked() {
+                settings.sidebar = SidebarLayout::default();
+            }
+        },
+    );
+
+    let ordered = ActiveView::ordered(&settings.sidebar);
+    let mut swap = None;
+    for (index, view) in ordered.iter().enumerate() {
+        ui.horizontal(|ui| {
+            let mut shown = !settings.sidebar.is_hidden(view.label());
+            let toggle = ui
+                .add_enabled(view.hideable(), egui::Checkbox::new(&mut shown, ""))
+                .on_disabled_hover_text("Settings is always shown");
+            if toggle.changed() {
+                settings.sidebar.set_hidden(view.label(), !shown);
+            }
+            ui.label(format!("{}  {}", view.icon(), view.label()));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .add_enabled(
+                        index + 1 < ordered.len(),
+                        egui::Button::new("\u{2193}").small(),
+                    )
+                    .clicked()
+                {
+                    swap = Some((index, index + 1));
+                }
+                if ui
+                    .add_enabled(index > 0, egui::Button::new("\u{2191}").small())
+                    .clicked()
+                {
+                    swap = Some((index - 1, index));
+                }
+            });
+        });
+    }
+    if let Some((a, b)) = swap {
+        let mut order = ordered;
+        order.swap(a, b);
+        settings.sidebar.order = order.iter().map(|v| v.label().to_string()).collect();
+    }
+    ui.add_space(14.0);
+}
+
+/// Launch-time memory check policy and per-group memory budgets
+fn render_memory_reservation(ui: &mut Ui, settings: &mut crate::core::Settings) {
+    setting_row(
+        ui,
+        "When a launch does not fit",
+        "Checked when an instance's memory limit exceeds free RAM, or its group's running instances would exceed the group's budget",
+        |ui| {
+            egui::ComboBox::from_id_salt("memory_reservation")
+                .selected_text(settings.memory_reservation.label())
+                .show_ui(ui, |ui| {
+                    for policy in ReservationPolicy::all() {
+                        ui.selectable_value(
+                            &mut settings.memory_reservation,
+                            policy,
+                            policy.label(),
+                        );
+                    }
+                });
+        },
+    );
+    setting_row(
+        ui,
+        "Group budgets",
+        "Running members count at their memory limit, or their current usage when they have none",
+        |ui| {
+            if ui.button("Add budget").clicked() {
+                settings.group_memory_budgets.push(GroupMemoryBudget {
+                    group: String::new(),
+                    memory_mb: 4096,
+                });
+            }
+        },
+    );
+
+    let mut remove = None;
+    for (index, budget) in settings.group_memory_budgets.iter_mut().enumerate() {
+        egui::Frame::none()
+            .fill(Theme::bg_tertiary())
+            .rounding(egui::Rounding::same(8.0))
+            .inner_margin(egui::Margin::symmetric(10.0, 6.0))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut budget.group)
+                            .hint_text("Group name")
+                            .desired_width(160.0),
+                    );
+                    ui.add(
+                        egui::DragValue::new(&mut budget.memory_mb)
+                            .range(1..=1_048_576)
+                            .suffix(" MB")
+                            .speed(64.0),
+                    );
+                    if ui
+                        .small_button("\u{2715}")
+                        .on_hover_text("Remove budget")
+                        .clicked()
+                    {
+                        remove = Some(index);
+                    }
+                });
+            });
+        ui.add_space(4.0);
+    }
+    if let Some(index) = remove {
+        settings.group_memory_budgets.remove(index);
+    }
+}
+
+/// Daily runtime budgets per executable, with today's usage
+/// Names of the custom metadata fields offered on every instance
+fn render_metadata_fields(ui: &mut Ui, settings: &mut crate::core::Settings) {
+    setting_row(
+        ui,
+        "Fields",
+        "Shown in the instance dialogs and as card badges, and searchable as field:value",
+        |ui| {
+            if ui.button("Add field").clicked() {
+                settings.metadata_fields.push(String::new());
+            }
+        },
+    );
+
+    let mut remove = None;
+    for (index, field) in settings.metadata_fields.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(field)
+                    .hint_text("e.g. Account")
+                    .desired_width(200.0),
+            );
+            if ui
+                .small_button("\u{2715}")
+                .on_hover_text("Remove field; values already set on instances are kept")
+                .clicked()
+            {
+                remove = Some(index);
+            }
+        });
+    }
+    if let Some(index) = remove {
+        settings.metadata_fields.remove(index);
+    }
+}
+
+fn render_known_issues(ui: &mut Ui, settings: &mut crate::core::Settings) {
+    setting_row(
+        ui,
+        "Executable hints",
+        "Shown in the New Instance dialog when the executable's name (without extension) matches",
+        |ui| {
+            if ui.button("Add hint").clicked() {
+                settings.known_issues.push(KnownIssue {
+                    executable: String::new(),
+                    hint: String::new(),
+                });
+            }
+        },
+    );
+
+    let mut remove = None;
+    for (index, issue) in settings.known_issues.iter_mut().enumerate() {
+        egui::Frame::none()
+            .fill(Theme::bg_tertiary())
+            .rounding(egui::Rounding::same(8.0))
+            .inner_margin(egui::Margin::symmetric(10.0, 6.0))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut issue.executable)
+                            .hint_text("discord")
+                            .desired_width(120.0),
+                    );
+                    ui.add(
+                        egui::TextEdit::multiline(&mut issue.hint)
+                            .hint_text("What to set up for several copies to work")
+                            .desired_rows(1)
+                            .desired_width(ui.available_width() - 30.0),
+                    );
+                    if ui
+                        .small_button("\u{2715}")
+                        .on_hover_text("Remove hint")
+                        .clicked()
+                    {
+                        remove = Some(index);
+                    }
+                });
+            });
+        ui.add_space(4.0);
+    }
+    if let Some(index) = remove {
+        settings.known_issues.remove(index);
+    }
+
+    ui.add_space(10.0);
+    setting_row(
+        ui,
+        "Restore built-in hints",
+        "Bring back the hints shipped with MultiInstance for executables that have none",
+        |ui| {
+            if ui.button("Restore").clicked() {
+                for issue in KnownIssue::defaults() {
+                    if !settings
+                        .known_issues
+                        .iter()
+                        .any(|i| i.executable.trim().eq_ignore_ascii_case(&issue.executable))
+                    {
+                        settings.known_issues.push(issue);
+                    }
+                }
+            }
+        },
+    );
+}
+
+/// Toggle and title patterns for closing crash dialogs
+fn render_crash_dialogs(ui: &mut Ui, crash_dialogs: &mut CrashDialogSettings) {
+    toggle_setting(
+        ui,
+        &mut crash_dialogs.enabled,
+        "Close crash dialogs",
+        "Close crash report dialogs of crashed instances so they exit and can auto-restart",
+    );
+    if !crash_dialogs.enabled {
+        return;
+    }
+
+    setting_row(
+        ui,
+        "Dialog titles",
+        "One per line; windows of an instance, or of Windows Error Reporting for it, whose title contains one are closed",
+        |ui| {
+            let mut patterns = crash_dialogs.title_patterns.join("\n");
+            let edit = ui.add(
+                egui::TextEdit::multiline(&mut patterns)
+                    .desired_rows(4)
+                    .desired_width(220.0),
+            );
+            if edit.changed() {
+                // Split on every newline so a line being typed is kept
+                crash_dialogs.title_patterns = patterns.split('\n').map(String::from).collect();
+            }
+        },
+    );
+}
+
+fn render_usage_limits(
+    ui: &mut Ui,
+    settings: &mut crate::core::Settings,
+    usage_today: &HashMap<String, u64>,
+) {
+    let mut remove = None;
+    for (index, limit) in settings.usage_limits.iter_mut().enumerate() {
+        let used_mins = usage_today
+            .get(&limit.executable.to_lowercase())
+            .copied()
+            .unwrap_or(0)
+            / 60;
+        let description = format!(
+            "Used {} of {} minutes today",
+            used_mins, limit.daily_minutes
+        );
+        setting_row(ui, &limit.executable, &description, |ui| {
+            if ui
+                .small_button("\u{2715}")
+                .on_hover_text("Remove limit")
+                .clicked()
+            {
+                remove = Some(index);
+            }
+            ui.add(
+                egui::DragValue::new(&mut limit.daily_minutes)
+                    .range(1..=1440)
+                    .suffix(" min/day")
+                    .speed(5.0),
+            );
+        });
+    }
+    if let Some(index) = remove {
+        settings.usage_limits.remove(index);
+    }
+
+    let id = egui::Id::new("usage_limit_new_executable");
+    let mut executable = ui.data_mut(|d| d.get_temp::<String>(id).unwrap_or_default());
+    setting_row(
+        ui,
+        "Add limit",
+        "Executable file name, e.g. game.exe; time counts while any of its instances runs",
+        |ui| {
+            let name = executable.trim().to_string();
+            let exists = settings
+                .usage_limits
+                .iter()
+                .any(|l| l.executable.eq_ignore_ascii_case(&name));
+            if ui
+                .add_enabled(!name.is_empty() && !exists, egui::Button::new("Add"))
+                .clicked()
+            {
+                settings.usage_limits.push(UsageLimit {
+                    executable: name,
+                    daily_minutes: 120,
+                });
+                executable.clear();
+            }
+            ui.add(
+                egui::TextEdit::singleline(&mut executable)
+                    .hint_text("game.exe")
+                    .desired_width(140.0),
+            );
+        },
+    );
+    ui.data_mut(|d| d.insert_temp(id, executable));
+}
+
+fn render_limit_presets(ui: &mut Ui, settings: &mut crate::core::Settings) {
+    let editing_id = egui::Id::new("limit_preset_editing");
+    let mut editing = ui.data(|d| d.get_temp::<usize>(editing_id));
+    let mut remove = None;
+
+    for (index, preset) in settings.limit_presets.iter_mut().enumerate() {
+        let is_editing = editing == Some(index);
+        setting_row(ui, &preset.name, &preset.summary(), |ui| {
+            if ui
+                .small_button("\u{2715}")
+                .on_hover_text("Remove preset")
+                .clicked()
+            {
+                remove = Some(index);
+            }
+            if ui
+                .selectable_label(is_editing, "Edit")
+                .on_hover_text("Edit the preset's limits")
+                .clicked()
+            {
+                editing = if is_editing { None } else { Some(index) };
+            }
+        });
+
+        if is_editing {
+            egui::Frame::none()
+                .fill(Theme::bg_tertiary())
+                .rounding(egui::Rounding::same(8.0))
+                .inner_margin(egui::Margin::same(12.0))
+                .show(ui, |ui| {
+                    egui::Grid::new(("limit_preset_grid", index))
+                        .num_columns(2)
+                        .spacing([12.0, 8.0])
+                        .show(ui, |ui| {
+                            ui.label("Name");
+                            ui.text_edit_singleline(&mut preset.name);
+                            ui.end_row();
+
+                            ui.label("CPU limit");
+                            ui.add(
+                                egui::Slider::new(&mut preset.limits.cpu_percent, 0..=100)
+                                    .custom_formatter(|n, _| {
+                                        if n == 0.0 {
+                                            "Unlimited".to_string()
+                                        } else {
+                                            format!("{:.0}%", n)
+                                        }
+                                    }),
+                            );
+                            ui.end_row();
+
+                            ui.label("Memory limit");
+                            ui.add(
+                                egui::DragValue::new(&mut preset.limits.memory_mb)
+                                    .range(0..=65536)
+                                    .suffix(" MB")
+                                    .speed(16.0),
+                            );
+                            ui.end_row();
+
+                            ui.label("Priority");
+                            ui.add(
+                                egui::Slider::new(
+                                    &mut preset.limits.priority,
+                                    ResourceLimits::EFFICIENCY_PRIORITY..=2,
+                                )
+                                .custom_formatter(|n, _| ResourceLimits::priority_label(n as i8)),
+                            );
+                            ui.end_row();
+
+                            ui.label("CPU affinity");
+                            AffinityPicker::show(
+                                ui,
+                                &mut preset.limits,
+                                &format!("limit_preset_affinity_{}", index),
+                            );
+                            ui.end_row();
+                        });
+                });
+            ui.add_space(14.0);
+        }
+    }
+    if let Some(index) = remove {
+        settings.limit_presets.remove(index);
+        editing = None;
+    }
+
+    let new_id = egui::Id::new("limit_preset_new_name");
+    let mut name = ui.data_mut(|d| d.get_temp::<String>(new_id).unwrap_or_default());
+    setting_row(
+        ui,
+        "Add preset",
+        "Starts unlimited; pick it in the instance and profile dialogs",
+        |ui| {
+            let trimmed = name.trim().to_string();
+            let exists = settings
+                .limit_presets
+                .iter()
+                .any(|p| p.name.eq_ignore_ascii_case(&trimmed));
+            if ui
+                .add_enabled(!trimmed.is_empty() && !exists, egui::Button::new("Add"))
+                .clicked()
+            {
+                settings.limit_presets.push(LimitPreset {
+                    name: trimmed,
+                    limits: ResourceLimits::default(),
+                });
+                editing = Some(settings.limit_presets.len() - 1);
+                name.clear();
+            }
+            ui.add(
+                egui::TextEdit::singleline(&mut name)
+                    .hint_text("Streaming")
+                    .desired_width(140.0),
+            );
+        },
+    );
+    setting_row(
+        ui,
+        "Restore built-in presets",
+        "Bring back Background, Gaming and Benchmark",
+        |ui| {
+            if ui.button("Restore").clicked() {
+                for preset in LimitPreset::defaults() {
+                    if !settings
+                        .limit_presets
+                        .iter()
+                        .any(|p| p.name.eq_ignore_ascii_case(&preset.name))
+                    {
+                        settings.limit_presets.push(preset);
+                    }
+                }
+            }
+        },
+    );
+
+    ui.data_mut(|d| {
+        match editing {
+            Some(index) => d.insert_temp(editing_id, index),
+            None => d.remove::<usize>(editing_id),
+        }
+        d.insert_temp(new_id, name);
+    });
+}
+
+/// Groups and instances a notification rule can be scoped to, with their labels
+fn alert_targets(state: &AppState) -> Vec<(AlertTarget, String)> {
+    let mut targets = vec![(AlertTarget::Any, "Any instance".to_string())];
+    for group in state.group_names() {
+        targets.push((
+            AlertTarget::Group(group.clone()),
+            format!("Group: {}", group),
+        ));
+    }
+    if let Ok(instances) = state.instances.read() {
+        let mut instances: Vec<_> = instances
+            .values()
+            .map(|i| (AlertTarget::Instance(i.id), i.display_name().to_string()))
+            .collect();
+        instances.sort_by_key(|(_, name)| name.to_lowercase());
+        targets.extend(instances);
+    }
+    targets
+}
+
+fn render_alert_rules(
+    ui: &mut Ui,
+    settings: &mut crate::core::Settings,
+    targets: &[(AlertTarget, String)],
+) {
+    setting_row(
+        ui,
+        "Routing rules",
+        "The first rule matching an event and instance picks the channels; other events follow the level above",
+        |ui| {
+            if ui.button("Add rule").clicked() {
+                settings.alert_rules.push(AlertRule::default());
+            }
+        },
+    );
+
+    let mut remove = None;
+    let mut raise = None;
+    for (index, rule) in settings.alert_rules.iter_mut().enumerate() {
+        egui::Frame::none()
+            .fill(Theme::bg_tertiary())
+            .rounding(egui::Rounding::same(8.0))
+            .inner_margin(egui::Margin::symmetric(10.0, 6.0))
+            .show(ui, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    egui::ComboBox::from_id_salt(("alert_rule_event", index))
+                        .width(110.0)
+                        .selected_text(rule.event.map_or("Any event", |e| e.label()))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut rule.event, None, "Any event");
+                            for event in AlertEvent::all() {
+                                ui.selectable_value(&mut rule.event, Some(*event), event.label());
+                            }
+                        });
+
+                    let target_label = targets
+                        .iter()
+                        .find(|(target, _)| *target == rule.target)
+                        .map_or("Removed instance", |(_, label)| label.as_str());
+                    egui::ComboBox::from_id_salt(("alert_rule_target", index))
+                        .width(150.0)
+                        .selected_text(target_label)
+                        .show_ui(ui, |ui| {
+                            for (target, label) in targets {
+                                ui.selectable_value(&mut rule.target, target.clone(), label);
+                            }
+                        });
+
+                    ui.label(egui::RichText::new("\u{2192}").color(Theme::text_muted()));
+                    for channel in AlertChannel::all() {
+                        let on = rule.channels.contains(channel);
+                        if ui.selectable_label(on, channel.label()).clicked() {
+                            if on {
+                                rule.channels.retain(|c| c != channel);
+                            } else {
+                                rule.channels.push(*channel);
+                            }
+                        }
+                    }
+                    if rule.channels.is_empty() {
+                        ui.label(
+                            egui::RichText::new("Silent")
+                                .size(11.0)
+                                .color(Theme::text_muted()),
+                        );
+                    }
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui
+                            .small_button("\u{2715}")
+                            .on_hover_text("Remove rule")
+                            .clicked()
+                        {
+                            remove = Some(index);
+                        }
+                        if index > 0
+                            && ui
+                                .small_button("\u{2191}")
+                                .on_hover_text("Check this rule earlier")
+                                .clicked()
+                        {
+                            raise = Some(index);
+                        }
+                    });
+                });
+            });
+        ui.add_space(4.0);
+    }
+    if let Some(index) = remove {
+        settings.alert_rules.remove(index);
+    } else if let Some(index) = raise {
+        settings.alert_rules.swap(index, index - 1);
+    }
+}
+
+/// Actions a hotkey can be bound to, with their labels
+fn hotkey_actions(state: &AppState) -> Vec<(HotkeyAction, String)> {
+    let mut actions = Vec::new();
+    if let Ok(profiles) = state.profiles.read() {
+        let mut profiles: Vec<_> = profiles
+            .values()
+            .map(|p| {
+                (
+                    HotkeyAction::LaunchProfile(p.id),
+                    format!("Launch {}", p.name),
+                )
+            })
+            .collect();
+        profiles.sort_by_key(|(_, label)| label.to_lowercase());
+        actions.extend(profiles);
+    }
+    for action in [
+        HotkeyAction::PauseAll,
+        HotkeyAction::ResumeAll,
+        HotkeyAction::EmergencyStop,
+        HotkeyAction::QuickSwitcher,
+        HotkeyAction::ToggleBroadcast,
+    ] {
+        actions.push((action, action.label().to_string()));
+    }
+    actions
+}
+
+/// Shortcut for a key pressed with modifiers, e.g. `Ctrl+Alt+F12`
+fn shortcut_text(key: egui::Key, modifiers: egui::Modifiers) -> String {
+    let mut parts = Vec::new();
+    if modifiers.ctrl {
+        parts.push("Ctrl");
+    }
+    if modifiers.alt {
+        parts.push("Alt");
+    }
+    if modifiers.shift {
+        parts.push("Shift");
+    }
+    if modifiers.mac_cmd {
+        parts.push("Cmd");
+    }
+    parts.push(key.name());
+    parts.join("+")
+}
+
+fn render_hotkeys(
+    ui: &mut Ui,
+    settings: &mut crate::core::Settings,
+    actions: &[(HotkeyAction, String)],
+    errors: &HashMap<String, String>,
+) {
+    setting_row(
+        ui,
+        "Global hotkeys",
+        "Work while another window is focused; use modifiers so they don't clash with other apps",
+        |ui| {
+            if ui.button("Add hotkey").clicked() {
+                settings.hotkeys.push(HotkeyBinding {
+                    shortcut: String::new(),
+                    action: HotkeyAction::EmergencyStop,
+                });
+            }
+        },
+    );
+
+    // Row whose shortcut is being recorded from the next key press
+    let recording_id = egui::Id::new("hotkey_recording");
+    let mut recording = ui.data(|d| d.get_temp::<Option<usize>>(recording_id).flatten());
+    if let Some(index) = recording.filter(|i| *i < settings.hotkeys.len()) {
+        let pressed = ui.input(|i| {
+            i.events.iter().find_map(|event| match event {
+                egui::Event::Key {
+                    key,
+                    pressed: true,
+                    modifiers,
+                    ..
+                } => Some((*key, *modifiers)),
+                _ => None,
+            })
+        });
+        if let Some((key, modifiers)) = pressed {
+            if key != egui::Key::Escape {
+                settings.hotkeys[index].shortcut = shortcut_text(key, modifiers);
+            }
+            recording = None;
+        }
+    }
+
+    let mut remove = None;
+    for (index, binding) in settings.hotkeys.iter_mut().enumerate() {
+        egui::Frame::none()
+            .fill(Theme::bg_tertiary())
+            .rounding(egui::Rounding::same(8.0))
+            .inner_margin(egui::Margin::symmetric(10.0, 6.0))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut binding.shortcut)
+                            .hint_text("Ctrl+Alt+F12")
+                            .desired_width(140.0),
+                    );
+                    let is_recording = recording == Some(index);
+                    let record_label = if is_recording {
+                        "Press keys…"
+                    } else {
+                        "Record"
+                    };
+                    if ui.selectable_label(is_recording, record_label).clicked() {
+                        recording = (!is_recording).then_some(index);
+                    }
+
+                    ui.label(egui::RichText::new("\u{2192}").color(Theme::text_muted()));
+                    let action_label = actions
+                        .iter()
+                        .find(|(action, _)| *action == binding.action)
+                        .map_or("Removed profile", |(_, label)| label.as_str());
+                    egui::ComboBox::from_id_salt(("hotkey_action", index))
+                        .width(170.0)
+                        .selected_text(action_label)
+                        .show_ui(ui, |ui| {
+                            for (action, label) in actions {
+                                ui.selectable_value(&mut binding.action, *action, label);
+                            }
+                        });
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui
+                            .small_button("\u{2715}")
+                            .on_hover_text("Remove hotkey")
+                            .clicked()
+                        {
+                            remove = Some(index);
+                        }
+                    });
+                });
+
+                if let Some(error) = errors.get(&binding.shortcut) {
+                    ui.label(egui::RichText::new(error).size(11.0).color(Theme::error()));
+                }
+            });
+        ui.add_space(4.0);
+    }
+    if let Some(index) = remove {
+        settings.hotkeys.remove(index);
+        recording = None;
+    }
+    ui.data_mut(|d| d.insert_temp(recording_id, recording));
+}
+
+/// Broadcast group, key whitelist and mouse option. Returns true when the
+/// Start/Stop button was clicked.
+fn render_broadcast(
+    ui: &mut Ui,
+    broadcast: &mut BroadcastSettings,
+    groups: &[String],
+    broadcasting: bool,
+) -> bool {
+    setting_row(
+        ui,
+        "Group",
+        "Keys typed into one of its running instances are sent to all the others",
+        |ui| {
+            egui::ComboBox::from_id_salt("broadcast_group")
+                .width(160.0)
+                .selected_text(broadcast.group.as_deref().unwrap_or("None"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut broadcast.group, None, "None");
+                    for group in groups {
+                        ui.selectable_value(&mut broadcast.group, Some(group.clone()), group);
+                    }
+                });
+        },
+    );
+
+    // Edited as text so a trailing comma survives until the next key is typed;
+    // the text is rebuilt when the settings were changed elsewhere
+    let parse = |text: &str| -> Vec<String> {
+        text.split(',')
+            .map(str::trim)
+            .filter(|k| !k.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+    let keys_id = egui::Id::new("broadcast_keys");
+    let mut keys = ui
+        .data_mut(|d| d.get_temp::<String>(keys_id))
+        .filter(|text| parse(text) == broadcast.keys)
+        .unwrap_or_else(|| broadcast.keys.join(", "));
+    setting_row(
+        ui,
+        "Keys",
+        "Comma separated key names like W, Space or F1; leave empty to send every key",
+        |ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut keys)
+                    .hint_text("All keys")
+                    .desired_width(220.0),
+            );
+            if response.changed() {
+                broadcast.keys = parse(&keys);
+            }
+        },
+    );
+    ui.data_mut(|d| d.insert_temp(keys_id, keys));
+
+    toggle_setting(
+        ui,
+        &mut broadcast.mouse,
+        "Mirror mouse clicks",
+        "Click at the same relative spot in the other windows",
+    );
+
+    let mut toggled = false;
+    let description = if broadcasting {
+        "Broadcasting now; also toggled by the \"Toggle input broadcasting\" hotkey"
+    } else {
+        "Also toggled by the \"Toggle input broadcasting\" hotkey"
+    };
+    setting_row(ui, "Broadcasting", description, |ui| {
+        let label = if broadcasting { "Stop" } else { "Start" };
+        if ui
+            .add_enabled(broadcast.group.is_some(), egui::Button::new(label))
+            .clicked()
+        {
+            toggled = true;
+        }
+    });
+    toggled
+}
+
+/// Custom theme being edited, with the name it is saved under
+#[derive(Clone)]
+struct ThemeEditor {
+    original: Option<String>,
+    theme: CustomTheme,
+}
+
+/// Custom theme change, run once the settings lock is released
+enum ThemeAction {
+    Save(Option<String>, CustomTheme),
+    Delete(String),
+    Import(PathBuf),
+    Export(String, PathBuf),
+}
+
+fn render_custom_themes(
+    ui: &mut Ui,
+    ctx: &Context,
+    settings: &crate::core::Settings,
+    selected: Option<&CustomTheme>,
+    names: &[String],
+) -> Option<ThemeAction> {
+    let editor_id = egui::Id::new("theme_editor");
+    let mut editor = ui.data_mut(|d| d.get_temp::<Option<ThemeEditor>>(editor_id).flatten());
+    let mut action = None;
+    let mut preview = false;
+
+    setting_row(
+        ui,
+        "Custom themes",
+        "Pick your own colors, or share them as theme files",
+        |ui| {
+            if let Some(theme) = selected {
+                if ui.button("Delete").clicked() {
+                    action = Some(ThemeAction::Delete(theme.name.clone()));
+                    editor = None;
+                }
+                if ui.button("Export").clicked() {
+                    action = rfd::FileDialog::new()
+                        .add_filter("Theme", &["json"])
+                        .set_file_name(format!("{}.json", theme.name))
+                        .save_file()
+                        .map(|path| ThemeAction::Export(theme.name.clone(), path));
+                }
+                if ui.button("Edit").clicked() {
+                    editor = Some(ThemeEditor {
+                        original: Some(theme.name.clone()),
+                        theme: theme.clone(),
+                    });
+                    preview = true;
+                }
+            }
+            if ui.button("Import").clicked() {
+                action = rfd::FileDialog::new()
+                    .add_filter("Theme", &["json"])
+                    .pick_file()
+                    .map(ThemeAction::Import);
+            }
+            if ui.button("New").clicked() {
+                let mut name = "My Theme".to_string();
+                let mut n = 2;
+                while names.contains(&name) {
+                    name = format!("My Theme ({})", n);
+                    n += 1;
+                }
+                // Start from what is shown right now
+                let theme = match selected {
+                    Some(theme) => CustomTheme {
+                        name,
+                        ..theme.clone()
+                    },
+                    None => CustomTheme::new(name, settings.theme == SettingsTheme::Light),
+                };
+                editor = Some(ThemeEditor {
+                    original: None,
+                    theme,
+                });
+                preview = true;
+            }
+        },
+    );
+
+    let mut close = false;
+    if let Some(edit) = editor.as_mut() {
+        egui::Frame::none()
+            .fill(Theme::bg_tertiary().linear_multiply(0.5))
+            .rounding(egui::Rounding::same(8.0))
+            .inner_margin(egui::Margin::same(12.0))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Name");
+                    ui.add(egui::TextEdit::singleline(&mut edit.theme.name).desired_width(200.0));
+                    ui.add_space(12.0);
+                    ui.label("Based on");
+                    preview |= ui
+                        .radio_value(&mut edit.theme.light, false, "Dark")
+                        .changed();
+                    preview |= ui
+                        .radio_value(&mut edit.theme.light, true, "Light")
+                        .changed();
+                });
+                ui.add_space(8.0);
+
+                let base = Palette::base(edit.theme.light);
+                let mut palette = Palette::custom(&edit.theme);
+                egui::Grid::new("theme_editor_colors")
+                    .num_columns(4)
+                    .spacing([12.0, 6.0])
+                    .show(ui, |ui| {
+                        for (i, (key, label)) in Palette::COLORS.iter().enumerate() {
+                            let Some(color) = palette.color_mut(key) else {
+                                continue;
+                            };
+                            ui.label(
+                                egui::RichText::new(*label)
+                                    .size(12.0)
+                                    .color(Theme::text_secondary()),
+                            );
+                            ui.horizontal(|ui| {
+                                if egui::color_picker::color_edit_button_srgba(
+                                    ui,
+                                    color,
+                                    egui::color_picker::Alpha::Opaque,
+                                )
+                                .changed()
+                                {
+                                    // Only colors that differ from the base are kept
+                                    if base.color(key) == Some(*color) {
+                                        edit.theme.colors.remove(*key);
+                                    } else {
+                                        let [r, g, b, _] = color.to_array();
+                                        edit.theme.colors.insert(
+                                            key.to_string(),
+                                            format!("#{:02x}{:02x}{:02x}", r, g, b),
+                                        );
+                                    }
+                                    preview = true;
+                                }
+                                if edit.theme.colors.contains_key(*key)
+                                    && ui
+                                        .small_button("\u{21BA}")
+                                        .on_hover_text("Reset to the base color")
+                                        .clicked()
+                                {
+                                    edit.theme.colors.remove(*key);
+                                    preview = true;
+                                }
+                            });
+                            if i % 2 == 1 {
+                                ui.end_row();
+                            }
+                        }
+                    });
+
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    let valid = !edit.theme.name.trim().is_empty();
+                    if ui
+                        .add_enabled(
+                            valid,
+                            egui::Button::new("Save Theme").fill(Theme::primary()),
+                        )
+                        .clicked()
+                    {
+                        action = Some(ThemeAction::Save(edit.original.clone(), edit.theme.clone()));
+                        close = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        Theme::apply(ctx, settings.theme, selected);
+                        close = true;
+                    }
+                });
+            });
+
+        // Preview changes on the whole window while editing
+        if preview && !close {
+            Theme::apply(ctx, settings.theme, Some(&edit.theme));
+        }
+    }
+    if close {
+        editor = None;
+    }
+
+    ui.data_mut(|d| d.insert_temp(editor_id, editor));
+    action
+}
+
+fn render_email_alerts(ui: &mut Ui, email: &mut EmailSettings) {
+    toggle_setting(
+        ui,
+        &mut email.enabled,
+        "Email critical events",
+        "Crash loops and host memory exhaustion are emailed even without a routing rule",
+    );
+
+    setting_row(ui, "SMTP server", "Host name and port", |ui| {
+        ui.add(egui::DragValue::new(&mut email.smtp_port).range(1..=65535));
+        ui.add(
+            egui::TextEdit::singleline(&mut email.smtp_host)
+                .hint_text("smtp.example.com")
+                .desired_width(160.0),
+        );
+    });
+
+    setting_row(ui, "Security", "How the connection is encrypted", |ui| {
+        egui::ComboBox::from_id_salt("email_security")
+            .width(110.0)
+            .selected_text(email.security.label())
+            .show_ui(ui, |ui| {
+                for security in SmtpSecurity::all() {
+                    if ui
+                        .selectable_label(email.security == *security, security.label())
+                        .clicked()
+                        && email.security != *security
+                    {
+                        email.security = *security;
+                        email.smtp_port = security.default_port();
+                    }
+                }
+            });
+    });
+
+    setting_row(
+        ui,
+        "Login",
+        "Leave empty if the server needs none; stored on this machine only",
+        |ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut email.password)
+                    .password(true)
+                    .hint_text("Password")
+                    .desired_width(110.0),
+            );
+            ui.add(
+                egui::TextEdit::singleline(&mut email.username)
+                    .hint_text("User name")
+                    .desired_width(110.0),
+            );
+        },
+    );
+
+    setting_row(ui, "From", "Sender address", |ui| {
+        ui.add(
+            egui::TextEdit::singleline(&mut email.from)
+                .hint_text("MultiInstance <alerts@example.com>")
+                .desired_width(230.0),
+        );
+    });
+
+    setting_row(ui, "To", "Recipients, separated by commas", |ui| {
+        ui.add(
+            egui::TextEdit::singleline(&mut email.to)
+                .hint_text("ops@example.com")
+                .desired_width(230.0),
+        );
+    });
+
+    setting_row(
+        ui,
+        "Rate limit",
+        "Minutes before the same event and instance is emailed again, and emails per hour (0 = unlimited)",
+        |ui| {
+            ui.add(
+                egui::DragValue::new(&mut email.max_per_hour)
+                    .range(0..=1000)
+                    .suffix(" /hour"),
+            );
+            ui.add(
+                egui::DragValue::new(&mut email.repeat_interval_mins)
+                    .range(0..=1440)
+                    .suffix(" min"),
+            );
+        },
+    );
+
+    ui.label(
+        egui::RichText::new("Subject")
+            .size(14.0)
+            .color(Theme::text_primary()),
+    );
+    ui.add(egui::TextEdit::singleline(&mut email.subject_template).desired_width(f32::INFINITY));
+    ui.add_space(8.0);
+    ui.label(
+        egui::RichText::new("Body")
+            .size(14.0)
+            .color(Theme::text_primary()),
+    );
+    ui.add(
+        egui::TextEdit::multiline(&mut email.body_template)
+            .desired_rows(4)
+            .desired_width(f32::INFINITY),
+    );
+    ui.label(
+        egui::RichText::new(format!("Placeholders: {}", email::TEMPLATE_HELP))
+            .size(12.0)
+            .color(Theme::text_secondary()),
+    );
+    ui.add_space(14.0);
+
+    // The test runs in the background; its outcome is shown once it arrives
+    let result_id = egui::Id::new("email_test_result");
+    let result: Arc<Mutex<Option<String>>> = ui.data_mut(|d| {
+        d.get_temp_mut_or_default::<Arc<Mutex<Option<String>>>>(result_id)
+            .clone()
+    });
+    let status = result.lock().ok().and_then(|r| r.clone());
+    setting_row(
+        ui,
+        "Test",
+        status
+            .as_deref()
+            .unwrap_or("Send a test email with these settings"),
+        |ui| {
+            if ui
+                .add_enabled(email.is_configured(), egui::Button::new("Send Test Email"))
+                .clicked()
+            {
+                let email = email.clone();
+                let ctx = ui.ctx().clone();
+                if let Ok(mut slot) = result.lock() {
+                    *slot = Some("Sending...".to_string());
+                }
+                std::thread::spawn(move || {
+                    let outcome = match email::send_test(&email) {
+                        Ok(()) => "Test email sent".to_string(),
+                        Err(e) => format!("Failed: {:#}", e),
+                    };
+                    if let Ok(mut slot) = result.lock() {
+                        *slot = Some(outcome);
+                    }
+                    ctx.request_repaint();
+                });
+            }
+        },
+    );
+}
+
+pub fn render(
+    ui: &mut Ui,
+    state: &mut AppState,
+    ctx: &Context,
+    unlocked: &mut bool,
+    migration: &mut Option<MigrationWizard>,
+) {
     egui::ScrollArea::vertical()
         .auto_shrink([false, false])
         .show(ui, |ui| {
@@ -144,6 +1520,18 @@ pub fn render(ui: &mut Ui, state: &mut AppState, ctx: &Context) {
             ui.vertical_centered(|ui| {
                 ui.set_max_width(680.0);
 
+                let usage_today = state.usage_today();
+                let alert_targets = alert_targets(state);
+                let hotkey_actions = hotkey_actions(state);
+                let groups = state.group_names();
+                let mut toggle_broadcast = false;
+                let theme_names = state.custom_theme_names();
+                let mut theme_action = None;
+                let hotkey_errors = state
+                    .hotkey_errors
+                    .read()
+                    .map(|e| e.clone())
+                    .unwrap_or_default();
                 let mut settings = state.settings.write().unwrap();
 
                 // Page header
@@ -153,14 +1541,14 @@ pub fn render(ui: &mut Ui, state: &mut AppState, ctx: &Context) {
                         egui::RichText::new("Settings")
                             .size(26.0)
                             .strong()
-                            .color(Color32::WHITE),
+                            .color(Theme::text_primary()),
                     );
                 });
                 ui.add_space(6.0);
                 ui.label(
                     egui::RichText::new("Configure application behavior and preferences")
                         .size(14.0)
-                        .color(Theme::TEXT_SECONDARY),
+                        .color(Theme::text_secondary()),
                 );
                 ui.add_space(24.0);
 
@@ -174,6 +1562,16 @@ pub fn render(ui: &mut Ui, state: &mut AppState, ctx: &Context) {
                         "Launch MultiInstance automatically when you log in",
                     );
 
+                    if crate::platform::machine_autostart_registered(crate::APP_NAME) {
+                        ui.label(
+                            egui::RichText::new(
+                                "Your administrator's installer already starts MultiInstance for all users",
+                            )
+                            .size(12.0)
+                            .color(Theme::text_muted()),
+                        );
+                    }
+
                     toggle_setting(
                         ui,
                         &mut settings.minimize_to_tray,
@@ -188,6 +1586,15 @@ pub fn render(ui: &mut Ui, state: &mut AppState, ctx: &Context) {
                         "Restore previous instances when starting the application",
                     );
 
+                    if settings.auto_restore_sessions {
+                        toggle_setting(
+                            ui,
+                            &mut settings.session_restore_prompt,
+                            "Ask before restoring",
+                            "Choose which instances to restore instead of relaunching all of them",
+                        );
+                    }
+
                     toggle_setting(
                         ui,
                         &mut settings.show_system_resources,
@@ -204,28 +1611,51 @@ pub fn render(ui: &mut Ui, state: &mut AppState, ctx: &Context) {
                     setting_row(ui, "Theme", "Choose your preferred color scheme", |ui| {
                         egui::ComboBox::from_id_salt("theme_select")
                             .width(130.0)
-                            .selected_text(settings.theme.label())
+                            .selected_text(
+                                settings
+                                    .custom_theme
+                                    .as_deref()
+                                    .unwrap_or(settings.theme.label()),
+                            )
                             .show_ui(ui, |ui| {
                                 for theme in SettingsTheme::all() {
-                                    let selected = settings.theme == *theme;
+                                    let selected =
+                                        settings.custom_theme.is_none() && settings.theme == *theme;
                                     if ui.selectable_label(selected, theme.label()).clicked() {
                                         settings.theme = *theme;
-                                        match theme {
-                                            SettingsTheme::Dark => {
-                                                crate::ui::theme::Theme::apply_dark(ctx)
-                                            }
-                                            SettingsTheme::Light => {
-                                                crate::ui::theme::Theme::apply_light(ctx)
-                                            }
-                                            SettingsTheme::System => {
-                                                crate::ui::theme::Theme::apply_dark(ctx)
-                                            }
-                                        }
+                                        settings.custom_theme = None;
+                                        Theme::apply(ctx, *theme, None);
+                                    }
+                                }
+                                if !theme_names.is_empty() {
+                                    ui.separator();
+                                }
+                                for name in &theme_names {
+                                    let selected = settings.custom_theme.as_ref() == Some(name);
+                                    if ui.selectable_label(selected, name).clicked() {
+                                        settings.custom_theme = Some(name.clone());
+                                        Theme::apply(
+                                            ctx,
+                                            settings.theme,
+                                            state.custom_theme(name).as_ref(),
+                                        );
                                     }
                                 }
                             });
                     });
 
+                    let selected_theme = settings
+                        .custom_theme
+                        .as_deref()
+                        .and_then(|name| state.custom_theme(name));
+                    theme_action = render_custom_themes(
+                        ui,
+                        ctx,
+                        &settings,
+                        selected_theme.as_ref(),
+                        &theme_names,
+                    );
+
                     setting_row(
                         ui,
                         "Default view",
@@ -244,6 +1674,9 @@ pub fn render(ui: &mut Ui, state: &mut AppState, ctx: &Context) {
                                 });
                         },
                     );
+
+                    render_card_display(ui, &mut settings.card_display);
+                    render_sidebar_layout(ui, &mut settings);
                 });
 
                 ui.add_space(20.0);
@@ -338,6 +1771,37 @@ pub fn render(ui: &mut Ui, state: &mut AppState, ctx: &Context) {
 
                 ui.add_space(20.0);
 
+                // Memory Reservation
+                section_header(ui, "\u{1F4BE}", "Memory Reservation");
+                section_frame(ui, |ui| {
+                    render_memory_reservation(ui, &mut settings);
+                });
+
+                ui.add_space(20.0);
+
+                // Limit Presets
+                section_header(ui, "\u{1F39A}", "Limit Presets");
+                section_frame(ui, |ui| {
+                    render_limit_presets(ui, &mut settings);
+                });
+
+                ui.add_space(20.0);
+
+                // Known Issues
+                section_header(ui, "\u{1F4A1}", "Known Issues");
+                section_frame(ui, |ui| {
+                    render_known_issues(ui, &mut settings);
+                });
+
+                ui.add_space(20.0);
+
+                section_header(ui, "\u{1F3F7}", "Metadata Fields");
+                section_frame(ui, |ui| {
+                    render_metadata_fields(ui, &mut settings);
+                });
+
+                ui.add_space(20.0);
+
                 // Automation
                 section_header(ui, "\u{1F504}", "Automation");
                 section_frame(ui, |ui| {
@@ -380,12 +1844,36 @@ pub fn render(ui: &mut Ui, state: &mut AppState, ctx: &Context) {
                         },
                     );
 
+                    setting_row(
+                        ui,
+                        "Pinned region capture",
+                        "How often pinned window regions on the dashboard refresh",
+                        |ui| {
+                            ui.add(
+                                egui::DragValue::new(&mut settings.region_capture_secs)
+                                    .range(1..=300)
+                                    .suffix(" sec")
+                                    .speed(1.0),
+                            );
+                        },
+                    );
+
                     toggle_setting(
                         ui,
                         &mut settings.enable_health_checks,
                         "Enable health checks",
                         "Periodically check if instances are responding correctly",
                     );
+
+                    render_crash_dialogs(ui, &mut settings.crash_dialogs);
+                });
+
+                ui.add_space(20.0);
+
+                // Usage Limits
+                section_header(ui, "\u{23F3}", "Usage Limits");
+                section_frame(ui, |ui| {
+                    render_usage_limits(ui, &mut settings, &usage_today);
                 });
 
                 ui.add_space(20.0);
@@ -418,6 +1906,50 @@ pub fn render(ui: &mut Ui, state: &mut AppState, ctx: &Context) {
                         "Play sound",
                         "Play an audio alert when notifications appear",
                     );
+
+                    setting_row(
+                        ui,
+                        "Webhook URL",
+                        "Alerts routed to the webhook are POSTed here as JSON",
+                        |ui| {
+                            ui.add(
+                                egui::TextEdit::singleline(&mut settings.alert_webhook_url)
+                                    .hint_text("https://example.com/hook")
+                                    .desired_width(220.0),
+                            );
+                        },
+                    );
+
+                    render_alert_rules(ui, &mut settings, &alert_targets);
+                });
+
+                ui.add_space(20.0);
+
+                // Email Alerts
+                section_header(ui, "\u{2709}", "Email Alerts");
+                section_frame(ui, |ui| {
+                    render_email_alerts(ui, &mut settings.email_alerts);
+                });
+
+                ui.add_space(20.0);
+
+                // Hotkeys
+                section_header(ui, "\u{2328}", "Hotkeys");
+                section_frame(ui, |ui| {
+                    render_hotkeys(ui, &mut settings, &hotkey_actions, &hotkey_errors);
+                });
+
+                ui.add_space(20.0);
+
+                // Input Broadcasting
+                section_header(ui, "\u{1F4E1}", "Input Broadcasting");
+                section_frame(ui, |ui| {
+                    toggle_broadcast = render_broadcast(
+                        ui,
+                        &mut settings.broadcast,
+                        &groups,
+                        state.is_broadcasting(),
+                    );
                 });
 
                 ui.add_space(20.0);
@@ -441,6 +1973,19 @@ pub fn render(ui: &mut Ui, state: &mut AppState, ctx: &Context) {
                         },
                     );
 
+                    setting_row(
+                        ui,
+                        "Resource graph history",
+                        "How far back the graphs in instance details go",
+                        |ui| {
+                            ui.add(
+                                egui::DragValue::new(&mut settings.usage_history_minutes)
+                                    .range(1..=240)
+                                    .suffix(" min"),
+                            );
+                        },
+                    );
+
                     let max_desc = if settings.max_instances == 0 {
                         "No limit on concurrent instances".to_string()
                     } else {
@@ -453,9 +1998,12 @@ pub fn render(ui: &mut Ui, state: &mut AppState, ctx: &Context) {
                     });
 
                     let retention_desc = if settings.history_retention_days == 0 {
-                        "Keep history forever".to_string()
+                        "Keep history and instance logs forever".to_string()
                     } else {
-                        format!("Keep {} days of history", settings.history_retention_days)
+                        format!(
+                            "Keep {} days of history and instance logs",
+                            settings.history_retention_days
+                        )
                     };
                     setting_row(ui, "History retention", &retention_desc, |ui| {
                         let mut days = settings.history_retention_days as i32;
@@ -474,11 +2022,103 @@ pub fn render(ui: &mut Ui, state: &mut AppState, ctx: &Context) {
                         "Debug logging",
                         "Enable verbose logging for troubleshooting",
                     );
+
+                    toggle_setting(
+                        ui,
+                        &mut settings.show_profiler_overlay,
+                        "Profiler overlay",
+                        "Show frame, monitor and database timings and MultiInstance's own footprint",
+                    );
+
+                    let memory_desc = if settings.self_budget.memory_mb == 0 {
+                        "Never warn about MultiInstance's own memory use".to_string()
+                    } else {
+                        format!(
+                            "Warn when MultiInstance itself uses over {} MB",
+                            settings.self_budget.memory_mb
+                        )
+                    };
+                    setting_row(ui, "Own memory budget", &memory_desc, |ui| {
+                        ui.add(
+                            egui::DragValue::new(&mut settings.self_budget.memory_mb)
+                                .range(0..=8192)
+                                .suffix(" MB")
+                                .speed(4.0),
+                        );
+                    });
+
+                    let cpu_desc = if settings.self_budget.cpu_percent <= 0.0 {
+                        "Never warn about MultiInstance's own CPU use".to_string()
+                    } else {
+                        format!(
+                            "Warn when MultiInstance itself keeps using over {:.0}% of a core",
+                            settings.self_budget.cpu_percent
+                        )
+                    };
+                    setting_row(ui, "Own CPU budget", &cpu_desc, |ui| {
+                        ui.add(
+                            egui::DragValue::new(&mut settings.self_budget.cpu_percent)
+                                .range(0.0..=400.0)
+                                .suffix("%")
+                                .speed(1.0),
+                        );
+                    });
+                });
+
+                ui.add_space(20.0);
+
+                // Security
+                section_header(ui, "\u{1F512}", "Security");
+                section_frame(ui, |ui| {
+                    render_security(ui, &mut settings, unlocked);
+                });
+
+                ui.add_space(20.0);
+
+                // Experimental
+                let mut checkpoint_action = None;
+                section_header(ui, "\u{1F9EA}", "Experimental");
+                section_frame(ui, |ui| {
+                    toggle_setting(
+                        ui,
+                        &mut settings.experimental_checkpoints,
+                        "Checkpoints (suspend to disk)",
+                        "Save running instances to disk and restore them later (Linux only, needs CRIU)",
+                    );
+
+                    if settings.experimental_checkpoints {
+                        let checkpoints = state.list_checkpoints(&settings.get_checkpoints_directory());
+                        if checkpoints.is_empty() {
+                            ui.label(
+                                egui::RichText::new("No saved checkpoints")
+                                    .size(12.0)
+                                    .color(Theme::text_muted()),
+                            );
+                        }
+                        for checkpoint in checkpoints {
+                            let description = format!(
+                                "Saved {}",
+                                checkpoint
+                                    .created_at
+                                    .with_timezone(&chrono::Local)
+                                    .format("%Y-%m-%d %H:%M")
+                            );
+                            setting_row(ui, &checkpoint.config.name, &description, |ui| {
+                                if ui.button("Delete").clicked() {
+                                    checkpoint_action = Some((checkpoint.clone(), false));
+                                }
+                                if ui.button("Restore").clicked() {
+                                    checkpoint_action = Some((checkpoint.clone(), true));
+                                }
+                            });
+                        }
+                    }
                 });
 
                 ui.add_space(20.0);
 
                 // Data
+                let mut settings_file_action = None;
                 section_header(ui, "\u{1F4C1}", "Data");
                 section_frame(ui, |ui| {
                     let data_dir = settings.get_data_directory();
@@ -486,7 +2126,7 @@ pub fn render(ui: &mut Ui, state: &mut AppState, ctx: &Context) {
                         if ui
                             .add(
                                 egui::Button::new("Open Folder")
-                                    .fill(Theme::BG_TERTIARY)
+                                    .fill(Theme::bg_tertiary())
                                     .rounding(egui::Rounding::same(6.0))
                                     .min_size(egui::vec2(100.0, 28.0)),
                             )
@@ -495,16 +2135,202 @@ pub fn render(ui: &mut Ui, state: &mut AppState, ctx: &Context) {
                             let _ = open::that(&data_dir);
                         }
                     });
+
+                    setting_row(
+                        ui,
+                        "Settings file",
+                        "Copy these settings to another machine (PIN lock and data directory stay here)",
+                        |ui| {
+                            if ui
+                                .add(
+                                    egui::Button::new("Import")
+                                        .fill(Theme::bg_tertiary())
+                                        .rounding(egui::Rounding::same(6.0))
+                                        .min_size(egui::vec2(80.0, 28.0)),
+                                )
+                                .clicked()
+                            {
+                                settings_file_action = rfd::FileDialog::new()
+                                    .add_filter("Settings", &["json"])
+                                    .pick_file()
+                                    .map(|path| (path, true));
+                            }
+                            if ui
+                                .add(
+                                    egui::Button::new("Export")
+                                        .fill(Theme::bg_tertiary())
+                                        .rounding(egui::Rounding::same(6.0))
+                                        .min_size(egui::vec2(80.0, 28.0)),
+                                )
+                                .clicked()
+                            {
+                                settings_file_action = rfd::FileDialog::new()
+                                    .add_filter("Settings", &["json"])
+                                    .set_file_name("multiinstance-settings.json")
+                                    .save_file()
+                                    .map(|path| (path, false));
+                            }
+                        },
+                    );
+
+                    setting_row(
+                        ui,
+                        "Move to another machine",
+                        "Everything in one encrypted archive: instances, profiles, settings, history and optionally instance data",
+                        |ui| {
+                            if ui
+                                .add(
+                                    egui::Button::new("Import")
+                                        .fill(Theme::bg_tertiary())
+                                        .rounding(egui::Rounding::same(6.0))
+                                        .min_size(egui::vec2(80.0, 28.0)),
+                                )
+                                .clicked()
+                            {
+                                *migration = Some(MigrationWizard::import());
+                            }
+                            if ui
+                                .add(
+                                    egui::Button::new("Export")
+                                        .fill(Theme::bg_tertiary())
+                                        .rounding(egui::Rounding::same(6.0))
+                                        .min_size(egui::vec2(80.0, 28.0)),
+                                )
+                                .clicked()
+                            {
+                                *migration = Some(MigrationWizard::export());
+                            }
+                        },
+                    );
+
+                    let sync_description = match &settings.sync_directory {
+                        Some(dir) => format!("Profiles and quick launch items are mirrored into {}", dir.to_string_lossy()),
+                        None => "Mirror profiles and quick launch items into a folder such as Dropbox or OneDrive to share them between machines".to_string(),
+                    };
+                    setting_row(ui, "Sync folder", &sync_description, |ui| {
+                        if settings.sync_directory.is_some()
+                            && ui
+                                .add(
+                                    egui::Button::new("Stop")
+                                        .fill(Theme::bg_tertiary())
+                                        .rounding(egui::Rounding::same(6.0))
+                                        .min_size(egui::vec2(80.0, 28.0)),
+                                )
+                                .clicked()
+                        {
+                            settings.sync_directory = None;
+                        }
+                        if ui
+                            .add(
+                                egui::Button::new("Choose...")
+                                    .fill(Theme::bg_tertiary())
+                                    .rounding(egui::Rounding::same(6.0))
+                                    .min_size(egui::vec2(80.0, 28.0)),
+                            )
+                            .clicked()
+                        {
+                            if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                                settings.sync_directory = Some(dir);
+                            }
+                        }
+                    });
+
+                    let scope = InstallScope::detect();
+                    let machine_dir = deployment::machine_directory();
+                    let description = format!(
+                        "{} install, shared templates in {}",
+                        scope.label(),
+                        machine_dir.to_string_lossy()
+                    );
+                    setting_row(ui, "Installation", &description, |ui| {
+                        if scope == InstallScope::PerMachine
+                            && ui
+                                .add(
+                                    egui::Button::new("Publish as Default")
+                                        .fill(Theme::bg_tertiary())
+                                        .rounding(egui::Rounding::same(6.0))
+                                        .min_size(egui::vec2(100.0, 28.0)),
+                                )
+                                .on_hover_text(
+                                    "Make these settings the starting configuration for new users (needs administrator rights)",
+                                )
+                                .clicked()
+                        {
+                            if let Err(e) = deployment::save_default_settings(&settings) {
+                                tracing::error!("Failed to publish default settings: {}", e);
+                            }
+                        }
+                    });
                 });
 
                 ui.add_space(32.0);
 
                 drop(settings);
 
+                if toggle_broadcast {
+                    if let Err(e) = state.toggle_broadcast() {
+                        tracing::error!("Failed to toggle input broadcasting: {}", e);
+                    }
+                }
+
+                if let Some(action) = theme_action {
+                    let result = match action {
+                        ThemeAction::Save(original, theme) => {
+                            let name = theme.name.trim().to_string();
+                            state
+                                .save_custom_theme(original.as_deref(), theme)
+                                .map(|_| Some(name))
+                        }
+                        ThemeAction::Import(path) => state.import_custom_theme(&path).map(Some),
+                        ThemeAction::Delete(name) => {
+                            state.delete_custom_theme(&name).map(|_| None)
+                        }
+                        ThemeAction::Export(name, path) => {
+                            state.export_custom_theme(&name, &path).map(|_| None)
+                        }
+                    };
+                    match result {
+                        Ok(selected) => {
+                            let mut settings = state.settings.write().unwrap();
+                            if selected.is_some() {
+                                settings.custom_theme = selected;
+                            }
+                            let custom = settings
+                                .custom_theme
+                                .as_deref()
+                                .and_then(|name| state.custom_theme(name));
+                            Theme::apply(ctx, settings.theme, custom.as_ref());
+                        }
+                        Err(e) => tracing::error!("Custom theme action failed: {}", e),
+                    }
+                }
+
+                if let Some((path, import)) = settings_file_action {
+                    let result = if import {
+                        state.import_settings(&path)
+                    } else {
+                        state.export_settings(&path)
+                    };
+                    if let Err(e) = result {
+                        tracing::error!("Settings file action failed: {}", e);
+                    }
+                }
+
+                if let Some((checkpoint, restore)) = checkpoint_action {
+                    let result = if restore {
+                        state.restore_checkpoint(&checkpoint).map(|_| ())
+                    } else {
+                        state.delete_checkpoint(&checkpoint)
+                    };
+                    if let Err(e) = result {
+                        tracing::error!("Checkpoint action failed: {}", e);
+                    }
+                }
+
                 // Action buttons
                 ui.horizontal(|ui| {
                     let save_btn = egui::Button::new("Save Settings")
-                        .fill(Theme::PRIMARY)
+                        .fill(Theme::primary())
                         .rounding(egui::Rounding::same(8.0))
                         .min_size(egui::vec2(140.0, 40.0));
 
@@ -517,12 +2343,20 @@ pub fn render(ui: &mut Ui, state: &mut AppState, ctx: &Context) {
                     ui.add_space(12.0);
 
                     let reset_btn = egui::Button::new("Reset to Defaults")
-                        .fill(Theme::BG_TERTIARY)
+                        .fill(Theme::bg_tertiary())
                         .rounding(egui::Rounding::same(8.0))
                         .min_size(egui::vec2(140.0, 40.0));
 
                     if ui.add(reset_btn).clicked() {
-                        *state.settings.write().unwrap() = crate::core::Settings::default();
+                        let mut settings = state.settings.write().unwrap();
+                        // Resetting must not silently remove the PIN lock
+                        let settings_lock = settings.settings_lock.take();
+                        // Start from the administrator's defaults when they exist
+                        let defaults = deployment::load_default_settings().unwrap_or_default();
+                        *settings = crate::core::Settings {
+                            settings_lock,
+                            ..defaults
+                        };
                     }
                 });
 
@@ -530,7 +2364,7 @@ pub fn render(ui: &mut Ui, state: &mut AppState, ctx: &Context) {
 
                 // About section
                 egui::Frame::none()
-                    .fill(Theme::BG_TERTIARY.linear_multiply(0.4))
+                    .fill(Theme::bg_tertiary().linear_multiply(0.4))
                     .rounding(egui::Rounding::same(12.0))
                     .inner_margin(egui::Margin::same(20.0))
                     .show(ui, |ui| {
@@ -542,7 +2376,7 @@ pub fn render(ui: &mut Ui, state: &mut AppState, ctx: &Context) {
                                 ))
                                 .size(15.0)
                                 .strong()
-                                .color(Color32::WHITE),
+                                .color(Theme::text_primary()),
                             );
                             ui.add_space(6.0);
                             ui.label(
@@ -550,7 +2384,7 @@ pub fn render(ui: &mut Ui, state: &mut AppState, ctx: &Context) {
                                     "Run multiple instances of single-instance applications",
                                 )
                                 .size(13.0)
-                                .color(Theme::TEXT_SECONDARY),
+                                .color(Theme::text_secondary()),
                             );
                         });
                     });
@@ -0,0 +1,227 @@
+//! Debounced, incremental instance search
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use egui::text::LayoutJob;
+use egui::{Color32, FontId, TextFormat};
+
+use crate::core::{Instance, InstanceId};
+use crate::ui::theme::Theme;
+
+/// Delay after the last keystroke before the filter is applied
+const DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Search box state and the instances matching the applied query
+pub struct InstanceSearch {
+    /// Text currently in the search box
+    pub input: String,
+    /// Lowercased query the matches were computed for
+    query: String,
+    /// Time of the last edit not yet applied
+    pending_since: Option<Instant>,
+    /// Matching instance IDs, in map order
+    matches: Vec<InstanceId>,
+    /// Number of instances when the matches were computed
+    indexed_count: usize,
+    /// Instance revision the matches were computed at
+    indexed_revision: u64,
+    /// Tags an instance must all have to match, from the filter chips
+    tags: Vec<String>,
+}
+
+impl Default for InstanceSearch {
+    fn default() -> Self {
+        Self {
+            input: String::new(),
+            query: String::new(),
+            pending_since: None,
+            matches: Vec::new(),
+            // Forces a full walk on the first update
+            indexed_count: usize::MAX,
+            indexed_revision: 0,
+            tags: Vec::new(),
+        }
+    }
+}
+
+impl InstanceSearch {
+    /// Record that the search box changed
+    pub fn mark_edited(&mut self) {
+        self.pending_since = Some(Instant::now());
+    }
+
+    /// The applied (debounced) query, lowercased
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Instances matching the applied query
+    pub fn matches(&self) -> &[InstanceId] {
+        &self.matches
+    }
+
+    /// Tags selected in the filter chips
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Select or deselect a filter tag; the matches are rebuilt on the next update
+    pub fn toggle_tag(&mut self, tag: &str) {
+        if let Some(index) = self.tags.iter().position(|t| t.eq_ignore_ascii_case(tag)) {
+            self.tags.remove(index);
+        } else {
+            self.tags.push(tag.to_string());
+        }
+        self.indexed_count = usize::MAX;
+    }
+
+    /// Deselect every filter tag
+    pub fn clear_tags(&mut self) {
+        if !self.tags.is_empty() {
+            self.tags.clear();
+            self.indexed_count = usize::MAX;
+        }
+    }
+
+    /// Whether a query or tag narrows the instances shown
+    pub fn is_filtered(&self) -> bool {
+        !self.query.is_empty() || !self.tags.is_empty()
+    }
+
+    fn has_tags(&self, instance: &Instance) -> bool {
+        self.tags.iter().all(|tag| {
+            instance
+                .config
+                .tags
+                .iter()
+                .any(|t| t.eq_ignore_ascii_case(tag))
+        })
+    }
+
+    /// Apply a pending edit once the debounce delay has passed, and refresh the
+    /// matches when instances were added, removed or saved with new settings.
+    pub fn update(
+        &mut self,
+        ctx: &egui::Context,
+        instances: &HashMap<InstanceId, Instance>,
+        revision: u64,
+    ) {
+        if let Some(since) = self.pending_since {
+            let elapsed = since.elapsed();
+            if elapsed < DEBOUNCE {
+                ctx.request_repaint_after(DEBOUNCE - elapsed);
+            } else {
+                self.pending_since = None;
+                let query = self.input.trim().to_lowercase();
+                if query != self.query {
+                    self.apply(query, instances);
+                }
+            }
+        }
+
+        if instances.len() != self.indexed_count || revision != self.indexed_revision {
+            self.indexed_revision = revision;
+            self.rebuild(instances);
+        }
+    }
+
+    /// Narrow the previous matches when the query only grew, otherwise walk everything
+    fn apply(&mut self, query: String, instances: &HashMap<InstanceId, Instance>) {
+        let narrows = !self.query.is_empty() && query.starts_with(&self.query);
+        self.query = query;
+
+        if narrows && instances.len() == self.indexed_count {
+            let mut matches = std::mem::take(&mut self.matches);
+            matches.retain(|id| {
+                instances
+                    .get(id)
+                    .map(|i| instance_matches(i, &self.query) && self.has_tags(i))
+                    .unwrap_or(false)
+            });
+            self.matches = matches;
+        } else {
+            self.rebuild(instances);
+        }
+    }
+
+    fn rebuild(&mut self, instances: &HashMap<InstanceId, Instance>) {
+        self.matches = instances
+            .iter()
+            .filter(|(_, i)| instance_matches(i, &self.query) && self.has_tags(i))
+            .map(|(id, _)| *id)
+            .collect();
+        self.indexed_count = instances.len();
+    }
+}
+
+/// Whether an instance matches a lowercased query. `field:value` matches the
+/// value of one metadata field.
+pub fn instance_matches(instance: &Instance, query: &str) -> bool {
+    if let Some((field, value)) = query.split_once(':') {
+        if let Some((_, actual)) = instance
+            .config
+            .metadata
+            .iter()
+            .find(|(name, _)| name.to_lowercase() == field.trim())
+        {
+            return actual.to_lowercase().contains(value.trim());
+        }
+    }
+    query.is_empty()
+        || instance.display_name().to_lowercase().contains(query)
+        || instance
+            .config
+            .executable_path
+            .to_string_lossy()
+            .to_lowercase()
+            .contains(query)
+        || instance
+            .config
+            .group
+            .as_ref()
+            .map(|g| g.to_lowercase().contains(query))
+            .unwrap_or(false)
+        || instance
+            .config
+            .tags
+            .iter()
+            .any(|t| t.to_lowercase().contains(query))
+        || instance
+            .config
+            .metadata
+            .iter()
+            .any(|(k, v)| k.to_lowercase().contains(query) || v.to_lowercase().contains(query))
+}
+
+/// Lay out `text` with every case-insensitive occurrence of `query` highlighted
+pub fn highlighted(text: &str, query: &str, size: f32, color: Color32) -> LayoutJob {
+    let plain = TextFormat::simple(FontId::proportional(size), color);
+    let mut job = LayoutJob::default();
+
+    // Byte offsets only line up when lowercasing keeps every character's length
+    let lower = text.to_lowercase();
+    if query.is_empty() || lower.len() != text.len() {
+        job.append(text, 0.0, plain);
+        return job;
+    }
+
+    let mark = TextFormat {
+        background: Theme::primary().linear_multiply(0.35),
+        color: Theme::text_primary(),
+        ..plain.clone()
+    };
+
+    let mut cursor = 0;
+    for (start, matched) in lower.match_indices(query) {
+        if start < cursor || !text.is_char_boundary(start) {
+            continue;
+        }
+        let end = start + matched.len();
+        job.append(&text[cursor..start], 0.0, plain.clone());
+        job.append(&text[start..end], 0.0, mark.clone());
+        cursor = end;
+    }
+    job.append(&text[cursor..], 0.0, plain);
+    job
+}
@@ -0,0 +1,308 @@
+//! Quick switcher - Alt-Tab style picker for the running instances
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use egui::{Context, Key, Modifiers};
+
+use crate::core::window_capture::{RegionCapture, THUMBNAIL_WIDTH};
+use crate::core::{AppState, InstanceId, InstanceStatus};
+use crate::ui::panels::dashboard::capture_texture;
+use crate::ui::theme::Theme;
+
+/// How often thumbnails are recaptured while the switcher is open
+const THUMBNAIL_INTERVAL: Duration = Duration::from_secs(1);
+
+const ICON_SIZE: f32 = 20.0;
+
+/// A running instance offered by the switcher
+struct Entry {
+    id: InstanceId,
+    name: String,
+    status: InstanceStatus,
+    icon_path: Option<PathBuf>,
+}
+
+/// Switcher state; the switcher is drawn over the main window while open
+#[derive(Debug, Default)]
+pub struct QuickSwitcher {
+    open: bool,
+    selected: usize,
+    last_capture: Option<Instant>,
+}
+
+impl QuickSwitcher {
+    /// Open the switcher, or move to the next instance when it already is,
+    /// so pressing the hotkey again cycles like Alt-Tab
+    pub fn open_or_advance(&mut self) {
+        if self.open {
+            self.selected += 1;
+        } else {
+            *self = Self {
+                open: true,
+                ..Self::default()
+            };
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Render the switcher. Returns the instance picked, after which it closes.
+    pub fn show(&mut self, ctx: &Context, state: &AppState) -> Option<InstanceId> {
+        if !self.open {
+            return None;
+        }
+        if self
+            .last_capture
+            .is_none_or(|t| t.elapsed() >= THUMBNAIL_INTERVAL)
+        {
+            state.capture_thumbnails();
+            self.last_capture = Some(Instant::now());
+        }
+
+        let mut entries: Vec<Entry> = state
+            .instances
+            .read()
+            .map(|instances| {
+                instances
+                    .values()
+                    .filter(|i| i.status.is_active() && i.pid.is_some())
+                    .map(|i| Entry {
+                        id: i.id,
+                        name: i.display_name().to_string(),
+                        status: i.status,
+                        icon_path: i.config.icon_path.clone(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        entries.sort_by_key(|e| e.name.to_lowercase());
+        let thumbnails = state
+            .window_thumbnails
+            .read()
+            .map(|t| t.clone())
+            .unwrap_or_default();
+
+        // Keyboard: Tab/arrows move, Enter picks, Escape closes
+        let count = entries.len().max(1);
+        self.selected %= count;
+        let mut picked = None;
+        ctx.input_mut(|i| {
+            if i.consume_key(Modifiers::SHIFT, Key::Tab)
+                || i.consume_key(Modifiers::NONE, Key::ArrowLeft)
+            {
+                self.selected = (self.selected + count - 1) % count;
+            }
+            if i.consume_key(Modifiers::NONE, Key::Tab)
+                || i.consume_key(Modifiers::NONE, Key::ArrowRight)
+            {
+                self.selected = (self.selected + 1) % count;
+            }
+            if i.consume_key(Modifiers::NONE, Key::Enter) {
+                picked = entries.get(self.selected).map(|e| e.id);
+            }
+            if i.consume_key(Modifiers::NONE, Key::Escape) {
+                self.open = false;
+            }
+        });
+
+        let screen = ctx.screen_rect();
+        egui::Area::new(egui::Id::new("quick_switcher_backdrop"))
+            .fixed_pos(screen.min)
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                let response = ui.allocate_rect(screen, egui::Sense::click());
+                ui.painter()
+                    .rect_filled(screen, 0.0, egui::Color32::from_black_alpha(140));
+                if response.clicked() {
+                    self.open = false;
+                }
+            });
+
+        egui::Area::new(egui::Id::new("quick_switcher"))
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .order(egui::Order::Tooltip)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(Theme::bg_elevated())
+                    .rounding(egui::Rounding::same(12.0))
+                    .stroke(egui::Stroke::new(1.0, Theme::border()))
+                    .inner_margin(egui::Margin::same(16.0))
+                    .show(ui, |ui| {
+                        ui.set_max_width((screen.width() - 80.0).max(THUMBNAIL_WIDTH as f32));
+                        ui.label(
+                            egui::RichText::new("Switch to instance")
+                                .strong()
+                                .color(Theme::text_primary()),
+                        );
+                        ui.add_space(12.0);
+
+                        if entries.is_empty() {
+                            ui.label(
+                                egui::RichText::new("No instances are running")
+                                    .color(Theme::text_muted()),
+                            );
+                            return;
+                        }
+
+                        ui.horizontal_wrapped(|ui| {
+                            for (index, entry) in entries.iter().enumerate() {
+                                let response = render_entry(
+                                    ui,
+                                    entry,
+                                    thumbnails.get(&entry.id),
+                                    index == self.selected,
+                                );
+                                if response.hovered() {
+                                    self.selected = index;
+                                }
+                                if response.clicked() {
+                                    picked = Some(entry.id);
+                                }
+                            }
+                        });
+
+                        ui.add_space(8.0);
+                        ui.label(
+                            egui::RichText::new(
+                                "Tab or arrows to move, Enter or click to switch, Esc to close",
+                            )
+                            .size(11.0)
+                            .color(Theme::text_muted()),
+                        );
+                    });
+            });
+
+        if picked.is_some() {
+            self.open = false;
+        }
+        picked
+    }
+}
+
+/// One instance: icon and name over its window thumbnail
+fn render_entry(
+    ui: &mut egui::Ui,
+    entry: &Entry,
+    thumbnail: Option<&RegionCapture>,
+    selected: bool,
+) -> egui::Response {
+    let width = THUMBNAIL_WIDTH as f32;
+    let frame = egui::Frame::none()
+        .fill(if selected {
+            Theme::bg_hover()
+        } else {
+            Theme::bg_secondary()
+        })
+        .rounding(egui::Rounding::same(8.0))
+        .stroke(if selected {
+            egui::Stroke::new(2.0, Theme::primary())
+        } else {
+            egui::Stroke::new(1.0, Theme::border_light())
+        })
+        .inner_margin(egui::Margin::same(8.0))
+        .show(ui, |ui| {
+            ui.set_width(width);
+            ui.horizontal(|ui| {
+                render_icon(ui, entry);
+                ui.add(
+                    egui::Label::new(
+                        egui::RichText::new(&entry.name)
+                            .strong()
+                            .color(Theme::text_primary()),
+                    )
+                    .truncate(),
+                );
+            });
+            ui.add_space(6.0);
+
+            let placeholder = |ui: &mut egui::Ui, text: &str| {
+                let (rect, _) =
+                    ui.allocate_exact_size(egui::vec2(width, width * 0.5625), egui::Sense::hover());
+                ui.painter()
+                    .rect_filled(rect, egui::Rounding::same(4.0), Theme::bg_tertiary());
+                ui.painter().text(
+                    rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    text,
+                    egui::FontId::proportional(11.0),
+                    Theme::text_muted(),
+                );
+            };
+            match thumbnail {
+                Some(capture) => {
+                    match capture_texture(ui.ctx(), "switcher_thumbnail", entry.id, capture) {
+                        Some(texture) => {
+                            let size = texture.size_vec2();
+                            let size = size * (width / size.x.max(1.0)).min(1.0);
+                            ui.image((texture.id(), size));
+                        }
+                        None => placeholder(ui, "No preview"),
+                    }
+                }
+                None => placeholder(ui, "Capturing…"),
+            }
+        });
+
+    let response = frame.response.interact(egui::Sense::click());
+    match thumbnail.and_then(|c| c.image.as_ref().err()) {
+        Some(error) => response.on_hover_text(error),
+        None => response,
+    }
+}
+
+/// The instance's custom icon, or its initial on its status color
+fn render_icon(ui: &mut egui::Ui, entry: &Entry) {
+    if let Some(texture) = entry
+        .icon_path
+        .as_deref()
+        .and_then(|path| icon_texture(ui.ctx(), path))
+    {
+        ui.image((texture.id(), egui::Vec2::splat(ICON_SIZE)));
+        return;
+    }
+
+    let (rect, _) = ui.allocate_exact_size(egui::Vec2::splat(ICON_SIZE), egui::Sense::hover());
+    ui.painter().rect_filled(
+        rect,
+        egui::Rounding::same(4.0),
+        Theme::status_color(&entry.status),
+    );
+    let initial = entry
+        .name
+        .chars()
+        .next()
+        .map(|c| c.to_uppercase().to_string())
+        .unwrap_or_default();
+    ui.painter().text(
+        rect.center(),
+        egui::Align2::CENTER_CENTER,
+        initial,
+        egui::FontId::proportional(12.0),
+        Theme::text_primary(),
+    );
+}
+
+/// Load a custom icon once; a file that fails to load is not retried
+fn icon_texture(ctx: &Context, path: &Path) -> Option<egui::TextureHandle> {
+    let key = egui::Id::new(("instance_icon", path));
+    if let Some(cached) = ctx.data(|d| d.get_temp::<Option<egui::TextureHandle>>(key)) {
+        return cached;
+    }
+    let texture = image::open(path).ok().map(|icon| {
+        let icon = icon.thumbnail(64, 64).to_rgba8();
+        let pixels = egui::ColorImage::from_rgba_unmultiplied(
+            [icon.width() as usize, icon.height() as usize],
+            icon.as_raw(),
+        );
+        ctx.load_texture(
+            format!("instance_icon_{}", path.display()),
+            pixels,
+            egui::TextureOptions::LINEAR,
+        )
+    });
+    ctx.data_mut(|d| d.insert_temp(key, texture.clone()));
+    texture
+}
@@ -1,100 +1,349 @@
 //! Theme and styling for the UI
 
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
 use egui::{Color32, FontFamily, FontId, Rounding, Stroke, TextStyle, Visuals};
 
+use crate::core::settings::Theme as SettingsTheme;
+use crate::core::CustomTheme;
+
+/// Palette applied last
+static PALETTE: RwLock<Palette> = RwLock::new(Palette::DARK);
+
+/// Colors of the applied theme
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    pub bg_primary: Color32,
+    /// Card background
+    pub bg_secondary: Color32,
+    /// Elevated elements
+    pub bg_tertiary: Color32,
+    pub bg_hover: Color32,
+    /// Modals/dropdowns
+    pub bg_elevated: Color32,
+    pub text_primary: Color32,
+    pub text_secondary: Color32,
+    pub text_muted: Color32,
+    pub border: Color32,
+    pub border_light: Color32,
+    pub primary: Color32,
+    pub primary_hover: Color32,
+    pub primary_light: Color32,
+    pub primary_dark: Color32,
+    pub success: Color32,
+    pub success_light: Color32,
+    pub warning: Color32,
+    pub warning_light: Color32,
+    pub error: Color32,
+    pub error_light: Color32,
+    pub info: Color32,
+}
+
+impl Palette {
+    /// Modern charcoal palette
+    pub const DARK: Self = Self {
+        bg_primary: Color32::from_rgb(17, 17, 27), // Deep charcoal
+        bg_secondary: Color32::from_rgb(24, 24, 37), // Card background
+        bg_tertiary: Color32::from_rgb(35, 35, 52), // Elevated elements
+        bg_hover: Color32::from_rgb(45, 45, 65),   // Hover state
+        bg_elevated: Color32::from_rgb(30, 30, 45), // Modals/dropdowns
+        text_primary: Color32::from_rgb(250, 250, 255), // Near white
+        text_secondary: Color32::from_rgb(161, 161, 180), // Gray-400
+        text_muted: Color32::from_rgb(113, 113, 132), // Gray-500
+        border: Color32::from_rgb(50, 50, 70),     // Subtle border
+        border_light: Color32::from_rgb(38, 38, 55), // Lighter border
+        // Refined indigo/violet accent
+        primary: Color32::from_rgb(99, 102, 241), // Indigo-500
+        primary_hover: Color32::from_rgb(79, 70, 229), // Indigo-600
+        primary_light: Color32::from_rgb(165, 180, 252), // Indigo-300
+        primary_dark: Color32::from_rgb(67, 56, 202), // Indigo-700
+        // Status colors - balanced and harmonious
+        success: Color32::from_rgb(16, 185, 129), // Emerald-500
+        success_light: Color32::from_rgb(52, 211, 153), // Emerald-400
+        warning: Color32::from_rgb(245, 158, 11), // Amber-500
+        warning_light: Color32::from_rgb(251, 191, 36), // Amber-400
+        error: Color32::from_rgb(244, 63, 94),    // Rose-500
+        error_light: Color32::from_rgb(251, 113, 133), // Rose-400
+        info: Color32::from_rgb(6, 182, 212),     // Cyan-500
+    };
+
+    /// Clean gray palette
+    pub const LIGHT: Self = Self {
+        bg_primary: Color32::from_rgb(249, 250, 251), // Gray-50
+        bg_secondary: Color32::from_rgb(243, 244, 246), // Gray-100
+        bg_tertiary: Color32::from_rgb(229, 231, 235), // Gray-200
+        bg_hover: Color32::from_rgb(209, 213, 219),   // Gray-300
+        bg_elevated: Color32::WHITE,
+        text_primary: Color32::from_rgb(17, 24, 39), // Gray-900
+        text_secondary: Color32::from_rgb(75, 85, 99), // Gray-600
+        text_muted: Color32::from_rgb(107, 114, 128), // Gray-500
+        border: Color32::from_rgb(209, 213, 219),    // Gray-300
+        border_light: Color32::from_rgb(229, 231, 235), // Gray-200
+        ..Self::DARK
+    };
+
+    /// Editable colors as (key, label), keys as stored in custom themes
+    pub const COLORS: [(&'static str, &'static str); 21] = [
+        ("bg_primary", "Background"),
+        ("bg_secondary", "Card background"),
+        ("bg_tertiary", "Elevated background"),
+        ("bg_hover", "Hover background"),
+        ("bg_elevated", "Dialog background"),
+        ("text_primary", "Text"),
+        ("text_secondary", "Secondary text"),
+        ("text_muted", "Muted text"),
+        ("border", "Border"),
+        ("border_light", "Light border"),
+        ("primary", "Accent"),
+        ("primary_hover", "Accent hover"),
+        ("primary_light", "Light accent"),
+        ("primary_dark", "Dark accent"),
+        ("success", "Success"),
+        ("success_light", "Light success"),
+        ("warning", "Warning"),
+        ("warning_light", "Light warning"),
+        ("error", "Error"),
+        ("error_light", "Light error"),
+        ("info", "Info"),
+    ];
+
+    /// Built-in palette a custom theme starts from
+    pub fn base(light: bool) -> Self {
+        if light {
+            Self::LIGHT
+        } else {
+            Self::DARK
+        }
+    }
+
+    /// Color stored under a key of `COLORS`
+    pub fn color(&self, key: &str) -> Option<Color32> {
+        let mut palette = *self;
+        palette.color_mut(key).copied()
+    }
+
+    /// Mutable color stored under a key of `COLORS`
+    pub fn color_mut(&mut self, key: &str) -> Option<&mut Color32> {
+        Some(match key {
+            "bg_primary" => &mut self.bg_primary,
+            "bg_secondary" => &mut self.bg_secondary,
+            "bg_tertiary" => &mut self.bg_tertiary,
+            "bg_hover" => &mut self.bg_hover,
+            "bg_elevated" => &mut self.bg_elevated,
+            "text_primary" => &mut self.text_primary,
+            "text_secondary" => &mut self.text_secondary,
+            "text_muted" => &mut self.text_muted,
+            "border" => &mut self.border,
+            "border_light" => &mut self.border_light,
+            "primary" => &mut self.primary,
+            "primary_hover" => &mut self.primary_hover,
+            "primary_light" => &mut self.primary_light,
+            "primary_dark" => &mut self.primary_dark,
+            "success" => &mut self.success,
+            "success_light" => &mut self.success_light,
+            "warning" => &mut self.warning,
+            "warning_light" => &mut self.warning_light,
+            "error" => &mut self.error,
+            "error_light" => &mut self.error_light,
+            "info" => &mut self.info,
+            _ => return None,
+        })
+    }
+
+    /// Replace colors with `#rrggbb` overrides, skipping unknown keys and invalid values
+    pub fn with_overrides(mut self, colors: &BTreeMap<String, String>) -> Self {
+        for (key, hex) in colors {
+            let Ok(color) = Color32::from_hex(hex) else {
+                tracing::warn!("Invalid theme color {} for {}", hex, key);
+                continue;
+            };
+            if let Some(slot) = self.color_mut(key) {
+                *slot = color;
+            }
+        }
+        self
+    }
+
+    /// Palette of a custom theme
+    pub fn custom(theme: &CustomTheme) -> Self {
+        Self::base(theme.light).with_overrides(&theme.colors)
+    }
+}
+
 /// Application color palette
 pub struct Theme;
 
 impl Theme {
-    // Primary colors - refined indigo/violet accent
-    pub const PRIMARY: Color32 = Color32::from_rgb(99, 102, 241); // Indigo-500
-    pub const PRIMARY_HOVER: Color32 = Color32::from_rgb(79, 70, 229); // Indigo-600
-    pub const PRIMARY_LIGHT: Color32 = Color32::from_rgb(165, 180, 252); // Indigo-300
-    pub const PRIMARY_DARK: Color32 = Color32::from_rgb(67, 56, 202); // Indigo-700
-
-    // Status colors - balanced and harmonious
-    pub const SUCCESS: Color32 = Color32::from_rgb(16, 185, 129); // Emerald-500
-    pub const SUCCESS_LIGHT: Color32 = Color32::from_rgb(52, 211, 153); // Emerald-400
-    pub const WARNING: Color32 = Color32::from_rgb(245, 158, 11); // Amber-500
-    pub const WARNING_LIGHT: Color32 = Color32::from_rgb(251, 191, 36); // Amber-400
-    pub const ERROR: Color32 = Color32::from_rgb(244, 63, 94); // Rose-500
-    pub const ERROR_LIGHT: Color32 = Color32::from_rgb(251, 113, 133); // Rose-400
-    pub const INFO: Color32 = Color32::from_rgb(6, 182, 212); // Cyan-500
-
-    // Neutral colors (dark theme) - modern charcoal palette
-    pub const BG_PRIMARY: Color32 = Color32::from_rgb(17, 17, 27); // Deep charcoal
-    pub const BG_SECONDARY: Color32 = Color32::from_rgb(24, 24, 37); // Card background
-    pub const BG_TERTIARY: Color32 = Color32::from_rgb(35, 35, 52); // Elevated elements
-    pub const BG_HOVER: Color32 = Color32::from_rgb(45, 45, 65); // Hover state
-    pub const BG_ELEVATED: Color32 = Color32::from_rgb(30, 30, 45); // Modals/dropdowns
-
-    // Text colors - crisp contrast
-    pub const TEXT_PRIMARY: Color32 = Color32::from_rgb(250, 250, 255); // Near white
-    pub const TEXT_SECONDARY: Color32 = Color32::from_rgb(161, 161, 180); // Gray-400
-    pub const TEXT_MUTED: Color32 = Color32::from_rgb(113, 113, 132); // Gray-500
-
-    // Border colors - subtle definition
-    pub const BORDER: Color32 = Color32::from_rgb(50, 50, 70); // Subtle border
-    pub const BORDER_LIGHT: Color32 = Color32::from_rgb(38, 38, 55); // Lighter border
-    pub const BORDER_ACCENT: Color32 = Color32::from_rgb(99, 102, 241); // Primary accent
-
-    // Instance status colors
-    pub const STATUS_RUNNING: Color32 = Self::SUCCESS;
-    pub const STATUS_STARTING: Color32 = Self::WARNING;
-    pub const STATUS_PAUSED: Color32 = Self::INFO;
-    pub const STATUS_STOPPED: Color32 = Self::TEXT_MUTED;
-    pub const STATUS_CRASHED: Color32 = Self::ERROR;
+    /// Palette of the theme applied last
+    pub fn palette() -> Palette {
+        PALETTE.read().map(|p| *p).unwrap_or(Palette::DARK)
+    }
+
+    pub fn bg_primary() -> Color32 {
+        Self::palette().bg_primary
+    }
+
+    pub fn bg_secondary() -> Color32 {
+        Self::palette().bg_secondary
+    }
+
+    pub fn bg_tertiary() -> Color32 {
+        Self::palette().bg_tertiary
+    }
+
+    pub fn bg_hover() -> Color32 {
+        Self::palette().bg_hover
+    }
+
+    pub fn bg_elevated() -> Color32 {
+        Self::palette().bg_elevated
+    }
+
+    pub fn text_primary() -> Color32 {
+        Self::palette().text_primary
+    }
+
+    pub fn text_secondary() -> Color32 {
+        Self::palette().text_secondary
+    }
+
+    pub fn text_muted() -> Color32 {
+        Self::palette().text_muted
+    }
+
+    pub fn border() -> Color32 {
+        Self::palette().border
+    }
+
+    pub fn border_light() -> Color32 {
+        Self::palette().border_light
+    }
+
+    pub fn primary() -> Color32 {
+        Self::palette().primary
+    }
+
+    pub fn primary_hover() -> Color32 {
+        Self::palette().primary_hover
+    }
+
+    pub fn primary_light() -> Color32 {
+        Self::palette().primary_light
+    }
+
+    pub fn primary_dark() -> Color32 {
+        Self::palette().primary_dark
+    }
+
+    pub fn success() -> Color32 {
+        Self::palette().success
+    }
+
+    pub fn success_light() -> Color32 {
+        Self::palette().success_light
+    }
+
+    pub fn warning() -> Color32 {
+        Self::palette().warning
+    }
+
+    pub fn warning_light() -> Color32 {
+        Self::palette().warning_light
+    }
+
+    pub fn error() -> Color32 {
+        Self::palette().error
+    }
+
+    pub fn error_light() -> Color32 {
+        Self::palette().error_light
+    }
+
+    pub fn info() -> Color32 {
+        Self::palette().info
+    }
+
+    /// Apply the configured theme, or a custom theme on top of it
+    pub fn apply(ctx: &egui::Context, theme: SettingsTheme, custom: Option<&CustomTheme>) {
+        match (custom, theme) {
+            (Some(custom), _) if custom.light => {
+                Self::apply_light_palette(ctx, Palette::custom(custom))
+            }
+            (Some(custom), _) => Self::apply_dark_palette(ctx, Palette::custom(custom)),
+            (None, SettingsTheme::Light) => Self::apply_light(ctx),
+            // System follows dark for now
+            (None, SettingsTheme::Dark | SettingsTheme::System) => Self::apply_dark(ctx),
+        }
+    }
 
     /// Apply dark theme to egui
     pub fn apply_dark(ctx: &egui::Context) {
+        Self::apply_dark_palette(ctx, Palette::DARK);
+    }
+
+    /// Apply light theme to egui
+    pub fn apply_light(ctx: &egui::Context) {
+        Self::apply_light_palette(ctx, Palette::LIGHT);
+    }
+
+    fn set_palette(palette: Palette) {
+        if let Ok(mut current) = PALETTE.write() {
+            *current = palette;
+        }
+    }
+
+    /// Apply dark styling with the given colors
+    fn apply_dark_palette(ctx: &egui::Context, palette: Palette) {
+        Self::set_palette(palette);
         let mut style = (*ctx.style()).clone();
 
         // Set up visuals
         let mut visuals = Visuals::dark();
 
-        visuals.panel_fill = Self::BG_PRIMARY;
-        visuals.window_fill = Self::BG_ELEVATED;
-        visuals.extreme_bg_color = Self::BG_PRIMARY;
-        visuals.faint_bg_color = Self::BG_TERTIARY;
+        visuals.panel_fill = palette.bg_primary;
+        visuals.window_fill = palette.bg_elevated;
+        visuals.extreme_bg_color = palette.bg_primary;
+        visuals.faint_bg_color = palette.bg_tertiary;
 
         // Non-interactive widgets (labels, etc.)
-        visuals.widgets.noninteractive.bg_fill = Self::BG_SECONDARY;
-        visuals.widgets.noninteractive.fg_stroke = Stroke::new(1.0, Self::TEXT_PRIMARY);
-        visuals.widgets.noninteractive.bg_stroke = Stroke::new(0.5, Self::BORDER_LIGHT);
+        visuals.widgets.noninteractive.bg_fill = palette.bg_secondary;
+        visuals.widgets.noninteractive.fg_stroke = Stroke::new(1.0, palette.text_primary);
+        visuals.widgets.noninteractive.bg_stroke = Stroke::new(0.5, palette.border_light);
         visuals.widgets.noninteractive.rounding = Rounding::same(6.0);
 
         // Inactive interactive widgets (buttons at rest)
-        visuals.widgets.inactive.bg_fill = Self::BG_TERTIARY;
-        visuals.widgets.inactive.fg_stroke = Stroke::new(1.0, Self::TEXT_SECONDARY);
-        visuals.widgets.inactive.bg_stroke = Stroke::new(0.5, Self::BORDER);
+        visuals.widgets.inactive.bg_fill = palette.bg_tertiary;
+        visuals.widgets.inactive.fg_stroke = Stroke::new(1.0, palette.text_secondary);
+        visuals.widgets.inactive.bg_stroke = Stroke::new(0.5, palette.border);
         visuals.widgets.inactive.rounding = Rounding::same(6.0);
 
         // Hovered widgets - smooth visual feedback
-        visuals.widgets.hovered.bg_fill = Self::BG_HOVER;
-        visuals.widgets.hovered.fg_stroke = Stroke::new(1.0, Self::TEXT_PRIMARY);
-        visuals.widgets.hovered.bg_stroke = Stroke::new(1.0, Self::PRIMARY.linear_multiply(0.6));
+        visuals.widgets.hovered.bg_fill = palette.bg_hover;
+        visuals.widgets.hovered.fg_stroke = Stroke::new(1.0, palette.text_primary);
+        visuals.widgets.hovered.bg_stroke = Stroke::new(1.0, palette.primary.linear_multiply(0.6));
         visuals.widgets.hovered.rounding = Rounding::same(6.0);
         visuals.widgets.hovered.expansion = 1.0;
 
         // Active/pressed widgets
-        visuals.widgets.active.bg_fill = Self::PRIMARY;
+        visuals.widgets.active.bg_fill = palette.primary;
         visuals.widgets.active.fg_stroke = Stroke::new(1.0, Color32::WHITE);
-        visuals.widgets.active.bg_stroke = Stroke::new(1.0, Self::PRIMARY_DARK);
+        visuals.widgets.active.bg_stroke = Stroke::new(1.0, palette.primary_dark);
         visuals.widgets.active.rounding = Rounding::same(6.0);
 
         // Open widgets (like ComboBox when open)
-        visuals.widgets.open.bg_fill = Self::BG_ELEVATED;
-        visuals.widgets.open.fg_stroke = Stroke::new(1.0, Self::TEXT_PRIMARY);
-        visuals.widgets.open.bg_stroke = Stroke::new(1.0, Self::PRIMARY.linear_multiply(0.5));
+        visuals.widgets.open.bg_fill = palette.bg_elevated;
+        visuals.widgets.open.fg_stroke = Stroke::new(1.0, palette.text_primary);
+        visuals.widgets.open.bg_stroke = Stroke::new(1.0, palette.primary.linear_multiply(0.5));
         visuals.widgets.open.rounding = Rounding::same(6.0);
 
         // Selection colors
-        visuals.selection.bg_fill = Self::PRIMARY.linear_multiply(0.25);
-        visuals.selection.stroke = Stroke::new(1.0, Self::PRIMARY);
+        visuals.selection.bg_fill = palette.primary.linear_multiply(0.25);
+        visuals.selection.stroke = Stroke::new(1.0, palette.primary);
 
         // Window styling
         visuals.window_rounding = Rounding::same(10.0);
-        visuals.window_stroke = Stroke::new(0.5, Self::BORDER);
+        visuals.window_stroke = Stroke::new(0.5, palette.border);
         visuals.window_shadow = egui::Shadow {
             offset: egui::vec2(0.0, 10.0),
             blur: 30.0,
@@ -156,56 +405,48 @@ impl Theme {
         ctx.set_style(style);
     }
 
-    /// Apply light theme to egui
-    pub fn apply_light(ctx: &egui::Context) {
+    /// Apply light styling with the given colors
+    fn apply_light_palette(ctx: &egui::Context, palette: Palette) {
+        Self::set_palette(palette);
         let mut style = (*ctx.style()).clone();
         let mut visuals = Visuals::light();
 
-        // Light theme background colors - clean and modern
-        let bg_primary = Color32::from_rgb(249, 250, 251); // Gray-50
-        let bg_secondary = Color32::from_rgb(243, 244, 246); // Gray-100
-        let bg_tertiary = Color32::from_rgb(229, 231, 235); // Gray-200
-        let bg_hover = Color32::from_rgb(209, 213, 219); // Gray-300
-        let text_primary = Color32::from_rgb(17, 24, 39); // Gray-900
-        let text_secondary = Color32::from_rgb(75, 85, 99); // Gray-600
-        let border = Color32::from_rgb(209, 213, 219); // Gray-300
-
-        visuals.panel_fill = bg_primary;
-        visuals.window_fill = Color32::WHITE;
+        visuals.panel_fill = palette.bg_primary;
+        visuals.window_fill = palette.bg_elevated;
         visuals.extreme_bg_color = Color32::WHITE;
-        visuals.faint_bg_color = bg_secondary;
+        visuals.faint_bg_color = palette.bg_secondary;
 
-        visuals.widgets.noninteractive.bg_fill = bg_secondary;
-        visuals.widgets.noninteractive.fg_stroke = Stroke::new(1.0, text_primary);
-        visuals.widgets.noninteractive.bg_stroke = Stroke::new(0.5, border);
+        visuals.widgets.noninteractive.bg_fill = palette.bg_secondary;
+        visuals.widgets.noninteractive.fg_stroke = Stroke::new(1.0, palette.text_primary);
+        visuals.widgets.noninteractive.bg_stroke = Stroke::new(0.5, palette.border);
         visuals.widgets.noninteractive.rounding = Rounding::same(6.0);
 
-        visuals.widgets.inactive.bg_fill = bg_tertiary;
-        visuals.widgets.inactive.fg_stroke = Stroke::new(1.0, text_secondary);
-        visuals.widgets.inactive.bg_stroke = Stroke::new(0.5, border);
+        visuals.widgets.inactive.bg_fill = palette.bg_tertiary;
+        visuals.widgets.inactive.fg_stroke = Stroke::new(1.0, palette.text_secondary);
+        visuals.widgets.inactive.bg_stroke = Stroke::new(0.5, palette.border);
         visuals.widgets.inactive.rounding = Rounding::same(6.0);
 
-        visuals.widgets.hovered.bg_fill = bg_hover;
-        visuals.widgets.hovered.fg_stroke = Stroke::new(1.0, text_primary);
-        visuals.widgets.hovered.bg_stroke = Stroke::new(1.0, Self::PRIMARY.linear_multiply(0.7));
+        visuals.widgets.hovered.bg_fill = palette.bg_hover;
+        visuals.widgets.hovered.fg_stroke = Stroke::new(1.0, palette.text_primary);
+        visuals.widgets.hovered.bg_stroke = Stroke::new(1.0, palette.primary.linear_multiply(0.7));
         visuals.widgets.hovered.rounding = Rounding::same(6.0);
         visuals.widgets.hovered.expansion = 1.0;
 
-        visuals.widgets.active.bg_fill = Self::PRIMARY;
+        visuals.widgets.active.bg_fill = palette.primary;
         visuals.widgets.active.fg_stroke = Stroke::new(1.0, Color32::WHITE);
-        visuals.widgets.active.bg_stroke = Stroke::new(1.0, Self::PRIMARY_DARK);
+        visuals.widgets.active.bg_stroke = Stroke::new(1.0, palette.primary_dark);
         visuals.widgets.active.rounding = Rounding::same(6.0);
 
-        visuals.widgets.open.bg_fill = Color32::WHITE;
-        visuals.widgets.open.fg_stroke = Stroke::new(1.0, text_primary);
-        visuals.widgets.open.bg_stroke = Stroke::new(1.0, Self::PRIMARY.linear_multiply(0.6));
+        visuals.widgets.open.bg_fill = palette.bg_elevated;
+        visuals.widgets.open.fg_stroke = Stroke::new(1.0, palette.text_primary);
+        visuals.widgets.open.bg_stroke = Stroke::new(1.0, palette.primary.linear_multiply(0.6));
         visuals.widgets.open.rounding = Rounding::same(6.0);
 
-        visuals.selection.bg_fill = Self::PRIMARY.linear_multiply(0.15);
-        visuals.selection.stroke = Stroke::new(1.0, Self::PRIMARY);
+        visuals.selection.bg_fill = palette.primary.linear_multiply(0.15);
+        visuals.selection.stroke = Stroke::new(1.0, palette.primary);
 
         visuals.window_rounding = Rounding::same(10.0);
-        visuals.window_stroke = Stroke::new(0.5, border);
+        visuals.window_stroke = Stroke::new(0.5, palette.border);
         visuals.window_shadow = egui::Shadow {
             offset: egui::vec2(0.0, 8.0),
             blur: 24.0,
@@ -262,17 +503,37 @@ impl Theme {
         ctx.set_style(style);
     }
 
+    /// Stable accent color for a group name
+    pub fn group_color(group: &str) -> Color32 {
+        const PALETTE: [Color32; 8] = [
+            Color32::from_rgb(99, 102, 241), // Indigo
+            Color32::from_rgb(16, 185, 129), // Emerald
+            Color32::from_rgb(245, 158, 11), // Amber
+            Color32::from_rgb(236, 72, 153), // Pink
+            Color32::from_rgb(6, 182, 212),  // Cyan
+            Color32::from_rgb(168, 85, 247), // Purple
+            Color32::from_rgb(132, 204, 22), // Lime
+            Color32::from_rgb(249, 115, 22), // Orange
+        ];
+        // FNV-1a, so colors stay the same across runs
+        let hash = group.bytes().fold(0x811c_9dc5u32, |h, b| {
+            (h ^ b as u32).wrapping_mul(0x0100_0193)
+        });
+        PALETTE[hash as usize % PALETTE.len()]
+    }
+
     /// Get color for instance status
     pub fn status_color(status: &crate::core::InstanceStatus) -> Color32 {
         use crate::core::InstanceStatus;
         match status {
-            InstanceStatus::Running => Self::STATUS_RUNNING,
-            InstanceStatus::Starting => Self::STATUS_STARTING,
-            InstanceStatus::Paused => Self::STATUS_PAUSED,
-            InstanceStatus::Stopping => Self::WARNING,
-            InstanceStatus::Stopped => Self::STATUS_STOPPED,
-            InstanceStatus::Crashed => Self::STATUS_CRASHED,
-            InstanceStatus::Unknown => Self::TEXT_MUTED,
+            InstanceStatus::Running => Self::success(),
+            InstanceStatus::Pending => Self::primary(),
+            InstanceStatus::Starting => Self::warning(),
+            InstanceStatus::Paused => Self::info(),
+            InstanceStatus::Stopping => Self::warning(),
+            InstanceStatus::Stopped => Self::text_muted(),
+            InstanceStatus::Crashed => Self::error(),
+            InstanceStatus::Unknown => Self::text_muted(),
         }
     }
 }
@@ -303,6 +564,7 @@ impl Icons {
     pub const GRID: &'static str = "▦";
     pub const LIST: &'static str = "☰";
     pub const COMPACT: &'static str = "▤";
+    pub const BOARD: &'static str = "▥";
     pub const EXPAND: &'static str = "⬚";
     pub const COLLAPSE: &'static str = "▣";
     pub const WARNING: &'static str = "⚠";
@@ -316,3 +578,50 @@ impl Icons {
     pub const EXPORT: &'static str = "📤";
     pub const IMPORT: &'static str = "📥";
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// WCAG contrast ratio between two colors
+    fn contrast(a: Color32, b: Color32) -> f32 {
+        fn luminance(color: Color32) -> f32 {
+            let channel = |c: u8| {
+                let c = c as f32 / 255.0;
+                if c <= 0.03928 {
+                    c / 12.92
+                } else {
+                    ((c + 0.055) / 1.055).powf(2.4)
+                }
+            };
+            0.2126 * channel(color.r()) + 0.7152 * channel(color.g()) + 0.0722 * channel(color.b())
+        }
+        let (a, b) = (luminance(a), luminance(b));
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    #[test]
+    fn built_in_palettes_keep_text_readable() {
+        for (name, palette) in [("Dark", Palette::DARK), ("Light", Palette::LIGHT)] {
+            for (text_name, text) in [
+                ("text_primary", palette.text_primary),
+                ("text_secondary", palette.text_secondary),
+            ] {
+                for (bg_name, bg) in [
+                    ("bg_primary", palette.bg_primary),
+                    ("bg_secondary", palette.bg_secondary),
+                ] {
+                    let ratio = contrast(text, bg);
+                    assert!(
+                        ratio >= 4.5,
+                        "{} {} on {} has contrast {:.2}",
+                        name,
+                        text_name,
+                        bg_name,
+                        ratio
+                    );
+                }
+            }
+        }
+    }
+}